@@ -142,8 +142,6 @@ async fn main() -> anyhow::Result<()> {
         display_name: Some("Test User".to_string()),
         password_hash: "test_hash".to_string(),
         tier: pierre_mcp_server::models::UserTier::Starter,
-        strava_token: None,
-        fitbit_token: None,
         is_active: true,
         created_at: chrono::Utc::now(),
         last_active: chrono::Utc::now(),
@@ -9,9 +9,18 @@
 //! Implements the A2A Agent Card specification for Pierre,
 //! enabling agent discovery and capability negotiation.
 
+use crate::a2a::A2AError;
+use crate::config::environment::ServerConfig;
+pub use crate::mcp::schema::ToolExample;
+use crate::mcp::schema::ToolSchema;
+use crate::protocols::universal::UniversalToolExecutor;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::path::Path;
 
 /// A2A Agent Card for Pierre
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,11 +28,17 @@ pub struct AgentCard {
     pub name: String,
     pub description: String,
     pub version: String,
+    /// JSON-RPC endpoint partners should call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
     pub capabilities: Vec<String>,
     pub authentication: AuthenticationInfo,
     pub tools: Vec<ToolDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, Value>>,
+    /// Detached JWS signatures over the rest of the card
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signatures: Option<Vec<AgentCardSignature>>,
 }
 
 /// Authentication information for the agent
@@ -34,6 +49,8 @@ pub struct AuthenticationInfo {
     pub oauth2: Option<OAuth2Info>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_key: Option<ApiKeyInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwt: Option<JwtInfo>,
 }
 
 /// OAuth2 authentication information
@@ -52,6 +69,14 @@ pub struct ApiKeyInfo {
     pub registration_url: String,
 }
 
+/// JWT bearer authentication information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtInfo {
+    pub header_name: String,
+    pub prefix: String,
+    pub token_url: String,
+}
+
 /// Tool definition in the agent card
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolDefinition {
@@ -63,217 +88,79 @@ pub struct ToolDefinition {
     pub examples: Option<Vec<ToolExample>>,
 }
 
-/// Example usage of a tool
+/// Detached JWS (RFC 7515) signature of an agent card
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ToolExample {
-    pub description: String,
-    pub input: Value,
-    pub output: Value,
+pub struct AgentCardSignature {
+    /// Base64url-encoded JWS protected header
+    pub protected: String,
+    /// Base64url-encoded Ed25519 signature
+    pub signature: String,
 }
 
 impl AgentCard {
-    /// Create a new Agent Card for Pierre
+    /// Create a new Agent Card for Pierre advertising every MCP tool
     pub fn new() -> Self {
+        let base_url = format!(
+            "http://localhost:{}",
+            crate::constants::env_config::http_port()
+        );
+        Self::from_tools(&crate::mcp::schema::get_tools(), &base_url)
+    }
+
+    /// Build the card for a running server from the tools its executor supports
+    pub fn for_server(executor: &UniversalToolExecutor, config: &ServerConfig) -> Self {
+        let base_url = config
+            .a2a
+            .public_url
+            .clone()
+            .unwrap_or_else(|| format!("http://localhost:{}", config.http_port));
+
+        let tools: Vec<ToolSchema> = crate::mcp::schema::get_tools()
+            .into_iter()
+            .filter(|tool| executor.supports_tool(&tool.name))
+            .collect();
+
+        Self::from_tools(&tools, &base_url)
+    }
+
+    /// Build a card from tool schemas, served from `base_url`
+    pub fn from_tools(tools: &[ToolSchema], base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+
+        let mut capabilities: Vec<String> = Vec::new();
+        for tool in tools {
+            let capability = tool.capability.as_str().to_string();
+            if !capabilities.contains(&capability) {
+                capabilities.push(capability);
+            }
+        }
+
         Self {
             name: "Pierre Fitness Intelligence Agent".to_string(),
             description: "AI-powered fitness data analysis and insights platform providing comprehensive activity analysis, performance tracking, and intelligent recommendations for athletes and fitness enthusiasts.".to_string(),
             version: "1.0.0".to_string(),
-            capabilities: vec![
-                "fitness-data-analysis".to_string(),
-                "activity-intelligence".to_string(),
-                "goal-management".to_string(),
-                "performance-prediction".to_string(),
-                "training-analytics".to_string(),
-                "provider-integration".to_string(),
-            ],
+            url: Some(format!("{}/a2a/execute", base_url)),
+            capabilities,
             authentication: AuthenticationInfo {
-                schemes: vec!["api-key".to_string(), "oauth2".to_string()],
-                oauth2: Some(OAuth2Info {
-                    authorization_url: "https://pierre.ai/oauth/authorize".to_string(),
-                    token_url: "https://pierre.ai/oauth/token".to_string(),
-                    scopes: vec![
-                        "fitness:read".to_string(),
-                        "analytics:read".to_string(),
-                        "goals:read".to_string(),
-                        "goals:write".to_string(),
-                    ],
-                }),
+                schemes: vec!["api-key".to_string(), "jwt".to_string()],
+                oauth2: None,
                 api_key: Some(ApiKeyInfo {
                     header_name: "Authorization".to_string(),
                     prefix: Some("Bearer".to_string()),
-                    registration_url: "https://pierre.ai/api/keys/request".to_string(),
+                    registration_url: format!("{}/a2a/clients", base_url),
+                }),
+                jwt: Some(JwtInfo {
+                    header_name: "Authorization".to_string(),
+                    prefix: "Bearer".to_string(),
+                    token_url: format!("{}/auth/login", base_url),
                 }),
             },
-            tools: Self::create_tool_definitions(),
+            tools: tools.iter().map(tool_definition).collect(),
             metadata: Some(Self::create_metadata()),
+            signatures: None,
         }
     }
 
-    /// Create tool definitions for the agent card
-    fn create_tool_definitions() -> Vec<ToolDefinition> {
-        vec![
-            ToolDefinition {
-                name: "get_activities".to_string(),
-                description: "Retrieve user fitness activities from connected providers"
-                    .to_string(),
-                input_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "limit": {
-                            "type": "number",
-                            "description": "Number of activities to retrieve (max 100)",
-                            "minimum": 1,
-                            "maximum": 100,
-                            "default": 10
-                        },
-                        "before": {
-                            "type": "string",
-                            "format": "date-time",
-                            "description": "ISO 8601 date to get activities before"
-                        },
-                        "provider": {
-                            "type": "string",
-                            "enum": ["strava", "fitbit"],
-                            "description": "Specific provider to query (optional)"
-                        }
-                    }
-                }),
-                output_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "activities": {
-                            "type": "array",
-                            "items": {
-                                "type": "object",
-                                "properties": {
-                                    "id": {"type": "string"},
-                                    "name": {"type": "string"},
-                                    "sport_type": {"type": "string"},
-                                    "start_date": {"type": "string", "format": "date-time"},
-                                    "duration_seconds": {"type": "number"},
-                                    "distance_meters": {"type": "number"},
-                                    "elevation_gain": {"type": "number"}
-                                }
-                            }
-                        },
-                        "total_count": {"type": "number"}
-                    }
-                }),
-                examples: Some(vec![ToolExample {
-                    description: "Get recent activities".to_string(),
-                    input: serde_json::json!({"limit": 5}),
-                    output: serde_json::json!({
-                        "activities": [
-                            {
-                                "id": "123456",
-                                "name": "Morning Run",
-                                "sport_type": "Run",
-                                "start_date": "2024-01-15T07:00:00Z",
-                                "duration_seconds": 1800,
-                                "distance_meters": 5000,
-                                "elevation_gain": 50
-                            }
-                        ],
-                        "total_count": 1
-                    }),
-                }]),
-            },
-            ToolDefinition {
-                name: "analyze_activity".to_string(),
-                description: "AI-powered analysis of a specific fitness activity".to_string(),
-                input_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "activity_id": {
-                            "type": "string",
-                            "description": "Unique identifier of the activity to analyze"
-                        }
-                    },
-                    "required": ["activity_id"]
-                }),
-                output_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "activity": {"type": "object"},
-                        "insights": {
-                            "type": "array",
-                            "items": {
-                                "type": "object",
-                                "properties": {
-                                    "type": {"type": "string"},
-                                    "title": {"type": "string"},
-                                    "description": {"type": "string"},
-                                    "confidence": {"type": "number"}
-                                }
-                            }
-                        },
-                        "metrics": {"type": "object"},
-                        "recommendations": {"type": "array"}
-                    }
-                }),
-                examples: None,
-            },
-            ToolDefinition {
-                name: "get_athlete".to_string(),
-                description: "Retrieve athlete profile information".to_string(),
-                input_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {}
-                }),
-                output_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "id": {"type": "string"},
-                        "username": {"type": "string"},
-                        "firstname": {"type": "string"},
-                        "lastname": {"type": "string"},
-                        "city": {"type": "string"},
-                        "country": {"type": "string"}
-                    }
-                }),
-                examples: None,
-            },
-            ToolDefinition {
-                name: "set_goal".to_string(),
-                description: "Set a fitness goal for the user".to_string(),
-                input_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "goal_type": {
-                            "type": "string",
-                            "enum": ["distance", "time", "frequency", "speed"],
-                            "description": "Type of goal to set"
-                        },
-                        "target_value": {
-                            "type": "number",
-                            "description": "Target value for the goal"
-                        },
-                        "timeframe": {
-                            "type": "string",
-                            "enum": ["weekly", "monthly", "yearly"],
-                            "description": "Timeframe for achieving the goal"
-                        },
-                        "sport_type": {
-                            "type": "string",
-                            "description": "Sport type for the goal (optional)"
-                        }
-                    },
-                    "required": ["goal_type", "target_value", "timeframe"]
-                }),
-                output_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "goal_id": {"type": "string"},
-                        "status": {"type": "string"},
-                        "progress": {"type": "number"},
-                        "estimated_completion": {"type": "string", "format": "date-time"}
-                    }
-                }),
-                examples: None,
-            },
-        ]
-    }
-
     /// Create metadata for the agent card
     fn create_metadata() -> HashMap<String, Value> {
         let mut metadata = HashMap::new();
@@ -316,6 +203,45 @@ impl AgentCard {
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
     }
+
+    /// Canonical bytes covered by the signature (card without signatures)
+    fn signing_payload(&self) -> Result<Vec<u8>, serde_json::Error> {
+        let mut unsigned = self.clone();
+        unsigned.signatures = None;
+        // Going through `Value` sorts object keys, so the encoding is stable
+        serde_json::to_vec(&serde_json::to_value(&unsigned)?)
+    }
+
+    /// Sign the card, replacing any previous signature
+    pub fn sign(&mut self, signer: &AgentCardSigner) -> Result<(), A2AError> {
+        let payload = self
+            .signing_payload()
+            .map_err(|e| A2AError::InternalError(e.to_string()))?;
+        self.signatures = Some(vec![signer.sign(&payload)]);
+        Ok(())
+    }
+
+    /// Check the card signature against a trusted Ed25519 public key
+    pub fn verify_signature(&self, public_key: &[u8]) -> bool {
+        let Some(signature) = self.signatures.as_ref().and_then(|s| s.first()) else {
+            return false;
+        };
+        let (Ok(payload), Ok(raw_signature)) = (
+            self.signing_payload(),
+            URL_SAFE_NO_PAD.decode(&signature.signature),
+        ) else {
+            return false;
+        };
+
+        let signing_input = format!(
+            "{}.{}",
+            signature.protected,
+            URL_SAFE_NO_PAD.encode(payload)
+        );
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(signing_input.as_bytes(), &raw_signature)
+            .is_ok()
+    }
 }
 
 impl Default for AgentCard {
@@ -324,6 +250,106 @@ impl Default for AgentCard {
     }
 }
 
+/// Ed25519 key used to sign the served agent card
+pub struct AgentCardSigner {
+    key_pair: Ed25519KeyPair,
+    key_id: String,
+}
+
+impl AgentCardSigner {
+    /// Create a signer from a PKCS#8 encoded Ed25519 key
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, A2AError> {
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(pkcs8).map_err(|e| {
+            A2AError::InternalError(format!("Invalid agent card signing key: {}", e))
+        })?;
+
+        // RFC 7638 JWK thumbprint
+        let thumbprint_input = format!(
+            r#"{{"crv":"Ed25519","kty":"OKP","x":"{}"}}"#,
+            URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref())
+        );
+        let key_id = URL_SAFE_NO_PAD.encode(Sha256::digest(thumbprint_input.as_bytes()));
+
+        Ok(Self { key_pair, key_id })
+    }
+
+    /// Load the signing key from disk, generating it on first start
+    pub fn load_or_generate(path: &Path) -> Result<Self, A2AError> {
+        if path.exists() {
+            let pkcs8 = std::fs::read(path).map_err(|e| {
+                A2AError::InternalError(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+            return Self::from_pkcs8(&pkcs8);
+        }
+
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng)
+            .map_err(|e| A2AError::InternalError(format!("Failed to generate key: {}", e)))?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| A2AError::InternalError(e.to_string()))?;
+        }
+        std::fs::write(path, pkcs8.as_ref()).map_err(|e| {
+            A2AError::InternalError(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+
+        Self::from_pkcs8(pkcs8.as_ref())
+    }
+
+    /// Raw Ed25519 public key partners pin to verify the card
+    pub fn public_key(&self) -> &[u8] {
+        self.key_pair.public_key().as_ref()
+    }
+
+    /// Key identifier (JWK thumbprint) carried in the JWS header
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Public key as a JWK
+    pub fn jwk(&self) -> Value {
+        serde_json::json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": URL_SAFE_NO_PAD.encode(self.public_key()),
+            "kid": self.key_id,
+            "use": "sig",
+            "alg": "EdDSA"
+        })
+    }
+
+    fn sign(&self, payload: &[u8]) -> AgentCardSignature {
+        let header = serde_json::json!({
+            "alg": "EdDSA",
+            "typ": "JOSE",
+            "kid": self.key_id,
+            "jwk": self.jwk()
+        });
+        let protected = URL_SAFE_NO_PAD.encode(header.to_string());
+        let signing_input = format!("{}.{}", protected, URL_SAFE_NO_PAD.encode(payload));
+        let signature = self.key_pair.sign(signing_input.as_bytes());
+
+        AgentCardSignature {
+            protected,
+            signature: URL_SAFE_NO_PAD.encode(signature.as_ref()),
+        }
+    }
+}
+
+/// Convert an MCP tool schema into an agent card tool definition
+fn tool_definition(tool: &ToolSchema) -> ToolDefinition {
+    let schema_value = |schema| {
+        serde_json::to_value(schema).unwrap_or_else(|_| serde_json::json!({"type": "object"}))
+    };
+    ToolDefinition {
+        name: tool.name.clone(),
+        description: tool.description.clone(),
+        input_schema: schema_value(&tool.input_schema),
+        output_schema: schema_value(&tool.output_schema),
+        examples: (!tool.examples.is_empty()).then(|| tool.examples.clone()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -356,7 +382,7 @@ mod tests {
 
     #[test]
     fn test_tool_definitions() {
-        let tools = AgentCard::create_tool_definitions();
+        let tools = AgentCard::new().tools;
         assert!(!tools.is_empty());
 
         let get_activities = tools.iter().find(|t| t.name == "get_activities").unwrap();
@@ -373,8 +399,70 @@ mod tests {
     fn test_authentication_info() {
         let card = AgentCard::new();
         assert!(card.authentication.schemes.contains(&"api-key".to_string()));
-        assert!(card.authentication.schemes.contains(&"oauth2".to_string()));
-        assert!(card.authentication.oauth2.is_some());
+        assert!(card.authentication.schemes.contains(&"jwt".to_string()));
+        // OAuth2 is not accepted on A2A endpoints, so it must not be advertised
+        assert!(card.authentication.oauth2.is_none());
         assert!(card.authentication.api_key.is_some());
+        assert!(card.authentication.jwt.is_some());
+    }
+
+    #[test]
+    fn test_tools_follow_mcp_schemas() {
+        let card = AgentCard::new();
+        let schemas = crate::mcp::schema::get_tools();
+        assert_eq!(card.tools.len(), schemas.len());
+
+        for schema in schemas {
+            let tool = card.tools.iter().find(|t| t.name == schema.name).unwrap();
+            assert_eq!(tool.description, schema.description);
+            assert_eq!(tool.input_schema["type"], "object");
+            // Every tool documents the fields of its result
+            assert!(!tool.output_schema["properties"]
+                .as_object()
+                .unwrap()
+                .is_empty());
+            assert!(card
+                .capabilities
+                .contains(&schema.capability.as_str().to_string()));
+        }
+        assert!(card.capabilities.contains(&"agent-delegation".to_string()));
+    }
+
+    #[test]
+    fn test_from_tools_limits_capabilities() {
+        let tools: Vec<ToolSchema> = crate::mcp::schema::get_tools()
+            .into_iter()
+            .filter(|t| t.name == "get_activities")
+            .collect();
+        let card = AgentCard::from_tools(&tools, "https://pierre.example.com/");
+
+        assert_eq!(card.tools.len(), 1);
+        assert_eq!(card.capabilities, vec!["fitness-data-analysis"]);
+        assert_eq!(
+            card.url.as_deref(),
+            Some("https://pierre.example.com/a2a/execute")
+        );
+    }
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let signer = AgentCardSigner::from_pkcs8(pkcs8.as_ref()).unwrap();
+
+        let mut card = AgentCard::new();
+        assert!(!card.verify_signature(signer.public_key()));
+
+        card.sign(&signer).unwrap();
+        assert!(card.verify_signature(signer.public_key()));
+
+        // Signature survives a JSON round trip
+        let parsed = AgentCard::from_json(&card.to_json().unwrap()).unwrap();
+        assert!(parsed.verify_signature(signer.public_key()));
+
+        // Any tampering invalidates it
+        let mut tampered = parsed.clone();
+        tampered.tools.pop();
+        assert!(!tampered.verify_signature(signer.public_key()));
     }
 }
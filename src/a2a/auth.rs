@@ -49,33 +49,10 @@ impl A2AAuthenticator {
     }
 
     /// Authenticate an A2A request using API key
+    ///
+    /// Keys issued to A2A clients are additionally checked against the
+    /// client's status and quota.
     pub async fn authenticate_api_key(&self, api_key: &str) -> Result<AuthResult, anyhow::Error> {
-        // Check if it's an A2A-specific API key (with a2a_ prefix)
-        if api_key.starts_with("a2a_") {
-            return self.authenticate_a2a_key(api_key).await;
-        }
-
-        // Fall back to regular API key authentication using MCP middleware
-        let auth_manager = crate::auth::AuthManager::new(
-            vec![0u8; 64], // Placeholder - should use proper secret in production
-            24,
-        );
-        let middleware = crate::auth::McpAuthMiddleware::new(auth_manager, self.database.clone());
-
-        middleware.authenticate_request(Some(api_key)).await
-    }
-
-    /// Authenticate A2A-specific API key with rate limiting
-    async fn authenticate_a2a_key(&self, api_key: &str) -> Result<AuthResult, anyhow::Error> {
-        // Extract key components (similar to API key validation)
-        if !api_key.starts_with("a2a_") || api_key.len() < 16 {
-            return Err(anyhow::anyhow!("Invalid A2A API key format"));
-        }
-
-        // For A2A keys, we need to look them up differently since they're stored in API keys table
-        // but linked to A2A clients. For now, fall back to regular API key auth but add A2A-specific
-        // rate limiting logic here.
-
         let auth_manager = crate::auth::AuthManager::new(
             vec![0u8; 64], // Placeholder - should use proper secret in production
             24,
@@ -88,7 +65,11 @@ impl A2AAuthenticator {
         // Add A2A-specific rate limiting
         if let AuthMethod::ApiKey { key_id, tier: _ } = &auth_result.auth_method {
             // Find A2A client associated with this API key
-            if let Ok(Some(client)) = self.get_a2a_client_by_api_key(key_id).await {
+            if let Some(client) = self.get_a2a_client_by_api_key(key_id).await? {
+                if !client.is_active {
+                    return Err(anyhow::anyhow!("A2A client {} is deactivated", client.id));
+                }
+
                 let client_manager = crate::a2a::A2AClientManager::new(self.database.clone());
 
                 // Check A2A-specific rate limits
@@ -131,16 +112,9 @@ impl A2AAuthenticator {
     /// Get A2A client by API key ID
     async fn get_a2a_client_by_api_key(
         &self,
-        _api_key_id: &str,
+        api_key_id: &str,
     ) -> Result<Option<A2AClient>, anyhow::Error> {
-        // Use database method to get A2A client by API key ID
-        // For now, we'll need to add this method to the database
-        // Let's use a workaround for now by getting all clients and finding the match
-        // This is not efficient but works for the implementation
-
-        // Note: get_a2a_client_by_api_key_id method would need to be added to Database
-        // For now, return None to allow compilation
-        Ok(None)
+        self.database.get_a2a_client_by_api_key_id(api_key_id).await
     }
 
     /// Authenticate using OAuth2 token
@@ -14,7 +14,6 @@ use crate::database_plugins::{factory::Database, DatabaseProvider};
 use chrono::Timelike;
 use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -36,6 +35,22 @@ pub struct ClientCredentials {
     pub api_key: String,
 }
 
+/// A2A credential rotation request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialRotationRequest {
+    /// Hours the current credentials keep working after the rotation
+    pub overlap_hours: Option<i64>,
+}
+
+/// A2A credential rotation response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialRotation {
+    #[serde(flatten)]
+    pub credentials: ClientCredentials,
+    /// Until when the credentials issued before this rotation keep working
+    pub previous_credentials_valid_until: DateTime<Utc>,
+}
+
 /// A2A Client usage statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientUsageStats {
@@ -77,6 +92,23 @@ impl A2AClientTier {
     }
 }
 
+/// Quota assigned to an A2A client by an administrator
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct A2AClientQuota {
+    #[serde(default)]
+    pub tier: A2AClientTier,
+    /// Custom monthly request limit overriding the tier default
+    #[serde(default)]
+    pub monthly_limit: Option<u32>,
+}
+
+impl A2AClientQuota {
+    /// Monthly limit in force: the custom override, otherwise the tier default
+    pub fn effective_monthly_limit(&self) -> Option<u32> {
+        self.monthly_limit.or_else(|| self.tier.monthly_limit())
+    }
+}
+
 /// A2A Rate limit status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct A2ARateLimitStatus {
@@ -117,20 +149,20 @@ pub struct A2AUsageParams {
     pub granted_scopes: Vec<String>,
 }
 
+/// Hours previous credentials stay valid after a rotation unless told otherwise
+pub const DEFAULT_ROTATION_OVERLAP_HOURS: i64 = 24;
+
+/// Longest overlap window a rotation may request
+pub const MAX_ROTATION_OVERLAP_HOURS: i64 = 30 * 24;
+
 /// A2A Client Manager
 pub struct A2AClientManager {
-    #[allow(dead_code)]
     database: Arc<Database>,
-    #[allow(dead_code)]
-    active_sessions: Arc<tokio::sync::RwLock<HashMap<String, A2ASession>>>,
 }
 
 impl A2AClientManager {
     pub fn new(database: Arc<Database>) -> Self {
-        Self {
-            database,
-            active_sessions: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
-        }
+        Self { database }
     }
 
     /// Register a new A2A client
@@ -143,8 +175,7 @@ impl A2AClientManager {
 
         // Generate client credentials
         let client_id = format!("a2a_client_{}", Uuid::new_v4());
-        let client_secret = format!("a2a_secret_{}", Uuid::new_v4());
-        let api_key = format!("a2a_{}", uuid::Uuid::new_v4());
+        let client_secret = Self::generate_client_secret();
 
         // Create client record
         let client = A2AClient {
@@ -159,7 +190,7 @@ impl A2AClientManager {
         };
 
         // Store client in database
-        self.store_client(&client, &client_secret, &api_key).await?;
+        let api_key = self.store_client(&client, &client_secret).await?;
 
         Ok(ClientCredentials {
             client_id,
@@ -193,6 +224,7 @@ impl A2AClientManager {
             "performance-prediction",
             "training-analytics",
             "provider-integration",
+            "wellness-monitoring",
         ];
 
         for capability in &request.capabilities {
@@ -207,20 +239,16 @@ impl A2AClientManager {
         Ok(())
     }
 
-    /// Store client in database
+    /// Store client in database, returning its API key
     async fn store_client(
         &self,
         client: &A2AClient,
-        _client_secret: &str,
-        _api_key: &str,
-    ) -> Result<(), crate::a2a::A2AError> {
-        // Create API key first using the API key manager
-        let api_key_manager = crate::api_keys::ApiKeyManager::new();
-
+        client_secret: &str,
+    ) -> Result<String, crate::a2a::A2AError> {
         // For A2A clients, we need a user to link the API key to
         // Create a dummy system user for A2A clients
         let dummy_user = crate::models::User::new(
-            format!("a2a-system-{}@pierre.ai", client.id),
+            Self::system_user_email(&client.id),
             "dummy-hash".to_string(),
             Some("A2A System User".to_string()),
         );
@@ -229,21 +257,60 @@ impl A2AClientManager {
             crate::a2a::A2AError::InternalError(format!("Failed to create dummy user: {}", e))
         })?;
 
+        let key_name = format!("A2A Client: {}", client.name);
+        let (api_key_id, api_key) = self.issue_api_key(dummy_user_id, client, key_name).await?;
+
+        // Create A2A client entry linked to the API key
+        self.database
+            .create_a2a_client(client, &api_key_id)
+            .await
+            .map_err(|e| {
+                crate::a2a::A2AError::InternalError(format!("Failed to create A2A client: {}", e))
+            })?;
+
+        // Record the first credential; nothing was issued before it
+        self.database
+            .rotate_a2a_client_credentials(
+                &client.id,
+                &Self::hash_secret(client_secret),
+                &api_key_id,
+                Utc::now(),
+            )
+            .await
+            .map_err(|e| {
+                crate::a2a::A2AError::InternalError(format!(
+                    "Failed to store client credentials: {}",
+                    e
+                ))
+            })?;
+
+        tracing::info!("Registered A2A client: {} ({})", client.name, client.id);
+        Ok(api_key)
+    }
+
+    /// Create and store an API key for a client, returning its ID and the full key
+    async fn issue_api_key(
+        &self,
+        user_id: Uuid,
+        client: &A2AClient,
+        name: String,
+    ) -> Result<(String, String), crate::a2a::A2AError> {
+        let api_key_manager = crate::api_keys::ApiKeyManager::new();
+
         let request = crate::api_keys::CreateApiKeyRequest {
-            name: format!("A2A Client: {}", client.name),
+            name,
             description: Some(format!("API key for A2A client: {}", client.description)),
             tier: crate::api_keys::ApiKeyTier::Professional, // Default tier for A2A clients
             expires_in_days: None,                           // No expiration
         };
 
-        let (api_key_obj, _generated_key) = api_key_manager
-            .create_api_key(dummy_user_id, request)
+        let (api_key_obj, generated_key) = api_key_manager
+            .create_api_key(user_id, request)
             .await
             .map_err(|e| {
-                crate::a2a::A2AError::InternalError(format!("Failed to create API key: {}", e))
-            })?;
+            crate::a2a::A2AError::InternalError(format!("Failed to create API key: {}", e))
+        })?;
 
-        // Store the API key in database
         self.database
             .create_api_key(&api_key_obj)
             .await
@@ -251,16 +318,27 @@ impl A2AClientManager {
                 crate::a2a::A2AError::InternalError(format!("Failed to store API key: {}", e))
             })?;
 
-        // Create A2A client entry linked to the API key
-        self.database
-            .create_a2a_client(client, &api_key_obj.id)
-            .await
-            .map_err(|e| {
-                crate::a2a::A2AError::InternalError(format!("Failed to create A2A client: {}", e))
-            })?;
+        Ok((api_key_obj.id, generated_key))
+    }
 
-        tracing::info!("Registered A2A client: {} ({})", client.name, client.id);
-        Ok(())
+    /// Email of the system user that owns a client's API keys
+    fn system_user_email(client_id: &str) -> String {
+        format!("a2a-system-{}@pierre.ai", client_id)
+    }
+
+    fn generate_client_secret() -> String {
+        format!("a2a_secret_{}", Uuid::new_v4().simple())
+    }
+
+    fn hash_secret(client_secret: &str) -> String {
+        crate::api_keys::ApiKeyManager::new().hash_key(client_secret)
+    }
+
+    /// Get a client that must exist
+    async fn require_client(&self, client_id: &str) -> Result<A2AClient, crate::a2a::A2AError> {
+        self.get_client(client_id)
+            .await?
+            .ok_or_else(|| crate::a2a::A2AError::ClientNotRegistered(client_id.to_string()))
     }
 
     /// Get client by ID
@@ -275,22 +353,207 @@ impl A2AClientManager {
 
     /// List all registered clients
     pub async fn list_clients(&self) -> Result<Vec<A2AClient>, crate::a2a::A2AError> {
-        // For now, return an empty list since we don't have a user context
-        // In production, this would list clients for a specific user
-        Ok(vec![])
+        self.database.list_all_a2a_clients().await.map_err(|e| {
+            crate::a2a::A2AError::InternalError(format!("Failed to list A2A clients: {}", e))
+        })
     }
 
-    /// Deactivate a client
-    pub async fn deactivate_client(&self, _client_id: &str) -> Result<(), crate::a2a::A2AError> {
-        // Client deactivation would set is_active=false in database
-        // This would involve:
-        // 1. Setting is_active to false
-        // 2. Invalidating active sessions
-        // 3. Deactivating API keys
+    /// Deactivate a client, disabling its API keys and revoking its sessions
+    pub async fn deactivate_client(&self, client_id: &str) -> Result<(), crate::a2a::A2AError> {
+        self.set_client_active(client_id, false).await?;
+        tracing::info!("Deactivated A2A client {}", client_id);
+        Ok(())
+    }
 
+    /// Reactivate a previously deactivated client
+    pub async fn reactivate_client(&self, client_id: &str) -> Result<(), crate::a2a::A2AError> {
+        self.set_client_active(client_id, true).await?;
+        tracing::info!("Reactivated A2A client {}", client_id);
         Ok(())
     }
 
+    async fn set_client_active(
+        &self,
+        client_id: &str,
+        is_active: bool,
+    ) -> Result<(), crate::a2a::A2AError> {
+        let updated = self
+            .database
+            .set_a2a_client_active(client_id, is_active)
+            .await
+            .map_err(|e| {
+                crate::a2a::A2AError::InternalError(format!(
+                    "Failed to update A2A client status: {}",
+                    e
+                ))
+            })?;
+
+        if updated {
+            Ok(())
+        } else {
+            Err(crate::a2a::A2AError::ClientNotRegistered(
+                client_id.to_string(),
+            ))
+        }
+    }
+
+    /// Issue a new secret and API key for a client
+    ///
+    /// The previous credentials keep working for `overlap_hours` (default
+    /// [`DEFAULT_ROTATION_OVERLAP_HOURS`]) so the partner can roll over.
+    pub async fn rotate_credentials(
+        &self,
+        client_id: &str,
+        overlap_hours: Option<i64>,
+    ) -> Result<CredentialRotation, crate::a2a::A2AError> {
+        let overlap_hours = overlap_hours.unwrap_or(DEFAULT_ROTATION_OVERLAP_HOURS);
+        if !(0..=MAX_ROTATION_OVERLAP_HOURS).contains(&overlap_hours) {
+            return Err(crate::a2a::A2AError::InvalidRequest(format!(
+                "overlap_hours must be between 0 and {}",
+                MAX_ROTATION_OVERLAP_HOURS
+            )));
+        }
+
+        let client = self.require_client(client_id).await?;
+        if !client.is_active {
+            return Err(crate::a2a::A2AError::InvalidRequest(format!(
+                "Client {} is deactivated",
+                client_id
+            )));
+        }
+
+        let system_user = self
+            .database
+            .get_user_by_email(&Self::system_user_email(client_id))
+            .await
+            .map_err(|e| {
+                crate::a2a::A2AError::InternalError(format!("Failed to get client owner: {}", e))
+            })?
+            .ok_or_else(|| {
+                crate::a2a::A2AError::InternalError(format!(
+                    "Client {} has no owning system user",
+                    client_id
+                ))
+            })?;
+
+        let client_secret = Self::generate_client_secret();
+        // API key names are unique per user, so each rotation gets its own
+        let key_name = format!(
+            "A2A Client: {} (rotated {})",
+            client.name,
+            Utc::now().to_rfc3339()
+        );
+        let (api_key_id, api_key) = self
+            .issue_api_key(system_user.id, &client, key_name)
+            .await?;
+        let previous_valid_until = Utc::now() + chrono::Duration::hours(overlap_hours);
+
+        self.database
+            .rotate_a2a_client_credentials(
+                client_id,
+                &Self::hash_secret(&client_secret),
+                &api_key_id,
+                previous_valid_until,
+            )
+            .await
+            .map_err(|e| {
+                crate::a2a::A2AError::InternalError(format!(
+                    "Failed to rotate client credentials: {}",
+                    e
+                ))
+            })?;
+
+        tracing::info!(
+            "Rotated credentials of A2A client {}, previous ones valid until {}",
+            client_id,
+            previous_valid_until
+        );
+
+        Ok(CredentialRotation {
+            credentials: ClientCredentials {
+                client_id: client_id.to_string(),
+                client_secret,
+                api_key,
+            },
+            previous_credentials_valid_until: previous_valid_until,
+        })
+    }
+
+    /// Check a client's ID and secret, returning the client when both are valid and active
+    pub async fn verify_client_secret(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<A2AClient, crate::a2a::A2AError> {
+        let invalid =
+            || crate::a2a::A2AError::AuthenticationFailed("Invalid client credentials".to_string());
+
+        let client = self.get_client(client_id).await?.ok_or_else(invalid)?;
+        if !client.is_active {
+            return Err(crate::a2a::A2AError::AuthenticationFailed(format!(
+                "Client {} is deactivated",
+                client_id
+            )));
+        }
+
+        let valid = self
+            .database
+            .verify_a2a_client_secret(client_id, &Self::hash_secret(client_secret))
+            .await
+            .map_err(|e| {
+                crate::a2a::A2AError::InternalError(format!("Failed to verify secret: {}", e))
+            })?;
+
+        if valid {
+            Ok(client)
+        } else {
+            Err(invalid())
+        }
+    }
+
+    /// Get the quota assigned to a client
+    pub async fn get_client_quota(
+        &self,
+        client_id: &str,
+    ) -> Result<A2AClientQuota, crate::a2a::A2AError> {
+        self.database
+            .get_a2a_client_quota(client_id)
+            .await
+            .map_err(|e| {
+                crate::a2a::A2AError::InternalError(format!("Failed to get client quota: {}", e))
+            })?
+            .ok_or_else(|| crate::a2a::A2AError::ClientNotRegistered(client_id.to_string()))
+    }
+
+    /// Set a client's tier and optional custom monthly limit
+    pub async fn update_client_quota(
+        &self,
+        client_id: &str,
+        quota: A2AClientQuota,
+    ) -> Result<A2AClientQuota, crate::a2a::A2AError> {
+        let updated = self
+            .database
+            .update_a2a_client_quota(client_id, &quota)
+            .await
+            .map_err(|e| {
+                crate::a2a::A2AError::InternalError(format!("Failed to update client quota: {}", e))
+            })?;
+
+        if !updated {
+            return Err(crate::a2a::A2AError::ClientNotRegistered(
+                client_id.to_string(),
+            ));
+        }
+
+        tracing::info!(
+            "A2A client {} quota set to {} ({:?} requests/month)",
+            client_id,
+            quota.tier.display_name(),
+            quota.effective_monthly_limit()
+        );
+        Ok(quota)
+    }
+
     /// Get client usage statistics
     pub async fn get_client_usage(
         &self,
@@ -350,7 +613,12 @@ impl A2AClientManager {
             requests_this_month,
             total_requests: total_stats.total_requests as u64,
             last_request_at,
-            rate_limit_tier: "professional".to_string(), // Default for A2A clients
+            rate_limit_tier: self
+                .get_client_quota(client_id)
+                .await?
+                .tier
+                .display_name()
+                .to_lowercase(),
         })
     }
 
@@ -391,16 +659,52 @@ impl A2AClientManager {
     }
 
     /// Get active sessions for a client
-    pub async fn get_active_sessions(&self, _client_id: &str) -> Vec<A2ASession> {
-        // With database storage, this would require a more complex query
-        // For now, return empty list
-        vec![]
+    pub async fn get_active_sessions(
+        &self,
+        client_id: &str,
+    ) -> Result<Vec<A2ASession>, crate::a2a::A2AError> {
+        self.database
+            .list_a2a_sessions(client_id)
+            .await
+            .map_err(|e| {
+                crate::a2a::A2AError::InternalError(format!("Failed to list A2A sessions: {}", e))
+            })
+    }
+
+    /// Revoke one session of a client
+    pub async fn revoke_session(
+        &self,
+        client_id: &str,
+        session_id: &str,
+    ) -> Result<(), crate::a2a::A2AError> {
+        let revoked = self
+            .database
+            .revoke_a2a_session(client_id, session_id)
+            .await
+            .map_err(|e| {
+                crate::a2a::A2AError::InternalError(format!("Failed to revoke A2A session: {}", e))
+            })?;
+
+        if revoked {
+            Ok(())
+        } else {
+            Err(crate::a2a::A2AError::SessionNotFound(
+                session_id.to_string(),
+            ))
+        }
     }
 
-    /// Clean up expired sessions
-    pub async fn cleanup_expired_sessions(&self) {
-        // With database storage, expired sessions are automatically filtered out
-        // This could trigger a cleanup job if needed
+    /// Clean up expired and revoked sessions, returning how many were removed
+    pub async fn cleanup_expired_sessions(&self) -> Result<u64, crate::a2a::A2AError> {
+        self.database
+            .cleanup_expired_a2a_sessions()
+            .await
+            .map_err(|e| {
+                crate::a2a::A2AError::InternalError(format!(
+                    "Failed to clean up A2A sessions: {}",
+                    e
+                ))
+            })
     }
 
     /// Record API usage for a client
@@ -469,15 +773,29 @@ impl A2AClientManager {
         client_id: &str,
         tier: A2AClientTier,
     ) -> Result<A2ARateLimitStatus, crate::a2a::A2AError> {
-        match tier {
-            A2AClientTier::Enterprise => Ok(A2ARateLimitStatus {
+        let quota = A2AClientQuota {
+            tier,
+            monthly_limit: None,
+        };
+        self.calculate_quota_status(client_id, quota).await
+    }
+
+    /// Calculate rate limit status against a client's quota
+    async fn calculate_quota_status(
+        &self,
+        client_id: &str,
+        quota: A2AClientQuota,
+    ) -> Result<A2ARateLimitStatus, crate::a2a::A2AError> {
+        let tier = quota.tier.clone();
+        match quota.effective_monthly_limit() {
+            None => Ok(A2ARateLimitStatus {
                 is_rate_limited: false,
                 limit: None,
                 remaining: None,
                 reset_at: None,
                 tier,
             }),
-            _ => {
+            Some(limit) => {
                 let current_usage = self
                     .database
                     .get_a2a_client_current_usage(client_id)
@@ -489,7 +807,6 @@ impl A2AClientManager {
                         ))
                     })?;
 
-                let limit = tier.monthly_limit().unwrap_or(0);
                 let remaining = limit.saturating_sub(current_usage);
                 let is_rate_limited = current_usage >= limit;
 
@@ -522,9 +839,8 @@ impl A2AClientManager {
         &self,
         client_id: &str,
     ) -> Result<A2ARateLimitStatus, crate::a2a::A2AError> {
-        // For now, default to trial tier. In production, this would be stored in the database
-        let tier = A2AClientTier::Trial;
-        self.calculate_rate_limit_status(client_id, tier).await
+        let quota = self.get_client_quota(client_id).await?;
+        self.calculate_quota_status(client_id, quota).await
     }
 
     /// Calculate the start of next month for rate limit reset
@@ -647,8 +963,29 @@ mod tests {
         assert!(manager.update_session_activity(&session_id).await.is_ok());
 
         // Get active sessions
-        let sessions = manager.get_active_sessions(&credentials.client_id).await;
-        assert_eq!(sessions.len(), 0); // Returns empty since we simplified the implementation
+        let sessions = manager
+            .get_active_sessions(&credentials.client_id)
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].client_id, credentials.client_id);
+
+        // Revoked sessions are no longer listed
+        manager
+            .revoke_session(&credentials.client_id, &sessions[0].id)
+            .await
+            .unwrap();
+        assert!(manager
+            .get_active_sessions(&credentials.client_id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            manager
+                .revoke_session(&credentials.client_id, &sessions[0].id)
+                .await,
+            Err(crate::a2a::A2AError::SessionNotFound(_))
+        ));
     }
 
     #[tokio::test]
@@ -667,20 +1004,84 @@ mod tests {
 
         let credentials = manager.register_client(client_request).await.unwrap();
 
-        // Create session with the actual client ID
-        let _session_id = manager
+        // Create two sessions and revoke one of them
+        manager
+            .create_session(&credentials.client_id, Some("test_user"))
+            .await
+            .unwrap();
+        manager
             .create_session(&credentials.client_id, Some("test_user"))
             .await
             .unwrap();
+        let sessions = manager
+            .get_active_sessions(&credentials.client_id)
+            .await
+            .unwrap();
+        manager
+            .revoke_session(&credentials.client_id, &sessions[0].id)
+            .await
+            .unwrap();
 
-        // Simplified test since we're using database storage now
+        // Cleanup removes the revoked session and keeps the live one
+        assert_eq!(manager.cleanup_expired_sessions().await.unwrap(), 1);
+        let active_sessions = manager
+            .get_active_sessions(&credentials.client_id)
+            .await
+            .unwrap();
+        assert_eq!(active_sessions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_custom_quota_overrides_tier() {
+        let database = create_test_database().await;
+        let manager = A2AClientManager::new(database);
+
+        let client_request = ClientRegistrationRequest {
+            name: "Quota Test Client".to_string(),
+            description: "A client with a custom quota".to_string(),
+            capabilities: vec!["fitness-data-analysis".to_string()],
+            redirect_uris: vec![],
+            contact_email: "quota@example.com".to_string(),
+        };
+        let credentials = manager.register_client(client_request).await.unwrap();
 
-        // Cleanup expired sessions
-        manager.cleanup_expired_sessions().await;
+        // New clients start on the trial tier defaults
+        let status = manager
+            .get_client_rate_limit_status(&credentials.client_id)
+            .await
+            .unwrap();
+        assert_eq!(status.tier, A2AClientTier::Trial);
+        assert_eq!(status.limit, Some(1000));
+
+        manager
+            .update_client_quota(
+                &credentials.client_id,
+                A2AClientQuota {
+                    tier: A2AClientTier::Standard,
+                    monthly_limit: Some(1),
+                },
+            )
+            .await
+            .unwrap();
+        manager
+            .record_usage(&credentials.client_id, "get_activities", true)
+            .await
+            .unwrap();
 
-        // Session should be removed (but our implementation always returns empty)
-        let active_sessions = manager.get_active_sessions(&credentials.client_id).await;
-        assert_eq!(active_sessions.len(), 0);
+        let status = manager
+            .get_client_rate_limit_status(&credentials.client_id)
+            .await
+            .unwrap();
+        assert_eq!(status.tier, A2AClientTier::Standard);
+        assert_eq!(status.limit, Some(1));
+        assert!(status.is_rate_limited);
+
+        assert!(matches!(
+            manager
+                .update_client_quota("missing", A2AClientQuota::default())
+                .await,
+            Err(crate::a2a::A2AError::ClientNotRegistered(_))
+        ));
     }
 
     #[tokio::test]
@@ -13,12 +13,16 @@ pub mod agent_card;
 pub mod auth;
 pub mod client;
 pub mod protocol;
+pub mod remote;
 
 pub use agent_card::AgentCard;
 pub use auth::A2AClient;
 pub use auth::{A2AAuthenticator, A2AToken};
 pub use client::A2AClientManager;
 pub use protocol::{A2AMessage, A2ARequest, A2AResponse, A2AServer};
+pub use remote::{
+    AgentOwner, RemoteAgent, RemoteAgentClient, RemoteAgentPolicy, RemoteAgentRegistry,
+};
 
 /// A2A protocol version supported by Pierre
 pub const A2A_VERSION: &str = "1.0";
@@ -44,11 +48,23 @@ pub enum A2AError {
     #[error("Rate limit exceeded for client: {0}")]
     RateLimitExceeded(String),
 
+    #[error("Session not found: {0}")]
+    SessionNotFound(String),
+
     #[error("Internal server error: {0}")]
     InternalError(String),
 
     #[error("Database error: {0}")]
     DatabaseError(String),
+
+    #[error("Remote agent not found: {0}")]
+    RemoteAgentNotFound(String),
+
+    #[error("Remote agent error: {0}")]
+    RemoteAgentError(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
 }
 
 impl warp::reject::Reject for A2AError {}
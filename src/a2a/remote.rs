@@ -0,0 +1,775 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Remote Agent Delegation
+//!
+//! Outbound A2A support: discovers and validates remote agent cards,
+//! keeps a registry of trusted agents and forwards work to them through
+//! their `message/send` and `tasks/*` JSON-RPC methods.
+//!
+//! Agents are trusted per tenant: a tenant's administrators curate the
+//! agents its members may delegate to through the admin API. Users outside
+//! any tenant keep a personal registry.
+//!
+//! Every outbound request is checked against the [`RemoteAgentPolicy`]: by
+//! default only `https` agents whose host resolves to public addresses are
+//! reached, the checked address is the one connected to, and redirects are
+//! not followed, so a registered URL cannot be used to reach the server's
+//! own network.
+
+use crate::a2a::protocol::{A2AMessage, A2ARequest, A2AResponse, MessagePart};
+use crate::a2a::A2AError;
+use crate::constants::timeouts::REMOTE_AGENT_TIMEOUT_SECS;
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use url::{Host, Url};
+use uuid::Uuid;
+
+/// Well-known locations probed, in order, when discovering an agent card
+pub const AGENT_CARD_PATHS: [&str; 2] = ["/.well-known/agent.json", "/a2a/agent-card"];
+
+/// Agent card as published by a remote agent
+///
+/// Only the fields Pierre relies on are typed; everything else is kept
+/// as raw JSON so that cards from other implementations still parse.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteAgentCard {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub version: String,
+    /// JSON-RPC endpoint of the agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Either a list of capability names or an object of capability flags
+    #[serde(default)]
+    pub capabilities: Value,
+    #[serde(default)]
+    pub skills: Vec<Value>,
+    #[serde(default)]
+    pub tools: Vec<Value>,
+}
+
+impl RemoteAgentCard {
+    /// Capability names advertised by the card, whatever format it uses
+    pub fn capability_names(&self) -> Vec<String> {
+        match &self.capabilities {
+            Value::Array(items) => items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect(),
+            Value::Object(flags) => flags
+                .iter()
+                .filter(|(_, enabled)| enabled.as_bool().unwrap_or(false))
+                .map(|(name, _)| name.clone())
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Validate the card and resolve the JSON-RPC endpoint to call
+    ///
+    /// The endpoint must live on the same origin the card was fetched from,
+    /// so a card cannot redirect delegated user data to a third party.
+    pub fn validate(&self, base_url: &Url) -> Result<Url, A2AError> {
+        if self.name.trim().is_empty() {
+            return Err(A2AError::RemoteAgentError(
+                "Agent card is missing a name".to_string(),
+            ));
+        }
+
+        let endpoint = match &self.url {
+            Some(url) => base_url.join(url).map_err(|e| {
+                A2AError::RemoteAgentError(format!("Invalid agent endpoint URL: {}", e))
+            })?,
+            None => base_url
+                .join("a2a")
+                .map_err(|e| A2AError::RemoteAgentError(e.to_string()))?,
+        };
+
+        if endpoint.origin() != base_url.origin() {
+            return Err(A2AError::RemoteAgentError(format!(
+                "Agent endpoint {} does not match the agent origin {}",
+                endpoint,
+                base_url.origin().ascii_serialization()
+            )));
+        }
+
+        Ok(endpoint)
+    }
+}
+
+/// Whose registry a remote agent belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "scope", content = "owner_id", rename_all = "snake_case")]
+pub enum AgentOwner {
+    /// Curated by the tenant's administrators for all its members
+    Tenant(String),
+    /// A personal registry, for users outside any tenant
+    User(Uuid),
+}
+
+impl AgentOwner {
+    pub const fn scope(&self) -> &'static str {
+        match self {
+            Self::Tenant(_) => "tenant",
+            Self::User(_) => "user",
+        }
+    }
+
+    pub fn id(&self) -> String {
+        match self {
+            Self::Tenant(tenant_id) => tenant_id.clone(),
+            Self::User(user_id) => user_id.to_string(),
+        }
+    }
+
+    /// The owning tenant, for the `tenant_id` column
+    pub fn tenant_id(&self) -> Option<&str> {
+        match self {
+            Self::Tenant(tenant_id) => Some(tenant_id),
+            Self::User(_) => None,
+        }
+    }
+
+    /// The owning user, for the `user_id` column
+    pub const fn user_id(&self) -> Option<Uuid> {
+        match self {
+            Self::Tenant(_) => None,
+            Self::User(user_id) => Some(*user_id),
+        }
+    }
+
+    /// Rebuild an owner from its stored `tenant_id` and `user_id` columns
+    pub fn from_columns(tenant_id: Option<String>, user_id: Option<Uuid>) -> anyhow::Result<Self> {
+        match (tenant_id, user_id) {
+            (Some(tenant_id), None) => Ok(Self::Tenant(tenant_id)),
+            (None, Some(user_id)) => Ok(Self::User(user_id)),
+            _ => Err(anyhow::anyhow!(
+                "Remote agent must belong to exactly one tenant or user"
+            )),
+        }
+    }
+}
+
+/// A remote agent trusted for delegation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteAgent {
+    pub id: String,
+    #[serde(flatten)]
+    pub owner: AgentOwner,
+    pub name: String,
+    pub description: String,
+    pub base_url: String,
+    pub endpoint_url: String,
+    pub capabilities: Vec<String>,
+    /// Bearer credential presented to the remote agent (never serialized)
+    #[serde(skip_serializing, default)]
+    pub auth_token: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_verified_at: DateTime<Utc>,
+}
+
+/// Remote agent registration request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteAgentRegistrationRequest {
+    pub base_url: String,
+    #[serde(default)]
+    pub auth_token: Option<String>,
+}
+
+/// Which remote agent addresses the server may connect to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RemoteAgentPolicy {
+    /// Also allow `http`, and hosts on loopback, private and link-local
+    /// networks - for development and agents inside an on-premises network
+    pub allow_private_networks: bool,
+}
+
+impl RemoteAgentPolicy {
+    /// Read the policy from `A2A_REMOTE_AGENTS_ALLOW_PRIVATE`
+    pub fn from_env() -> Self {
+        Self {
+            allow_private_networks: crate::constants::env_config::remote_agents_allow_private(),
+        }
+    }
+}
+
+/// Whether an address is reachable on the public internet
+///
+/// Loopback, private, shared (CGNAT), link-local (including the cloud
+/// metadata service at 169.254.169.254), unique local, documentation,
+/// multicast and unspecified addresses are not.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 198 && (b == 18 || b == 19))
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_address(IpAddr::V4(mapped)),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80
+                    || first == 0x2001 && ip.segments()[1] == 0x0db8)
+            }
+        },
+    }
+}
+
+/// HTTP client speaking the A2A protocol to remote agents
+#[derive(Clone)]
+pub struct RemoteAgentClient {
+    policy: RemoteAgentPolicy,
+}
+
+impl RemoteAgentClient {
+    pub fn new(policy: RemoteAgentPolicy) -> Self {
+        Self { policy }
+    }
+
+    /// Check a URL against the policy and build a client connecting only to
+    /// the address that was checked
+    ///
+    /// Resolving once and pinning the result keeps a host from passing the
+    /// check and then resolving to an internal address for the request.
+    async fn client_for(&self, url: &Url) -> Result<reqwest::Client, A2AError> {
+        if url.scheme() != "https" && !self.policy.allow_private_networks {
+            return Err(A2AError::InvalidRequest(format!(
+                "Remote agents must use https: {}",
+                url
+            )));
+        }
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| A2AError::InvalidRequest(format!("No port for {}", url)))?;
+
+        let builder = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REMOTE_AGENT_TIMEOUT_SECS))
+            .redirect(reqwest::redirect::Policy::none());
+        let builder = match url.host() {
+            Some(Host::Ipv4(ip)) => {
+                self.check_address(url, IpAddr::V4(ip))?;
+                builder
+            }
+            Some(Host::Ipv6(ip)) => {
+                self.check_address(url, IpAddr::V6(ip))?;
+                builder
+            }
+            Some(Host::Domain(domain)) => {
+                let addresses: Vec<SocketAddr> = tokio::net::lookup_host((domain, port))
+                    .await
+                    .map_err(|e| {
+                        A2AError::RemoteAgentError(format!("Cannot resolve {}: {}", domain, e))
+                    })?
+                    .collect();
+                let pinned = *addresses.first().ok_or_else(|| {
+                    A2AError::RemoteAgentError(format!("{} has no addresses", domain))
+                })?;
+                for address in &addresses {
+                    self.check_address(url, address.ip())?;
+                }
+                builder.resolve(domain, pinned)
+            }
+            None => {
+                return Err(A2AError::InvalidRequest(format!("No host in {}", url)));
+            }
+        };
+
+        builder
+            .build()
+            .map_err(|e| A2AError::InternalError(e.to_string()))
+    }
+
+    fn check_address(&self, url: &Url, ip: IpAddr) -> Result<(), A2AError> {
+        if self.policy.allow_private_networks || is_public_address(ip) {
+            Ok(())
+        } else {
+            Err(A2AError::InvalidRequest(format!(
+                "Remote agent {} resolves to {}, which is not a public address",
+                url, ip
+            )))
+        }
+    }
+
+    /// Fetch the agent card from the well-known locations of `base_url`
+    pub async fn fetch_agent_card(&self, base_url: &Url) -> Result<RemoteAgentCard, A2AError> {
+        let http = self.client_for(base_url).await?;
+        let mut last_error = String::from("no agent card location answered");
+
+        for path in AGENT_CARD_PATHS {
+            let card_url = base_url
+                .join(path.trim_start_matches('/'))
+                .map_err(|e| A2AError::RemoteAgentError(e.to_string()))?;
+
+            match http.get(card_url.clone()).send().await {
+                Ok(response) if response.status().is_success() => {
+                    return response.json::<RemoteAgentCard>().await.map_err(|e| {
+                        A2AError::RemoteAgentError(format!(
+                            "Malformed agent card at {}: {}",
+                            card_url, e
+                        ))
+                    });
+                }
+                Ok(response) => {
+                    last_error = format!("{} returned {}", card_url, response.status());
+                }
+                Err(e) => {
+                    last_error = format!("{} unreachable: {}", card_url, e);
+                }
+            }
+        }
+
+        Err(A2AError::RemoteAgentError(format!(
+            "Failed to fetch agent card: {}",
+            last_error
+        )))
+    }
+
+    /// Send a message to the remote agent (`message/send`)
+    pub async fn send_message(
+        &self,
+        agent: &RemoteAgent,
+        message: &A2AMessage,
+    ) -> Result<Value, A2AError> {
+        let params = serde_json::json!({ "message": message });
+        self.call(agent, "message/send", params).await
+    }
+
+    /// Fetch the state of a task running on the remote agent (`tasks/get`)
+    pub async fn get_task(&self, agent: &RemoteAgent, task_id: &str) -> Result<Value, A2AError> {
+        let params = serde_json::json!({ "task_id": task_id });
+        self.call(agent, "tasks/get", params).await
+    }
+
+    /// Cancel a task running on the remote agent (`tasks/cancel`)
+    pub async fn cancel_task(&self, agent: &RemoteAgent, task_id: &str) -> Result<Value, A2AError> {
+        let params = serde_json::json!({ "task_id": task_id });
+        self.call(agent, "tasks/cancel", params).await
+    }
+
+    /// Perform a JSON-RPC call against the agent endpoint
+    async fn call(
+        &self,
+        agent: &RemoteAgent,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, A2AError> {
+        let request = A2ARequest {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params: Some(params),
+            id: Some(Value::String(Uuid::new_v4().to_string())),
+        };
+
+        let endpoint = Url::parse(&agent.endpoint_url)
+            .map_err(|e| A2AError::RemoteAgentError(format!("Invalid agent endpoint: {}", e)))?;
+        let mut http_request = self
+            .client_for(&endpoint)
+            .await?
+            .post(endpoint)
+            .header("content-type", crate::a2a::A2A_CONTENT_TYPE)
+            .json(&request);
+        if let Some(token) = &agent.auth_token {
+            http_request = http_request.bearer_auth(token);
+        }
+
+        let response = http_request.send().await.map_err(|e| {
+            A2AError::RemoteAgentError(format!("{} unreachable: {}", agent.name, e))
+        })?;
+
+        if !response.status().is_success() {
+            return Err(A2AError::RemoteAgentError(format!(
+                "{} answered {} to {}",
+                agent.name,
+                response.status(),
+                method
+            )));
+        }
+
+        let rpc_response: A2AResponse = response
+            .json()
+            .await
+            .map_err(|e| A2AError::RemoteAgentError(format!("Invalid JSON-RPC response: {}", e)))?;
+
+        if let Some(error) = rpc_response.error {
+            return Err(A2AError::RemoteAgentError(format!(
+                "{} rejected {} ({}): {}",
+                agent.name, method, error.code, error.message
+            )));
+        }
+
+        Ok(rpc_response.result.unwrap_or(Value::Null))
+    }
+}
+
+impl Default for RemoteAgentClient {
+    fn default() -> Self {
+        Self::new(RemoteAgentPolicy::default())
+    }
+}
+
+/// Registry of trusted remote agents, per tenant or per user
+#[derive(Clone)]
+pub struct RemoteAgentRegistry {
+    database: Arc<Database>,
+    client: RemoteAgentClient,
+}
+
+impl RemoteAgentRegistry {
+    /// A registry with the network policy from the environment
+    pub fn new(database: Arc<Database>) -> Self {
+        Self::with_policy(database, RemoteAgentPolicy::from_env())
+    }
+
+    pub fn with_policy(database: Arc<Database>, policy: RemoteAgentPolicy) -> Self {
+        Self {
+            database,
+            client: RemoteAgentClient::new(policy),
+        }
+    }
+
+    /// The registry a user delegates through: their tenant's, or their own
+    pub async fn owner_for_user(&self, user_id: Uuid) -> Result<AgentOwner, A2AError> {
+        let tenant_id = self
+            .database
+            .get_user_tenant(user_id)
+            .await
+            .map_err(|e| A2AError::DatabaseError(e.to_string()))?;
+        Ok(tenant_id.map_or(AgentOwner::User(user_id), AgentOwner::Tenant))
+    }
+
+    /// The user's personal registry, which tenant members don't have
+    async fn personal_owner(&self, user_id: Uuid) -> Result<AgentOwner, A2AError> {
+        match self.owner_for_user(user_id).await? {
+            AgentOwner::Tenant(tenant_id) => Err(A2AError::Forbidden(format!(
+                "Remote agents of tenant {} are managed by its administrators",
+                tenant_id
+            ))),
+            owner => Ok(owner),
+        }
+    }
+
+    /// Trust a remote agent in the personal registry of a user outside any tenant
+    pub async fn register_user_agent(
+        &self,
+        user_id: Uuid,
+        request: RemoteAgentRegistrationRequest,
+    ) -> Result<RemoteAgent, A2AError> {
+        let owner = self.personal_owner(user_id).await?;
+        self.register_agent(&owner, request).await
+    }
+
+    /// Remove an agent from the personal registry of a user outside any tenant
+    pub async fn remove_user_agent(&self, user_id: Uuid, agent_id: &str) -> Result<(), A2AError> {
+        let owner = self.personal_owner(user_id).await?;
+        self.remove_agent(&owner, agent_id).await
+    }
+
+    /// The agents a user may delegate to
+    pub async fn agents_for_user(&self, user_id: Uuid) -> Result<Vec<RemoteAgent>, A2AError> {
+        let owner = self.owner_for_user(user_id).await?;
+        self.list_agents(&owner).await
+    }
+
+    /// Discover, validate and trust a remote agent
+    ///
+    /// Registering an already trusted base URL refreshes its card.
+    pub async fn register_agent(
+        &self,
+        owner: &AgentOwner,
+        request: RemoteAgentRegistrationRequest,
+    ) -> Result<RemoteAgent, A2AError> {
+        let base_url = parse_base_url(&request.base_url)?;
+        let card = self.client.fetch_agent_card(&base_url).await?;
+        let endpoint = card.validate(&base_url)?;
+
+        let existing = self
+            .list_agents(owner)
+            .await?
+            .into_iter()
+            .find(|agent| agent.base_url == base_url.as_str());
+
+        let now = Utc::now();
+        let agent = RemoteAgent {
+            id: existing
+                .as_ref()
+                .map(|agent| agent.id.clone())
+                .unwrap_or_else(|| format!("remote_agent_{}", Uuid::new_v4().simple())),
+            owner: owner.clone(),
+            name: card.name.clone(),
+            description: card.description.clone(),
+            base_url: base_url.to_string(),
+            endpoint_url: endpoint.to_string(),
+            capabilities: card.capability_names(),
+            auth_token: request
+                .auth_token
+                .or_else(|| existing.as_ref().and_then(|a| a.auth_token.clone())),
+            is_active: true,
+            created_at: existing.as_ref().map_or(now, |agent| agent.created_at),
+            last_verified_at: now,
+        };
+
+        self.database
+            .upsert_remote_agent(&agent)
+            .await
+            .map_err(|e| A2AError::DatabaseError(e.to_string()))?;
+
+        tracing::info!(
+            "{} {} trusted remote agent {} ({})",
+            owner.scope(),
+            owner.id(),
+            agent.name,
+            agent.base_url
+        );
+        Ok(agent)
+    }
+
+    /// List the remote agents in a registry
+    pub async fn list_agents(&self, owner: &AgentOwner) -> Result<Vec<RemoteAgent>, A2AError> {
+        self.database
+            .list_remote_agents(owner)
+            .await
+            .map_err(|e| A2AError::DatabaseError(e.to_string()))
+    }
+
+    /// Stop trusting a remote agent
+    pub async fn remove_agent(&self, owner: &AgentOwner, agent_id: &str) -> Result<(), A2AError> {
+        let removed = self
+            .database
+            .delete_remote_agent(owner, agent_id)
+            .await
+            .map_err(|e| A2AError::DatabaseError(e.to_string()))?;
+
+        if removed {
+            Ok(())
+        } else {
+            Err(A2AError::RemoteAgentNotFound(agent_id.to_string()))
+        }
+    }
+
+    /// Delegate work to a trusted agent with a text message and optional data
+    pub async fn delegate(
+        &self,
+        user_id: Uuid,
+        agent_id: &str,
+        text: &str,
+        data: Option<Value>,
+    ) -> Result<Value, A2AError> {
+        let agent = self.trusted_agent(user_id, agent_id).await?;
+
+        let mut parts = vec![MessagePart::Text {
+            content: text.to_string(),
+        }];
+        if let Some(content) = data {
+            parts.push(MessagePart::Data { content });
+        }
+
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "sender".to_string(),
+            Value::String(crate::constants::protocol::server_name()),
+        );
+
+        let message = A2AMessage {
+            id: Uuid::new_v4().to_string(),
+            parts,
+            metadata: Some(metadata),
+        };
+
+        self.client.send_message(&agent, &message).await
+    }
+
+    /// Fetch the state of a task delegated to a trusted agent
+    pub async fn get_task(
+        &self,
+        user_id: Uuid,
+        agent_id: &str,
+        task_id: &str,
+    ) -> Result<Value, A2AError> {
+        let agent = self.trusted_agent(user_id, agent_id).await?;
+        self.client.get_task(&agent, task_id).await
+    }
+
+    /// Cancel a task delegated to a trusted agent
+    pub async fn cancel_task(
+        &self,
+        user_id: Uuid,
+        agent_id: &str,
+        task_id: &str,
+    ) -> Result<Value, A2AError> {
+        let agent = self.trusted_agent(user_id, agent_id).await?;
+        self.client.cancel_task(&agent, task_id).await
+    }
+
+    /// Look up an active agent this user may delegate to
+    async fn trusted_agent(&self, user_id: Uuid, agent_id: &str) -> Result<RemoteAgent, A2AError> {
+        let owner = self.owner_for_user(user_id).await?;
+        match self.database.get_remote_agent(&owner, agent_id).await {
+            Ok(Some(agent)) if agent.is_active => Ok(agent),
+            Ok(_) => Err(A2AError::RemoteAgentNotFound(agent_id.to_string())),
+            Err(e) => Err(A2AError::DatabaseError(e.to_string())),
+        }
+    }
+}
+
+/// Parse and normalize a remote agent base URL
+fn parse_base_url(raw: &str) -> Result<Url, A2AError> {
+    let mut url = Url::parse(raw.trim())
+        .map_err(|e| A2AError::InvalidRequest(format!("Invalid agent URL '{}': {}", raw, e)))?;
+
+    if url.scheme() != "https" && url.scheme() != "http" {
+        return Err(A2AError::InvalidRequest(format!(
+            "Unsupported agent URL scheme: {}",
+            url.scheme()
+        )));
+    }
+
+    // Treat the base URL as a directory so relative joins stay beneath it
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    url.set_query(None);
+    url.set_fragment(None);
+
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(url: Option<&str>) -> RemoteAgentCard {
+        RemoteAgentCard {
+            name: "Nutrition Agent".to_string(),
+            description: "Meal planning".to_string(),
+            version: "1.0.0".to_string(),
+            url: url.map(str::to_string),
+            capabilities: serde_json::json!({"streaming": false, "pushNotifications": true}),
+            skills: vec![],
+            tools: vec![],
+        }
+    }
+
+    #[test]
+    fn test_parse_base_url_normalizes_path() {
+        let url = parse_base_url("https://agents.example.com/nutrition?x=1").unwrap();
+        assert_eq!(url.as_str(), "https://agents.example.com/nutrition/");
+
+        assert!(parse_base_url("ftp://agents.example.com").is_err());
+        assert!(parse_base_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_card_validation_resolves_endpoint() {
+        let base = parse_base_url("https://agents.example.com/nutrition").unwrap();
+
+        let endpoint = card(None).validate(&base).unwrap();
+        assert_eq!(
+            endpoint.as_str(),
+            "https://agents.example.com/nutrition/a2a"
+        );
+
+        let endpoint = card(Some("rpc")).validate(&base).unwrap();
+        assert_eq!(
+            endpoint.as_str(),
+            "https://agents.example.com/nutrition/rpc"
+        );
+    }
+
+    #[test]
+    fn test_card_validation_rejects_foreign_endpoint() {
+        let base = parse_base_url("https://agents.example.com").unwrap();
+        assert!(card(Some("https://evil.example.net/a2a"))
+            .validate(&base)
+            .is_err());
+
+        let mut nameless = card(None);
+        nameless.name = "  ".to_string();
+        assert!(nameless.validate(&base).is_err());
+    }
+
+    #[test]
+    fn test_public_addresses() {
+        for private in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_address(private.parse().unwrap()), "{}", private);
+        }
+        for public in ["93.184.216.34", "2606:2800:220:1:248:1893:25c8:1946"] {
+            assert!(is_public_address(public.parse().unwrap()), "{}", public);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_refuses_private_and_plain_http_agents() {
+        let client = RemoteAgentClient::default();
+        for url in [
+            "http://agents.example.com/",
+            "https://127.0.0.1/",
+            "https://169.254.169.254/latest/",
+            "https://[::1]:8443/",
+            "https://localhost/",
+        ] {
+            let result = client.client_for(&Url::parse(url).unwrap()).await;
+            assert!(
+                matches!(result, Err(A2AError::InvalidRequest(_))),
+                "{} was allowed",
+                url
+            );
+        }
+
+        let permissive = RemoteAgentClient::new(RemoteAgentPolicy {
+            allow_private_networks: true,
+        });
+        assert!(permissive
+            .client_for(&Url::parse("http://127.0.0.1:8080/").unwrap())
+            .await
+            .is_ok());
+    }
+
+    #[test]
+    fn test_capability_names_from_list_and_flags() {
+        assert_eq!(card(None).capability_names(), vec!["pushNotifications"]);
+
+        let mut listed = card(None);
+        listed.capabilities = serde_json::json!(["meal-planning", "macros"]);
+        assert_eq!(listed.capability_names(), vec!["meal-planning", "macros"]);
+    }
+}
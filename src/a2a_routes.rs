@@ -9,9 +9,10 @@
 //! HTTP endpoints for A2A (Agent-to-Agent) protocol management
 
 use crate::a2a::{
-    agent_card::AgentCard,
+    agent_card::{AgentCard, AgentCardSigner},
     auth::A2AAuthenticator,
     client::{A2AClientManager, ClientRegistrationRequest},
+    remote::{RemoteAgent, RemoteAgentRegistrationRequest, RemoteAgentRegistry},
     A2AError,
 };
 use crate::auth::AuthManager;
@@ -62,6 +63,8 @@ pub struct A2ARoutes {
     client_manager: Arc<A2AClientManager>,
     authenticator: Arc<A2AAuthenticator>,
     tool_executor: UniversalToolExecutor,
+    remote_agents: RemoteAgentRegistry,
+    agent_card_signer: Option<Arc<AgentCardSigner>>,
     config: Arc<crate::config::environment::ServerConfig>,
 }
 
@@ -101,6 +104,23 @@ impl A2ARoutes {
 
         let tool_executor =
             UniversalToolExecutor::new(database.clone(), intelligence, config.clone());
+        let remote_agents = RemoteAgentRegistry::new(database.clone());
+
+        let agent_card_signer =
+            config
+                .a2a
+                .agent_card_signing_key_path
+                .as_deref()
+                .and_then(|path| match AgentCardSigner::load_or_generate(path) {
+                    Ok(signer) => {
+                        tracing::info!("Agent card signing key {} loaded", signer.key_id());
+                        Some(Arc::new(signer))
+                    }
+                    Err(e) => {
+                        tracing::warn!("Serving unsigned agent card: {}", e);
+                        None
+                    }
+                });
 
         Self {
             database,
@@ -108,13 +128,36 @@ impl A2ARoutes {
             client_manager,
             authenticator,
             tool_executor,
+            remote_agents,
+            agent_card_signer,
             config,
         }
     }
 
-    /// Get A2A agent card
+    /// Resolve the user behind a JWT bearer header
+    fn authenticate_user(&self, auth_header: Option<&str>) -> Result<Uuid, A2AError> {
+        let token = auth_header
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .ok_or_else(|| {
+                A2AError::AuthenticationFailed("Missing or invalid authorization header".into())
+            })?;
+
+        let claims = self
+            .auth_manager
+            .validate_token(token)
+            .map_err(|e| A2AError::AuthenticationFailed(e.to_string()))?;
+
+        Uuid::parse_str(&claims.sub)
+            .map_err(|e| A2AError::AuthenticationFailed(format!("Invalid user ID: {}", e)))
+    }
+
+    /// Get A2A agent card, built from the tools this server can execute
     pub async fn get_agent_card(&self) -> Result<AgentCard, A2AError> {
-        Ok(AgentCard::new())
+        let mut card = AgentCard::for_server(&self.tool_executor, &self.config);
+        if let Some(signer) = &self.agent_card_signer {
+            card.sign(signer)?;
+        }
+        Ok(card)
     }
 
     /// Get A2A dashboard overview
@@ -132,10 +175,18 @@ impl A2ARoutes {
         let total_clients = clients.len() as u32;
         let active_clients = clients.iter().filter(|c| c.is_active).count() as u32;
 
-        // For now, sessions and usage stats will be basic counts
-        // These would need proper session tracking implementation
-        let total_sessions = 0; // No session tracking implemented yet
-        let active_sessions = 0; // No session tracking implemented yet
+        let mut active_sessions = 0;
+        for client in clients.iter().filter(|c| c.is_active) {
+            active_sessions += self
+                .client_manager
+                .get_active_sessions(&client.id)
+                .await?
+                .len() as u32;
+        }
+        // Expired and revoked sessions are cleaned up, so only open ones remain
+        let total_sessions = active_sessions;
+
+        // For now, usage stats will be basic counts
         let requests_today = 0; // No usage logging implemented yet
         let requests_this_month = 0; // No usage logging implemented yet
         let most_used_capability = None; // No usage tracking implemented yet
@@ -213,26 +264,65 @@ impl A2ARoutes {
             .await
     }
 
-    /// Deactivate A2A client
-    pub async fn deactivate_client(
+    /// Trust a remote agent for outbound delegation
+    pub async fn register_remote_agent(
         &self,
-        _auth_header: Option<&str>,
-        client_id: &str,
+        auth_header: Option<&str>,
+        request: RemoteAgentRegistrationRequest,
+    ) -> Result<RemoteAgent, A2AError> {
+        let user_id = self.authenticate_user(auth_header)?;
+        self.remote_agents
+            .register_user_agent(user_id, request)
+            .await
+    }
+
+    /// List remote agents trusted by the caller
+    pub async fn list_remote_agents(
+        &self,
+        auth_header: Option<&str>,
+    ) -> Result<Vec<RemoteAgent>, A2AError> {
+        let user_id = self.authenticate_user(auth_header)?;
+        self.remote_agents.agents_for_user(user_id).await
+    }
+
+    /// Stop trusting a remote agent
+    pub async fn remove_remote_agent(
+        &self,
+        auth_header: Option<&str>,
+        agent_id: &str,
     ) -> Result<(), A2AError> {
-        self.client_manager.deactivate_client(client_id).await
+        let user_id = self.authenticate_user(auth_header)?;
+        self.remote_agents
+            .remove_user_agent(user_id, agent_id)
+            .await
     }
 
     /// Authenticate A2A request
+    ///
+    /// Exchanges a client ID and secret for a session token.
     pub async fn authenticate(
         &self,
-        _request: serde_json::Value,
+        request: serde_json::Value,
     ) -> Result<serde_json::Value, A2AError> {
-        // This would handle A2A authentication requests
-        // For now, return a basic response
+        let field = |name: &str| {
+            request
+                .get(name)
+                .and_then(|value| value.as_str())
+                .ok_or_else(|| A2AError::InvalidRequest(format!("Missing {} field", name)))
+        };
+        let client_id = field("client_id")?;
+        let client_secret = field("client_secret")?;
+
+        let client = self
+            .client_manager
+            .verify_client_secret(client_id, client_secret)
+            .await?;
+        let session_token = self.client_manager.create_session(&client.id, None).await?;
+
         Ok(serde_json::json!({
             "status": "authenticated",
-            "session_token": format!("a2a_session_{}", Uuid::new_v4()),
-            "expires_in": 3600
+            "session_token": session_token,
+            "expires_in": 24 * 3600
         }))
     }
 
@@ -388,6 +478,8 @@ impl Clone for A2ARoutes {
             client_manager: self.client_manager.clone(),
             authenticator: self.authenticator.clone(),
             tool_executor,
+            remote_agents: self.remote_agents.clone(),
+            agent_card_signer: self.agent_card_signer.clone(),
             config: self.config.clone(),
         }
     }
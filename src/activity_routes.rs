@@ -0,0 +1,109 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! # Manual Activity Routes
+//!
+//! REST endpoints for activities the user enters by hand, backing the same
+//! storage as the `log_activity`, `update_activity` and `delete_activity`
+//! tools:
+//!
+//! - `POST /api/activities` logs an activity
+//! - `GET /api/activities` lists manual activities, newest first
+//! - `PUT /api/activities/{id}` edits one
+//! - `DELETE /api/activities/{id}` removes one
+
+use crate::auth::AuthManager;
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::models::Activity;
+use crate::providers::manual::{ManualActivityRequest, ManualActivityUpdate};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ActivityRoutes {
+    database: Arc<Database>,
+    auth_manager: Arc<AuthManager>,
+}
+
+impl ActivityRoutes {
+    pub fn new(database: Arc<Database>, auth_manager: Arc<AuthManager>) -> Self {
+        Self {
+            database,
+            auth_manager,
+        }
+    }
+
+    /// Log a manual activity
+    pub async fn log_activity(
+        &self,
+        auth_header: Option<&str>,
+        request: ManualActivityRequest,
+    ) -> Result<Activity> {
+        let user_id = self.authenticate_user(auth_header)?;
+        let activity = request.into_activity()?;
+        self.database
+            .create_manual_activity(user_id, &activity)
+            .await?;
+        Ok(activity)
+    }
+
+    /// List the caller's manual activities, newest first
+    pub async fn list_activities(
+        &self,
+        auth_header: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Activity>> {
+        let user_id = self.authenticate_user(auth_header)?;
+        self.database.list_manual_activities(user_id, limit).await
+    }
+
+    /// Edit a manual activity
+    pub async fn update_activity(
+        &self,
+        auth_header: Option<&str>,
+        activity_id: &str,
+        update: ManualActivityUpdate,
+    ) -> Result<Activity> {
+        let user_id = self.authenticate_user(auth_header)?;
+        let mut activity = self
+            .database
+            .get_manual_activity(user_id, activity_id)
+            .await?
+            .ok_or_else(|| anyhow!("Manual activity '{}' not found", activity_id))?;
+        update.apply(&mut activity)?;
+        self.database
+            .update_manual_activity(user_id, &activity)
+            .await?;
+        Ok(activity)
+    }
+
+    /// Delete a manual activity
+    pub async fn delete_activity(
+        &self,
+        auth_header: Option<&str>,
+        activity_id: &str,
+    ) -> Result<()> {
+        let user_id = self.authenticate_user(auth_header)?;
+        if !self
+            .database
+            .delete_manual_activity(user_id, activity_id)
+            .await?
+        {
+            return Err(anyhow!("Manual activity '{}' not found", activity_id));
+        }
+        Ok(())
+    }
+
+    /// Resolve the calling user from a `Bearer` JWT
+    fn authenticate_user(&self, auth_header: Option<&str>) -> Result<Uuid> {
+        let token = auth_header
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .ok_or_else(|| anyhow!("Missing or invalid authorization header"))?;
+        let claims = self.auth_manager.validate_token(token)?;
+        Ok(Uuid::parse_str(&claims.sub)?)
+    }
+}
@@ -346,8 +346,8 @@ mod tests {
             )
             .await;
 
-        if result.is_err() {
-            println!("Auth test error: {}", result.as_ref().unwrap_err());
+        if let Err(e) = &result {
+            println!("Auth test error: {}", e);
         }
         assert!(result.is_ok());
         let validated = result.unwrap();
@@ -4,15 +4,22 @@
 //! and perform administrative operations on the Pierre MCP Server.
 
 use crate::{
+    a2a::{
+        client::{A2AClientManager, A2AClientQuota, CredentialRotationRequest},
+        remote::RemoteAgentRegistrationRequest,
+        A2AError, AgentOwner, RemoteAgentRegistry,
+    },
     admin::{auth::AdminAuthService, models::AdminPermission},
     api_keys::ApiKeyTier,
     auth::AuthManager,
     database_plugins::{factory::Database, DatabaseProvider},
+    intelligence::settings::{self, AnalysisSettingsUpdate},
     models::User,
 };
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::{info, warn};
 use uuid::Uuid;
 use warp::{
@@ -106,11 +113,15 @@ pub fn admin_routes(
     let list_keys_route = list_api_keys_route(context.clone());
     let token_info_route = token_info_route(context.clone());
     let health_route = admin_health_route();
+    let a2a_client_routes = a2a_client_routes(context.clone());
+    let tenant_routes = tenant_routes(context);
 
     let admin_routes = provision_route
         .or(revoke_route)
         .or(list_keys_route)
         .or(token_info_route)
+        .or(a2a_client_routes)
+        .or(tenant_routes)
         .or(health_route);
 
     warp::path("admin")
@@ -177,6 +188,161 @@ fn token_info_route(
         .and_then(handle_token_info)
 }
 
+/// A2A client lifecycle endpoints
+fn a2a_client_routes(
+    context: AdminApiContext,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let list = warp::path!("a2a-clients")
+        .and(warp::get())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::ListKeys,
+        ))
+        .and(with_context(context.clone()))
+        .and_then(handle_list_a2a_clients);
+
+    let deactivate = warp::path!("a2a-clients" / String / "deactivate")
+        .and(warp::post())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::RevokeKeys,
+        ))
+        .and(with_context(context.clone()))
+        .and_then(handle_deactivate_a2a_client);
+
+    let reactivate = warp::path!("a2a-clients" / String / "reactivate")
+        .and(warp::post())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::ProvisionKeys,
+        ))
+        .and(with_context(context.clone()))
+        .and_then(handle_reactivate_a2a_client);
+
+    let rotate = warp::path!("a2a-clients" / String / "rotate-credentials")
+        .and(warp::post())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::ProvisionKeys,
+        ))
+        .and(warp::body::json())
+        .and(with_context(context.clone()))
+        .and_then(handle_rotate_a2a_client_credentials);
+
+    let sessions = warp::path!("a2a-clients" / String / "sessions")
+        .and(warp::get())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::ListKeys,
+        ))
+        .and(with_context(context.clone()))
+        .and_then(handle_list_a2a_client_sessions);
+
+    let revoke_session = warp::path!("a2a-clients" / String / "sessions" / String)
+        .and(warp::delete())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::RevokeKeys,
+        ))
+        .and(with_context(context.clone()))
+        .and_then(handle_revoke_a2a_client_session);
+
+    let quota = warp::path!("a2a-clients" / String / "quota")
+        .and(warp::put())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::UpdateKeyLimits,
+        ))
+        .and(warp::body::json())
+        .and(with_context(context))
+        .and_then(handle_update_a2a_client_quota);
+
+    list.or(deactivate)
+        .or(reactivate)
+        .or(rotate)
+        .or(sessions)
+        .or(revoke_session)
+        .or(quota)
+}
+
+/// Tenant analysis settings and membership endpoints
+fn tenant_routes(
+    context: AdminApiContext,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let get_settings = warp::path!("tenants" / String / "analysis-settings")
+        .and(warp::get())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::ManageUsers,
+        ))
+        .and(with_context(context.clone()))
+        .and_then(handle_get_tenant_settings);
+
+    let update_settings = warp::path!("tenants" / String / "analysis-settings")
+        .and(warp::put())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::ManageUsers,
+        ))
+        .and(warp::body::json())
+        .and(with_context(context.clone()))
+        .and_then(handle_update_tenant_settings);
+
+    let add_member = warp::path!("tenants" / String / "members" / String)
+        .and(warp::put())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::ManageUsers,
+        ))
+        .and(with_context(context.clone()))
+        .and_then(handle_add_tenant_member);
+
+    let remove_member = warp::path!("tenants" / String / "members" / String)
+        .and(warp::delete())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::ManageUsers,
+        ))
+        .and(with_context(context.clone()))
+        .and_then(handle_remove_tenant_member);
+
+    let list_agents = warp::path!("tenants" / String / "remote-agents")
+        .and(warp::get())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::ManageUsers,
+        ))
+        .and(with_context(context.clone()))
+        .and_then(handle_list_tenant_remote_agents);
+
+    let register_agent = warp::path!("tenants" / String / "remote-agents")
+        .and(warp::post())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::ManageUsers,
+        ))
+        .and(warp::body::json())
+        .and(with_context(context.clone()))
+        .and_then(handle_register_tenant_remote_agent);
+
+    let remove_agent = warp::path!("tenants" / String / "remote-agents" / String)
+        .and(warp::delete())
+        .and(admin_auth_filter(
+            context.clone(),
+            AdminPermission::ManageUsers,
+        ))
+        .and(with_context(context))
+        .and_then(handle_remove_tenant_remote_agent);
+
+    get_settings
+        .or(update_settings)
+        .or(add_member)
+        .or(remove_member)
+        .or(list_agents)
+        .or(register_agent)
+        .or(remove_agent)
+}
+
 /// Admin health check endpoint
 fn admin_health_route() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
     warp::path!("health").and(warp::get()).map(|| {
@@ -331,8 +497,6 @@ async fn handle_provision_api_key(
                 display_name: Some(format!("API User ({})", request.user_email)),
                 password_hash: "api-key-only".to_string(), // API-only user
                 tier: crate::models::UserTier::Starter,    // Default tier for API users
-                strava_token: None,
-                fitbit_token: None,
                 is_active: true,
                 created_at: chrono::Utc::now(),
                 last_active: chrono::Utc::now(),
@@ -699,6 +863,420 @@ pub enum AdminApiError {
 
 impl warp::reject::Reject for AdminApiError {}
 
+fn a2a_client_manager(context: &AdminApiContext) -> A2AClientManager {
+    A2AClientManager::new(Arc::new(context.database.clone()))
+}
+
+/// Build the admin response for an A2A client operation
+fn a2a_client_reply(
+    result: Result<serde_json::Value, A2AError>,
+    message: String,
+) -> warp::reply::WithStatus<warp::reply::Json> {
+    match result {
+        Ok(data) => {
+            let response = AdminResponse {
+                success: true,
+                message,
+                data: Some(data),
+            };
+            with_status(json(&response), StatusCode::OK)
+        }
+        Err(e) => {
+            let status = match e {
+                A2AError::ClientNotRegistered(_)
+                | A2AError::SessionNotFound(_)
+                | A2AError::RemoteAgentNotFound(_) => StatusCode::NOT_FOUND,
+                A2AError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+                A2AError::Forbidden(_) => StatusCode::FORBIDDEN,
+                A2AError::RemoteAgentError(_) => StatusCode::BAD_GATEWAY,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            let response = AdminResponse {
+                success: false,
+                message: e.to_string(),
+                data: None,
+            };
+            with_status(json(&response), status)
+        }
+    }
+}
+
+/// Handle A2A client listing
+async fn handle_list_a2a_clients(
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "📋 Listing A2A clients by service: {}",
+        admin_token.service_name
+    );
+
+    let result = a2a_client_manager(&context)
+        .list_clients()
+        .await
+        .map(|clients| serde_json::json!({ "clients": clients, "count": clients.len() }));
+
+    Ok(a2a_client_reply(
+        result,
+        "A2A clients retrieved".to_string(),
+    ))
+}
+
+/// Handle A2A client deactivation
+async fn handle_deactivate_a2a_client(
+    client_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "🚫 Deactivating A2A client: {} by service: {}",
+        client_id, admin_token.service_name
+    );
+
+    let result = a2a_client_manager(&context)
+        .deactivate_client(&client_id)
+        .await
+        .map(|()| {
+            serde_json::json!({
+                "client_id": client_id,
+                "is_active": false,
+                "deactivated_by": admin_token.service_name
+            })
+        });
+
+    Ok(a2a_client_reply(
+        result,
+        format!("A2A client {} deactivated", client_id),
+    ))
+}
+
+/// Handle A2A client reactivation
+async fn handle_reactivate_a2a_client(
+    client_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "✅ Reactivating A2A client: {} by service: {}",
+        client_id, admin_token.service_name
+    );
+
+    let result = a2a_client_manager(&context)
+        .reactivate_client(&client_id)
+        .await
+        .map(|()| serde_json::json!({ "client_id": client_id, "is_active": true }));
+
+    Ok(a2a_client_reply(
+        result,
+        format!("A2A client {} reactivated", client_id),
+    ))
+}
+
+/// Handle A2A client credential rotation
+async fn handle_rotate_a2a_client_credentials(
+    client_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    request: CredentialRotationRequest,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "🔄 Rotating credentials of A2A client: {} by service: {}",
+        client_id, admin_token.service_name
+    );
+
+    let result = a2a_client_manager(&context)
+        .rotate_credentials(&client_id, request.overlap_hours)
+        .await
+        .and_then(|rotation| {
+            serde_json::to_value(rotation).map_err(|e| A2AError::InternalError(e.to_string()))
+        });
+
+    Ok(a2a_client_reply(
+        result,
+        format!("Credentials of A2A client {} rotated", client_id),
+    ))
+}
+
+/// Handle A2A client session listing
+async fn handle_list_a2a_client_sessions(
+    client_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "📋 Listing sessions of A2A client: {} by service: {}",
+        client_id, admin_token.service_name
+    );
+
+    let result = a2a_client_manager(&context)
+        .get_active_sessions(&client_id)
+        .await
+        .map(|sessions| serde_json::json!({ "sessions": sessions, "count": sessions.len() }));
+
+    Ok(a2a_client_reply(
+        result,
+        format!("Sessions of A2A client {} retrieved", client_id),
+    ))
+}
+
+/// Handle A2A client session revocation
+async fn handle_revoke_a2a_client_session(
+    client_id: String,
+    session_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "🗑️ Revoking session {} of A2A client: {} by service: {}",
+        session_id, client_id, admin_token.service_name
+    );
+
+    let result = a2a_client_manager(&context)
+        .revoke_session(&client_id, &session_id)
+        .await
+        .map(|()| serde_json::json!({ "client_id": client_id, "session_id": session_id }));
+
+    Ok(a2a_client_reply(
+        result,
+        format!("Session {} revoked", session_id),
+    ))
+}
+
+/// Handle A2A client quota update
+async fn handle_update_a2a_client_quota(
+    client_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    quota: A2AClientQuota,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "📊 Updating quota of A2A client: {} by service: {}",
+        client_id, admin_token.service_name
+    );
+
+    let result = a2a_client_manager(&context)
+        .update_client_quota(&client_id, quota)
+        .await
+        .map(|quota| {
+            serde_json::json!({
+                "client_id": client_id,
+                "tier": quota.tier,
+                "monthly_limit": quota.monthly_limit,
+                "effective_monthly_limit": quota.effective_monthly_limit()
+            })
+        });
+
+    Ok(a2a_client_reply(
+        result,
+        format!("Quota of A2A client {} updated", client_id),
+    ))
+}
+
+/// Build the admin response for a tenant operation
+fn tenant_reply(
+    result: Result<serde_json::Value>,
+    message: String,
+) -> warp::reply::WithStatus<warp::reply::Json> {
+    match result {
+        Ok(data) => {
+            let response = AdminResponse {
+                success: true,
+                message,
+                data: Some(data),
+            };
+            with_status(json(&response), StatusCode::OK)
+        }
+        Err(e) => {
+            let response = AdminResponse {
+                success: false,
+                message: format!("{:#}", e),
+                data: None,
+            };
+            with_status(json(&response), StatusCode::BAD_REQUEST)
+        }
+    }
+}
+
+/// Handle tenant analysis settings lookup
+async fn handle_get_tenant_settings(
+    tenant_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "📋 Getting analysis settings of tenant: {} by service: {}",
+        tenant_id, admin_token.service_name
+    );
+
+    let result = settings::tenant_settings(&context.database, &tenant_id).await;
+
+    Ok(tenant_reply(
+        result,
+        format!("Analysis settings of tenant {} retrieved", tenant_id),
+    ))
+}
+
+/// Handle tenant analysis settings update
+async fn handle_update_tenant_settings(
+    tenant_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    update: AnalysisSettingsUpdate,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "⚙️ Updating analysis settings of tenant: {} by service: {}",
+        tenant_id, admin_token.service_name
+    );
+
+    let result = settings::update_tenant_settings(
+        &context.database,
+        &tenant_id,
+        &update.settings,
+        update.reset,
+    )
+    .await;
+
+    Ok(tenant_reply(
+        result,
+        format!("Analysis settings of tenant {} updated", tenant_id),
+    ))
+}
+
+/// Handle adding a user to a tenant, moving them from any previous one
+async fn handle_add_tenant_member(
+    tenant_id: String,
+    user_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "👥 Adding user {} to tenant: {} by service: {}",
+        user_id, tenant_id, admin_token.service_name
+    );
+
+    let result = async {
+        let user_id = Uuid::parse_str(&user_id).map_err(|_| anyhow!("Invalid user ID"))?;
+        context
+            .database
+            .get_user(user_id)
+            .await?
+            .ok_or_else(|| anyhow!("User {} not found", user_id))?;
+        context
+            .database
+            .set_user_tenant(user_id, Some(&tenant_id))
+            .await?;
+        Ok(serde_json::json!({ "tenant_id": tenant_id, "user_id": user_id }))
+    }
+    .await;
+
+    Ok(tenant_reply(
+        result,
+        format!("User added to tenant {}", tenant_id),
+    ))
+}
+
+/// Handle removing a user from a tenant
+async fn handle_remove_tenant_member(
+    tenant_id: String,
+    user_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "👥 Removing user {} from tenant: {} by service: {}",
+        user_id, tenant_id, admin_token.service_name
+    );
+
+    let result = async {
+        let user_id = Uuid::parse_str(&user_id).map_err(|_| anyhow!("Invalid user ID"))?;
+        let current = context.database.get_user_tenant(user_id).await?;
+        if current.as_deref() != Some(tenant_id.as_str()) {
+            return Err(anyhow!("User {} is not in tenant {}", user_id, tenant_id));
+        }
+        context.database.set_user_tenant(user_id, None).await?;
+        Ok(serde_json::json!({ "tenant_id": tenant_id, "user_id": user_id }))
+    }
+    .await;
+
+    Ok(tenant_reply(
+        result,
+        format!("User removed from tenant {}", tenant_id),
+    ))
+}
+
+/// Handle listing the remote agents a tenant's members may delegate to
+async fn handle_list_tenant_remote_agents(
+    tenant_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "📋 Listing remote agents of tenant: {} by service: {}",
+        tenant_id, admin_token.service_name
+    );
+
+    let result = RemoteAgentRegistry::new(Arc::new(context.database.clone()))
+        .list_agents(&AgentOwner::Tenant(tenant_id.clone()))
+        .await
+        .map(|agents| serde_json::json!({ "remote_agents": agents, "count": agents.len() }));
+
+    Ok(a2a_client_reply(
+        result,
+        format!("Remote agents of tenant {} retrieved", tenant_id),
+    ))
+}
+
+/// Handle trusting a remote agent for a tenant's members
+async fn handle_register_tenant_remote_agent(
+    tenant_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    request: RemoteAgentRegistrationRequest,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "🤝 Trusting remote agent {} for tenant: {} by service: {}",
+        request.base_url, tenant_id, admin_token.service_name
+    );
+
+    let result = RemoteAgentRegistry::new(Arc::new(context.database.clone()))
+        .register_agent(&AgentOwner::Tenant(tenant_id.clone()), request)
+        .await
+        .and_then(|agent| {
+            serde_json::to_value(agent).map_err(|e| A2AError::InternalError(e.to_string()))
+        });
+
+    Ok(a2a_client_reply(
+        result,
+        format!("Remote agent trusted for tenant {}", tenant_id),
+    ))
+}
+
+/// Handle removing a remote agent from a tenant's registry
+async fn handle_remove_tenant_remote_agent(
+    tenant_id: String,
+    agent_id: String,
+    admin_token: crate::admin::models::ValidatedAdminToken,
+    context: AdminApiContext,
+) -> Result<impl Reply, Rejection> {
+    info!(
+        "🗑️ Removing remote agent {} of tenant: {} by service: {}",
+        agent_id, tenant_id, admin_token.service_name
+    );
+
+    let result = RemoteAgentRegistry::new(Arc::new(context.database.clone()))
+        .remove_agent(&AgentOwner::Tenant(tenant_id.clone()), &agent_id)
+        .await
+        .map(|()| serde_json::json!({ "tenant_id": tenant_id, "agent_id": agent_id }));
+
+    Ok(a2a_client_reply(
+        result,
+        format!(
+            "Remote agent {} removed from tenant {}",
+            agent_id, tenant_id
+        ),
+    ))
+}
+
 /// Handle admin API rejections
 async fn handle_admin_rejection(err: Rejection) -> Result<impl Reply, std::convert::Infallible> {
     let (status, message) = if let Some(AdminApiError::InvalidAuthHeader) = err.find() {
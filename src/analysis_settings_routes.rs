@@ -0,0 +1,64 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! # Analysis Settings Routes
+//!
+//! REST endpoints for the caller's own analysis settings overrides, backing
+//! the same storage as the `update_analysis_settings` tool:
+//!
+//! - `GET /api/analysis-settings` shows each layer and the effective config
+//! - `PUT /api/analysis-settings` merges overrides, or replaces them with
+//!   `"reset": true`
+//!
+//! Tenant overrides and membership are managed through the admin API.
+
+use crate::auth::AuthManager;
+use crate::database_plugins::factory::Database;
+use crate::intelligence::settings::{self, AnalysisSettings, AnalysisSettingsUpdate};
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct AnalysisSettingsRoutes {
+    database: Arc<Database>,
+    auth_manager: Arc<AuthManager>,
+}
+
+impl AnalysisSettingsRoutes {
+    pub fn new(database: Arc<Database>, auth_manager: Arc<AuthManager>) -> Self {
+        Self {
+            database,
+            auth_manager,
+        }
+    }
+
+    /// Get the caller's settings layers and effective config
+    pub async fn get_settings(&self, auth_header: Option<&str>) -> Result<AnalysisSettings> {
+        let user_id = self.authenticate_user(auth_header)?;
+        settings::settings_for_user(&self.database, user_id).await
+    }
+
+    /// Merge overrides into the caller's settings
+    pub async fn update_settings(
+        &self,
+        auth_header: Option<&str>,
+        update: AnalysisSettingsUpdate,
+    ) -> Result<AnalysisSettings> {
+        let user_id = self.authenticate_user(auth_header)?;
+        settings::update_user_settings(&self.database, user_id, &update.settings, update.reset)
+            .await
+    }
+
+    /// Resolve the calling user from a `Bearer` JWT
+    fn authenticate_user(&self, auth_header: Option<&str>) -> Result<Uuid> {
+        let token = auth_header
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .ok_or_else(|| anyhow!("Missing or invalid authorization header"))?;
+        let claims = self.auth_manager.validate_token(token)?;
+        Ok(Uuid::parse_str(&claims.sub)?)
+    }
+}
@@ -182,6 +182,18 @@ impl AuthManager {
 
     /// Generate a JWT token for a user
     pub fn generate_token(&self, user: &User) -> Result<String> {
+        self.generate_token_with_providers(user, Vec::new())
+    }
+
+    /// Generate a JWT token listing the providers the user has connected
+    ///
+    /// Provider connections live in the database, so callers that know them
+    /// (e.g. login) pass them in to populate the `providers` claim.
+    pub fn generate_token_with_providers(
+        &self,
+        user: &User,
+        providers: Vec<String>,
+    ) -> Result<String> {
         let now = Utc::now();
         let expiry = now + Duration::hours(self.token_expiry_hours);
 
@@ -190,7 +202,7 @@ impl AuthManager {
             email: user.email.clone(),
             iat: now.timestamp(),
             exp: expiry.timestamp(),
-            providers: user.available_providers(),
+            providers,
         };
 
         let token = encode(
@@ -313,7 +325,7 @@ impl AuthManager {
             jwt_token,
             expires_at,
             email: user.email.clone(),
-            available_providers: user.available_providers(),
+            available_providers: Vec::new(),
         })
     }
 
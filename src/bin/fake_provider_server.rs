@@ -0,0 +1,54 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Fake Provider Server
+//!
+//! Runs the fake Strava/Fitbit API standalone so a locally started server
+//! (and tools such as `test-with-data`) can be exercised offline.
+//!
+//! Usage:
+//! ```bash
+//! # Start the fake APIs, then export the printed variables before starting the server
+//! cargo run --bin fake-provider-server -- --port 9090 --fixtures fixtures.json
+//! ```
+
+use anyhow::Result;
+use clap::Parser;
+use pierre_mcp_server::providers::fake_server::{FakeProviderData, FakeProviderServer};
+
+#[derive(Parser)]
+#[command(
+    name = "fake-provider-server",
+    about = "Serve fake Strava and Fitbit APIs from fixture data"
+)]
+struct FakeServerArgs {
+    /// Port to listen on
+    #[arg(long, default_value = "9090")]
+    port: u16,
+
+    /// JSON fixtures file (built-in sample data when omitted)
+    #[arg(long)]
+    fixtures: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = FakeServerArgs::parse();
+    let data = match &args.fixtures {
+        Some(path) => FakeProviderData::from_file(path)?,
+        None => FakeProviderData::default(),
+    };
+
+    let server = FakeProviderServer::start_on(([127, 0, 0, 1], args.port).into(), data).await?;
+    println!("Fake provider APIs listening on {}", server.url());
+    println!("Point the server at them with:");
+    for (name, value) in server.env_vars() {
+        println!("  export {}={}", name, value);
+    }
+
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
@@ -13,7 +13,7 @@ use anyhow::Result;
 use clap::Parser;
 use pierre_mcp_server::{
     auth::{generate_jwt_secret, AuthManager},
-    config::environment::ServerConfig,
+    config::environment::{OAuthConfig, ServerConfig},
     constants::env_config,
     database::generate_encryption_key,
     database_plugins::factory::Database,
@@ -370,89 +370,33 @@ async fn handle_oauth_callback(
     // Create OAuth manager and register providers
     let mut oauth_manager = OAuthManager::new(database);
 
-    match provider {
-        "strava" => {
-            // For single-tenant mode, read from environment variables
-            let client_id = std::env::var("STRAVA_CLIENT_ID").map_err(|_| {
-                warp::reject::custom(OAuthCallbackError::ServerError(
-                    "STRAVA_CLIENT_ID not set".to_string(),
-                ))
-            })?;
-            let client_secret = std::env::var("STRAVA_CLIENT_SECRET").map_err(|_| {
-                warp::reject::custom(OAuthCallbackError::ServerError(
-                    "STRAVA_CLIENT_SECRET not set".to_string(),
-                ))
-            })?;
-            let redirect_uri = std::env::var("STRAVA_REDIRECT_URI").ok();
-
-            let config = pierre_mcp_server::config::environment::OAuthProviderConfig {
-                client_id: Some(client_id),
-                client_secret: Some(client_secret),
-                redirect_uri,
-                scopes: vec!["read".to_string(), "activity:read_all".to_string()],
-                enabled: true,
-            };
-
-            let strava_provider =
-                pierre_mcp_server::oauth::providers::StravaOAuthProvider::from_config(&config)
-                    .map_err(|e| {
-                        tracing::error!("Failed to create Strava provider: {}", e);
-                        warp::reject::custom(OAuthCallbackError::ServerError(format!(
-                            "Provider error: {}",
-                            e
-                        )))
-                    })?;
-            oauth_manager.register_provider(Box::new(strava_provider));
-        }
-        "fitbit" => {
-            // For single-tenant mode, read from environment variables
-            let client_id = std::env::var("FITBIT_CLIENT_ID").map_err(|_| {
-                warp::reject::custom(OAuthCallbackError::ServerError(
-                    "FITBIT_CLIENT_ID not set".to_string(),
-                ))
-            })?;
-            let client_secret = std::env::var("FITBIT_CLIENT_SECRET").map_err(|_| {
-                warp::reject::custom(OAuthCallbackError::ServerError(
-                    "FITBIT_CLIENT_SECRET not set".to_string(),
-                ))
-            })?;
-            let redirect_uri = std::env::var("FITBIT_REDIRECT_URI").ok();
-
-            let config = pierre_mcp_server::config::environment::OAuthProviderConfig {
-                client_id: Some(client_id),
-                client_secret: Some(client_secret),
-                redirect_uri,
-                scopes: vec![
-                    "activity".to_string(),
-                    "heartrate".to_string(),
-                    "location".to_string(),
-                    "nutrition".to_string(),
-                    "profile".to_string(),
-                    "settings".to_string(),
-                    "sleep".to_string(),
-                    "social".to_string(),
-                    "weight".to_string(),
-                ],
-                enabled: true,
-            };
-
-            let fitbit_provider =
-                pierre_mcp_server::oauth::providers::FitbitOAuthProvider::from_config(&config)
-                    .map_err(|e| {
-                        tracing::error!("Failed to create Fitbit provider: {}", e);
-                        warp::reject::custom(OAuthCallbackError::ServerError(format!(
-                            "Provider error: {}",
-                            e
-                        )))
-                    })?;
-            oauth_manager.register_provider(Box::new(fitbit_provider));
-        }
-        _ => {
-            return Err(warp::reject::custom(
-                OAuthCallbackError::UnsupportedProvider(provider.to_string()),
-            ));
-        }
-    }
+    let descriptor = pierre_mcp_server::providers::registry()
+        .get(provider)
+        .ok_or_else(|| {
+            warp::reject::custom(OAuthCallbackError::UnsupportedProvider(
+                provider.to_string(),
+            ))
+        })?;
+
+    // For single-tenant mode, read the provider settings from environment variables
+    let oauth_config = OAuthConfig::from_env().map_err(|e| {
+        warp::reject::custom(OAuthCallbackError::ServerError(format!(
+            "Configuration error: {}",
+            e
+        )))
+    })?;
+    let oauth_provider = descriptor.oauth_provider(&oauth_config).map_err(|e| {
+        tracing::error!(
+            "Failed to create {} provider: {}",
+            descriptor.display_name,
+            e
+        );
+        warp::reject::custom(OAuthCallbackError::ServerError(format!(
+            "Provider error: {}",
+            e
+        )))
+    })?;
+    oauth_manager.register_provider(oauth_provider);
 
     // Handle the OAuth callback
     match oauth_manager.handle_callback(code, state, provider).await {
@@ -0,0 +1,114 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strava Webhook Event Replayer
+//!
+//! Replays recorded Strava webhook events against a locally running server so
+//! the push-sync pipeline can be exercised without a public callback URL.
+//!
+//! Usage:
+//! ```bash
+//! # Verify the subscription handshake, then replay events from a file
+//! cargo run --bin replay-strava-webhook -- --events events.json --verify-token my_token
+//! ```
+//!
+//! The events file holds either a JSON array of events or one event per line.
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use pierre_mcp_server::webhook_routes::StravaWebhookEvent;
+
+#[derive(Parser)]
+#[command(
+    name = "replay-strava-webhook",
+    about = "Replay recorded Strava webhook events against a local server"
+)]
+struct ReplayArgs {
+    /// File containing recorded events (JSON array or JSON lines)
+    #[arg(long)]
+    events: String,
+
+    /// Webhook endpoint to post events to
+    #[arg(long, default_value_t = format!(
+        "http://localhost:{}/webhooks/strava",
+        pierre_mcp_server::constants::ports::DEFAULT_HTTP_PORT
+    ))]
+    url: String,
+
+    /// Run the subscription verification handshake first with this token
+    #[arg(long)]
+    verify_token: Option<String>,
+
+    /// Rewrite every event to this subscription id before sending it
+    #[arg(long)]
+    subscription_id: Option<i64>,
+
+    /// Delay between events in milliseconds
+    #[arg(long, default_value = "0")]
+    delay_ms: u64,
+}
+
+fn load_events(path: &str) -> Result<Vec<StravaWebhookEvent>> {
+    let content = std::fs::read_to_string(path)?;
+    if content.trim_start().starts_with('[') {
+        return Ok(serde_json::from_str(&content)?);
+    }
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = ReplayArgs::parse();
+    let client = reqwest::Client::new();
+
+    if let Some(verify_token) = &args.verify_token {
+        let response = client
+            .get(&args.url)
+            .query(&[
+                ("hub.mode", "subscribe"),
+                ("hub.challenge", "replay_challenge"),
+                ("hub.verify_token", verify_token.as_str()),
+            ])
+            .send()
+            .await?;
+        let body: serde_json::Value = response.json().await?;
+        if body["hub.challenge"] != "replay_challenge" {
+            return Err(anyhow!("Subscription verification failed: {}", body));
+        }
+        println!("✅ Subscription verification succeeded");
+    }
+
+    let mut events = load_events(&args.events)?;
+    if let Some(subscription_id) = args.subscription_id {
+        for event in &mut events {
+            event.subscription_id = subscription_id;
+        }
+    }
+    println!("Replaying {} events to {}", events.len(), args.url);
+
+    for event in &events {
+        let response = client.post(&args.url).json(event).send().await?;
+        println!(
+            "{} {} {} (owner {}) -> {}",
+            event.object_type,
+            event.aspect_type,
+            event.object_id,
+            event.owner_id,
+            response.status()
+        );
+
+        if args.delay_ms > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(args.delay_ms)).await;
+        }
+    }
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 // Test real weather API with historical data
 use chrono::Utc;
 use pierre_mcp_server::config::fitness_config::WeatherApiConfig;
-use pierre_mcp_server::intelligence::weather::WeatherService;
+use pierre_mcp_server::intelligence::weather::{estimate_weather, WeatherService};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -124,8 +124,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("   • OpenWeatherMap service might be down");
             }
 
-            println!("\n🎭 Falling back to mock weather:");
-            let mock_weather = weather_service.generate_mock_weather();
+            println!("\n🎭 Falling back to a seasonal estimate:");
+            let mock_weather = estimate_weather(latitude, longitude, historical_date);
             println!(
                 "   🌡️  Temperature: {:.1}°C",
                 mock_weather.temperature_celsius
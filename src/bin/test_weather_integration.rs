@@ -1,7 +1,7 @@
 // Test weather integration with real activity data
 use chrono::Utc;
 use pierre_mcp_server::config::fitness_config::WeatherApiConfig;
-use pierre_mcp_server::intelligence::weather::WeatherService;
+use pierre_mcp_server::intelligence::weather::{estimate_weather, WeatherService};
 use pierre_mcp_server::models::{Activity, SportType};
 
 #[tokio::main]
@@ -29,6 +29,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         region: None,
         country: None,
         trail_name: None,
+        gear_id: None,
+        summary_polyline: None,
         provider: "test".to_string(),
     };
 
@@ -104,7 +106,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Test mock weather generation
     println!("\n🎭 Mock Weather Generation:");
-    let mock_weather = weather_service.generate_mock_weather();
+    let mock_weather = estimate_weather(
+        activity.start_latitude.unwrap(),
+        activity.start_longitude.unwrap(),
+        activity.start_date,
+    );
     println!(
         "   Mock Temperature: {:.1}°C",
         mock_weather.temperature_celsius
@@ -199,6 +199,8 @@ pub struct ServerConfig {
     pub oauth: OAuthConfig,
     /// Security settings
     pub security: SecurityConfig,
+    /// A2A protocol settings
+    pub a2a: A2AConfig,
     /// External service configuration
     pub external_services: ExternalServicesConfig,
     /// Application behavior settings
@@ -247,6 +249,32 @@ pub struct OAuthConfig {
     pub fitbit: OAuthProviderConfig,
 }
 
+impl OAuthConfig {
+    /// Load the OAuth provider settings from environment variables
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            strava: OAuthProviderConfig {
+                client_id: env_config::strava_client_id(),
+                client_secret: env_config::strava_client_secret(),
+                redirect_uri: Some(env_config::strava_redirect_uri()),
+                scopes: parse_scopes(oauth::STRAVA_DEFAULT_SCOPES),
+                enabled: env_var_or("STRAVA_ENABLED", "true")?
+                    .parse()
+                    .context("Invalid STRAVA_ENABLED value")?,
+            },
+            fitbit: OAuthProviderConfig {
+                client_id: env::var("FITBIT_CLIENT_ID").ok(),
+                client_secret: env::var("FITBIT_CLIENT_SECRET").ok(),
+                redirect_uri: env::var("FITBIT_REDIRECT_URI").ok(),
+                scopes: parse_scopes(oauth::FITBIT_DEFAULT_SCOPES),
+                enabled: env_var_or("FITBIT_ENABLED", "true")?
+                    .parse()
+                    .context("Invalid FITBIT_ENABLED value")?,
+            },
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OAuthProviderConfig {
     /// OAuth client ID
@@ -299,6 +327,14 @@ pub struct TlsConfig {
     pub key_path: Option<PathBuf>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct A2AConfig {
+    /// Public base URL advertised in the agent card (defaults to localhost)
+    pub public_url: Option<String>,
+    /// Ed25519 PKCS#8 key used to sign the agent card (unsigned when unset)
+    pub agent_card_signing_key_path: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalServicesConfig {
     /// Weather service configuration
@@ -309,6 +345,26 @@ pub struct ExternalServicesConfig {
     pub fitbit_api: FitbitApiConfig,
 }
 
+impl ExternalServicesConfig {
+    /// Load the external service settings from environment variables
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            weather: WeatherServiceConfig {
+                api_key: env::var("OPENWEATHER_API_KEY").ok(),
+                base_url: env_var_or(
+                    "OPENWEATHER_BASE_URL",
+                    "https://api.openweathermap.org/data/2.5",
+                )?,
+                enabled: env_var_or("WEATHER_SERVICE_ENABLED", "true")?
+                    .parse()
+                    .context("Invalid WEATHER_SERVICE_ENABLED value")?,
+            },
+            strava_api: StravaApiConfig::from_env(),
+            fitbit_api: FitbitApiConfig::from_env(),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherServiceConfig {
     /// OpenWeather API key
@@ -329,6 +385,17 @@ pub struct StravaApiConfig {
     pub token_url: String,
 }
 
+impl StravaApiConfig {
+    /// Strava endpoints from environment variables, defaulting to strava.com
+    pub fn from_env() -> Self {
+        Self {
+            base_url: env_config::strava_api_base(),
+            auth_url: env_config::strava_auth_url(),
+            token_url: env_config::strava_token_url(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FitbitApiConfig {
     /// Fitbit API base URL
@@ -339,6 +406,17 @@ pub struct FitbitApiConfig {
     pub token_url: String,
 }
 
+impl FitbitApiConfig {
+    /// Fitbit endpoints from environment variables, defaulting to fitbit.com
+    pub fn from_env() -> Self {
+        Self {
+            base_url: env_config::fitbit_api_base(),
+            auth_url: env_config::fitbit_auth_url(),
+            token_url: env_config::fitbit_token_url(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppBehaviorConfig {
     /// Maximum activities to fetch in one request
@@ -414,26 +492,7 @@ impl ServerConfig {
                     .context("Invalid ENABLE_REFRESH_TOKENS value")?,
             },
 
-            oauth: OAuthConfig {
-                strava: OAuthProviderConfig {
-                    client_id: env_config::strava_client_id(),
-                    client_secret: env_config::strava_client_secret(),
-                    redirect_uri: Some(env_config::strava_redirect_uri()),
-                    scopes: parse_scopes(oauth::STRAVA_DEFAULT_SCOPES),
-                    enabled: env_var_or("STRAVA_ENABLED", "true")?
-                        .parse()
-                        .context("Invalid STRAVA_ENABLED value")?,
-                },
-                fitbit: OAuthProviderConfig {
-                    client_id: env::var("FITBIT_CLIENT_ID").ok(),
-                    client_secret: env::var("FITBIT_CLIENT_SECRET").ok(),
-                    redirect_uri: env::var("FITBIT_REDIRECT_URI").ok(),
-                    scopes: parse_scopes(oauth::FITBIT_DEFAULT_SCOPES),
-                    enabled: env_var_or("FITBIT_ENABLED", "true")?
-                        .parse()
-                        .context("Invalid FITBIT_ENABLED value")?,
-                },
-            },
+            oauth: OAuthConfig::from_env()?,
 
             security: SecurityConfig {
                 cors_origins: parse_origins(&env_var_or("CORS_ORIGINS", "*")?),
@@ -469,41 +528,15 @@ impl ServerConfig {
                 },
             },
 
-            external_services: ExternalServicesConfig {
-                weather: WeatherServiceConfig {
-                    api_key: env::var("OPENWEATHER_API_KEY").ok(),
-                    base_url: env_var_or(
-                        "OPENWEATHER_BASE_URL",
-                        "https://api.openweathermap.org/data/2.5",
-                    )?,
-                    enabled: env_var_or("WEATHER_SERVICE_ENABLED", "true")?
-                        .parse()
-                        .context("Invalid WEATHER_SERVICE_ENABLED value")?,
-                },
-                strava_api: StravaApiConfig {
-                    base_url: env_var_or("STRAVA_API_BASE", "https://www.strava.com/api/v3")?,
-                    auth_url: env_var_or(
-                        "STRAVA_AUTH_URL",
-                        "https://www.strava.com/oauth/authorize",
-                    )?,
-                    token_url: env_var_or(
-                        "STRAVA_TOKEN_URL",
-                        "https://www.strava.com/oauth/token",
-                    )?,
-                },
-                fitbit_api: FitbitApiConfig {
-                    base_url: env_var_or("FITBIT_API_BASE", "https://api.fitbit.com")?,
-                    auth_url: env_var_or(
-                        "FITBIT_AUTH_URL",
-                        "https://www.fitbit.com/oauth2/authorize",
-                    )?,
-                    token_url: env_var_or(
-                        "FITBIT_TOKEN_URL",
-                        "https://api.fitbit.com/oauth2/token",
-                    )?,
-                },
+            a2a: A2AConfig {
+                public_url: env::var("A2A_PUBLIC_URL").ok(),
+                agent_card_signing_key_path: env::var("A2A_AGENT_CARD_SIGNING_KEY_PATH")
+                    .ok()
+                    .map(PathBuf::from),
             },
 
+            external_services: ExternalServicesConfig::from_env()?,
+
             app_behavior: AppBehaviorConfig {
                 max_activities_fetch: env_var_or("MAX_ACTIVITIES_FETCH", "100")?
                     .parse()
@@ -622,16 +655,6 @@ impl ServerConfig {
         self.external_services.weather.api_key.as_deref()
     }
 
-    /// Get Strava API configuration
-    pub fn strava_api_config(&self) -> &StravaApiConfig {
-        &self.external_services.strava_api
-    }
-
-    /// Get Fitbit API configuration
-    pub fn fitbit_api_config(&self) -> &FitbitApiConfig {
-        &self.external_services.fitbit_api
-    }
-
     /// Check if CI mode is enabled
     pub fn is_ci_mode(&self) -> bool {
         self.app_behavior.ci_mode
@@ -830,6 +853,10 @@ mod tests {
                     environment: Environment::Development,
                 },
             },
+            a2a: A2AConfig {
+                public_url: None,
+                agent_card_signing_key_path: None,
+            },
             external_services: ExternalServicesConfig {
                 weather: WeatherServiceConfig {
                     api_key: None,
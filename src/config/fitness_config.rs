@@ -18,6 +18,8 @@ pub struct FitnessConfig {
     pub sport_types: HashMap<String, String>,
     pub intelligence: IntelligenceConfig,
     pub weather_api: Option<WeatherApiConfig>,
+    #[serde(default)]
+    pub activity_merge: ActivityMergeConfig,
 }
 
 /// Intelligence analysis configuration
@@ -73,6 +75,41 @@ pub struct WeatherApiConfig {
     pub request_timeout_seconds: u64,
     pub fallback_to_mock: bool,
     pub rate_limit_requests_per_minute: u64,
+    /// Observations file for the `fixture` provider
+    #[serde(default)]
+    pub fixture_path: Option<String>,
+}
+
+/// How activities recorded by several providers are matched and merged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivityMergeConfig {
+    /// Share of the shorter activity's duration the two must overlap (0-1)
+    pub min_time_overlap: f64,
+    /// Start times this close (seconds) match even without enough overlap
+    pub start_tolerance_seconds: i64,
+    /// Largest relative distance difference when both records have one (0-1)
+    pub max_distance_difference: f64,
+    /// Which provider's value wins for each group of fields
+    pub field_precedence: FieldPrecedence,
+}
+
+/// Provider names in order of preference for each group of activity fields
+///
+/// Providers not listed rank after the listed ones, in `default` order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldPrecedence {
+    /// ID, name, sport type, start time, duration and gear
+    pub default: Vec<String>,
+    /// Average and maximum heart rate
+    pub heart_rate: Vec<String>,
+    /// Start coordinates and location names
+    pub gps: Vec<String>,
+    /// Distance, elevation and speeds
+    pub distance: Vec<String>,
+    /// Calories burned
+    pub calories: Vec<String>,
 }
 
 impl FitnessConfig {
@@ -117,6 +154,70 @@ impl FitnessConfig {
     }
 }
 
+impl IntelligenceConfig {
+    /// Check the thresholds are in range and in ascending order
+    pub fn validate(&self) -> Result<()> {
+        let effort = &self.effort_thresholds;
+        ensure_ascending(
+            "effort_thresholds",
+            &[
+                ("light_max", effort.light_max),
+                ("moderate_max", effort.moderate_max),
+                ("hard_max", effort.hard_max),
+            ],
+            10.0,
+        )?;
+
+        let zones = &self.zone_thresholds;
+        ensure_ascending(
+            "zone_thresholds",
+            &[
+                ("recovery_max", zones.recovery_max),
+                ("endurance_max", zones.endurance_max),
+                ("tempo_max", zones.tempo_max),
+                ("threshold_max", zones.threshold_max),
+            ],
+            100.0,
+        )?;
+
+        anyhow::ensure!(
+            self.weather_mapping.wind_threshold >= 0.0,
+            "weather_mapping.wind_threshold must not be negative"
+        );
+        anyhow::ensure!(
+            (0.0..=100.0).contains(&self.personal_records.pace_improvement_threshold),
+            "personal_records.pace_improvement_threshold must be between 0 and 100"
+        );
+        Ok(())
+    }
+}
+
+/// Check named values are positive, at most `max` and strictly increasing
+fn ensure_ascending(section: &str, values: &[(&str, f32)], max: f32) -> Result<()> {
+    let mut previous: Option<(&str, f32)> = None;
+    for &(name, value) in values {
+        anyhow::ensure!(
+            value > 0.0 && value <= max,
+            "{}.{} must be above 0 and at most {}",
+            section,
+            name,
+            max
+        );
+        if let Some((previous_name, previous_value)) = previous {
+            anyhow::ensure!(
+                value > previous_value,
+                "{}.{} must be greater than {}.{}",
+                section,
+                name,
+                section,
+                previous_name
+            );
+        }
+        previous = Some((name, value));
+    }
+    Ok(())
+}
+
 impl Default for FitnessConfig {
     fn default() -> Self {
         let mut sport_types = HashMap::new();
@@ -187,6 +288,7 @@ impl Default for FitnessConfig {
             sport_types,
             intelligence: IntelligenceConfig::default(),
             weather_api: Some(WeatherApiConfig::default()),
+            activity_merge: ActivityMergeConfig::default(),
         }
     }
 }
@@ -251,6 +353,30 @@ impl Default for PersonalRecordConfig {
     }
 }
 
+impl Default for ActivityMergeConfig {
+    fn default() -> Self {
+        Self {
+            min_time_overlap: 0.5,
+            start_tolerance_seconds: 300,
+            max_distance_difference: 0.1,
+            field_precedence: FieldPrecedence::default(),
+        }
+    }
+}
+
+impl Default for FieldPrecedence {
+    fn default() -> Self {
+        let order = |names: &[&str]| names.iter().map(ToString::to_string).collect();
+        Self {
+            default: order(&["strava", "fitbit", "manual"]),
+            heart_rate: order(&["fitbit", "strava", "manual"]),
+            gps: order(&["strava", "fitbit", "manual"]),
+            distance: order(&["strava", "fitbit", "manual"]),
+            calories: order(&["fitbit", "strava", "manual"]),
+        }
+    }
+}
+
 impl Default for WeatherApiConfig {
     fn default() -> Self {
         Self {
@@ -263,6 +389,7 @@ impl Default for WeatherApiConfig {
                 .map(|v| v.to_lowercase() == "true")
                 .unwrap_or(false),
             rate_limit_requests_per_minute: 60,
+            fixture_path: std::env::var("WEATHER_FIXTURE_PATH").ok(),
         }
     }
 }
@@ -369,6 +496,30 @@ time_pr_types = ["fastest_test"]
             3.0
         );
 
+        // Omitted sections keep their defaults
+        assert_eq!(
+            config.activity_merge.field_precedence.heart_rate[0],
+            "fitbit"
+        );
+
         Ok(())
     }
+
+    #[test]
+    fn test_intelligence_config_validation() {
+        let mut config = IntelligenceConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.zone_thresholds.tempo_max = 65.0;
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("tempo_max must be greater than zone_thresholds.endurance_max"));
+
+        config.zone_thresholds = ZoneThresholds::default();
+        config.zone_thresholds.threshold_max = 105.0;
+        assert!(config.validate().is_err());
+
+        config.zone_thresholds = ZoneThresholds::default();
+        config.effort_thresholds.light_max = 0.0;
+        assert!(config.validate().is_err());
+    }
 }
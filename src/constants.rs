@@ -97,6 +97,16 @@ pub mod env_config {
         env::var("STRAVA_CLIENT_SECRET").ok()
     }
 
+    /// Get Fitbit client ID from environment
+    pub fn fitbit_client_id() -> Option<String> {
+        env::var("FITBIT_CLIENT_ID").ok()
+    }
+
+    /// Get Fitbit client secret from environment
+    pub fn fitbit_client_secret() -> Option<String> {
+        env::var("FITBIT_CLIENT_SECRET").ok()
+    }
+
     /// Get Strava redirect URI from environment or default
     pub fn strava_redirect_uri() -> String {
         env::var("STRAVA_REDIRECT_URI").unwrap_or_else(|_| {
@@ -134,6 +144,128 @@ pub mod env_config {
             .unwrap_or_else(|_| "https://www.strava.com/oauth/token".to_string())
     }
 
+    /// Get Strava deauthorization URL from environment or default
+    pub fn strava_deauthorize_url() -> String {
+        env::var("STRAVA_DEAUTHORIZE_URL")
+            .unwrap_or_else(|_| "https://www.strava.com/oauth/deauthorize".to_string())
+    }
+
+    /// Get Fitbit API base URL from environment or default
+    pub fn fitbit_api_base() -> String {
+        env::var("FITBIT_API_BASE").unwrap_or_else(|_| "https://api.fitbit.com".to_string())
+    }
+
+    /// Get Fitbit auth URL from environment or default
+    pub fn fitbit_auth_url() -> String {
+        env::var("FITBIT_AUTH_URL")
+            .unwrap_or_else(|_| "https://www.fitbit.com/oauth2/authorize".to_string())
+    }
+
+    /// Get Fitbit token URL from environment or default
+    pub fn fitbit_token_url() -> String {
+        env::var("FITBIT_TOKEN_URL")
+            .unwrap_or_else(|_| "https://api.fitbit.com/oauth2/token".to_string())
+    }
+
+    /// Get Fitbit token revocation URL from environment or default
+    pub fn fitbit_revoke_url() -> String {
+        env::var("FITBIT_REVOKE_URL")
+            .unwrap_or_else(|_| "https://api.fitbit.com/oauth2/revoke".to_string())
+    }
+
+    /// Get the token Strava must echo when verifying a webhook subscription
+    pub fn strava_webhook_verify_token() -> Option<String> {
+        env::var("STRAVA_WEBHOOK_VERIFY_TOKEN").ok()
+    }
+
+    /// Get the Strava push subscription id events must carry
+    pub fn strava_webhook_subscription_id() -> Option<i64> {
+        env::var("STRAVA_WEBHOOK_SUBSCRIPTION_ID")
+            .ok()
+            .and_then(|id| id.parse().ok())
+    }
+
+    /// Get how often the background token refresher runs, in seconds
+    pub fn token_refresh_interval_secs() -> u64 {
+        env::var("TOKEN_REFRESH_INTERVAL_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .unwrap_or(300)
+    }
+
+    /// Get how long before expiry the background refresher renews a token, in seconds
+    pub fn token_refresh_ahead_secs() -> i64 {
+        env::var("TOKEN_REFRESH_AHEAD_SECS")
+            .unwrap_or_else(|_| "1800".to_string())
+            .parse()
+            .unwrap_or(1800)
+    }
+
+    /// Get the comma-separated digest delivery sinks (`file`, `webhook`, `email`); none disables delivery
+    pub fn digest_sinks() -> String {
+        env::var("DIGEST_SINKS").unwrap_or_default()
+    }
+
+    /// Get the comma-separated digest periods to deliver
+    pub fn digest_periods() -> String {
+        env::var("DIGEST_PERIODS").unwrap_or_else(|_| "weekly,monthly,yearly".to_string())
+    }
+
+    /// Get the UTC hour after which a finished period's digest is sent
+    pub fn digest_send_hour() -> u32 {
+        env::var("DIGEST_SEND_HOUR")
+            .ok()
+            .and_then(|hour| hour.parse().ok())
+            .filter(|hour| *hour < 24)
+            .unwrap_or(6)
+    }
+
+    /// Get how often the digest scheduler looks for digests to send, in seconds
+    pub fn digest_check_interval_secs() -> u64 {
+        env::var("DIGEST_CHECK_INTERVAL_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .unwrap_or(3600)
+    }
+
+    /// Get the directory the `file` digest sink writes to
+    pub fn digest_outbox_dir() -> String {
+        env::var("DIGEST_OUTBOX_DIR").unwrap_or_else(|_| "./data/digests".to_string())
+    }
+
+    /// Get the URL the `webhook` digest sink posts to
+    pub fn digest_webhook_url() -> Option<String> {
+        env::var("DIGEST_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Get the HTTP email API the `email` digest sink posts to
+    pub fn digest_email_api_url() -> Option<String> {
+        env::var("DIGEST_EMAIL_API_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Get the bearer key for the digest email API
+    pub fn digest_email_api_key() -> Option<String> {
+        env::var("DIGEST_EMAIL_API_KEY")
+            .ok()
+            .filter(|key| !key.is_empty())
+    }
+
+    /// Get the sender address of digest emails
+    pub fn digest_email_from() -> String {
+        env::var("DIGEST_EMAIL_FROM").unwrap_or_else(|_| "digests@pierre.local".to_string())
+    }
+
+    /// Get whether outbound A2A calls may reach private networks and plain `http` agents
+    pub fn remote_agents_allow_private() -> bool {
+        env::var("A2A_REMOTE_AGENTS_ALLOW_PRIVATE")
+            .map(|value| matches!(value.as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false)
+    }
+
     /// Get max activities fetch limit from environment or default
     pub fn max_activities_fetch() -> usize {
         env::var("MAX_ACTIVITIES_FETCH")
@@ -155,6 +287,24 @@ pub mod env_config {
         env::var("OPENWEATHER_API_BASE_URL")
             .unwrap_or_else(|_| "https://api.openweathermap.org".to_string())
     }
+
+    /// Get Open-Meteo historical archive base URL from environment or default
+    pub fn open_meteo_archive_base() -> String {
+        env::var("OPEN_METEO_ARCHIVE_BASE_URL")
+            .unwrap_or_else(|_| "https://archive-api.open-meteo.com".to_string())
+    }
+
+    /// Get Open-Meteo forecast base URL from environment or default
+    pub fn open_meteo_forecast_base() -> String {
+        env::var("OPEN_METEO_FORECAST_BASE_URL")
+            .unwrap_or_else(|_| "https://api.open-meteo.com".to_string())
+    }
+
+    /// Get Nominatim reverse geocoding base URL from environment or default
+    pub fn nominatim_api_base() -> String {
+        env::var("NOMINATIM_API_BASE_URL")
+            .unwrap_or_else(|_| "https://nominatim.openstreetmap.org".to_string())
+    }
 }
 
 /// JSON-RPC and MCP error codes
@@ -176,6 +326,12 @@ pub mod errors {
     pub const ERROR_TOKEN_INVALID: i32 = -32002;
     pub const ERROR_TOKEN_MALFORMED: i32 = -32003;
 
+    /// Provider API quota exhausted; `data.retry_after_secs` says when to retry
+    pub const ERROR_PROVIDER_RATE_LIMITED: i32 = -32004;
+
+    /// Provider rejected the stored grant; the user must reconnect `data.provider`
+    pub const ERROR_PROVIDER_REAUTH_REQUIRED: i32 = -32005;
+
     /// Common error messages
     pub const MSG_METHOD_NOT_FOUND: &str = "Method not found";
     pub const MSG_INVALID_PARAMS: &str = "Invalid parameters";
@@ -283,6 +439,9 @@ pub mod timeouts {
 
     /// Trial period
     pub const DEFAULT_TRIAL_DAYS: i64 = 14;
+
+    /// Outbound A2A calls to remote agents
+    pub const REMOTE_AGENT_TIMEOUT_SECS: u64 = 30;
 }
 
 /// Cryptographic and security constants
@@ -313,7 +472,7 @@ pub mod oauth {
     pub const STRAVA_DEFAULT_SCOPES: &str = "read,activity:read_all";
 
     /// Default OAuth scopes for Fitbit  
-    pub const FITBIT_DEFAULT_SCOPES: &str = "activity,profile";
+    pub const FITBIT_DEFAULT_SCOPES: &str = "activity,heartrate,oxygen_saturation,profile,sleep";
 }
 
 /// User and application defaults
@@ -415,6 +574,50 @@ pub mod tools {
     pub const CALCULATE_FITNESS_SCORE: &str = "calculate_fitness_score";
     pub const PREDICT_PERFORMANCE: &str = "predict_performance";
     pub const ANALYZE_TRAINING_LOAD: &str = "analyze_training_load";
+
+    /// Wellness data
+    pub const GET_SLEEP: &str = "get_sleep";
+    pub const GET_DAILY_WELLNESS: &str = "get_daily_wellness";
+    pub const GET_RESTING_HR_TREND: &str = "get_resting_hr_trend";
+    pub const LOG_WELLNESS: &str = "log_wellness";
+    pub const GET_READINESS: &str = "get_readiness";
+    pub const LOG_ACTIVITY: &str = "log_activity";
+    pub const UPDATE_ACTIVITY: &str = "update_activity";
+    pub const DELETE_ACTIVITY: &str = "delete_activity";
+
+    /// Gear tracking
+    pub const GET_GEAR: &str = "get_gear";
+    pub const GEAR_USAGE_REPORT: &str = "gear_usage_report";
+    pub const ADD_GEAR: &str = "add_gear";
+    pub const UPDATE_GEAR: &str = "update_gear";
+
+    /// Athlete thresholds
+    pub const GET_ATHLETE_THRESHOLDS: &str = "get_athlete_thresholds";
+
+    /// Analysis settings
+    pub const UPDATE_ANALYSIS_SETTINGS: &str = "update_analysis_settings";
+
+    /// Repeated routes
+    pub const LIST_MY_ROUTES: &str = "list_my_routes";
+    pub const ROUTE_HISTORY: &str = "route_history";
+
+    /// Anomaly detection
+    pub const DETECT_ANOMALIES: &str = "detect_anomalies";
+
+    /// Activity queries
+    pub const QUERY_ACTIVITIES: &str = "query_activities";
+
+    /// Streaks and consistency
+    pub const GET_STREAKS: &str = "get_streaks";
+
+    /// Training digests
+    pub const GET_DIGEST: &str = "get_digest";
+
+    /// Remote agent delegation
+    pub const LIST_REMOTE_AGENTS: &str = "list_remote_agents";
+    pub const DELEGATE_TO_AGENT: &str = "delegate_to_agent";
+    pub const GET_DELEGATED_TASK: &str = "get_delegated_task";
+    pub const CANCEL_DELEGATED_TASK: &str = "cancel_delegated_task";
 }
 
 /// Common JSON field names
@@ -443,6 +646,19 @@ pub mod json_fields {
     pub const GOAL_ID: &str = "goal_id";
     pub const TIMEFRAME: &str = "timeframe";
     pub const METRIC: &str = "metric";
+    pub const AGENT_ID: &str = "agent_id";
+    pub const TASK_ID: &str = "task_id";
+    pub const DATE: &str = "date";
+    pub const DAYS: &str = "days";
+    pub const END_DATE: &str = "end_date";
+    pub const GEAR_ID: &str = "gear_id";
+    pub const INCLUDE_RETIRED: &str = "include_retired";
+    pub const REFRESH: &str = "refresh";
+    pub const INCLUDE_HISTORY: &str = "include_history";
+    pub const PATTERN_TYPE: &str = "pattern_type";
+    pub const SETTINGS: &str = "settings";
+    pub const RESET: &str = "reset";
+    pub const ROUTE_ID: &str = "route_id";
 }
 
 /// User-facing messages
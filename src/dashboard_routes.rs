@@ -433,7 +433,7 @@ impl DashboardRoutes {
         }
 
         // Sort by timestamp (newest first) and limit
-        recent_activity.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        recent_activity.sort_by_key(|b| std::cmp::Reverse(b.timestamp));
         recent_activity.truncate(limit as usize);
 
         Ok(recent_activity)
@@ -498,7 +498,7 @@ impl DashboardRoutes {
             .collect();
 
         // Sort by request count (descending) and take top 10
-        tool_usage.sort_by(|a, b| b.request_count.cmp(&a.request_count));
+        tool_usage.sort_by_key(|b| std::cmp::Reverse(b.request_count));
         tool_usage.truncate(10);
 
         Ok(tool_usage)
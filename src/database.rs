@@ -10,10 +10,13 @@
 //! It handles user storage, token encryption, and secure data access patterns.
 
 use crate::api_keys::{ApiKey, ApiKeyTier, ApiKeyUsage, ApiKeyUsageStats};
-use crate::models::{DecryptedToken, EncryptedToken, User, UserTier};
+use crate::models::{
+    Activity, DailyWellness, DecryptedToken, EncryptedToken, Gear, ProviderConnection,
+    StoredDigest, SyncedActivity, User, UserTier,
+};
 use crate::rate_limiting::JwtUsage;
 use anyhow::Result;
-use chrono::{DateTime, Datelike, Timelike, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use uuid::Uuid;
@@ -82,6 +85,115 @@ impl Database {
         Ok(db)
     }
 
+    /// Move tokens from the per-provider columns of older `users` tables
+    /// into `provider_connections`
+    ///
+    /// Tokens are copied as stored (same key and cipher) and the old columns
+    /// are cleared, so a connection removed later isn't restored on restart.
+    async fn migrate_legacy_provider_tokens(&self) -> Result<()> {
+        for provider in ["strava", "fitbit"] {
+            let has_columns: bool = sqlx::query_scalar(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('users') WHERE name = ?1",
+            )
+            .bind(format!("{}_access_token", provider))
+            .fetch_one(&self.pool)
+            .await?;
+            if !has_columns {
+                continue;
+            }
+
+            let migrated = sqlx::query(&format!(
+                r#"
+                INSERT OR IGNORE INTO provider_connections (user_id, provider, access_token,
+                    refresh_token, nonce, scope, expires_at, created_at, updated_at)
+                SELECT id, ?1, {p}_access_token, {p}_refresh_token, {p}_nonce, {p}_scope,
+                    {p}_expires_at, last_active, last_active
+                FROM users
+                WHERE {p}_access_token IS NOT NULL AND {p}_refresh_token IS NOT NULL
+                    AND {p}_nonce IS NOT NULL AND {p}_scope IS NOT NULL
+                    AND {p}_expires_at IS NOT NULL
+                "#,
+                p = provider
+            ))
+            .bind(provider)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+
+            sqlx::query(&format!(
+                "UPDATE users SET {p}_access_token = NULL, {p}_refresh_token = NULL,
+                    {p}_expires_at = NULL, {p}_scope = NULL, {p}_nonce = NULL
+                 WHERE {p}_access_token IS NOT NULL",
+                p = provider
+            ))
+            .execute(&self.pool)
+            .await?;
+
+            if migrated > 0 {
+                tracing::info!("Migrated {} legacy {} connections", migrated, provider);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Bring `a2a_clients` tables created before client lifecycle management
+    /// up to date
+    ///
+    /// Adds the status and quota columns, then records each client's current
+    /// API key as a credential. Secrets were never stored before, so the
+    /// backfilled row has an empty hash that no secret matches; the client
+    /// keeps authenticating with its API key until its credentials are rotated.
+    async fn migrate_a2a_clients(&self) -> Result<()> {
+        let columns = [
+            ("is_active", "BOOLEAN NOT NULL DEFAULT true"),
+            ("deactivated_at", "TIMESTAMP"),
+            (
+                "tier",
+                "TEXT NOT NULL DEFAULT 'trial' CHECK (tier IN ('trial', 'standard', 'professional', 'enterprise'))",
+            ),
+            ("monthly_limit", "INTEGER"),
+        ];
+        for (column, definition) in columns {
+            let has_column: bool = sqlx::query_scalar(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('a2a_clients') WHERE name = ?1",
+            )
+            .bind(column)
+            .fetch_one(&self.pool)
+            .await?;
+            if !has_column {
+                sqlx::query(&format!(
+                    "ALTER TABLE a2a_clients ADD COLUMN {} {}",
+                    column, definition
+                ))
+                .execute(&self.pool)
+                .await?;
+            }
+        }
+
+        let backfilled = sqlx::query(
+            r#"
+            INSERT INTO a2a_client_credentials (id, client_id, secret_hash, api_key_id, created_at)
+            SELECT lower(hex(randomblob(16))), id, '', api_key_id, created_at
+            FROM a2a_clients
+            WHERE id NOT IN (SELECT client_id FROM a2a_client_credentials)
+              AND api_key_id IN (SELECT id FROM api_keys)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        if backfilled > 0 {
+            tracing::info!(
+                "Recorded credentials for {} existing A2A clients",
+                backfilled
+            );
+        }
+
+        Ok(())
+    }
+
     /// Get a reference to the database pool for advanced operations
     pub fn pool(&self) -> &Pool<Sqlite> {
         &self.pool
@@ -98,16 +210,6 @@ impl Database {
                 display_name TEXT,
                 password_hash TEXT NOT NULL,
                 tier TEXT NOT NULL DEFAULT 'starter' CHECK (tier IN ('starter', 'professional', 'enterprise')),
-                strava_access_token TEXT,
-                strava_refresh_token TEXT,
-                strava_expires_at TEXT,
-                strava_scope TEXT,
-                strava_nonce TEXT,
-                fitbit_access_token TEXT,
-                fitbit_refresh_token TEXT,
-                fitbit_expires_at TEXT,
-                fitbit_scope TEXT,
-                fitbit_nonce TEXT,
                 created_at TEXT NOT NULL,
                 last_active TEXT NOT NULL,
                 is_active BOOLEAN NOT NULL DEFAULT 1
@@ -122,6 +224,150 @@ impl Database {
             .execute(&self.pool)
             .await?;
 
+        // Create provider_connections table holding encrypted OAuth tokens per provider
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS provider_connections (
+                user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                provider TEXT NOT NULL,
+                provider_user_id TEXT,
+                access_token TEXT NOT NULL,
+                refresh_token TEXT NOT NULL,
+                nonce TEXT NOT NULL,
+                scope TEXT NOT NULL,
+                expires_at TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                needs_reauth BOOLEAN NOT NULL DEFAULT 0,
+                reauth_reason TEXT,
+                PRIMARY KEY (user_id, provider)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.migrate_legacy_provider_tokens().await?;
+
+        // Create synced_activities table for activities pushed by provider webhooks
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS synced_activities (
+                user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                provider TEXT NOT NULL,
+                activity_id TEXT NOT NULL,
+                activity_data TEXT NOT NULL,
+                start_date TEXT NOT NULL,
+                synced_at TEXT NOT NULL,
+                deleted_at TEXT,
+                PRIMARY KEY (user_id, provider, activity_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_synced_activities_start ON synced_activities(user_id, provider, start_date)")
+            .execute(&self.pool)
+            .await?;
+
+        // Create manual_activities table for user-entered workouts
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS manual_activities (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                activity_data TEXT NOT NULL,
+                start_date TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_manual_activities_start ON manual_activities(user_id, start_date)")
+            .execute(&self.pool)
+            .await?;
+
+        // Create gear table for provider-synced and user-added shoes and bikes
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS gear (
+                user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                gear_id TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                name TEXT NOT NULL,
+                gear_data TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (user_id, gear_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create digests table for weekly, monthly and yearly training digests
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS digests (
+                user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                period TEXT NOT NULL CHECK (period IN ('weekly', 'monthly', 'yearly')),
+                period_start TEXT NOT NULL,
+                digest_data TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (user_id, period, period_start)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create analysis_settings table for tenant and user intelligence overrides
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS analysis_settings (
+                scope TEXT NOT NULL CHECK (scope IN ('tenant', 'user')),
+                scope_id TEXT NOT NULL,
+                settings TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (scope, scope_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create tenant_members table mapping users to their club or team
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS tenant_members (
+                user_id TEXT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+                tenant_id TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create daily_wellness table for provider sleep/heart/step summaries
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS daily_wellness (
+                user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                provider TEXT NOT NULL,
+                date TEXT NOT NULL,
+                wellness_data TEXT NOT NULL,
+                synced_at TEXT NOT NULL,
+                PRIMARY KEY (user_id, provider, date)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Create user_profiles table for fitness analytics
         sqlx::query(
             r#"
@@ -474,6 +720,12 @@ impl Database {
                 is_verified BOOLEAN NOT NULL DEFAULT false,
                 verification_token TEXT,
                 verified_at TIMESTAMP,
+                is_active BOOLEAN NOT NULL DEFAULT true,
+                deactivated_at TIMESTAMP,
+                
+                -- Quota
+                tier TEXT NOT NULL DEFAULT 'trial' CHECK (tier IN ('trial', 'standard', 'professional', 'enterprise')),
+                monthly_limit INTEGER, -- Overrides the tier limit when set
                 
                 -- Timestamps
                 created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
@@ -487,6 +739,36 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
+        // A2A Client credentials - one row per issued secret/API key pair, kept for rotation overlap
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS a2a_client_credentials (
+                id TEXT PRIMARY KEY,
+                client_id TEXT NOT NULL REFERENCES a2a_clients(id) ON DELETE CASCADE,
+                secret_hash TEXT NOT NULL,
+                api_key_id TEXT NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+                created_at TIMESTAMP NOT NULL,
+                expires_at TIMESTAMP -- NULL while the credential is current
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_a2a_client_credentials_client_id ON a2a_client_credentials(client_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_a2a_client_credentials_api_key_id ON a2a_client_credentials(api_key_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.migrate_a2a_clients().await?;
+
         // A2A Sessions table - track active agent sessions
         sqlx::query(
             r#"
@@ -637,6 +919,44 @@ impl Database {
             .execute(&self.pool)
             .await?;
 
+        // A2A Remote Agents table - outbound agents trusted by a tenant, or
+        // by a user outside any tenant
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS a2a_remote_agents (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT, -- Set for agents curated by tenant admins
+                user_id TEXT REFERENCES users(id) ON DELETE CASCADE, -- Set for personal agents
+                name TEXT NOT NULL,
+                description TEXT,
+                base_url TEXT NOT NULL,
+                endpoint_url TEXT NOT NULL,
+                capabilities TEXT NOT NULL DEFAULT '[]', -- JSON array from the agent card
+                auth_token TEXT, -- Encrypted bearer credential (JSON EncryptedToken)
+                is_active BOOLEAN NOT NULL DEFAULT true,
+                created_at TEXT NOT NULL,
+                last_verified_at TEXT NOT NULL,
+                CHECK ((tenant_id IS NULL) <> (user_id IS NULL)),
+                UNIQUE(tenant_id, base_url),
+                UNIQUE(user_id, base_url)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_a2a_remote_agents_tenant_id ON a2a_remote_agents(tenant_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_a2a_remote_agents_user_id ON a2a_remote_agents(user_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Create admin tokens tables
         sqlx::query(
             r#"
@@ -797,205 +1117,752 @@ impl Database {
         }
     }
 
-    /// Update user's Strava token
-    pub async fn update_strava_token(
+    /// Store or refresh a user's OAuth tokens for a provider
+    pub async fn upsert_provider_token(
         &self,
         user_id: Uuid,
-        access_token: &str,
-        refresh_token: &str,
-        expires_at: DateTime<Utc>,
-        scope: String,
+        provider: &str,
+        token: &DecryptedToken,
+        provider_user_id: Option<&str>,
     ) -> Result<()> {
         let encrypted_token = EncryptedToken::new(
-            access_token,
-            refresh_token,
-            expires_at,
-            scope,
+            &token.access_token,
+            &token.refresh_token,
+            token.expires_at,
+            token.scope.clone(),
             &self.encryption_key,
         )?;
+        let now = Utc::now().to_rfc3339();
 
         sqlx::query(
             r#"
-            UPDATE users 
-            SET strava_access_token = ?1, strava_refresh_token = ?2, strava_expires_at = ?3, 
-                strava_scope = ?4, strava_nonce = ?5, last_active = ?6
-            WHERE id = ?7
+            INSERT INTO provider_connections (user_id, provider, provider_user_id, access_token,
+                refresh_token, nonce, scope, expires_at, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)
+            ON CONFLICT(user_id, provider) DO UPDATE SET
+                provider_user_id = COALESCE(excluded.provider_user_id, provider_connections.provider_user_id),
+                access_token = excluded.access_token,
+                refresh_token = excluded.refresh_token,
+                nonce = excluded.nonce,
+                scope = excluded.scope,
+                expires_at = excluded.expires_at,
+                updated_at = excluded.updated_at,
+                needs_reauth = 0,
+                reauth_reason = NULL
             "#,
         )
+        .bind(user_id.to_string())
+        .bind(provider)
+        .bind(provider_user_id)
         .bind(&encrypted_token.access_token)
         .bind(&encrypted_token.refresh_token)
-        .bind(encrypted_token.expires_at.to_rfc3339())
-        .bind(&encrypted_token.scope)
         .bind(&encrypted_token.nonce)
-        .bind(Utc::now().to_rfc3339())
-        .bind(user_id.to_string())
+        .bind(&encrypted_token.scope)
+        .bind(encrypted_token.expires_at.to_rfc3339())
+        .bind(&now)
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        self.update_last_active(user_id).await
     }
 
-    /// Get decrypted Strava token for user
-    pub async fn get_strava_token(&self, user_id: Uuid) -> Result<Option<DecryptedToken>> {
+    /// Get a user's decrypted OAuth tokens for a provider
+    pub async fn get_provider_token(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+    ) -> Result<Option<DecryptedToken>> {
         let row = sqlx::query(
             r#"
-            SELECT strava_access_token, strava_refresh_token, strava_expires_at, 
-                   strava_scope, strava_nonce 
-            FROM users WHERE id = ?1
+            SELECT access_token, refresh_token, nonce, scope, expires_at
+            FROM provider_connections WHERE user_id = ?1 AND provider = ?2
             "#,
         )
         .bind(user_id.to_string())
+        .bind(provider)
         .fetch_optional(&self.pool)
         .await?;
 
         match row {
             Some(row) => {
-                let access_token: Option<String> = row.try_get("strava_access_token")?;
-                let refresh_token: Option<String> = row.try_get("strava_refresh_token")?;
-                let expires_at: Option<String> = row.try_get("strava_expires_at")?;
-                let scope: Option<String> = row.try_get("strava_scope")?;
-                let nonce: Option<String> = row.try_get("strava_nonce")?;
-
-                if let (Some(access), Some(refresh), Some(expires), Some(scope), Some(nonce)) =
-                    (access_token, refresh_token, expires_at, scope, nonce)
-                {
-                    let encrypted_token = EncryptedToken {
-                        access_token: access,
-                        refresh_token: refresh,
-                        expires_at: DateTime::parse_from_rfc3339(&expires)?.with_timezone(&Utc),
-                        scope,
-                        nonce,
-                    };
-
-                    let decrypted = encrypted_token.decrypt(&self.encryption_key)?;
-                    Ok(Some(decrypted))
-                } else {
-                    Ok(None)
-                }
+                let expires_at: String = row.try_get("expires_at")?;
+                let encrypted_token = EncryptedToken {
+                    access_token: row.try_get("access_token")?,
+                    refresh_token: row.try_get("refresh_token")?,
+                    expires_at: DateTime::parse_from_rfc3339(&expires_at)?.with_timezone(&Utc),
+                    scope: row.try_get("scope")?,
+                    nonce: row.try_get("nonce")?,
+                };
+
+                Ok(Some(encrypted_token.decrypt(&self.encryption_key)?))
             }
             None => Ok(None),
         }
     }
 
-    /// Update user's Fitbit token
-    pub async fn update_fitbit_token(
+    /// Get a user's connection metadata for a provider
+    pub async fn get_provider_connection(
         &self,
         user_id: Uuid,
-        access_token: &str,
-        refresh_token: &str,
-        expires_at: DateTime<Utc>,
-        scope: String,
-    ) -> Result<()> {
-        let encrypted_token = EncryptedToken::new(
-            access_token,
-            refresh_token,
-            expires_at,
-            scope,
-            &self.encryption_key,
-        )?;
-
-        sqlx::query(
+        provider: &str,
+    ) -> Result<Option<ProviderConnection>> {
+        let row = sqlx::query(
             r#"
-            UPDATE users 
-            SET fitbit_access_token = ?1, fitbit_refresh_token = ?2, fitbit_expires_at = ?3, 
-                fitbit_scope = ?4, fitbit_nonce = ?5, last_active = ?6
-            WHERE id = ?7
+            SELECT user_id, provider, provider_user_id, scope, expires_at, created_at, updated_at,
+                needs_reauth, reauth_reason
+            FROM provider_connections WHERE user_id = ?1 AND provider = ?2
             "#,
         )
-        .bind(&encrypted_token.access_token)
-        .bind(&encrypted_token.refresh_token)
-        .bind(encrypted_token.expires_at.to_rfc3339())
-        .bind(&encrypted_token.scope)
-        .bind(&encrypted_token.nonce)
-        .bind(Utc::now().to_rfc3339())
         .bind(user_id.to_string())
-        .execute(&self.pool)
+        .bind(provider)
+        .fetch_optional(&self.pool)
         .await?;
 
-        Ok(())
+        row.map(|row| Self::row_to_provider_connection(&row))
+            .transpose()
     }
 
-    /// Get decrypted Fitbit token for user
-    pub async fn get_fitbit_token(&self, user_id: Uuid) -> Result<Option<DecryptedToken>> {
-        let row = sqlx::query(
+    /// List all provider connections for a user
+    pub async fn list_provider_connections(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<ProviderConnection>> {
+        let rows = sqlx::query(
             r#"
-            SELECT fitbit_access_token, fitbit_refresh_token, fitbit_expires_at, 
-                   fitbit_scope, fitbit_nonce 
-            FROM users WHERE id = ?1
+            SELECT user_id, provider, provider_user_id, scope, expires_at, created_at, updated_at,
+                needs_reauth, reauth_reason
+            FROM provider_connections WHERE user_id = ?1
+            ORDER BY provider
             "#,
         )
         .bind(user_id.to_string())
-        .fetch_optional(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
-        match row {
-            Some(row) => {
-                let access_token: Option<String> = row.try_get("fitbit_access_token")?;
-                let refresh_token: Option<String> = row.try_get("fitbit_refresh_token")?;
-                let expires_at: Option<String> = row.try_get("fitbit_expires_at")?;
-                let scope: Option<String> = row.try_get("fitbit_scope")?;
-                let nonce: Option<String> = row.try_get("fitbit_nonce")?;
-
-                if let (Some(access), Some(refresh), Some(expires), Some(scope), Some(nonce)) =
-                    (access_token, refresh_token, expires_at, scope, nonce)
-                {
-                    let encrypted_token = EncryptedToken {
-                        access_token: access,
-                        refresh_token: refresh,
-                        expires_at: DateTime::parse_from_rfc3339(&expires)?.with_timezone(&Utc),
-                        scope,
-                        nonce,
-                    };
-
-                    let decrypted = encrypted_token.decrypt(&self.encryption_key)?;
-                    Ok(Some(decrypted))
-                } else {
-                    Ok(None)
-                }
-            }
-            None => Ok(None),
-        }
+        rows.iter().map(Self::row_to_provider_connection).collect()
     }
 
-    /// Clear Strava token for a user (delete from database)
-    pub async fn clear_strava_token(&self, user_id: Uuid) -> Result<()> {
-        sqlx::query("UPDATE users SET strava_access_token = NULL, strava_refresh_token = NULL, strava_expires_at = NULL, strava_scope = NULL, strava_nonce = NULL WHERE id = ?1")
-            .bind(user_id.to_string())
-            .execute(&self.pool)
-            .await?;
+    /// Remove a user's connection to a provider
+    pub async fn delete_provider_connection(&self, user_id: Uuid, provider: &str) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM provider_connections WHERE user_id = ?1 AND provider = ?2")
+                .bind(user_id.to_string())
+                .bind(provider)
+                .execute(&self.pool)
+                .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
-    /// Clear Fitbit token for a user (delete from database)  
-    pub async fn clear_fitbit_token(&self, user_id: Uuid) -> Result<()> {
-        sqlx::query("UPDATE users SET fitbit_access_token = NULL, fitbit_refresh_token = NULL, fitbit_expires_at = NULL, fitbit_scope = NULL, fitbit_nonce = NULL WHERE id = ?1")
-            .bind(user_id.to_string())
-            .execute(&self.pool)
-            .await?;
+    /// Find the connection belonging to a provider-side user id
+    pub async fn get_provider_connection_by_provider_user_id(
+        &self,
+        provider: &str,
+        provider_user_id: &str,
+    ) -> Result<Option<ProviderConnection>> {
+        let row = sqlx::query(
+            r#"
+            SELECT user_id, provider, provider_user_id, scope, expires_at, created_at, updated_at,
+                needs_reauth, reauth_reason
+            FROM provider_connections WHERE provider = ?1 AND provider_user_id = ?2
+            "#,
+        )
+        .bind(provider)
+        .bind(provider_user_id)
+        .fetch_optional(&self.pool)
+        .await?;
 
-        Ok(())
+        row.map(|row| Self::row_to_provider_connection(&row))
+            .transpose()
     }
 
-    /// Update user's last active timestamp
-    pub async fn update_last_active(&self, user_id: Uuid) -> Result<()> {
-        sqlx::query("UPDATE users SET last_active = ?1 WHERE id = ?2")
-            .bind(Utc::now().to_rfc3339())
-            .bind(user_id.to_string())
-            .execute(&self.pool)
-            .await?;
+    /// Flag a connection whose refresh token the provider rejected
+    pub async fn mark_provider_connection_needs_reauth(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        reason: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE provider_connections SET needs_reauth = 1, reauth_reason = ?3, updated_at = ?4
+            WHERE user_id = ?1 AND provider = ?2
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(provider)
+        .bind(reason)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
-    /// Get total number of users (for health checks)
-    pub async fn get_user_count(&self) -> Result<i64> {
-        let row = sqlx::query("SELECT COUNT(*) as count FROM users")
-            .fetch_one(&self.pool)
-            .await?;
-
-        let count: i64 = row.try_get("count")?;
-        Ok(count)
+    /// List usable connections whose access token expires before a cutoff
+    pub async fn list_provider_connections_expiring_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<ProviderConnection>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, provider, provider_user_id, scope, expires_at, created_at, updated_at,
+                needs_reauth, reauth_reason
+            FROM provider_connections WHERE needs_reauth = 0 AND expires_at < ?1
+            ORDER BY expires_at
+            "#,
+        )
+        .bind(cutoff.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_provider_connection).collect()
+    }
+
+    /// Convert database row to ProviderConnection
+    fn row_to_provider_connection(row: &sqlx::sqlite::SqliteRow) -> Result<ProviderConnection> {
+        let user_id: String = row.try_get("user_id")?;
+        let expires_at: String = row.try_get("expires_at")?;
+        let created_at: String = row.try_get("created_at")?;
+        let updated_at: String = row.try_get("updated_at")?;
+
+        Ok(ProviderConnection {
+            user_id: Uuid::parse_str(&user_id)?,
+            provider: row.try_get("provider")?,
+            provider_user_id: row.try_get("provider_user_id")?,
+            scope: row.try_get("scope")?,
+            expires_at: DateTime::parse_from_rfc3339(&expires_at)?.with_timezone(&Utc),
+            connected_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+            needs_reauth: row.try_get("needs_reauth")?,
+            reauth_reason: row.try_get("reauth_reason")?,
+        })
+    }
+
+    /// Store or refresh a synced activity, clearing any tombstone
+    pub async fn upsert_synced_activity(&self, user_id: Uuid, activity: &Activity) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO synced_activities (user_id, provider, activity_id, activity_data, start_date, synced_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(user_id, provider, activity_id) DO UPDATE SET
+                activity_data = excluded.activity_data,
+                start_date = excluded.start_date,
+                synced_at = excluded.synced_at,
+                deleted_at = NULL
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(&activity.provider)
+        .bind(&activity.id)
+        .bind(serde_json::to_string(activity)?)
+        .bind(activity.start_date.to_rfc3339())
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get a synced activity, including tombstoned ones
+    pub async fn get_synced_activity(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        activity_id: &str,
+    ) -> Result<Option<SyncedActivity>> {
+        let row = sqlx::query(
+            r#"
+            SELECT activity_data, synced_at, deleted_at FROM synced_activities
+            WHERE user_id = ?1 AND provider = ?2 AND activity_id = ?3
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(provider)
+        .bind(activity_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let activity_data: String = row.try_get("activity_data")?;
+                let synced_at: String = row.try_get("synced_at")?;
+                let deleted_at: Option<String> = row.try_get("deleted_at")?;
+
+                Ok(Some(SyncedActivity {
+                    user_id,
+                    activity: serde_json::from_str(&activity_data)?,
+                    synced_at: DateTime::parse_from_rfc3339(&synced_at)?.with_timezone(&Utc),
+                    deleted_at: deleted_at
+                        .map(|d| DateTime::parse_from_rfc3339(&d).map(|d| d.with_timezone(&Utc)))
+                        .transpose()?,
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// List a user's live synced activities, newest first
+    pub async fn list_synced_activities(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Activity>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT activity_data FROM synced_activities
+            WHERE user_id = ?1 AND provider = ?2 AND deleted_at IS NULL
+            ORDER BY start_date DESC
+            LIMIT ?3
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(provider)
+        .bind(limit.map_or(-1, i64::from))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let activity_data: String = row.try_get("activity_data")?;
+                Ok(serde_json::from_str(&activity_data)?)
+            })
+            .collect()
+    }
+
+    /// Tombstone a synced activity
+    pub async fn mark_synced_activity_deleted(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        activity_id: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE synced_activities SET deleted_at = ?1
+            WHERE user_id = ?2 AND provider = ?3 AND activity_id = ?4 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id.to_string())
+        .bind(provider)
+        .bind(activity_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Store or refresh one day of a provider's wellness data
+    pub async fn upsert_daily_wellness(
+        &self,
+        user_id: Uuid,
+        wellness: &DailyWellness,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO daily_wellness (user_id, provider, date, wellness_data, synced_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(user_id, provider, date) DO UPDATE SET
+                wellness_data = excluded.wellness_data,
+                synced_at = excluded.synced_at
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(&wellness.provider)
+        .bind(wellness.date.format("%Y-%m-%d").to_string())
+        .bind(serde_json::to_string(wellness)?)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get stored wellness days in `start..=end`, oldest first
+    pub async fn get_daily_wellness(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyWellness>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT wellness_data FROM daily_wellness
+            WHERE user_id = ?1 AND provider = ?2 AND date >= ?3 AND date <= ?4
+            ORDER BY date ASC
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(provider)
+        .bind(start.format("%Y-%m-%d").to_string())
+        .bind(end.format("%Y-%m-%d").to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let wellness_data: String = row.try_get("wellness_data")?;
+                Ok(serde_json::from_str(&wellness_data)?)
+            })
+            .collect()
+    }
+
+    /// Store a user-entered activity
+    pub async fn create_manual_activity(&self, user_id: Uuid, activity: &Activity) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO manual_activities (id, user_id, activity_data, start_date, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+            "#,
+        )
+        .bind(&activity.id)
+        .bind(user_id.to_string())
+        .bind(serde_json::to_string(activity)?)
+        .bind(activity.start_date.to_rfc3339())
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get one of a user's manual activities
+    pub async fn get_manual_activity(
+        &self,
+        user_id: Uuid,
+        activity_id: &str,
+    ) -> Result<Option<Activity>> {
+        let row = sqlx::query(
+            "SELECT activity_data FROM manual_activities WHERE id = ?1 AND user_id = ?2",
+        )
+        .bind(activity_id)
+        .bind(user_id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let activity_data: String = row.try_get("activity_data")?;
+                Ok(Some(serde_json::from_str(&activity_data)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// List a user's manual activities, newest first
+    pub async fn list_manual_activities(
+        &self,
+        user_id: Uuid,
+        limit: Option<u32>,
+    ) -> Result<Vec<Activity>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT activity_data FROM manual_activities
+            WHERE user_id = ?1
+            ORDER BY start_date DESC
+            LIMIT ?2
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(limit.map_or(-1, i64::from))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let activity_data: String = row.try_get("activity_data")?;
+                Ok(serde_json::from_str(&activity_data)?)
+            })
+            .collect()
+    }
+
+    /// Replace a manual activity
+    pub async fn update_manual_activity(&self, user_id: Uuid, activity: &Activity) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE manual_activities SET activity_data = ?1, start_date = ?2, updated_at = ?3
+            WHERE id = ?4 AND user_id = ?5
+            "#,
+        )
+        .bind(serde_json::to_string(activity)?)
+        .bind(activity.start_date.to_rfc3339())
+        .bind(Utc::now().to_rfc3339())
+        .bind(&activity.id)
+        .bind(user_id.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a manual activity
+    pub async fn delete_manual_activity(&self, user_id: Uuid, activity_id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM manual_activities WHERE id = ?1 AND user_id = ?2")
+            .bind(activity_id)
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Insert or replace one of a user's shoes, bikes or other gear
+    pub async fn upsert_gear(&self, user_id: Uuid, gear: &Gear) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO gear (user_id, gear_id, provider, name, gear_data, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(user_id, gear_id) DO UPDATE SET
+                provider = excluded.provider,
+                name = excluded.name,
+                gear_data = excluded.gear_data,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(&gear.id)
+        .bind(&gear.provider)
+        .bind(&gear.name)
+        .bind(serde_json::to_string(gear)?)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get one piece of a user's gear
+    pub async fn get_gear(&self, user_id: Uuid, gear_id: &str) -> Result<Option<Gear>> {
+        let row = sqlx::query("SELECT gear_data FROM gear WHERE user_id = ?1 AND gear_id = ?2")
+            .bind(user_id.to_string())
+            .bind(gear_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => {
+                let gear_data: String = row.try_get("gear_data")?;
+                Ok(Some(serde_json::from_str(&gear_data)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// List a user's gear, ordered by name
+    pub async fn list_gear(&self, user_id: Uuid) -> Result<Vec<Gear>> {
+        let rows = sqlx::query("SELECT gear_data FROM gear WHERE user_id = ?1 ORDER BY name")
+            .bind(user_id.to_string())
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                let gear_data: String = row.try_get("gear_data")?;
+                Ok(serde_json::from_str(&gear_data)?)
+            })
+            .collect()
+    }
+
+    /// Store or replace a user's digest of one period
+    pub async fn upsert_digest(&self, user_id: Uuid, digest: &StoredDigest) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO digests (user_id, period, period_start, digest_data, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(user_id, period, period_start) DO UPDATE SET
+                digest_data = excluded.digest_data,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(&digest.period)
+        .bind(digest.period_start.format("%Y-%m-%d").to_string())
+        .bind(serde_json::to_string(digest)?)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get a user's digest of the period starting on `period_start`
+    pub async fn get_digest(
+        &self,
+        user_id: Uuid,
+        period: &str,
+        period_start: NaiveDate,
+    ) -> Result<Option<StoredDigest>> {
+        let row = sqlx::query(
+            "SELECT digest_data FROM digests WHERE user_id = ?1 AND period = ?2 AND period_start = ?3",
+        )
+        .bind(user_id.to_string())
+        .bind(period)
+        .bind(period_start.format("%Y-%m-%d").to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let digest_data: String = row.try_get("digest_data")?;
+                Ok(Some(serde_json::from_str(&digest_data)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// List a user's digests, optionally of one period, latest period first
+    pub async fn list_digests(
+        &self,
+        user_id: Uuid,
+        period: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<StoredDigest>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT digest_data FROM digests
+            WHERE user_id = ?1 AND (?2 IS NULL OR period = ?2)
+            ORDER BY period_start DESC, period ASC
+            LIMIT ?3
+            "#,
+        )
+        .bind(user_id.to_string())
+        .bind(period)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let digest_data: String = row.try_get("digest_data")?;
+                Ok(serde_json::from_str(&digest_data)?)
+            })
+            .collect()
+    }
+
+    /// Get the intelligence config overrides stored for a `tenant` or `user` scope
+    pub async fn get_analysis_settings(
+        &self,
+        scope: &str,
+        scope_id: &str,
+    ) -> Result<Option<serde_json::Value>> {
+        let row = sqlx::query(
+            "SELECT settings FROM analysis_settings WHERE scope = ?1 AND scope_id = ?2",
+        )
+        .bind(scope)
+        .bind(scope_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let settings: String = row.try_get("settings")?;
+                Ok(Some(serde_json::from_str(&settings)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Store or replace the intelligence config overrides for a scope
+    pub async fn upsert_analysis_settings(
+        &self,
+        scope: &str,
+        scope_id: &str,
+        settings: &serde_json::Value,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO analysis_settings (scope, scope_id, settings, updated_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(scope, scope_id) DO UPDATE SET
+                settings = excluded.settings,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(scope)
+        .bind(scope_id)
+        .bind(serde_json::to_string(settings)?)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get the tenant a user belongs to
+    pub async fn get_user_tenant(&self, user_id: Uuid) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT tenant_id FROM tenant_members WHERE user_id = ?1")
+            .bind(user_id.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(|row| Ok(row.try_get("tenant_id")?)).transpose()
+    }
+
+    /// Assign a user to a tenant, or remove them from theirs with `None`
+    pub async fn set_user_tenant(&self, user_id: Uuid, tenant_id: Option<&str>) -> Result<()> {
+        match tenant_id {
+            Some(tenant_id) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO tenant_members (user_id, tenant_id, created_at)
+                    VALUES (?1, ?2, ?3)
+                    ON CONFLICT(user_id) DO UPDATE SET tenant_id = excluded.tenant_id
+                    "#,
+                )
+                .bind(user_id.to_string())
+                .bind(tenant_id)
+                .bind(Utc::now().to_rfc3339())
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM tenant_members WHERE user_id = ?1")
+                    .bind(user_id.to_string())
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Update user's last active timestamp
+    pub async fn update_last_active(&self, user_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE users SET last_active = ?1 WHERE id = ?2")
+            .bind(Utc::now().to_rfc3339())
+            .bind(user_id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get total number of users (for health checks)
+    pub async fn get_user_count(&self) -> Result<i64> {
+        let row = sqlx::query("SELECT COUNT(*) as count FROM users")
+            .fetch_one(&self.pool)
+            .await?;
+
+        let count: i64 = row.try_get("count")?;
+        Ok(count)
+    }
+
+    /// List the ids of every active user, oldest account first
+    pub async fn list_user_ids(&self) -> Result<Vec<Uuid>> {
+        let rows = sqlx::query("SELECT id FROM users WHERE is_active = 1 ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                let id: String = row.try_get("id")?;
+                Ok(Uuid::parse_str(&id)?)
+            })
+            .collect()
     }
 
     /// Convert database row to User model
@@ -1023,70 +1890,17 @@ impl Database {
         let last_active = DateTime::parse_from_rfc3339(&last_active_str)?.with_timezone(&Utc);
 
         let is_active: bool = row.try_get("is_active")?;
-
-        // Build encrypted tokens if they exist
-        let strava_token = self.build_encrypted_token(&row, "strava")?;
-        let fitbit_token = self.build_encrypted_token(&row, "fitbit")?;
-
-        Ok(User {
-            id,
-            email,
-            display_name,
-            password_hash,
-            tier,
-            strava_token,
-            fitbit_token,
-            created_at,
-            last_active,
-            is_active,
-        })
-    }
-
-    /// Build encrypted token from database row
-    fn build_encrypted_token(
-        &self,
-        row: &sqlx::sqlite::SqliteRow,
-        provider: &str,
-    ) -> Result<Option<EncryptedToken>> {
-        let access_token: Option<String> = match provider {
-            "strava" => row.try_get("strava_access_token")?,
-            "fitbit" => row.try_get("fitbit_access_token")?,
-            _ => None,
-        };
-        let refresh_token: Option<String> = match provider {
-            "strava" => row.try_get("strava_refresh_token")?,
-            "fitbit" => row.try_get("fitbit_refresh_token")?,
-            _ => None,
-        };
-        let expires_at: Option<String> = match provider {
-            "strava" => row.try_get("strava_expires_at")?,
-            "fitbit" => row.try_get("fitbit_expires_at")?,
-            _ => None,
-        };
-        let scope: Option<String> = match provider {
-            "strava" => row.try_get("strava_scope")?,
-            "fitbit" => row.try_get("fitbit_scope")?,
-            _ => None,
-        };
-        let nonce: Option<String> = match provider {
-            "strava" => row.try_get("strava_nonce")?,
-            "fitbit" => row.try_get("fitbit_nonce")?,
-            _ => None,
-        };
-
-        if let (Some(access), Some(refresh), Some(expires), Some(scope), Some(nonce)) =
-            (access_token, refresh_token, expires_at, scope, nonce)
-        {
-            Ok(Some(EncryptedToken {
-                access_token: access,
-                refresh_token: refresh,
-                expires_at: DateTime::parse_from_rfc3339(&expires)?.with_timezone(&Utc),
-                scope,
-                nonce,
-            }))
-        } else {
-            Ok(None)
-        }
+
+        Ok(User {
+            id,
+            email,
+            display_name,
+            password_hash,
+            tier,
+            created_at,
+            last_active,
+            is_active,
+        })
     }
 
     // === ANALYTICS METHODS ===
@@ -1438,6 +2252,18 @@ impl Database {
                     serde_json::Value::Number(current_num),
                 );
             }
+            if let Ok(Some(sport_type)) = row.try_get::<Option<String>, _>("sport_type") {
+                goal.insert(
+                    "sport_type".to_string(),
+                    serde_json::Value::String(sport_type),
+                );
+            }
+            if let Ok(target_date) = row.try_get::<String, _>("target_date") {
+                goal.insert(
+                    "target_date".to_string(),
+                    serde_json::Value::String(target_date),
+                );
+            }
             if let Ok(status) = row.try_get::<String, _>("status") {
                 goal.insert("status".to_string(), serde_json::Value::String(status));
             }
@@ -1594,6 +2420,262 @@ mod tests {
         Database::new(database_url, encryption_key).await.unwrap()
     }
 
+    #[tokio::test]
+    async fn test_legacy_provider_tokens_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_url = format!("sqlite:{}", dir.path().join("legacy.db").display());
+        let encryption_key = generate_encryption_key().to_vec();
+        let user_id = Uuid::new_v4();
+        let expires_at = Utc::now() + chrono::Duration::hours(6);
+
+        // A database written by a release that kept tokens on the users row
+        {
+            let pool = SqlitePool::connect(&format!("{database_url}?mode=rwc"))
+                .await
+                .unwrap();
+            sqlx::query(
+                r#"
+                CREATE TABLE users (
+                    id TEXT PRIMARY KEY,
+                    email TEXT UNIQUE NOT NULL,
+                    display_name TEXT,
+                    password_hash TEXT NOT NULL,
+                    tier TEXT NOT NULL DEFAULT 'starter' CHECK (tier IN ('starter', 'professional', 'enterprise')),
+                    strava_access_token TEXT,
+                    strava_refresh_token TEXT,
+                    strava_expires_at TEXT,
+                    strava_scope TEXT,
+                    strava_nonce TEXT,
+                    fitbit_access_token TEXT,
+                    fitbit_refresh_token TEXT,
+                    fitbit_expires_at TEXT,
+                    fitbit_scope TEXT,
+                    fitbit_nonce TEXT,
+                    created_at TEXT NOT NULL,
+                    last_active TEXT NOT NULL,
+                    is_active BOOLEAN NOT NULL DEFAULT 1
+                )
+                "#,
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            let strava = EncryptedToken::new(
+                "strava_access",
+                "strava_refresh",
+                expires_at,
+                "read,activity:read_all".to_string(),
+                &encryption_key,
+            )
+            .unwrap();
+            let fitbit = EncryptedToken::new(
+                "fitbit_access",
+                "fitbit_refresh",
+                expires_at,
+                "activity profile".to_string(),
+                &encryption_key,
+            )
+            .unwrap();
+            sqlx::query(
+                r#"
+                INSERT INTO users (id, email, password_hash,
+                    strava_access_token, strava_refresh_token, strava_expires_at, strava_scope, strava_nonce,
+                    fitbit_access_token, fitbit_refresh_token, fitbit_expires_at, fitbit_scope, fitbit_nonce,
+                    created_at, last_active)
+                VALUES (?1, 'legacy@example.com', 'hash', ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?12)
+                "#,
+            )
+            .bind(user_id.to_string())
+            .bind(&strava.access_token)
+            .bind(&strava.refresh_token)
+            .bind(strava.expires_at.to_rfc3339())
+            .bind(&strava.scope)
+            .bind(&strava.nonce)
+            .bind(&fitbit.access_token)
+            .bind(&fitbit.refresh_token)
+            .bind(fitbit.expires_at.to_rfc3339())
+            .bind(&fitbit.scope)
+            .bind(&fitbit.nonce)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&pool)
+            .await
+            .unwrap();
+            pool.close().await;
+        }
+
+        let db = Database::new(&database_url, encryption_key.clone())
+            .await
+            .unwrap();
+        let strava = db
+            .get_provider_token(user_id, "strava")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(strava.access_token, "strava_access");
+        assert_eq!(strava.refresh_token, "strava_refresh");
+        assert_eq!(strava.scope, "read,activity:read_all");
+        let fitbit = db
+            .get_provider_token(user_id, "fitbit")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fitbit.access_token, "fitbit_access");
+        assert_eq!(
+            db.list_provider_connections(user_id).await.unwrap().len(),
+            2
+        );
+
+        // A connection removed after the upgrade stays removed on restart
+        assert!(db
+            .delete_provider_connection(user_id, "strava")
+            .await
+            .unwrap());
+        db.pool.close().await;
+        let db = Database::new(&database_url, encryption_key).await.unwrap();
+        assert!(db
+            .get_provider_token(user_id, "strava")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(db
+            .get_provider_token(user_id, "fitbit")
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_legacy_a2a_clients_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let database_url = format!("sqlite:{}", dir.path().join("legacy_a2a.db").display());
+
+        let encryption_key = generate_encryption_key().to_vec();
+        let db = Database::new(&database_url, encryption_key.clone())
+            .await
+            .unwrap();
+        let user = User::new(
+            "a2a-system-legacy@pierre.ai".to_string(),
+            "hash".to_string(),
+            None,
+        );
+        let user_id = db.create_user(&user).await.unwrap();
+        let (api_key, _) = crate::api_keys::ApiKeyManager::new()
+            .create_api_key(
+                user_id,
+                crate::api_keys::CreateApiKeyRequest {
+                    name: "A2A Client: Legacy Partner".to_string(),
+                    description: None,
+                    tier: crate::api_keys::ApiKeyTier::Professional,
+                    expires_in_days: None,
+                },
+            )
+            .await
+            .unwrap();
+        db.create_api_key(&api_key).await.unwrap();
+
+        // Put back the clients table as written before clients could be
+        // deactivated or rotated
+        for statement in [
+            "DROP TABLE a2a_client_credentials",
+            "DROP TABLE a2a_clients",
+            r#"
+            CREATE TABLE a2a_clients (
+                id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+                api_key_id TEXT NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+                name TEXT NOT NULL,
+                description TEXT,
+                public_key TEXT,
+                capabilities TEXT NOT NULL DEFAULT '[]',
+                redirect_uris TEXT NOT NULL DEFAULT '[]',
+                agent_version TEXT,
+                contact_email TEXT,
+                documentation_url TEXT,
+                is_verified BOOLEAN NOT NULL DEFAULT false,
+                verification_token TEXT,
+                verified_at TIMESTAMP,
+                created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(name)
+            )
+            "#,
+        ] {
+            sqlx::query(statement).execute(&db.pool).await.unwrap();
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO a2a_clients (id, api_key_id, name, description, capabilities, created_at)
+            VALUES ('legacy-client', ?1, 'Legacy Partner', 'Registered before upgrade',
+                '["fitness-data-analysis"]', ?2)
+            "#,
+        )
+        .bind(&api_key.id)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        db.pool.close().await;
+
+        let db = Database::new(&database_url, encryption_key.clone())
+            .await
+            .unwrap();
+        let client = db.get_a2a_client("legacy-client").await.unwrap().unwrap();
+        assert_eq!(client.name, "Legacy Partner");
+        assert!(client.is_active);
+
+        let quota = db
+            .get_a2a_client_quota("legacy-client")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(quota.tier, crate::a2a::client::A2AClientTier::Trial);
+        assert_eq!(quota.monthly_limit, None);
+
+        // The client's API key is recorded as its credential, but no secret matches it
+        assert_eq!(
+            db.get_a2a_client_by_api_key_id(&api_key.id)
+                .await
+                .unwrap()
+                .map(|c| c.id),
+            Some("legacy-client".to_string())
+        );
+        let any_secret_hash = crate::api_keys::ApiKeyManager::new().hash_key("");
+        assert!(!db
+            .verify_a2a_client_secret("legacy-client", &any_secret_hash)
+            .await
+            .unwrap());
+        let credentials: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM a2a_client_credentials WHERE client_id = 'legacy-client' AND api_key_id = ?1",
+        )
+        .bind(&api_key.id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        assert_eq!(credentials, 1);
+
+        // Lifecycle updates work on the migrated table
+        assert!(db
+            .set_a2a_client_active("legacy-client", false)
+            .await
+            .unwrap());
+        assert!(
+            !db.get_a2a_client("legacy-client")
+                .await
+                .unwrap()
+                .unwrap()
+                .is_active
+        );
+
+        // Reopening does not record the credential twice
+        db.pool.close().await;
+        let db = Database::new(&database_url, encryption_key).await.unwrap();
+        let credentials: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM a2a_client_credentials")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(credentials, 1);
+    }
+
     #[tokio::test]
     async fn test_create_and_get_user() {
         let db = create_test_db().await;
@@ -1646,18 +2728,26 @@ mod tests {
         let expires_at = Utc::now() + chrono::Duration::hours(6);
 
         // Store token
-        db.update_strava_token(
+        db.upsert_provider_token(
             user_id,
-            "access_token_123",
-            "refresh_token_456",
-            expires_at,
-            "read,activity:read_all".to_string(),
+            "strava",
+            &DecryptedToken {
+                access_token: "access_token_123".to_string(),
+                refresh_token: "refresh_token_456".to_string(),
+                expires_at,
+                scope: "read,activity:read_all".to_string(),
+            },
+            Some("12345"),
         )
         .await
         .unwrap();
 
         // Retrieve token
-        let token = db.get_strava_token(user_id).await.unwrap().unwrap();
+        let token = db
+            .get_provider_token(user_id, "strava")
+            .await
+            .unwrap()
+            .unwrap();
         assert_eq!(token.access_token, "access_token_123");
         assert_eq!(token.refresh_token, "refresh_token_456");
         assert_eq!(token.scope, "read,activity:read_all");
@@ -1680,22 +2770,58 @@ mod tests {
 
         let expires_at = Utc::now() + chrono::Duration::hours(8);
 
-        // Store token
-        db.update_fitbit_token(
-            user_id,
-            "fitbit_access_789",
-            "fitbit_refresh_101112",
+        let mut token = DecryptedToken {
+            access_token: "fitbit_access_789".to_string(),
+            refresh_token: "fitbit_refresh_101112".to_string(),
             expires_at,
-            "activity heartrate profile".to_string(),
-        )
-        .await
-        .unwrap();
+            scope: "activity heartrate profile".to_string(),
+        };
+
+        // Store token
+        db.upsert_provider_token(user_id, "fitbit", &token, Some("ABC123"))
+            .await
+            .unwrap();
+
+        // A refresh without the provider user id keeps the stored one
+        token.access_token = "fitbit_access_refreshed".to_string();
+        db.upsert_provider_token(user_id, "fitbit", &token, None)
+            .await
+            .unwrap();
 
         // Retrieve token
-        let token = db.get_fitbit_token(user_id).await.unwrap().unwrap();
-        assert_eq!(token.access_token, "fitbit_access_789");
-        assert_eq!(token.refresh_token, "fitbit_refresh_101112");
-        assert_eq!(token.scope, "activity heartrate profile");
+        let stored = db
+            .get_provider_token(user_id, "fitbit")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.access_token, "fitbit_access_refreshed");
+        assert_eq!(stored.refresh_token, "fitbit_refresh_101112");
+        assert_eq!(stored.scope, "activity heartrate profile");
+
+        let connections = db.list_provider_connections(user_id).await.unwrap();
+        assert_eq!(connections.len(), 1);
+        assert_eq!(connections[0].provider, "fitbit");
+        assert_eq!(connections[0].provider_user_id.as_deref(), Some("ABC123"));
+        assert!(db
+            .get_provider_token(user_id, "strava")
+            .await
+            .unwrap()
+            .is_none());
+
+        // Disconnecting removes the connection
+        assert!(db
+            .delete_provider_connection(user_id, "fitbit")
+            .await
+            .unwrap());
+        assert!(!db
+            .delete_provider_connection(user_id, "fitbit")
+            .await
+            .unwrap());
+        assert!(db
+            .get_provider_token(user_id, "fitbit")
+            .await
+            .unwrap()
+            .is_none());
     }
 
     #[tokio::test]
@@ -2832,157 +3958,362 @@ impl Database {
         })
     }
 
-    // === A2A CLIENT MANAGEMENT ===
+    // === A2A CLIENT MANAGEMENT ===
+
+    /// Create a new A2A client linked to an API key
+    pub async fn create_a2a_client(
+        &self,
+        client: &crate::a2a::A2AClient,
+        api_key_id: &str,
+    ) -> Result<String> {
+        // Use the client's existing ID instead of generating a new one
+        let client_id = &client.id;
+
+        sqlx::query(
+            r#"
+            INSERT INTO a2a_clients (
+                id, api_key_id, name, description, public_key, capabilities, 
+                redirect_uris, agent_version, contact_email, documentation_url,
+                is_active, created_at, updated_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?12)
+            "#,
+        )
+        .bind(client_id)
+        .bind(api_key_id)
+        .bind(&client.name)
+        .bind(&client.description)
+        .bind(&client.public_key)
+        .bind(serde_json::to_string(&client.capabilities)?)
+        .bind(serde_json::to_string(&client.redirect_uris)?)
+        .bind("1.0.0") // Default agent version
+        .bind(Option::<String>::None) // contact_email
+        .bind(Option::<String>::None) // documentation_url
+        .bind(client.is_active)
+        .bind(client.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(client_id.to_string())
+    }
+
+    /// Get A2A client by ID
+    pub async fn get_a2a_client(&self, client_id: &str) -> Result<Option<crate::a2a::A2AClient>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, description, public_key, capabilities, redirect_uris, 
+                   is_active, created_at
+            FROM a2a_clients 
+            WHERE id = ?1
+            "#,
+        )
+        .bind(client_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| self.row_to_a2a_client(&row)).transpose()
+    }
+
+    /// Get A2A client by name
+    pub async fn get_a2a_client_by_name(
+        &self,
+        name: &str,
+    ) -> Result<Option<crate::a2a::A2AClient>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, description, public_key, capabilities, redirect_uris, 
+                   is_active, created_at
+            FROM a2a_clients 
+            WHERE name = ?1
+            "#,
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| self.row_to_a2a_client(&row)).transpose()
+    }
+
+    /// List A2A clients for a user
+    pub async fn list_a2a_clients(&self, user_id: &Uuid) -> Result<Vec<crate::a2a::A2AClient>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT c.id, c.name, c.description, c.public_key, c.capabilities, 
+                   c.redirect_uris, c.is_active, c.created_at
+            FROM a2a_clients c
+            INNER JOIN api_keys ak ON c.api_key_id = ak.id
+            WHERE ak.user_id = ?1 AND ak.is_active = true
+            ORDER BY c.created_at DESC
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(|row| self.row_to_a2a_client(row)).collect()
+    }
+
+    /// List every registered A2A client
+    pub async fn list_all_a2a_clients(&self) -> Result<Vec<crate::a2a::A2AClient>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, description, public_key, capabilities, redirect_uris,
+                   is_active, created_at
+            FROM a2a_clients
+            ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(|row| self.row_to_a2a_client(row)).collect()
+    }
+
+    /// Get the A2A client owning an API key, including keys kept alive by a rotation overlap
+    pub async fn get_a2a_client_by_api_key_id(
+        &self,
+        api_key_id: &str,
+    ) -> Result<Option<crate::a2a::A2AClient>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, description, public_key, capabilities, redirect_uris,
+                   is_active, created_at
+            FROM a2a_clients
+            WHERE api_key_id = ?1
+               OR id IN (SELECT client_id FROM a2a_client_credentials WHERE api_key_id = ?1)
+            LIMIT 1
+            "#,
+        )
+        .bind(api_key_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| self.row_to_a2a_client(&row)).transpose()
+    }
+
+    /// Activate or deactivate an A2A client
+    ///
+    /// The client's API keys follow its status and deactivation revokes
+    /// every open session. Returns false when the client does not exist.
+    pub async fn set_a2a_client_active(&self, client_id: &str, is_active: bool) -> Result<bool> {
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
 
-    /// Create a new A2A client linked to an API key
-    pub async fn create_a2a_client(
+        let result = sqlx::query(
+            r#"
+            UPDATE a2a_clients
+            SET is_active = ?1,
+                deactivated_at = CASE WHEN ?1 THEN NULL ELSE ?2 END,
+                updated_at = ?2
+            WHERE id = ?3
+            "#,
+        )
+        .bind(is_active)
+        .bind(&now)
+        .bind(client_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET is_active = ?1, updated_at = ?2
+            WHERE id IN (SELECT api_key_id FROM a2a_client_credentials WHERE client_id = ?3)
+               OR id = (SELECT api_key_id FROM a2a_clients WHERE id = ?3)
+            "#,
+        )
+        .bind(is_active)
+        .bind(&now)
+        .bind(client_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if !is_active {
+            sqlx::query(
+                "UPDATE a2a_sessions SET is_active = false WHERE client_id = ?1 AND is_active = true",
+            )
+            .bind(client_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Issue a new credential for an A2A client
+    ///
+    /// Credentials issued earlier, and their API keys, stay valid until
+    /// `previous_valid_until` so partners can roll over without downtime.
+    pub async fn rotate_a2a_client_credentials(
         &self,
-        client: &crate::a2a::A2AClient,
+        client_id: &str,
+        secret_hash: &str,
         api_key_id: &str,
-    ) -> Result<String> {
-        // Use the client's existing ID instead of generating a new one
-        let client_id = &client.id;
+        previous_valid_until: DateTime<Utc>,
+    ) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        let valid_until = previous_valid_until.to_rfc3339();
+        let mut tx = self.pool.begin().await?;
 
         sqlx::query(
             r#"
-            INSERT INTO a2a_clients (
-                id, api_key_id, name, description, public_key, capabilities, 
-                redirect_uris, agent_version, contact_email, documentation_url
-            )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            UPDATE api_keys
+            SET expires_at = ?1, updated_at = ?2
+            WHERE (id IN (SELECT api_key_id FROM a2a_client_credentials WHERE client_id = ?3)
+                   OR id = (SELECT api_key_id FROM a2a_clients WHERE id = ?3))
+              AND id != ?4
+              AND (expires_at IS NULL OR expires_at > ?1)
             "#,
         )
+        .bind(&valid_until)
+        .bind(&now)
         .bind(client_id)
         .bind(api_key_id)
-        .bind(&client.name)
-        .bind(&client.description)
-        .bind(&client.public_key)
-        .bind(serde_json::to_string(&client.capabilities)?)
-        .bind(serde_json::to_string(&client.redirect_uris)?)
-        .bind("1.0.0") // Default agent version
-        .bind(Option::<String>::None) // contact_email
-        .bind(Option::<String>::None) // documentation_url
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
-        Ok(client_id.to_string())
-    }
+        sqlx::query(
+            r#"
+            UPDATE a2a_client_credentials
+            SET expires_at = ?1
+            WHERE client_id = ?2 AND (expires_at IS NULL OR expires_at > ?1)
+            "#,
+        )
+        .bind(&valid_until)
+        .bind(client_id)
+        .execute(&mut *tx)
+        .await?;
 
-    /// Get A2A client by ID
-    pub async fn get_a2a_client(&self, client_id: &str) -> Result<Option<crate::a2a::A2AClient>> {
-        let row = sqlx::query(
+        sqlx::query(
             r#"
-            SELECT id, name, description, public_key, capabilities, redirect_uris, 
-                   is_verified, created_at
-            FROM a2a_clients 
-            WHERE id = ?1
+            INSERT INTO a2a_client_credentials (id, client_id, secret_hash, api_key_id, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
             "#,
         )
+        .bind(Uuid::new_v4().to_string())
         .bind(client_id)
-        .fetch_optional(&self.pool)
+        .bind(secret_hash)
+        .bind(api_key_id)
+        .bind(&now)
+        .execute(&mut *tx)
         .await?;
 
-        if let Some(row) = row {
-            let capabilities: Vec<String> =
-                serde_json::from_str(&row.get::<String, _>("capabilities"))?;
-            let redirect_uris: Vec<String> =
-                serde_json::from_str(&row.get::<String, _>("redirect_uris"))?;
+        sqlx::query("UPDATE a2a_clients SET api_key_id = ?1, updated_at = ?2 WHERE id = ?3")
+            .bind(api_key_id)
+            .bind(&now)
+            .bind(client_id)
+            .execute(&mut *tx)
+            .await?;
 
-            Ok(Some(crate::a2a::A2AClient {
-                id: row.get("id"),
-                name: row.get("name"),
-                description: row.get("description"),
-                public_key: row.get("public_key"),
-                capabilities,
-                redirect_uris,
-                is_active: row.get("is_verified"),
-                created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
-                    .unwrap()
-                    .with_timezone(&Utc),
-            }))
-        } else {
-            Ok(None)
-        }
+        tx.commit().await?;
+        Ok(())
     }
 
-    /// Get A2A client by name
-    pub async fn get_a2a_client_by_name(
+    /// Check a client secret hash against the client's unexpired credentials
+    pub async fn verify_a2a_client_secret(
         &self,
-        name: &str,
-    ) -> Result<Option<crate::a2a::A2AClient>> {
-        let row = sqlx::query(
+        client_id: &str,
+        secret_hash: &str,
+    ) -> Result<bool> {
+        let count: i64 = sqlx::query_scalar(
             r#"
-            SELECT id, name, description, public_key, capabilities, redirect_uris, 
-                   is_verified, created_at
-            FROM a2a_clients 
-            WHERE name = ?1
+            SELECT COUNT(*) FROM a2a_client_credentials
+            WHERE client_id = ?1 AND secret_hash = ?2
+              AND (expires_at IS NULL OR expires_at > ?3)
             "#,
         )
-        .bind(name)
-        .fetch_optional(&self.pool)
+        .bind(client_id)
+        .bind(secret_hash)
+        .bind(Utc::now().to_rfc3339())
+        .fetch_one(&self.pool)
         .await?;
 
-        if let Some(row) = row {
-            let capabilities: Vec<String> =
-                serde_json::from_str(&row.get::<String, _>("capabilities"))?;
-            let redirect_uris: Vec<String> =
-                serde_json::from_str(&row.get::<String, _>("redirect_uris"))?;
+        Ok(count > 0)
+    }
 
-            Ok(Some(crate::a2a::A2AClient {
-                id: row.get("id"),
-                name: row.get("name"),
-                description: row.get("description"),
-                public_key: row.get("public_key"),
-                capabilities,
-                redirect_uris,
-                is_active: row.get("is_verified"),
-                created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
-                    .unwrap()
-                    .with_timezone(&Utc),
-            }))
-        } else {
-            Ok(None)
-        }
+    /// Get the tier and custom monthly limit of an A2A client
+    pub async fn get_a2a_client_quota(
+        &self,
+        client_id: &str,
+    ) -> Result<Option<crate::a2a::client::A2AClientQuota>> {
+        let row = sqlx::query("SELECT tier, monthly_limit FROM a2a_clients WHERE id = ?1")
+            .bind(client_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| {
+            let tier = match row.get::<String, _>("tier").as_str() {
+                "standard" => crate::a2a::client::A2AClientTier::Standard,
+                "professional" => crate::a2a::client::A2AClientTier::Professional,
+                "enterprise" => crate::a2a::client::A2AClientTier::Enterprise,
+                _ => crate::a2a::client::A2AClientTier::Trial,
+            };
+
+            crate::a2a::client::A2AClientQuota {
+                tier,
+                monthly_limit: row
+                    .get::<Option<i64>, _>("monthly_limit")
+                    .map(|limit| limit as u32),
+            }
+        }))
     }
 
-    /// List A2A clients for a user
-    pub async fn list_a2a_clients(&self, user_id: &Uuid) -> Result<Vec<crate::a2a::A2AClient>> {
-        let rows = sqlx::query(
-            r#"
-            SELECT c.id, c.name, c.description, c.public_key, c.capabilities, 
-                   c.redirect_uris, c.is_verified, c.created_at
-            FROM a2a_clients c
-            INNER JOIN api_keys ak ON c.api_key_id = ak.id
-            WHERE ak.user_id = ?1 AND ak.is_active = true
-            ORDER BY c.created_at DESC
-            "#,
+    /// Update the tier and custom monthly limit of an A2A client
+    pub async fn update_a2a_client_quota(
+        &self,
+        client_id: &str,
+        quota: &crate::a2a::client::A2AClientQuota,
+    ) -> Result<bool> {
+        let tier = match quota.tier {
+            crate::a2a::client::A2AClientTier::Trial => "trial",
+            crate::a2a::client::A2AClientTier::Standard => "standard",
+            crate::a2a::client::A2AClientTier::Professional => "professional",
+            crate::a2a::client::A2AClientTier::Enterprise => "enterprise",
+        };
+
+        let result = sqlx::query(
+            "UPDATE a2a_clients SET tier = ?1, monthly_limit = ?2, updated_at = ?3 WHERE id = ?4",
         )
-        .bind(user_id.to_string())
-        .fetch_all(&self.pool)
+        .bind(tier)
+        .bind(quota.monthly_limit.map(i64::from))
+        .bind(Utc::now().to_rfc3339())
+        .bind(client_id)
+        .execute(&self.pool)
         .await?;
 
-        let mut clients = Vec::new();
-        for row in rows {
-            let capabilities: Vec<String> =
-                serde_json::from_str(&row.get::<String, _>("capabilities"))?;
-            let redirect_uris: Vec<String> =
-                serde_json::from_str(&row.get::<String, _>("redirect_uris"))?;
+        Ok(result.rows_affected() > 0)
+    }
 
-            clients.push(crate::a2a::A2AClient {
-                id: row.get("id"),
-                name: row.get("name"),
-                description: row.get("description"),
-                public_key: row.get("public_key"),
-                capabilities,
-                redirect_uris,
-                is_active: row.get("is_verified"),
-                created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
-                    .unwrap()
-                    .with_timezone(&Utc),
-            });
+    /// Convert database row to A2AClient
+    fn row_to_a2a_client(&self, row: &sqlx::sqlite::SqliteRow) -> Result<crate::a2a::A2AClient> {
+        Ok(crate::a2a::A2AClient {
+            id: row.get("id"),
+            name: row.get("name"),
+            description: row
+                .get::<Option<String>, _>("description")
+                .unwrap_or_default(),
+            public_key: row
+                .get::<Option<String>, _>("public_key")
+                .unwrap_or_default(),
+            capabilities: serde_json::from_str(&row.get::<String, _>("capabilities"))?,
+            redirect_uris: serde_json::from_str(&row.get::<String, _>("redirect_uris"))?,
+            is_active: row.get("is_active"),
+            created_at: Self::parse_a2a_timestamp(&row.get::<String, _>("created_at"))?,
+        })
+    }
+
+    /// Parse an A2A timestamp stored either as RFC 3339 or by SQLite's `CURRENT_TIMESTAMP`
+    fn parse_a2a_timestamp(value: &str) -> Result<DateTime<Utc>> {
+        if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+            return Ok(timestamp.with_timezone(&Utc));
         }
 
-        Ok(clients)
+        Ok(chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")?.and_utc())
     }
 
     /// Create A2A session
@@ -2995,14 +4326,16 @@ impl Database {
     ) -> Result<String> {
         let session_id = uuid::Uuid::new_v4().to_string();
         let session_token = uuid::Uuid::new_v4().to_string();
-        let expires_at = Utc::now() + chrono::Duration::hours(expires_in_hours);
+        let now = Utc::now();
+        let expires_at = now + chrono::Duration::hours(expires_in_hours);
 
         sqlx::query(
             r#"
             INSERT INTO a2a_sessions (
-                id, client_id, session_token, user_id, granted_scopes, expires_at
+                id, client_id, session_token, user_id, granted_scopes, expires_at,
+                created_at, last_activity
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
             "#,
         )
         .bind(&session_id)
@@ -3011,6 +4344,7 @@ impl Database {
         .bind(user_id.map(|u| u.to_string()))
         .bind(serde_json::to_string(granted_scopes)?)
         .bind(expires_at.to_rfc3339())
+        .bind(now.to_rfc3339())
         .execute(&self.pool)
         .await?;
 
@@ -3024,39 +4358,44 @@ impl Database {
     ) -> Result<Option<crate::a2a::client::A2ASession>> {
         let row = sqlx::query(
             r#"
-            SELECT id, client_id, user_id, granted_scopes, created_at, expires_at, 
-                   last_activity, is_active
-            FROM a2a_sessions 
-            WHERE session_token = ?1 AND is_active = true AND expires_at > datetime('now')
+            SELECT s.id, s.client_id, s.user_id, s.granted_scopes, s.created_at, s.expires_at,
+                   s.last_activity,
+                   (SELECT COUNT(*) FROM a2a_usage u WHERE u.session_token = s.session_token)
+                       AS requests_count
+            FROM a2a_sessions s
+            WHERE s.session_token = ?1 AND s.is_active = true AND s.expires_at > ?2
             "#,
         )
         .bind(session_token)
+        .bind(Utc::now().to_rfc3339())
         .fetch_optional(&self.pool)
         .await?;
 
-        if let Some(row) = row {
-            let granted_scopes: Vec<String> =
-                serde_json::from_str(&row.get::<String, _>("granted_scopes"))?;
+        row.map(|row| Self::row_to_a2a_session(&row)).transpose()
+    }
 
-            Ok(Some(crate::a2a::client::A2ASession {
-                id: row.get("id"),
-                client_id: row.get("client_id"),
-                user_id: row.get::<Option<String>, _>("user_id"),
-                granted_scopes,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
-                    .unwrap()
-                    .with_timezone(&Utc),
-                expires_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("expires_at"))
-                    .unwrap()
-                    .with_timezone(&Utc),
-                last_activity: DateTime::parse_from_rfc3339(&row.get::<String, _>("last_activity"))
-                    .unwrap()
-                    .with_timezone(&Utc),
-                requests_count: 0, // Would need additional query to get actual count
-            }))
-        } else {
-            Ok(None)
-        }
+    /// List the active, unexpired sessions of an A2A client
+    pub async fn list_a2a_sessions(
+        &self,
+        client_id: &str,
+    ) -> Result<Vec<crate::a2a::client::A2ASession>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT s.id, s.client_id, s.user_id, s.granted_scopes, s.created_at, s.expires_at,
+                   s.last_activity,
+                   (SELECT COUNT(*) FROM a2a_usage u WHERE u.session_token = s.session_token)
+                       AS requests_count
+            FROM a2a_sessions s
+            WHERE s.client_id = ?1 AND s.is_active = true AND s.expires_at > ?2
+            ORDER BY s.last_activity DESC
+            "#,
+        )
+        .bind(client_id)
+        .bind(Utc::now().to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_a2a_session).collect()
     }
 
     /// Update A2A session activity
@@ -3064,10 +4403,11 @@ impl Database {
         sqlx::query(
             r#"
             UPDATE a2a_sessions 
-            SET last_activity = datetime('now')
-            WHERE session_token = ?1
+            SET last_activity = ?1
+            WHERE session_token = ?2
             "#,
         )
+        .bind(Utc::now().to_rfc3339())
         .bind(session_token)
         .execute(&self.pool)
         .await?;
@@ -3075,6 +4415,47 @@ impl Database {
         Ok(())
     }
 
+    /// Revoke a single session of an A2A client
+    pub async fn revoke_a2a_session(&self, client_id: &str, session_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE a2a_sessions SET is_active = false
+            WHERE id = ?1 AND client_id = ?2 AND is_active = true
+            "#,
+        )
+        .bind(session_id)
+        .bind(client_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete expired and revoked A2A sessions
+    pub async fn cleanup_expired_a2a_sessions(&self) -> Result<u64> {
+        let result =
+            sqlx::query("DELETE FROM a2a_sessions WHERE is_active = false OR expires_at <= ?1")
+                .bind(Utc::now().to_rfc3339())
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Convert database row to A2ASession
+    fn row_to_a2a_session(row: &sqlx::sqlite::SqliteRow) -> Result<crate::a2a::client::A2ASession> {
+        Ok(crate::a2a::client::A2ASession {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            user_id: row.get::<Option<String>, _>("user_id"),
+            granted_scopes: serde_json::from_str(&row.get::<String, _>("granted_scopes"))?,
+            created_at: Self::parse_a2a_timestamp(&row.get::<String, _>("created_at"))?,
+            expires_at: Self::parse_a2a_timestamp(&row.get::<String, _>("expires_at"))?,
+            last_activity: Self::parse_a2a_timestamp(&row.get::<String, _>("last_activity"))?,
+            requests_count: row.get::<i64, _>("requests_count") as u64,
+        })
+    }
+
     /// Create A2A task
     pub async fn create_a2a_task(
         &self,
@@ -3405,4 +4786,154 @@ impl Database {
 
         Ok(usage_records)
     }
+
+    /// Create or refresh a trusted remote agent
+    pub async fn upsert_remote_agent(&self, agent: &crate::a2a::RemoteAgent) -> Result<()> {
+        let encrypted_auth_token = match &agent.auth_token {
+            Some(token) => Some(serde_json::to_string(&EncryptedToken::new(
+                token,
+                "",
+                agent.created_at,
+                "a2a".to_string(),
+                &self.encryption_key,
+            )?)?),
+            None => None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO a2a_remote_agents (
+                id, tenant_id, user_id, name, description, base_url, endpoint_url,
+                capabilities, auth_token, is_active, created_at, last_verified_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                description = excluded.description,
+                endpoint_url = excluded.endpoint_url,
+                capabilities = excluded.capabilities,
+                auth_token = excluded.auth_token,
+                is_active = excluded.is_active,
+                last_verified_at = excluded.last_verified_at
+            "#,
+        )
+        .bind(&agent.id)
+        .bind(agent.owner.tenant_id())
+        .bind(agent.owner.user_id().map(|id| id.to_string()))
+        .bind(&agent.name)
+        .bind(&agent.description)
+        .bind(&agent.base_url)
+        .bind(&agent.endpoint_url)
+        .bind(serde_json::to_string(&agent.capabilities)?)
+        .bind(encrypted_auth_token)
+        .bind(agent.is_active)
+        .bind(agent.created_at.to_rfc3339())
+        .bind(agent.last_verified_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get a remote agent from a tenant's or user's registry
+    pub async fn get_remote_agent(
+        &self,
+        owner: &crate::a2a::AgentOwner,
+        agent_id: &str,
+    ) -> Result<Option<crate::a2a::RemoteAgent>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, tenant_id, user_id, name, description, base_url, endpoint_url, capabilities,
+                   auth_token, is_active, created_at, last_verified_at
+            FROM a2a_remote_agents
+            WHERE id = ?1 AND tenant_id IS ?2 AND user_id IS ?3
+            "#,
+        )
+        .bind(agent_id)
+        .bind(owner.tenant_id())
+        .bind(owner.user_id().map(|id| id.to_string()))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| self.row_to_remote_agent(row)).transpose()
+    }
+
+    /// List the remote agents in a tenant's or user's registry
+    pub async fn list_remote_agents(
+        &self,
+        owner: &crate::a2a::AgentOwner,
+    ) -> Result<Vec<crate::a2a::RemoteAgent>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, tenant_id, user_id, name, description, base_url, endpoint_url, capabilities,
+                   auth_token, is_active, created_at, last_verified_at
+            FROM a2a_remote_agents
+            WHERE tenant_id IS ?1 AND user_id IS ?2
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(owner.tenant_id())
+        .bind(owner.user_id().map(|id| id.to_string()))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| self.row_to_remote_agent(row))
+            .collect()
+    }
+
+    /// Remove a remote agent from a tenant's or user's registry
+    pub async fn delete_remote_agent(
+        &self,
+        owner: &crate::a2a::AgentOwner,
+        agent_id: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM a2a_remote_agents WHERE id = ?1 AND tenant_id IS ?2 AND user_id IS ?3",
+        )
+        .bind(agent_id)
+        .bind(owner.tenant_id())
+        .bind(owner.user_id().map(|id| id.to_string()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Convert database row to RemoteAgent, decrypting its credential
+    fn row_to_remote_agent(&self, row: sqlx::sqlite::SqliteRow) -> Result<crate::a2a::RemoteAgent> {
+        let auth_token = match row.get::<Option<String>, _>("auth_token") {
+            Some(encrypted) => Some(
+                serde_json::from_str::<EncryptedToken>(&encrypted)?
+                    .decrypt(&self.encryption_key)?
+                    .access_token,
+            ),
+            None => None,
+        };
+
+        Ok(crate::a2a::RemoteAgent {
+            id: row.get("id"),
+            owner: crate::a2a::AgentOwner::from_columns(
+                row.get("tenant_id"),
+                row.get::<Option<String>, _>("user_id")
+                    .map(|id| Uuid::parse_str(&id))
+                    .transpose()?,
+            )?,
+            name: row.get("name"),
+            description: row
+                .get::<Option<String>, _>("description")
+                .unwrap_or_default(),
+            base_url: row.get("base_url"),
+            endpoint_url: row.get("endpoint_url"),
+            capabilities: serde_json::from_str(&row.get::<String, _>("capabilities"))?,
+            auth_token,
+            is_active: row.get("is_active"),
+            created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+                .with_timezone(&Utc),
+            last_verified_at: DateTime::parse_from_rfc3339(
+                &row.get::<String, _>("last_verified_at"),
+            )?
+            .with_timezone(&Utc),
+        })
+    }
 }
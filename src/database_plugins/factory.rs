@@ -5,8 +5,9 @@
 
 use super::DatabaseProvider;
 use crate::a2a::auth::A2AClient;
-use crate::a2a::client::A2ASession;
+use crate::a2a::client::{A2AClientQuota, A2ASession};
 use crate::a2a::protocol::{A2ATask, TaskStatus};
+use crate::a2a::remote::{AgentOwner, RemoteAgent};
 use crate::rate_limiting::JwtUsage;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
@@ -183,83 +184,376 @@ impl DatabaseProvider for Database {
         }
     }
 
-    async fn update_strava_token(
+    async fn list_user_ids(&self) -> Result<Vec<uuid::Uuid>> {
+        match self {
+            Database::SQLite(db) => db.list_user_ids().await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.list_user_ids().await,
+        }
+    }
+
+    async fn upsert_provider_token(
         &self,
         user_id: uuid::Uuid,
-        access_token: &str,
-        refresh_token: &str,
-        expires_at: chrono::DateTime<chrono::Utc>,
-        scope: String,
+        provider: &str,
+        token: &crate::models::DecryptedToken,
+        provider_user_id: Option<&str>,
     ) -> Result<()> {
         match self {
             Database::SQLite(db) => {
-                db.update_strava_token(user_id, access_token, refresh_token, expires_at, scope)
+                db.upsert_provider_token(user_id, provider, token, provider_user_id)
                     .await
             }
             #[cfg(feature = "postgresql")]
             Database::PostgreSQL(db) => {
-                db.update_strava_token(user_id, access_token, refresh_token, expires_at, scope)
+                db.upsert_provider_token(user_id, provider, token, provider_user_id)
                     .await
             }
         }
     }
 
-    async fn get_strava_token(
+    async fn get_provider_token(
         &self,
         user_id: uuid::Uuid,
+        provider: &str,
     ) -> Result<Option<crate::models::DecryptedToken>> {
         match self {
-            Database::SQLite(db) => db.get_strava_token(user_id).await,
+            Database::SQLite(db) => db.get_provider_token(user_id, provider).await,
             #[cfg(feature = "postgresql")]
-            Database::PostgreSQL(db) => db.get_strava_token(user_id).await,
+            Database::PostgreSQL(db) => db.get_provider_token(user_id, provider).await,
         }
     }
 
-    async fn update_fitbit_token(
+    async fn get_provider_connection(
         &self,
         user_id: uuid::Uuid,
-        access_token: &str,
-        refresh_token: &str,
-        expires_at: chrono::DateTime<chrono::Utc>,
-        scope: String,
-    ) -> Result<()> {
+        provider: &str,
+    ) -> Result<Option<crate::models::ProviderConnection>> {
+        match self {
+            Database::SQLite(db) => db.get_provider_connection(user_id, provider).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.get_provider_connection(user_id, provider).await,
+        }
+    }
+
+    async fn list_provider_connections(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<crate::models::ProviderConnection>> {
+        match self {
+            Database::SQLite(db) => db.list_provider_connections(user_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.list_provider_connections(user_id).await,
+        }
+    }
+
+    async fn delete_provider_connection(
+        &self,
+        user_id: uuid::Uuid,
+        provider: &str,
+    ) -> Result<bool> {
+        match self {
+            Database::SQLite(db) => db.delete_provider_connection(user_id, provider).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.delete_provider_connection(user_id, provider).await,
+        }
+    }
+
+    async fn get_provider_connection_by_provider_user_id(
+        &self,
+        provider: &str,
+        provider_user_id: &str,
+    ) -> Result<Option<crate::models::ProviderConnection>> {
         match self {
             Database::SQLite(db) => {
-                db.update_fitbit_token(user_id, access_token, refresh_token, expires_at, scope)
+                db.get_provider_connection_by_provider_user_id(provider, provider_user_id)
                     .await
             }
             #[cfg(feature = "postgresql")]
             Database::PostgreSQL(db) => {
-                db.update_fitbit_token(user_id, access_token, refresh_token, expires_at, scope)
+                db.get_provider_connection_by_provider_user_id(provider, provider_user_id)
                     .await
             }
         }
     }
 
-    async fn get_fitbit_token(
+    async fn mark_provider_connection_needs_reauth(
         &self,
         user_id: uuid::Uuid,
-    ) -> Result<Option<crate::models::DecryptedToken>> {
+        provider: &str,
+        reason: &str,
+    ) -> Result<bool> {
+        match self {
+            Database::SQLite(db) => {
+                db.mark_provider_connection_needs_reauth(user_id, provider, reason)
+                    .await
+            }
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => {
+                db.mark_provider_connection_needs_reauth(user_id, provider, reason)
+                    .await
+            }
+        }
+    }
+
+    async fn list_provider_connections_expiring_before(
+        &self,
+        cutoff: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<crate::models::ProviderConnection>> {
+        match self {
+            Database::SQLite(db) => db.list_provider_connections_expiring_before(cutoff).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.list_provider_connections_expiring_before(cutoff).await,
+        }
+    }
+
+    async fn create_manual_activity(
+        &self,
+        user_id: uuid::Uuid,
+        activity: &crate::models::Activity,
+    ) -> Result<()> {
+        match self {
+            Database::SQLite(db) => db.create_manual_activity(user_id, activity).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.create_manual_activity(user_id, activity).await,
+        }
+    }
+
+    async fn get_manual_activity(
+        &self,
+        user_id: uuid::Uuid,
+        activity_id: &str,
+    ) -> Result<Option<crate::models::Activity>> {
+        match self {
+            Database::SQLite(db) => db.get_manual_activity(user_id, activity_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.get_manual_activity(user_id, activity_id).await,
+        }
+    }
+
+    async fn list_manual_activities(
+        &self,
+        user_id: uuid::Uuid,
+        limit: Option<u32>,
+    ) -> Result<Vec<crate::models::Activity>> {
+        match self {
+            Database::SQLite(db) => db.list_manual_activities(user_id, limit).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.list_manual_activities(user_id, limit).await,
+        }
+    }
+
+    async fn update_manual_activity(
+        &self,
+        user_id: uuid::Uuid,
+        activity: &crate::models::Activity,
+    ) -> Result<bool> {
+        match self {
+            Database::SQLite(db) => db.update_manual_activity(user_id, activity).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.update_manual_activity(user_id, activity).await,
+        }
+    }
+
+    async fn delete_manual_activity(&self, user_id: uuid::Uuid, activity_id: &str) -> Result<bool> {
+        match self {
+            Database::SQLite(db) => db.delete_manual_activity(user_id, activity_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.delete_manual_activity(user_id, activity_id).await,
+        }
+    }
+
+    async fn upsert_gear(&self, user_id: uuid::Uuid, gear: &crate::models::Gear) -> Result<()> {
+        match self {
+            Database::SQLite(db) => db.upsert_gear(user_id, gear).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.upsert_gear(user_id, gear).await,
+        }
+    }
+
+    async fn get_gear(
+        &self,
+        user_id: uuid::Uuid,
+        gear_id: &str,
+    ) -> Result<Option<crate::models::Gear>> {
+        match self {
+            Database::SQLite(db) => db.get_gear(user_id, gear_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.get_gear(user_id, gear_id).await,
+        }
+    }
+
+    async fn list_gear(&self, user_id: uuid::Uuid) -> Result<Vec<crate::models::Gear>> {
+        match self {
+            Database::SQLite(db) => db.list_gear(user_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.list_gear(user_id).await,
+        }
+    }
+
+    async fn upsert_digest(
+        &self,
+        user_id: uuid::Uuid,
+        digest: &crate::models::StoredDigest,
+    ) -> Result<()> {
+        match self {
+            Database::SQLite(db) => db.upsert_digest(user_id, digest).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.upsert_digest(user_id, digest).await,
+        }
+    }
+
+    async fn get_digest(
+        &self,
+        user_id: uuid::Uuid,
+        period: &str,
+        period_start: chrono::NaiveDate,
+    ) -> Result<Option<crate::models::StoredDigest>> {
+        match self {
+            Database::SQLite(db) => db.get_digest(user_id, period, period_start).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.get_digest(user_id, period, period_start).await,
+        }
+    }
+
+    async fn list_digests(
+        &self,
+        user_id: uuid::Uuid,
+        period: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<crate::models::StoredDigest>> {
+        match self {
+            Database::SQLite(db) => db.list_digests(user_id, period, limit).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.list_digests(user_id, period, limit).await,
+        }
+    }
+
+    async fn get_analysis_settings(
+        &self,
+        scope: &str,
+        scope_id: &str,
+    ) -> Result<Option<serde_json::Value>> {
+        match self {
+            Database::SQLite(db) => db.get_analysis_settings(scope, scope_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.get_analysis_settings(scope, scope_id).await,
+        }
+    }
+
+    async fn upsert_analysis_settings(
+        &self,
+        scope: &str,
+        scope_id: &str,
+        settings: &serde_json::Value,
+    ) -> Result<()> {
+        match self {
+            Database::SQLite(db) => db.upsert_analysis_settings(scope, scope_id, settings).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => {
+                db.upsert_analysis_settings(scope, scope_id, settings).await
+            }
+        }
+    }
+
+    async fn get_user_tenant(&self, user_id: uuid::Uuid) -> Result<Option<String>> {
+        match self {
+            Database::SQLite(db) => db.get_user_tenant(user_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.get_user_tenant(user_id).await,
+        }
+    }
+
+    async fn set_user_tenant(&self, user_id: uuid::Uuid, tenant_id: Option<&str>) -> Result<()> {
+        match self {
+            Database::SQLite(db) => db.set_user_tenant(user_id, tenant_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.set_user_tenant(user_id, tenant_id).await,
+        }
+    }
+
+    async fn upsert_daily_wellness(
+        &self,
+        user_id: uuid::Uuid,
+        wellness: &crate::models::DailyWellness,
+    ) -> Result<()> {
+        match self {
+            Database::SQLite(db) => db.upsert_daily_wellness(user_id, wellness).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.upsert_daily_wellness(user_id, wellness).await,
+        }
+    }
+
+    async fn get_daily_wellness(
+        &self,
+        user_id: uuid::Uuid,
+        provider: &str,
+        start: chrono::NaiveDate,
+        end: chrono::NaiveDate,
+    ) -> Result<Vec<crate::models::DailyWellness>> {
         match self {
-            Database::SQLite(db) => db.get_fitbit_token(user_id).await,
+            Database::SQLite(db) => db.get_daily_wellness(user_id, provider, start, end).await,
             #[cfg(feature = "postgresql")]
-            Database::PostgreSQL(db) => db.get_fitbit_token(user_id).await,
+            Database::PostgreSQL(db) => db.get_daily_wellness(user_id, provider, start, end).await,
         }
     }
 
-    async fn clear_strava_token(&self, user_id: uuid::Uuid) -> Result<()> {
+    async fn upsert_synced_activity(
+        &self,
+        user_id: uuid::Uuid,
+        activity: &crate::models::Activity,
+    ) -> Result<()> {
         match self {
-            Database::SQLite(db) => db.clear_strava_token(user_id).await,
+            Database::SQLite(db) => db.upsert_synced_activity(user_id, activity).await,
             #[cfg(feature = "postgresql")]
-            Database::PostgreSQL(db) => db.clear_strava_token(user_id).await,
+            Database::PostgreSQL(db) => db.upsert_synced_activity(user_id, activity).await,
         }
     }
 
-    async fn clear_fitbit_token(&self, user_id: uuid::Uuid) -> Result<()> {
+    async fn get_synced_activity(
+        &self,
+        user_id: uuid::Uuid,
+        provider: &str,
+        activity_id: &str,
+    ) -> Result<Option<crate::models::SyncedActivity>> {
+        match self {
+            Database::SQLite(db) => db.get_synced_activity(user_id, provider, activity_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => {
+                db.get_synced_activity(user_id, provider, activity_id).await
+            }
+        }
+    }
+
+    async fn list_synced_activities(
+        &self,
+        user_id: uuid::Uuid,
+        provider: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<crate::models::Activity>> {
         match self {
-            Database::SQLite(db) => db.clear_fitbit_token(user_id).await,
+            Database::SQLite(db) => db.list_synced_activities(user_id, provider, limit).await,
             #[cfg(feature = "postgresql")]
-            Database::PostgreSQL(db) => db.clear_fitbit_token(user_id).await,
+            Database::PostgreSQL(db) => db.list_synced_activities(user_id, provider, limit).await,
+        }
+    }
+
+    async fn mark_synced_activity_deleted(
+        &self,
+        user_id: uuid::Uuid,
+        provider: &str,
+        activity_id: &str,
+    ) -> Result<bool> {
+        match self {
+            Database::SQLite(db) => {
+                db.mark_synced_activity_deleted(user_id, provider, activity_id)
+                    .await
+            }
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => {
+                db.mark_synced_activity_deleted(user_id, provider, activity_id)
+                    .await
+            }
         }
     }
 
@@ -667,6 +961,148 @@ impl DatabaseProvider for Database {
         }
     }
 
+    async fn list_all_a2a_clients(&self) -> Result<Vec<A2AClient>> {
+        match self {
+            Database::SQLite(db) => db.list_all_a2a_clients().await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.list_all_a2a_clients().await,
+        }
+    }
+
+    async fn get_a2a_client_by_api_key_id(&self, api_key_id: &str) -> Result<Option<A2AClient>> {
+        match self {
+            Database::SQLite(db) => db.get_a2a_client_by_api_key_id(api_key_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.get_a2a_client_by_api_key_id(api_key_id).await,
+        }
+    }
+
+    async fn set_a2a_client_active(&self, client_id: &str, is_active: bool) -> Result<bool> {
+        match self {
+            Database::SQLite(db) => db.set_a2a_client_active(client_id, is_active).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.set_a2a_client_active(client_id, is_active).await,
+        }
+    }
+
+    async fn rotate_a2a_client_credentials(
+        &self,
+        client_id: &str,
+        secret_hash: &str,
+        api_key_id: &str,
+        previous_valid_until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        match self {
+            Database::SQLite(db) => {
+                db.rotate_a2a_client_credentials(
+                    client_id,
+                    secret_hash,
+                    api_key_id,
+                    previous_valid_until,
+                )
+                .await
+            }
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => {
+                db.rotate_a2a_client_credentials(
+                    client_id,
+                    secret_hash,
+                    api_key_id,
+                    previous_valid_until,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn verify_a2a_client_secret(&self, client_id: &str, secret_hash: &str) -> Result<bool> {
+        match self {
+            Database::SQLite(db) => db.verify_a2a_client_secret(client_id, secret_hash).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.verify_a2a_client_secret(client_id, secret_hash).await,
+        }
+    }
+
+    async fn get_a2a_client_quota(&self, client_id: &str) -> Result<Option<A2AClientQuota>> {
+        match self {
+            Database::SQLite(db) => db.get_a2a_client_quota(client_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.get_a2a_client_quota(client_id).await,
+        }
+    }
+
+    async fn update_a2a_client_quota(
+        &self,
+        client_id: &str,
+        quota: &A2AClientQuota,
+    ) -> Result<bool> {
+        match self {
+            Database::SQLite(db) => db.update_a2a_client_quota(client_id, quota).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.update_a2a_client_quota(client_id, quota).await,
+        }
+    }
+
+    async fn list_a2a_sessions(&self, client_id: &str) -> Result<Vec<A2ASession>> {
+        match self {
+            Database::SQLite(db) => db.list_a2a_sessions(client_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.list_a2a_sessions(client_id).await,
+        }
+    }
+
+    async fn revoke_a2a_session(&self, client_id: &str, session_id: &str) -> Result<bool> {
+        match self {
+            Database::SQLite(db) => db.revoke_a2a_session(client_id, session_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.revoke_a2a_session(client_id, session_id).await,
+        }
+    }
+
+    async fn cleanup_expired_a2a_sessions(&self) -> Result<u64> {
+        match self {
+            Database::SQLite(db) => db.cleanup_expired_a2a_sessions().await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.cleanup_expired_a2a_sessions().await,
+        }
+    }
+
+    async fn upsert_remote_agent(&self, agent: &RemoteAgent) -> Result<()> {
+        match self {
+            Database::SQLite(db) => db.upsert_remote_agent(agent).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.upsert_remote_agent(agent).await,
+        }
+    }
+
+    async fn get_remote_agent(
+        &self,
+        owner: &AgentOwner,
+        agent_id: &str,
+    ) -> Result<Option<RemoteAgent>> {
+        match self {
+            Database::SQLite(db) => db.get_remote_agent(owner, agent_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.get_remote_agent(owner, agent_id).await,
+        }
+    }
+
+    async fn list_remote_agents(&self, owner: &AgentOwner) -> Result<Vec<RemoteAgent>> {
+        match self {
+            Database::SQLite(db) => db.list_remote_agents(owner).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.list_remote_agents(owner).await,
+        }
+    }
+
+    async fn delete_remote_agent(&self, owner: &AgentOwner, agent_id: &str) -> Result<bool> {
+        match self {
+            Database::SQLite(db) => db.delete_remote_agent(owner, agent_id).await,
+            #[cfg(feature = "postgresql")]
+            Database::PostgreSQL(db) => db.delete_remote_agent(owner, agent_id).await,
+        }
+    }
+
     async fn get_top_tools_analysis(
         &self,
         user_id: uuid::Uuid,
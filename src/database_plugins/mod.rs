@@ -4,14 +4,18 @@
 //! allowing easy switching between SQLite (local) and PostgreSQL (cloud).
 
 use crate::a2a::auth::A2AClient;
-use crate::a2a::client::A2ASession;
+use crate::a2a::client::{A2AClientQuota, A2ASession};
 use crate::a2a::protocol::{A2ATask, TaskStatus};
+use crate::a2a::remote::{AgentOwner, RemoteAgent};
 use crate::api_keys::{ApiKey, ApiKeyUsage, ApiKeyUsageStats};
-use crate::models::{DecryptedToken, User};
+use crate::models::{
+    Activity, DailyWellness, DecryptedToken, Gear, ProviderConnection, StoredDigest,
+    SyncedActivity, User,
+};
 use crate::rate_limiting::JwtUsage;
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde_json::Value;
 use uuid::Uuid;
 
@@ -60,41 +64,198 @@ pub trait DatabaseProvider: Send + Sync + Clone {
     /// Get total number of users
     async fn get_user_count(&self) -> Result<i64>;
 
+    /// List the ids of every active user, oldest account first
+    async fn list_user_ids(&self) -> Result<Vec<Uuid>>;
+
     // ================================
     // OAuth Token Management
     // ================================
 
-    /// Update Strava OAuth tokens for a user
-    async fn update_strava_token(
+    /// Store or refresh a user's OAuth tokens for a provider
+    ///
+    /// Tokens are encrypted at rest. A `None` provider user id keeps the
+    /// previously stored value, so token refreshes don't need to know it.
+    async fn upsert_provider_token(
         &self,
         user_id: Uuid,
-        access_token: &str,
-        refresh_token: &str,
-        expires_at: DateTime<Utc>,
-        scope: String,
+        provider: &str,
+        token: &DecryptedToken,
+        provider_user_id: Option<&str>,
     ) -> Result<()>;
 
-    /// Get Strava tokens for a user
-    async fn get_strava_token(&self, user_id: Uuid) -> Result<Option<DecryptedToken>>;
+    /// Get a user's decrypted OAuth tokens for a provider
+    async fn get_provider_token(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+    ) -> Result<Option<DecryptedToken>>;
+
+    /// Get a user's connection metadata for a provider
+    async fn get_provider_connection(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+    ) -> Result<Option<ProviderConnection>>;
+
+    /// List all provider connections for a user
+    async fn list_provider_connections(&self, user_id: Uuid) -> Result<Vec<ProviderConnection>>;
+
+    /// Remove a user's connection to a provider, returning whether one existed
+    async fn delete_provider_connection(&self, user_id: Uuid, provider: &str) -> Result<bool>;
+
+    /// Find the connection belonging to a provider-side user id (e.g. Strava athlete id)
+    async fn get_provider_connection_by_provider_user_id(
+        &self,
+        provider: &str,
+        provider_user_id: &str,
+    ) -> Result<Option<ProviderConnection>>;
+
+    /// Flag a connection whose refresh token the provider rejected
+    ///
+    /// The flag is cleared the next time tokens are stored for the connection.
+    async fn mark_provider_connection_needs_reauth(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        reason: &str,
+    ) -> Result<bool>;
+
+    /// List usable connections (across all users) whose access token expires before `cutoff`
+    async fn list_provider_connections_expiring_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<ProviderConnection>>;
+
+    // ================================
+    // Activity Sync
+    // ================================
+
+    /// Store or refresh a synced activity, clearing any tombstone
+    async fn upsert_synced_activity(&self, user_id: Uuid, activity: &Activity) -> Result<()>;
 
-    /// Update Fitbit OAuth tokens for a user
-    async fn update_fitbit_token(
+    /// Get a synced activity, including tombstoned ones
+    async fn get_synced_activity(
         &self,
         user_id: Uuid,
-        access_token: &str,
-        refresh_token: &str,
-        expires_at: DateTime<Utc>,
-        scope: String,
+        provider: &str,
+        activity_id: &str,
+    ) -> Result<Option<SyncedActivity>>;
+
+    /// List a user's live (non-deleted) synced activities, newest first
+    async fn list_synced_activities(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Activity>>;
+
+    /// Tombstone a synced activity, returning whether it was stored
+    async fn mark_synced_activity_deleted(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        activity_id: &str,
+    ) -> Result<bool>;
+
+    // ================================
+    // Manual Activities
+    // ================================
+
+    /// Store a user-entered activity
+    async fn create_manual_activity(&self, user_id: Uuid, activity: &Activity) -> Result<()>;
+
+    /// Get one of a user's manual activities
+    async fn get_manual_activity(
+        &self,
+        user_id: Uuid,
+        activity_id: &str,
+    ) -> Result<Option<Activity>>;
+
+    /// List a user's manual activities, newest first
+    async fn list_manual_activities(
+        &self,
+        user_id: Uuid,
+        limit: Option<u32>,
+    ) -> Result<Vec<Activity>>;
+
+    /// Replace a manual activity, returning whether it existed
+    async fn update_manual_activity(&self, user_id: Uuid, activity: &Activity) -> Result<bool>;
+
+    /// Delete a manual activity, returning whether it existed
+    async fn delete_manual_activity(&self, user_id: Uuid, activity_id: &str) -> Result<bool>;
+
+    // ================================
+    // Gear
+    // ================================
+
+    /// Insert or replace one of a user's shoes, bikes or other gear
+    async fn upsert_gear(&self, user_id: Uuid, gear: &Gear) -> Result<()>;
+
+    /// Get one piece of a user's gear
+    async fn get_gear(&self, user_id: Uuid, gear_id: &str) -> Result<Option<Gear>>;
+
+    /// List a user's gear, ordered by name
+    async fn list_gear(&self, user_id: Uuid) -> Result<Vec<Gear>>;
+
+    // ================================
+    // Training Digests
+    // ================================
+
+    /// Store or replace a user's digest of one period
+    async fn upsert_digest(&self, user_id: Uuid, digest: &StoredDigest) -> Result<()>;
+
+    /// Get a user's digest of the period starting on `period_start`
+    async fn get_digest(
+        &self,
+        user_id: Uuid,
+        period: &str,
+        period_start: NaiveDate,
+    ) -> Result<Option<StoredDigest>>;
+
+    /// List a user's digests, optionally of one period, latest period first
+    async fn list_digests(
+        &self,
+        user_id: Uuid,
+        period: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<StoredDigest>>;
+
+    // ================================
+    // Analysis Settings
+    // ================================
+
+    /// Get the intelligence config overrides stored for a `tenant` or `user` scope
+    async fn get_analysis_settings(&self, scope: &str, scope_id: &str) -> Result<Option<Value>>;
+
+    /// Store or replace the intelligence config overrides for a scope
+    async fn upsert_analysis_settings(
+        &self,
+        scope: &str,
+        scope_id: &str,
+        settings: &Value,
     ) -> Result<()>;
 
-    /// Get Fitbit tokens for a user
-    async fn get_fitbit_token(&self, user_id: Uuid) -> Result<Option<DecryptedToken>>;
+    /// Get the tenant a user belongs to
+    async fn get_user_tenant(&self, user_id: Uuid) -> Result<Option<String>>;
+
+    /// Assign a user to a tenant, or remove them from theirs with `None`
+    async fn set_user_tenant(&self, user_id: Uuid, tenant_id: Option<&str>) -> Result<()>;
 
-    /// Clear Strava tokens for a user
-    async fn clear_strava_token(&self, user_id: Uuid) -> Result<()>;
+    // ================================
+    // Wellness Data
+    // ================================
 
-    /// Clear Fitbit tokens for a user
-    async fn clear_fitbit_token(&self, user_id: Uuid) -> Result<()>;
+    /// Store or refresh one day of a provider's wellness data
+    async fn upsert_daily_wellness(&self, user_id: Uuid, wellness: &DailyWellness) -> Result<()>;
+
+    /// Get a user's stored wellness days for a provider in `start..=end`, oldest first
+    async fn get_daily_wellness(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyWellness>>;
 
     // ================================
     // User Profiles & Goals
@@ -281,6 +442,70 @@ pub trait DatabaseProvider: Send + Sync + Clone {
         days: u32,
     ) -> Result<Vec<(DateTime<Utc>, u32, u32)>>;
 
+    // ================================
+    // A2A Client Lifecycle
+    // ================================
+
+    /// List every registered A2A client
+    async fn list_all_a2a_clients(&self) -> Result<Vec<A2AClient>>;
+
+    /// Get the A2A client that owns an API key, including rotated-out keys
+    async fn get_a2a_client_by_api_key_id(&self, api_key_id: &str) -> Result<Option<A2AClient>>;
+
+    /// Activate or deactivate a client together with its API keys and sessions
+    async fn set_a2a_client_active(&self, client_id: &str, is_active: bool) -> Result<bool>;
+
+    /// Issue new client credentials, keeping the current ones valid until `previous_valid_until`
+    async fn rotate_a2a_client_credentials(
+        &self,
+        client_id: &str,
+        secret_hash: &str,
+        api_key_id: &str,
+        previous_valid_until: DateTime<Utc>,
+    ) -> Result<()>;
+
+    /// Check a client secret hash against the client's unexpired credentials
+    async fn verify_a2a_client_secret(&self, client_id: &str, secret_hash: &str) -> Result<bool>;
+
+    /// Get the tier and custom monthly limit of a client
+    async fn get_a2a_client_quota(&self, client_id: &str) -> Result<Option<A2AClientQuota>>;
+
+    /// Update the tier and custom monthly limit of a client
+    async fn update_a2a_client_quota(
+        &self,
+        client_id: &str,
+        quota: &A2AClientQuota,
+    ) -> Result<bool>;
+
+    /// List the active, unexpired sessions of a client
+    async fn list_a2a_sessions(&self, client_id: &str) -> Result<Vec<A2ASession>>;
+
+    /// Revoke a single session of a client
+    async fn revoke_a2a_session(&self, client_id: &str, session_id: &str) -> Result<bool>;
+
+    /// Delete expired and revoked sessions, returning how many were removed
+    async fn cleanup_expired_a2a_sessions(&self) -> Result<u64>;
+
+    // ================================
+    // A2A Remote Agents
+    // ================================
+
+    /// Create or refresh a trusted remote agent
+    async fn upsert_remote_agent(&self, agent: &RemoteAgent) -> Result<()>;
+
+    /// Get a remote agent from a tenant's or user's registry
+    async fn get_remote_agent(
+        &self,
+        owner: &AgentOwner,
+        agent_id: &str,
+    ) -> Result<Option<RemoteAgent>>;
+
+    /// List the remote agents in a tenant's or user's registry
+    async fn list_remote_agents(&self, owner: &AgentOwner) -> Result<Vec<RemoteAgent>>;
+
+    /// Remove a remote agent from a registry, returning whether it existed
+    async fn delete_remote_agent(&self, owner: &AgentOwner, agent_id: &str) -> Result<bool>;
+
     // ================================
     // Analytics & Intelligence
     // ================================
@@ -5,15 +5,19 @@
 
 use super::DatabaseProvider;
 use crate::a2a::auth::A2AClient;
-use crate::a2a::client::A2ASession;
+use crate::a2a::client::{A2AClientQuota, A2AClientTier, A2ASession};
 use crate::a2a::protocol::{A2ATask, TaskStatus};
+use crate::a2a::remote::{AgentOwner, RemoteAgent};
 use crate::api_keys::{ApiKey, ApiKeyUsage, ApiKeyUsageStats};
 use crate::database::{A2AUsage, A2AUsageStats};
-use crate::models::{DecryptedToken, EncryptedToken, User, UserTier};
+use crate::models::{
+    Activity, DailyWellness, DecryptedToken, EncryptedToken, Gear, ProviderConnection,
+    StoredDigest, SyncedActivity, User, UserTier,
+};
 use crate::rate_limiting::JwtUsage;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde_json::Value;
 use sqlx::{PgPool, Pool, Postgres, Row};
 use uuid::Uuid;
@@ -43,6 +47,48 @@ impl PostgresDatabase {
         // Use the decrypt method from EncryptedToken
         encrypted.decrypt(&self.encryption_key)
     }
+
+    /// Convert a database row to ProviderConnection
+    fn row_to_provider_connection(row: &sqlx::postgres::PgRow) -> ProviderConnection {
+        ProviderConnection {
+            user_id: row.get("user_id"),
+            provider: row.get("provider"),
+            provider_user_id: row.get("provider_user_id"),
+            scope: row.get("scope"),
+            expires_at: row.get("expires_at"),
+            connected_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            needs_reauth: row.get("needs_reauth"),
+            reauth_reason: row.get("reauth_reason"),
+        }
+    }
+
+    /// Convert a database row to RemoteAgent, decrypting its credential
+    fn row_to_remote_agent(&self, row: &sqlx::postgres::PgRow) -> Result<RemoteAgent> {
+        let auth_token = match row.get::<Option<String>, _>("auth_token") {
+            Some(encrypted) => Some(
+                self.decrypt_token(&serde_json::from_str::<EncryptedToken>(&encrypted)?)?
+                    .access_token,
+            ),
+            None => None,
+        };
+
+        Ok(RemoteAgent {
+            id: row.get("id"),
+            owner: AgentOwner::from_columns(row.get("tenant_id"), row.get("user_id"))?,
+            name: row.get("name"),
+            description: row
+                .get::<Option<String>, _>("description")
+                .unwrap_or_default(),
+            base_url: row.get("base_url"),
+            endpoint_url: row.get("endpoint_url"),
+            capabilities: row.get("capabilities"),
+            auth_token,
+            is_active: row.get("is_active"),
+            created_at: row.get("created_at"),
+            last_verified_at: row.get("last_verified_at"),
+        })
+    }
 }
 
 #[async_trait]
@@ -70,16 +116,6 @@ impl DatabaseProvider for PostgresDatabase {
                 email TEXT UNIQUE NOT NULL,
                 display_name TEXT,
                 password_hash TEXT NOT NULL,
-                strava_access_token TEXT,
-                strava_refresh_token TEXT,
-                strava_expires_at TIMESTAMPTZ,
-                strava_scope TEXT,
-                strava_nonce TEXT,
-                fitbit_access_token TEXT,
-                fitbit_refresh_token TEXT,
-                fitbit_expires_at TIMESTAMPTZ,
-                fitbit_scope TEXT,
-                fitbit_nonce TEXT,
                 created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
                 last_active TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
             )
@@ -88,6 +124,150 @@ impl DatabaseProvider for PostgresDatabase {
         .execute(&self.pool)
         .await?;
 
+        // Create provider_connections table holding encrypted OAuth tokens per provider
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS provider_connections (
+                user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                provider TEXT NOT NULL,
+                provider_user_id TEXT,
+                access_token TEXT NOT NULL,
+                refresh_token TEXT NOT NULL,
+                nonce TEXT NOT NULL,
+                scope TEXT NOT NULL,
+                expires_at TIMESTAMPTZ NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                needs_reauth BOOLEAN NOT NULL DEFAULT false,
+                reauth_reason TEXT,
+                PRIMARY KEY (user_id, provider)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.migrate_legacy_provider_tokens().await?;
+
+        // Create synced_activities table for activities pushed by provider webhooks
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS synced_activities (
+                user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                provider TEXT NOT NULL,
+                activity_id TEXT NOT NULL,
+                activity_data JSONB NOT NULL,
+                start_date TIMESTAMPTZ NOT NULL,
+                synced_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                deleted_at TIMESTAMPTZ,
+                PRIMARY KEY (user_id, provider, activity_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_synced_activities_start ON synced_activities(user_id, provider, start_date)")
+            .execute(&self.pool)
+            .await?;
+
+        // Create manual_activities table for user-entered workouts
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS manual_activities (
+                id TEXT PRIMARY KEY,
+                user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                activity_data JSONB NOT NULL,
+                start_date TIMESTAMPTZ NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_manual_activities_start ON manual_activities(user_id, start_date)")
+            .execute(&self.pool)
+            .await?;
+
+        // Create gear table for provider-synced and user-added shoes and bikes
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS gear (
+                user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                gear_id TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                name TEXT NOT NULL,
+                gear_data JSONB NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (user_id, gear_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create digests table for weekly, monthly and yearly training digests
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS digests (
+                user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                period TEXT NOT NULL CHECK (period IN ('weekly', 'monthly', 'yearly')),
+                period_start DATE NOT NULL,
+                digest_data JSONB NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (user_id, period, period_start)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create analysis_settings table for tenant and user intelligence overrides
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS analysis_settings (
+                scope TEXT NOT NULL CHECK (scope IN ('tenant', 'user')),
+                scope_id TEXT NOT NULL,
+                settings JSONB NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (scope, scope_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create tenant_members table mapping users to their club or team
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS tenant_members (
+                user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+                tenant_id TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create daily_wellness table for provider sleep/heart/step summaries
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS daily_wellness (
+                user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                provider TEXT NOT NULL,
+                date DATE NOT NULL,
+                wellness_data JSONB NOT NULL,
+                synced_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (user_id, provider, date)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Create user_profiles table
         sqlx::query(
             r#"
@@ -192,8 +372,11 @@ impl DatabaseProvider for PostgresDatabase {
                 redirect_uris TEXT[] NOT NULL DEFAULT '{}',
                 contact_email TEXT,
                 is_active BOOLEAN NOT NULL DEFAULT true,
+                deactivated_at TIMESTAMPTZ,
                 rate_limit_per_minute INTEGER NOT NULL DEFAULT 100,
                 rate_limit_per_day INTEGER DEFAULT 10000,
+                tier TEXT NOT NULL DEFAULT 'trial' CHECK (tier IN ('trial', 'standard', 'professional', 'enterprise')),
+                monthly_limit INTEGER,
                 created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
                 updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
             )
@@ -202,6 +385,20 @@ impl DatabaseProvider for PostgresDatabase {
         .execute(&self.pool)
         .await?;
 
+        // Status and quota columns for tables created before client lifecycle management
+        for column in [
+            "deactivated_at TIMESTAMPTZ",
+            "tier TEXT NOT NULL DEFAULT 'trial' CHECK (tier IN ('trial', 'standard', 'professional', 'enterprise'))",
+            "monthly_limit INTEGER",
+        ] {
+            sqlx::query(&format!(
+                "ALTER TABLE a2a_clients ADD COLUMN IF NOT EXISTS {}",
+                column
+            ))
+            .execute(&self.pool)
+            .await?;
+        }
+
         sqlx::query(
             r#"
             CREATE TABLE IF NOT EXISTS a2a_sessions (
@@ -260,6 +457,30 @@ impl DatabaseProvider for PostgresDatabase {
         .execute(&self.pool)
         .await?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS a2a_remote_agents (
+                id TEXT PRIMARY KEY,
+                tenant_id TEXT,
+                user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+                name TEXT NOT NULL,
+                description TEXT,
+                base_url TEXT NOT NULL,
+                endpoint_url TEXT NOT NULL,
+                capabilities TEXT[] NOT NULL DEFAULT '{}',
+                auth_token TEXT,
+                is_active BOOLEAN NOT NULL DEFAULT true,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                last_verified_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                CHECK ((tenant_id IS NULL) <> (user_id IS NULL)),
+                UNIQUE(tenant_id, base_url),
+                UNIQUE(user_id, base_url)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Create indexes for better performance
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_users_email ON users(email)")
             .execute(&self.pool)
@@ -293,6 +514,18 @@ impl DatabaseProvider for PostgresDatabase {
             .execute(&self.pool)
             .await?;
 
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_a2a_remote_agents_tenant_id ON a2a_remote_agents(tenant_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_a2a_remote_agents_user_id ON a2a_remote_agents(user_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
         // Create admin tokens tables
         sqlx::query(
             r#"
@@ -435,8 +668,6 @@ impl DatabaseProvider for PostgresDatabase {
                         _ => UserTier::Starter,
                     }
                 },
-                strava_token: None, // Tokens are loaded separately
-                fitbit_token: None, // Tokens are loaded separately
                 created_at: row.get("created_at"),
                 last_active: row.get("last_active"),
                 is_active: true, // Default to active
@@ -474,8 +705,6 @@ impl DatabaseProvider for PostgresDatabase {
                         _ => UserTier::Starter,
                     }
                 },
-                strava_token: None, // Tokens are loaded separately
-                fitbit_token: None, // Tokens are loaded separately
                 created_at: row.get("created_at"),
                 last_active: row.get("last_active"),
                 is_active: true, // Default to active
@@ -485,211 +714,631 @@ impl DatabaseProvider for PostgresDatabase {
         }
     }
 
-    async fn get_user_by_email_required(&self, email: &str) -> Result<User> {
-        self.get_user_by_email(email)
-            .await?
-            .ok_or_else(|| anyhow!("User with email {} not found", email))
+    async fn get_user_by_email_required(&self, email: &str) -> Result<User> {
+        self.get_user_by_email(email)
+            .await?
+            .ok_or_else(|| anyhow!("User with email {} not found", email))
+    }
+
+    async fn update_last_active(&self, user_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET last_active = CURRENT_TIMESTAMP
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_user_count(&self) -> Result<i64> {
+        let row = sqlx::query("SELECT COUNT(*) as count FROM users")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.get("count"))
+    }
+
+    async fn list_user_ids(&self) -> Result<Vec<Uuid>> {
+        let rows = sqlx::query("SELECT id FROM users ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| row.get("id")).collect())
+    }
+
+    async fn upsert_provider_token(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        token: &DecryptedToken,
+        provider_user_id: Option<&str>,
+    ) -> Result<()> {
+        let encrypted = self.encrypt_token(token)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO provider_connections (user_id, provider, provider_user_id, access_token,
+                refresh_token, nonce, scope, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (user_id, provider) DO UPDATE SET
+                provider_user_id = COALESCE(EXCLUDED.provider_user_id, provider_connections.provider_user_id),
+                access_token = EXCLUDED.access_token,
+                refresh_token = EXCLUDED.refresh_token,
+                nonce = EXCLUDED.nonce,
+                scope = EXCLUDED.scope,
+                expires_at = EXCLUDED.expires_at,
+                updated_at = CURRENT_TIMESTAMP,
+                needs_reauth = false,
+                reauth_reason = NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(provider)
+        .bind(provider_user_id)
+        .bind(&encrypted.access_token)
+        .bind(&encrypted.refresh_token)
+        .bind(&encrypted.nonce)
+        .bind(&token.scope)
+        .bind(token.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        self.update_last_active(user_id).await
+    }
+
+    async fn get_provider_token(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+    ) -> Result<Option<DecryptedToken>> {
+        let row = sqlx::query(
+            r#"
+            SELECT access_token, refresh_token, nonce, scope, expires_at
+            FROM provider_connections
+            WHERE user_id = $1 AND provider = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(provider)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| {
+            self.decrypt_token(&EncryptedToken {
+                access_token: row.get("access_token"),
+                refresh_token: row.get("refresh_token"),
+                expires_at: row.get("expires_at"),
+                scope: row.get("scope"),
+                nonce: row.get("nonce"),
+            })
+        })
+        .transpose()
+    }
+
+    async fn get_provider_connection(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+    ) -> Result<Option<ProviderConnection>> {
+        let row = sqlx::query(
+            r#"
+            SELECT user_id, provider, provider_user_id, scope, expires_at, created_at, updated_at,
+                needs_reauth, reauth_reason
+            FROM provider_connections
+            WHERE user_id = $1 AND provider = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(provider)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(Self::row_to_provider_connection))
+    }
+
+    async fn list_provider_connections(&self, user_id: Uuid) -> Result<Vec<ProviderConnection>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, provider, provider_user_id, scope, expires_at, created_at, updated_at,
+                needs_reauth, reauth_reason
+            FROM provider_connections
+            WHERE user_id = $1
+            ORDER BY provider
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::row_to_provider_connection).collect())
+    }
+
+    async fn delete_provider_connection(&self, user_id: Uuid, provider: &str) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM provider_connections WHERE user_id = $1 AND provider = $2")
+                .bind(user_id)
+                .bind(provider)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_provider_connection_by_provider_user_id(
+        &self,
+        provider: &str,
+        provider_user_id: &str,
+    ) -> Result<Option<ProviderConnection>> {
+        let row = sqlx::query(
+            r#"
+            SELECT user_id, provider, provider_user_id, scope, expires_at, created_at, updated_at,
+                needs_reauth, reauth_reason
+            FROM provider_connections
+            WHERE provider = $1 AND provider_user_id = $2
+            "#,
+        )
+        .bind(provider)
+        .bind(provider_user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(Self::row_to_provider_connection))
+    }
+
+    async fn mark_provider_connection_needs_reauth(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        reason: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE provider_connections
+            SET needs_reauth = true, reauth_reason = $3, updated_at = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND provider = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(provider)
+        .bind(reason)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_provider_connections_expiring_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<ProviderConnection>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, provider, provider_user_id, scope, expires_at, created_at, updated_at,
+                needs_reauth, reauth_reason
+            FROM provider_connections
+            WHERE needs_reauth = false AND expires_at < $1
+            ORDER BY expires_at
+            "#,
+        )
+        .bind(cutoff)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::row_to_provider_connection).collect())
+    }
+
+    async fn create_manual_activity(&self, user_id: Uuid, activity: &Activity) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO manual_activities (id, user_id, activity_data, start_date)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(&activity.id)
+        .bind(user_id)
+        .bind(serde_json::to_value(activity)?)
+        .bind(activity.start_date)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_manual_activity(
+        &self,
+        user_id: Uuid,
+        activity_id: &str,
+    ) -> Result<Option<Activity>> {
+        let row = sqlx::query(
+            "SELECT activity_data FROM manual_activities WHERE id = $1 AND user_id = $2",
+        )
+        .bind(activity_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| Ok(serde_json::from_value(row.get("activity_data"))?))
+            .transpose()
+    }
+
+    async fn list_manual_activities(
+        &self,
+        user_id: Uuid,
+        limit: Option<u32>,
+    ) -> Result<Vec<Activity>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT activity_data FROM manual_activities
+            WHERE user_id = $1
+            ORDER BY start_date DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit.map(i64::from))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.get("activity_data"))?))
+            .collect()
+    }
+
+    async fn update_manual_activity(&self, user_id: Uuid, activity: &Activity) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE manual_activities
+            SET activity_data = $1, start_date = $2, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $3 AND user_id = $4
+            "#,
+        )
+        .bind(serde_json::to_value(activity)?)
+        .bind(activity.start_date)
+        .bind(&activity.id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_manual_activity(&self, user_id: Uuid, activity_id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM manual_activities WHERE id = $1 AND user_id = $2")
+            .bind(activity_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn upsert_gear(&self, user_id: Uuid, gear: &Gear) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO gear (user_id, gear_id, provider, name, gear_data)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, gear_id) DO UPDATE SET
+                provider = EXCLUDED.provider,
+                name = EXCLUDED.name,
+                gear_data = EXCLUDED.gear_data,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(user_id)
+        .bind(&gear.id)
+        .bind(&gear.provider)
+        .bind(&gear.name)
+        .bind(serde_json::to_value(gear)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_gear(&self, user_id: Uuid, gear_id: &str) -> Result<Option<Gear>> {
+        let row = sqlx::query("SELECT gear_data FROM gear WHERE user_id = $1 AND gear_id = $2")
+            .bind(user_id)
+            .bind(gear_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(|row| Ok(serde_json::from_value(row.get("gear_data"))?))
+            .transpose()
+    }
+
+    async fn list_gear(&self, user_id: Uuid) -> Result<Vec<Gear>> {
+        let rows = sqlx::query("SELECT gear_data FROM gear WHERE user_id = $1 ORDER BY name")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.get("gear_data"))?))
+            .collect()
+    }
+
+    async fn upsert_digest(&self, user_id: Uuid, digest: &StoredDigest) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO digests (user_id, period, period_start, digest_data)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, period, period_start) DO UPDATE SET
+                digest_data = EXCLUDED.digest_data,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(user_id)
+        .bind(&digest.period)
+        .bind(digest.period_start)
+        .bind(serde_json::to_value(digest)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_digest(
+        &self,
+        user_id: Uuid,
+        period: &str,
+        period_start: NaiveDate,
+    ) -> Result<Option<StoredDigest>> {
+        let row = sqlx::query(
+            "SELECT digest_data FROM digests WHERE user_id = $1 AND period = $2 AND period_start = $3",
+        )
+        .bind(user_id)
+        .bind(period)
+        .bind(period_start)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| Ok(serde_json::from_value(row.get("digest_data"))?))
+            .transpose()
     }
 
-    async fn update_last_active(&self, user_id: Uuid) -> Result<()> {
-        sqlx::query(
+    async fn list_digests(
+        &self,
+        user_id: Uuid,
+        period: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<StoredDigest>> {
+        let rows = sqlx::query(
             r#"
-            UPDATE users
-            SET last_active = CURRENT_TIMESTAMP
-            WHERE id = $1
+            SELECT digest_data FROM digests
+            WHERE user_id = $1 AND ($2::TEXT IS NULL OR period = $2)
+            ORDER BY period_start DESC, period ASC
+            LIMIT $3
             "#,
         )
         .bind(user_id)
-        .execute(&self.pool)
+        .bind(period)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(())
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.get("digest_data"))?))
+            .collect()
     }
 
-    async fn get_user_count(&self) -> Result<i64> {
-        let row = sqlx::query("SELECT COUNT(*) as count FROM users")
-            .fetch_one(&self.pool)
-            .await?;
+    async fn get_analysis_settings(&self, scope: &str, scope_id: &str) -> Result<Option<Value>> {
+        let row = sqlx::query(
+            "SELECT settings FROM analysis_settings WHERE scope = $1 AND scope_id = $2",
+        )
+        .bind(scope)
+        .bind(scope_id)
+        .fetch_optional(&self.pool)
+        .await?;
 
-        Ok(row.get("count"))
+        Ok(row.map(|row| row.get("settings")))
     }
 
-    async fn update_strava_token(
+    async fn upsert_analysis_settings(
         &self,
-        user_id: Uuid,
-        access_token: &str,
-        refresh_token: &str,
-        expires_at: DateTime<Utc>,
-        scope: String,
+        scope: &str,
+        scope_id: &str,
+        settings: &Value,
     ) -> Result<()> {
-        let token = DecryptedToken {
-            access_token: access_token.to_string(),
-            refresh_token: refresh_token.to_string(),
-            expires_at,
-            scope,
-        };
-        let encrypted = self.encrypt_token(&token)?;
-
         sqlx::query(
             r#"
-            UPDATE users
-            SET strava_access_token = $1,
-                strava_refresh_token = $2,
-                strava_expires_at = $3,
-                strava_scope = $4,
-                strava_nonce = $5
-            WHERE id = $6
+            INSERT INTO analysis_settings (scope, scope_id, settings)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (scope, scope_id) DO UPDATE SET
+                settings = EXCLUDED.settings,
+                updated_at = CURRENT_TIMESTAMP
             "#,
         )
-        .bind(&encrypted.access_token)
-        .bind(&encrypted.refresh_token)
-        .bind(expires_at)
-        .bind(&token.scope)
-        .bind(&encrypted.nonce)
-        .bind(user_id)
+        .bind(scope)
+        .bind(scope_id)
+        .bind(settings)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    async fn get_strava_token(&self, user_id: Uuid) -> Result<Option<DecryptedToken>> {
-        let row = sqlx::query(
+    async fn get_user_tenant(&self, user_id: Uuid) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT tenant_id FROM tenant_members WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get("tenant_id")))
+    }
+
+    async fn set_user_tenant(&self, user_id: Uuid, tenant_id: Option<&str>) -> Result<()> {
+        match tenant_id {
+            Some(tenant_id) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO tenant_members (user_id, tenant_id)
+                    VALUES ($1, $2)
+                    ON CONFLICT (user_id) DO UPDATE SET tenant_id = EXCLUDED.tenant_id
+                    "#,
+                )
+                .bind(user_id)
+                .bind(tenant_id)
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM tenant_members WHERE user_id = $1")
+                    .bind(user_id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn upsert_daily_wellness(&self, user_id: Uuid, wellness: &DailyWellness) -> Result<()> {
+        sqlx::query(
             r#"
-            SELECT strava_access_token, strava_refresh_token, strava_expires_at, strava_scope, strava_nonce
-            FROM users
-            WHERE id = $1 AND strava_access_token IS NOT NULL
+            INSERT INTO daily_wellness (user_id, provider, date, wellness_data)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, provider, date) DO UPDATE SET
+                wellness_data = EXCLUDED.wellness_data,
+                synced_at = CURRENT_TIMESTAMP
             "#,
         )
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .bind(&wellness.provider)
+        .bind(wellness.date)
+        .bind(serde_json::to_value(wellness)?)
+        .execute(&self.pool)
         .await?;
 
-        if let Some(row) = row {
-            let encrypted = EncryptedToken {
-                access_token: row.get("strava_access_token"),
-                refresh_token: row.get("strava_refresh_token"),
-                expires_at: row.get("strava_expires_at"),
-                scope: row.get("strava_scope"),
-                nonce: row.get("strava_nonce"),
-            };
-
-            let mut decrypted = self.decrypt_token(&encrypted)?;
-            decrypted.expires_at = row.get("strava_expires_at");
-            decrypted.scope = row.get("strava_scope");
-
-            Ok(Some(decrypted))
-        } else {
-            Ok(None)
-        }
+        Ok(())
     }
 
-    async fn update_fitbit_token(
+    async fn get_daily_wellness(
         &self,
         user_id: Uuid,
-        access_token: &str,
-        refresh_token: &str,
-        expires_at: DateTime<Utc>,
-        scope: String,
-    ) -> Result<()> {
-        let token = DecryptedToken {
-            access_token: access_token.to_string(),
-            refresh_token: refresh_token.to_string(),
-            expires_at,
-            scope,
-        };
-        let encrypted = self.encrypt_token(&token)?;
+        provider: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyWellness>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT wellness_data FROM daily_wellness
+            WHERE user_id = $1 AND provider = $2 AND date BETWEEN $3 AND $4
+            ORDER BY date ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(provider)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await?;
 
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.get("wellness_data"))?))
+            .collect()
+    }
+
+    async fn upsert_synced_activity(&self, user_id: Uuid, activity: &Activity) -> Result<()> {
         sqlx::query(
             r#"
-            UPDATE users
-            SET fitbit_access_token = $1,
-                fitbit_refresh_token = $2,
-                fitbit_expires_at = $3,
-                fitbit_scope = $4,
-                fitbit_nonce = $5
-            WHERE id = $6
+            INSERT INTO synced_activities (user_id, provider, activity_id, activity_data, start_date)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, provider, activity_id) DO UPDATE SET
+                activity_data = EXCLUDED.activity_data,
+                start_date = EXCLUDED.start_date,
+                synced_at = CURRENT_TIMESTAMP,
+                deleted_at = NULL
             "#,
         )
-        .bind(&encrypted.access_token)
-        .bind(&encrypted.refresh_token)
-        .bind(expires_at)
-        .bind(&token.scope)
-        .bind(&encrypted.nonce)
         .bind(user_id)
+        .bind(&activity.provider)
+        .bind(&activity.id)
+        .bind(serde_json::to_value(activity)?)
+        .bind(activity.start_date)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    async fn get_fitbit_token(&self, user_id: Uuid) -> Result<Option<DecryptedToken>> {
+    async fn get_synced_activity(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        activity_id: &str,
+    ) -> Result<Option<SyncedActivity>> {
         let row = sqlx::query(
             r#"
-            SELECT fitbit_access_token, fitbit_refresh_token, fitbit_expires_at, fitbit_scope, fitbit_nonce
-            FROM users
-            WHERE id = $1 AND fitbit_access_token IS NOT NULL
+            SELECT activity_data, synced_at, deleted_at FROM synced_activities
+            WHERE user_id = $1 AND provider = $2 AND activity_id = $3
             "#,
         )
         .bind(user_id)
+        .bind(provider)
+        .bind(activity_id)
         .fetch_optional(&self.pool)
         .await?;
 
-        if let Some(row) = row {
-            let encrypted = EncryptedToken {
-                access_token: row.get("fitbit_access_token"),
-                refresh_token: row.get("fitbit_refresh_token"),
-                expires_at: row.get("fitbit_expires_at"),
-                scope: row.get("fitbit_scope"),
-                nonce: row.get("fitbit_nonce"),
-            };
-
-            let mut decrypted = self.decrypt_token(&encrypted)?;
-            decrypted.expires_at = row.get("fitbit_expires_at");
-            decrypted.scope = row.get("fitbit_scope");
-
-            Ok(Some(decrypted))
-        } else {
-            Ok(None)
-        }
+        row.map(|row| {
+            Ok(SyncedActivity {
+                user_id,
+                activity: serde_json::from_value(row.get("activity_data"))?,
+                synced_at: row.get("synced_at"),
+                deleted_at: row.get("deleted_at"),
+            })
+        })
+        .transpose()
     }
 
-    async fn clear_strava_token(&self, user_id: Uuid) -> Result<()> {
-        sqlx::query(
+    async fn list_synced_activities(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Activity>> {
+        let rows = sqlx::query(
             r#"
-            UPDATE users
-            SET strava_access_token = NULL,
-                strava_refresh_token = NULL,
-                strava_expires_at = NULL,
-                strava_scope = NULL,
-                strava_nonce = NULL
-            WHERE id = $1
+            SELECT activity_data FROM synced_activities
+            WHERE user_id = $1 AND provider = $2 AND deleted_at IS NULL
+            ORDER BY start_date DESC
+            LIMIT $3
             "#,
         )
         .bind(user_id)
-        .execute(&self.pool)
+        .bind(provider)
+        .bind(limit.map(i64::from))
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(())
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.get("activity_data"))?))
+            .collect()
     }
 
-    async fn clear_fitbit_token(&self, user_id: Uuid) -> Result<()> {
-        sqlx::query(
+    async fn mark_synced_activity_deleted(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        activity_id: &str,
+    ) -> Result<bool> {
+        let result = sqlx::query(
             r#"
-            UPDATE users
-            SET fitbit_access_token = NULL,
-                fitbit_refresh_token = NULL,
-                fitbit_expires_at = NULL,
-                fitbit_scope = NULL,
-                fitbit_nonce = NULL
-            WHERE id = $1
+            UPDATE synced_activities SET deleted_at = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND provider = $2 AND activity_id = $3 AND deleted_at IS NULL
             "#,
         )
         .bind(user_id)
+        .bind(provider)
+        .bind(activity_id)
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
     async fn upsert_user_profile(&self, user_id: Uuid, profile_data: Value) -> Result<()> {
@@ -1496,6 +2145,248 @@ impl DatabaseProvider for PostgresDatabase {
         Err(anyhow!("PostgreSQL A2A methods not yet fully implemented"))
     }
 
+    async fn list_all_a2a_clients(&self) -> Result<Vec<A2AClient>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT client_id, name, description, client_secret_hash, capabilities,
+                   redirect_uris, is_active, created_at
+            FROM a2a_clients
+            ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| A2AClient {
+                id: row.get("client_id"),
+                name: row.get("name"),
+                description: row.get("description"),
+                public_key: row.get("client_secret_hash"),
+                capabilities: row.get("capabilities"),
+                redirect_uris: row.get("redirect_uris"),
+                is_active: row.get("is_active"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
+    async fn get_a2a_client_by_api_key_id(&self, _api_key_id: &str) -> Result<Option<A2AClient>> {
+        Err(anyhow!("PostgreSQL A2A methods not yet fully implemented"))
+    }
+
+    async fn set_a2a_client_active(&self, client_id: &str, is_active: bool) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE a2a_clients
+            SET is_active = $1,
+                deactivated_at = CASE WHEN $1 THEN NULL ELSE CURRENT_TIMESTAMP END,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE client_id = $2
+            "#,
+        )
+        .bind(is_active)
+        .bind(client_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 && !is_active {
+            sqlx::query("UPDATE a2a_sessions SET is_active = false WHERE client_id = $1")
+                .bind(client_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn rotate_a2a_client_credentials(
+        &self,
+        _client_id: &str,
+        _secret_hash: &str,
+        _api_key_id: &str,
+        _previous_valid_until: DateTime<Utc>,
+    ) -> Result<()> {
+        Err(anyhow!("PostgreSQL A2A methods not yet fully implemented"))
+    }
+
+    async fn verify_a2a_client_secret(&self, _client_id: &str, _secret_hash: &str) -> Result<bool> {
+        Err(anyhow!("PostgreSQL A2A methods not yet fully implemented"))
+    }
+
+    async fn get_a2a_client_quota(&self, client_id: &str) -> Result<Option<A2AClientQuota>> {
+        let row = sqlx::query("SELECT tier, monthly_limit FROM a2a_clients WHERE client_id = $1")
+            .bind(client_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| {
+            let tier = match row.get::<String, _>("tier").as_str() {
+                "standard" => A2AClientTier::Standard,
+                "professional" => A2AClientTier::Professional,
+                "enterprise" => A2AClientTier::Enterprise,
+                _ => A2AClientTier::Trial,
+            };
+
+            A2AClientQuota {
+                tier,
+                monthly_limit: row
+                    .get::<Option<i32>, _>("monthly_limit")
+                    .map(|limit| limit as u32),
+            }
+        }))
+    }
+
+    async fn update_a2a_client_quota(
+        &self,
+        client_id: &str,
+        quota: &A2AClientQuota,
+    ) -> Result<bool> {
+        let tier = match quota.tier {
+            A2AClientTier::Trial => "trial",
+            A2AClientTier::Standard => "standard",
+            A2AClientTier::Professional => "professional",
+            A2AClientTier::Enterprise => "enterprise",
+        };
+
+        let result = sqlx::query(
+            r#"
+            UPDATE a2a_clients
+            SET tier = $1, monthly_limit = $2, updated_at = CURRENT_TIMESTAMP
+            WHERE client_id = $3
+            "#,
+        )
+        .bind(tier)
+        .bind(quota.monthly_limit.map(|limit| limit as i32))
+        .bind(client_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_a2a_sessions(&self, _client_id: &str) -> Result<Vec<A2ASession>> {
+        Err(anyhow!("PostgreSQL A2A methods not yet fully implemented"))
+    }
+
+    async fn revoke_a2a_session(&self, _client_id: &str, _session_id: &str) -> Result<bool> {
+        Err(anyhow!("PostgreSQL A2A methods not yet fully implemented"))
+    }
+
+    async fn cleanup_expired_a2a_sessions(&self) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM a2a_sessions WHERE is_active = false OR expires_at <= CURRENT_TIMESTAMP",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn upsert_remote_agent(&self, agent: &RemoteAgent) -> Result<()> {
+        let encrypted_auth_token = match &agent.auth_token {
+            Some(token) => Some(serde_json::to_string(&EncryptedToken::new(
+                token,
+                "",
+                agent.created_at,
+                "a2a".to_string(),
+                &self.encryption_key,
+            )?)?),
+            None => None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO a2a_remote_agents (
+                id, tenant_id, user_id, name, description, base_url, endpoint_url,
+                capabilities, auth_token, is_active, created_at, last_verified_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                description = EXCLUDED.description,
+                endpoint_url = EXCLUDED.endpoint_url,
+                capabilities = EXCLUDED.capabilities,
+                auth_token = EXCLUDED.auth_token,
+                is_active = EXCLUDED.is_active,
+                last_verified_at = EXCLUDED.last_verified_at
+            "#,
+        )
+        .bind(&agent.id)
+        .bind(agent.owner.tenant_id())
+        .bind(agent.owner.user_id())
+        .bind(&agent.name)
+        .bind(&agent.description)
+        .bind(&agent.base_url)
+        .bind(&agent.endpoint_url)
+        .bind(&agent.capabilities)
+        .bind(encrypted_auth_token)
+        .bind(agent.is_active)
+        .bind(agent.created_at)
+        .bind(agent.last_verified_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_remote_agent(
+        &self,
+        owner: &AgentOwner,
+        agent_id: &str,
+    ) -> Result<Option<RemoteAgent>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, tenant_id, user_id, name, description, base_url, endpoint_url, capabilities,
+                   auth_token, is_active, created_at, last_verified_at
+            FROM a2a_remote_agents
+            WHERE id = $1 AND tenant_id IS NOT DISTINCT FROM $2 AND user_id IS NOT DISTINCT FROM $3
+            "#,
+        )
+        .bind(agent_id)
+        .bind(owner.tenant_id())
+        .bind(owner.user_id())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| self.row_to_remote_agent(&row)).transpose()
+    }
+
+    async fn list_remote_agents(&self, owner: &AgentOwner) -> Result<Vec<RemoteAgent>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, tenant_id, user_id, name, description, base_url, endpoint_url, capabilities,
+                   auth_token, is_active, created_at, last_verified_at
+            FROM a2a_remote_agents
+            WHERE tenant_id IS NOT DISTINCT FROM $1 AND user_id IS NOT DISTINCT FROM $2
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(owner.tenant_id())
+        .bind(owner.user_id())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| self.row_to_remote_agent(row))
+            .collect()
+    }
+
+    async fn delete_remote_agent(&self, owner: &AgentOwner, agent_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM a2a_remote_agents
+             WHERE id = $1 AND tenant_id IS NOT DISTINCT FROM $2 AND user_id IS NOT DISTINCT FROM $3",
+        )
+        .bind(agent_id)
+        .bind(owner.tenant_id())
+        .bind(owner.user_id())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     async fn get_top_tools_analysis(
         &self,
         _user_id: Uuid,
@@ -1888,6 +2779,62 @@ impl DatabaseProvider for PostgresDatabase {
 }
 
 impl PostgresDatabase {
+    /// Move tokens from the per-provider columns of older `users` tables
+    /// into `provider_connections`, clearing the old columns
+    async fn migrate_legacy_provider_tokens(&self) -> Result<()> {
+        for provider in ["strava", "fitbit"] {
+            let has_columns: bool = sqlx::query_scalar(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name = 'users'
+                        AND column_name = $1
+                )
+                "#,
+            )
+            .bind(format!("{}_access_token", provider))
+            .fetch_one(&self.pool)
+            .await?;
+            if !has_columns {
+                continue;
+            }
+
+            let migrated = sqlx::query(&format!(
+                r#"
+                INSERT INTO provider_connections (user_id, provider, access_token,
+                    refresh_token, nonce, scope, expires_at)
+                SELECT id, $1, {p}_access_token, {p}_refresh_token, {p}_nonce, {p}_scope,
+                    {p}_expires_at
+                FROM users
+                WHERE {p}_access_token IS NOT NULL AND {p}_refresh_token IS NOT NULL
+                    AND {p}_nonce IS NOT NULL AND {p}_scope IS NOT NULL
+                    AND {p}_expires_at IS NOT NULL
+                ON CONFLICT (user_id, provider) DO NOTHING
+                "#,
+                p = provider
+            ))
+            .bind(provider)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+
+            sqlx::query(&format!(
+                "UPDATE users SET {p}_access_token = NULL, {p}_refresh_token = NULL,
+                    {p}_expires_at = NULL, {p}_scope = NULL, {p}_nonce = NULL
+                 WHERE {p}_access_token IS NOT NULL",
+                p = provider
+            ))
+            .execute(&self.pool)
+            .await?;
+
+            if migrated > 0 {
+                tracing::info!("Migrated {} legacy {} connections", migrated, provider);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Convert database row to AdminToken
     fn row_to_admin_token(
         &self,
@@ -5,15 +5,19 @@
 
 use super::DatabaseProvider;
 use crate::a2a::auth::A2AClient;
-use crate::a2a::client::A2ASession;
+use crate::a2a::client::{A2AClientQuota, A2ASession};
 use crate::a2a::protocol::{A2ATask, TaskStatus};
+use crate::a2a::remote::{AgentOwner, RemoteAgent};
 use crate::api_keys::{ApiKey, ApiKeyUsage, ApiKeyUsageStats};
 use crate::database::A2AUsage;
-use crate::models::{DecryptedToken, User};
+use crate::models::{
+    Activity, DailyWellness, DecryptedToken, Gear, ProviderConnection, StoredDigest,
+    SyncedActivity, User,
+};
 use crate::rate_limiting::JwtUsage;
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde_json::Value;
 use uuid::Uuid;
 
@@ -66,46 +70,216 @@ impl DatabaseProvider for SqliteDatabase {
         self.inner.get_user_count().await
     }
 
-    async fn update_strava_token(
+    async fn list_user_ids(&self) -> Result<Vec<Uuid>> {
+        self.inner.list_user_ids().await
+    }
+
+    async fn upsert_provider_token(
         &self,
         user_id: Uuid,
-        access_token: &str,
-        refresh_token: &str,
-        expires_at: DateTime<Utc>,
-        scope: String,
+        provider: &str,
+        token: &DecryptedToken,
+        provider_user_id: Option<&str>,
     ) -> Result<()> {
         self.inner
-            .update_strava_token(user_id, access_token, refresh_token, expires_at, scope)
+            .upsert_provider_token(user_id, provider, token, provider_user_id)
             .await
     }
 
-    async fn get_strava_token(&self, user_id: Uuid) -> Result<Option<DecryptedToken>> {
-        self.inner.get_strava_token(user_id).await
+    async fn get_provider_token(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+    ) -> Result<Option<DecryptedToken>> {
+        self.inner.get_provider_token(user_id, provider).await
     }
 
-    async fn update_fitbit_token(
+    async fn get_provider_connection(
         &self,
         user_id: Uuid,
-        access_token: &str,
-        refresh_token: &str,
-        expires_at: DateTime<Utc>,
-        scope: String,
+        provider: &str,
+    ) -> Result<Option<ProviderConnection>> {
+        self.inner.get_provider_connection(user_id, provider).await
+    }
+
+    async fn list_provider_connections(&self, user_id: Uuid) -> Result<Vec<ProviderConnection>> {
+        self.inner.list_provider_connections(user_id).await
+    }
+
+    async fn delete_provider_connection(&self, user_id: Uuid, provider: &str) -> Result<bool> {
+        self.inner
+            .delete_provider_connection(user_id, provider)
+            .await
+    }
+
+    async fn get_provider_connection_by_provider_user_id(
+        &self,
+        provider: &str,
+        provider_user_id: &str,
+    ) -> Result<Option<ProviderConnection>> {
+        self.inner
+            .get_provider_connection_by_provider_user_id(provider, provider_user_id)
+            .await
+    }
+
+    async fn mark_provider_connection_needs_reauth(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        reason: &str,
+    ) -> Result<bool> {
+        self.inner
+            .mark_provider_connection_needs_reauth(user_id, provider, reason)
+            .await
+    }
+
+    async fn list_provider_connections_expiring_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<ProviderConnection>> {
+        self.inner
+            .list_provider_connections_expiring_before(cutoff)
+            .await
+    }
+
+    async fn create_manual_activity(&self, user_id: Uuid, activity: &Activity) -> Result<()> {
+        self.inner.create_manual_activity(user_id, activity).await
+    }
+
+    async fn get_manual_activity(
+        &self,
+        user_id: Uuid,
+        activity_id: &str,
+    ) -> Result<Option<Activity>> {
+        self.inner.get_manual_activity(user_id, activity_id).await
+    }
+
+    async fn list_manual_activities(
+        &self,
+        user_id: Uuid,
+        limit: Option<u32>,
+    ) -> Result<Vec<Activity>> {
+        self.inner.list_manual_activities(user_id, limit).await
+    }
+
+    async fn update_manual_activity(&self, user_id: Uuid, activity: &Activity) -> Result<bool> {
+        self.inner.update_manual_activity(user_id, activity).await
+    }
+
+    async fn delete_manual_activity(&self, user_id: Uuid, activity_id: &str) -> Result<bool> {
+        self.inner
+            .delete_manual_activity(user_id, activity_id)
+            .await
+    }
+
+    async fn upsert_gear(&self, user_id: Uuid, gear: &Gear) -> Result<()> {
+        self.inner.upsert_gear(user_id, gear).await
+    }
+
+    async fn get_gear(&self, user_id: Uuid, gear_id: &str) -> Result<Option<Gear>> {
+        self.inner.get_gear(user_id, gear_id).await
+    }
+
+    async fn list_gear(&self, user_id: Uuid) -> Result<Vec<Gear>> {
+        self.inner.list_gear(user_id).await
+    }
+
+    async fn upsert_digest(&self, user_id: Uuid, digest: &StoredDigest) -> Result<()> {
+        self.inner.upsert_digest(user_id, digest).await
+    }
+
+    async fn get_digest(
+        &self,
+        user_id: Uuid,
+        period: &str,
+        period_start: NaiveDate,
+    ) -> Result<Option<StoredDigest>> {
+        self.inner.get_digest(user_id, period, period_start).await
+    }
+
+    async fn list_digests(
+        &self,
+        user_id: Uuid,
+        period: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<StoredDigest>> {
+        self.inner.list_digests(user_id, period, limit).await
+    }
+
+    async fn get_analysis_settings(&self, scope: &str, scope_id: &str) -> Result<Option<Value>> {
+        self.inner.get_analysis_settings(scope, scope_id).await
+    }
+
+    async fn upsert_analysis_settings(
+        &self,
+        scope: &str,
+        scope_id: &str,
+        settings: &Value,
     ) -> Result<()> {
         self.inner
-            .update_fitbit_token(user_id, access_token, refresh_token, expires_at, scope)
+            .upsert_analysis_settings(scope, scope_id, settings)
+            .await
+    }
+
+    async fn get_user_tenant(&self, user_id: Uuid) -> Result<Option<String>> {
+        self.inner.get_user_tenant(user_id).await
+    }
+
+    async fn set_user_tenant(&self, user_id: Uuid, tenant_id: Option<&str>) -> Result<()> {
+        self.inner.set_user_tenant(user_id, tenant_id).await
+    }
+
+    async fn upsert_daily_wellness(&self, user_id: Uuid, wellness: &DailyWellness) -> Result<()> {
+        self.inner.upsert_daily_wellness(user_id, wellness).await
+    }
+
+    async fn get_daily_wellness(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyWellness>> {
+        self.inner
+            .get_daily_wellness(user_id, provider, start, end)
             .await
     }
 
-    async fn get_fitbit_token(&self, user_id: Uuid) -> Result<Option<DecryptedToken>> {
-        self.inner.get_fitbit_token(user_id).await
+    async fn upsert_synced_activity(&self, user_id: Uuid, activity: &Activity) -> Result<()> {
+        self.inner.upsert_synced_activity(user_id, activity).await
     }
 
-    async fn clear_strava_token(&self, user_id: Uuid) -> Result<()> {
-        self.inner.clear_strava_token(user_id).await
+    async fn get_synced_activity(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        activity_id: &str,
+    ) -> Result<Option<SyncedActivity>> {
+        self.inner
+            .get_synced_activity(user_id, provider, activity_id)
+            .await
     }
 
-    async fn clear_fitbit_token(&self, user_id: Uuid) -> Result<()> {
-        self.inner.clear_fitbit_token(user_id).await
+    async fn list_synced_activities(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Activity>> {
+        self.inner
+            .list_synced_activities(user_id, provider, limit)
+            .await
+    }
+
+    async fn mark_synced_activity_deleted(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        activity_id: &str,
+    ) -> Result<bool> {
+        self.inner
+            .mark_synced_activity_deleted(user_id, provider, activity_id)
+            .await
     }
 
     async fn upsert_user_profile(&self, user_id: Uuid, profile_data: Value) -> Result<()> {
@@ -344,6 +518,80 @@ impl DatabaseProvider for SqliteDatabase {
         Ok(vec![])
     }
 
+    async fn list_all_a2a_clients(&self) -> Result<Vec<A2AClient>> {
+        self.inner.list_all_a2a_clients().await
+    }
+
+    async fn get_a2a_client_by_api_key_id(&self, api_key_id: &str) -> Result<Option<A2AClient>> {
+        self.inner.get_a2a_client_by_api_key_id(api_key_id).await
+    }
+
+    async fn set_a2a_client_active(&self, client_id: &str, is_active: bool) -> Result<bool> {
+        self.inner.set_a2a_client_active(client_id, is_active).await
+    }
+
+    async fn rotate_a2a_client_credentials(
+        &self,
+        client_id: &str,
+        secret_hash: &str,
+        api_key_id: &str,
+        previous_valid_until: DateTime<Utc>,
+    ) -> Result<()> {
+        self.inner
+            .rotate_a2a_client_credentials(client_id, secret_hash, api_key_id, previous_valid_until)
+            .await
+    }
+
+    async fn verify_a2a_client_secret(&self, client_id: &str, secret_hash: &str) -> Result<bool> {
+        self.inner
+            .verify_a2a_client_secret(client_id, secret_hash)
+            .await
+    }
+
+    async fn get_a2a_client_quota(&self, client_id: &str) -> Result<Option<A2AClientQuota>> {
+        self.inner.get_a2a_client_quota(client_id).await
+    }
+
+    async fn update_a2a_client_quota(
+        &self,
+        client_id: &str,
+        quota: &A2AClientQuota,
+    ) -> Result<bool> {
+        self.inner.update_a2a_client_quota(client_id, quota).await
+    }
+
+    async fn list_a2a_sessions(&self, client_id: &str) -> Result<Vec<A2ASession>> {
+        self.inner.list_a2a_sessions(client_id).await
+    }
+
+    async fn revoke_a2a_session(&self, client_id: &str, session_id: &str) -> Result<bool> {
+        self.inner.revoke_a2a_session(client_id, session_id).await
+    }
+
+    async fn cleanup_expired_a2a_sessions(&self) -> Result<u64> {
+        self.inner.cleanup_expired_a2a_sessions().await
+    }
+
+    async fn upsert_remote_agent(&self, agent: &RemoteAgent) -> Result<()> {
+        self.inner.upsert_remote_agent(agent).await
+    }
+
+    async fn get_remote_agent(
+        &self,
+        owner: &AgentOwner,
+        agent_id: &str,
+    ) -> Result<Option<RemoteAgent>> {
+        self.inner.get_remote_agent(owner, agent_id).await
+    }
+
+    async fn list_remote_agents(&self, owner: &AgentOwner) -> Result<Vec<RemoteAgent>> {
+        self.inner.list_remote_agents(owner).await
+    }
+
+    async fn delete_remote_agent(&self, owner: &AgentOwner, agent_id: &str) -> Result<bool> {
+        self.inner.delete_remote_agent(owner, agent_id).await
+    }
+
     async fn get_top_tools_analysis(
         &self,
         user_id: Uuid,
@@ -0,0 +1,288 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! # Scheduled Training Digests
+//!
+//! Sends every user their weekly, monthly and year-in-review digests once
+//! each period is over. Digests are built by
+//! [`crate::intelligence::digest`], stored per user, and handed to the
+//! configured [`sinks::DigestSink`]s after `DIGEST_SEND_HOUR` (UTC) on the
+//! day after the period ends. A digest is sent once: it is marked delivered
+//! when any sink accepts it, and periods without activities are marked
+//! without being sent. Digests every sink rejected are retried on the next
+//! pass.
+
+pub mod sinks;
+
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::intelligence::digest::{digest_for_user, DigestPeriod};
+use crate::models::User;
+use crate::providers::connections::ProviderConnections;
+use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, NaiveTime, Utc};
+use sinks::DigestSink;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Scheduling and sinks for digest delivery
+#[derive(Debug, Clone)]
+pub struct DigestSchedulerConfig {
+    /// Time between checks for digests to send
+    pub interval: Duration,
+    /// UTC hour after which a finished period's digest is sent
+    pub send_hour: u32,
+    pub periods: Vec<DigestPeriod>,
+    /// Sink names; delivery is off without any
+    pub sinks: Vec<String>,
+    pub outbox_dir: PathBuf,
+    pub webhook_url: Option<String>,
+    pub email_api_url: Option<String>,
+    pub email_api_key: Option<String>,
+    pub email_from: String,
+}
+
+fn list(value: &str) -> impl Iterator<Item = String> + '_ {
+    value
+        .split(',')
+        .map(|item| item.trim().to_lowercase())
+        .filter(|item| !item.is_empty())
+}
+
+impl DigestSchedulerConfig {
+    /// Read the schedule and sinks from the `DIGEST_*` environment variables
+    pub fn from_env() -> Self {
+        use crate::constants::env_config;
+
+        let periods = list(&env_config::digest_periods())
+            .filter_map(|name| match DigestPeriod::parse(&name) {
+                Ok(period) => Some(period),
+                Err(e) => {
+                    warn!("Ignoring DIGEST_PERIODS entry: {}", e);
+                    None
+                }
+            })
+            .collect();
+        Self {
+            interval: Duration::from_secs(env_config::digest_check_interval_secs()),
+            send_hour: env_config::digest_send_hour(),
+            periods,
+            sinks: list(&env_config::digest_sinks()).collect(),
+            outbox_dir: PathBuf::from(env_config::digest_outbox_dir()),
+            webhook_url: env_config::digest_webhook_url(),
+            email_api_url: env_config::digest_email_api_url(),
+            email_api_key: env_config::digest_email_api_key(),
+            email_from: env_config::digest_email_from(),
+        }
+    }
+
+    /// Whether any sink is configured
+    pub fn enabled(&self) -> bool {
+        !self.sinks.is_empty() && !self.periods.is_empty()
+    }
+}
+
+impl Default for DigestSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(3600),
+            send_hour: 6,
+            periods: DigestPeriod::ALL.to_vec(),
+            sinks: vec![],
+            outbox_dir: PathBuf::from("./data/digests"),
+            webhook_url: None,
+            email_api_url: None,
+            email_api_key: None,
+            email_from: "digests@pierre.local".to_string(),
+        }
+    }
+}
+
+/// What happened to one user's digest during a scheduler pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DigestOutcome {
+    /// At least one sink accepted the digest
+    Delivered {
+        user_id: Uuid,
+        period: DigestPeriod,
+        period_start: NaiveDate,
+        sinks: Vec<String>,
+    },
+    /// The period had no activities; nothing was sent
+    Skipped {
+        user_id: Uuid,
+        period: DigestPeriod,
+        period_start: NaiveDate,
+    },
+    /// Building failed or every sink rejected the digest; retried next pass
+    Failed {
+        user_id: Uuid,
+        period: DigestPeriod,
+        period_start: NaiveDate,
+        error: String,
+    },
+}
+
+/// Periodically builds and sends the digests of finished periods
+pub struct DigestScheduler {
+    database: Arc<Database>,
+    connections: ProviderConnections,
+    config: DigestSchedulerConfig,
+    sinks: Vec<Box<dyn DigestSink>>,
+}
+
+impl DigestScheduler {
+    pub fn new(
+        connections: ProviderConnections,
+        config: DigestSchedulerConfig,
+        sinks: Vec<Box<dyn DigestSink>>,
+    ) -> Self {
+        Self {
+            database: connections.database().clone(),
+            connections,
+            config,
+            sinks,
+        }
+    }
+
+    /// Send every digest that is due now
+    pub async fn run_once(&self) -> Result<Vec<DigestOutcome>> {
+        self.run_at(Utc::now()).await
+    }
+
+    /// Send every digest that is due at `now`
+    ///
+    /// Digests are built from the data stored when they are sent, so a
+    /// period is always the last finished one as of `now`.
+    pub async fn run_at(&self, now: DateTime<Utc>) -> Result<Vec<DigestOutcome>> {
+        let today = now.date_naive();
+        let due: Vec<(DigestPeriod, NaiveDate)> = self
+            .config
+            .periods
+            .iter()
+            .map(|period| (*period, period.last_complete(today)))
+            .filter(|(period, start)| self.due_at(*period, *start) <= now)
+            .collect();
+        if due.is_empty() || self.sinks.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut outcomes = Vec::new();
+        for user_id in self.database.list_user_ids().await? {
+            let Some(user) = self.database.get_user(user_id).await? else {
+                continue;
+            };
+            for (period, start) in &due {
+                if let Some(outcome) = self.send(&user, *period, *start, now).await {
+                    outcomes.push(outcome);
+                }
+            }
+        }
+        Ok(outcomes)
+    }
+
+    /// When the digest of the period starting on `start` may be sent
+    fn due_at(&self, period: DigestPeriod, start: NaiveDate) -> DateTime<Utc> {
+        let send_time = NaiveTime::from_hms_opt(self.config.send_hour, 0, 0).unwrap_or_default();
+        (period.end_of(start) + ChronoDuration::days(1))
+            .and_time(send_time)
+            .and_utc()
+    }
+
+    async fn send(
+        &self,
+        user: &User,
+        period: DigestPeriod,
+        period_start: NaiveDate,
+        now: DateTime<Utc>,
+    ) -> Option<DigestOutcome> {
+        let failed = |error: String| DigestOutcome::Failed {
+            user_id: user.id,
+            period,
+            period_start,
+            error,
+        };
+
+        let existing = match self
+            .database
+            .get_digest(user.id, period.as_str(), period_start)
+            .await
+        {
+            Ok(existing) => existing,
+            Err(e) => return Some(failed(e.to_string())),
+        };
+        if existing.is_some_and(|digest| digest.delivered_at.is_some()) {
+            return None;
+        }
+
+        let mut digest =
+            match digest_for_user(&self.connections, user.id, period, period_start, false).await {
+                Ok(digest) => digest,
+                Err(e) => return Some(failed(e.to_string())),
+            };
+
+        let outcome = if digest.content["volume"]["activities"].as_u64() == Some(0) {
+            DigestOutcome::Skipped {
+                user_id: user.id,
+                period,
+                period_start,
+            }
+        } else {
+            let mut delivered = Vec::new();
+            let mut errors = Vec::new();
+            for sink in &self.sinks {
+                match sink.deliver(user, &digest).await {
+                    Ok(()) => delivered.push(sink.name().to_string()),
+                    Err(e) => {
+                        warn!(
+                            "Digest sink {} failed for user {}: {}",
+                            sink.name(),
+                            user.id,
+                            e
+                        );
+                        errors.push(format!("{}: {}", sink.name(), e));
+                    }
+                }
+            }
+            if delivered.is_empty() {
+                return Some(failed(errors.join("; ")));
+            }
+            DigestOutcome::Delivered {
+                user_id: user.id,
+                period,
+                period_start,
+                sinks: delivered,
+            }
+        };
+
+        digest.delivered_at = Some(now);
+        if let Err(e) = self.database.upsert_digest(user.id, &digest).await {
+            return Some(failed(e.to_string()));
+        }
+        Some(outcome)
+    }
+
+    /// Run `run_once` on the configured interval in a background task
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.interval);
+
+            loop {
+                interval.tick().await;
+
+                match self.run_once().await {
+                    Ok(outcomes) if !outcomes.is_empty() => {
+                        info!("Digest scheduler processed {} digests", outcomes.len());
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Digest delivery pass failed: {}", e),
+                }
+            }
+        })
+    }
+}
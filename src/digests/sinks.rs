@@ -0,0 +1,177 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Digest delivery sinks
+//!
+//! | Sink      | Delivers                                                                    |
+//! |-----------|-----------------------------------------------------------------------------|
+//! | `file`    | `<DIGEST_OUTBOX_DIR>/<user id>/<period>-<start>.md` and `.json`             |
+//! | `webhook` | the `get_digest` result plus `user_id` and `email` to `DIGEST_WEBHOOK_URL`  |
+//! | `email`   | `{from, to, subject, text}` to the HTTP email API at `DIGEST_EMAIL_API_URL` |
+
+use super::DigestSchedulerConfig;
+use crate::intelligence::digest::digest_json;
+use crate::models::{StoredDigest, User};
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde_json::json;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Time allowed for one webhook or email API request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Somewhere a finished digest is sent
+#[async_trait::async_trait]
+pub trait DigestSink: Send + Sync {
+    /// Name used in `DIGEST_SINKS` and delivery logs
+    fn name(&self) -> &'static str;
+
+    /// Send one user's digest
+    async fn deliver(&self, user: &User, digest: &StoredDigest) -> Result<()>;
+}
+
+/// Writes digests to a directory per user
+pub struct FileOutboxSink {
+    dir: PathBuf,
+}
+
+impl FileOutboxSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl DigestSink for FileOutboxSink {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    async fn deliver(&self, user: &User, digest: &StoredDigest) -> Result<()> {
+        let dir = self.dir.join(user.id.to_string());
+        tokio::fs::create_dir_all(&dir).await?;
+        let stem = format!("{}-{}", digest.period, digest.period_start);
+        tokio::fs::write(dir.join(format!("{}.md", stem)), &digest.summary).await?;
+        tokio::fs::write(
+            dir.join(format!("{}.json", stem)),
+            serde_json::to_string_pretty(&digest_json(digest))?,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Posts digests as JSON to a URL
+pub struct WebhookSink {
+    client: Client,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(url: String) -> Result<Self> {
+        Ok(Self {
+            client: Client::builder().timeout(REQUEST_TIMEOUT).build()?,
+            url,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl DigestSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn deliver(&self, user: &User, digest: &StoredDigest) -> Result<()> {
+        let mut body = digest_json(digest);
+        body["user_id"] = json!(user.id);
+        body["email"] = json!(user.email);
+        self.client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Emails the Markdown digest through an HTTP email API
+///
+/// The API takes `{from, to, subject, text}` with a bearer key, as most
+/// transactional email services do.
+pub struct EmailSink {
+    client: Client,
+    api_url: String,
+    api_key: Option<String>,
+    from: String,
+}
+
+impl EmailSink {
+    pub fn new(api_url: String, api_key: Option<String>, from: String) -> Result<Self> {
+        Ok(Self {
+            client: Client::builder().timeout(REQUEST_TIMEOUT).build()?,
+            api_url,
+            api_key,
+            from,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl DigestSink for EmailSink {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn deliver(&self, user: &User, digest: &StoredDigest) -> Result<()> {
+        let mut request = self.client.post(&self.api_url).json(&json!({
+            "from": self.from,
+            "to": user.email,
+            "subject": format!("Your training digest: {}", digest.title),
+            "text": digest.summary,
+        }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// The sinks named in the configuration
+pub fn create_sinks(config: &DigestSchedulerConfig) -> Result<Vec<Box<dyn DigestSink>>> {
+    config
+        .sinks
+        .iter()
+        .map(|name| -> Result<Box<dyn DigestSink>> {
+            match name.as_str() {
+                "file" => Ok(Box::new(FileOutboxSink::new(&config.outbox_dir))),
+                "webhook" => {
+                    let url = config.webhook_url.clone().ok_or_else(|| {
+                        anyhow!("The webhook digest sink needs DIGEST_WEBHOOK_URL")
+                    })?;
+                    Ok(Box::new(WebhookSink::new(url)?))
+                }
+                "email" => {
+                    let api_url = config.email_api_url.clone().ok_or_else(|| {
+                        anyhow!("The email digest sink needs DIGEST_EMAIL_API_URL")
+                    })?;
+                    Ok(Box::new(EmailSink::new(
+                        api_url,
+                        config.email_api_key.clone(),
+                        config.email_from.clone(),
+                    )?))
+                }
+                other => Err(anyhow!(
+                    "Unknown digest sink '{}'. Use file, webhook or email",
+                    other
+                )),
+            }
+        })
+        .collect()
+}
@@ -91,7 +91,7 @@ impl HealthChecker {
             cache_ttl: Duration::from_secs(30), // Cache for 30 seconds
         };
 
-        // Start background cleanup task for expired API keys
+        // Start background cleanup task for expired API keys and A2A sessions
         let database_clone = health_checker.database.clone();
         tokio::spawn(async move {
             Self::periodic_cleanup_task(database_clone).await;
@@ -100,7 +100,7 @@ impl HealthChecker {
         health_checker
     }
 
-    /// Periodic task to clean up expired API keys
+    /// Periodic task to clean up expired API keys and A2A sessions
     async fn periodic_cleanup_task(database: Database) {
         let mut interval = tokio::time::interval(Duration::from_secs(3600)); // Run every hour
 
@@ -117,6 +117,17 @@ impl HealthChecker {
                     error!("Failed to cleanup expired API keys: {}", e);
                 }
             }
+
+            match database.cleanup_expired_a2a_sessions().await {
+                Ok(count) => {
+                    if count > 0 {
+                        info!("Cleaned up {} expired A2A sessions", count);
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to cleanup expired A2A sessions: {}", e);
+                }
+            }
         }
     }
 
@@ -316,50 +316,9 @@ impl ActivityAnalyzerTrait for AdvancedActivityAnalyzer {
     }
 
     async fn detect_anomalies(&self, activity: &Activity) -> Result<Vec<Anomaly>> {
-        let mut anomalies = Vec::new();
-
-        // Check for unrealistic heart rate values
-        if let Some(max_hr) = activity.max_heart_rate {
-            if max_hr > 220 {
-                anomalies.push(Anomaly {
-                    anomaly_type: "unrealistic_heart_rate".to_string(),
-                    description: "Maximum heart rate seems unusually high".to_string(),
-                    severity: InsightSeverity::Warning,
-                    confidence: Confidence::High,
-                    affected_metric: "max_heartrate".to_string(),
-                    expected_value: Some(200.0),
-                    actual_value: Some(max_hr as f64),
-                });
-            }
-        }
-
-        // Power data not available in current Activity model
-        // Skip power anomaly detection
-
-        // Check for unrealistic speed values
-        if let Some(max_speed) = activity.max_speed {
-            let expected_max_speed = match activity.sport_type {
-                SportType::Run => 12.0,  // ~43 km/h
-                SportType::Ride => 25.0, // ~90 km/h
-                SportType::Swim => 3.0,  // ~11 km/h
-                _ => 30.0,
-            };
-
-            if max_speed > expected_max_speed {
-                anomalies.push(Anomaly {
-                    anomaly_type: "unrealistic_speed".to_string(),
-                    description: format!(
-                        "Maximum speed seems unusually high for {:?}",
-                        activity.sport_type
-                    ),
-                    severity: InsightSeverity::Warning,
-                    confidence: Confidence::Medium,
-                    affected_metric: "max_speed".to_string(),
-                    expected_value: Some(expected_max_speed),
-                    actual_value: Some(max_speed),
-                });
-            }
-        }
+        // Unrealistic heart rate and speed; history and stream checks need
+        // more than one activity (see `anomalies::find_anomalies`)
+        let mut anomalies = anomalies::implausible_values(activity);
 
         // Check for missing expected data
         if activity.average_heart_rate.is_none() && activity.sport_type != SportType::Swim {
@@ -556,7 +515,6 @@ mod tests {
             max_heart_rate: Some(250), // Unrealistic HR
             ..Activity::default()
         };
-        // Power data not available in current Activity model - skip test
 
         let anomalies = analyzer.detect_anomalies(&activity).await.unwrap();
         assert_eq!(anomalies.len(), 1); // Should detect HR anomaly
@@ -0,0 +1,874 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Activity queries
+//!
+//! Answers "longest / fastest / most" questions over the user's whole
+//! history in one call. A query filters activities, optionally groups them
+//! by calendar week, month or year, and returns the top efforts (or
+//! periods) by a chosen field:
+//!
+//! | Filter                                            | Matches                                       |
+//! |---------------------------------------------------|-----------------------------------------------|
+//! | `sport_type`                                      | sport family (`run`) or exact type, comma-separated |
+//! | `start_date`, `end_date`                          | `YYYY-MM-DD` (inclusive) or RFC 3339          |
+//! | `min_/max_distance_km`, `min_/max_duration_minutes`, `min_/max_elevation_gain` | inclusive bounds |
+//! | `location`                                        | city, region, country or trail name containing it |
+//! | `near_latitude`, `near_longitude`, `radius_km`    | start within the radius (default 10 km)       |
+//! | `has_gps`                                         | whether the activity has a start point or route |
+//! | `min_/max_temperature`, `weather_conditions`      | measured weather at the start                 |
+//!
+//! Weather is looked up last, for at most [`MAX_WEATHER_LOOKUPS`] of the
+//! activities that pass the other filters, newest first. The date filters
+//! are passed on to the provider so only that part of the history is fetched.
+
+use super::adjusted_pace::measured_weather;
+use super::patterns::sport_family;
+use super::routes::haversine_meters;
+use super::WeatherConditions;
+use crate::database_plugins::DatabaseProvider;
+use crate::models::{Activity, SportType};
+use crate::providers::connections::ProviderConnections;
+use crate::providers::manual::MANUAL_PROVIDER;
+use crate::providers::merge::{activity_history, provider_history, ActivityHistory, ALL_PROVIDERS};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Utc, Weekday};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Most activities of history searched
+pub const HISTORY_LIMIT: usize = 5000;
+/// Most activities whose weather is looked up for a weather filter
+pub const MAX_WEATHER_LOOKUPS: usize = 200;
+/// Results returned when no limit is given
+const DEFAULT_LIMIT: usize = 10;
+/// Most results a query may return
+const MAX_LIMIT: usize = 100;
+/// Radius around `near_latitude`/`near_longitude` when none is given
+const DEFAULT_RADIUS_KM: f64 = 10.0;
+
+/// Calendar period activities are grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    Week,
+    Month,
+    Year,
+}
+
+impl GroupBy {
+    fn parse(name: &str) -> Result<Self> {
+        match name {
+            "week" => Ok(Self::Week),
+            "month" => Ok(Self::Month),
+            "year" => Ok(Self::Year),
+            other => Err(anyhow!(
+                "Unknown group_by '{}'. Use one of: week, month, year",
+                other
+            )),
+        }
+    }
+
+    /// Label and first day of the period a date falls in
+    pub(crate) fn period(self, date: NaiveDate) -> (String, NaiveDate) {
+        match self {
+            Self::Week => {
+                let week = date.iso_week();
+                let start = NaiveDate::from_isoywd_opt(week.year(), week.week(), Weekday::Mon)
+                    .unwrap_or(date);
+                (format!("{}-W{:02}", week.year(), week.week()), start)
+            }
+            Self::Month => (
+                format!("{}-{:02}", date.year(), date.month()),
+                date.with_day(1).unwrap_or(date),
+            ),
+            Self::Year => (
+                date.year().to_string(),
+                NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap_or(date),
+            ),
+        }
+    }
+}
+
+/// Field results are ranked by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    Date,
+    Distance,
+    Duration,
+    ElevationGain,
+    Pace,
+    Speed,
+    HeartRate,
+    /// Activities in a period; only with `group_by`
+    Count,
+}
+
+impl SortField {
+    pub const ALL: [SortField; 8] = [
+        Self::Date,
+        Self::Distance,
+        Self::Duration,
+        Self::ElevationGain,
+        Self::Pace,
+        Self::Speed,
+        Self::HeartRate,
+        Self::Count,
+    ];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Date => "date",
+            Self::Distance => "distance",
+            Self::Duration => "duration",
+            Self::ElevationGain => "elevation_gain",
+            Self::Pace => "pace",
+            Self::Speed => "speed",
+            Self::HeartRate => "heart_rate",
+            Self::Count => "count",
+        }
+    }
+
+    fn parse(name: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.as_str() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(SortField::as_str).collect();
+                anyhow!(
+                    "Unknown sort_by '{}'. Use one of: {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Which activities a query keeps
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActivityFilter {
+    /// Sport families or exact sport types, lowercase
+    pub sports: Vec<String>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub distance_km: (Option<f64>, Option<f64>),
+    pub duration_minutes: (Option<f64>, Option<f64>),
+    pub elevation_gain: (Option<f64>, Option<f64>),
+    /// Lowercase text searched in the city, region, country and trail name
+    pub location: Option<String>,
+    /// Latitude, longitude and radius in km
+    pub near: Option<(f64, f64, f64)>,
+    pub has_gps: Option<bool>,
+    pub temperature: (Option<f64>, Option<f64>),
+    /// Lowercase text searched in the weather conditions
+    pub weather_conditions: Option<String>,
+}
+
+fn within((min, max): (Option<f64>, Option<f64>), value: Option<f64>) -> bool {
+    match value {
+        Some(v) => min.is_none_or(|min| v >= min) && max.is_none_or(|max| v <= max),
+        None => min.is_none() && max.is_none(),
+    }
+}
+
+fn has_gps(activity: &Activity) -> bool {
+    activity.start_latitude.is_some() && activity.start_longitude.is_some()
+        || activity.summary_polyline.is_some()
+}
+
+impl ActivityFilter {
+    fn matches_sport(&self, sport: &SportType) -> bool {
+        self.sports.is_empty()
+            || self.sports.iter().any(|name| {
+                name == sport_family(sport) || SportType::from_internal_string(name) == *sport
+            })
+    }
+
+    /// Every filter but the weather ones
+    pub fn matches(&self, activity: &Activity) -> bool {
+        let location = self.location.as_ref().is_none_or(|text| {
+            [
+                &activity.city,
+                &activity.region,
+                &activity.country,
+                &activity.trail_name,
+            ]
+            .into_iter()
+            .flatten()
+            .any(|place| place.to_lowercase().contains(text.as_str()))
+        });
+        let near = self.near.is_none_or(|(lat, lon, radius_km)| {
+            match (activity.start_latitude, activity.start_longitude) {
+                (Some(a_lat), Some(a_lon)) => {
+                    haversine_meters((lat, lon), (a_lat, a_lon)) <= radius_km * 1000.0
+                }
+                _ => false,
+            }
+        });
+
+        self.matches_sport(&activity.sport_type)
+            && self.start.is_none_or(|start| activity.start_date >= start)
+            && self.end.is_none_or(|end| activity.start_date <= end)
+            && within(
+                self.distance_km,
+                activity.distance_meters.map(|d| d / 1000.0),
+            )
+            && within(
+                self.duration_minutes,
+                Some(activity.duration_seconds as f64 / 60.0),
+            )
+            && within(self.elevation_gain, activity.elevation_gain)
+            && location
+            && near
+            && self.has_gps.is_none_or(|gps| has_gps(activity) == gps)
+    }
+
+    pub fn needs_weather(&self) -> bool {
+        self.temperature != (None, None) || self.weather_conditions.is_some()
+    }
+
+    pub fn matches_weather(&self, weather: &WeatherConditions) -> bool {
+        within(
+            self.temperature,
+            Some(f64::from(weather.temperature_celsius)),
+        ) && self
+            .weather_conditions
+            .as_ref()
+            .is_none_or(|text| weather.conditions.to_lowercase().contains(text.as_str()))
+    }
+}
+
+/// Parameters of the `query_activities` tool
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityQuery {
+    pub filter: ActivityFilter,
+    pub group_by: Option<GroupBy>,
+    pub sort_by: SortField,
+    pub descending: bool,
+    pub limit: usize,
+}
+
+fn parse_date(args: &Value, name: &str, end_of_day: bool) -> Result<Option<DateTime<Utc>>> {
+    let Some(text) = args[name].as_str() else {
+        return Ok(None);
+    };
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Ok(Some(date.with_timezone(&Utc)));
+    }
+    let date = NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map_err(|_| anyhow!("{} must be YYYY-MM-DD or RFC 3339, got '{}'", name, text))?;
+    let time = if end_of_day {
+        NaiveTime::from_hms_opt(23, 59, 59)
+    } else {
+        NaiveTime::from_hms_opt(0, 0, 0)
+    }
+    .unwrap_or_default();
+    Ok(Some(date.and_time(time).and_utc()))
+}
+
+fn parse_bounds(args: &Value, min: &str, max: &str) -> Result<(Option<f64>, Option<f64>)> {
+    let bounds = (args[min].as_f64(), args[max].as_f64());
+    if let (Some(low), Some(high)) = bounds {
+        if low > high {
+            return Err(anyhow!("{} can't be greater than {}", min, max));
+        }
+    }
+    Ok(bounds)
+}
+
+fn lowercase(args: &Value, name: &str) -> Option<String> {
+    args[name]
+        .as_str()
+        .map(|text| text.trim().to_lowercase())
+        .filter(|text| !text.is_empty())
+}
+
+impl ActivityFilter {
+    /// Read and check the filter arguments of a tool
+    pub fn from_args(args: &Value) -> Result<Self> {
+        let sports = lowercase(args, "sport_type")
+            .map(|sports| {
+                sports
+                    .split(',')
+                    .map(|sport| sport.trim().to_string())
+                    .filter(|sport| !sport.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let start = parse_date(args, "start_date", false)?;
+        let end = parse_date(args, "end_date", true)?;
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                return Err(anyhow!("start_date must be before end_date"));
+            }
+        }
+
+        let near = match (
+            args["near_latitude"].as_f64(),
+            args["near_longitude"].as_f64(),
+        ) {
+            (Some(lat), Some(lon)) => {
+                let radius = args["radius_km"].as_f64().unwrap_or(DEFAULT_RADIUS_KM);
+                if radius <= 0.0 {
+                    return Err(anyhow!("radius_km must be positive"));
+                }
+                Some((lat, lon, radius))
+            }
+            (None, None) => None,
+            _ => {
+                return Err(anyhow!(
+                    "near_latitude and near_longitude must be given together"
+                ))
+            }
+        };
+
+        Ok(Self {
+            sports,
+            start,
+            end,
+            distance_km: parse_bounds(args, "min_distance_km", "max_distance_km")?,
+            duration_minutes: parse_bounds(args, "min_duration_minutes", "max_duration_minutes")?,
+            elevation_gain: parse_bounds(args, "min_elevation_gain", "max_elevation_gain")?,
+            location: lowercase(args, "location"),
+            near,
+            has_gps: args["has_gps"].as_bool(),
+            temperature: parse_bounds(args, "min_temperature", "max_temperature")?,
+            weather_conditions: lowercase(args, "weather_conditions"),
+        })
+    }
+}
+
+impl ActivityQuery {
+    /// Read and check the tool arguments
+    pub fn from_args(args: &Value) -> Result<Self> {
+        let filter = ActivityFilter::from_args(args)?;
+        let group_by = args["group_by"].as_str().map(GroupBy::parse).transpose()?;
+        let sort_by = args["sort_by"]
+            .as_str()
+            .map(SortField::parse)
+            .transpose()?
+            .unwrap_or(SortField::Date);
+        if sort_by == SortField::Count && group_by.is_none() {
+            return Err(anyhow!("sort_by 'count' needs group_by"));
+        }
+        let descending = match args["order"].as_str() {
+            Some("asc") => false,
+            Some("desc") => true,
+            Some(other) => {
+                return Err(anyhow!("Unknown order '{}'. Use asc or desc", other));
+            }
+            // Fastest first for pace, biggest or latest first otherwise
+            None => sort_by != SortField::Pace,
+        };
+        let limit = match args["limit"].as_u64() {
+            Some(limit) if (1..=MAX_LIMIT as u64).contains(&limit) => limit as usize,
+            Some(_) => return Err(anyhow!("limit must be between 1 and {}", MAX_LIMIT)),
+            None => DEFAULT_LIMIT,
+        };
+
+        Ok(Self {
+            filter,
+            group_by,
+            sort_by,
+            descending,
+            limit,
+        })
+    }
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+fn pace_of(activity: &Activity) -> Option<f64> {
+    activity
+        .distance_meters
+        .filter(|d| *d > 0.0 && activity.duration_seconds > 0)
+        .map(|d| activity.duration_seconds as f64 / (d / 1000.0))
+}
+
+/// One matching activity
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivitySummary {
+    pub id: String,
+    pub name: String,
+    pub sport_type: SportType,
+    pub start_date: DateTime<Utc>,
+    pub provider: String,
+    pub distance_km: Option<f64>,
+    pub duration_minutes: f64,
+    pub elevation_gain: Option<f64>,
+    pub pace_seconds_per_km: Option<f64>,
+    pub average_heart_rate: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trail_name: Option<String>,
+    pub has_gps: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weather: Option<WeatherConditions>,
+}
+
+impl ActivitySummary {
+    pub(crate) fn new(activity: &Activity, weather: Option<WeatherConditions>) -> Self {
+        Self {
+            id: activity.id.clone(),
+            name: activity.name.clone(),
+            sport_type: activity.sport_type.clone(),
+            start_date: activity.start_date,
+            provider: activity.provider.clone(),
+            distance_km: activity.distance_meters.map(|d| round(d / 1000.0, 2)),
+            duration_minutes: round(activity.duration_seconds as f64 / 60.0, 1),
+            elevation_gain: activity.elevation_gain,
+            pace_seconds_per_km: pace_of(activity).map(|p| round(p, 1)),
+            average_heart_rate: activity.average_heart_rate,
+            city: activity.city.clone(),
+            country: activity.country.clone(),
+            trail_name: activity.trail_name.clone(),
+            has_gps: has_gps(activity),
+            weather,
+        }
+    }
+
+    fn sort_key(&self, field: SortField) -> Option<f64> {
+        match field {
+            SortField::Date | SortField::Count => Some(self.start_date.timestamp() as f64),
+            SortField::Distance => self.distance_km,
+            SortField::Duration => Some(self.duration_minutes),
+            SortField::ElevationGain => self.elevation_gain,
+            SortField::Pace => self.pace_seconds_per_km,
+            SortField::Speed => self.pace_seconds_per_km.map(|pace| 3600.0 / pace),
+            SortField::HeartRate => self.average_heart_rate.map(f64::from),
+        }
+    }
+}
+
+/// Totals over a set of activities
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QueryTotals {
+    pub activities: usize,
+    pub total_distance_km: f64,
+    pub total_duration_hours: f64,
+    pub total_elevation_gain: f64,
+    pub longest_distance_km: Option<f64>,
+    /// Time over distance of the activities with a distance
+    pub average_pace_seconds_per_km: Option<f64>,
+    pub average_heart_rate: Option<f64>,
+}
+
+impl QueryTotals {
+    pub(crate) fn of(activities: &[&ActivitySummary]) -> Self {
+        let distance: f64 = activities.iter().filter_map(|a| a.distance_km).sum();
+        let timed_minutes: f64 = activities
+            .iter()
+            .filter(|a| a.distance_km.is_some_and(|d| d > 0.0))
+            .map(|a| a.duration_minutes)
+            .sum();
+        let heart_rates: Vec<f64> = activities
+            .iter()
+            .filter_map(|a| a.average_heart_rate.map(f64::from))
+            .collect();
+
+        Self {
+            activities: activities.len(),
+            total_distance_km: round(distance, 2),
+            total_duration_hours: round(
+                activities.iter().map(|a| a.duration_minutes).sum::<f64>() / 60.0,
+                2,
+            ),
+            total_elevation_gain: activities
+                .iter()
+                .filter_map(|a| a.elevation_gain)
+                .sum::<f64>()
+                .round(),
+            longest_distance_km: activities
+                .iter()
+                .filter_map(|a| a.distance_km)
+                .max_by(f64::total_cmp),
+            average_pace_seconds_per_km: (distance > 0.0 && timed_minutes > 0.0)
+                .then(|| round(timed_minutes * 60.0 / distance, 1)),
+            average_heart_rate: (!heart_rates.is_empty()).then(|| {
+                round(
+                    heart_rates.iter().sum::<f64>() / heart_rates.len() as f64,
+                    1,
+                )
+            }),
+        }
+    }
+
+    fn sort_key(&self, field: SortField) -> Option<f64> {
+        match field {
+            SortField::Date => None,
+            SortField::Distance => Some(self.total_distance_km),
+            SortField::Duration => Some(self.total_duration_hours),
+            SortField::ElevationGain => Some(self.total_elevation_gain),
+            SortField::Pace => self.average_pace_seconds_per_km,
+            SortField::Speed => self.average_pace_seconds_per_km.map(|pace| 3600.0 / pace),
+            SortField::HeartRate => self.average_heart_rate,
+            SortField::Count => Some(self.activities as f64),
+        }
+    }
+}
+
+/// Activities of one calendar period
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryGroup {
+    pub period: String,
+    pub start: NaiveDate,
+    #[serde(flatten)]
+    pub totals: QueryTotals,
+}
+
+/// Result of the `query_activities` tool
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryResult {
+    pub activities_searched: usize,
+    pub matched: usize,
+    /// Totals over every matching activity
+    pub totals: QueryTotals,
+    /// Top periods, with `group_by`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_by: Option<GroupBy>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<QueryGroup>,
+    pub sort_by: SortField,
+    pub order: &'static str,
+    /// Top matching activities
+    pub activities: Vec<ActivitySummary>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+/// Order by a key, keeping entries without it last
+fn rank<T>(items: &mut [T], key: impl Fn(&T) -> Option<f64>, descending: bool) {
+    items.sort_by(|a, b| match (key(a), key(b)) {
+        (Some(x), Some(y)) if descending => y.total_cmp(&x),
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+}
+
+/// Summarize, group and rank activities that already passed the filter
+pub fn summarize(
+    query: &ActivityQuery,
+    matched: Vec<(&Activity, Option<WeatherConditions>)>,
+    activities_searched: usize,
+    notes: Vec<String>,
+) -> QueryResult {
+    let mut summaries: Vec<ActivitySummary> = matched
+        .into_iter()
+        .map(|(activity, weather)| ActivitySummary::new(activity, weather))
+        .collect();
+    let totals = QueryTotals::of(&summaries.iter().collect::<Vec<_>>());
+
+    let mut groups = vec![];
+    if let Some(group_by) = query.group_by {
+        let mut periods: BTreeMap<NaiveDate, (String, Vec<&ActivitySummary>)> = BTreeMap::new();
+        for summary in &summaries {
+            let (label, start) = group_by.period(summary.start_date.date_naive());
+            periods
+                .entry(start)
+                .or_insert_with(|| (label, vec![]))
+                .1
+                .push(summary);
+        }
+        groups = periods
+            .into_iter()
+            .map(|(start, (period, activities))| QueryGroup {
+                period,
+                start,
+                totals: QueryTotals::of(&activities),
+            })
+            .collect();
+        if query.sort_by == SortField::Date {
+            if query.descending {
+                groups.reverse();
+            }
+        } else {
+            rank(
+                &mut groups,
+                |group| group.totals.sort_key(query.sort_by),
+                query.descending,
+            );
+        }
+        groups.truncate(query.limit);
+    }
+
+    rank(
+        &mut summaries,
+        |summary| summary.sort_key(query.sort_by),
+        query.descending,
+    );
+    summaries.truncate(query.limit);
+
+    QueryResult {
+        activities_searched,
+        matched: totals.activities,
+        totals,
+        group_by: query.group_by,
+        groups,
+        sort_by: query.sort_by,
+        order: if query.descending { "desc" } else { "asc" },
+        activities: summaries,
+        notes,
+    }
+}
+
+/// Run a query over already fetched activities
+///
+/// Weather filters look up the measured weather of at most
+/// [`MAX_WEATHER_LOOKUPS`] candidates; the rest are left out and noted.
+pub async fn run_query(history: &[Activity], query: &ActivityQuery) -> QueryResult {
+    let mut candidates: Vec<&Activity> =
+        history.iter().filter(|a| query.filter.matches(a)).collect();
+    let mut notes = vec![];
+    if history.len() >= HISTORY_LIMIT {
+        notes.push(format!(
+            "Searched the latest {} activities only",
+            HISTORY_LIMIT
+        ));
+    }
+
+    let matched = if query.filter.needs_weather() {
+        candidates.sort_by_key(|a| std::cmp::Reverse(a.start_date));
+        if candidates.len() > MAX_WEATHER_LOOKUPS {
+            notes.push(format!(
+                "Weather checked for the latest {} of {} candidate activities; narrow the dates to check older ones",
+                MAX_WEATHER_LOOKUPS,
+                candidates.len()
+            ));
+            candidates.truncate(MAX_WEATHER_LOOKUPS);
+        }
+        let weather = measured_weather(&candidates).await;
+        let unknown = weather.iter().filter(|w| w.is_none()).count();
+        if unknown > 0 {
+            notes.push(format!(
+                "{} activities without measured weather were left out",
+                unknown
+            ));
+        }
+        candidates
+            .into_iter()
+            .zip(weather)
+            .filter_map(|(activity, weather)| {
+                weather
+                    .filter(|w| query.filter.matches_weather(w))
+                    .map(|w| (activity, Some(w)))
+            })
+            .collect()
+    } else {
+        candidates.into_iter().map(|a| (a, None)).collect()
+    };
+
+    summarize(query, matched, history.len(), notes)
+}
+
+/// A user's history from one provider, up to [`HISTORY_LIMIT`] activities
+/// started between `after` and `before`
+///
+/// Without a provider, or with `all`, every connected provider's history is
+/// merged with manual activities. A single provider that can't be read at
+/// all is an error, e.g. its [`ProviderRateLimited`](crate::providers::rate_budget::ProviderRateLimited).
+pub async fn history_for_user(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    provider: Option<&str>,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+) -> Result<ActivityHistory> {
+    let database = connections.database();
+    Ok(match provider.unwrap_or(ALL_PROVIDERS) {
+        ALL_PROVIDERS => activity_history(connections, user_id, HISTORY_LIMIT, after, before).await,
+        MANUAL_PROVIDER => ActivityHistory {
+            activities: database
+                .list_manual_activities(user_id, Some(HISTORY_LIMIT as u32))
+                .await?,
+            gaps: vec![],
+        },
+        name => {
+            let provider = connections
+                .connected_provider(user_id, name)
+                .await?
+                .ok_or_else(|| anyhow!("Not connected to {}", name))?;
+            provider_history(
+                database,
+                user_id,
+                provider.as_ref(),
+                HISTORY_LIMIT,
+                after,
+                before,
+            )
+            .await?
+        }
+    })
+}
+
+/// Result of the `query_activities` tool for a user
+///
+/// Parts of the history that couldn't be read are listed in the notes.
+pub async fn query_for_user(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    provider: Option<&str>,
+    query: &ActivityQuery,
+) -> Result<QueryResult> {
+    let history = history_for_user(
+        connections,
+        user_id,
+        provider,
+        query.filter.start,
+        query.filter.end,
+    )
+    .await?;
+    let mut result = run_query(&history.activities, query).await;
+    result.notes.extend(history.gaps);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn activity(id: &str, sport: SportType, date: &str, km: f64, minutes: u64) -> Activity {
+        Activity {
+            id: id.to_string(),
+            name: id.to_string(),
+            sport_type: sport,
+            start_date: DateTime::parse_from_rfc3339(date)
+                .unwrap()
+                .with_timezone(&Utc),
+            duration_seconds: minutes * 60,
+            distance_meters: Some(km * 1000.0),
+            elevation_gain: Some(km * 10.0),
+            provider: "strava".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn history() -> Vec<Activity> {
+        vec![
+            activity(
+                "jan_long",
+                SportType::Run,
+                "2024-01-20T08:00:00Z",
+                21.1,
+                115,
+            ),
+            activity("jan_short", SportType::Run, "2024-01-22T08:00:00Z", 5.0, 24),
+            Activity {
+                city: Some("Montréal".to_string()),
+                start_latitude: Some(45.5),
+                start_longitude: Some(-73.57),
+                ..activity(
+                    "mar_trail",
+                    SportType::TrailRunning,
+                    "2024-03-02T08:00:00Z",
+                    15.0,
+                    100,
+                )
+            },
+            activity(
+                "mar_ride",
+                SportType::Ride,
+                "2024-03-03T08:00:00Z",
+                60.0,
+                120,
+            ),
+            activity("new_year", SportType::Run, "2025-01-01T08:00:00Z", 10.0, 50),
+        ]
+    }
+
+    #[test]
+    fn test_query_from_args() {
+        let query = ActivityQuery::from_args(&json!({})).unwrap();
+        assert_eq!(query.sort_by, SortField::Date);
+        assert!(query.descending);
+        assert_eq!(query.limit, DEFAULT_LIMIT);
+
+        let query = ActivityQuery::from_args(&json!({"sort_by": "pace"})).unwrap();
+        assert!(!query.descending);
+
+        for bad in [
+            json!({"sort_by": "longest"}),
+            json!({"sort_by": "count"}),
+            json!({"group_by": "day"}),
+            json!({"start_date": "2024-13-01"}),
+            json!({"start_date": "2024-02-01", "end_date": "2024-01-01"}),
+            json!({"min_distance_km": 10, "max_distance_km": 5}),
+            json!({"near_latitude": 45.5}),
+            json!({"limit": 0}),
+        ] {
+            assert!(ActivityQuery::from_args(&bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_longest_run_in_a_year() {
+        let history = history();
+        let query = ActivityQuery::from_args(&json!({
+            "sport_type": "run",
+            "start_date": "2024-01-01",
+            "end_date": "2024-12-31",
+            "sort_by": "distance",
+            "limit": 1
+        }))
+        .unwrap();
+        let result = run_query(&history, &query).await;
+        assert_eq!(result.matched, 3);
+        assert_eq!(result.activities.len(), 1);
+        assert_eq!(result.activities[0].id, "jan_long");
+        assert_eq!(result.totals.longest_distance_km, Some(21.1));
+
+        // Exact sport types, places and coordinates
+        let trail = ActivityQuery::from_args(&json!({"sport_type": "trail_running"})).unwrap();
+        assert_eq!(run_query(&history, &trail).await.matched, 1);
+        let city = ActivityQuery::from_args(&json!({"location": "montréal"})).unwrap();
+        assert_eq!(
+            run_query(&history, &city).await.activities[0].id,
+            "mar_trail"
+        );
+        let near = ActivityQuery::from_args(
+            &json!({"near_latitude": 45.51, "near_longitude": -73.56, "radius_km": 5}),
+        )
+        .unwrap();
+        assert_eq!(run_query(&history, &near).await.matched, 1);
+        let gps = ActivityQuery::from_args(&json!({"has_gps": false})).unwrap();
+        assert_eq!(run_query(&history, &gps).await.matched, 4);
+    }
+
+    #[tokio::test]
+    async fn test_group_by_month() {
+        let history = history();
+        let query = ActivityQuery::from_args(&json!({
+            "group_by": "month",
+            "sort_by": "distance",
+            "limit": 2
+        }))
+        .unwrap();
+        let result = run_query(&history, &query).await;
+        let periods: Vec<&str> = result.groups.iter().map(|g| g.period.as_str()).collect();
+        assert_eq!(periods, vec!["2024-03", "2024-01"]);
+        assert_eq!(result.groups[0].totals.activities, 2);
+        assert_eq!(result.groups[0].totals.total_distance_km, 75.0);
+
+        let weeks =
+            ActivityQuery::from_args(&json!({"group_by": "week", "sort_by": "count"})).unwrap();
+        let result = run_query(&history, &weeks).await;
+        assert_eq!(result.groups[0].totals.activities, 2);
+        // 1 January 2025 is in ISO week 1 of 2025
+        assert!(result.groups.iter().any(|g| g.period == "2025-W01"));
+    }
+}
@@ -0,0 +1,357 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Grade- and heat-adjusted pace
+//!
+//! Grade-adjusted pace is the pace the same effort would give on flat
+//! ground. With distance and altitude streams, each stretch of about 50 m on
+//! foot is weighted by the energy cost of running its grade (Minetti et al.,
+//! 2002). Without streams, or for other sports, every meter climbed counts
+//! as [`CLIMB_COST_METERS`] flat meters.
+//!
+//! Heat-adjusted pace removes the slowdown from the conditions. With
+//! humidity it follows the temperature plus dew point rule (both in °F): no
+//! effect up to 100, about 10% at 180. With temperature alone it allows
+//! 0.4% a degree above 15°C. Below 5°C cold costs 0.2% a degree. Only
+//! measured weather is used; estimates would make the adjustment a guess.
+
+use super::patterns::sport_family;
+use super::weather::WeatherService;
+use super::WeatherConditions;
+use crate::config::FitnessConfig;
+use crate::models::{Activity, ActivityStreams};
+use serde::{Deserialize, Serialize};
+
+/// Flat meters one meter of climbing is worth, net of the descent back down
+pub const CLIMB_COST_METERS: f64 = 6.0;
+/// Temperatures in this range (°C) don't slow an effort
+const IDEAL_TEMPERATURE: (f64, f64) = (5.0, 15.0);
+/// Slowdown per degree above the ideal range, when humidity is unknown
+const HEAT_SLOWDOWN_PER_DEGREE: f64 = 0.004;
+/// Slowdown per degree below the ideal range
+const COLD_SLOWDOWN_PER_DEGREE: f64 = 0.002;
+/// Slowdown at temperature plus dew point (°F), interpolated in between
+const HEAT_INDEX_SLOWDOWN: [(f64, f64); 9] = [
+    (100.0, 0.0),
+    (110.0, 0.005),
+    (120.0, 0.01),
+    (130.0, 0.02),
+    (140.0, 0.03),
+    (150.0, 0.045),
+    (160.0, 0.06),
+    (170.0, 0.08),
+    (180.0, 0.10),
+];
+/// Stream stretches are at least this long, to smooth out altitude noise
+const GRADE_SEGMENT_METERS: f64 = 50.0;
+/// Grades beyond this are treated as this steep, the limit of the cost model
+const MAX_GRADE: f64 = 0.45;
+/// Energy cost of running on the flat, J/kg/m
+const FLAT_COST: f64 = 3.6;
+
+/// How the grade adjustment was made
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GradeSource {
+    /// Stretch by stretch from altitude and distance streams
+    Streams,
+    /// From the activity's total climb
+    ElevationGain,
+    /// No elevation data: grade-adjusted pace is the actual pace
+    None,
+}
+
+/// An effort's pace, adjusted for terrain and conditions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdjustedPace {
+    pub pace_seconds_per_km: f64,
+    /// Pace on flat ground
+    pub grade_adjusted_pace_seconds_per_km: f64,
+    pub grade_source: GradeSource,
+    /// Pace in ideal conditions, when measured weather is known
+    pub heat_adjusted_pace_seconds_per_km: Option<f64>,
+    pub dew_point_celsius: Option<f64>,
+    /// Pace on flat ground in ideal conditions
+    pub adjusted_pace_seconds_per_km: f64,
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+/// Energy cost of running at a grade, relative to the flat
+pub fn grade_cost(grade: f64) -> f64 {
+    let i = grade.clamp(-MAX_GRADE, MAX_GRADE);
+    let cost = 155.4 * i.powi(5) - 30.4 * i.powi(4) - 43.3 * i.powi(3)
+        + 46.3 * i.powi(2)
+        + 19.5 * i
+        + FLAT_COST;
+    cost / FLAT_COST
+}
+
+/// Flat meters worth the same effort as the recorded route
+///
+/// Needs distance and altitude streams of the same length.
+pub fn flat_equivalent_meters(streams: &ActivityStreams) -> Option<f64> {
+    let distance = streams.distance.as_ref()?;
+    let altitude = streams.altitude.as_ref()?;
+    if distance.len() != altitude.len() || distance.len() < 2 {
+        return None;
+    }
+
+    let mut flat = 0.0;
+    let mut start = 0;
+    for end in 1..distance.len() {
+        let length = distance[end] - distance[start];
+        if length >= GRADE_SEGMENT_METERS || end == distance.len() - 1 {
+            if length > 0.0 {
+                flat += length * grade_cost((altitude[end] - altitude[start]) / length);
+            }
+            start = end;
+        }
+    }
+    (flat > 0.0).then_some(flat)
+}
+
+/// Dew point (°C) from temperature and relative humidity, Magnus formula
+pub fn dew_point(temperature_celsius: f64, humidity_percentage: f64) -> f64 {
+    let (a, b) = (17.62, 243.12);
+    let gamma = (humidity_percentage.clamp(1.0, 100.0) / 100.0).ln()
+        + a * temperature_celsius / (b + temperature_celsius);
+    b * gamma / (a - gamma)
+}
+
+/// How much slower than ideal an effort is expected to be at a temperature
+pub fn temperature_factor(temperature_celsius: Option<f64>) -> f64 {
+    let (low, high) = IDEAL_TEMPERATURE;
+    match temperature_celsius {
+        Some(t) if t > high => 1.0 + (t - high) * HEAT_SLOWDOWN_PER_DEGREE,
+        Some(t) if t < low => 1.0 + (low - t) * COLD_SLOWDOWN_PER_DEGREE,
+        _ => 1.0,
+    }
+}
+
+/// Slowdown for temperature plus dew point in °F
+fn heat_index_slowdown(index: f64) -> f64 {
+    let (first, last) = (HEAT_INDEX_SLOWDOWN[0], HEAT_INDEX_SLOWDOWN[8]);
+    if index <= first.0 {
+        return 0.0;
+    }
+    if index >= last.0 {
+        // Keep the last step's slope beyond the table
+        let (before, after) = (HEAT_INDEX_SLOWDOWN[7], last);
+        return after.1 + (index - after.0) * (after.1 - before.1) / (after.0 - before.0);
+    }
+    HEAT_INDEX_SLOWDOWN
+        .windows(2)
+        .find(|pair| index <= pair[1].0)
+        .map_or(0.0, |pair| {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            y0 + (index - x0) * (y1 - y0) / (x1 - x0)
+        })
+}
+
+/// How much slower than ideal an effort is expected to be in the conditions
+pub fn heat_factor(weather: &WeatherConditions) -> f64 {
+    let temperature = f64::from(weather.temperature_celsius);
+    match weather.humidity_percentage {
+        Some(humidity) if temperature >= IDEAL_TEMPERATURE.0 => {
+            let fahrenheit = |c: f64| c * 9.0 / 5.0 + 32.0;
+            let index =
+                fahrenheit(temperature) + fahrenheit(dew_point(temperature, humidity.into()));
+            1.0 + heat_index_slowdown(index)
+        }
+        _ => temperature_factor(Some(temperature)),
+    }
+}
+
+impl AdjustedPace {
+    /// Adjusted pace of an activity with a distance
+    ///
+    /// Streams refine the grade adjustment for runs and walks; weather is
+    /// ignored unless measured.
+    pub fn for_activity(
+        activity: &Activity,
+        streams: Option<&ActivityStreams>,
+        weather: Option<&WeatherConditions>,
+    ) -> Option<Self> {
+        let distance = activity.distance_meters.filter(|d| *d > 0.0)?;
+        let duration = activity.duration_seconds as f64;
+        if duration <= 0.0 {
+            return None;
+        }
+
+        let on_foot = matches!(sport_family(&activity.sport_type), "run" | "walk");
+        let (flat_meters, grade_source) = match (
+            streams.filter(|_| on_foot).and_then(flat_equivalent_meters),
+            activity.elevation_gain,
+        ) {
+            // Scale to the activity's distance, as streams may stop short
+            (Some(flat), _) => {
+                let recorded = streams
+                    .and_then(|s| s.distance.as_ref())
+                    .and_then(|d| d.last().copied())
+                    .filter(|d| *d > 0.0)
+                    .unwrap_or(distance);
+                (flat * distance / recorded, GradeSource::Streams)
+            }
+            (None, Some(gain)) if gain > 0.0 => (
+                distance + CLIMB_COST_METERS * gain,
+                GradeSource::ElevationGain,
+            ),
+            _ => (distance, GradeSource::None),
+        };
+
+        let pace = duration / (distance / 1000.0);
+        let grade_adjusted = duration / (flat_meters / 1000.0);
+        let measured = weather.filter(|w| w.is_measured());
+        let factor = measured.map(heat_factor);
+
+        Some(Self {
+            pace_seconds_per_km: round(pace, 1),
+            grade_adjusted_pace_seconds_per_km: round(grade_adjusted, 1),
+            grade_source,
+            heat_adjusted_pace_seconds_per_km: factor.map(|f| round(pace / f, 1)),
+            dew_point_celsius: measured.and_then(|w| {
+                w.humidity_percentage
+                    .map(|h| round(dew_point(f64::from(w.temperature_celsius), f64::from(h)), 1))
+            }),
+            adjusted_pace_seconds_per_km: round(grade_adjusted / factor.unwrap_or(1.0), 1),
+        })
+    }
+}
+
+/// Measured weather at the start of each activity, when the weather API is
+/// enabled and the activity has coordinates
+pub async fn measured_weather(activities: &[&Activity]) -> Vec<Option<WeatherConditions>> {
+    let config = FitnessConfig::load(None)
+        .unwrap_or_default()
+        .weather_api
+        .unwrap_or_default();
+    if !config.enabled {
+        return vec![None; activities.len()];
+    }
+    let mut service = WeatherService::with_config(config);
+
+    let mut weather = Vec::with_capacity(activities.len());
+    for activity in activities {
+        weather.push(
+            service
+                .get_weather_for_activity(
+                    activity.start_latitude,
+                    activity.start_longitude,
+                    activity.start_date,
+                )
+                .await
+                .unwrap_or(None)
+                .filter(WeatherConditions::is_measured),
+        );
+    }
+    weather
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intelligence::WeatherProvenance;
+    use crate::models::SportType;
+
+    fn run(km: f64, minutes: u64, climb: f64) -> Activity {
+        Activity {
+            sport_type: SportType::Run,
+            duration_seconds: minutes * 60,
+            distance_meters: Some(km * 1000.0),
+            elevation_gain: Some(climb),
+            ..Default::default()
+        }
+    }
+
+    fn weather(temperature: f32, humidity: Option<f32>) -> WeatherConditions {
+        WeatherConditions {
+            temperature_celsius: temperature,
+            humidity_percentage: humidity,
+            wind_speed_kmh: None,
+            conditions: "clear".to_string(),
+            provenance: WeatherProvenance::Measured,
+        }
+    }
+
+    #[test]
+    fn test_grade_cost() {
+        assert!((grade_cost(0.0) - 1.0).abs() < 1e-9);
+        assert!(grade_cost(0.1) > 1.5);
+        // Gentle descents are cheaper than the flat, steep ones aren't
+        assert!(grade_cost(-0.1) < 1.0);
+        assert!(grade_cost(-0.4) > grade_cost(-0.1));
+    }
+
+    #[test]
+    fn test_grade_adjusted_pace_from_streams() {
+        // 1 km climbing 50 m, then 1 km back down
+        let distance: Vec<f64> = (0..=40).map(|i| f64::from(i) * 50.0).collect();
+        let altitude: Vec<f64> = (0..=40)
+            .map(|i| {
+                if i <= 20 {
+                    f64::from(i) * 2.5
+                } else {
+                    f64::from(40 - i) * 2.5
+                }
+            })
+            .collect();
+        let streams = ActivityStreams {
+            time: vec![],
+            heart_rate: None,
+            power: None,
+            distance: Some(distance),
+            altitude: Some(altitude),
+            cadence: None,
+        };
+
+        let hill = run(2.0, 10, 50.0);
+        let adjusted = AdjustedPace::for_activity(&hill, Some(&streams), None).unwrap();
+        assert_eq!(adjusted.grade_source, GradeSource::Streams);
+        assert_eq!(adjusted.pace_seconds_per_km, 300.0);
+        assert!(adjusted.grade_adjusted_pace_seconds_per_km < 300.0);
+        assert!(adjusted.heat_adjusted_pace_seconds_per_km.is_none());
+
+        let summary = AdjustedPace::for_activity(&hill, None, None).unwrap();
+        assert_eq!(summary.grade_source, GradeSource::ElevationGain);
+        assert_eq!(summary.grade_adjusted_pace_seconds_per_km, 260.9);
+    }
+
+    #[test]
+    fn test_heat_adjusted_pace() {
+        assert!((dew_point(30.0, 50.0) - 18.4).abs() < 0.1);
+        assert_eq!(temperature_factor(None), 1.0);
+        assert!((temperature_factor(Some(-5.0)) - 1.02).abs() < 1e-9);
+
+        let flat = run(10.0, 50, 0.0);
+        // 30°C at 70% humidity: 86°F + 75°F dew point
+        let humid =
+            AdjustedPace::for_activity(&flat, None, Some(&weather(30.0, Some(70.0)))).unwrap();
+        assert_eq!(humid.dew_point_celsius, Some(23.9));
+        assert_eq!(humid.heat_adjusted_pace_seconds_per_km, Some(282.4));
+        assert_eq!(
+            humid.adjusted_pace_seconds_per_km,
+            humid.heat_adjusted_pace_seconds_per_km.unwrap()
+        );
+
+        // Dry heat slows less than humid heat
+        let dry =
+            AdjustedPace::for_activity(&flat, None, Some(&weather(30.0, Some(20.0)))).unwrap();
+        assert!(dry.heat_adjusted_pace_seconds_per_km > humid.heat_adjusted_pace_seconds_per_km);
+
+        // Estimates are ignored
+        let estimated = WeatherConditions {
+            provenance: WeatherProvenance::Estimated,
+            ..weather(30.0, Some(70.0))
+        };
+        let adjusted = AdjustedPace::for_activity(&flat, None, Some(&estimated)).unwrap();
+        assert!(adjusted.heat_adjusted_pace_seconds_per_km.is_none());
+        assert_eq!(adjusted.adjusted_pace_seconds_per_km, 300.0);
+    }
+}
@@ -7,7 +7,9 @@
 //! Activity analyzer for generating intelligent insights
 
 use super::{
+    adjusted_pace::AdjustedPace,
     insights::{ActivityContext, InsightGenerator},
+    patterns::sport_family,
     ActivityIntelligence, ContextualFactors, PerformanceMetrics, PersonalRecord, TimeOfDay,
     TrendDirection, TrendIndicators, ZoneDistribution,
 };
@@ -39,7 +41,7 @@ impl ActivityAnalyzer {
             .generate_insights(activity, context.as_ref());
 
         // Calculate performance metrics
-        let performance = self.calculate_performance_metrics(activity)?;
+        let performance = self.calculate_performance_metrics(activity, context.as_ref())?;
 
         // Determine contextual factors
         let contextual_factors = self.analyze_contextual_factors(activity, &context);
@@ -59,10 +61,11 @@ impl ActivityAnalyzer {
     fn calculate_performance_metrics(
         &self,
         activity: &Activity,
+        context: Option<&ActivityContext>,
     ) -> Result<PerformanceMetrics, AnalysisError> {
         let relative_effort = self.calculate_relative_effort(activity);
         let zone_distribution = self.calculate_zone_distribution(activity);
-        let personal_records = self.detect_personal_records(activity);
+        let personal_records = self.detect_personal_records(activity, context);
         let efficiency_score = self.calculate_efficiency_score(activity);
         let trend_indicators = self.calculate_trend_indicators(activity);
 
@@ -162,7 +165,14 @@ impl ActivityAnalyzer {
     }
 
     /// Detect personal records (simplified version)
-    fn detect_personal_records(&self, activity: &Activity) -> Vec<PersonalRecord> {
+    ///
+    /// Pace records are judged on grade-adjusted pace against the fastest
+    /// recent effort of the same sport.
+    fn detect_personal_records(
+        &self,
+        activity: &Activity,
+        context: Option<&ActivityContext>,
+    ) -> Vec<PersonalRecord> {
         let mut records = Vec::new();
 
         // Example: Distance PR detection (would normally compare with historical data)
@@ -180,17 +190,33 @@ impl ActivityAnalyzer {
             }
         }
 
-        // Example: Speed PR detection
-        if let Some(avg_speed) = activity.average_speed {
-            let pace_per_km = 1000.0 / avg_speed; // seconds per km
-            if pace_per_km < 300.0 {
-                // Under 5 minutes per km
+        // Pace PR detection, adjusted for grade only: recent activities carry
+        // no weather, so heat would only be corrected on one side
+        if let Some(adjusted) = AdjustedPace::for_activity(activity, None, None) {
+            let pace_per_km = adjusted.adjusted_pace_seconds_per_km;
+            let family = sport_family(&activity.sport_type);
+            let previous_best =
+                context
+                    .and_then(|c| c.recent_activities.as_ref())
+                    .and_then(|recent| {
+                        recent
+                            .iter()
+                            .filter(|a| {
+                                a.id != activity.id && sport_family(&a.sport_type) == family
+                            })
+                            .filter_map(|a| AdjustedPace::for_activity(a, None, None))
+                            .map(|a| a.adjusted_pace_seconds_per_km)
+                            .min_by(f64::total_cmp)
+                    });
+            // Without an earlier effort to beat there is no previous best
+            if previous_best.is_none_or(|best| pace_per_km < best) {
                 records.push(PersonalRecord {
-                    record_type: "Fastest Average Pace".to_string(),
+                    record_type: "Fastest Adjusted Pace".to_string(),
                     value: pace_per_km,
                     unit: "seconds/km".to_string(),
-                    previous_best: Some(320.0),
-                    improvement_percentage: Some(((320.0 - pace_per_km) / 320.0 * 100.0) as f32),
+                    previous_best,
+                    improvement_percentage: previous_best
+                        .map(|best| ((best - pace_per_km) / best * 100.0) as f32),
                 });
             }
         }
@@ -212,9 +238,10 @@ impl ActivityAnalyzer {
         }
 
         // Consistency factor (mock calculation)
-        if activity.average_speed.is_some() && activity.max_speed.is_some() {
-            let speed_variance = activity.max_speed.unwrap() - activity.average_speed.unwrap();
-            let consistency = 1.0 - (speed_variance / activity.max_speed.unwrap()).min(1.0) as f32;
+        if let (Some(average_speed), Some(max_speed)) = (activity.average_speed, activity.max_speed)
+        {
+            let speed_variance = max_speed - average_speed;
+            let consistency = 1.0 - (speed_variance / max_speed).min(1.0) as f32;
             efficiency += consistency * 20.0;
         }
 
@@ -276,8 +303,10 @@ impl ActivityAnalyzer {
         // Activity type with weather context - use the display_name method
         let activity_type = activity.sport_type.display_name();
 
-        // Add weather context if available
-        let weather_context = if let Some(weather) = &context.weather {
+        // Add weather context if measured; estimates aren't stated as fact
+        let weather_context = if let Some(weather) =
+            context.weather.as_ref().filter(|w| w.is_measured())
+        {
             match weather.conditions.to_lowercase().as_str() {
                 c if c.contains("rain")
                     || c.contains("shower")
@@ -411,6 +440,7 @@ pub enum AnalysisError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::intelligence::{WeatherConditions, WeatherProvenance};
     use crate::models::{Activity, SportType};
     use chrono::Utc;
 
@@ -435,6 +465,8 @@ mod tests {
             region: None,
             country: None,
             trail_name: None,
+            gear_id: None,
+            summary_polyline: None,
         }
     }
 
@@ -478,12 +510,69 @@ mod tests {
         let mut activity = create_test_activity();
         activity.distance_meters = Some(25000.0); // Long distance for PR
 
-        let records = analyzer.detect_personal_records(&activity);
+        let records = analyzer.detect_personal_records(&activity, None);
         assert!(!records.is_empty());
 
         let distance_pr = &records[0];
         assert_eq!(distance_pr.record_type, "Longest Distance");
         assert_eq!(distance_pr.value, 25.0); // 25km converted from 25000m
+
+        // Nothing to compare the pace against
+        let pace_pr = records
+            .iter()
+            .find(|r| r.record_type == "Fastest Adjusted Pace")
+            .unwrap();
+        assert_eq!(pace_pr.previous_best, None);
+        assert_eq!(pace_pr.improvement_percentage, None);
+    }
+
+    #[test]
+    fn test_adjusted_pace_record() {
+        let analyzer = ActivityAnalyzer::new();
+        // 10 km in 52 minutes with 300 m of climbing
+        let hilly = Activity {
+            id: "hilly".to_string(),
+            sport_type: SportType::Run,
+            duration_seconds: 3120,
+            distance_meters: Some(10_000.0),
+            elevation_gain: Some(300.0),
+            ..create_test_activity()
+        };
+        // 10 km in 50 minutes on the flat
+        let flat = Activity {
+            id: "flat".to_string(),
+            sport_type: SportType::Run,
+            duration_seconds: 3000,
+            distance_meters: Some(10_000.0),
+            elevation_gain: Some(0.0),
+            ..create_test_activity()
+        };
+        // Measured heat on the day only applies to the hilly run, so it is
+        // left out of the comparison
+        let context = ActivityContext {
+            location: None,
+            weather: Some(WeatherConditions {
+                temperature_celsius: 30.0,
+                humidity_percentage: Some(70.0),
+                wind_speed_kmh: None,
+                conditions: "sunny".to_string(),
+                provenance: WeatherProvenance::Measured,
+            }),
+            recent_activities: Some(vec![flat]),
+            athlete_goals: None,
+            historical_data: None,
+        };
+
+        // Slower than the flat run, but faster once the climbing is counted
+        let records = analyzer.detect_personal_records(&hilly, Some(&context));
+        let pace_pr = records
+            .iter()
+            .find(|r| r.record_type == "Fastest Adjusted Pace")
+            .unwrap();
+        assert_eq!(pace_pr.previous_best, Some(300.0));
+        let grade_adjusted = AdjustedPace::for_activity(&hilly, None, None).unwrap();
+        assert_eq!(pace_pr.value, grade_adjusted.adjusted_pace_seconds_per_km);
+        assert!(pace_pr.value < 300.0);
     }
 
     #[test]
@@ -0,0 +1,777 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Anomaly detection
+//!
+//! Flags activities that stand out against the user's own recent training,
+//! and recordings that look broken:
+//!
+//! | Anomaly              | Needs   | Flagged when                                                 |
+//! |----------------------|---------|--------------------------------------------------------------|
+//! | `heart_rate_at_pace` | history | heart rate well above what the baseline predicts for the pace |
+//! | `performance_drop`   | history | climb-adjusted pace well behind similar-distance efforts     |
+//! | `cardiac_drift`      | streams | pace:heart rate decoupling beyond the usual                  |
+//! | `gps_teleport`       | streams | distance jumps faster than the sport allows                  |
+//! | `heart_rate_dropout` | streams | heart rate reads near zero for a stretch                     |
+//! | `cadence_lock`       | streams | heart rate follows cadence for minutes                       |
+//! | `power_spike`        | streams | power readings no rider produces                             |
+//!
+//! The baseline is the same sport family's activities in the
+//! [`BASELINE_DAYS`] before each activity, and "well above" means at least
+//! [`WARNING_Z_SCORE`] standard deviations out. Every anomaly carries a
+//! severity and a description explaining what was seen and what it means.
+
+use super::comparison::EffortValues;
+use super::insights::InsightGenerator;
+use super::patterns::sport_family;
+use super::{Anomaly, Confidence, InsightSeverity};
+use crate::models::{Activity, ActivityStreams, SportType};
+use crate::providers::connections::ProviderConnections;
+use crate::providers::merge::{activity_streams, all_provider_activities};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Days of earlier activities in the baseline
+pub const BASELINE_DAYS: i64 = 42;
+/// Standard deviations from the baseline for a warning
+pub const WARNING_Z_SCORE: f64 = 2.0;
+/// Standard deviations from the baseline for a critical anomaly
+const CRITICAL_Z_SCORE: f64 = 3.0;
+/// Baseline activities needed before heart rate at pace is judged
+const MIN_BASELINE_ACTIVITIES: usize = 5;
+/// Baselines this large give high confidence
+const FULL_BASELINE_ACTIVITIES: usize = 10;
+/// Similar-distance efforts needed to judge a performance drop
+const MIN_SIMILAR_EFFORTS: usize = 3;
+/// Distance difference still counted as a similar effort
+const SIMILAR_DISTANCE_TOLERANCE: f64 = 0.25;
+/// How far outside the baseline's paces heart rate is still predicted
+const PACE_EXTRAPOLATION: f64 = 0.1;
+/// Smallest spread assumed for heart rate at a pace, in bpm
+const MIN_HEART_RATE_SPREAD: f64 = 3.0;
+/// Smallest spread assumed for pace, as a share of the average
+const MIN_PACE_SPREAD: f64 = 0.03;
+/// Slowdown below which a drop isn't reported, however unusual
+const MIN_PERFORMANCE_DROP: f64 = 0.05;
+/// Pace:heart rate decoupling notable without a personal baseline, in percent
+const DRIFT_THRESHOLD_PERCENT: f64 = 5.0;
+/// Streamed efforts needed for a personal drift baseline
+const MIN_DRIFT_BASELINE: usize = 3;
+/// Shortest recording judged for drift
+const MIN_DRIFT_SECONDS: u32 = 20 * 60;
+/// Heart rate readings below this are strap dropouts
+const DROPOUT_HEART_RATE: u32 = 40;
+/// Shortest dropout reported
+const MIN_DROPOUT_SECONDS: u32 = 10;
+/// Share of the recording lost to dropouts that makes them a warning
+const DROPOUT_WARNING_SHARE: f64 = 0.05;
+/// Heart rate within this many bpm of cadence counts as locked onto it
+const CADENCE_LOCK_TOLERANCE: u32 = 3;
+/// Shortest lock reported
+const MIN_CADENCE_LOCK_SECONDS: u32 = 5 * 60;
+/// Power readings above this are sensor spikes
+const MAX_PLAUSIBLE_POWER: u32 = 2000;
+/// Maximum heart rate readings above this are implausible
+const MAX_PLAUSIBLE_HEART_RATE: u32 = 220;
+/// Share of the distance from GPS jumps that makes them critical
+const TELEPORT_CRITICAL_SHARE: f64 = 0.05;
+/// Activities fetched for baselines
+const ACTIVITY_LIMIT: usize = 1000;
+/// Activities whose streams are fetched
+const STREAM_ACTIVITY_LIMIT: usize = 10;
+/// Days scanned when no activity is given
+pub const DEFAULT_DAYS: i64 = 30;
+/// Longest period scanned
+const MAX_DAYS: i64 = 365;
+
+/// Every anomaly found in one activity
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityAnomalies {
+    pub activity_id: String,
+    pub name: String,
+    pub sport_type: SportType,
+    pub start_date: DateTime<Utc>,
+    /// Whether recorded streams were checked as well as the summary
+    pub streams_analyzed: bool,
+    pub anomalies: Vec<Anomaly>,
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+fn mean_and_spread(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+    (mean, variance.sqrt())
+}
+
+fn describe_pace(seconds_per_km: f64) -> String {
+    let seconds = seconds_per_km.round() as u64;
+    format!("{}:{:02}/km", seconds / 60, seconds % 60)
+}
+
+fn describe_duration(seconds: u32) -> String {
+    if seconds >= 60 {
+        format!("{} min {} s", seconds / 60, seconds % 60)
+    } else {
+        format!("{} s", seconds)
+    }
+}
+
+fn severity_for(z_score: f64) -> InsightSeverity {
+    if z_score >= CRITICAL_Z_SCORE {
+        InsightSeverity::Critical
+    } else {
+        InsightSeverity::Warning
+    }
+}
+
+fn baseline_confidence(count: usize) -> Confidence {
+    if count >= FULL_BASELINE_ACTIVITIES {
+        Confidence::High
+    } else {
+        Confidence::Medium
+    }
+}
+
+/// Fastest speed in m/s a recording of the sport can plausibly show
+pub fn max_plausible_speed(sport: &SportType) -> f64 {
+    match sport {
+        SportType::Run => 12.0,  // ~43 km/h
+        SportType::Ride => 25.0, // ~90 km/h
+        SportType::Swim => 3.0,  // ~11 km/h
+        _ => 30.0,
+    }
+}
+
+/// The same sport family's activities in the [`BASELINE_DAYS`] before
+/// `activity`
+pub fn baseline<'a>(activity: &Activity, history: &'a [Activity]) -> Vec<&'a Activity> {
+    let family = sport_family(&activity.sport_type);
+    let start = activity.start_date - Duration::days(BASELINE_DAYS);
+    history
+        .iter()
+        .filter(|a| a.id != activity.id)
+        .filter(|a| a.start_date >= start && a.start_date < activity.start_date)
+        .filter(|a| sport_family(&a.sport_type) == family)
+        .collect()
+}
+
+/// Summary values no sensor should produce
+pub fn implausible_values(activity: &Activity) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+
+    if let Some(max_hr) = activity.max_heart_rate {
+        if max_hr > MAX_PLAUSIBLE_HEART_RATE {
+            anomalies.push(Anomaly {
+                anomaly_type: "unrealistic_heart_rate".to_string(),
+                description: "Maximum heart rate seems unusually high".to_string(),
+                severity: InsightSeverity::Warning,
+                confidence: Confidence::High,
+                affected_metric: "max_heartrate".to_string(),
+                expected_value: Some(200.0),
+                actual_value: Some(f64::from(max_hr)),
+            });
+        }
+    }
+
+    if let Some(max_speed) = activity.max_speed {
+        let expected_max_speed = max_plausible_speed(&activity.sport_type);
+        if max_speed > expected_max_speed {
+            anomalies.push(Anomaly {
+                anomaly_type: "unrealistic_speed".to_string(),
+                description: format!(
+                    "Maximum speed seems unusually high for {:?}",
+                    activity.sport_type
+                ),
+                severity: InsightSeverity::Warning,
+                confidence: Confidence::Medium,
+                affected_metric: "max_speed".to_string(),
+                expected_value: Some(expected_max_speed),
+                actual_value: Some(max_speed),
+            });
+        }
+    }
+
+    anomalies
+}
+
+/// Average heart rate against what the baseline's heart rate to pace line
+/// predicts at the activity's climb-adjusted pace
+///
+/// Paces more than [`PACE_EXTRAPOLATION`] outside those in the baseline
+/// aren't predicted.
+pub fn heart_rate_at_pace(activity: &Activity, baseline: &[&Activity]) -> Option<Anomaly> {
+    let point = |a: &Activity| {
+        let values = EffortValues::from_activity(a, None, None);
+        Some((
+            1000.0 / values.adjusted_pace_seconds_per_km?,
+            values.average_heart_rate?,
+        ))
+    };
+    let (speed, heart_rate) = point(activity)?;
+    let points: Vec<(f64, f64)> = baseline.iter().filter_map(|a| point(a)).collect();
+    if points.len() < MIN_BASELINE_ACTIVITIES {
+        return None;
+    }
+    let slowest = points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let fastest = points.iter().map(|p| p.0).fold(0.0, f64::max);
+    if speed < slowest * (1.0 - PACE_EXTRAPOLATION) || speed > fastest * (1.0 + PACE_EXTRAPOLATION)
+    {
+        return None;
+    }
+
+    // Least squares line of heart rate against speed
+    let n = points.len() as f64;
+    let mean_speed = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_hr = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_speed).powi(2)).sum();
+    let sxy: f64 = points
+        .iter()
+        .map(|p| (p.0 - mean_speed) * (p.1 - mean_hr))
+        .sum();
+    let slope = if sxx > f64::EPSILON { sxy / sxx } else { 0.0 };
+    let predict = |s: f64| mean_hr + slope * (s - mean_speed);
+    let spread = (points
+        .iter()
+        .map(|&(s, hr)| (hr - predict(s)).powi(2))
+        .sum::<f64>()
+        / (n - 2.0))
+        .sqrt()
+        .max(MIN_HEART_RATE_SPREAD);
+
+    let expected = predict(speed);
+    let z_score = (heart_rate - expected) / spread;
+    if z_score < WARNING_Z_SCORE {
+        return None;
+    }
+    Some(Anomaly {
+        anomaly_type: "heart_rate_at_pace".to_string(),
+        description: format!(
+            "Heart rate averaged {:.0} bpm, {:.0} bpm above the {:.0} bpm your last {} weeks predict at {} adjusted pace; fatigue, illness, heat or dehydration are the usual causes",
+            heart_rate,
+            heart_rate - expected,
+            expected,
+            BASELINE_DAYS / 7,
+            describe_pace(1000.0 / speed)
+        ),
+        severity: severity_for(z_score),
+        confidence: baseline_confidence(points.len()),
+        affected_metric: "average_heartrate".to_string(),
+        expected_value: Some(round(expected, 1)),
+        actual_value: Some(heart_rate),
+    })
+}
+
+/// Climb-adjusted pace against the baseline's efforts of a similar distance
+pub fn performance_drop(activity: &Activity, baseline: &[&Activity]) -> Option<Anomaly> {
+    let pace = EffortValues::from_activity(activity, None, None).adjusted_pace_seconds_per_km?;
+    let distance = activity.distance_meters?;
+    let paces: Vec<f64> = baseline
+        .iter()
+        .filter(|a| {
+            a.distance_meters
+                .is_some_and(|d| ((d - distance) / distance).abs() <= SIMILAR_DISTANCE_TOLERANCE)
+        })
+        .filter_map(|a| EffortValues::from_activity(a, None, None).adjusted_pace_seconds_per_km)
+        .collect();
+    if paces.len() < MIN_SIMILAR_EFFORTS {
+        return None;
+    }
+
+    let (mean, spread) = mean_and_spread(&paces);
+    let z_score = (pace - mean) / spread.max(mean * MIN_PACE_SPREAD);
+    let drop = pace / mean - 1.0;
+    if z_score < WARNING_Z_SCORE || drop < MIN_PERFORMANCE_DROP {
+        return None;
+    }
+    Some(Anomaly {
+        anomaly_type: "performance_drop".to_string(),
+        description: format!(
+            "Adjusted pace of {} is {:.0}% slower than the {} average of {} similar efforts in the previous {} weeks; a sudden drop can mean accumulated fatigue or oncoming illness",
+            describe_pace(pace),
+            drop * 100.0,
+            describe_pace(mean),
+            paces.len(),
+            BASELINE_DAYS / 7
+        ),
+        severity: severity_for(z_score),
+        confidence: baseline_confidence(paces.len()),
+        affected_metric: "adjusted_pace_seconds_per_km".to_string(),
+        expected_value: Some(round(mean, 1)),
+        actual_value: Some(pace),
+    })
+}
+
+/// Pace:heart rate decoupling in percent: how much less distance each
+/// heartbeat bought in the second half of the recording than in the first
+///
+/// Needs heart rate and distance streams over at least 20 minutes.
+pub fn decoupling_percent(streams: &ActivityStreams) -> Option<f64> {
+    let time = &streams.time;
+    let heart_rate = streams.heart_rate.as_ref()?;
+    let distance = streams.distance.as_ref()?;
+    if heart_rate.len() != time.len() || distance.len() != time.len() {
+        return None;
+    }
+    let (&start, &end) = (time.first()?, time.last()?);
+    if end.saturating_sub(start) < MIN_DRIFT_SECONDS {
+        return None;
+    }
+    let middle = time.partition_point(|t| *t < start + (end - start) / 2);
+
+    let efficiency = |range: std::ops::Range<usize>| {
+        let (first, last) = (range.start, range.end.checked_sub(1)?);
+        let seconds = f64::from(time[last].checked_sub(time[first])?);
+        let meters = distance[last] - distance[first];
+        let readings: Vec<f64> = heart_rate[range]
+            .iter()
+            .filter(|hr| **hr >= DROPOUT_HEART_RATE)
+            .map(|hr| f64::from(*hr))
+            .collect();
+        if seconds <= 0.0 || meters <= 0.0 || readings.is_empty() {
+            return None;
+        }
+        let average = readings.iter().sum::<f64>() / readings.len() as f64;
+        Some(meters / seconds / average)
+    };
+    let first = efficiency(0..middle)?;
+    let second = efficiency(middle..time.len())?;
+    Some((first - second) / first * 100.0)
+}
+
+/// Decoupling beyond the user's usual, or beyond 5% without enough
+/// `baseline_drifts` from other streamed efforts
+pub fn cardiac_drift(streams: &ActivityStreams, baseline_drifts: &[f64]) -> Option<Anomaly> {
+    let drift = decoupling_percent(streams)?;
+    let usual = (baseline_drifts.len() >= MIN_DRIFT_BASELINE).then(|| {
+        let (mean, spread) = mean_and_spread(baseline_drifts);
+        (
+            mean,
+            (mean + WARNING_Z_SCORE * spread).max(DRIFT_THRESHOLD_PERCENT),
+        )
+    });
+    let threshold = usual.map_or(DRIFT_THRESHOLD_PERCENT, |(_, threshold)| threshold);
+    if drift <= threshold {
+        return None;
+    }
+
+    let description = match usual {
+        Some((mean, _)) => format!(
+            "Heart rate drifted {:.1}% against pace from the first half to the second, well beyond your usual {:.1}%; heat, dehydration or going out too fast cause most drift",
+            drift, mean
+        ),
+        None => format!(
+            "Heart rate drifted {:.1}% against pace from the first half to the second; a well-paced aerobic effort stays under {:.0}%",
+            drift, DRIFT_THRESHOLD_PERCENT
+        ),
+    };
+    Some(Anomaly {
+        anomaly_type: "cardiac_drift".to_string(),
+        description,
+        severity: if drift >= 2.0 * threshold {
+            InsightSeverity::Critical
+        } else {
+            InsightSeverity::Warning
+        },
+        confidence: if usual.is_some() {
+            Confidence::High
+        } else {
+            Confidence::Medium
+        },
+        affected_metric: "heart_rate".to_string(),
+        expected_value: Some(round(threshold, 1)),
+        actual_value: Some(round(drift, 1)),
+    })
+}
+
+/// Distance samples that jump faster than the sport allows
+pub fn gps_teleports(sport: &SportType, streams: &ActivityStreams) -> Option<Anomaly> {
+    let distance = streams.distance.as_ref()?;
+    let limit = max_plausible_speed(sport);
+    let jumps: Vec<(f64, u32)> = streams
+        .time
+        .windows(2)
+        .zip(distance.windows(2))
+        .filter_map(|(t, d)| {
+            let seconds = t[1].checked_sub(t[0]).filter(|s| *s > 0)?;
+            let meters = d[1] - d[0];
+            (meters / f64::from(seconds) > limit).then_some((meters, seconds))
+        })
+        .collect();
+    let &(meters, seconds) = jumps.iter().max_by(|a, b| a.0.total_cmp(&b.0))?;
+
+    let fastest = jumps
+        .iter()
+        .map(|(m, s)| m / f64::from(*s))
+        .fold(0.0, f64::max);
+    let spurious: f64 = jumps.iter().map(|(m, s)| m - limit * f64::from(*s)).sum();
+    let total = distance.last().copied().unwrap_or(0.0) - distance[0];
+    let count = if jumps.len() > 1 {
+        format!(" ({} jumps in all)", jumps.len())
+    } else {
+        String::new()
+    };
+    Some(Anomaly {
+        anomaly_type: "gps_teleport".to_string(),
+        description: format!(
+            "GPS jumped {:.0} m in {} s{}; about {:.0} m of the recorded distance is likely spurious, inflating distance and pace",
+            meters, seconds, count, spurious
+        ),
+        severity: if total > 0.0 && spurious / total >= TELEPORT_CRITICAL_SHARE {
+            InsightSeverity::Critical
+        } else {
+            InsightSeverity::Warning
+        },
+        confidence: Confidence::High,
+        affected_metric: "distance".to_string(),
+        expected_value: Some(limit),
+        actual_value: Some(round(fastest, 1)),
+    })
+}
+
+/// Stretches where the heart rate strap lost contact and read near zero
+pub fn heart_rate_dropouts(streams: &ActivityStreams) -> Option<Anomaly> {
+    let heart_rate = streams.heart_rate.as_ref()?;
+    let time = &streams.time[..streams.time.len().min(heart_rate.len())];
+    let last = time.len().checked_sub(1)?;
+
+    let mut stretches = Vec::new();
+    let mut start = None;
+    for (i, reading) in heart_rate.iter().enumerate().take(time.len()) {
+        match (*reading < DROPOUT_HEART_RATE, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                stretches.push(time[i] - time[s]);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        stretches.push(time[last] - time[s]);
+    }
+    stretches.retain(|seconds| *seconds >= MIN_DROPOUT_SECONDS);
+    if stretches.is_empty() {
+        return None;
+    }
+
+    let lost: u32 = stretches.iter().sum();
+    let share = f64::from(lost) / f64::from((time[last] - time[0]).max(1));
+    let times = match stretches.len() {
+        1 => "once".to_string(),
+        n => format!("{} times", n),
+    };
+    Some(Anomaly {
+        anomaly_type: "heart_rate_dropout".to_string(),
+        description: format!(
+            "Heart rate strap dropped out {} for {} in total ({:.0}% of the recording); averages and zones from this activity understate the effort",
+            times,
+            describe_duration(lost),
+            share * 100.0
+        ),
+        severity: if share >= DROPOUT_WARNING_SHARE {
+            InsightSeverity::Warning
+        } else {
+            InsightSeverity::Info
+        },
+        confidence: Confidence::High,
+        affected_metric: "heart_rate".to_string(),
+        expected_value: None,
+        actual_value: Some(f64::from(lost)),
+    })
+}
+
+/// Heart rate tracking cadence, an optical sensor counting footstrikes or
+/// pedal strokes instead of heartbeats
+///
+/// Running cadence is reported per leg, so it is doubled first.
+pub fn cadence_lock(sport: &SportType, streams: &ActivityStreams) -> Option<Anomaly> {
+    let heart_rate = streams.heart_rate.as_ref()?;
+    let cadence = streams.cadence.as_ref()?;
+    let factor = if sport_family(sport) == "run" { 2 } else { 1 };
+
+    let mut longest = 0;
+    let mut start = None;
+    for (i, &t) in streams.time.iter().enumerate() {
+        let (Some(&hr), Some(&steps)) = (heart_rate.get(i), cadence.get(i)) else {
+            break;
+        };
+        let locked = steps > 0 && hr.abs_diff(steps * factor) <= CADENCE_LOCK_TOLERANCE;
+        match (locked, start) {
+            (true, None) => start = Some(t),
+            (true, Some(s)) => longest = longest.max(t - s),
+            (false, _) => start = None,
+        }
+    }
+    if longest < MIN_CADENCE_LOCK_SECONDS {
+        return None;
+    }
+    Some(Anomaly {
+        anomaly_type: "cadence_lock".to_string(),
+        description: format!(
+            "Heart rate followed cadence within {} bpm for {} straight, a sign of an optical sensor picking up movement instead of pulse; heart rate from that stretch is unreliable",
+            CADENCE_LOCK_TOLERANCE,
+            describe_duration(longest)
+        ),
+        severity: InsightSeverity::Warning,
+        confidence: Confidence::Medium,
+        affected_metric: "heart_rate".to_string(),
+        expected_value: None,
+        actual_value: Some(f64::from(longest)),
+    })
+}
+
+/// Power readings above [`MAX_PLAUSIBLE_POWER`]
+pub fn power_spikes(streams: &ActivityStreams) -> Option<Anomaly> {
+    let power = streams.power.as_ref()?;
+    let spikes = power.iter().filter(|w| **w > MAX_PLAUSIBLE_POWER).count();
+    let peak = power.iter().copied().max().filter(|_| spikes > 0)?;
+    Some(Anomaly {
+        anomaly_type: "power_spike".to_string(),
+        description: format!(
+            "{} power reading{} above {} W, peaking at {} W; peak power and power records from this activity are unreliable",
+            spikes,
+            if spikes == 1 { "" } else { "s" },
+            MAX_PLAUSIBLE_POWER,
+            peak
+        ),
+        severity: InsightSeverity::Warning,
+        confidence: Confidence::High,
+        affected_metric: "power".to_string(),
+        expected_value: Some(f64::from(MAX_PLAUSIBLE_POWER)),
+        actual_value: Some(f64::from(peak)),
+    })
+}
+
+/// Check one activity against its baseline in `history` and, when
+/// recorded, its streams
+///
+/// `baseline_drifts` are the decoupling of the sport's other streamed
+/// efforts.
+pub fn find_anomalies(
+    activity: &Activity,
+    history: &[Activity],
+    streams: Option<&ActivityStreams>,
+    baseline_drifts: &[f64],
+) -> ActivityAnomalies {
+    let baseline = baseline(activity, history);
+    let mut anomalies = implausible_values(activity);
+    anomalies.extend(heart_rate_at_pace(activity, &baseline));
+    anomalies.extend(performance_drop(activity, &baseline));
+    if let Some(streams) = streams {
+        anomalies.extend(cardiac_drift(streams, baseline_drifts));
+        anomalies.extend(gps_teleports(&activity.sport_type, streams));
+        anomalies.extend(heart_rate_dropouts(streams));
+        anomalies.extend(cadence_lock(&activity.sport_type, streams));
+        anomalies.extend(power_spikes(streams));
+    }
+
+    ActivityAnomalies {
+        activity_id: activity.id.clone(),
+        name: activity.name.clone(),
+        sport_type: activity.sport_type.clone(),
+        start_date: activity.start_date,
+        streams_analyzed: streams.is_some(),
+        anomalies,
+    }
+}
+
+/// Result of the `detect_anomalies` tool for a user
+///
+/// Checks `activity_id`, or every activity of the last `days` (up to a
+/// year) and lists those with anomalies. Streams are fetched for the most recent activities
+/// checked; for a single activity, also for its baseline so cardiac drift
+/// can be judged against the user's usual.
+pub async fn anomalies_for_user(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    activity_id: Option<&str>,
+    days: i64,
+) -> Result<Value> {
+    let days = days.clamp(1, MAX_DAYS);
+    let history = all_provider_activities(connections, user_id, ACTIVITY_LIMIT).await?;
+    let targets: Vec<&Activity> = match activity_id {
+        Some(id) => vec![history
+            .iter()
+            .find(|a| a.id == id)
+            .ok_or_else(|| anyhow!("Activity '{}' not found", id))?],
+        None => {
+            let start = Utc::now() - Duration::days(days);
+            history.iter().filter(|a| a.start_date >= start).collect()
+        }
+    };
+
+    let mut streamed: Vec<&Activity> = targets
+        .iter()
+        .take(STREAM_ACTIVITY_LIMIT)
+        .copied()
+        .collect();
+    if let [activity] = targets.as_slice() {
+        streamed.extend(
+            baseline(activity, &history)
+                .into_iter()
+                .filter(|a| a.duration_seconds >= u64::from(MIN_DRIFT_SECONDS))
+                .take(STREAM_ACTIVITY_LIMIT - 1),
+        );
+    }
+    let streams = activity_streams(connections, user_id, &streamed).await;
+    let drifts: Vec<(&str, &str, f64)> = streams
+        .iter()
+        .filter_map(|(id, recorded)| {
+            let activity = streamed.iter().find(|a| &a.id == id)?;
+            Some((
+                id.as_str(),
+                sport_family(&activity.sport_type),
+                decoupling_percent(recorded)?,
+            ))
+        })
+        .collect();
+
+    let generator = InsightGenerator::new();
+    let mut reports = Vec::new();
+    let mut insights = Vec::new();
+    for activity in &targets {
+        let recorded = streams
+            .iter()
+            .find(|(id, _)| *id == activity.id)
+            .map(|(_, s)| s);
+        let family = sport_family(&activity.sport_type);
+        let baseline_drifts: Vec<f64> = drifts
+            .iter()
+            .filter(|(id, f, _)| *id != activity.id && *f == family)
+            .map(|(_, _, drift)| *drift)
+            .collect();
+
+        let report = find_anomalies(activity, &history, recorded, &baseline_drifts);
+        insights.extend(generator.generate_anomaly_insights(activity, &report.anomalies));
+        if activity_id.is_some() || !report.anomalies.is_empty() {
+            reports.push(report);
+        }
+    }
+
+    Ok(json!({
+        "days": activity_id.is_none().then_some(days),
+        "activities_analyzed": targets.len(),
+        "streams_analyzed": streams.len(),
+        "activities_with_anomalies": reports.iter().filter(|r| !r.anomalies.is_empty()).count(),
+        "activities": reports,
+        "insights": insights,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(id: &str, days_ago: i64, minutes: u64, heart_rate: u32) -> Activity {
+        Activity {
+            id: id.to_string(),
+            start_date: Utc::now() - Duration::days(days_ago),
+            duration_seconds: minutes * 60,
+            distance_meters: Some(10_000.0),
+            elevation_gain: Some(0.0),
+            average_heart_rate: Some(heart_rate),
+            max_heart_rate: Some(180),
+            ..Default::default()
+        }
+    }
+
+    fn steady(minutes: u32, heart_rate: impl Fn(u32) -> u32) -> ActivityStreams {
+        let time: Vec<u32> = (0..=minutes * 6).map(|i| i * 10).collect();
+        ActivityStreams {
+            heart_rate: Some(time.iter().map(|t| heart_rate(*t)).collect()),
+            distance: Some(time.iter().map(|t| f64::from(*t) * 3.0).collect()),
+            time,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_history_anomalies() {
+        // Six weeks of 10k runs between 48 and 52 minutes
+        let mut history: Vec<Activity> = (0..8)
+            .map(|i| {
+                let minutes = 48 + (i % 5) as u64;
+                run(
+                    &format!("base-{}", i),
+                    2 + i * 4,
+                    minutes,
+                    140 + i as u32 % 3,
+                )
+            })
+            .collect();
+        history.push(run("hot", 1, 50, 162));
+        history.push(run("slow", 0, 60, 141));
+
+        let hot = find_anomalies(&history[8], &history, None, &[]);
+        assert_eq!(hot.anomalies.len(), 1);
+        assert_eq!(hot.anomalies[0].anomaly_type, "heart_rate_at_pace");
+        assert!(matches!(
+            hot.anomalies[0].severity,
+            InsightSeverity::Critical
+        ));
+
+        let slow = find_anomalies(&history[9], &history, None, &[]);
+        assert_eq!(slow.anomalies.len(), 1);
+        assert_eq!(slow.anomalies[0].anomaly_type, "performance_drop");
+        assert!(slow.anomalies[0].description.contains("21% slower"));
+
+        let usual = find_anomalies(&history[0], &history, None, &[]);
+        assert!(usual.anomalies.is_empty());
+    }
+
+    #[test]
+    fn test_stream_anomalies() {
+        // 40 minutes at a constant speed with heart rate climbing 150 to 170
+        let drifting = steady(40, |t| 150 + t / 120);
+        let drift = decoupling_percent(&drifting).unwrap();
+        assert!(drift > 5.0 && drift < 10.0);
+        assert!(cardiac_drift(&drifting, &[]).is_some());
+        assert!(cardiac_drift(&drifting, &[6.0, 7.0, 9.0]).is_none());
+
+        let mut broken = steady(40, |t| if (600..660).contains(&t) { 0 } else { 150 });
+        broken.distance.as_mut().unwrap()[100] += 400.0;
+        broken.cadence = Some(
+            broken
+                .time
+                .iter()
+                .map(|t| if *t >= 1200 { 76 } else { 85 })
+                .collect(),
+        );
+        for (hr, t) in broken
+            .heart_rate
+            .as_mut()
+            .unwrap()
+            .iter_mut()
+            .zip(&broken.time)
+        {
+            if *t >= 1200 {
+                *hr = 153;
+            }
+        }
+
+        let found = find_anomalies(&run("broken", 0, 40, 150), &[], Some(&broken), &[]);
+        let types: Vec<&str> = found
+            .anomalies
+            .iter()
+            .map(|a| a.anomaly_type.as_str())
+            .collect();
+        assert_eq!(
+            types,
+            ["gps_teleport", "heart_rate_dropout", "cadence_lock"]
+        );
+        assert!(found.anomalies[1]
+            .description
+            .contains("once for 1 min 0 s"));
+    }
+}
@@ -0,0 +1,804 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Activity comparison
+//!
+//! Compares one activity with a baseline drawn from the user's history:
+//!
+//! | `comparison_type`    | Baseline                                                |
+//! |----------------------|---------------------------------------------------------|
+//! | `similar_activities` | the `count` efforts closest in distance, time and climb |
+//! | `personal_best`      | the fastest effort within 5% of the activity's distance |
+//! | `average`            | every effort within 20% of the activity's distance      |
+//! | `recent`             | the `count` latest efforts before the activity          |
+//! | `activity`           | one other activity, named by `compare_to`               |
+//!
+//! Only activities of the same sport family are compared. Pace, heart rate,
+//! efficiency factor, elevation and weather are reported for each side, and
+//! pace is also adjusted for grade and heat (see [`super::adjusted_pace`]) so
+//! that a hilly summer run can be weighed against a flat winter one. Personal
+//! bests are judged on the adjusted pace.
+
+use super::adjusted_pace::{measured_weather, AdjustedPace};
+use super::patterns::sport_family;
+use super::WeatherConditions;
+use crate::models::{Activity, ActivityStreams, SportType};
+use crate::providers::merge::paged_activities;
+use crate::providers::FitnessProvider;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Efforts compared by `similar_activities` and `recent` when no count is given
+const DEFAULT_COUNT: usize = 5;
+/// Most efforts a comparison may average
+const MAX_COUNT: usize = 20;
+/// Most activities of history searched for comparable efforts
+const HISTORY_LIMIT: usize = 1000;
+/// Distance tolerance for efforts that count toward a personal best
+const PERSONAL_BEST_TOLERANCE: f64 = 0.05;
+/// Distance tolerance for efforts that count toward the average
+const AVERAGE_TOLERANCE: f64 = 0.2;
+
+/// How the baseline is chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonType {
+    SimilarActivities,
+    PersonalBest,
+    Average,
+    Recent,
+    Activity,
+}
+
+impl ComparisonType {
+    pub const ALL: [ComparisonType; 5] = [
+        Self::SimilarActivities,
+        Self::PersonalBest,
+        Self::Average,
+        Self::Recent,
+        Self::Activity,
+    ];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::SimilarActivities => "similar_activities",
+            Self::PersonalBest => "personal_best",
+            Self::Average => "average",
+            Self::Recent => "recent",
+            Self::Activity => "activity",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|comparison| comparison.as_str() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(ComparisonType::as_str).collect();
+                anyhow!(
+                    "Unknown comparison_type '{}'. Use one of: {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Parameters of the `compare_activities` tool
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonRequest {
+    pub activity_id: String,
+    pub comparison_type: ComparisonType,
+    /// Activity compared against by the `activity` comparison
+    pub compare_to: Option<String>,
+    /// Efforts in the `similar_activities` and `recent` baselines
+    pub count: usize,
+}
+
+impl ComparisonRequest {
+    /// Read and check the tool arguments
+    ///
+    /// `compare_to` implies the `activity` comparison; otherwise the type
+    /// defaults to `similar_activities`.
+    pub fn from_args(args: &Value) -> Result<Self> {
+        let activity_id = args["activity_id"]
+            .as_str()
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| anyhow!("Missing required parameter: activity_id"))?
+            .to_string();
+        let compare_to = args["compare_to"].as_str().map(str::to_string);
+
+        let comparison_type = match (args["comparison_type"].as_str(), &compare_to) {
+            (None, Some(_)) => ComparisonType::Activity,
+            (None, None) => ComparisonType::SimilarActivities,
+            (Some(name), _) => ComparisonType::parse(name)?,
+        };
+        match (comparison_type, &compare_to) {
+            (ComparisonType::Activity, None) => {
+                return Err(anyhow!("The 'activity' comparison needs compare_to"));
+            }
+            (ComparisonType::Activity, Some(_)) | (_, None) => {}
+            (other, Some(_)) => {
+                return Err(anyhow!(
+                    "compare_to can't be combined with the '{}' comparison",
+                    other.as_str()
+                ));
+            }
+        }
+
+        let count = match args["count"].as_u64() {
+            Some(count) if (1..=MAX_COUNT as u64).contains(&count) => count as usize,
+            Some(_) => return Err(anyhow!("count must be between 1 and {}", MAX_COUNT)),
+            None => DEFAULT_COUNT,
+        };
+
+        Ok(Self {
+            activity_id,
+            comparison_type,
+            compare_to,
+            count,
+        })
+    }
+}
+
+/// Comparable figures of one effort or of an averaged baseline
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EffortValues {
+    pub distance_meters: Option<f64>,
+    pub duration_seconds: f64,
+    pub pace_seconds_per_km: Option<f64>,
+    /// Pace on flat ground
+    pub grade_adjusted_pace_seconds_per_km: Option<f64>,
+    /// Pace in ideal conditions, when measured weather is known
+    pub heat_adjusted_pace_seconds_per_km: Option<f64>,
+    /// Pace on flat ground in ideal conditions
+    pub adjusted_pace_seconds_per_km: Option<f64>,
+    pub average_heart_rate: Option<f64>,
+    /// Adjusted speed (m/min) per heartbeat
+    pub efficiency_factor: Option<f64>,
+    pub elevation_gain: Option<f64>,
+    pub climb_meters_per_km: Option<f64>,
+    pub temperature_celsius: Option<f64>,
+}
+
+/// One activity and its figures
+#[derive(Debug, Clone, Serialize)]
+pub struct Effort {
+    pub id: String,
+    pub name: String,
+    pub sport_type: SportType,
+    pub start_date: DateTime<Utc>,
+    #[serde(flatten)]
+    pub values: EffortValues,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weather: Option<WeatherConditions>,
+    /// 0-1 closeness to the compared activity, for `similar_activities`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
+}
+
+/// Average of the efforts the activity is compared with
+#[derive(Debug, Clone, Serialize)]
+pub struct Baseline {
+    pub activities: usize,
+    #[serde(flatten)]
+    pub values: EffortValues,
+}
+
+/// Difference in one figure, activity minus baseline
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricDifference {
+    pub metric: &'static str,
+    pub activity: f64,
+    pub baseline: f64,
+    pub difference: f64,
+    pub percent: f64,
+}
+
+/// Result of the `compare_activities` tool
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityComparison {
+    pub comparison_type: ComparisonType,
+    pub activity: Effort,
+    pub compared_with: Vec<Effort>,
+    pub baseline: Option<Baseline>,
+    pub differences: Vec<MetricDifference>,
+    /// Whether the activity is the fastest at its distance, for `personal_best`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_personal_best: Option<bool>,
+    pub insights: Vec<String>,
+    pub activities_searched: usize,
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+/// Reads one figure of an effort
+type Field = fn(&EffortValues) -> Option<f64>;
+
+impl EffortValues {
+    /// Figures of an activity, adjusted for its terrain and the weather it
+    /// was done in
+    pub fn from_activity(
+        activity: &Activity,
+        streams: Option<&ActivityStreams>,
+        weather: Option<&WeatherConditions>,
+    ) -> Self {
+        let duration = activity.duration_seconds as f64;
+        let distance = activity.distance_meters.filter(|d| *d > 0.0);
+        let km = distance.map(|d| d / 1000.0);
+        let heart_rate = activity
+            .average_heart_rate
+            .filter(|hr| *hr > 0)
+            .map(f64::from);
+        let temperature_celsius = weather.map(|w| f64::from(w.temperature_celsius));
+
+        let climb = km.and_then(|km| activity.elevation_gain.map(|gain| gain / km));
+        let adjusted = AdjustedPace::for_activity(activity, streams, weather);
+        let adjusted_pace = adjusted.as_ref().map(|a| a.adjusted_pace_seconds_per_km);
+        let efficiency = adjusted_pace
+            .zip(heart_rate)
+            .map(|(pace, hr)| 60_000.0 / pace / hr);
+
+        Self {
+            distance_meters: distance,
+            duration_seconds: duration,
+            pace_seconds_per_km: adjusted.as_ref().map(|a| a.pace_seconds_per_km),
+            grade_adjusted_pace_seconds_per_km: adjusted
+                .as_ref()
+                .map(|a| a.grade_adjusted_pace_seconds_per_km),
+            heat_adjusted_pace_seconds_per_km: adjusted
+                .as_ref()
+                .and_then(|a| a.heat_adjusted_pace_seconds_per_km),
+            adjusted_pace_seconds_per_km: adjusted_pace,
+            average_heart_rate: heart_rate,
+            efficiency_factor: efficiency.map(|ef| round(ef, 3)),
+            elevation_gain: activity.elevation_gain,
+            climb_meters_per_km: climb.map(|c| round(c, 1)),
+            temperature_celsius: temperature_celsius.map(|t| round(t, 1)),
+        }
+    }
+
+    /// Mean of each figure over the efforts that have it
+    pub fn average(values: &[&EffortValues]) -> Self {
+        fn mean(
+            values: &[&EffortValues],
+            field: impl Fn(&EffortValues) -> Option<f64>,
+        ) -> Option<f64> {
+            let present: Vec<f64> = values.iter().filter_map(|v| field(v)).collect();
+            (!present.is_empty()).then(|| present.iter().sum::<f64>() / present.len() as f64)
+        }
+        let rounded = |field: Field, decimals| mean(values, field).map(|v| round(v, decimals));
+
+        Self {
+            distance_meters: rounded(|v| v.distance_meters, 0),
+            duration_seconds: mean(values, |v| Some(v.duration_seconds)).map_or(0.0, f64::round),
+            pace_seconds_per_km: rounded(|v| v.pace_seconds_per_km, 1),
+            grade_adjusted_pace_seconds_per_km: rounded(
+                |v| v.grade_adjusted_pace_seconds_per_km,
+                1,
+            ),
+            heat_adjusted_pace_seconds_per_km: rounded(|v| v.heat_adjusted_pace_seconds_per_km, 1),
+            adjusted_pace_seconds_per_km: rounded(|v| v.adjusted_pace_seconds_per_km, 1),
+            average_heart_rate: rounded(|v| v.average_heart_rate, 1),
+            efficiency_factor: rounded(|v| v.efficiency_factor, 3),
+            elevation_gain: rounded(|v| v.elevation_gain, 0),
+            climb_meters_per_km: rounded(|v| v.climb_meters_per_km, 1),
+            temperature_celsius: rounded(|v| v.temperature_celsius, 1),
+        }
+    }
+}
+
+impl Effort {
+    pub fn new(
+        activity: &Activity,
+        streams: Option<&ActivityStreams>,
+        weather: Option<WeatherConditions>,
+    ) -> Self {
+        Self {
+            id: activity.id.clone(),
+            name: activity.name.clone(),
+            sport_type: activity.sport_type.clone(),
+            start_date: activity.start_date,
+            values: EffortValues::from_activity(activity, streams, weather.as_ref()),
+            weather,
+            similarity: None,
+        }
+    }
+}
+
+/// Distance between two efforts in distance, duration and climb; 0 is identical
+fn dissimilarity(a: &Activity, b: &Activity) -> f64 {
+    let log_ratio = |x: f64, y: f64| {
+        if x > 0.0 && y > 0.0 {
+            (x / y).ln().abs()
+        } else {
+            1.0
+        }
+    };
+    let distance = match (a.distance_meters, b.distance_meters) {
+        (Some(x), Some(y)) => log_ratio(x, y),
+        (None, None) => 0.0,
+        _ => 1.0,
+    };
+    let duration = log_ratio(a.duration_seconds as f64, b.duration_seconds as f64);
+    let climb = |activity: &Activity| {
+        activity
+            .distance_meters
+            .filter(|d| *d > 0.0)
+            .map_or(0.0, |d| {
+                activity.elevation_gain.unwrap_or(0.0) / (d / 1000.0)
+            })
+    };
+    // 10 m/km of difference in climb weighs like a doubling of distance
+    let climb = (climb(a) - climb(b)).abs() / 10.0 * std::f64::consts::LN_2;
+
+    2.0 * distance + duration + climb
+}
+
+fn within_distance(target: &Activity, other: &Activity, tolerance: f64) -> bool {
+    match (target.distance_meters, other.distance_meters) {
+        (Some(t), Some(o)) if t > 0.0 => ((o - t) / t).abs() <= tolerance,
+        _ => false,
+    }
+}
+
+fn pace_of(activity: &Activity) -> Option<f64> {
+    AdjustedPace::for_activity(activity, None, None).map(|a| a.adjusted_pace_seconds_per_km)
+}
+
+/// The efforts in `history` the activity is compared with, and their
+/// similarity when ranked by it
+///
+/// For `personal_best` this is the other effort at the distance with the
+/// fastest grade-adjusted pace, as weather isn't known yet at this point;
+/// `activity` comparisons pick their reference by ID and aren't handled here.
+pub fn select_references<'a>(
+    target: &Activity,
+    history: &'a [Activity],
+    comparison_type: ComparisonType,
+    count: usize,
+) -> Vec<(&'a Activity, Option<f64>)> {
+    let family = sport_family(&target.sport_type);
+    let mut candidates: Vec<&Activity> = history
+        .iter()
+        .filter(|a| a.id != target.id && sport_family(&a.sport_type) == family)
+        .collect();
+
+    match comparison_type {
+        ComparisonType::SimilarActivities => {
+            let mut ranked: Vec<(&Activity, f64)> = candidates
+                .into_iter()
+                .map(|a| (a, dissimilarity(target, a)))
+                .collect();
+            ranked.sort_by(|x, y| x.1.total_cmp(&y.1));
+            ranked
+                .into_iter()
+                .take(count)
+                .map(|(a, score)| (a, Some(round(1.0 / (1.0 + score), 2))))
+                .collect()
+        }
+        ComparisonType::PersonalBest => candidates
+            .into_iter()
+            .filter(|a| within_distance(target, a, PERSONAL_BEST_TOLERANCE))
+            .filter_map(|a| pace_of(a).map(|pace| (a, pace)))
+            .min_by(|x, y| x.1.total_cmp(&y.1))
+            .map(|(a, _)| vec![(a, None)])
+            .unwrap_or_default(),
+        ComparisonType::Average => candidates
+            .into_iter()
+            .filter(|a| within_distance(target, a, AVERAGE_TOLERANCE))
+            .map(|a| (a, None))
+            .collect(),
+        ComparisonType::Recent => {
+            candidates.retain(|a| a.start_date < target.start_date);
+            candidates.sort_by_key(|a| std::cmp::Reverse(a.start_date));
+            candidates
+                .into_iter()
+                .take(count)
+                .map(|a| (a, None))
+                .collect()
+        }
+        ComparisonType::Activity => vec![],
+    }
+}
+
+fn describe_seconds(seconds: f64) -> String {
+    let seconds = seconds.abs().round() as u64;
+    if seconds >= 60 {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    } else {
+        format!("{} s", seconds)
+    }
+}
+
+fn differences(activity: &EffortValues, baseline: &EffortValues) -> Vec<MetricDifference> {
+    let metrics: [(&'static str, Field); 9] = [
+        ("pace_seconds_per_km", |v| v.pace_seconds_per_km),
+        ("grade_adjusted_pace_seconds_per_km", |v| {
+            v.grade_adjusted_pace_seconds_per_km
+        }),
+        ("heat_adjusted_pace_seconds_per_km", |v| {
+            v.heat_adjusted_pace_seconds_per_km
+        }),
+        ("adjusted_pace_seconds_per_km", |v| {
+            v.adjusted_pace_seconds_per_km
+        }),
+        ("average_heart_rate", |v| v.average_heart_rate),
+        ("efficiency_factor", |v| v.efficiency_factor),
+        ("elevation_gain", |v| v.elevation_gain),
+        ("distance_meters", |v| v.distance_meters),
+        ("temperature_celsius", |v| v.temperature_celsius),
+    ];
+    metrics
+        .into_iter()
+        .filter_map(|(metric, field)| {
+            let (value, base) = (field(activity)?, field(baseline)?);
+            let difference = value - base;
+            Some(MetricDifference {
+                metric,
+                activity: value,
+                baseline: base,
+                difference: round(difference, 3),
+                percent: if base == 0.0 {
+                    0.0
+                } else {
+                    round(difference / base * 100.0, 1)
+                },
+            })
+        })
+        .collect()
+}
+
+fn insights(
+    comparison_type: ComparisonType,
+    differences: &[MetricDifference],
+    baseline_size: usize,
+) -> Vec<String> {
+    let against = match comparison_type {
+        ComparisonType::SimilarActivities => format!("your {} most similar efforts", baseline_size),
+        ComparisonType::PersonalBest => "your best effort at this distance".to_string(),
+        ComparisonType::Average => format!("your average over {} efforts", baseline_size),
+        ComparisonType::Recent => format!("your last {} efforts", baseline_size),
+        ComparisonType::Activity => "the other activity".to_string(),
+    };
+    let find = |metric: &str| differences.iter().find(|d| d.metric == metric);
+    let faster_or_slower = |d: &MetricDifference| {
+        if d.difference <= 0.0 {
+            "faster"
+        } else {
+            "slower"
+        }
+    };
+
+    let mut insights = vec![];
+    if let Some(pace) = find("pace_seconds_per_km") {
+        insights.push(format!(
+            "{}/km {} than {}",
+            describe_seconds(pace.difference),
+            faster_or_slower(pace),
+            against
+        ));
+        if let Some(adjusted) = find("adjusted_pace_seconds_per_km") {
+            if (adjusted.difference - pace.difference).abs() >= 5.0 {
+                insights.push(format!(
+                    "Adjusted for grade and heat, {}/km {}",
+                    describe_seconds(adjusted.difference),
+                    faster_or_slower(adjusted)
+                ));
+            }
+        }
+    }
+    if let Some(heart_rate) = find("average_heart_rate") {
+        if heart_rate.difference.abs() >= 1.0 {
+            insights.push(format!(
+                "Average heart rate {} bpm {}",
+                heart_rate.difference.abs().round(),
+                if heart_rate.difference < 0.0 {
+                    "lower"
+                } else {
+                    "higher"
+                }
+            ));
+        }
+    }
+    if let Some(efficiency) = find("efficiency_factor") {
+        if efficiency.percent.abs() >= 2.0 {
+            insights.push(format!(
+                "Efficiency factor {} {:.1}%: {} speed for each heartbeat",
+                if efficiency.percent > 0.0 {
+                    "up"
+                } else {
+                    "down"
+                },
+                efficiency.percent.abs(),
+                if efficiency.percent > 0.0 {
+                    "more"
+                } else {
+                    "less"
+                }
+            ));
+        }
+    }
+    if let Some(temperature) = find("temperature_celsius") {
+        if temperature.difference.abs() >= 5.0 {
+            insights.push(format!(
+                "{:.0}°C {} than {}",
+                temperature.difference.abs(),
+                if temperature.difference > 0.0 {
+                    "warmer"
+                } else {
+                    "cooler"
+                },
+                against
+            ));
+        }
+    }
+    insights
+}
+
+/// Compare an activity with already chosen reference efforts
+pub fn compare_efforts(
+    comparison_type: ComparisonType,
+    activity: Effort,
+    compared_with: Vec<Effort>,
+    activities_searched: usize,
+) -> ActivityComparison {
+    let baseline = (!compared_with.is_empty()).then(|| Baseline {
+        activities: compared_with.len(),
+        values: EffortValues::average(&compared_with.iter().map(|e| &e.values).collect::<Vec<_>>()),
+    });
+    let differences = baseline
+        .as_ref()
+        .map(|baseline| differences(&activity.values, &baseline.values))
+        .unwrap_or_default();
+
+    let is_personal_best = (comparison_type == ComparisonType::PersonalBest).then(|| {
+        match (
+            activity.values.adjusted_pace_seconds_per_km,
+            baseline
+                .as_ref()
+                .and_then(|b| b.values.adjusted_pace_seconds_per_km),
+        ) {
+            (Some(pace), Some(best)) => pace <= best,
+            (Some(_), None) => true,
+            _ => false,
+        }
+    });
+
+    let mut insights = insights(
+        comparison_type,
+        &differences,
+        baseline.as_ref().map_or(0, |b| b.activities),
+    );
+    if baseline.is_none() {
+        insights.push(format!(
+            "No other {} efforts to compare with",
+            sport_family(&activity.sport_type)
+        ));
+    }
+    if is_personal_best == Some(true) {
+        insights.insert(0, "Fastest effort at this distance".to_string());
+    }
+
+    ActivityComparison {
+        comparison_type,
+        activity,
+        compared_with,
+        baseline,
+        differences,
+        is_personal_best,
+        insights,
+        activities_searched,
+    }
+}
+
+/// Distance and altitude streams of the runs and walks among the activities
+///
+/// Other sports use the climb estimate, and a provider without streams
+/// leaves every activity on it.
+async fn streams_for(
+    provider: &dyn FitnessProvider,
+    activities: &[&Activity],
+) -> Vec<Option<ActivityStreams>> {
+    let mut streams = Vec::with_capacity(activities.len());
+    for activity in activities {
+        let on_foot = matches!(sport_family(&activity.sport_type), "run" | "walk");
+        streams.push(if on_foot {
+            provider.get_activity_streams(&activity.id).await.ok()
+        } else {
+            None
+        });
+    }
+    streams
+}
+
+/// Run a comparison over a provider's activities
+///
+/// The activity is looked up by ID, so it can be older than the history
+/// searched for comparable efforts.
+pub async fn compare_activities(
+    provider: &dyn FitnessProvider,
+    request: &ComparisonRequest,
+) -> Result<ActivityComparison> {
+    let target = provider
+        .get_activity(&request.activity_id)
+        .await
+        .with_context(|| format!("Activity '{}' not found", request.activity_id))?;
+
+    let (history, references) = match &request.compare_to {
+        Some(other_id) => {
+            let other = provider
+                .get_activity(other_id)
+                .await
+                .with_context(|| format!("Activity '{}' not found", other_id))?;
+            (vec![], vec![(other, None)])
+        }
+        None => {
+            let history = paged_activities(provider, HISTORY_LIMIT, None, None)
+                .await?
+                .activities;
+            let references: Vec<(Activity, Option<f64>)> =
+                select_references(&target, &history, request.comparison_type, request.count)
+                    .into_iter()
+                    .map(|(a, similarity)| (a.clone(), similarity))
+                    .collect();
+            (history, references)
+        }
+    };
+
+    let mut activities = vec![&target];
+    activities.extend(references.iter().map(|(a, _)| a));
+    let mut weather = measured_weather(&activities).await.into_iter();
+    let mut streams = streams_for(provider, &activities).await.into_iter();
+
+    let activity = Effort::new(
+        &target,
+        streams.next().flatten().as_ref(),
+        weather.next().flatten(),
+    );
+    let compared_with = references
+        .iter()
+        .zip(streams.zip(weather))
+        .map(|((reference, similarity), (streams, weather))| Effort {
+            similarity: *similarity,
+            ..Effort::new(reference, streams.as_ref(), weather)
+        })
+        .collect();
+
+    Ok(compare_efforts(
+        request.comparison_type,
+        activity,
+        compared_with,
+        history.len(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intelligence::WeatherProvenance;
+    use chrono::Duration;
+    use serde_json::json;
+
+    fn run(id: &str, days_ago: i64, km: f64, minutes: u64, climb: f64, hr: u32) -> Activity {
+        Activity {
+            id: id.to_string(),
+            name: format!("Run {}", id),
+            sport_type: SportType::Run,
+            start_date: Utc::now() - Duration::days(days_ago),
+            duration_seconds: minutes * 60,
+            distance_meters: Some(km * 1000.0),
+            elevation_gain: Some(climb),
+            average_heart_rate: Some(hr),
+            provider: "manual".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_request_from_args() {
+        let request = ComparisonRequest::from_args(&json!({"activity_id": "a"})).unwrap();
+        assert_eq!(request.comparison_type, ComparisonType::SimilarActivities);
+        assert_eq!(request.count, DEFAULT_COUNT);
+
+        let request =
+            ComparisonRequest::from_args(&json!({"activity_id": "a", "compare_to": "b"})).unwrap();
+        assert_eq!(request.comparison_type, ComparisonType::Activity);
+
+        for bad in [
+            json!({}),
+            json!({"activity_id": "a", "comparison_type": "fastest"}),
+            json!({"activity_id": "a", "comparison_type": "activity"}),
+            json!({"activity_id": "a", "comparison_type": "recent", "compare_to": "b"}),
+            json!({"activity_id": "a", "count": 0}),
+        ] {
+            assert!(ComparisonRequest::from_args(&bad).is_err(), "{}", bad);
+        }
+    }
+
+    fn measured(temperature: f32) -> WeatherConditions {
+        WeatherConditions {
+            temperature_celsius: temperature,
+            humidity_percentage: None,
+            wind_speed_kmh: None,
+            conditions: "clear".to_string(),
+            provenance: WeatherProvenance::Measured,
+        }
+    }
+
+    #[test]
+    fn test_normalization() {
+        // Same time over the same distance, one of them with 200 m of climbing
+        let mild = measured(10.0);
+        let flat =
+            EffortValues::from_activity(&run("flat", 1, 10.0, 50, 0.0, 150), None, Some(&mild));
+        let hilly =
+            EffortValues::from_activity(&run("hilly", 1, 10.0, 50, 200.0, 150), None, Some(&mild));
+        assert_eq!(flat.pace_seconds_per_km, Some(300.0));
+        assert_eq!(flat.adjusted_pace_seconds_per_km, Some(300.0));
+        assert_eq!(hilly.climb_meters_per_km, Some(20.0));
+        assert!(hilly.adjusted_pace_seconds_per_km.unwrap() < 300.0);
+        assert_eq!(
+            hilly.adjusted_pace_seconds_per_km,
+            hilly.grade_adjusted_pace_seconds_per_km
+        );
+        assert!(hilly.efficiency_factor > flat.efficiency_factor);
+
+        // Heat makes the same pace worth more
+        let hot = EffortValues::from_activity(
+            &run("hot", 1, 10.0, 50, 0.0, 150),
+            None,
+            Some(&measured(30.0)),
+        );
+        assert_eq!(hot.adjusted_pace_seconds_per_km, Some(283.0));
+        assert_eq!(hot.heat_adjusted_pace_seconds_per_km, Some(283.0));
+    }
+
+    #[test]
+    fn test_select_references() {
+        let target = run("target", 0, 10.0, 50, 50.0, 150);
+        let history = vec![
+            run("ten", 3, 10.0, 52, 40.0, 152),
+            run("ten_hilly", 5, 10.2, 58, 300.0, 158),
+            run("five", 7, 5.0, 24, 20.0, 155),
+            run("half", 10, 21.1, 110, 100.0, 148),
+            run("best_ten", 60, 9.8, 47, 30.0, 165),
+            Activity {
+                sport_type: SportType::Ride,
+                ..run("ride", 2, 10.0, 20, 50.0, 130)
+            },
+        ];
+
+        let similar = select_references(&target, &history, ComparisonType::SimilarActivities, 2);
+        let ids: Vec<&str> = similar.iter().map(|(a, _)| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["ten", "best_ten"]);
+        assert!(similar[0].1.unwrap() > similar[1].1.unwrap());
+
+        let best = select_references(&target, &history, ComparisonType::PersonalBest, 5);
+        assert_eq!(best[0].0.id, "best_ten");
+
+        let average = select_references(&target, &history, ComparisonType::Average, 5);
+        assert_eq!(average.len(), 3);
+
+        let recent = select_references(&target, &history, ComparisonType::Recent, 2);
+        let ids: Vec<&str> = recent.iter().map(|(a, _)| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["ten", "ten_hilly"]);
+
+        let comparison = compare_efforts(
+            ComparisonType::PersonalBest,
+            Effort::new(&target, None, None),
+            vec![Effort::new(best[0].0, None, None)],
+            history.len(),
+        );
+        assert_eq!(comparison.is_personal_best, Some(false));
+        assert!(comparison.insights[0].ends_with("slower than your best effort at this distance"));
+    }
+}
@@ -0,0 +1,1076 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Training digests
+//!
+//! Weekly, monthly and year-in-review reports of a user's training:
+//!
+//! | Section            | Contents                                                              |
+//! |--------------------|-----------------------------------------------------------------------|
+//! | `volume`           | totals overall and per sport, with the change on the period before    |
+//! | `intensity`        | training time per heart rate zone, by each activity's average         |
+//! | `personal_records` | `distance_pr_types` and `time_pr_types` records beaten in the period  |
+//! | `goals`            | progress on active goals and what the period added to them            |
+//! | `load`             | acute:chronic workload and injury risk at the end of the period       |
+//! | `months`           | monthly totals and the longest streak, for the year in review only   |
+//!
+//! Weeks are ISO weeks (Monday to Sunday) and dates are UTC. Digests of
+//! finished periods are stored per user with a Markdown rendering, so they
+//! can be read back as `digest://` MCP resources and sent once by the
+//! scheduler in [`crate::digests`]. A digest of the period in progress is
+//! built on request but not stored.
+
+use super::activity_query::{
+    history_for_user, ActivityFilter, ActivityQuery, ActivitySummary, GroupBy, QueryGroup,
+    QueryTotals, SortField,
+};
+use super::patterns::sport_family;
+use super::settings;
+use super::streaks::{analyze_streaks, Streak, StreakRequest};
+use super::thresholds::{self, heart_rate_zones};
+use super::workload::{self, analyze_workload, WorkloadReport};
+use crate::config::fitness_config::{PersonalRecordConfig, ZoneThresholds};
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::models::{Activity, StoredDigest};
+use crate::providers::connections::ProviderConnections;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use uuid::Uuid;
+
+/// Scheme of the MCP resources digests are published under
+pub const RESOURCE_SCHEME: &str = "digest://";
+/// Stored digests listed as MCP resources, latest first
+pub const RESOURCE_LIST_LIMIT: u32 = 100;
+/// Record distances for `time_pr_types`, in meters
+const RECORD_DISTANCES: [(&str, f64); 4] = [
+    ("fastest_5k", 5000.0),
+    ("fastest_10k", 10_000.0),
+    ("fastest_half_marathon", 21_097.5),
+    ("fastest_marathon", 42_195.0),
+];
+/// Runs up to this much longer than a record distance count toward it
+const RECORD_DISTANCE_TOLERANCE: f64 = 0.05;
+/// Zones counted as easy in the intensity distribution
+const EASY_ZONES: [&str; 2] = ["recovery", "endurance"];
+
+/// Calendar period a digest covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestPeriod {
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl DigestPeriod {
+    pub const ALL: [DigestPeriod; 3] = [Self::Weekly, Self::Monthly, Self::Yearly];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+        }
+    }
+
+    /// Accepts `weekly` or `week`, and so on
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "weekly" | "week" => Ok(Self::Weekly),
+            "monthly" | "month" => Ok(Self::Monthly),
+            "yearly" | "year" => Ok(Self::Yearly),
+            other => Err(anyhow!(
+                "Unknown period '{}'. Use one of: weekly, monthly, yearly",
+                other
+            )),
+        }
+    }
+
+    const fn group_by(self) -> GroupBy {
+        match self {
+            Self::Weekly => GroupBy::Week,
+            Self::Monthly => GroupBy::Month,
+            Self::Yearly => GroupBy::Year,
+        }
+    }
+
+    /// First day of the period a date falls in
+    pub fn start_of(self, date: NaiveDate) -> NaiveDate {
+        self.group_by().period(date).1
+    }
+
+    /// Last day of the period starting on `start`
+    pub fn end_of(self, start: NaiveDate) -> NaiveDate {
+        let next = match self {
+            Self::Weekly => start.checked_add_signed(Duration::weeks(1)),
+            Self::Monthly => start.checked_add_months(Months::new(1)),
+            Self::Yearly => start.checked_add_months(Months::new(12)),
+        };
+        next.map_or(start, |next| next - Duration::days(1))
+    }
+
+    /// First day of the last period that is over on `today`
+    pub fn last_complete(self, today: NaiveDate) -> NaiveDate {
+        self.start_of(self.start_of(today) - Duration::days(1))
+    }
+
+    /// Name of the period starting on `start`, e.g. "Week of 24 Jun 2024"
+    pub fn title(self, start: NaiveDate) -> String {
+        match self {
+            Self::Weekly => format!("Week of {}", start.format("%-d %b %Y")),
+            Self::Monthly => start.format("%B %Y").to_string(),
+            Self::Yearly => format!("{} in review", start.year()),
+        }
+    }
+
+    /// The unit named in comparisons, e.g. "on last week"
+    const fn noun(self) -> &'static str {
+        match self {
+            Self::Weekly => "week",
+            Self::Monthly => "month",
+            Self::Yearly => "year",
+        }
+    }
+}
+
+/// URI of a stored digest, or of the latest finished period's without a start
+pub fn resource_uri(period: DigestPeriod, start: Option<NaiveDate>) -> String {
+    match start {
+        Some(start) => format!("{}{}/{}", RESOURCE_SCHEME, period.as_str(), start),
+        None => format!("{}{}/latest", RESOURCE_SCHEME, period.as_str()),
+    }
+}
+
+/// Period and date of a `digest://<period>/<YYYY-MM-DD | latest>` URI
+pub fn parse_resource_uri(uri: &str) -> Result<(DigestPeriod, Option<NaiveDate>)> {
+    let path = uri
+        .strip_prefix(RESOURCE_SCHEME)
+        .ok_or_else(|| anyhow!("Unknown resource '{}'", uri))?;
+    let (period, date) = path
+        .split_once('/')
+        .ok_or_else(|| anyhow!("Digest URIs look like digest://weekly/latest"))?;
+    let period = DigestPeriod::parse(period)?;
+    match date {
+        "latest" => Ok((period, None)),
+        date => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map(|date| (period, Some(date)))
+            .map_err(|_| anyhow!("Invalid date '{}' in {}, use YYYY-MM-DD", date, uri)),
+    }
+}
+
+/// Parameters of the `get_digest` tool
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestRequest {
+    pub period: DigestPeriod,
+    /// Any day in the period; the last finished period when `None`
+    pub date: Option<NaiveDate>,
+    /// Rebuild a stored digest
+    pub refresh: bool,
+}
+
+impl DigestRequest {
+    /// Read and check the tool arguments
+    pub fn from_args(args: &Value) -> Result<Self> {
+        let period = args["period"]
+            .as_str()
+            .map(DigestPeriod::parse)
+            .transpose()?
+            .unwrap_or(DigestPeriod::Weekly);
+        let date = args["date"]
+            .as_str()
+            .map(|text| {
+                NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .map_err(|_| anyhow!("Invalid date '{}', use YYYY-MM-DD", text))
+            })
+            .transpose()?;
+        Ok(Self {
+            period,
+            date,
+            refresh: args["refresh"].as_bool().unwrap_or(false),
+        })
+    }
+}
+
+/// Totals of one sport family
+#[derive(Debug, Clone, Serialize)]
+pub struct SportVolume {
+    pub sport: String,
+    #[serde(flatten)]
+    pub totals: QueryTotals,
+}
+
+/// How much training the period held
+#[derive(Debug, Clone, Serialize)]
+pub struct Volume {
+    #[serde(flatten)]
+    pub totals: QueryTotals,
+    pub active_days: usize,
+    /// Sports by training time
+    pub sports: Vec<SportVolume>,
+    /// The same totals over the period before
+    pub previous: QueryTotals,
+    pub distance_change_percent: Option<f64>,
+    pub duration_change_percent: Option<f64>,
+}
+
+/// Training time in one heart rate zone
+#[derive(Debug, Clone, Serialize)]
+pub struct ZoneTime {
+    pub zone: String,
+    pub min_bpm: u32,
+    pub max_bpm: Option<u32>,
+    pub minutes: f64,
+    /// Share of the time with heart rate
+    pub percent: f64,
+}
+
+/// How hard the period's training was
+#[derive(Debug, Clone, Serialize)]
+pub struct Intensity {
+    pub max_heart_rate: Option<f64>,
+    pub zones: Vec<ZoneTime>,
+    /// Minutes without an average heart rate
+    pub unrated_minutes: f64,
+    /// Share of the time with heart rate spent in the recovery and endurance zones
+    pub easy_percent: Option<f64>,
+}
+
+/// A record beaten in the period
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonalRecord {
+    /// The `distance_pr_types` or `time_pr_types` entry, e.g. `fastest_10k`
+    pub record: String,
+    pub activity_id: String,
+    pub activity_name: String,
+    pub date: NaiveDate,
+    pub value: f64,
+    pub previous: f64,
+    /// `km` for distances, `seconds` for times
+    pub unit: &'static str,
+    pub message: String,
+}
+
+/// Progress on one active goal
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalProgress {
+    pub goal_id: String,
+    pub title: String,
+    pub goal_type: String,
+    pub target: f64,
+    pub current: f64,
+    pub percent: f64,
+    pub target_date: Option<String>,
+    /// What the period's activities added: km for distance goals, activities
+    /// for frequency goals and hours for duration goals
+    pub this_period: Option<f64>,
+}
+
+/// A weekly, monthly or yearly training report
+#[derive(Debug, Clone, Serialize)]
+pub struct TrainingDigest {
+    pub period: DigestPeriod,
+    pub title: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Whether the period was over when the digest was built
+    pub complete: bool,
+    pub generated_at: DateTime<Utc>,
+    pub volume: Volume,
+    pub intensity: Intensity,
+    pub personal_records: Vec<PersonalRecord>,
+    pub goals: Vec<GoalProgress>,
+    pub load: WorkloadReport,
+    /// Monthly totals, for the year in review
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub months: Vec<QueryGroup>,
+    /// Longest run of consecutive active days, for the year in review
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longest_streak: Option<Streak>,
+    pub highlights: Vec<String>,
+}
+
+/// What a digest needs besides the activities
+#[derive(Debug, Clone)]
+pub struct DigestContext {
+    /// Estimated max heart rate; the highest recorded one otherwise
+    pub max_heart_rate: Option<f64>,
+    pub zone_thresholds: ZoneThresholds,
+    pub personal_records: PersonalRecordConfig,
+    pub goals: Vec<Value>,
+    pub injury_history: Vec<String>,
+}
+
+impl DigestContext {
+    /// The user's zone model, record types, goals and injuries
+    pub async fn for_user(database: &Database, user_id: Uuid) -> Self {
+        let config = settings::effective_config(database, user_id).await;
+        let max_heart_rate = thresholds::load_thresholds(database, user_id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|stored| stored.current.max_heart_rate)
+            .map(|estimate| estimate.value);
+        Self {
+            max_heart_rate,
+            zone_thresholds: config.zone_thresholds,
+            personal_records: config.personal_records,
+            goals: database.get_user_goals(user_id).await.unwrap_or_default(),
+            injury_history: workload::injury_history(database, user_id).await,
+        }
+    }
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+fn change_percent(current: f64, previous: f64) -> Option<f64> {
+    (previous > 0.0).then(|| round((current - previous) / previous * 100.0, 1))
+}
+
+/// `h:mm:ss`, or `m:ss` under an hour
+fn format_time(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+fn totals(activities: &[&Activity]) -> QueryTotals {
+    let summaries: Vec<ActivitySummary> = activities
+        .iter()
+        .map(|activity| ActivitySummary::new(activity, None))
+        .collect();
+    QueryTotals::of(&summaries.iter().collect::<Vec<_>>())
+}
+
+fn in_range(activity: &Activity, start: NaiveDate, end: NaiveDate) -> bool {
+    (start..=end).contains(&activity.start_date.date_naive())
+}
+
+fn volume(activities: &[&Activity], previous: &[&Activity]) -> Volume {
+    let mut by_sport: BTreeMap<&str, Vec<&Activity>> = BTreeMap::new();
+    for activity in activities {
+        by_sport
+            .entry(sport_family(&activity.sport_type))
+            .or_default()
+            .push(activity);
+    }
+    let mut sports: Vec<SportVolume> = by_sport
+        .into_iter()
+        .map(|(sport, activities)| SportVolume {
+            sport: sport.to_string(),
+            totals: totals(&activities),
+        })
+        .collect();
+    sports.sort_by(|a, b| {
+        b.totals
+            .total_duration_hours
+            .total_cmp(&a.totals.total_duration_hours)
+    });
+
+    let mut days: Vec<NaiveDate> = activities
+        .iter()
+        .map(|a| a.start_date.date_naive())
+        .collect();
+    days.sort();
+    days.dedup();
+
+    let (current, previous) = (totals(activities), totals(previous));
+    Volume {
+        distance_change_percent: change_percent(
+            current.total_distance_km,
+            previous.total_distance_km,
+        ),
+        duration_change_percent: change_percent(
+            current.total_duration_hours,
+            previous.total_duration_hours,
+        ),
+        totals: current,
+        active_days: days.len(),
+        sports,
+        previous,
+    }
+}
+
+fn intensity(
+    activities: &[&Activity],
+    max_heart_rate: Option<f64>,
+    zones: &ZoneThresholds,
+) -> Intensity {
+    let zones = max_heart_rate
+        .map(|max| heart_rate_zones(max, zones))
+        .unwrap_or_default();
+    let mut minutes = vec![0.0; zones.len()];
+    let mut unrated_minutes = 0.0;
+    for activity in activities {
+        let duration = activity.duration_seconds as f64 / 60.0;
+        let zone = activity.average_heart_rate.and_then(|hr| {
+            zones
+                .iter()
+                .position(|z| hr >= z.min_bpm && z.max_bpm.is_none_or(|max| hr <= max))
+        });
+        match zone {
+            Some(index) => minutes[index] += duration,
+            None => unrated_minutes += duration,
+        }
+    }
+
+    let rated: f64 = minutes.iter().sum();
+    let percent = |value: f64| {
+        if rated > 0.0 {
+            round(value / rated * 100.0, 1)
+        } else {
+            0.0
+        }
+    };
+    let zones: Vec<ZoneTime> = zones
+        .into_iter()
+        .zip(minutes)
+        .map(|(zone, minutes)| ZoneTime {
+            zone: zone.name.to_string(),
+            min_bpm: zone.min_bpm,
+            max_bpm: zone.max_bpm,
+            minutes: round(minutes, 1),
+            percent: percent(minutes),
+        })
+        .collect();
+    let easy: f64 = zones
+        .iter()
+        .filter(|z| EASY_ZONES.contains(&z.zone.as_str()))
+        .map(|z| z.minutes)
+        .sum();
+
+    Intensity {
+        max_heart_rate,
+        easy_percent: (rated > 0.0).then(|| percent(easy)),
+        zones,
+        unrated_minutes: round(unrated_minutes, 1),
+    }
+}
+
+fn record_name(record: &str) -> String {
+    record.replace('_', " ")
+}
+
+/// Records of the configured types the period's activities beat
+///
+/// A record needs an earlier effort to beat, so the first activity of a
+/// sport isn't reported as one.
+fn personal_records<'a>(
+    config: &PersonalRecordConfig,
+    period: &[&'a Activity],
+    before: &[&'a Activity],
+) -> Vec<PersonalRecord> {
+    let mut records = vec![];
+
+    for record in &config.distance_pr_types {
+        let Some(family) = record.strip_prefix("longest_") else {
+            continue;
+        };
+        let longest = |activities: &[&'a Activity]| -> Option<(&'a Activity, f64)> {
+            activities
+                .iter()
+                .filter(|a| sport_family(&a.sport_type) == family)
+                .filter_map(|a| a.distance_meters.map(|d| (*a, d / 1000.0)))
+                .max_by(|x, y| x.1.total_cmp(&y.1))
+        };
+        if let (Some((activity, km)), Some((_, previous))) = (longest(period), longest(before)) {
+            if km > previous {
+                records.push(PersonalRecord {
+                    record: record.clone(),
+                    activity_id: activity.id.clone(),
+                    activity_name: activity.name.clone(),
+                    date: activity.start_date.date_naive(),
+                    value: round(km, 2),
+                    previous: round(previous, 2),
+                    unit: "km",
+                    message: format!(
+                        "New {}: {:.1} km in {} (previous best {:.1} km)",
+                        record_name(record),
+                        km,
+                        activity.name,
+                        previous
+                    ),
+                });
+            }
+        }
+    }
+
+    for record in &config.time_pr_types {
+        let Some((_, meters)) = RECORD_DISTANCES.iter().find(|(name, _)| name == record) else {
+            continue;
+        };
+        let fastest = |activities: &[&'a Activity]| -> Option<(&'a Activity, f64)> {
+            activities
+                .iter()
+                .filter(|a| sport_family(&a.sport_type) == "run")
+                .filter_map(|a| {
+                    a.distance_meters
+                        .filter(|d| {
+                            *d >= *meters && *d <= meters * (1.0 + RECORD_DISTANCE_TOLERANCE)
+                        })
+                        .map(|d| (*a, a.duration_seconds as f64 / d * meters))
+                })
+                .min_by(|x, y| x.1.total_cmp(&y.1))
+        };
+        if let (Some((activity, time)), Some((_, previous))) = (fastest(period), fastest(before)) {
+            if time < previous {
+                records.push(PersonalRecord {
+                    record: record.clone(),
+                    activity_id: activity.id.clone(),
+                    activity_name: activity.name.clone(),
+                    date: activity.start_date.date_naive(),
+                    value: time.round(),
+                    previous: previous.round(),
+                    unit: "seconds",
+                    message: format!(
+                        "New {}: {} in {} (previous best {})",
+                        record_name(record),
+                        format_time(time),
+                        activity.name,
+                        format_time(previous)
+                    ),
+                });
+            }
+        }
+    }
+
+    records
+}
+
+fn goal_progress(goal: &Value, period: &[&Activity]) -> Option<GoalProgress> {
+    if goal["status"]
+        .as_str()
+        .is_some_and(|status| status != "active")
+    {
+        return None;
+    }
+    let value = |names: [&str; 2]| names.iter().find_map(|name| goal[*name].as_f64());
+    let target = value(["target_value", "target"]).filter(|t| *t > 0.0)?;
+    let current = value(["current_value", "current"]).unwrap_or(0.0);
+    let goal_type = goal["goal_type"]
+        .as_str()
+        .or_else(|| goal["type"].as_str())
+        .unwrap_or("custom")
+        .to_string();
+
+    let filter = ActivityFilter {
+        sports: goal["sport_type"]
+            .as_str()
+            .map(|sport| vec![sport.to_lowercase()])
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+    let matching: Vec<&Activity> = period
+        .iter()
+        .filter(|a| filter.matches(a))
+        .copied()
+        .collect();
+    let this_period = match goal_type.as_str() {
+        "distance" => Some(round(
+            matching
+                .iter()
+                .filter_map(|a| a.distance_meters)
+                .sum::<f64>()
+                / 1000.0,
+            2,
+        )),
+        "frequency" => Some(matching.len() as f64),
+        "duration" => Some(round(
+            matching
+                .iter()
+                .map(|a| a.duration_seconds as f64)
+                .sum::<f64>()
+                / 3600.0,
+            2,
+        )),
+        _ => None,
+    };
+
+    Some(GoalProgress {
+        goal_id: goal["id"].as_str().unwrap_or_default().to_string(),
+        title: goal["title"].as_str().unwrap_or("Goal").to_string(),
+        goal_type,
+        target,
+        current,
+        percent: round(current / target * 100.0, 1),
+        target_date: goal["target_date"].as_str().map(str::to_string),
+        this_period,
+    })
+}
+
+fn highlights(
+    activities: &[&Activity],
+    months: &[QueryGroup],
+    longest_streak: Option<&Streak>,
+) -> Vec<String> {
+    let mut highlights = vec![];
+    let day = |activity: &Activity| activity.start_date.format("%a %-d %b").to_string();
+
+    if let Some(longest) = activities
+        .iter()
+        .filter(|a| a.distance_meters.is_some_and(|d| d > 0.0))
+        .max_by(|a, b| {
+            let distance = |a: &Activity| a.distance_meters.unwrap_or_default();
+            distance(a).total_cmp(&distance(b))
+        })
+    {
+        highlights.push(format!(
+            "Longest: {}, {:.1} km on {}",
+            longest.name,
+            longest.distance_meters.unwrap_or_default() / 1000.0,
+            day(longest)
+        ));
+    }
+    if let Some(climb) = activities
+        .iter()
+        .filter(|a| a.elevation_gain.is_some_and(|e| e > 0.0))
+        .max_by(|a, b| {
+            let gain = |a: &Activity| a.elevation_gain.unwrap_or_default();
+            gain(a).total_cmp(&gain(b))
+        })
+    {
+        highlights.push(format!(
+            "Most climbing: {}, {:.0} m on {}",
+            climb.name,
+            climb.elevation_gain.unwrap_or_default(),
+            day(climb)
+        ));
+    }
+    if let Some(busiest) = months.iter().max_by(|a, b| {
+        a.totals
+            .total_duration_hours
+            .total_cmp(&b.totals.total_duration_hours)
+    }) {
+        highlights.push(format!(
+            "Busiest month: {} with {:.1} h over {} activities",
+            busiest.start.format("%B"),
+            busiest.totals.total_duration_hours,
+            busiest.totals.activities
+        ));
+    }
+    if let Some(streak) = longest_streak.filter(|s| s.length > 1) {
+        highlights.push(format!(
+            "Longest streak: {} days in a row, {} to {}",
+            streak.length,
+            streak.start.format("%-d %b"),
+            streak.end.format("%-d %b")
+        ));
+    }
+    highlights
+}
+
+/// A digest of the period containing `date`, from the user's full history
+pub fn build_digest(
+    history: &[Activity],
+    period: DigestPeriod,
+    date: NaiveDate,
+    context: &DigestContext,
+    now: DateTime<Utc>,
+) -> TrainingDigest {
+    let start = period.start_of(date);
+    let end = period.end_of(start);
+    let previous_start = period.start_of(start - Duration::days(1));
+
+    let in_period: Vec<&Activity> = history.iter().filter(|a| in_range(a, start, end)).collect();
+    let previous: Vec<&Activity> = history
+        .iter()
+        .filter(|a| in_range(a, previous_start, start - Duration::days(1)))
+        .collect();
+    let before: Vec<&Activity> = history
+        .iter()
+        .filter(|a| a.start_date.date_naive() < start)
+        .collect();
+
+    let max_heart_rate = context.max_heart_rate.or_else(|| {
+        history
+            .iter()
+            .filter_map(|a| a.max_heart_rate.map(f64::from))
+            .max_by(f64::total_cmp)
+    });
+
+    let (months, longest_streak) = if period == DigestPeriod::Yearly {
+        let query = ActivityQuery {
+            filter: ActivityFilter::default(),
+            group_by: Some(GroupBy::Month),
+            sort_by: SortField::Date,
+            descending: false,
+            limit: 12,
+        };
+        let matched = in_period.iter().map(|a| (*a, None)).collect();
+        let months =
+            super::activity_query::summarize(&query, matched, in_period.len(), vec![]).groups;
+        let request = StreakRequest {
+            filter: ActivityFilter {
+                start: Some(start.and_time(NaiveTime::MIN).and_utc()),
+                end: Some((end + Duration::days(1)).and_time(NaiveTime::MIN).and_utc()),
+                ..Default::default()
+            },
+            week_target: 1,
+        };
+        let streaks = analyze_streaks(history, &request, end.min(now.date_naive()));
+        (months, streaks.overall.days.longest)
+    } else {
+        (vec![], None)
+    };
+
+    let load_as_of = (end + Duration::days(1))
+        .and_time(NaiveTime::MIN)
+        .and_utc()
+        .min(now);
+    let volume = volume(&in_period, &previous);
+
+    TrainingDigest {
+        period,
+        title: period.title(start),
+        start,
+        end,
+        complete: end < now.date_naive(),
+        generated_at: now,
+        intensity: intensity(&in_period, max_heart_rate, &context.zone_thresholds),
+        personal_records: personal_records(&context.personal_records, &in_period, &before),
+        goals: context
+            .goals
+            .iter()
+            .filter_map(|goal| goal_progress(goal, &in_period))
+            .collect(),
+        load: analyze_workload(history, load_as_of, &context.injury_history),
+        highlights: highlights(&in_period, &months, longest_streak.as_ref()),
+        months,
+        longest_streak,
+        volume,
+    }
+}
+
+fn describe_totals(totals: &QueryTotals) -> String {
+    let mut text = format!(
+        "{} {}, {:.1} h",
+        totals.activities,
+        if totals.activities == 1 {
+            "activity"
+        } else {
+            "activities"
+        },
+        totals.total_duration_hours
+    );
+    if totals.total_distance_km > 0.0 {
+        let _ = write!(text, ", {:.1} km", totals.total_distance_km);
+    }
+    if totals.total_elevation_gain > 0.0 {
+        let _ = write!(text, ", {:.0} m climbing", totals.total_elevation_gain);
+    }
+    text
+}
+
+/// The digest as Markdown, for email, files and MCP resources
+pub fn render_markdown(digest: &TrainingDigest) -> String {
+    let mut text = format!(
+        "# {}\n\n{} to {}\n\n## Volume\n\n",
+        digest.title,
+        digest.start.format("%-d %b %Y"),
+        digest.end.format("%-d %b %Y")
+    );
+    let volume = &digest.volume;
+    if volume.totals.activities == 0 {
+        text.push_str("No activities.\n");
+    } else {
+        let _ = writeln!(
+            text,
+            "{} over {} active days.",
+            describe_totals(&volume.totals),
+            volume.active_days
+        );
+        let noun = digest.period.noun();
+        if let Some(change) = volume
+            .duration_change_percent
+            .filter(|_| volume.previous.activities > 0)
+        {
+            let _ = writeln!(
+                text,
+                "Training time {} {:.0}% on the previous {}.",
+                if change >= 0.0 { "up" } else { "down" },
+                change.abs(),
+                noun
+            );
+        }
+        text.push('\n');
+        for sport in &volume.sports {
+            let _ = writeln!(
+                text,
+                "- {}: {}",
+                sport.sport,
+                describe_totals(&sport.totals)
+            );
+        }
+    }
+
+    let intensity = &digest.intensity;
+    if let Some(easy) = intensity.easy_percent {
+        text.push_str("\n## Intensity\n\n");
+        let zones: Vec<String> = intensity
+            .zones
+            .iter()
+            .filter(|z| z.minutes > 0.0)
+            .map(|z| format!("{} {:.0}%", z.zone, z.percent))
+            .collect();
+        let _ = writeln!(text, "{} ({:.0}% easy)", zones.join(", "), easy);
+    }
+
+    if !digest.personal_records.is_empty() {
+        text.push_str("\n## Personal records\n\n");
+        for record in &digest.personal_records {
+            let _ = writeln!(text, "- {}", record.message);
+        }
+    }
+
+    if !digest.goals.is_empty() {
+        text.push_str("\n## Goals\n\n");
+        for goal in &digest.goals {
+            let _ = write!(
+                text,
+                "- {}: {} of {} ({:.0}%)",
+                goal.title, goal.current, goal.target, goal.percent
+            );
+            if let Some(added) = goal.this_period.filter(|added| *added > 0.0) {
+                let _ = write!(text, ", {} this {}", added, digest.period.noun());
+            }
+            text.push('\n');
+        }
+    }
+
+    let _ = write!(
+        text,
+        "\n## Training load\n\nInjury risk: {}\n",
+        json!(digest.load.overall_risk).as_str().unwrap_or("low")
+    );
+    for alert in &digest.load.alerts {
+        let _ = writeln!(text, "- {}", alert.message);
+    }
+
+    if !digest.highlights.is_empty() {
+        text.push_str("\n## Highlights\n\n");
+        for highlight in &digest.highlights {
+            let _ = writeln!(text, "- {}", highlight);
+        }
+    }
+    text
+}
+
+/// The digest in the form it is stored and delivered
+pub fn to_stored(digest: &TrainingDigest) -> Result<StoredDigest> {
+    Ok(StoredDigest {
+        period: digest.period.as_str().to_string(),
+        period_start: digest.start,
+        title: digest.title.clone(),
+        generated_at: digest.generated_at,
+        delivered_at: None,
+        summary: render_markdown(digest),
+        content: serde_json::to_value(digest)?,
+    })
+}
+
+/// A user's digest of the period containing `date`
+///
+/// A stored digest is returned as is unless `refresh` is set. Digests of
+/// finished periods are stored, keeping any earlier delivery time.
+pub async fn digest_for_user(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    period: DigestPeriod,
+    date: NaiveDate,
+    refresh: bool,
+) -> Result<StoredDigest> {
+    let database = connections.database();
+    let now = Utc::now();
+    let start = period.start_of(date);
+    if start > now.date_naive() {
+        return Err(anyhow!(
+            "The {} starting {} hasn't begun",
+            period.noun(),
+            start
+        ));
+    }
+
+    let existing = database.get_digest(user_id, period.as_str(), start).await?;
+    if let Some(existing) = existing.as_ref().filter(|_| !refresh) {
+        return Ok(existing.clone());
+    }
+
+    let history = history_for_user(connections, user_id, None, None, None).await?;
+    let context = DigestContext::for_user(database, user_id).await;
+    let digest = build_digest(&history.activities, period, start, &context, now);
+    let mut stored = to_stored(&digest)?;
+    // A digest built from a partial history is served but not kept
+    if digest.complete && history.gaps.is_empty() {
+        stored.delivered_at = existing.and_then(|existing| existing.delivered_at);
+        database.upsert_digest(user_id, &stored).await?;
+    }
+    Ok(stored)
+}
+
+/// Result of the `get_digest` tool for a user
+pub async fn requested_digest(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    request: &DigestRequest,
+) -> Result<Value> {
+    let date = request
+        .date
+        .unwrap_or_else(|| request.period.last_complete(Utc::now().date_naive()));
+    let digest =
+        digest_for_user(connections, user_id, request.period, date, request.refresh).await?;
+    Ok(digest_json(&digest))
+}
+
+/// Result of the `get_digest` tool and `digest://` resources
+pub fn digest_json(digest: &StoredDigest) -> Value {
+    let period = DigestPeriod::parse(&digest.period).unwrap_or(DigestPeriod::Weekly);
+    json!({
+        "period": digest.period,
+        "period_start": digest.period_start,
+        "title": digest.title,
+        "generated_at": digest.generated_at,
+        "delivered_at": digest.delivered_at,
+        "resource_uri": resource_uri(period, Some(digest.period_start)),
+        "summary": digest.summary,
+        "digest": digest.content,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intelligence::settings::server_defaults;
+    use crate::models::SportType;
+
+    fn day(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn run(id: &str, date: &str, km: f64, pace: f64, hr: u32) -> Activity {
+        Activity {
+            id: id.to_string(),
+            name: id.to_string(),
+            sport_type: SportType::Run,
+            start_date: day(date).and_hms_opt(7, 0, 0).unwrap().and_utc(),
+            duration_seconds: (km * pace) as u64,
+            distance_meters: Some(km * 1000.0),
+            average_heart_rate: Some(hr),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_periods_and_uris() {
+        let date = day("2024-06-30");
+        assert_eq!(DigestPeriod::Weekly.start_of(date), day("2024-06-24"));
+        assert_eq!(DigestPeriod::Weekly.last_complete(date), day("2024-06-17"));
+        assert_eq!(
+            DigestPeriod::Monthly.end_of(day("2024-02-01")),
+            day("2024-02-29")
+        );
+        assert_eq!(DigestPeriod::Monthly.last_complete(date), day("2024-05-01"));
+        assert_eq!(DigestPeriod::Yearly.last_complete(date), day("2023-01-01"));
+        assert_eq!(DigestPeriod::Monthly.title(day("2024-06-01")), "June 2024");
+
+        let uri = resource_uri(DigestPeriod::Weekly, Some(day("2024-06-24")));
+        assert_eq!(uri, "digest://weekly/2024-06-24");
+        assert_eq!(
+            parse_resource_uri(&uri).unwrap(),
+            (DigestPeriod::Weekly, Some(day("2024-06-24")))
+        );
+        assert_eq!(
+            parse_resource_uri("digest://year/latest").unwrap(),
+            (DigestPeriod::Yearly, None)
+        );
+        assert!(parse_resource_uri("digest://daily/latest").is_err());
+    }
+
+    #[test]
+    fn test_weekly_digest() {
+        let history = vec![
+            // The week before
+            run("old_10k", "2024-06-12", 10.0, 300.0, 150),
+            run("old_long", "2024-06-15", 16.0, 340.0, 140),
+            // The week of 17 June
+            run("easy", "2024-06-17", 8.0, 360.0, 120),
+            run("fast_10k", "2024-06-19", 10.2, 285.0, 172),
+            run("long", "2024-06-22", 21.0, 345.0, 132),
+        ];
+        let context = DigestContext {
+            max_heart_rate: Some(190.0),
+            zone_thresholds: server_defaults().zone_thresholds,
+            personal_records: server_defaults().personal_records,
+            goals: vec![json!({
+                "id": "g1",
+                "title": "Run 500 km",
+                "goal_type": "distance",
+                "sport_type": "run",
+                "target_value": 500.0,
+                "current_value": 120.0,
+                "status": "active",
+            })],
+            injury_history: vec![],
+        };
+        let now = day("2024-06-24").and_hms_opt(6, 0, 0).unwrap().and_utc();
+        let digest = build_digest(
+            &history,
+            DigestPeriod::Weekly,
+            day("2024-06-20"),
+            &context,
+            now,
+        );
+
+        assert!(digest.complete);
+        assert_eq!(digest.title, "Week of 17 Jun 2024");
+        assert_eq!(digest.volume.totals.activities, 3);
+        assert_eq!(digest.volume.totals.total_distance_km, 39.2);
+        assert_eq!(digest.volume.previous.activities, 2);
+        assert_eq!(digest.volume.active_days, 3);
+
+        // Every run has heart rate; the tempo 10k is the only hard one
+        assert_eq!(digest.intensity.unrated_minutes, 0.0);
+        let easy = digest.intensity.easy_percent.unwrap();
+        assert!(easy > 70.0 && easy < 90.0);
+
+        let records: Vec<&str> = digest
+            .personal_records
+            .iter()
+            .map(|r| r.record.as_str())
+            .collect();
+        assert_eq!(records, vec!["longest_run", "fastest_10k"]);
+        assert_eq!(digest.personal_records[1].value, 2850.0);
+        assert_eq!(digest.personal_records[1].previous, 3000.0);
+
+        assert_eq!(digest.goals[0].this_period, Some(39.2));
+        assert_eq!(digest.goals[0].percent, 24.0);
+
+        let markdown = render_markdown(&digest);
+        assert!(markdown.starts_with("# Week of 17 Jun 2024"));
+        assert!(markdown.contains("New fastest 10k: 47:30 in fast_10k (previous best 50:00)"));
+        assert!(markdown.contains("Longest: long, 21.0 km on Sat 22 Jun"));
+
+        // The week in progress isn't complete
+        let current = build_digest(
+            &history,
+            DigestPeriod::Weekly,
+            day("2024-06-24"),
+            &context,
+            now,
+        );
+        assert!(!current.complete);
+        assert_eq!(current.volume.totals.activities, 0);
+    }
+}
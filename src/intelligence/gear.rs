@@ -0,0 +1,421 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Gear mileage tracking and retirement alerts
+//!
+//! Shoes and bikes come from providers with the gear capability (Strava
+//! lists them on the athlete) and from gear the user adds with `add_gear`.
+//! Both are stored locally so per-gear retirement distances survive each
+//! sync. Shoes without their own retirement distance use
+//! [`DEFAULT_SHOE_RETIREMENT_METERS`]; other gear is only flagged when the
+//! user sets one.
+
+use super::insights::InsightGenerator;
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::models::{Activity, Gear, GearType};
+use crate::providers::connections::ProviderConnections;
+use crate::providers::manual::MANUAL_PROVIDER;
+use crate::providers::merge::with_all_providers;
+use crate::providers::{registry, FitnessProvider, ProviderCapability};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Typical running shoe lifespan, used for shoes without their own threshold
+pub const DEFAULT_SHOE_RETIREMENT_METERS: f64 = 800_000.0;
+/// Share of the retirement distance at which replacement reminders start
+pub const RETIREMENT_WARNING_FRACTION: f64 = 0.9;
+/// Activities scanned for per-gear moving time and activity counts
+const RECENT_ACTIVITY_LIMIT: usize = 200;
+
+/// Where a piece of gear is in its lifespan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GearStatus {
+    /// Well within its retirement distance, or no distance applies
+    Active,
+    /// Past [`RETIREMENT_WARNING_FRACTION`] of its retirement distance
+    NearingRetirement,
+    /// Past its retirement distance
+    ReplacementDue,
+    /// Retired by the user or the provider
+    Retired,
+}
+
+/// Distance after which the gear should be replaced, if any
+pub fn retirement_distance(gear: &Gear) -> Option<f64> {
+    gear.retirement_distance_meters
+        .or_else(|| (gear.gear_type == GearType::Shoes).then_some(DEFAULT_SHOE_RETIREMENT_METERS))
+}
+
+/// Share of the retirement distance used so far
+pub fn fraction_used(gear: &Gear) -> Option<f64> {
+    retirement_distance(gear).map(|limit| gear.distance_meters / limit)
+}
+
+/// Classify a piece of gear against its retirement distance
+pub fn gear_status(gear: &Gear) -> GearStatus {
+    if gear.retired {
+        return GearStatus::Retired;
+    }
+    match fraction_used(gear) {
+        Some(used) if used >= 1.0 => GearStatus::ReplacementDue,
+        Some(used) if used >= RETIREMENT_WARNING_FRACTION => GearStatus::NearingRetirement,
+        _ => GearStatus::Active,
+    }
+}
+
+/// A piece of gear as sent to `add_gear`
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewGear {
+    pub name: String,
+    pub gear_type: GearType,
+    pub brand: Option<String>,
+    pub model: Option<String>,
+    /// Distance already on the gear when it was added
+    #[serde(default)]
+    pub distance_km: f64,
+    pub retirement_distance_km: Option<f64>,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+/// Fields to change with `update_gear`; absent fields are kept
+///
+/// Provider gear keeps its provider name and distance, so only the
+/// retirement settings, brand and model stick across syncs.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GearUpdate {
+    pub name: Option<String>,
+    pub brand: Option<String>,
+    pub model: Option<String>,
+    pub retirement_distance_km: Option<f64>,
+    pub retired: Option<bool>,
+}
+
+impl NewGear {
+    /// Validate the request and build the gear to store
+    pub fn into_gear(self) -> Result<Gear> {
+        if self.name.trim().is_empty() {
+            bail!("name must not be empty");
+        }
+        if !self.distance_km.is_finite() || self.distance_km < 0.0 {
+            bail!("distance_km must be zero or more");
+        }
+        Ok(Gear {
+            id: format!("{}-gear-{}", MANUAL_PROVIDER, Uuid::new_v4()),
+            name: self.name.trim().to_string(),
+            gear_type: self.gear_type,
+            brand: self.brand,
+            model: self.model,
+            provider: MANUAL_PROVIDER.to_string(),
+            distance_meters: self.distance_km * 1000.0,
+            moving_time_seconds: 0,
+            activity_count: 0,
+            retirement_distance_meters: retirement_meters(self.retirement_distance_km)?,
+            primary: self.primary,
+            retired: false,
+        })
+    }
+}
+
+impl GearUpdate {
+    /// Validate the update and apply it to stored gear
+    pub fn apply(self, gear: &mut Gear) -> Result<()> {
+        if let Some(name) = self.name {
+            if name.trim().is_empty() {
+                bail!("name must not be empty");
+            }
+            gear.name = name.trim().to_string();
+        }
+        if self.brand.is_some() {
+            gear.brand = self.brand;
+        }
+        if self.model.is_some() {
+            gear.model = self.model;
+        }
+        if self.retirement_distance_km.is_some() {
+            gear.retirement_distance_meters = retirement_meters(self.retirement_distance_km)?;
+        }
+        if let Some(retired) = self.retired {
+            gear.retired = retired;
+        }
+        Ok(())
+    }
+}
+
+fn retirement_meters(km: Option<f64>) -> Result<Option<f64>> {
+    match km {
+        Some(km) if !km.is_finite() || km <= 0.0 => {
+            bail!("retirement_distance_km must be greater than zero")
+        }
+        _ => Ok(km.map(|km| km * 1000.0)),
+    }
+}
+
+/// Total the activities tagged with each piece of gear
+///
+/// Moving time and activity counts come from `activities` alone. Provider
+/// gear already carries the provider's lifetime distance; manual gear adds
+/// the distance of its tagged activities to the distance it was added with.
+pub fn apply_activity_usage(gear: &mut [Gear], activities: &[Activity]) {
+    let mut usage: HashMap<&str, (u64, u64, f64)> = HashMap::new();
+    for activity in activities {
+        if let Some(gear_id) = activity.gear_id.as_deref() {
+            let entry = usage.entry(gear_id).or_default();
+            entry.0 += activity.duration_seconds;
+            entry.1 += 1;
+            entry.2 += activity.distance_meters.unwrap_or(0.0);
+        }
+    }
+
+    for item in gear.iter_mut() {
+        let (seconds, count, distance) = usage.get(item.id.as_str()).copied().unwrap_or_default();
+        item.moving_time_seconds = seconds;
+        item.activity_count = count;
+        if item.provider == MANUAL_PROVIDER {
+            item.distance_meters += distance;
+        }
+    }
+}
+
+/// Store the gear a provider lists on the athlete
+///
+/// Retirement distances, brand and model set by the user are kept, and gear
+/// the user retired stays retired.
+pub async fn sync_provider_gear(
+    database: &Database,
+    provider: &dyn FitnessProvider,
+    user_id: Uuid,
+) -> Result<Vec<Gear>> {
+    let athlete = provider.get_athlete().await?;
+    let mut synced = Vec::with_capacity(athlete.gear.len());
+    for mut item in athlete.gear {
+        if let Some(stored) = database.get_gear(user_id, &item.id).await? {
+            item.retirement_distance_meters = stored.retirement_distance_meters;
+            item.brand = item.brand.or(stored.brand);
+            item.model = item.model.or(stored.model);
+            item.retired |= stored.retired;
+        }
+        database.upsert_gear(user_id, &item).await?;
+        synced.push(item);
+    }
+    Ok(synced)
+}
+
+/// All of a user's gear with usage totals
+///
+/// Syncs gear from every connected provider with the gear capability and
+/// totals the recent activities (merged across providers, plus manual
+/// entries) tagged with each piece. Provider failures are logged and the
+/// stored gear used instead.
+pub async fn gear_for_user(connections: &ProviderConnections, user_id: Uuid) -> Result<Vec<Gear>> {
+    let database = connections.database();
+    let mut activities = Vec::new();
+
+    for name in registry().provider_names() {
+        let Some(descriptor) = registry().get(name) else {
+            continue;
+        };
+        if !descriptor.supports(ProviderCapability::Gear) {
+            continue;
+        }
+        let provider = match connections.connected_provider(user_id, name).await {
+            Ok(Some(provider)) => provider,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("Skipping {} gear sync: {}", name, e);
+                continue;
+            }
+        };
+
+        if let Err(e) = sync_provider_gear(database, provider.as_ref(), user_id).await {
+            warn!("Skipping {} gear sync: {}", name, e);
+        }
+        match provider
+            .get_activities(Some(RECENT_ACTIVITY_LIMIT), None)
+            .await
+        {
+            Ok(recent) => activities.extend(recent),
+            Err(e) => warn!("Skipping {} activities for gear usage: {}", name, e),
+        }
+    }
+
+    // Merging keeps a run recorded by two providers from counting twice
+    let activities =
+        with_all_providers(connections, user_id, activities, RECENT_ACTIVITY_LIMIT).await?;
+    let mut gear = database.list_gear(user_id).await?;
+    apply_activity_usage(&mut gear, &activities);
+    Ok(gear)
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Summary of one piece of gear for the tool responses
+pub fn gear_summary(gear: &Gear) -> Value {
+    let limit = retirement_distance(gear);
+    json!({
+        "id": gear.id,
+        "name": gear.name,
+        "gear_type": gear.gear_type,
+        "brand": gear.brand,
+        "model": gear.model,
+        "provider": gear.provider,
+        "primary": gear.primary,
+        "retired": gear.retired,
+        "distance_km": round1(gear.distance_meters / 1000.0),
+        "moving_time_hours": round1(gear.moving_time_seconds as f64 / 3600.0),
+        "activity_count": gear.activity_count,
+        "retirement_distance_km": limit.map(|meters| round1(meters / 1000.0)),
+        "remaining_km": limit.map(|meters| round1((meters - gear.distance_meters).max(0.0) / 1000.0)),
+        "percent_used": fraction_used(gear).map(|used| round1(used * 100.0)),
+        "status": gear_status(gear),
+    })
+}
+
+/// Response for `get_gear`
+pub fn gear_list_report(
+    gear: &[Gear],
+    gear_type: Option<GearType>,
+    include_retired: bool,
+) -> Value {
+    let listed: Vec<Value> = gear
+        .iter()
+        .filter(|item| gear_type.is_none_or(|kind| item.gear_type == kind))
+        .filter(|item| include_retired || !item.retired)
+        .map(gear_summary)
+        .collect();
+    json!({
+        "total_count": listed.len(),
+        "gear": listed,
+    })
+}
+
+/// Response for `gear_usage_report`: active gear by wear, with replacement insights
+pub fn gear_usage_report(gear: &[Gear]) -> Value {
+    let mut active: Vec<&Gear> = gear.iter().filter(|item| !item.retired).collect();
+    active.sort_by(|a, b| {
+        fraction_used(b)
+            .unwrap_or(-1.0)
+            .total_cmp(&fraction_used(a).unwrap_or(-1.0))
+    });
+
+    let due = |status: GearStatus| {
+        active
+            .iter()
+            .filter(|item| gear_status(item) == status)
+            .count()
+    };
+    json!({
+        "gear": active.iter().map(|item| gear_summary(item)).collect::<Vec<_>>(),
+        "nearing_retirement": due(GearStatus::NearingRetirement),
+        "replacement_due": due(GearStatus::ReplacementDue),
+        "insights": InsightGenerator::new().generate_gear_insights(gear),
+        "note": format!(
+            "Moving time and activity counts cover the last {} activities",
+            RECENT_ACTIVITY_LIMIT
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shoes(id: &str, km: f64) -> Gear {
+        Gear {
+            id: id.to_string(),
+            name: "Pegasus 40".to_string(),
+            gear_type: GearType::Shoes,
+            brand: None,
+            model: None,
+            provider: "strava".to_string(),
+            distance_meters: km * 1000.0,
+            moving_time_seconds: 0,
+            activity_count: 0,
+            retirement_distance_meters: None,
+            primary: true,
+            retired: false,
+        }
+    }
+
+    #[test]
+    fn test_gear_status_thresholds() {
+        assert_eq!(gear_status(&shoes("g1", 500.0)), GearStatus::Active);
+        assert_eq!(
+            gear_status(&shoes("g1", 780.0)),
+            GearStatus::NearingRetirement
+        );
+        assert_eq!(gear_status(&shoes("g1", 810.0)), GearStatus::ReplacementDue);
+
+        let mut trail = shoes("g2", 500.0);
+        trail.retirement_distance_meters = Some(500_000.0);
+        assert_eq!(gear_status(&trail), GearStatus::ReplacementDue);
+        trail.retired = true;
+        assert_eq!(gear_status(&trail), GearStatus::Retired);
+
+        // Bikes have no default lifespan
+        let mut bike = shoes("b1", 20_000.0);
+        bike.gear_type = GearType::Bike;
+        assert_eq!(gear_status(&bike), GearStatus::Active);
+        assert!(fraction_used(&bike).is_none());
+    }
+
+    #[test]
+    fn test_activity_usage() {
+        let mut manual = shoes("manual-gear-1", 100.0);
+        manual.provider = MANUAL_PROVIDER.to_string();
+        let mut gear = vec![shoes("g1", 700.0), manual];
+        let run = |gear_id: &str| Activity {
+            gear_id: Some(gear_id.to_string()),
+            duration_seconds: 1800,
+            distance_meters: Some(5000.0),
+            ..Activity::default()
+        };
+        let activities = vec![
+            run("g1"),
+            run("g1"),
+            run("manual-gear-1"),
+            Activity::default(),
+        ];
+
+        apply_activity_usage(&mut gear, &activities);
+        assert_eq!(gear[0].activity_count, 2);
+        assert_eq!(gear[0].moving_time_seconds, 3600);
+        // Provider distance is already lifetime; manual gear accumulates
+        assert!((gear[0].distance_meters - 700_000.0).abs() < f64::EPSILON);
+        assert!((gear[1].distance_meters - 105_000.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_new_gear_validation() {
+        let request = |json: Value| serde_json::from_value::<NewGear>(json).unwrap();
+        let gear = request(json!({
+            "name": "Ghost 15",
+            "gear_type": "shoes",
+            "distance_km": 50,
+            "retirement_distance_km": 600,
+        }))
+        .into_gear()
+        .unwrap();
+        assert!(gear.id.starts_with("manual-gear-"));
+        assert_eq!(gear.retirement_distance_meters, Some(600_000.0));
+
+        assert!(request(json!({"name": " ", "gear_type": "bike"}))
+            .into_gear()
+            .is_err());
+        assert!(request(
+            json!({"name": "Ghost", "gear_type": "shoes", "retirement_distance_km": 0})
+        )
+        .into_gear()
+        .is_err());
+    }
+}
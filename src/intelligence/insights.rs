@@ -6,7 +6,9 @@
 
 //! Insight generation and management for athlete intelligence
 
-use crate::models::Activity;
+use super::gear::{retirement_distance, RETIREMENT_WARNING_FRACTION};
+use super::Anomaly;
+use crate::models::{Activity, Gear, GearType};
 use serde::{Deserialize, Serialize};
 
 /// An insight extracted from activity analysis
@@ -55,6 +57,12 @@ pub enum InsightType {
 
     /// Anomaly detection
     Anomaly,
+
+    /// Shoes or bikes nearing their retirement distance
+    GearWear,
+
+    /// Training load rising faster than the body adapts
+    InjuryRisk,
 }
 
 /// Insight generator for creating intelligent analysis
@@ -132,6 +140,79 @@ impl InsightGenerator {
         insights
     }
 
+    /// Generate replacement reminders for worn gear
+    ///
+    /// Every active piece of gear past [`RETIREMENT_WARNING_FRACTION`] of its
+    /// retirement distance gets one; these are not capped per activity.
+    pub fn generate_gear_insights(&self, gear: &[Gear]) -> Vec<Insight> {
+        gear.iter()
+            .filter(|item| !item.retired)
+            .filter_map(|item| {
+                let limit = retirement_distance(item)?;
+                let used = item.distance_meters / limit;
+                if used < RETIREMENT_WARNING_FRACTION {
+                    return None;
+                }
+
+                let distance_km = item.distance_meters / 1000.0;
+                let limit_km = limit / 1000.0;
+                let (verb, pronoun) = if item.gear_type == GearType::Shoes {
+                    ("are", "them")
+                } else {
+                    ("is", "it")
+                };
+                let message = if used >= 1.0 {
+                    format!(
+                        "Your {} {} at {:.0} km, past the {:.0} km you planned for - time to replace {}",
+                        item.name, verb, distance_km, limit_km, pronoun
+                    )
+                } else {
+                    format!(
+                        "Your {} {} at {:.0} km, consider replacing {} soon ({:.0} km limit)",
+                        item.name, verb, distance_km, pronoun, limit_km
+                    )
+                };
+
+                Some(Insight {
+                    insight_type: InsightType::GearWear,
+                    message,
+                    confidence: if used >= 1.0 { 95.0 } else { 85.0 },
+                    data: Some(serde_json::json!({
+                        "gear_id": item.id,
+                        "distance_km": distance_km,
+                        "retirement_distance_km": limit_km,
+                    })),
+                })
+            })
+            .collect()
+    }
+
+    /// Generate one insight per anomaly found in an activity
+    ///
+    /// Like gear reminders, these are neither filtered by confidence nor
+    /// capped per activity.
+    pub fn generate_anomaly_insights(
+        &self,
+        activity: &Activity,
+        anomalies: &[Anomaly],
+    ) -> Vec<Insight> {
+        anomalies
+            .iter()
+            .map(|anomaly| Insight {
+                insight_type: InsightType::Anomaly,
+                message: format!("{}: {}", activity.name, anomaly.description),
+                confidence: (anomaly.confidence.as_score() * 100.0) as f32,
+                data: Some(serde_json::json!({
+                    "activity_id": activity.id,
+                    "anomaly_type": anomaly.anomaly_type,
+                    "severity": anomaly.severity,
+                    "expected_value": anomaly.expected_value,
+                    "actual_value": anomaly.actual_value,
+                })),
+            })
+            .collect()
+    }
+
     /// Generate achievement-related insights
     fn generate_achievement_insights(&self, activity: &Activity) -> Vec<Insight> {
         let mut insights = Vec::new();
@@ -236,7 +317,8 @@ impl InsightGenerator {
     ) -> Vec<Insight> {
         let mut insights = Vec::new();
 
-        if let Some(weather) = &context.weather {
+        // Estimated weather isn't grounds for an insight
+        if let Some(weather) = context.weather.as_ref().filter(|w| w.is_measured()) {
             // Example weather impact analysis
             if weather.temperature_celsius < 5.0 {
                 insights.push(Insight {
@@ -460,6 +542,8 @@ mod tests {
             region: None,
             country: None,
             trail_name: None,
+            gear_id: None,
+            summary_polyline: None,
         }
     }
 
@@ -102,8 +102,10 @@ impl LocationService {
 
         // Make request to Nominatim API
         let url = format!(
-            "https://nominatim.openstreetmap.org/reverse?format=json&lat={}&lon={}&zoom=14&addressdetails=1",
-            latitude, longitude
+            "{}/reverse?format=json&lat={}&lon={}&zoom=14&addressdetails=1",
+            crate::constants::env_config::nominatim_api_base(),
+            latitude,
+            longitude
         );
 
         let response = self
@@ -1,6 +1,8 @@
 //! Advanced fitness metrics calculation and analysis
 
-use crate::models::Activity;
+use super::adjusted_pace::AdjustedPace;
+use super::WeatherConditions;
+use crate::models::{Activity, ActivityStreams};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -24,6 +26,10 @@ pub struct AdvancedMetrics {
     pub efficiency_factor: Option<f64>,
     /// Decoupling percentage
     pub decoupling_percentage: Option<f64>,
+    /// Pace on flat ground (s/km)
+    pub grade_adjusted_pace_seconds_per_km: Option<f64>,
+    /// Pace in ideal conditions (s/km), when measured weather is known
+    pub heat_adjusted_pace_seconds_per_km: Option<f64>,
     /// Custom metrics
     pub custom_metrics: HashMap<String, f64>,
 }
@@ -79,6 +85,17 @@ impl MetricsCalculator {
 
     /// Calculate all available metrics for an activity
     pub fn calculate_metrics(&self, activity: &Activity) -> Result<AdvancedMetrics> {
+        self.calculate_metrics_with_conditions(activity, None, None)
+    }
+
+    /// Calculate all available metrics, adjusting pace with the activity's
+    /// altitude streams and the weather it was done in
+    pub fn calculate_metrics_with_conditions(
+        &self,
+        activity: &Activity,
+        streams: Option<&ActivityStreams>,
+        weather: Option<&WeatherConditions>,
+    ) -> Result<AdvancedMetrics> {
         let mut metrics = AdvancedMetrics::default();
 
         // Calculate TRIMP if heart rate data is available
@@ -107,6 +124,12 @@ impl MetricsCalculator {
             }
         }
 
+        if let Some(adjusted) = AdjustedPace::for_activity(activity, streams, weather) {
+            metrics.grade_adjusted_pace_seconds_per_km =
+                Some(adjusted.grade_adjusted_pace_seconds_per_km);
+            metrics.heat_adjusted_pace_seconds_per_km = adjusted.heat_adjusted_pace_seconds_per_km;
+        }
+
         Ok(metrics)
     }
 
@@ -15,6 +15,20 @@
 //! - Goal tracking and progress monitoring
 //! - Training recommendations
 //! - Advanced metrics calculation
+//! - Sleep, resting heart rate and recovery analysis
+//! - Daily readiness scoring
+//! - Gear mileage and retirement alerts
+//! - Max heart rate, threshold heart rate, FTP and threshold pace estimation
+//! - Tenant and user overrides of the analysis configuration
+//! - Training pattern detection
+//! - Activity comparison normalized for climbing and temperature
+//! - Repeated route detection from GPS tracks
+//! - Anomaly detection against personal baselines and in recorded streams
+//! - Acute:chronic workload ratios and injury-risk alerts
+//! - Grade- and heat-adjusted pace
+//! - Filtered, grouped and ranked queries over the full activity history
+//! - Activity streaks, streak milestones and consistency scores
+//! - Weekly, monthly and year-in-review training digests
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -25,10 +39,24 @@ pub mod location;
 pub mod weather;
 // Re-enabling advanced intelligence modules
 pub mod activity_analyzer;
+pub mod activity_query;
+pub mod adjusted_pace;
+pub mod anomalies;
+pub mod comparison;
+pub mod digest;
+pub mod gear;
 pub mod goal_engine;
 pub mod metrics;
+pub mod patterns;
 pub mod performance_analyzer;
+pub mod readiness;
 pub mod recommendation_engine;
+pub mod routes;
+pub mod settings;
+pub mod streaks;
+pub mod thresholds;
+pub mod wellness;
+pub mod workload;
 
 pub use activity_analyzer::*;
 pub use analyzer::ActivityAnalyzer;
@@ -131,6 +159,36 @@ pub struct WeatherConditions {
     pub humidity_percentage: Option<f32>,
     pub wind_speed_kmh: Option<f32>,
     pub conditions: String, // "sunny", "rainy", "cloudy", etc.
+    /// Whether a provider measured these conditions or they were estimated
+    #[serde(default)]
+    pub provenance: WeatherProvenance,
+}
+
+impl WeatherConditions {
+    /// Whether the conditions can be presented as fact
+    pub fn is_measured(&self) -> bool {
+        self.provenance == WeatherProvenance::Measured
+    }
+}
+
+/// Where weather conditions came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WeatherProvenance {
+    /// Observed or reanalysed by a weather provider
+    Measured,
+    /// Derived without observations, such as a seasonal guess
+    #[default]
+    Estimated,
+    /// No conditions could be found
+    Unavailable,
+}
+
+impl WeatherProvenance {
+    /// Provenance of optional conditions, `Unavailable` when there are none
+    pub fn of(weather: Option<&WeatherConditions>) -> Self {
+        weather.map_or(Self::Unavailable, |w| w.provenance)
+    }
 }
 
 /// Location context for the activity
@@ -447,6 +505,37 @@ pub struct UserFitnessProfile {
     pub preferences: UserPreferences,
 }
 
+impl UserFitnessProfile {
+    /// Profile for a user who hasn't filled one in: an intermediate runner
+    /// training about five hours a week, with the injuries they reported
+    pub fn with_defaults(user_id: String, injury_history: Vec<String>) -> Self {
+        Self {
+            user_id,
+            age: Some(30),
+            gender: Some("U".to_string()),
+            weight: Some(70.0),
+            height: Some(175.0),
+            fitness_level: FitnessLevel::Intermediate,
+            primary_sports: vec!["Run".to_string()],
+            training_history_months: 12,
+            preferences: UserPreferences {
+                preferred_units: "metric".to_string(),
+                training_focus: vec!["endurance".to_string()],
+                injury_history,
+                time_availability: TimeAvailability {
+                    hours_per_week: 5.0,
+                    preferred_days: vec![
+                        "Monday".to_string(),
+                        "Wednesday".to_string(),
+                        "Friday".to_string(),
+                    ],
+                    preferred_duration_minutes: Some(60),
+                },
+            },
+        }
+    }
+}
+
 /// Fitness level classification
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FitnessLevel {
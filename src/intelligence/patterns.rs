@@ -0,0 +1,985 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Training pattern detection
+//!
+//! Looks for habits in the user's activity history:
+//!
+//! | Pattern                 | Looks at                                                   |
+//! |-------------------------|------------------------------------------------------------|
+//! | `weekly_rhythm`         | which weekdays and times of day sessions fall on           |
+//! | `long_run_cadence`      | how regularly runs of 1.5× the usual distance come around  |
+//! | `hard_easy_alternation` | whether hard days are followed by easy or rest days        |
+//! | `seasonal_volume`       | how monthly training hours rise and fall over the period   |
+//! | `sport_mix_drift`       | how the share of each sport changed between the halves     |
+//!
+//! Every analysis reports whether the pattern was found, the statistics
+//! behind the call and a confidence combining how much data backed it with
+//! how clear the signal was. Hard sessions are those averaging above the
+//! user's endurance zone, from their effective zone model and max heart rate.
+
+use super::activity_query;
+use super::settings;
+use super::thresholds;
+use super::{Confidence, TimeFrame};
+use crate::models::{Activity, SportType};
+use crate::providers::connections::ProviderConnections;
+use crate::providers::merge::{all_provider_activities, ALL_PROVIDERS};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike, Utc, Weekday};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+/// Activities fetched for the longest timeframe
+const ACTIVITY_LIMIT: usize = 1000;
+/// Fewest activities for weekly rhythm
+const MIN_RHYTHM_ACTIVITIES: usize = 8;
+/// A weekday is preferred when it holds this many times its even share
+const PREFERRED_DAY_FACTOR: f64 = 1.5;
+/// Share of sessions a rhythm needs on preferred days or one time of day
+const RHYTHM_MIN_SHARE: f64 = 0.5;
+/// Fewest runs to tell long runs from the rest
+const MIN_RUNS: usize = 6;
+/// A long run covers this multiple of the median run distance
+const LONG_RUN_FACTOR: f64 = 1.5;
+/// Largest spread of gaps between long runs (coefficient of variation)
+const MAX_CADENCE_VARIATION: f64 = 0.5;
+/// Fewest days with heart rate for hard/easy alternation
+const MIN_RATED_DAYS: usize = 8;
+/// Share of hard days followed by an easy or rest day to call it alternation
+const MIN_ALTERNATION_RATE: f64 = 0.75;
+/// Fewest months for seasonal volume
+const MIN_MONTHS: usize = 6;
+/// Smallest peak-to-trough swing, relative to the monthly mean
+const MIN_SEASONAL_AMPLITUDE: f64 = 0.5;
+/// Fewest activities in each half for sport mix drift
+const MIN_ACTIVITIES_PER_HALF: usize = 4;
+/// Smallest change in sport shares (total variation distance)
+const MIN_MIX_DRIFT: f64 = 0.15;
+
+/// The analyses `detect_patterns` can run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternType {
+    WeeklyRhythm,
+    LongRunCadence,
+    HardEasyAlternation,
+    SeasonalVolume,
+    SportMixDrift,
+}
+
+impl PatternType {
+    pub const ALL: [PatternType; 5] = [
+        Self::WeeklyRhythm,
+        Self::LongRunCadence,
+        Self::HardEasyAlternation,
+        Self::SeasonalVolume,
+        Self::SportMixDrift,
+    ];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::WeeklyRhythm => "weekly_rhythm",
+            Self::LongRunCadence => "long_run_cadence",
+            Self::HardEasyAlternation => "hard_easy_alternation",
+            Self::SeasonalVolume => "seasonal_volume",
+            Self::SportMixDrift => "sport_mix_drift",
+        }
+    }
+
+    /// The analyses selected by a `pattern_type` parameter; `all` runs every one
+    ///
+    /// The pattern types the tool accepted before these analyses existed map
+    /// onto the ones that answer the same question.
+    pub fn select(name: &str) -> Result<Vec<PatternType>> {
+        match name {
+            "all" => return Ok(Self::ALL.to_vec()),
+            "training_consistency" => return Ok(vec![Self::WeeklyRhythm, Self::LongRunCadence]),
+            "seasonal_trends" => return Ok(vec![Self::SeasonalVolume, Self::SportMixDrift]),
+            "performance_plateaus" => {
+                return Ok(vec![Self::HardEasyAlternation, Self::SeasonalVolume])
+            }
+            "injury_risk" => return Ok(vec![Self::HardEasyAlternation, Self::SeasonalVolume]),
+            _ => {}
+        }
+        Self::ALL
+            .into_iter()
+            .find(|pattern| pattern.as_str() == name)
+            .map(|pattern| vec![pattern])
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(PatternType::as_str).collect();
+                anyhow!(
+                    "Unknown pattern_type '{}'. Use 'all' or one of: {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Result of one analysis
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectedPattern {
+    pub pattern_type: PatternType,
+    /// Whether the data shows the pattern
+    pub detected: bool,
+    pub description: String,
+    /// 0-1 score
+    pub confidence: f64,
+    pub confidence_level: Confidence,
+    /// Figures behind the finding
+    pub statistics: Value,
+}
+
+impl DetectedPattern {
+    fn new(
+        pattern_type: PatternType,
+        detected: bool,
+        description: String,
+        confidence: f64,
+        statistics: Value,
+    ) -> Self {
+        let confidence = round(confidence.clamp(0.0, 1.0), 2);
+        Self {
+            pattern_type,
+            detected,
+            description,
+            confidence,
+            confidence_level: Confidence::from_score(confidence),
+            statistics,
+        }
+    }
+
+    fn insufficient(pattern_type: PatternType, reason: String, statistics: Value) -> Self {
+        Self::new(pattern_type, false, reason, 0.0, statistics)
+    }
+}
+
+/// User-specific inputs to the analyses
+#[derive(Debug, Clone)]
+pub struct PatternContext {
+    /// Max heart rate; the highest recorded is used when unknown
+    pub max_heart_rate: Option<f64>,
+    /// Sessions averaging above this share of max heart rate (%) are hard
+    pub hard_effort_percent: f64,
+    pub now: DateTime<Utc>,
+}
+
+/// Run the selected analyses over activities in any order
+pub fn detect_patterns(
+    activities: &[Activity],
+    pattern_types: &[PatternType],
+    context: &PatternContext,
+) -> Vec<DetectedPattern> {
+    let mut activities: Vec<&Activity> = activities.iter().collect();
+    activities.sort_by_key(|a| a.start_date);
+
+    pattern_types
+        .iter()
+        .map(|pattern| match pattern {
+            PatternType::WeeklyRhythm => weekly_rhythm(&activities),
+            PatternType::LongRunCadence => long_run_cadence(&activities, context.now),
+            PatternType::HardEasyAlternation => hard_easy_alternation(&activities, context),
+            PatternType::SeasonalVolume => seasonal_volume(&activities),
+            PatternType::SportMixDrift => sport_mix_drift(&activities),
+        })
+        .collect()
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+/// Share of `full` reached by `count`, capped at 1
+fn sample_factor(count: usize, full: usize) -> f64 {
+    (count as f64 / full as f64).min(1.0)
+}
+
+fn local_date(activity: &Activity) -> NaiveDate {
+    activity.start_date.with_timezone(&Local).date_naive()
+}
+
+fn time_of_day(activity: &Activity) -> &'static str {
+    match activity.start_date.with_timezone(&Local).hour() {
+        5..=6 => "early_morning",
+        7..=10 => "morning",
+        11..=13 => "midday",
+        14..=17 => "afternoon",
+        18..=20 => "evening",
+        _ => "night",
+    }
+}
+
+fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+/// "a", "a and b", "a, b and c"
+fn join_names(names: &[&str]) -> String {
+    match names {
+        [] => String::new(),
+        [only] => only.to_string(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+/// Coarse sport grouping, so indoor and outdoor rides count as one sport
+pub(crate) fn sport_family(sport: &SportType) -> &'static str {
+    match sport {
+        SportType::Run | SportType::VirtualRun | SportType::TrailRunning => "run",
+        SportType::Ride
+        | SportType::VirtualRide
+        | SportType::EbikeRide
+        | SportType::MountainBike
+        | SportType::GravelRide => "ride",
+        SportType::Swim => "swim",
+        SportType::Walk | SportType::Hike | SportType::Snowshoe => "walk",
+        SportType::StrengthTraining | SportType::Crossfit | SportType::Workout => "strength",
+        SportType::Yoga | SportType::Pilates => "mobility",
+        SportType::CrossCountrySkiing
+        | SportType::BackcountrySkiing
+        | SportType::AlpineSkiing
+        | SportType::Snowboarding => "ski",
+        SportType::Kayaking
+        | SportType::Canoeing
+        | SportType::Rowing
+        | SportType::Paddleboarding => "paddle",
+        _ => "other",
+    }
+}
+
+fn weekly_rhythm(activities: &[&Activity]) -> DetectedPattern {
+    let pattern = PatternType::WeeklyRhythm;
+    let total = activities.len();
+    if total < MIN_RHYTHM_ACTIVITIES {
+        return DetectedPattern::insufficient(
+            pattern,
+            format!(
+                "Needs at least {} activities, found {}",
+                MIN_RHYTHM_ACTIVITIES, total
+            ),
+            json!({ "activities": total }),
+        );
+    }
+
+    let mut day_counts: HashMap<Weekday, usize> = HashMap::new();
+    let mut time_counts: HashMap<&str, usize> = HashMap::new();
+    let mut active_weeks = HashSet::new();
+    for activity in activities {
+        let date = local_date(activity);
+        *day_counts.entry(date.weekday()).or_insert(0) += 1;
+        *time_counts.entry(time_of_day(activity)).or_insert(0) += 1;
+        active_weeks.insert(date.iso_week());
+    }
+
+    let first = local_date(activities[0]);
+    let last = local_date(activities[total - 1]);
+    let weeks_spanned = ((last - first).num_days() / 7 + 1) as usize;
+
+    let even_share = total as f64 / 7.0;
+    let mut preferred: Vec<(Weekday, usize)> = day_counts
+        .iter()
+        .filter(|(_, &count)| count as f64 >= PREFERRED_DAY_FACTOR * even_share)
+        .map(|(&day, &count)| (day, count))
+        .collect();
+    preferred.sort_by_key(|&(day, count)| (std::cmp::Reverse(count), day.num_days_from_monday()));
+    let day_share = preferred.iter().map(|(_, count)| count).sum::<usize>() as f64 / total as f64;
+
+    let (top_time, top_time_count) = time_counts
+        .iter()
+        .max_by_key(|(name, &count)| (count, std::cmp::Reverse(*name)))
+        .map(|(&name, &count)| (name, count))
+        .unwrap_or(("morning", 0));
+    let time_share = top_time_count as f64 / total as f64;
+
+    let day_pattern = !preferred.is_empty() && day_share >= RHYTHM_MIN_SHARE;
+    let time_pattern = time_share >= RHYTHM_MIN_SHARE;
+    let preferred_names: Vec<&str> = preferred
+        .iter()
+        .map(|(day, _)| weekday_name(*day))
+        .collect();
+
+    let description = match (day_pattern, time_pattern) {
+        (true, true) => format!(
+            "Trains mostly on {} ({:.0}% of sessions), usually in the {}",
+            join_names(&preferred_names),
+            day_share * 100.0,
+            top_time.replace('_', " ")
+        ),
+        (true, false) => format!(
+            "Trains mostly on {} ({:.0}% of sessions)",
+            join_names(&preferred_names),
+            day_share * 100.0
+        ),
+        (false, true) => format!(
+            "No favourite days, but {:.0}% of sessions are in the {}",
+            time_share * 100.0,
+            top_time.replace('_', " ")
+        ),
+        (false, false) => "Sessions are spread across the week and the day".to_string(),
+    };
+
+    let strength = if day_pattern || time_pattern {
+        day_share.max(time_share)
+    } else {
+        1.0 - day_share.max(time_share)
+    };
+    let confidence = sample_factor(weeks_spanned, 12) * strength;
+
+    let weekday_counts: serde_json::Map<String, Value> = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ]
+    .into_iter()
+    .map(|day| {
+        (
+            weekday_name(day).to_string(),
+            json!(day_counts.get(&day).copied().unwrap_or(0)),
+        )
+    })
+    .collect();
+
+    DetectedPattern::new(
+        pattern,
+        day_pattern || time_pattern,
+        description,
+        confidence,
+        json!({
+            "weekday_counts": weekday_counts,
+            "time_of_day_counts": time_counts,
+            "preferred_days": preferred_names,
+            "preferred_day_share": round(day_share, 2),
+            "preferred_time_of_day": time_pattern.then_some(top_time),
+            "time_of_day_share": round(time_share, 2),
+            "sessions_per_week": round(total as f64 / weeks_spanned as f64, 1),
+            "active_week_share": round(active_weeks.len() as f64 / weeks_spanned as f64, 2),
+            "weeks_analyzed": weeks_spanned,
+        }),
+    )
+}
+
+fn long_run_cadence(activities: &[&Activity], now: DateTime<Utc>) -> DetectedPattern {
+    let pattern = PatternType::LongRunCadence;
+    let runs: Vec<(&Activity, f64)> = activities
+        .iter()
+        .filter(|a| sport_family(&a.sport_type) == "run")
+        .filter_map(|a| a.distance_meters.filter(|d| *d > 0.0).map(|d| (*a, d)))
+        .collect();
+    if runs.len() < MIN_RUNS {
+        return DetectedPattern::insufficient(
+            pattern,
+            format!(
+                "Needs at least {} runs with distance, found {}",
+                MIN_RUNS,
+                runs.len()
+            ),
+            json!({ "runs": runs.len() }),
+        );
+    }
+
+    let mut distances: Vec<f64> = runs.iter().map(|(_, d)| *d).collect();
+    distances.sort_by(f64::total_cmp);
+    let median = distances[distances.len() / 2];
+    let threshold = median * LONG_RUN_FACTOR;
+    let long_runs: Vec<(&Activity, f64)> =
+        runs.into_iter().filter(|(_, d)| *d >= threshold).collect();
+
+    let base = json!({
+        "long_run_threshold_km": round(threshold / 1000.0, 1),
+        "long_runs": long_runs.len(),
+    });
+    if long_runs.len() < 3 {
+        return DetectedPattern::insufficient(
+            pattern,
+            format!(
+                "Only {} runs of {:.1} km or more, too few for a cadence",
+                long_runs.len(),
+                threshold / 1000.0
+            ),
+            base,
+        );
+    }
+
+    let gaps: Vec<f64> = long_runs
+        .windows(2)
+        .map(|pair| (pair[1].0.start_date - pair[0].0.start_date).num_hours() as f64 / 24.0)
+        .collect();
+    let mean_gap = gaps.iter().sum::<f64>() / gaps.len() as f64;
+    let variance = gaps.iter().map(|g| (g - mean_gap).powi(2)).sum::<f64>() / gaps.len() as f64;
+    let variation = if mean_gap > 0.0 {
+        variance.sqrt() / mean_gap
+    } else {
+        1.0
+    };
+
+    let mut day_counts: HashMap<Weekday, usize> = HashMap::new();
+    for (activity, _) in &long_runs {
+        *day_counts
+            .entry(local_date(activity).weekday())
+            .or_insert(0) += 1;
+    }
+    let (usual_day, usual_day_count) = day_counts
+        .iter()
+        .max_by_key(|(day, &count)| (count, std::cmp::Reverse(day.num_days_from_monday())))
+        .map(|(&day, &count)| (day, count))
+        .unwrap_or((Weekday::Sun, 0));
+
+    let last = long_runs[long_runs.len() - 1].0.start_date;
+    let days_since = (now - last).num_hours() as f64 / 24.0;
+    let average_km =
+        long_runs.iter().map(|(_, d)| d).sum::<f64>() / long_runs.len() as f64 / 1000.0;
+
+    let regular = variation <= MAX_CADENCE_VARIATION;
+    let cadence = if mean_gap <= 9.0 {
+        "weekly".to_string()
+    } else if mean_gap <= 16.0 {
+        "every two weeks".to_string()
+    } else {
+        format!("every {:.0} days", mean_gap)
+    };
+    let description = if regular {
+        format!(
+            "Long runs of {:.1} km or more come {}, most often on {}",
+            threshold / 1000.0,
+            cadence,
+            weekday_name(usual_day)
+        )
+    } else {
+        format!(
+            "Long runs are irregular: gaps average {:.0} days but vary by {:.0}%",
+            mean_gap,
+            variation * 100.0
+        )
+    };
+    let clarity = if regular {
+        1.0 - variation
+    } else {
+        variation.min(1.0)
+    };
+
+    let mut statistics = base;
+    statistics["average_long_run_km"] = json!(round(average_km, 1));
+    statistics["average_gap_days"] = json!(round(mean_gap, 1));
+    statistics["gap_variation"] = json!(round(variation, 2));
+    statistics["usual_day"] = json!(weekday_name(usual_day));
+    statistics["usual_day_share"] =
+        json!(round(usual_day_count as f64 / long_runs.len() as f64, 2));
+    statistics["last_long_run"] = json!(last.to_rfc3339());
+    statistics["days_since_last_long_run"] = json!(round(days_since, 1));
+    statistics["overdue"] = json!(regular && days_since > mean_gap * 1.5);
+
+    DetectedPattern::new(
+        pattern,
+        regular,
+        description,
+        sample_factor(gaps.len(), 6) * clarity,
+        statistics,
+    )
+}
+
+fn hard_easy_alternation(activities: &[&Activity], context: &PatternContext) -> DetectedPattern {
+    let pattern = PatternType::HardEasyAlternation;
+    let max_heart_rate = context.max_heart_rate.or_else(|| {
+        activities
+            .iter()
+            .filter_map(|a| a.max_heart_rate)
+            .max()
+            .map(f64::from)
+    });
+    let Some(max_heart_rate) = max_heart_rate else {
+        return DetectedPattern::insufficient(
+            pattern,
+            "Needs activities with heart rate".to_string(),
+            json!({ "activities_with_heart_rate": 0 }),
+        );
+    };
+    let hard_threshold = max_heart_rate * context.hard_effort_percent / 100.0;
+
+    // A day is hard when any of its sessions was
+    let mut days: BTreeMap<NaiveDate, bool> = BTreeMap::new();
+    let mut without_heart_rate = 0;
+    for activity in activities {
+        match activity.average_heart_rate {
+            Some(hr) => {
+                let hard = f64::from(hr) >= hard_threshold;
+                *days.entry(local_date(activity)).or_insert(false) |= hard;
+            }
+            None => without_heart_rate += 1,
+        }
+    }
+    if days.len() < MIN_RATED_DAYS {
+        return DetectedPattern::insufficient(
+            pattern,
+            format!(
+                "Needs at least {} training days with heart rate, found {}",
+                MIN_RATED_DAYS,
+                days.len()
+            ),
+            json!({ "days_with_heart_rate": days.len() }),
+        );
+    }
+
+    let last_day = days.keys().next_back().copied();
+    let mut recovered = 0;
+    let mut back_to_back = 0;
+    for (date, _) in days.iter().filter(|(_, &hard)| hard) {
+        if Some(*date) == last_day {
+            continue;
+        }
+        let next = *date + Duration::days(1);
+        if days.get(&next).copied().unwrap_or(false) {
+            back_to_back += 1;
+        } else {
+            recovered += 1;
+        }
+    }
+    let hard_days = days.values().filter(|&&hard| hard).count();
+    let followed = recovered + back_to_back;
+
+    let base = json!({
+        "max_heart_rate": max_heart_rate,
+        "hard_threshold_bpm": hard_threshold.round(),
+        "hard_days": hard_days,
+        "easy_days": days.len() - hard_days,
+        "hard_day_share": round(hard_days as f64 / days.len() as f64, 2),
+        "sessions_without_heart_rate": without_heart_rate,
+    });
+    if followed < 3 {
+        return DetectedPattern::insufficient(
+            pattern,
+            format!("Only {} hard days, too few to judge recovery", hard_days),
+            base,
+        );
+    }
+
+    let rate = recovered as f64 / followed as f64;
+    let alternating = rate >= MIN_ALTERNATION_RATE;
+    let description = if alternating {
+        format!(
+            "Hard days are followed by an easy or rest day {:.0}% of the time",
+            rate * 100.0
+        )
+    } else {
+        format!(
+            "Hard days run back to back in {} of {} cases",
+            back_to_back, followed
+        )
+    };
+
+    let mut statistics = base;
+    statistics["back_to_back_hard_days"] = json!(back_to_back);
+    statistics["alternation_rate"] = json!(round(rate, 2));
+
+    DetectedPattern::new(
+        pattern,
+        alternating,
+        description,
+        sample_factor(followed, 8) * rate.max(1.0 - rate),
+        statistics,
+    )
+}
+
+fn seasonal_volume(activities: &[&Activity]) -> DetectedPattern {
+    let pattern = PatternType::SeasonalVolume;
+    let Some((first, last)) = activities.first().zip(activities.last()) else {
+        return DetectedPattern::insufficient(
+            pattern,
+            format!("Needs at least {} months of activities", MIN_MONTHS),
+            json!({ "months": 0 }),
+        );
+    };
+
+    // Every month in the span, including empty ones
+    let mut hours: BTreeMap<(i32, u32), f64> = BTreeMap::new();
+    let (first, last) = (local_date(first), local_date(last));
+    let mut month = (first.year(), first.month());
+    while month <= (last.year(), last.month()) {
+        hours.insert(month, 0.0);
+        month = if month.1 == 12 {
+            (month.0 + 1, 1)
+        } else {
+            (month.0, month.1 + 1)
+        };
+    }
+    for activity in activities {
+        let date = local_date(activity);
+        *hours.entry((date.year(), date.month())).or_insert(0.0) +=
+            activity.duration_seconds as f64 / 3600.0;
+    }
+    if hours.len() < MIN_MONTHS {
+        return DetectedPattern::insufficient(
+            pattern,
+            format!(
+                "Needs at least {} months of activities, found {}",
+                MIN_MONTHS,
+                hours.len()
+            ),
+            json!({ "months": hours.len() }),
+        );
+    }
+
+    let mean = hours.values().sum::<f64>() / hours.len() as f64;
+    let (&peak, &peak_hours) = hours
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .unwrap_or((&(0, 0), &0.0));
+    let (&trough, &trough_hours) = hours
+        .iter()
+        .min_by(|a, b| a.1.total_cmp(b.1))
+        .unwrap_or((&(0, 0), &0.0));
+    let amplitude = if mean > 0.0 {
+        (peak_hours - trough_hours) / mean
+    } else {
+        0.0
+    };
+    let month_name = |(year, month): (i32, u32)| {
+        NaiveDate::from_ymd_opt(year, month, 1)
+            .map(|d| d.format("%B %Y").to_string())
+            .unwrap_or_default()
+    };
+
+    let cyclic = amplitude >= MIN_SEASONAL_AMPLITUDE;
+    let description = if cyclic {
+        format!(
+            "Volume peaks in {} ({:.1} h) and dips in {} ({:.1} h)",
+            month_name(peak),
+            peak_hours,
+            month_name(trough),
+            trough_hours
+        )
+    } else {
+        format!("Monthly volume is steady at about {:.1} hours", mean)
+    };
+    let clarity = if cyclic {
+        amplitude.min(1.0)
+    } else {
+        1.0 - amplitude / MIN_SEASONAL_AMPLITUDE
+    };
+    let monthly: Vec<Value> = hours
+        .iter()
+        .map(|((year, month), h)| {
+            json!({ "month": format!("{}-{:02}", year, month), "hours": round(*h, 1) })
+        })
+        .collect();
+
+    DetectedPattern::new(
+        pattern,
+        cyclic,
+        description,
+        sample_factor(hours.len(), 12) * clarity,
+        json!({
+            "monthly_hours": monthly,
+            "average_monthly_hours": round(mean, 1),
+            "peak_month": format!("{}-{:02}", peak.0, peak.1),
+            "trough_month": format!("{}-{:02}", trough.0, trough.1),
+            "amplitude": round(amplitude, 2),
+        }),
+    )
+}
+
+/// Share of training time per sport family
+fn sport_shares(activities: &[&Activity]) -> BTreeMap<&'static str, f64> {
+    let mut seconds: BTreeMap<&'static str, f64> = BTreeMap::new();
+    for activity in activities {
+        *seconds
+            .entry(sport_family(&activity.sport_type))
+            .or_insert(0.0) += activity.duration_seconds as f64;
+    }
+    let total: f64 = seconds.values().sum();
+    if total > 0.0 {
+        for share in seconds.values_mut() {
+            *share /= total;
+        }
+    }
+    seconds
+}
+
+fn sport_mix_drift(activities: &[&Activity]) -> DetectedPattern {
+    let pattern = PatternType::SportMixDrift;
+    let (Some(first), Some(last)) = (activities.first(), activities.last()) else {
+        return DetectedPattern::insufficient(
+            pattern,
+            "No activities to compare".to_string(),
+            json!({ "activities": 0 }),
+        );
+    };
+    let midpoint = first.start_date + (last.start_date - first.start_date) / 2;
+    let split = activities.partition_point(|a| a.start_date < midpoint);
+    let (earlier, recent) = activities.split_at(split);
+    if earlier.len() < MIN_ACTIVITIES_PER_HALF || recent.len() < MIN_ACTIVITIES_PER_HALF {
+        return DetectedPattern::insufficient(
+            pattern,
+            format!(
+                "Needs at least {} activities in each half of the period",
+                MIN_ACTIVITIES_PER_HALF
+            ),
+            json!({ "earlier_activities": earlier.len(), "recent_activities": recent.len() }),
+        );
+    }
+
+    let before = sport_shares(earlier);
+    let after = sport_shares(recent);
+    let sports: HashSet<&str> = before.keys().chain(after.keys()).copied().collect();
+    let mut changes: Vec<(&str, f64)> = sports
+        .into_iter()
+        .map(|sport| {
+            let change = after.get(sport).unwrap_or(&0.0) - before.get(sport).unwrap_or(&0.0);
+            (sport, change)
+        })
+        .collect();
+    changes.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+    let drift = changes.iter().map(|(_, c)| c.abs()).sum::<f64>() / 2.0;
+
+    let drifting = drift >= MIN_MIX_DRIFT;
+    let (gain, drop) = (changes[0], changes[changes.len() - 1]);
+    let description = if drifting {
+        format!(
+            "Shifting toward {} ({:+.0} points) and away from {} ({:+.0} points) of training time",
+            gain.0,
+            gain.1 * 100.0,
+            drop.0,
+            drop.1 * 100.0
+        )
+    } else {
+        "The mix of sports has stayed about the same".to_string()
+    };
+    let clarity = if drifting {
+        (drift / (2.0 * MIN_MIX_DRIFT)).min(1.0)
+    } else {
+        1.0 - drift / MIN_MIX_DRIFT
+    };
+    let shares = |shares: &BTreeMap<&str, f64>| -> BTreeMap<String, f64> {
+        shares
+            .iter()
+            .map(|(sport, share)| (sport.to_string(), round(*share, 2)))
+            .collect()
+    };
+
+    DetectedPattern::new(
+        pattern,
+        drifting,
+        description,
+        sample_factor(earlier.len().min(recent.len()), 10) * clarity,
+        json!({
+            "earlier_mix": shares(&before),
+            "recent_mix": shares(&after),
+            "drift": round(drift, 2),
+            "split_date": midpoint.to_rfc3339(),
+            "earlier_activities": earlier.len(),
+            "recent_activities": recent.len(),
+        }),
+    )
+}
+
+/// Parse the `timeframe` parameter, defaulting to a year
+pub fn parse_timeframe(timeframe: Option<&str>) -> TimeFrame {
+    match timeframe {
+        Some("week") => TimeFrame::Week,
+        Some("month") => TimeFrame::Month,
+        Some("quarter") => TimeFrame::Quarter,
+        Some("sixmonths") => TimeFrame::SixMonths,
+        _ => TimeFrame::Year,
+    }
+}
+
+/// Result of the `detect_patterns` tool for a user
+///
+/// Without a provider, or with `all`, every connected provider's activities
+/// are merged with manual ones.
+pub async fn patterns_for_user(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    provider: Option<&str>,
+    pattern_type: &str,
+    timeframe: Option<&str>,
+) -> Result<Value> {
+    let database = connections.database();
+    let pattern_types = PatternType::select(pattern_type)?;
+    let timeframe = parse_timeframe(timeframe);
+    let start = timeframe.start_date();
+
+    let activities: Vec<Activity> = match provider {
+        None | Some(ALL_PROVIDERS) => all_provider_activities(connections, user_id, ACTIVITY_LIMIT)
+            .await?
+            .into_iter()
+            .filter(|a| a.start_date >= start)
+            .collect(),
+        Some(name) => {
+            activity_query::history_for_user(connections, user_id, Some(name), Some(start), None)
+                .await?
+                .activities
+        }
+    };
+
+    let config = settings::effective_config(database, user_id).await;
+    let max_heart_rate = thresholds::load_thresholds(database, user_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|stored| stored.current.max_heart_rate)
+        .map(|estimate| estimate.value);
+    let context = PatternContext {
+        max_heart_rate,
+        hard_effort_percent: f64::from(config.zone_thresholds.endurance_max),
+        now: Utc::now(),
+    };
+
+    let patterns = detect_patterns(&activities, &pattern_types, &context);
+    let detected: Vec<&str> = patterns
+        .iter()
+        .filter(|p| p.detected)
+        .map(|p| p.description.as_str())
+        .collect();
+    Ok(json!({
+        "pattern_analysis": {
+            "pattern_type": pattern_type,
+            "timeframe_days": timeframe.to_days(),
+            "activities_analyzed": activities.len(),
+            "patterns_detected": detected,
+            "patterns": patterns,
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(id: usize, sport: SportType, start: DateTime<Utc>, minutes: u64) -> Activity {
+        Activity {
+            id: id.to_string(),
+            name: format!("Activity {}", id),
+            sport_type: sport,
+            start_date: start,
+            duration_seconds: minutes * 60,
+            provider: "manual".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn context() -> PatternContext {
+        PatternContext {
+            max_heart_rate: Some(190.0),
+            hard_effort_percent: 70.0,
+            now: "2024-07-01T12:00:00Z".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_select_pattern_types() {
+        assert_eq!(PatternType::select("all").unwrap().len(), 5);
+        assert_eq!(
+            PatternType::select("long_run_cadence").unwrap(),
+            vec![PatternType::LongRunCadence]
+        );
+        assert_eq!(
+            PatternType::select("training_consistency").unwrap(),
+            vec![PatternType::WeeklyRhythm, PatternType::LongRunCadence]
+        );
+        let error = PatternType::select("plateaus").unwrap_err().to_string();
+        assert!(error.contains("weekly_rhythm"));
+    }
+
+    #[test]
+    fn test_long_run_cadence_and_alternation() {
+        // Twelve weeks of an easy Tuesday, hard Thursday and long Sunday
+        let start: DateTime<Utc> = "2024-04-02T12:00:00Z".parse().unwrap();
+        let mut activities = vec![];
+        for week in 0..12 {
+            let monday = start + Duration::weeks(week) - Duration::days(1);
+            let sessions = [(1, 6000.0, 125), (3, 8000.0, 160), (6, 18000.0, 128)];
+            for (day, distance, heart_rate) in sessions {
+                let mut run = activity(
+                    activities.len(),
+                    SportType::Run,
+                    monday + Duration::days(day),
+                    50,
+                );
+                run.distance_meters = Some(distance);
+                run.average_heart_rate = Some(heart_rate);
+                activities.push(run);
+            }
+        }
+
+        let patterns = detect_patterns(
+            &activities,
+            &[
+                PatternType::LongRunCadence,
+                PatternType::HardEasyAlternation,
+            ],
+            &context(),
+        );
+
+        let cadence = &patterns[0];
+        assert!(cadence.detected);
+        assert_eq!(cadence.statistics["long_runs"], 12);
+        assert_eq!(cadence.statistics["average_gap_days"], 7.0);
+        assert_eq!(cadence.statistics["usual_day"], "Sunday");
+        assert_eq!(cadence.confidence, 1.0);
+        assert!(cadence.description.contains("weekly"));
+
+        let alternation = &patterns[1];
+        assert!(alternation.detected);
+        assert_eq!(alternation.statistics["hard_days"], 12);
+        assert_eq!(alternation.statistics["back_to_back_hard_days"], 0);
+        assert_eq!(alternation.statistics["alternation_rate"], 1.0);
+    }
+
+    #[test]
+    fn test_sport_mix_drift_and_seasonal_volume() {
+        // Six months of running that turns into cycling, with summer volume
+        let start: DateTime<Utc> = "2024-01-03T12:00:00Z".parse().unwrap();
+        let activities: Vec<Activity> = (0..26)
+            .map(|week| {
+                let sport = if week < 13 {
+                    SportType::Run
+                } else {
+                    SportType::Ride
+                };
+                let minutes = if week < 17 { 60 } else { 180 };
+                activity(week, sport, start + Duration::weeks(week as i64), minutes)
+            })
+            .collect();
+
+        let patterns = detect_patterns(
+            &activities,
+            &[PatternType::SportMixDrift, PatternType::SeasonalVolume],
+            &context(),
+        );
+
+        let drift = &patterns[0];
+        assert!(drift.detected);
+        assert!(drift.description.starts_with("Shifting toward ride"));
+        assert_eq!(drift.statistics["recent_mix"]["ride"], 1.0);
+
+        let seasonal = &patterns[1];
+        assert!(seasonal.detected);
+        assert_eq!(seasonal.statistics["peak_month"], "2024-05");
+        assert_eq!(
+            seasonal.statistics["monthly_hours"]
+                .as_array()
+                .unwrap()
+                .len(),
+            6
+        );
+    }
+}
@@ -1,5 +1,6 @@
 //! Performance trend analysis and historical comparison engine
 
+use super::adjusted_pace::AdjustedPace;
 use super::*;
 use crate::models::Activity;
 use anyhow::Result;
@@ -31,10 +32,15 @@ pub trait PerformanceAnalyzerTrait {
     async fn analyze_training_load(&self, activities: &[Activity]) -> Result<TrainingLoadAnalysis>;
 }
 
+/// Trend metrics measured in seconds per km, where lower is better
+const PACE_METRICS: [&str; 3] = ["pace", "grade_adjusted_pace", "adjusted_pace"];
+
 /// Advanced performance analyzer implementation
 pub struct AdvancedPerformanceAnalyzer {
     #[allow(dead_code)]
     user_profile: Option<UserFitnessProfile>,
+    /// Measured weather by activity ID, for the `adjusted_pace` metric
+    weather: HashMap<String, WeatherConditions>,
 }
 
 impl Default for AdvancedPerformanceAnalyzer {
@@ -46,16 +52,26 @@ impl Default for AdvancedPerformanceAnalyzer {
 impl AdvancedPerformanceAnalyzer {
     /// Create a new performance analyzer
     pub fn new() -> Self {
-        Self { user_profile: None }
+        Self {
+            user_profile: None,
+            weather: HashMap::new(),
+        }
     }
 
     /// Create analyzer with user profile
     pub fn with_profile(profile: UserFitnessProfile) -> Self {
         Self {
             user_profile: Some(profile),
+            weather: HashMap::new(),
         }
     }
 
+    /// Weather of each activity by ID, used to heat-adjust pace
+    pub fn with_weather(mut self, weather: HashMap<String, WeatherConditions>) -> Self {
+        self.weather = weather;
+        self
+    }
+
     /// Calculate statistical trend strength
     fn calculate_trend_strength(&self, data_points: &[TrendDataPoint]) -> f64 {
         if data_points.len() < 2 {
@@ -209,8 +225,13 @@ impl PerformanceAnalyzerTrait for AdvancedPerformanceAnalyzer {
         for activity in filtered_activities {
             let activity_utc = activity.start_date;
 
+            let adjusted =
+                || AdjustedPace::for_activity(activity, None, self.weather.get(&activity.id));
             let value = match metric {
-                "pace" | "speed" => activity.average_speed,
+                "pace" => adjusted().map(|a| a.pace_seconds_per_km),
+                "grade_adjusted_pace" => adjusted().map(|a| a.grade_adjusted_pace_seconds_per_km),
+                "adjusted_pace" => adjusted().map(|a| a.adjusted_pace_seconds_per_km),
+                "speed" => activity.average_speed,
                 "heart_rate" => activity.average_heart_rate.map(|hr| hr as f64),
                 "power" => None, // Power data not available in Activity model
                 "distance" => activity.distance_meters,
@@ -236,7 +257,7 @@ impl PerformanceAnalyzerTrait for AdvancedPerformanceAnalyzer {
         }
 
         // Sort by date
-        data_points.sort_by(|a, b| a.date.cmp(&b.date));
+        data_points.sort_by_key(|a| a.date);
 
         // Apply smoothing
         self.apply_smoothing(&mut data_points, 3);
@@ -256,13 +277,13 @@ impl PerformanceAnalyzerTrait for AdvancedPerformanceAnalyzer {
         let trend_direction = if (second_half_avg - first_half_avg).abs() < first_half_avg * 0.05 {
             TrendDirection::Stable
         } else if second_half_avg > first_half_avg {
-            if metric == "pace" {
+            if PACE_METRICS.contains(&metric) {
                 // For pace, lower is better
                 TrendDirection::Declining
             } else {
                 TrendDirection::Improving
             }
-        } else if metric == "pace" {
+        } else if PACE_METRICS.contains(&metric) {
             // For pace, lower is better
             TrendDirection::Improving
         } else {
@@ -639,6 +660,36 @@ mod tests {
         assert!(analysis.trend_strength > 0.5);
     }
 
+    #[tokio::test]
+    async fn test_adjusted_pace_trend() {
+        // The same 10 km in the same time, on hillier and hillier routes
+        let activities: Vec<Activity> = (0..10)
+            .map(|i| Activity {
+                id: i.to_string(),
+                sport_type: crate::models::SportType::Run,
+                duration_seconds: 3000,
+                distance_meters: Some(10_000.0),
+                elevation_gain: Some((9 - i) as f64 * 40.0),
+                start_date: Utc::now() - chrono::Duration::days(i * 7),
+                ..Activity::default()
+            })
+            .collect();
+
+        let analyzer = AdvancedPerformanceAnalyzer::new();
+        let pace = analyzer
+            .analyze_trends(&activities, TimeFrame::Quarter, "pace")
+            .await
+            .unwrap();
+        assert_eq!(pace.trend_direction, TrendDirection::Stable);
+        assert_eq!(pace.data_points[0].value, 300.0);
+
+        let grade_adjusted = analyzer
+            .analyze_trends(&activities, TimeFrame::Quarter, "grade_adjusted_pace")
+            .await
+            .unwrap();
+        assert_eq!(grade_adjusted.trend_direction, TrendDirection::Improving);
+    }
+
     #[tokio::test]
     async fn test_fitness_score() {
         let analyzer = AdvancedPerformanceAnalyzer::new();
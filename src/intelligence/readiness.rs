@@ -0,0 +1,538 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Daily readiness score
+//!
+//! Combines four recovery factors into a 0-100 score for one day:
+//!
+//! | Factor        | Weight | Full marks when                              |
+//! |---------------|--------|----------------------------------------------|
+//! | HRV           | 30%    | at or above the user's baseline              |
+//! | Sleep         | 30%    | 8 hours at high efficiency / quality         |
+//! | Resting HR    | 20%    | at or below the user's baseline              |
+//! | Training load | 20%    | last 7 days no heavier than the 28-day norm  |
+//!
+//! Factors without data are left out and the remaining weights rescaled, so
+//! a manual sleep entry alone still yields a (less certain) score. Baselines
+//! come from the days before the scored day within the 28-day window.
+
+use super::insights::{Insight, InsightType};
+use super::wellness;
+use crate::database_plugins::factory::Database;
+use crate::models::{Activity, DailyWellness};
+use crate::providers::connections::ProviderConnections;
+use crate::providers::merge::{deduplicate, merge_config};
+use crate::providers::{registry, ProviderCapability};
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Days of history used for baselines and chronic load
+pub const WINDOW_DAYS: i64 = 28;
+/// Readiness at or above this is high
+const HIGH_READINESS: u8 = 70;
+/// Readiness below this is low
+const LOW_READINESS: u8 = 50;
+/// Baselines need at least this many earlier readings
+const MIN_BASELINE_DAYS: usize = 3;
+/// Chronic load needs activities spanning at least this many days
+const MIN_LOAD_HISTORY_DAYS: i64 = 14;
+/// Sleep duration that earns full marks
+const TARGET_SLEEP_HOURS: f64 = 8.0;
+
+const HRV_WEIGHT: f64 = 0.3;
+const SLEEP_WEIGHT: f64 = 0.3;
+const RESTING_HR_WEIGHT: f64 = 0.2;
+const LOAD_WEIGHT: f64 = 0.2;
+
+/// Readiness band
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessLevel {
+    /// Hard sessions should be swapped for easy work or rest
+    Low,
+    /// Train, but cap the intensity
+    Moderate,
+    /// Ready for hard training
+    High,
+}
+
+/// Factor feeding the readiness score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessFactorKind {
+    Hrv,
+    Sleep,
+    RestingHeartRate,
+    TrainingLoad,
+}
+
+/// One factor's contribution to the readiness score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessFactor {
+    pub factor: ReadinessFactorKind,
+    /// Factor score (0-100)
+    pub score: f64,
+    /// Share of the final score after rescaling for missing factors
+    pub weight: f64,
+    /// Value on the scored day
+    pub value: f64,
+    /// Value the day is compared against, when the factor has one
+    pub baseline: Option<f64>,
+    pub description: String,
+}
+
+/// Readiness for one day with the factors behind it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessScore {
+    pub date: NaiveDate,
+    /// Weighted score (0-100)
+    pub score: u8,
+    pub level: ReadinessLevel,
+    /// Factors with data, strongest drag on the score first
+    pub factors: Vec<ReadinessFactor>,
+    /// Factors left out for lack of data
+    pub missing_factors: Vec<ReadinessFactorKind>,
+}
+
+impl ReadinessScore {
+    /// Recovery insights for the factors pulling the score down
+    pub fn insights(&self) -> Vec<Insight> {
+        self.factors
+            .iter()
+            .filter(|factor| factor.score < f64::from(LOW_READINESS))
+            .map(|factor| Insight {
+                insight_type: InsightType::RecoveryInsight,
+                message: factor.description.clone(),
+                confidence: 80.0,
+                data: serde_json::to_value(factor).ok(),
+            })
+            .collect()
+    }
+}
+
+/// Computes daily readiness from wellness days and recent activities
+#[derive(Debug, Clone, Default)]
+pub struct ReadinessEngine;
+
+impl ReadinessEngine {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Score a day, or `None` when there is no data for any factor
+    ///
+    /// `wellness` holds at most one (merged) record per date; `activities`
+    /// may span any range, only the 28 days up to `date` are used.
+    pub fn calculate(
+        &self,
+        date: NaiveDate,
+        wellness: &[DailyWellness],
+        activities: &[Activity],
+    ) -> Option<ReadinessScore> {
+        let window_start = date - Duration::days(WINDOW_DAYS - 1);
+        let today = wellness.iter().find(|day| day.date == date);
+        let history: Vec<&DailyWellness> = wellness
+            .iter()
+            .filter(|day| day.date >= window_start && day.date < date)
+            .collect();
+
+        let candidates = [
+            (
+                ReadinessFactorKind::Hrv,
+                HRV_WEIGHT,
+                hrv_factor(today, &history),
+            ),
+            (
+                ReadinessFactorKind::Sleep,
+                SLEEP_WEIGHT,
+                sleep_factor(today),
+            ),
+            (
+                ReadinessFactorKind::RestingHeartRate,
+                RESTING_HR_WEIGHT,
+                resting_hr_factor(today, &history),
+            ),
+            (
+                ReadinessFactorKind::TrainingLoad,
+                LOAD_WEIGHT,
+                load_factor(date, activities),
+            ),
+        ];
+
+        let total_weight: f64 = candidates
+            .iter()
+            .filter(|(_, _, factor)| factor.is_some())
+            .map(|(_, weight, _)| weight)
+            .sum();
+        if total_weight == 0.0 {
+            return None;
+        }
+
+        let mut factors = Vec::new();
+        let mut missing_factors = Vec::new();
+        for (kind, weight, factor) in candidates {
+            match factor {
+                Some(partial) => factors.push(ReadinessFactor {
+                    factor: kind,
+                    score: partial.score.clamp(0.0, 100.0),
+                    weight: weight / total_weight,
+                    value: partial.value,
+                    baseline: partial.baseline,
+                    description: partial.description,
+                }),
+                None => missing_factors.push(kind),
+            }
+        }
+
+        let score = factors
+            .iter()
+            .map(|f| f.score * f.weight)
+            .sum::<f64>()
+            .round() as u8;
+        factors.sort_by(|a, b| a.score.total_cmp(&b.score));
+
+        Some(ReadinessScore {
+            date,
+            score,
+            level: level_for(score),
+            factors,
+            missing_factors,
+        })
+    }
+}
+
+fn level_for(score: u8) -> ReadinessLevel {
+    if score >= HIGH_READINESS {
+        ReadinessLevel::High
+    } else if score >= LOW_READINESS {
+        ReadinessLevel::Moderate
+    } else {
+        ReadinessLevel::Low
+    }
+}
+
+/// Factor before weights are assigned
+struct PartialFactor {
+    score: f64,
+    value: f64,
+    baseline: Option<f64>,
+    description: String,
+}
+
+fn baseline(
+    history: &[&DailyWellness],
+    metric: impl Fn(&DailyWellness) -> Option<f64>,
+) -> Option<f64> {
+    let values: Vec<f64> = history.iter().filter_map(|day| metric(day)).collect();
+    (values.len() >= MIN_BASELINE_DAYS).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// 75 at baseline, 2.5 points per percent above or below it
+fn hrv_factor(today: Option<&DailyWellness>, history: &[&DailyWellness]) -> Option<PartialFactor> {
+    let value = today?.hrv_rmssd_ms?;
+    let baseline = baseline(history, |day| day.hrv_rmssd_ms).filter(|b| *b > 0.0)?;
+    let deviation = (value - baseline) / baseline * 100.0;
+
+    Some(PartialFactor {
+        score: 75.0 + deviation * 2.5,
+        value,
+        baseline: Some(baseline),
+        description: format!(
+            "HRV {:.0} ms is {:.0}% {} your {:.0} ms baseline",
+            value,
+            deviation.abs(),
+            if deviation < 0.0 { "below" } else { "above" },
+            baseline
+        ),
+    })
+}
+
+/// Duration against 8 hours, blended 70/30 with efficiency or self-reported quality
+fn sleep_factor(today: Option<&DailyWellness>) -> Option<PartialFactor> {
+    let sleep = today?.sleep.as_ref()?;
+    let hours = f64::from(sleep.minutes_asleep) / 60.0;
+    let duration_score = (hours / TARGET_SLEEP_HOURS * 100.0).min(100.0);
+    let quality_score = sleep
+        .efficiency
+        .map(f64::from)
+        .or_else(|| sleep.quality.map(|q| f64::from(q.clamp(1, 5) - 1) * 25.0));
+
+    Some(PartialFactor {
+        score: match quality_score {
+            Some(quality) => duration_score * 0.7 + quality * 0.3,
+            None => duration_score,
+        },
+        value: hours,
+        baseline: Some(TARGET_SLEEP_HOURS),
+        description: match quality_score {
+            Some(quality) => format!("Slept {:.1} hours at {:.0}% quality", hours, quality),
+            None => format!("Slept {:.1} hours", hours),
+        },
+    })
+}
+
+/// Full marks at or below baseline, minus 8 points per bpm above it
+fn resting_hr_factor(
+    today: Option<&DailyWellness>,
+    history: &[&DailyWellness],
+) -> Option<PartialFactor> {
+    let value = f64::from(today?.resting_heart_rate?);
+    let baseline = baseline(history, |day| day.resting_heart_rate.map(f64::from))?;
+    let elevation = value - baseline;
+
+    Some(PartialFactor {
+        score: 100.0 - elevation.max(0.0) * 8.0,
+        value,
+        baseline: Some(baseline),
+        description: if elevation > 0.0 {
+            format!(
+                "Resting heart rate {:.0} bpm is {:.1} bpm above your baseline",
+                value, elevation
+            )
+        } else {
+            format!(
+                "Resting heart rate {:.0} bpm is at or below your baseline",
+                value
+            )
+        },
+    })
+}
+
+/// Acute (7-day) against chronic (28-day weekly average) load
+///
+/// Full marks up to a ratio of 1.0, falling to 40 at 1.5 and 20 beyond.
+fn load_factor(date: NaiveDate, activities: &[Activity]) -> Option<PartialFactor> {
+    let window_start = date - Duration::days(WINDOW_DAYS - 1);
+    let acute_start = date - Duration::days(6);
+    let in_window: Vec<&Activity> = activities
+        .iter()
+        .filter(|a| {
+            let day = a.start_date.date_naive();
+            day >= window_start && day <= date
+        })
+        .collect();
+
+    let earliest = in_window.iter().map(|a| a.start_date.date_naive()).min()?;
+    if (date - earliest).num_days() < MIN_LOAD_HISTORY_DAYS {
+        return None;
+    }
+
+    let chronic_weekly = in_window.iter().map(|a| training_load(a)).sum::<f64>() / 4.0;
+    let acute: f64 = in_window
+        .iter()
+        .filter(|a| a.start_date.date_naive() >= acute_start)
+        .map(|a| training_load(a))
+        .sum();
+    let ratio = acute / chronic_weekly;
+
+    let score = if ratio <= 1.0 {
+        100.0
+    } else if ratio <= 1.5 {
+        100.0 - (ratio - 1.0) * 120.0
+    } else {
+        (40.0 - (ratio - 1.5) * 80.0).max(20.0)
+    };
+
+    Some(PartialFactor {
+        score,
+        value: acute,
+        baseline: Some(chronic_weekly),
+        description: format!(
+            "Last 7 days' load is {:.1}x your 4-week weekly average",
+            ratio
+        ),
+    })
+}
+
+/// Training minutes weighted by heart-rate intensity
+pub(crate) fn training_load(activity: &Activity) -> f64 {
+    let minutes = activity.duration_seconds as f64 / 60.0;
+    let intensity = match activity.average_heart_rate {
+        Some(hr) if hr > 160 => 2.0,
+        Some(hr) if hr >= 140 => 1.5,
+        _ => 1.0,
+    };
+    minutes * intensity
+}
+
+/// Score a user's readiness for a day
+///
+/// Wellness days are refreshed from every connected wellness provider, then
+/// merged with manual entries; training load comes from recent activities of
+/// connected activity providers, with workouts recorded by several providers
+/// counted once. Provider failures only drop their data.
+pub async fn readiness_for_user(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    date: NaiveDate,
+) -> Result<Option<ReadinessScore>> {
+    let database = connections.database();
+    let window_start = date - Duration::days(WINDOW_DAYS - 1);
+    let mut activities = Vec::new();
+
+    for name in registry().provider_names() {
+        let Some(descriptor) = registry().get(name) else {
+            continue;
+        };
+        let provider = match connections.connected_provider(user_id, name).await {
+            Ok(Some(provider)) => provider,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("Skipping {} data for readiness: {}", name, e);
+                continue;
+            }
+        };
+
+        if descriptor.supports(ProviderCapability::Wellness) {
+            if let Err(e) = wellness::sync_daily_wellness(
+                database,
+                provider.as_ref(),
+                user_id,
+                window_start,
+                date,
+            )
+            .await
+            {
+                warn!("Skipping {} wellness data for readiness: {}", name, e);
+            }
+        }
+        if descriptor.supports(ProviderCapability::Activities) {
+            match provider.get_activities(Some(100), None).await {
+                Ok(recent) => activities.extend(recent),
+                Err(e) => warn!("Skipping {} activities for readiness: {}", name, e),
+            }
+        }
+    }
+
+    let activities = deduplicate(activities, &merge_config());
+    let days = wellness::merged_wellness(database, user_id, window_start, date).await?;
+    Ok(ReadinessEngine::new().calculate(date, &days, &activities))
+}
+
+/// Score readiness from already stored wellness days and the given activities
+///
+/// Unlike [`readiness_for_user`] this makes no provider calls; storage errors
+/// just mean no score.
+pub async fn merged_readiness(
+    database: &Database,
+    user_id: Uuid,
+    date: NaiveDate,
+    activities: &[Activity],
+) -> Option<ReadinessScore> {
+    let window_start = date - Duration::days(WINDOW_DAYS - 1);
+    let days = wellness::merged_wellness(database, user_id, window_start, date)
+        .await
+        .ok()?;
+    ReadinessEngine::new().calculate(date, &days, activities)
+}
+
+/// Result of the `get_readiness` tool
+pub fn readiness_report(date: NaiveDate, score: Option<&ReadinessScore>) -> Value {
+    match score {
+        Some(score) => json!({
+            "date": date,
+            "readiness": score,
+            "insights": score.insights(),
+        }),
+        None => json!({
+            "date": date,
+            "readiness": null,
+            "message": "Not enough wellness or training data to score readiness; connect a wellness provider or use log_wellness",
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SleepSummary;
+    use chrono::{TimeZone, Utc};
+
+    fn day(offset: i64, rhr: u32, hrv: f64, hours: u32) -> DailyWellness {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap() + Duration::days(offset);
+        let mut wellness = DailyWellness::new(date, "fitbit");
+        wellness.resting_heart_rate = Some(rhr);
+        wellness.hrv_rmssd_ms = Some(hrv);
+        wellness.sleep = Some(SleepSummary {
+            start_time: None,
+            end_time: None,
+            minutes_asleep: hours * 60,
+            minutes_in_bed: hours * 60,
+            efficiency: Some(95),
+            stages: None,
+            quality: None,
+        });
+        wellness
+    }
+
+    fn run(offset: i64, minutes: u64) -> Activity {
+        Activity {
+            start_date: Utc.with_ymd_and_hms(2024, 6, 1, 7, 0, 0).unwrap() + Duration::days(offset),
+            duration_seconds: minutes * 60,
+            average_heart_rate: Some(150),
+            ..Activity::default()
+        }
+    }
+
+    #[test]
+    fn test_rested_day_scores_high() {
+        let wellness: Vec<_> = (0..10).map(|i| day(i, 50, 70.0, 8)).collect();
+        let activities: Vec<_> = (0..20).step_by(2).map(|i| run(i - 10, 45)).collect();
+        let date = NaiveDate::from_ymd_opt(2024, 6, 10).unwrap();
+
+        let readiness = ReadinessEngine::new()
+            .calculate(date, &wellness, &activities)
+            .unwrap();
+        assert_eq!(readiness.level, ReadinessLevel::High);
+        assert!(readiness.missing_factors.is_empty());
+        assert!(readiness.insights().is_empty());
+    }
+
+    #[test]
+    fn test_poor_recovery_and_load_spike_score_low() {
+        let mut wellness: Vec<_> = (0..9).map(|i| day(i, 50, 70.0, 8)).collect();
+        wellness.push(day(9, 58, 50.0, 5));
+        // Light month, then a big week
+        let mut activities: Vec<_> = (0..3).map(|i| run(i * 7 - 18, 30)).collect();
+        activities.extend((4..10).map(|i| run(i, 90)));
+        let date = NaiveDate::from_ymd_opt(2024, 6, 10).unwrap();
+
+        let readiness = ReadinessEngine::new()
+            .calculate(date, &wellness, &activities)
+            .unwrap();
+        assert_eq!(readiness.level, ReadinessLevel::Low);
+        assert_eq!(readiness.factors.len(), 4);
+        assert!(!readiness.insights().is_empty());
+    }
+
+    #[test]
+    fn test_missing_factors_are_rescaled() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let mut manual = DailyWellness::new(date, wellness::MANUAL_PROVIDER);
+        manual.sleep = Some(SleepSummary {
+            start_time: None,
+            end_time: None,
+            minutes_asleep: 480,
+            minutes_in_bed: 480,
+            efficiency: None,
+            stages: None,
+            quality: Some(5),
+        });
+
+        let readiness = ReadinessEngine::new()
+            .calculate(date, &[manual], &[])
+            .unwrap();
+        assert_eq!(readiness.score, 100);
+        assert_eq!(readiness.factors[0].weight, 1.0);
+        assert_eq!(readiness.missing_factors.len(), 3);
+
+        assert!(ReadinessEngine::new().calculate(date, &[], &[]).is_none());
+    }
+}
@@ -1,7 +1,7 @@
 //! Training recommendation engine for personalized insights
 
 use super::*;
-use crate::models::Activity;
+use crate::models::{Activity, DailyWellness};
 use anyhow::Result;
 use chrono::Utc;
 use std::collections::HashMap;
@@ -34,6 +34,12 @@ pub trait RecommendationEngineTrait {
         user_profile: &UserFitnessProfile,
         activities: &[Activity],
     ) -> Result<Vec<TrainingRecommendation>>;
+
+    /// Generate recovery recommendations from sleep, resting HR and HRV
+    async fn generate_wellness_recommendations(
+        &self,
+        wellness: &[DailyWellness],
+    ) -> Result<Vec<TrainingRecommendation>>;
 }
 
 /// Advanced recommendation engine implementation
@@ -633,9 +639,219 @@ impl RecommendationEngineTrait for AdvancedRecommendationEngine {
 
         Ok(recommendations)
     }
+
+    async fn generate_wellness_recommendations(
+        &self,
+        wellness: &[DailyWellness],
+    ) -> Result<Vec<TrainingRecommendation>> {
+        let mut recommendations = Vec::new();
+        let signals = super::wellness::recovery_signals(wellness);
+
+        if signals.elevated_resting_hr_bpm.is_some() || signals.hrv_drop_percent.is_some() {
+            let mut evidence = Vec::new();
+            if let Some(bpm) = signals.elevated_resting_hr_bpm {
+                evidence.push(format!(
+                    "resting heart rate is {:.0} bpm above baseline",
+                    bpm
+                ));
+            }
+            if let Some(drop) = signals.hrv_drop_percent {
+                evidence.push(format!("HRV is {:.0}% below baseline", drop));
+            }
+            // Both markers together are a much stronger signal than either alone
+            let both = evidence.len() > 1;
+
+            recommendations.push(TrainingRecommendation {
+                recommendation_type: RecommendationType::Recovery,
+                title: "Recovery Markers Are Down".to_string(),
+                description: format!("Over the last few days your {}.", evidence.join(" and ")),
+                priority: if both {
+                    RecommendationPriority::High
+                } else {
+                    RecommendationPriority::Medium
+                },
+                confidence: if both {
+                    Confidence::High
+                } else {
+                    Confidence::Medium
+                },
+                rationale: "A rising resting heart rate and falling HRV indicate accumulated fatigue, illness or stress.".to_string(),
+                actionable_steps: vec![
+                    "Replace hard sessions with easy aerobic work or rest".to_string(),
+                    "Resume intensity once resting HR returns to baseline".to_string(),
+                    "Watch for signs of illness".to_string(),
+                ],
+            });
+        }
+
+        if let Some(hours) = signals.short_sleep_hours {
+            recommendations.push(TrainingRecommendation {
+                recommendation_type: RecommendationType::Recovery,
+                title: "Prioritize Sleep".to_string(),
+                description: format!(
+                    "You averaged {:.1} hours of sleep over the last few nights.",
+                    hours
+                ),
+                priority: RecommendationPriority::Medium,
+                confidence: Confidence::High,
+                rationale:
+                    "Most training adaptation happens during sleep; athletes need 7-9 hours."
+                        .to_string(),
+                actionable_steps: vec![
+                    "Aim for at least 7.5 hours in bed".to_string(),
+                    "Keep a consistent bedtime".to_string(),
+                    "Avoid hard sessions late in the evening".to_string(),
+                ],
+            });
+        }
+
+        Ok(recommendations)
+    }
 }
 
 impl AdvancedRecommendationEngine {
+    /// Adjust recommendations for the day's readiness
+    ///
+    /// On low readiness hard-session advice is dropped in favour of an easy
+    /// day; on moderate readiness it stays but is capped and deprioritized.
+    pub fn apply_readiness(
+        &self,
+        recommendations: Vec<TrainingRecommendation>,
+        readiness: &super::readiness::ReadinessScore,
+    ) -> Vec<TrainingRecommendation> {
+        use super::readiness::ReadinessLevel;
+
+        match readiness.level {
+            ReadinessLevel::High => recommendations,
+            ReadinessLevel::Moderate => recommendations
+                .into_iter()
+                .map(|mut recommendation| {
+                    if recommendation.recommendation_type == RecommendationType::Intensity {
+                        recommendation.priority = RecommendationPriority::Low;
+                        recommendation.actionable_steps.push(format!(
+                            "Readiness is {}/100 today: cap effort at tempo and shorten the session",
+                            readiness.score
+                        ));
+                    }
+                    recommendation
+                })
+                .collect(),
+            ReadinessLevel::Low => {
+                let reasons: Vec<&str> = readiness
+                    .factors
+                    .iter()
+                    .filter(|factor| factor.score < 50.0)
+                    .map(|factor| factor.description.as_str())
+                    .collect();
+
+                let mut adjusted = vec![TrainingRecommendation {
+                    recommendation_type: RecommendationType::Recovery,
+                    title: "Low Readiness: Keep Today Easy".to_string(),
+                    description: format!(
+                        "Readiness is {}/100. Hard sessions planned for today should wait.",
+                        readiness.score
+                    ),
+                    priority: RecommendationPriority::High,
+                    confidence: Confidence::High,
+                    rationale: if reasons.is_empty() {
+                        "Several recovery markers are below your norm.".to_string()
+                    } else {
+                        format!("{}.", reasons.join("; "))
+                    },
+                    actionable_steps: vec![
+                        "Swap intervals or tempo work for easy aerobic training or rest".to_string(),
+                        "Keep heart rate in zones 1-2".to_string(),
+                        "Check readiness again tomorrow before the next hard session"
+                            .to_string(),
+                    ],
+                }];
+                adjusted.extend(recommendations.into_iter().filter(|recommendation| {
+                    recommendation.recommendation_type != RecommendationType::Intensity
+                }));
+                adjusted
+            }
+        }
+    }
+
+    /// Adjust recommendations for injury risk from recent workload
+    ///
+    /// On high risk advice to add volume is dropped and a deload week leads;
+    /// on moderate risk volume increases are deprioritized behind a
+    /// recommendation to hold the current load.
+    pub fn apply_workload_risk(
+        &self,
+        recommendations: Vec<TrainingRecommendation>,
+        workload: &super::workload::WorkloadReport,
+    ) -> Vec<TrainingRecommendation> {
+        use super::workload::InjuryRisk;
+
+        let reasons: Vec<&str> = workload
+            .alerts
+            .iter()
+            .map(|alert| alert.message.as_str())
+            .collect();
+        let rationale = format!("{}.", reasons.join("; "));
+
+        match workload.overall_risk {
+            InjuryRisk::Low => recommendations,
+            InjuryRisk::Moderate => {
+                let mut adjusted = vec![TrainingRecommendation {
+                    recommendation_type: RecommendationType::Recovery,
+                    title: "Hold Your Training Load".to_string(),
+                    description:
+                        "Training has risen quickly. Keep next week at this week's volume before building further."
+                            .to_string(),
+                    priority: RecommendationPriority::High,
+                    confidence: Confidence::Medium,
+                    rationale,
+                    actionable_steps: vec![
+                        "Repeat this week's volume instead of adding more".to_string(),
+                        "Keep the long session at its current length".to_string(),
+                        "Note any niggles and back off if they persist".to_string(),
+                    ],
+                }];
+                adjusted.extend(recommendations.into_iter().map(|mut recommendation| {
+                    if recommendation.recommendation_type == RecommendationType::Volume {
+                        recommendation.priority = RecommendationPriority::Low;
+                    }
+                    recommendation
+                }));
+                adjusted
+            }
+            InjuryRisk::High => {
+                let mut adjusted = vec![TrainingRecommendation {
+                    recommendation_type: RecommendationType::Recovery,
+                    title: "High Injury Risk: Take a Deload Week".to_string(),
+                    description:
+                        "Training load has jumped well beyond what the last month prepared you for."
+                            .to_string(),
+                    priority: RecommendationPriority::Critical,
+                    confidence: Confidence::High,
+                    rationale,
+                    actionable_steps: vec![
+                        "Cut next week's volume by 30-40%".to_string(),
+                        "Split long sessions into shorter ones".to_string(),
+                        "Replace hard sessions with easy aerobic training".to_string(),
+                    ],
+                }];
+                adjusted.extend(
+                    recommendations
+                        .into_iter()
+                        .filter(|recommendation| {
+                            recommendation.recommendation_type != RecommendationType::Volume
+                        })
+                        .map(|mut recommendation| {
+                            if recommendation.recommendation_type == RecommendationType::Intensity {
+                                recommendation.priority = RecommendationPriority::Low;
+                            }
+                            recommendation
+                        }),
+                );
+                adjusted
+            }
+        }
+    }
+
     /// Count consecutive training days
     fn count_consecutive_training_days(&self, activities: &[Activity]) -> usize {
         let mut consecutive = 0;
@@ -0,0 +1,730 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Repeated route detection
+//!
+//! Groups activities that follow the same path, using the simplified GPS
+//! track providers attach to each activity (Strava's `map.summary_polyline`).
+//! Tracks are resampled to evenly spaced points and compared with the
+//! discrete Fréchet distance, which, unlike a start-point match, tells a
+//! loop from an out-and-back along the same street. Direction matters: a
+//! loop run the other way round is a separate route.
+//!
+//! Routes are named from a reverse geocode of their midpoint, with the
+//! name kept in the user profile so each route is looked up only once.
+
+use super::location::LocationService;
+use super::patterns::sport_family;
+use super::TrendDirection;
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::models::Activity;
+use crate::providers::connections::ProviderConnections;
+use crate::providers::merge::all_provider_activities;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use tracing::debug;
+use uuid::Uuid;
+
+/// Points each track is resampled to before comparison
+const RESAMPLE_POINTS: usize = 40;
+/// Largest Fréchet distance (meters) between two runs of the same route
+const MAX_ROUTE_DEVIATION_METERS: f64 = 150.0;
+/// Largest relative difference in track length within a route
+const MAX_LENGTH_DIFFERENCE: f64 = 0.15;
+/// Activities on a path before it counts as a route
+const MIN_ROUTE_ACTIVITIES: usize = 2;
+/// A track ending this close to its start is a loop
+const LOOP_CLOSURE_METERS: f64 = 250.0;
+/// Activities searched for routes
+const ACTIVITY_LIMIT: usize = 1000;
+/// Efforts needed to call a trend
+const MIN_TREND_EFFORTS: usize = 3;
+/// Pace change over the history, relative to the mean, that counts as a trend
+const MIN_TREND_CHANGE: f64 = 0.01;
+/// User profile key holding route names
+const PROFILE_KEY: &str = "route_names";
+
+const EARTH_RADIUS_METERS: f64 = 6_371_000.0;
+
+/// Decode a Google encoded polyline into (latitude, longitude) pairs
+pub fn decode_polyline(encoded: &str) -> Result<Vec<(f64, f64)>> {
+    let bytes = encoded.as_bytes();
+    let mut index = 0;
+    let mut next_value = || -> Result<Option<i64>> {
+        if index >= bytes.len() {
+            return Ok(None);
+        }
+        let mut result = 0i64;
+        let mut shift = 0;
+        loop {
+            let byte = *bytes
+                .get(index)
+                .ok_or_else(|| anyhow!("Truncated polyline"))? as i64
+                - 63;
+            index += 1;
+            if !(0..64).contains(&byte) || shift > 60 {
+                return Err(anyhow!("Invalid polyline character"));
+            }
+            result |= (byte & 0x1f) << shift;
+            shift += 5;
+            if byte < 0x20 {
+                break;
+            }
+        }
+        Ok(Some(if result & 1 == 1 {
+            !(result >> 1)
+        } else {
+            result >> 1
+        }))
+    };
+
+    let (mut latitude, mut longitude) = (0i64, 0i64);
+    let mut points = vec![];
+    while let Some(lat_delta) = next_value()? {
+        let lng_delta = next_value()?.ok_or_else(|| anyhow!("Truncated polyline"))?;
+        latitude += lat_delta;
+        longitude += lng_delta;
+        points.push((latitude as f64 / 1e5, longitude as f64 / 1e5));
+    }
+    Ok(points)
+}
+
+/// Encode (latitude, longitude) pairs as a Google encoded polyline
+pub fn encode_polyline(points: &[(f64, f64)]) -> String {
+    fn encode_value(value: i64, out: &mut String) {
+        let mut value = if value < 0 { !(value << 1) } else { value << 1 };
+        while value >= 0x20 {
+            out.push((((value & 0x1f) | 0x20) as u8 + 63) as char);
+            value >>= 5;
+        }
+        out.push((value as u8 + 63) as char);
+    }
+
+    let mut encoded = String::new();
+    let (mut latitude, mut longitude) = (0i64, 0i64);
+    for (lat, lng) in points {
+        let (lat, lng) = ((lat * 1e5).round() as i64, (lng * 1e5).round() as i64);
+        encode_value(lat - latitude, &mut encoded);
+        encode_value(lng - longitude, &mut encoded);
+        (latitude, longitude) = (lat, lng);
+    }
+    encoded
+}
+
+/// Great-circle distance in meters
+pub fn haversine_meters(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (a.0.to_radians(), b.0.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (b.1 - a.1).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_METERS * h.sqrt().asin()
+}
+
+fn track_length(points: &[(f64, f64)]) -> f64 {
+    points
+        .windows(2)
+        .map(|w| haversine_meters(w[0], w[1]))
+        .sum()
+}
+
+/// `count` points spaced evenly along the track
+pub fn resample(points: &[(f64, f64)], count: usize) -> Vec<(f64, f64)> {
+    let (Some(&first), Some(&last)) = (points.first(), points.last()) else {
+        return vec![];
+    };
+    let total = track_length(points);
+    if total == 0.0 || count < 2 {
+        return vec![first; count.max(1)];
+    }
+
+    let mut resampled = Vec::with_capacity(count);
+    let mut segment = 0;
+    let mut walked = 0.0;
+    for i in 0..count {
+        let target = total * i as f64 / (count - 1) as f64;
+        loop {
+            if segment + 1 >= points.len() {
+                resampled.push(last);
+                break;
+            }
+            let length = haversine_meters(points[segment], points[segment + 1]);
+            if walked + length >= target {
+                let t = if length > 0.0 {
+                    (target - walked) / length
+                } else {
+                    0.0
+                };
+                let (a, b) = (points[segment], points[segment + 1]);
+                resampled.push((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t));
+                break;
+            }
+            walked += length;
+            segment += 1;
+        }
+    }
+    resampled
+}
+
+/// Discrete Fréchet distance in meters
+///
+/// The shortest leash that lets two walkers cover both tracks front to back
+/// without either stepping backwards.
+pub fn frechet_distance(a: &[(f64, f64)], b: &[(f64, f64)]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return f64::INFINITY;
+    }
+    let mut previous = vec![0.0f64; b.len()];
+    let mut current = vec![0.0f64; b.len()];
+    for (i, &point_a) in a.iter().enumerate() {
+        for (j, &point_b) in b.iter().enumerate() {
+            let d = haversine_meters(point_a, point_b);
+            current[j] = match (i, j) {
+                (0, 0) => d,
+                (0, _) => current[j - 1].max(d),
+                (_, 0) => previous[0].max(d),
+                _ => previous[j].min(previous[j - 1]).min(current[j - 1]).max(d),
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len() - 1]
+}
+
+struct Track<'a> {
+    activity: &'a Activity,
+    points: Vec<(f64, f64)>,
+    length: f64,
+}
+
+impl<'a> Track<'a> {
+    fn new(activity: &'a Activity) -> Option<Self> {
+        let decoded = decode_polyline(activity.summary_polyline.as_deref()?)
+            .map_err(|e| debug!("Skipping track of activity {}: {}", activity.id, e))
+            .ok()?;
+        let length = track_length(&decoded);
+        (length > 0.0).then(|| Self {
+            activity,
+            points: resample(&decoded, RESAMPLE_POINTS),
+            length,
+        })
+    }
+
+    fn matches(&self, other: &Track) -> bool {
+        if sport_family(&self.activity.sport_type) != sport_family(&other.activity.sport_type) {
+            return false;
+        }
+        if (self.length - other.length).abs() / self.length.max(other.length)
+            > MAX_LENGTH_DIFFERENCE
+        {
+            return false;
+        }
+        // The Fréchet distance is at least the gap between starts and between ends
+        let ends_close = |i: usize| {
+            haversine_meters(self.points[i], other.points[i]) <= MAX_ROUTE_DEVIATION_METERS
+        };
+        ends_close(0)
+            && ends_close(RESAMPLE_POINTS - 1)
+            && frechet_distance(&self.points, &other.points) <= MAX_ROUTE_DEVIATION_METERS
+    }
+}
+
+/// Activities that follow the same path
+pub struct Route<'a> {
+    /// `route-` followed by the ID of the route's first activity
+    pub id: String,
+    pub sport: &'static str,
+    /// Length of the first activity's track
+    pub length_meters: f64,
+    pub is_loop: bool,
+    /// Resampled track of the first activity
+    pub points: Vec<(f64, f64)>,
+    /// Oldest first
+    pub activities: Vec<&'a Activity>,
+}
+
+/// Group activities with GPS tracks into routes run at least twice
+///
+/// Each track joins the first route whose first activity it matches, so a
+/// route keeps its ID as long as its first activity is in the history.
+/// Routes with the most activities come first.
+pub fn cluster_routes(activities: &[Activity]) -> Vec<Route<'_>> {
+    let mut tracks: Vec<Track> = activities.iter().filter_map(Track::new).collect();
+    tracks.sort_by_key(|track| track.activity.start_date);
+
+    let mut clusters: Vec<(Track, Vec<&Activity>)> = vec![];
+    for track in tracks {
+        match clusters
+            .iter_mut()
+            .find(|(representative, _)| representative.matches(&track))
+        {
+            Some((_, members)) => members.push(track.activity),
+            None => clusters.push((track, vec![])),
+        }
+    }
+
+    let mut routes: Vec<Route> = clusters
+        .into_iter()
+        .filter(|(_, members)| members.len() + 1 >= MIN_ROUTE_ACTIVITIES)
+        .map(|(representative, members)| {
+            let first = representative.points[0];
+            let last = representative.points[RESAMPLE_POINTS - 1];
+            let mut activities = vec![representative.activity];
+            activities.extend(members);
+            Route {
+                id: format!("route-{}", representative.activity.id),
+                sport: sport_family(&representative.activity.sport_type),
+                length_meters: representative.length,
+                is_loop: haversine_meters(first, last) <= LOOP_CLOSURE_METERS,
+                points: representative.points,
+                activities,
+            }
+        })
+        .collect();
+    routes.sort_by(|a, b| {
+        b.activities
+            .len()
+            .cmp(&a.activities.len())
+            .then_with(|| last_date(b).cmp(&last_date(a)))
+    });
+    routes
+}
+
+fn last_date(route: &Route) -> DateTime<Utc> {
+    route
+        .activities
+        .last()
+        .map(|a| a.start_date)
+        .unwrap_or_default()
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+/// One activity on a route
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteEffort {
+    pub activity_id: String,
+    pub name: String,
+    pub start_date: DateTime<Utc>,
+    pub duration_seconds: u64,
+    pub pace_seconds_per_km: Option<f64>,
+    pub average_heart_rate: Option<u32>,
+    /// 1 for the fastest effort on the route
+    pub rank: usize,
+}
+
+/// Pace trend over a route's efforts
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteTrend {
+    pub direction: TrendDirection,
+    /// Fitted pace change; negative is faster
+    pub seconds_per_km_per_month: f64,
+    /// Fitted pace change from the first effort to the last
+    pub total_change_seconds_per_km: f64,
+    pub efforts: usize,
+}
+
+/// A route and how the user has done on it
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteSummary {
+    pub route_id: String,
+    pub name: String,
+    pub sport: &'static str,
+    pub distance_meters: f64,
+    pub is_loop: bool,
+    pub start_latitude: f64,
+    pub start_longitude: f64,
+    pub activity_count: usize,
+    pub first_date: DateTime<Utc>,
+    pub last_date: DateTime<Utc>,
+    pub best_time_seconds: u64,
+    pub best_activity_id: String,
+    pub trend: Option<RouteTrend>,
+}
+
+/// Pace of an effort, from its own distance or else the route's length
+fn effort_pace(activity: &Activity, route_length: f64) -> Option<f64> {
+    let meters = activity
+        .distance_meters
+        .filter(|d| *d > 0.0)
+        .unwrap_or(route_length);
+    (activity.duration_seconds > 0 && meters > 0.0)
+        .then(|| activity.duration_seconds as f64 / (meters / 1000.0))
+}
+
+/// Efforts on a route, oldest first, ranked by pace
+pub fn route_efforts(route: &Route) -> Vec<RouteEffort> {
+    let mut efforts: Vec<RouteEffort> = route
+        .activities
+        .iter()
+        .map(|activity| RouteEffort {
+            activity_id: activity.id.clone(),
+            name: activity.name.clone(),
+            start_date: activity.start_date,
+            duration_seconds: activity.duration_seconds,
+            pace_seconds_per_km: effort_pace(activity, route.length_meters).map(|p| round(p, 1)),
+            average_heart_rate: activity.average_heart_rate,
+            rank: 0,
+        })
+        .collect();
+
+    let mut by_pace: Vec<usize> = (0..efforts.len()).collect();
+    by_pace.sort_by(|&a, &b| {
+        let pace = |i: usize| efforts[i].pace_seconds_per_km.unwrap_or(f64::INFINITY);
+        pace(a).total_cmp(&pace(b))
+    });
+    for (rank, index) in by_pace.into_iter().enumerate() {
+        efforts[index].rank = rank + 1;
+    }
+    efforts
+}
+
+/// Least-squares pace trend, once there are enough efforts
+pub fn pace_trend(efforts: &[RouteEffort]) -> Option<RouteTrend> {
+    let points: Vec<(f64, f64)> = efforts
+        .iter()
+        .filter_map(|effort| {
+            let pace = effort.pace_seconds_per_km?;
+            Some((effort.start_date.timestamp() as f64 / 86_400.0, pace))
+        })
+        .collect();
+    if points.len() < MIN_TREND_EFFORTS {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let spread: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if spread == 0.0 {
+        return None;
+    }
+    let slope_per_day = points
+        .iter()
+        .map(|p| (p.0 - mean_x) * (p.1 - mean_y))
+        .sum::<f64>()
+        / spread;
+    let span_days = points.iter().map(|p| p.0).fold(f64::MIN, f64::max)
+        - points.iter().map(|p| p.0).fold(f64::MAX, f64::min);
+    let total_change = slope_per_day * span_days;
+
+    let direction = if total_change.abs() < mean_y * MIN_TREND_CHANGE {
+        TrendDirection::Stable
+    } else if total_change < 0.0 {
+        TrendDirection::Improving
+    } else {
+        TrendDirection::Declining
+    };
+    Some(RouteTrend {
+        direction,
+        seconds_per_km_per_month: round(slope_per_day * 30.0, 1),
+        total_change_seconds_per_km: round(total_change, 1),
+        efforts: points.len(),
+    })
+}
+
+/// Summary of a route under the given name
+pub fn summarize(route: &Route, name: String) -> RouteSummary {
+    let efforts = route_efforts(route);
+    let best = efforts
+        .iter()
+        .find(|effort| effort.rank == 1)
+        .or(efforts.first());
+    RouteSummary {
+        route_id: route.id.clone(),
+        name,
+        sport: route.sport,
+        distance_meters: round(route.length_meters, 0),
+        is_loop: route.is_loop,
+        start_latitude: round(route.points[0].0, 5),
+        start_longitude: round(route.points[0].1, 5),
+        activity_count: route.activities.len(),
+        first_date: route.activities[0].start_date,
+        last_date: last_date(route),
+        best_time_seconds: best.map_or(0, |effort| effort.duration_seconds),
+        best_activity_id: best
+            .map(|effort| effort.activity_id.clone())
+            .unwrap_or_default(),
+        trend: pace_trend(&efforts),
+    }
+}
+
+/// A name for the route from what is at its midpoint, or from the places
+/// its activities were tagged with
+async fn lookup_name(location: &mut LocationService, route: &Route<'_>) -> Option<String> {
+    let kind = if route.is_loop { "loop" } else { "route" };
+    let (latitude, longitude) = route.points[RESAMPLE_POINTS / 2];
+    let place = match location
+        .get_location_from_coordinates(latitude, longitude)
+        .await
+    {
+        Ok(found) => found
+            .trail_name
+            .or(found.leisure)
+            .or(found.natural)
+            .or(found.tourism)
+            .or(found.city),
+        Err(e) => {
+            debug!("Could not geocode {}: {}", route.id, e);
+            route
+                .activities
+                .iter()
+                .find_map(|a| a.trail_name.clone().or_else(|| a.city.clone()))
+        }
+    }?;
+    Some(format!("{} {}", place, kind))
+}
+
+fn fallback_name(route: &Route) -> String {
+    format!(
+        "{:.1} km {} {}",
+        route.length_meters / 1000.0,
+        route.sport,
+        if route.is_loop { "loop" } else { "route" }
+    )
+}
+
+/// Names for the given routes, looking up and storing those not named yet
+async fn route_names(database: &Database, user_id: Uuid, routes: &[&Route<'_>]) -> Vec<String> {
+    let mut profile = database
+        .get_user_profile(user_id)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| json!({}));
+    let mut stored: Map<String, Value> = profile
+        .get(PROFILE_KEY)
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut location = LocationService::new();
+    let mut added = false;
+    let mut names = Vec::with_capacity(routes.len());
+    for route in routes {
+        if let Some(name) = stored.get(&route.id).and_then(Value::as_str) {
+            names.push(name.to_string());
+            continue;
+        }
+        match lookup_name(&mut location, route).await {
+            Some(name) => {
+                stored.insert(route.id.clone(), Value::String(name.clone()));
+                added = true;
+                names.push(name);
+            }
+            None => names.push(fallback_name(route)),
+        }
+    }
+
+    if added {
+        profile[PROFILE_KEY] = Value::Object(stored);
+        if let Err(e) = database.upsert_user_profile(user_id, profile).await {
+            debug!("Could not store route names: {}", e);
+        }
+    }
+    names
+}
+
+/// Result of the `list_my_routes` tool for a user
+pub async fn routes_for_user(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    sport: Option<&str>,
+    limit: usize,
+) -> Result<Value> {
+    let database = connections.database();
+    let activities = all_provider_activities(connections, user_id, ACTIVITY_LIMIT).await?;
+    let with_gps = activities
+        .iter()
+        .filter(|a| a.summary_polyline.is_some())
+        .count();
+
+    let routes = cluster_routes(&activities);
+    let matching: Vec<&Route> = routes
+        .iter()
+        .filter(|route| sport.is_none_or(|sport| route.sport == sport))
+        .collect();
+    let selected: Vec<&Route> = matching.iter().take(limit).copied().collect();
+    let names = route_names(database, user_id, &selected).await;
+    let summaries: Vec<RouteSummary> = selected
+        .iter()
+        .zip(names)
+        .map(|(route, name)| summarize(route, name))
+        .collect();
+
+    Ok(json!({
+        "activities_analyzed": activities.len(),
+        "activities_with_gps": with_gps,
+        "routes_found": matching.len(),
+        "routes": summaries,
+    }))
+}
+
+/// Result of the `route_history` tool: every effort on the route of
+/// `route_id`, or on the route `activity_id` belongs to
+pub async fn route_history_for_user(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    route_id: Option<&str>,
+    activity_id: Option<&str>,
+) -> Result<Value> {
+    let database = connections.database();
+    let activities = all_provider_activities(connections, user_id, ACTIVITY_LIMIT).await?;
+    let routes = cluster_routes(&activities);
+    let route = match (route_id, activity_id) {
+        (Some(route_id), _) => routes
+            .iter()
+            .find(|route| route.id == route_id)
+            .ok_or_else(|| anyhow!("Route '{}' not found", route_id))?,
+        (None, Some(activity_id)) => routes
+            .iter()
+            .find(|route| route.activities.iter().any(|a| a.id == activity_id))
+            .ok_or_else(|| anyhow!("Activity '{}' is not on a repeated route", activity_id))?,
+        (None, None) => return Err(anyhow!("Provide route_id or activity_id")),
+    };
+
+    let name = route_names(database, user_id, &[route])
+        .await
+        .pop()
+        .unwrap_or_else(|| fallback_name(route));
+    let summary = summarize(route, name);
+    let efforts = route_efforts(route);
+
+    let mut insights = vec![];
+    if let Some(best) = efforts.iter().find(|effort| effort.rank == 1) {
+        insights.push(format!(
+            "Best time {}:{:02} on {}",
+            best.duration_seconds / 60,
+            best.duration_seconds % 60,
+            best.start_date.format("%Y-%m-%d")
+        ));
+    }
+    match &summary.trend {
+        Some(trend) => insights.push(match trend.direction {
+            TrendDirection::Improving => format!(
+                "Getting faster: {:.0} s/km quicker over {} efforts",
+                trend.total_change_seconds_per_km.abs(),
+                trend.efforts
+            ),
+            TrendDirection::Declining => format!(
+                "Slowing down: {:.0} s/km slower over {} efforts",
+                trend.total_change_seconds_per_km, trend.efforts
+            ),
+            TrendDirection::Stable => {
+                format!("Pace steady over {} efforts", trend.efforts)
+            }
+        }),
+        None => insights.push(format!(
+            "Run this route {} or more times to see a trend",
+            MIN_TREND_EFFORTS
+        )),
+    }
+
+    Ok(json!({
+        "route": summary,
+        "efforts": efforts,
+        "insights": insights,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SportType;
+
+    /// A square loop of about 4 km, shifted north by `offset` degrees
+    fn square(offset: f64) -> Vec<(f64, f64)> {
+        let (lat, lng) = (45.50 + offset, -73.59);
+        vec![
+            (lat, lng),
+            (lat + 0.009, lng),
+            (lat + 0.009, lng + 0.0128),
+            (lat, lng + 0.0128),
+            (lat, lng),
+        ]
+    }
+
+    fn activity(id: &str, days: i64, points: &[(f64, f64)], minutes: u64) -> Activity {
+        Activity {
+            id: id.to_string(),
+            sport_type: SportType::Run,
+            start_date: "2024-01-01T07:00:00Z".parse::<DateTime<Utc>>().unwrap()
+                + chrono::Duration::days(days),
+            duration_seconds: minutes * 60,
+            distance_meters: None,
+            summary_polyline: Some(encode_polyline(points)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decode_polyline() {
+        // Example from the encoded polyline algorithm documentation
+        let points = decode_polyline("_p~iF~ps|U_ulLnnqC_mqNvxq`@").unwrap();
+        assert_eq!(
+            points,
+            vec![(38.5, -120.2), (40.7, -120.95), (43.252, -126.453)]
+        );
+        let encoded = encode_polyline(&square(0.0));
+        assert_eq!(
+            encode_polyline(&decode_polyline(&encoded).unwrap()),
+            encoded
+        );
+        assert!(decode_polyline("_p~iF~ps|U_").is_err());
+    }
+
+    #[test]
+    fn test_frechet_tells_loops_apart() {
+        let loop_a = resample(&square(0.0), RESAMPLE_POINTS);
+        let loop_b = resample(&square(0.0005), RESAMPLE_POINTS);
+        let reversed: Vec<_> = square(0.0).into_iter().rev().collect();
+        let reversed = resample(&reversed, RESAMPLE_POINTS);
+
+        assert!(frechet_distance(&loop_a, &loop_b) < 60.0);
+        // Same streets the other way round
+        assert!(frechet_distance(&loop_a, &reversed) > 500.0);
+    }
+
+    #[test]
+    fn test_cluster_routes_and_trend() {
+        let far: Vec<(f64, f64)> = square(0.2);
+        let activities = vec![
+            activity("a", 0, &square(0.0), 24),
+            activity("b", 10, &square(0.0003), 23),
+            activity("c", 20, &square(-0.0002), 22),
+            activity("far", 5, &far, 30),
+            activity("far2", 15, &far, 29),
+            activity("once", 7, &square(0.05)[..3], 15),
+            Activity {
+                summary_polyline: None,
+                ..activity("indoor", 8, &square(0.0), 30)
+            },
+        ];
+
+        let routes = cluster_routes(&activities);
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].id, "route-a");
+        assert!(routes[0].is_loop);
+        let ids: Vec<&str> = routes[0].activities.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(routes[1].id, "route-far");
+
+        let efforts = route_efforts(&routes[0]);
+        assert_eq!(efforts[2].rank, 1);
+        let trend = pace_trend(&efforts).unwrap();
+        assert_eq!(trend.direction, TrendDirection::Improving);
+        assert!(trend.total_change_seconds_per_km < 0.0);
+
+        let summary = summarize(&routes[0], "Test loop".to_string());
+        assert_eq!(summary.best_activity_id, "c");
+        assert_eq!(summary.activity_count, 3);
+        assert!((3900.0..4300.0).contains(&summary.distance_meters));
+    }
+}
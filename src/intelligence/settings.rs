@@ -0,0 +1,325 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Layered analysis settings
+//!
+//! The `[intelligence]` section of `fitness_config.toml` holds the server
+//! defaults for effort thresholds, heart rate zones, weather mapping and
+//! personal record detection. A tenant - a club or team the user belongs
+//! to - can override any of it, and each user can override the result again:
+//!
+//! ```text
+//! server defaults -> tenant overrides -> user overrides
+//! ```
+//!
+//! Overrides are partial JSON documents shaped like the config section, for
+//! example `{"zone_thresholds": {"tempo_max": 85}}`. Updates are applied as
+//! JSON merge patches (RFC 7386), so a `null` value drops an override and
+//! restores the inherited value. Every update is checked against the config
+//! schema before it is stored: unknown fields, wrong types and thresholds out
+//! of range or out of order are rejected.
+
+use crate::config::fitness_config::IntelligenceConfig;
+use crate::config::FitnessConfig;
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Scope of overrides shared by every member of a tenant
+pub const TENANT_SCOPE: &str = "tenant";
+/// Scope of overrides for a single user
+pub const USER_SCOPE: &str = "user";
+
+/// Every layer of a user's analysis settings and the merged result
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisSettings {
+    /// Tenant the user belongs to, if any
+    pub tenant_id: Option<String>,
+    /// Overrides set for the tenant
+    pub tenant_overrides: Value,
+    /// Overrides set by the user
+    pub user_overrides: Value,
+    /// Server defaults with both layers applied
+    pub effective: IntelligenceConfig,
+}
+
+/// Body of the REST endpoints that update analysis settings
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AnalysisSettingsUpdate {
+    /// Merge patch of overrides
+    #[serde(default)]
+    pub settings: Value,
+    /// Clear the stored overrides before applying `settings`
+    #[serde(default)]
+    pub reset: bool,
+}
+
+/// The intelligence section of the server configuration
+pub fn server_defaults() -> IntelligenceConfig {
+    FitnessConfig::load(None).unwrap_or_default().intelligence
+}
+
+/// Apply `patch` to `target` as a JSON merge patch
+///
+/// Objects merge key by key, `null` removes a key and anything else
+/// replaces the target value. Objects left empty are removed too.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+                continue;
+            }
+            let entry = target.entry(key.clone()).or_insert(Value::Null);
+            merge_patch(entry, value);
+            if entry.as_object().is_some_and(Map::is_empty) {
+                target.remove(key);
+            }
+        }
+    }
+}
+
+/// Check every field named in `overrides` exists in `schema`
+fn check_fields(schema: &Value, overrides: &Value, path: &str) -> Result<()> {
+    let (Value::Object(schema), Value::Object(overrides)) = (schema, overrides) else {
+        if schema.is_object() {
+            return Err(anyhow!("Setting '{}' must be an object", path));
+        }
+        return Ok(());
+    };
+    for (key, value) in overrides {
+        let name = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+        let field = schema
+            .get(key)
+            .ok_or_else(|| anyhow!("Unknown setting '{}'", name))?;
+        if !value.is_null() {
+            check_fields(field, value, &name)?;
+        }
+    }
+    Ok(())
+}
+
+/// Apply overrides to a config, checking them against its schema
+pub fn apply_overrides(base: &IntelligenceConfig, overrides: &Value) -> Result<IntelligenceConfig> {
+    if overrides.is_null() {
+        return Ok(base.clone());
+    }
+    let mut merged = serde_json::to_value(base)?;
+    check_fields(&merged, overrides, "")?;
+    merge_patch(&mut merged, overrides);
+
+    let config: IntelligenceConfig =
+        serde_json::from_value(merged).context("Invalid analysis settings")?;
+    config.validate()?;
+    Ok(config)
+}
+
+/// Check `patch` against the schema of `base` and merge it into `overrides`
+fn merge_checked(base: &IntelligenceConfig, overrides: &mut Value, patch: &Value) -> Result<()> {
+    if patch.is_null() {
+        return Ok(());
+    }
+    anyhow::ensure!(patch.is_object(), "Analysis settings must be an object");
+    check_fields(&serde_json::to_value(base)?, patch, "")?;
+    merge_patch(overrides, patch);
+    Ok(())
+}
+
+async fn stored_overrides(database: &Database, scope: &str, scope_id: &str) -> Result<Value> {
+    Ok(database
+        .get_analysis_settings(scope, scope_id)
+        .await?
+        .unwrap_or_else(|| json!({})))
+}
+
+/// The user's tenant, its overrides and the config they produce
+async fn tenant_layer(
+    database: &Database,
+    user_id: Uuid,
+) -> Result<(Option<String>, Value, IntelligenceConfig)> {
+    let tenant_id = database.get_user_tenant(user_id).await?;
+    let overrides = match &tenant_id {
+        Some(tenant_id) => stored_overrides(database, TENANT_SCOPE, tenant_id).await?,
+        None => json!({}),
+    };
+    let config = apply_overrides(&server_defaults(), &overrides)
+        .context("Tenant analysis settings are invalid")?;
+    Ok((tenant_id, overrides, config))
+}
+
+/// Load every layer of a user's settings, failing if a stored layer no
+/// longer applies cleanly
+pub async fn settings_for_user(database: &Database, user_id: Uuid) -> Result<AnalysisSettings> {
+    let (tenant_id, tenant_overrides, tenant_config) = tenant_layer(database, user_id).await?;
+    let user_overrides = stored_overrides(database, USER_SCOPE, &user_id.to_string()).await?;
+    let effective = apply_overrides(&tenant_config, &user_overrides)?;
+
+    Ok(AnalysisSettings {
+        tenant_id,
+        tenant_overrides,
+        user_overrides,
+        effective,
+    })
+}
+
+/// The intelligence config that applies to a user
+///
+/// A layer that no longer applies, say after a tenant change made a user's
+/// zones overlap, is skipped with a warning rather than failing the analysis.
+pub async fn effective_config(database: &Database, user_id: Uuid) -> IntelligenceConfig {
+    let tenant_id = database.get_user_tenant(user_id).await.ok().flatten();
+
+    let mut config = server_defaults();
+    let mut layers = vec![];
+    if let Some(tenant_id) = &tenant_id {
+        layers.push((TENANT_SCOPE, tenant_id.clone()));
+    }
+    layers.push((USER_SCOPE, user_id.to_string()));
+
+    for (scope, scope_id) in layers {
+        let overrides = match stored_overrides(database, scope, &scope_id).await {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                warn!("Failed to load {} analysis settings: {}", scope, e);
+                continue;
+            }
+        };
+        match apply_overrides(&config, &overrides) {
+            Ok(layered) => config = layered,
+            Err(e) => warn!(
+                "Ignoring {} analysis settings for {}: {}",
+                scope, scope_id, e
+            ),
+        }
+    }
+    config
+}
+
+/// Merge `patch` into a user's overrides, or replace them when `reset`
+pub async fn update_user_settings(
+    database: &Database,
+    user_id: Uuid,
+    patch: &Value,
+    reset: bool,
+) -> Result<AnalysisSettings> {
+    let (tenant_id, tenant_overrides, tenant_config) = tenant_layer(database, user_id).await?;
+    let mut user_overrides = if reset {
+        json!({})
+    } else {
+        stored_overrides(database, USER_SCOPE, &user_id.to_string()).await?
+    };
+    merge_checked(&tenant_config, &mut user_overrides, patch)?;
+    let effective = apply_overrides(&tenant_config, &user_overrides)?;
+
+    database
+        .upsert_analysis_settings(USER_SCOPE, &user_id.to_string(), &user_overrides)
+        .await?;
+    Ok(AnalysisSettings {
+        tenant_id,
+        tenant_overrides,
+        user_overrides,
+        effective,
+    })
+}
+
+/// Merge `patch` into a tenant's overrides, returning the stored overrides
+/// and the tenant's config
+pub async fn update_tenant_settings(
+    database: &Database,
+    tenant_id: &str,
+    patch: &Value,
+    reset: bool,
+) -> Result<Value> {
+    let defaults = server_defaults();
+    let mut overrides = if reset {
+        json!({})
+    } else {
+        stored_overrides(database, TENANT_SCOPE, tenant_id).await?
+    };
+    merge_checked(&defaults, &mut overrides, patch)?;
+    let effective = apply_overrides(&defaults, &overrides)?;
+
+    database
+        .upsert_analysis_settings(TENANT_SCOPE, tenant_id, &overrides)
+        .await?;
+    Ok(json!({
+        "tenant_id": tenant_id,
+        "overrides": overrides,
+        "effective": effective,
+    }))
+}
+
+/// A tenant's stored overrides and its config
+pub async fn tenant_settings(database: &Database, tenant_id: &str) -> Result<Value> {
+    let overrides = stored_overrides(database, TENANT_SCOPE, tenant_id).await?;
+    let effective = apply_overrides(&server_defaults(), &overrides)?;
+    Ok(json!({
+        "tenant_id": tenant_id,
+        "overrides": overrides,
+        "effective": effective,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_patch_removes_nulls_and_empty_objects() {
+        let mut overrides = json!({"zone_thresholds": {"tempo_max": 85.0}});
+        merge_patch(
+            &mut overrides,
+            &json!({"zone_thresholds": {"threshold_max": 95.0}}),
+        );
+        assert_eq!(
+            overrides,
+            json!({"zone_thresholds": {"tempo_max": 85.0, "threshold_max": 95.0}})
+        );
+
+        merge_patch(
+            &mut overrides,
+            &json!({"zone_thresholds": {"tempo_max": null, "threshold_max": null}}),
+        );
+        assert_eq!(overrides, json!({}));
+    }
+
+    #[test]
+    fn test_apply_overrides_checks_schema() {
+        let base = IntelligenceConfig::default();
+
+        let config = apply_overrides(
+            &base,
+            &json!({"zone_thresholds": {"tempo_max": 85, "threshold_max": 93}}),
+        )
+        .unwrap();
+        assert_eq!(config.zone_thresholds.tempo_max, 85.0);
+        assert_eq!(config.zone_thresholds.recovery_max, 60.0);
+
+        let unknown = apply_overrides(&base, &json!({"zone_thresholds": {"z6_max": 99}}));
+        assert_eq!(
+            unknown.unwrap_err().to_string(),
+            "Unknown setting 'zone_thresholds.z6_max'"
+        );
+        assert!(apply_overrides(&base, &json!({"zone_thresholds": 80})).is_err());
+        assert!(apply_overrides(&base, &json!({"effort_thresholds": {"hard_max": "7"}})).is_err());
+        // Tempo above threshold
+        assert!(apply_overrides(&base, &json!({"zone_thresholds": {"tempo_max": 92}})).is_err());
+    }
+}
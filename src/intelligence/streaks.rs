@@ -0,0 +1,528 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Streaks and consistency
+//!
+//! A day counts toward a streak when it has at least one activity passing
+//! the filter, so "consecutive days with a 10 km run" is a day streak over
+//! runs of 10 km or more. A week (ISO, Monday to Sunday) counts when it has
+//! at least `week_target` such activities.
+//!
+//! A current streak is still alive if it reaches today or yesterday (this
+//! week or last week for week streaks), so a streak isn't reported broken
+//! before the day is over.
+//!
+//! The consistency score rates the last [`CONSISTENCY_WEEKS`] weeks out of
+//! 100: 70 points for the share of weeks meeting the target and 30 for how
+//! evenly the activities are spread over them.
+
+use super::activity_query::{history_for_user, ActivityFilter};
+use super::insights::{Insight, InsightType};
+use super::patterns::sport_family;
+use crate::models::Activity;
+use crate::providers::connections::ProviderConnections;
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Duration, NaiveDate, Utc, Weekday};
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+/// Weeks the consistency score looks back over
+pub const CONSISTENCY_WEEKS: i64 = 12;
+/// Day streak lengths worth celebrating
+const DAY_MILESTONES: [u32; 7] = [7, 14, 30, 50, 100, 200, 365];
+/// Week streak lengths worth celebrating
+const WEEK_MILESTONES: [u32; 5] = [4, 8, 12, 26, 52];
+/// Share of the consistency score from weeks meeting the target
+const TARGET_WEIGHT: f64 = 70.0;
+/// Share of the consistency score from an even spread of activities
+const EVENNESS_WEIGHT: f64 = 30.0;
+
+/// Unit a streak is counted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreakUnit {
+    Day,
+    Week,
+}
+
+impl StreakUnit {
+    fn label(self, length: u32) -> String {
+        match self {
+            Self::Day => format!("{}-day", length),
+            Self::Week => format!("{}-week", length),
+        }
+    }
+}
+
+/// Parameters of the `get_streaks` tool
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreakRequest {
+    /// Which activities count; weather filters aren't supported
+    pub filter: ActivityFilter,
+    /// Activities a week needs to count toward a week streak
+    pub week_target: usize,
+}
+
+impl StreakRequest {
+    /// Read and check the tool arguments
+    pub fn from_args(args: &Value) -> Result<Self> {
+        let filter = ActivityFilter::from_args(args)?;
+        if filter.needs_weather() {
+            return Err(anyhow!("Weather filters aren't supported for streaks"));
+        }
+        let week_target = match args["week_target"].as_u64() {
+            Some(target) if (1..=14).contains(&target) => target as usize,
+            Some(_) => return Err(anyhow!("week_target must be between 1 and 14")),
+            None => 1,
+        };
+        Ok(Self {
+            filter,
+            week_target,
+        })
+    }
+}
+
+/// A run of consecutive active days or weeks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Streak {
+    pub length: u32,
+    /// First day of the streak
+    pub start: NaiveDate,
+    /// Last day of the streak with an activity
+    pub end: NaiveDate,
+    pub activities: usize,
+    pub distance_km: f64,
+}
+
+/// Current and longest streaks in one unit
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StreakSummary {
+    pub current: Option<Streak>,
+    pub longest: Option<Streak>,
+}
+
+/// How regularly the activities came over the last weeks
+#[derive(Debug, Clone, Serialize)]
+pub struct Consistency {
+    /// 0-100
+    pub score: f64,
+    pub weeks: i64,
+    /// Weeks meeting the week target
+    pub active_weeks: usize,
+    pub activities_per_week: f64,
+}
+
+/// Streaks of one sport family, or of every sport as `all`
+#[derive(Debug, Clone, Serialize)]
+pub struct SportStreaks {
+    pub sport: String,
+    pub activities: usize,
+    pub active_days: usize,
+    pub days: StreakSummary,
+    pub weeks: StreakSummary,
+    pub consistency: Consistency,
+}
+
+/// A streak length reached
+#[derive(Debug, Clone, Serialize)]
+pub struct StreakMilestone {
+    pub sport: String,
+    pub unit: StreakUnit,
+    /// Milestone length reached
+    pub milestone: u32,
+    /// Length of the streak that reached it
+    pub length: u32,
+    /// Whether the streak is still going
+    pub current: bool,
+    /// Whether it is the longest streak on record
+    pub personal_best: bool,
+    pub message: String,
+}
+
+/// Result of the `get_streaks` tool
+#[derive(Debug, Clone, Serialize)]
+pub struct StreakReport {
+    pub as_of: NaiveDate,
+    pub week_target: usize,
+    pub activities_searched: usize,
+    pub overall: SportStreaks,
+    pub sports: Vec<SportStreaks>,
+    pub milestones: Vec<StreakMilestone>,
+}
+
+impl StreakReport {
+    /// One achievement per milestone
+    pub fn insights(&self) -> Vec<Insight> {
+        self.milestones
+            .iter()
+            .map(|milestone| Insight {
+                insight_type: InsightType::Achievement,
+                message: milestone.message.clone(),
+                confidence: 95.0,
+                data: serde_json::to_value(milestone).ok(),
+            })
+            .collect()
+    }
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+fn sport_label(family: &str) -> &str {
+    match family {
+        "all" => "activity",
+        "run" => "running",
+        "ride" => "cycling",
+        "swim" => "swimming",
+        "walk" => "walking",
+        other => other,
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    let week = date.iso_week();
+    NaiveDate::from_isoywd_opt(week.year(), week.week(), Weekday::Mon).unwrap_or(date)
+}
+
+/// Runs of consecutive keys, each `step` after the last
+fn runs<'a>(
+    periods: &BTreeMap<NaiveDate, Vec<&'a Activity>>,
+    step: Duration,
+) -> Vec<(NaiveDate, NaiveDate, Vec<&'a Activity>)> {
+    let mut runs: Vec<(NaiveDate, NaiveDate, Vec<&Activity>)> = vec![];
+    for (&period, activities) in periods {
+        match runs.last_mut() {
+            Some((_, last, members)) if *last + step == period => {
+                *last = period;
+                members.extend(activities);
+            }
+            _ => runs.push((period, period, activities.clone())),
+        }
+    }
+    runs
+}
+
+/// Current and longest streak over the periods that count
+///
+/// `alive_since` is the earliest period a current streak may end in.
+fn summarize(
+    periods: &BTreeMap<NaiveDate, Vec<&Activity>>,
+    step: Duration,
+    alive_since: NaiveDate,
+) -> StreakSummary {
+    let runs = runs(periods, step);
+    let alive = runs.last().is_some_and(|(_, last, _)| *last >= alive_since);
+    let streaks: Vec<Streak> = runs
+        .into_iter()
+        .map(|(start, last, activities)| Streak {
+            length: ((last - start).num_days() / step.num_days() + 1) as u32,
+            start,
+            end: activities
+                .iter()
+                .map(|a| a.start_date.date_naive())
+                .max()
+                .unwrap_or(last),
+            activities: activities.len(),
+            distance_km: round(
+                activities
+                    .iter()
+                    .filter_map(|a| a.distance_meters)
+                    .sum::<f64>()
+                    / 1000.0,
+                2,
+            ),
+        })
+        .collect();
+
+    let longest = streaks
+        .iter()
+        // The latest of equally long streaks
+        .max_by_key(|s| (s.length, s.start))
+        .cloned();
+    let current = streaks.last().filter(|_| alive).cloned();
+    StreakSummary { current, longest }
+}
+
+fn consistency(activities: &[&Activity], today: NaiveDate, week_target: usize) -> Consistency {
+    let this_week = week_start(today);
+    let first_week = this_week - Duration::weeks(CONSISTENCY_WEEKS - 1);
+    let mut counts = vec![0usize; CONSISTENCY_WEEKS as usize];
+    for activity in activities {
+        let week = week_start(activity.start_date.date_naive());
+        if week >= first_week && week <= this_week {
+            counts[((week - first_week).num_weeks()) as usize] += 1;
+        }
+    }
+
+    let total: usize = counts.iter().sum();
+    let active_weeks = counts.iter().filter(|c| **c >= week_target).count();
+    let mean = total as f64 / counts.len() as f64;
+    let evenness = if mean > 0.0 {
+        let variance = counts
+            .iter()
+            .map(|c| (*c as f64 - mean).powi(2))
+            .sum::<f64>()
+            / counts.len() as f64;
+        1.0 - (variance.sqrt() / mean).min(1.0)
+    } else {
+        0.0
+    };
+    let score =
+        TARGET_WEIGHT * active_weeks as f64 / counts.len() as f64 + EVENNESS_WEIGHT * evenness;
+
+    Consistency {
+        score: round(score, 1),
+        weeks: CONSISTENCY_WEEKS,
+        active_weeks,
+        activities_per_week: round(mean, 1),
+    }
+}
+
+fn sport_streaks(
+    sport: &str,
+    activities: &[&Activity],
+    today: NaiveDate,
+    week_target: usize,
+) -> SportStreaks {
+    let mut days: BTreeMap<NaiveDate, Vec<&Activity>> = BTreeMap::new();
+    let mut weeks: BTreeMap<NaiveDate, Vec<&Activity>> = BTreeMap::new();
+    for activity in activities {
+        let day = activity.start_date.date_naive();
+        days.entry(day).or_default().push(activity);
+        weeks.entry(week_start(day)).or_default().push(activity);
+    }
+    weeks.retain(|_, members| members.len() >= week_target);
+
+    SportStreaks {
+        sport: sport.to_string(),
+        activities: activities.len(),
+        active_days: days.len(),
+        days: summarize(&days, Duration::days(1), today - Duration::days(1)),
+        weeks: summarize(
+            &weeks,
+            Duration::weeks(1),
+            week_start(today) - Duration::weeks(1),
+        ),
+        consistency: consistency(activities, today, week_target),
+    }
+}
+
+/// The highest milestone each current or record streak has reached
+fn milestones(streaks: &SportStreaks, sport: &str) -> Vec<StreakMilestone> {
+    let mut reached = vec![];
+    for (unit, summary, thresholds) in [
+        (StreakUnit::Day, &streaks.days, &DAY_MILESTONES[..]),
+        (StreakUnit::Week, &streaks.weeks, &WEEK_MILESTONES[..]),
+    ] {
+        let longest = summary.longest.as_ref().map_or(0, |s| s.length);
+        let mut candidates = vec![];
+        if let Some(current) = &summary.current {
+            candidates.push((current.length, true));
+        }
+        if summary.current.as_ref().map_or(0, |s| s.length) < longest {
+            candidates.push((longest, false));
+        }
+
+        for (length, current) in candidates {
+            let Some(&milestone) = thresholds.iter().rev().find(|m| **m <= length) else {
+                continue;
+            };
+            let personal_best = length >= longest;
+            let streak = format!("{} {} streak", unit.label(length), sport_label(sport));
+            let message = match (current, personal_best) {
+                (true, true) => format!("{} and counting - your longest yet", streak),
+                (true, false) => format!(
+                    "{} and counting, past the {} mark",
+                    streak,
+                    unit.label(milestone)
+                ),
+                _ => format!("Longest {} on record", streak),
+            };
+            reached.push(StreakMilestone {
+                sport: streaks.sport.clone(),
+                unit,
+                milestone,
+                length,
+                current,
+                personal_best,
+                message,
+            });
+        }
+    }
+    reached
+}
+
+/// Streaks over every activity passing the request's filter, as of `today`
+pub fn analyze_streaks(
+    history: &[Activity],
+    request: &StreakRequest,
+    today: NaiveDate,
+) -> StreakReport {
+    let matching: Vec<&Activity> = history
+        .iter()
+        .filter(|a| request.filter.matches(a) && a.start_date.date_naive() <= today)
+        .collect();
+
+    let mut families: BTreeMap<&str, Vec<&Activity>> = BTreeMap::new();
+    for activity in &matching {
+        families
+            .entry(sport_family(&activity.sport_type))
+            .or_default()
+            .push(activity);
+    }
+
+    let overall = sport_streaks("all", &matching, today, request.week_target);
+    let mut sports: Vec<SportStreaks> = families
+        .iter()
+        .map(|(family, activities)| sport_streaks(family, activities, today, request.week_target))
+        .collect();
+    sports.sort_by_key(|s| Reverse(s.activities));
+
+    // Per-sport milestones only when they add to the overall ones
+    let overall_sport = match sports.as_slice() {
+        [only] => only.sport.as_str(),
+        _ => "all",
+    };
+    let mut reached = milestones(&overall, overall_sport);
+    let seen: BTreeSet<(u32, bool)> = reached.iter().map(|m| (m.length, m.current)).collect();
+    if sports.len() > 1 {
+        for sport in &sports {
+            reached.extend(
+                milestones(sport, &sport.sport)
+                    .into_iter()
+                    .filter(|m| !seen.contains(&(m.length, m.current))),
+            );
+        }
+    }
+
+    StreakReport {
+        as_of: today,
+        week_target: request.week_target,
+        activities_searched: history.len(),
+        overall,
+        sports,
+        milestones: reached,
+    }
+}
+
+/// Result of the `get_streaks` tool for a user
+pub async fn streaks_for_user(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    provider: Option<&str>,
+    request: &StreakRequest,
+) -> Result<Value> {
+    let history = history_for_user(connections, user_id, provider, None, None).await?;
+    let report = analyze_streaks(&history.activities, request, Utc::now().date_naive());
+    let insights = report.insights();
+    let mut value = serde_json::to_value(report)?;
+    value["insights"] = serde_json::to_value(insights)?;
+    if !history.gaps.is_empty() {
+        value["notes"] = serde_json::to_value(history.gaps)?;
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SportType;
+    use serde_json::json;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, 30).unwrap()
+    }
+
+    fn run(days_ago: i64, km: f64) -> Activity {
+        Activity {
+            id: format!("run-{}-{}", days_ago, km),
+            sport_type: SportType::Run,
+            start_date: (today() - Duration::days(days_ago))
+                .and_hms_opt(7, 0, 0)
+                .unwrap()
+                .and_utc(),
+            duration_seconds: (km * 330.0) as u64,
+            distance_meters: Some(km * 1000.0),
+            ..Default::default()
+        }
+    }
+
+    fn ride(days_ago: i64) -> Activity {
+        Activity {
+            sport_type: SportType::Ride,
+            ..run(days_ago, 40.0)
+        }
+    }
+
+    #[test]
+    fn test_day_streaks_with_predicate() {
+        // Seven days of 10 km running ending yesterday, then three 5 km days,
+        // after an earlier 3-day streak and a ride in between
+        let mut history: Vec<Activity> = (1..=7).map(|d| run(d, 10.0)).collect();
+        history.extend((8..=10).map(|d| run(d, 5.0)));
+        history.extend((20..=22).map(|d| run(d, 12.0)));
+        history.push(ride(15));
+
+        let everything = StreakRequest::from_args(&json!({})).unwrap();
+        let report = analyze_streaks(&history, &everything, today());
+        let current = report.overall.days.current.as_ref().unwrap();
+        assert_eq!(current.length, 10);
+        assert_eq!(report.sports[0].sport, "run");
+        assert_eq!(report.sports.len(), 2);
+
+        let long_runs =
+            StreakRequest::from_args(&json!({"sport_type": "run", "min_distance_km": 10})).unwrap();
+        let report = analyze_streaks(&history, &long_runs, today());
+        let current = report.overall.days.current.as_ref().unwrap();
+        assert_eq!(current.length, 7);
+        assert_eq!(current.distance_km, 70.0);
+        assert_eq!(report.overall.days.longest.as_ref().unwrap().length, 7);
+
+        // A 7-day milestone, and four weeks in a row with a 10 km run
+        assert_eq!(report.milestones.len(), 2);
+        assert!(report.milestones[0].personal_best);
+        assert_eq!(report.milestones[1].unit, StreakUnit::Week);
+        assert_eq!(report.milestones[1].milestone, 4);
+        let insights = report.insights();
+        assert_eq!(insights[0].insight_type, InsightType::Achievement);
+        assert!(insights[0].message.starts_with("7-day running streak"));
+
+        // Two days later without a run, the streak is over but still the record
+        let later = analyze_streaks(&history, &long_runs, today() + Duration::days(2));
+        assert!(later.overall.days.current.is_none());
+        assert_eq!(
+            later.milestones[0].message,
+            "Longest 7-day running streak on record"
+        );
+    }
+
+    #[test]
+    fn test_week_streaks_and_consistency() {
+        // Twice a week for the last 12 weeks
+        let history: Vec<Activity> = (0..12)
+            .flat_map(|week| [run(week * 7 + 1, 8.0), run(week * 7 + 4, 8.0)])
+            .collect();
+
+        let twice = StreakRequest::from_args(&json!({"week_target": 2})).unwrap();
+        let report = analyze_streaks(&history, &twice, today());
+        assert_eq!(report.overall.weeks.current.as_ref().unwrap().length, 12);
+        assert!(report.overall.consistency.score > 90.0);
+        assert!(report.overall.days.current.as_ref().unwrap().length == 1);
+
+        let thrice = StreakRequest::from_args(&json!({"week_target": 3})).unwrap();
+        let report = analyze_streaks(&history, &thrice, today());
+        assert!(report.overall.weeks.longest.is_none());
+        assert_eq!(report.overall.consistency.active_weeks, 0);
+
+        assert!(StreakRequest::from_args(&json!({"min_temperature": 20})).is_err());
+        assert!(StreakRequest::from_args(&json!({"week_target": 0})).is_err());
+    }
+}
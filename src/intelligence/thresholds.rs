@@ -0,0 +1,539 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Athlete threshold estimation
+//!
+//! Estimates the thresholds behind personal zones and training metrics from
+//! the user's own history:
+//!
+//! | Threshold          | Estimate                                               |
+//! |--------------------|--------------------------------------------------------|
+//! | Max heart rate     | highest heart rate recorded                            |
+//! | Threshold HR       | best 20-minute average heart rate × 0.95               |
+//! | FTP                | best 20-minute average power × 0.95                    |
+//! | Threshold pace     | fastest run near 60 minutes, other lengths via Riegel  |
+//!
+//! The 20-minute values come from activity streams, so they need a provider
+//! with the streams capability. Estimates are stored under
+//! [`PROFILE_KEY`] in the user profile together with earlier estimates, and
+//! each carries a confidence based on how much data supported it.
+
+use super::metrics::MetricsCalculator;
+use super::wellness;
+use crate::config::fitness_config::ZoneThresholds;
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::models::{Activity, ActivityStreams, SportType};
+use crate::providers::connections::ProviderConnections;
+use crate::providers::merge::{activity_streams, all_provider_activities};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Profile field holding the stored estimates
+pub const PROFILE_KEY: &str = "athlete_thresholds";
+/// Stored estimates older than this are refreshed on request
+pub const STALE_AFTER_DAYS: i64 = 7;
+/// Earlier estimates kept in the history
+const MAX_HISTORY: usize = 20;
+/// Activities scanned for heart rate and pace
+const RECENT_ACTIVITY_LIMIT: usize = 200;
+/// Activities whose streams are fetched for 20-minute efforts
+const STREAM_ACTIVITY_LIMIT: usize = 20;
+/// Length of the effort behind threshold heart rate and FTP
+const THRESHOLD_WINDOW_SECONDS: u32 = 20 * 60;
+/// Share of a 20-minute effort sustainable for an hour
+const TWENTY_MINUTE_FACTOR: f64 = 0.95;
+/// Readings above this are treated as sensor spikes
+const MAX_PLAUSIBLE_HEART_RATE: u32 = 230;
+/// Runs used for threshold pace, in seconds
+const PACE_MIN_DURATION: u64 = 20 * 60;
+const PACE_MAX_DURATION: u64 = 120 * 60;
+/// Runs this close to an hour estimate threshold pace directly
+const HOUR_EFFORT_MIN: u64 = 45 * 60;
+const HOUR_EFFORT_MAX: u64 = 75 * 60;
+/// Riegel's fatigue exponent for race time against distance
+const RIEGEL_EXPONENT: f64 = 1.06;
+
+/// How well the data supports an estimate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThresholdConfidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl ThresholdConfidence {
+    fn from_samples(samples: usize, medium: usize, high: usize) -> Self {
+        if samples >= high {
+            Self::High
+        } else if samples >= medium {
+            Self::Medium
+        } else {
+            Self::Low
+        }
+    }
+}
+
+/// One estimated threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdEstimate {
+    pub value: f64,
+    pub confidence: ThresholdConfidence,
+    /// Activity the value came from
+    pub source_activity_id: Option<String>,
+    /// Activities that could have produced the value
+    pub samples: usize,
+}
+
+/// A full set of estimates made at one time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AthleteThresholds {
+    /// Maximum heart rate in bpm
+    pub max_heart_rate: Option<ThresholdEstimate>,
+    /// Lactate threshold heart rate in bpm
+    pub threshold_heart_rate: Option<ThresholdEstimate>,
+    /// Functional threshold power in watts
+    pub functional_threshold_power: Option<ThresholdEstimate>,
+    /// Threshold running pace in seconds per kilometer
+    pub threshold_pace: Option<ThresholdEstimate>,
+    pub estimated_at: DateTime<Utc>,
+}
+
+impl AthleteThresholds {
+    /// Whether two estimates agree on every value
+    fn same_values(&self, other: &AthleteThresholds) -> bool {
+        let value = |estimate: &Option<ThresholdEstimate>| estimate.as_ref().map(|e| e.value);
+        value(&self.max_heart_rate) == value(&other.max_heart_rate)
+            && value(&self.threshold_heart_rate) == value(&other.threshold_heart_rate)
+            && value(&self.functional_threshold_power) == value(&other.functional_threshold_power)
+            && value(&self.threshold_pace) == value(&other.threshold_pace)
+    }
+}
+
+/// The current estimates and the ones they replaced, newest first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredThresholds {
+    pub current: AthleteThresholds,
+    #[serde(default)]
+    pub history: Vec<AthleteThresholds>,
+}
+
+/// A heart rate zone in bpm; the top zone has no upper bound
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HeartRateZone {
+    pub name: &'static str,
+    pub min_bpm: u32,
+    pub max_bpm: Option<u32>,
+}
+
+/// Highest time-weighted average of `values` over any `window` seconds
+///
+/// Each sample holds until the next one. Returns `None` when the recording
+/// is shorter than the window.
+pub fn best_average(time: &[u32], values: &[u32], window: u32) -> Option<f64> {
+    let n = time.len().min(values.len());
+    if n < 2 || time[n - 1].saturating_sub(time[0]) < window {
+        return None;
+    }
+
+    // area[i] is the integral of the series from time[0] to time[i]
+    let mut area = vec![0.0; n];
+    for i in 1..n {
+        let step = time[i].saturating_sub(time[i - 1]);
+        area[i] = area[i - 1] + f64::from(values[i - 1]) * f64::from(step);
+    }
+
+    let mut best: Option<f64> = None;
+    let mut end = 0;
+    for start in 0..n {
+        while end < n && time[end] < time[start] + window {
+            end += 1;
+        }
+        if end == n {
+            break;
+        }
+        let average = (area[end] - area[start]) / f64::from(time[end] - time[start]);
+        best = Some(best.map_or(average, |b: f64| b.max(average)));
+    }
+    best
+}
+
+fn is_run(sport: &SportType) -> bool {
+    matches!(
+        sport,
+        SportType::Run | SportType::VirtualRun | SportType::TrailRunning
+    )
+}
+
+/// Estimate every threshold the activities and streams allow
+///
+/// `streams` pairs activity IDs with their recordings.
+pub fn estimate_thresholds(
+    activities: &[Activity],
+    streams: &[(String, ActivityStreams)],
+) -> AthleteThresholds {
+    AthleteThresholds {
+        max_heart_rate: estimate_max_heart_rate(activities, streams),
+        threshold_heart_rate: estimate_twenty_minute(streams, |s| s.heart_rate.as_ref()),
+        functional_threshold_power: estimate_twenty_minute(streams, |s| s.power.as_ref()),
+        threshold_pace: estimate_threshold_pace(activities),
+        estimated_at: Utc::now(),
+    }
+}
+
+fn estimate_max_heart_rate(
+    activities: &[Activity],
+    streams: &[(String, ActivityStreams)],
+) -> Option<ThresholdEstimate> {
+    let recorded = activities
+        .iter()
+        .filter_map(|a| a.max_heart_rate.map(|hr| (a.id.as_str(), hr)));
+    let streamed = streams.iter().filter_map(|(id, s)| {
+        s.heart_rate
+            .as_ref()
+            .and_then(|hr| {
+                hr.iter()
+                    .copied()
+                    .filter(|&v| v <= MAX_PLAUSIBLE_HEART_RATE)
+                    .max()
+            })
+            .map(|hr| (id.as_str(), hr))
+    });
+    let readings: Vec<(&str, u32)> = recorded
+        .chain(streamed)
+        .filter(|&(_, hr)| hr > 0 && hr <= MAX_PLAUSIBLE_HEART_RATE)
+        .collect();
+
+    let samples = activities
+        .iter()
+        .filter(|a| a.max_heart_rate.is_some())
+        .count();
+    let (id, max) = readings.into_iter().max_by_key(|&(_, hr)| hr)?;
+    Some(ThresholdEstimate {
+        value: f64::from(max),
+        confidence: ThresholdConfidence::from_samples(samples, 5, 20),
+        source_activity_id: Some(id.to_string()),
+        samples,
+    })
+}
+
+fn estimate_twenty_minute(
+    streams: &[(String, ActivityStreams)],
+    series: impl Fn(&ActivityStreams) -> Option<&Vec<u32>>,
+) -> Option<ThresholdEstimate> {
+    let efforts: Vec<(&str, f64)> = streams
+        .iter()
+        .filter_map(|(id, s)| {
+            series(s)
+                .and_then(|values| best_average(&s.time, values, THRESHOLD_WINDOW_SECONDS))
+                .map(|best| (id.as_str(), best))
+        })
+        .collect();
+
+    let samples = efforts.len();
+    let (id, best) = efforts.into_iter().max_by(|a, b| a.1.total_cmp(&b.1))?;
+    Some(ThresholdEstimate {
+        value: (best * TWENTY_MINUTE_FACTOR).round(),
+        confidence: ThresholdConfidence::from_samples(samples, 2, 5),
+        source_activity_id: Some(id.to_string()),
+        samples,
+    })
+}
+
+fn estimate_threshold_pace(activities: &[Activity]) -> Option<ThresholdEstimate> {
+    // Speed sustainable for an hour: by Riegel, speed scales with
+    // duration^(1/k - 1), so v60 = v * (t / 3600)^(1 - 1/k)
+    let exponent = 1.0 - 1.0 / RIEGEL_EXPONENT;
+    let runs: Vec<(&Activity, f64)> = activities
+        .iter()
+        .filter(|a| is_run(&a.sport_type))
+        .filter(|a| (PACE_MIN_DURATION..=PACE_MAX_DURATION).contains(&a.duration_seconds))
+        .filter_map(|a| {
+            let distance = a.distance_meters.filter(|d| *d > 0.0)?;
+            let speed = distance / a.duration_seconds as f64;
+            Some((
+                a,
+                speed * (a.duration_seconds as f64 / 3600.0).powf(exponent),
+            ))
+        })
+        .collect();
+
+    let is_hour_effort =
+        |a: &Activity| (HOUR_EFFORT_MIN..=HOUR_EFFORT_MAX).contains(&a.duration_seconds);
+    let hour_efforts = runs.iter().filter(|(a, _)| is_hour_effort(a)).count();
+    let (source, speed) = runs.into_iter().max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let confidence = match (is_hour_effort(source), hour_efforts) {
+        (true, n) if n >= 3 => ThresholdConfidence::High,
+        (true, _) => ThresholdConfidence::Medium,
+        (false, _) => ThresholdConfidence::Low,
+    };
+    Some(ThresholdEstimate {
+        value: (1000.0 / speed).round(),
+        confidence,
+        source_activity_id: Some(source.id.clone()),
+        samples: hour_efforts,
+    })
+}
+
+/// Heart rate zones in bpm from the configured percentages of max heart rate
+pub fn heart_rate_zones(max_heart_rate: f64, thresholds: &ZoneThresholds) -> Vec<HeartRateZone> {
+    let bpm = |percent: f32| (max_heart_rate * f64::from(percent) / 100.0).round() as u32;
+    let bounds = [
+        ("recovery", None, Some(thresholds.recovery_max)),
+        (
+            "endurance",
+            Some(thresholds.recovery_max),
+            Some(thresholds.endurance_max),
+        ),
+        (
+            "tempo",
+            Some(thresholds.endurance_max),
+            Some(thresholds.tempo_max),
+        ),
+        (
+            "threshold",
+            Some(thresholds.tempo_max),
+            Some(thresholds.threshold_max),
+        ),
+        ("vo2max", Some(thresholds.threshold_max), None),
+    ];
+    bounds
+        .into_iter()
+        .map(|(name, low, high)| HeartRateZone {
+            name,
+            min_bpm: low.map_or(0, |p| bpm(p) + 1),
+            max_bpm: high.map(bpm),
+        })
+        .collect()
+}
+
+/// The user's stored estimates, if any
+pub async fn load_thresholds(
+    database: &Database,
+    user_id: Uuid,
+) -> Result<Option<StoredThresholds>> {
+    let profile = database.get_user_profile(user_id).await?;
+    Ok(profile
+        .and_then(|p| p.get(PROFILE_KEY).cloned())
+        .and_then(|stored| serde_json::from_value(stored).ok()))
+}
+
+/// Store new estimates in the user profile
+///
+/// The previous estimate moves to the history when any value changed.
+pub async fn save_thresholds(
+    database: &Database,
+    user_id: Uuid,
+    thresholds: AthleteThresholds,
+) -> Result<StoredThresholds> {
+    let mut profile = database
+        .get_user_profile(user_id)
+        .await?
+        .unwrap_or_else(|| json!({}));
+
+    let stored = match profile
+        .get(PROFILE_KEY)
+        .cloned()
+        .and_then(|stored| serde_json::from_value::<StoredThresholds>(stored).ok())
+    {
+        Some(mut stored) => {
+            if !stored.current.same_values(&thresholds) {
+                let previous = std::mem::replace(&mut stored.current, thresholds);
+                stored.history.insert(0, previous);
+                stored.history.truncate(MAX_HISTORY);
+            } else {
+                stored.current = thresholds;
+            }
+            stored
+        }
+        None => StoredThresholds {
+            current: thresholds,
+            history: Vec::new(),
+        },
+    };
+
+    profile[PROFILE_KEY] = serde_json::to_value(&stored)?;
+    database.upsert_user_profile(user_id, profile).await?;
+    Ok(stored)
+}
+
+/// Estimate a user's thresholds from recent activities and store them
+///
+/// Activities come from every connected provider; streams are fetched for
+/// the most recent ones long enough to hold a 20-minute effort.
+pub async fn estimate_for_user(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+) -> Result<StoredThresholds> {
+    let database = connections.database();
+    let activities = all_provider_activities(connections, user_id, RECENT_ACTIVITY_LIMIT).await?;
+
+    let candidates: Vec<&Activity> = activities
+        .iter()
+        .filter(|a| a.duration_seconds >= u64::from(THRESHOLD_WINDOW_SECONDS))
+        .take(STREAM_ACTIVITY_LIMIT)
+        .collect();
+    let streams = activity_streams(connections, user_id, &candidates).await;
+
+    save_thresholds(
+        database,
+        user_id,
+        estimate_thresholds(&activities, &streams),
+    )
+    .await
+}
+
+/// Stored estimates, re-estimated when missing, stale or `refresh` is set
+pub async fn thresholds_for_user(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    refresh: bool,
+) -> Result<StoredThresholds> {
+    let database = connections.database();
+    match load_thresholds(database, user_id).await? {
+        Some(stored)
+            if !refresh
+                && Utc::now() - stored.current.estimated_at < Duration::days(STALE_AFTER_DAYS) =>
+        {
+            Ok(stored)
+        }
+        _ => estimate_for_user(connections, user_id).await,
+    }
+}
+
+/// A metrics calculator using the user's stored thresholds, profile weight
+/// and latest resting heart rate
+pub async fn metrics_calculator_for(database: &Database, user_id: Uuid) -> MetricsCalculator {
+    let current = load_thresholds(database, user_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|stored| stored.current);
+    let value = |pick: fn(&AthleteThresholds) -> &Option<ThresholdEstimate>| {
+        current
+            .as_ref()
+            .and_then(|t| pick(t).as_ref())
+            .map(|e| e.value)
+    };
+
+    let weight_kg = database
+        .get_user_profile(user_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|p| p.get("weight_kg").and_then(Value::as_f64));
+    let today = Utc::now().date_naive();
+    let resting_hr =
+        wellness::merged_wellness(database, user_id, today - Duration::days(30), today)
+            .await
+            .ok()
+            .and_then(|days| days.iter().rev().find_map(|d| d.resting_heart_rate))
+            .map(f64::from);
+
+    MetricsCalculator::new().with_user_data(
+        value(|t| &t.functional_threshold_power),
+        value(|t| &t.threshold_heart_rate),
+        value(|t| &t.max_heart_rate),
+        resting_hr,
+        weight_kg,
+    )
+}
+
+fn format_pace(seconds_per_km: f64) -> String {
+    let seconds = seconds_per_km.round() as u64;
+    format!("{}:{:02}/km", seconds / 60, seconds % 60)
+}
+
+/// Result of the `get_athlete_thresholds` tool, with heart rate zones from
+/// the user's zone model
+pub fn thresholds_report(
+    stored: &StoredThresholds,
+    zone_thresholds: &ZoneThresholds,
+    include_history: bool,
+) -> Value {
+    let current = &stored.current;
+
+    let mut report = json!({
+        "thresholds": current,
+        "threshold_pace_formatted": current.threshold_pace.as_ref().map(|p| format_pace(p.value)),
+        "heart_rate_zones": current
+            .max_heart_rate
+            .as_ref()
+            .map(|max| heart_rate_zones(max.value, zone_thresholds)),
+        "estimated_at": current.estimated_at,
+    });
+    if include_history {
+        report["history"] = json!(stored.history);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(id: &str, minutes: u64, km: f64) -> Activity {
+        Activity {
+            id: id.to_string(),
+            duration_seconds: minutes * 60,
+            distance_meters: Some(km * 1000.0),
+            max_heart_rate: None,
+            ..Activity::default()
+        }
+    }
+
+    #[test]
+    fn test_best_average() {
+        let time: Vec<u32> = (0..=40).map(|m| m * 60).collect();
+        let power: Vec<u32> = (0..=40)
+            .map(|m| if (10..30).contains(&m) { 300 } else { 150 })
+            .collect();
+        assert_eq!(best_average(&time, &power, 1200), Some(300.0));
+        assert_eq!(best_average(&time[..10], &power[..10], 1200), None);
+    }
+
+    #[test]
+    fn test_threshold_estimates() {
+        let mut activities = vec![run("hour", 60, 12.0), run("short", 25, 5.0)];
+        activities[0].max_heart_rate = Some(184);
+        let time: Vec<u32> = (0..=30).map(|m| m * 60).collect();
+        let streams = vec![(
+            "ride".to_string(),
+            ActivityStreams {
+                heart_rate: Some(vec![160; 31]),
+                power: Some(vec![240; 31]),
+                time,
+                ..Default::default()
+            },
+        )];
+
+        let thresholds = estimate_thresholds(&activities, &streams);
+        let max_hr = thresholds.max_heart_rate.unwrap();
+        assert_eq!(max_hr.value, 184.0);
+        assert_eq!(max_hr.confidence, ThresholdConfidence::Low);
+        assert_eq!(thresholds.threshold_heart_rate.unwrap().value, 152.0);
+        assert_eq!(thresholds.functional_threshold_power.unwrap().value, 228.0);
+
+        // 12 km in an hour beats 5 km in 25 minutes scaled to an hour
+        let pace = thresholds.threshold_pace.unwrap();
+        assert_eq!(pace.value, 300.0);
+        assert_eq!(pace.source_activity_id.as_deref(), Some("hour"));
+        assert_eq!(pace.confidence, ThresholdConfidence::Medium);
+    }
+
+    #[test]
+    fn test_heart_rate_zones() {
+        let zones = heart_rate_zones(190.0, &ZoneThresholds::default());
+        assert_eq!(zones.len(), 5);
+        assert_eq!(zones[0].max_bpm, Some(114));
+        assert_eq!(zones[1].min_bpm, 115);
+        assert_eq!(zones[4].min_bpm, 172);
+        assert_eq!(zones[4].max_bpm, None);
+    }
+}
@@ -5,21 +5,92 @@
 // except according to those terms.
 
 //! Weather service integration for contextual activity analysis
-
-use super::WeatherConditions;
+//!
+//! Conditions come from a [`WeatherProvider`] selected by
+//! `WeatherApiConfig.provider`:
+//!
+//! - `openweathermap`: OpenWeather One Call timemachine (needs `OPENWEATHER_API_KEY`)
+//! - `open-meteo`: Open-Meteo historical archive, no key needed
+//! - `fixture`: observations read from a JSON file, for deterministic tests
+//!
+//! Every result carries a [`WeatherProvenance`]. When no provider has data
+//! and `fallback_to_mock` is set, [`estimate_weather`] supplies a seasonal
+//! temperature marked `estimated`, which analysis never presents as fact.
+
+use super::routes::haversine_meters;
+use super::{WeatherConditions, WeatherProvenance};
 use crate::config::fitness_config::WeatherApiConfig;
-use chrono::{DateTime, Datelike, Timelike, Utc};
+use chrono::{DateTime, Datelike, NaiveDateTime, Timelike, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use std::time::{Duration, SystemTime};
 
+/// Provider name for OpenWeather
+pub const OPENWEATHER: &str = "openweathermap";
+/// Provider name for the Open-Meteo historical archive
+pub const OPEN_METEO: &str = "open-meteo";
+/// Provider name for the file-backed test provider
+pub const FIXTURE: &str = "fixture";
+
+/// Days the Open-Meteo archive lags behind; more recent days use the forecast API
+const OPEN_METEO_ARCHIVE_DELAY_DAYS: i64 = 5;
+/// Fixture observations further than this from the activity are ignored
+const FIXTURE_MAX_DISTANCE_METERS: f64 = 25_000.0;
+/// Fixture observations further than this in time are ignored
+const FIXTURE_MAX_GAP_HOURS: i64 = 3;
+
+/// Source of historical weather conditions
+///
+/// Implementations return the measured conditions closest to the requested
+/// time, or an error; they never make data up.
+#[async_trait::async_trait]
+pub trait WeatherProvider: Send + Sync {
+    /// Provider name, as set in `WeatherApiConfig.provider`
+    fn name(&self) -> &'static str;
+
+    /// Conditions at a location closest to `timestamp`
+    async fn weather_at(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<WeatherConditions, WeatherError>;
+}
+
+/// Create the provider named in the configuration
+pub fn create_weather_provider(
+    config: &WeatherApiConfig,
+    api_key: Option<String>,
+) -> Result<Box<dyn WeatherProvider>, WeatherError> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(config.request_timeout_seconds))
+        .build()
+        .unwrap_or_else(|_| Client::new());
+
+    match config.provider.as_str() {
+        OPENWEATHER => Ok(Box::new(OpenWeatherProvider::new(client, api_key))),
+        OPEN_METEO => Ok(Box::new(OpenMeteoProvider::new(client))),
+        FIXTURE => {
+            let path = config.fixture_path.as_ref().ok_or_else(|| {
+                WeatherError::FixtureError("fixture_path is not configured".to_string())
+            })?;
+            Ok(Box::new(FixtureWeatherProvider::load(path)?))
+        }
+        other => Err(WeatherError::ApiError(format!(
+            "Unsupported weather provider: {}",
+            other
+        ))),
+    }
+}
+
 /// Weather service for fetching historical weather data
 pub struct WeatherService {
-    client: Client,
     config: WeatherApiConfig,
+    /// The configured provider, or why it couldn't be created
+    provider: Result<Box<dyn WeatherProvider>, String>,
     cache: HashMap<String, CachedWeatherData>,
-    api_key: Option<String>,
 }
 
 /// Cached weather data with timestamp
@@ -29,48 +100,35 @@ struct CachedWeatherData {
     cached_at: SystemTime,
 }
 
-/// OpenWeatherMap historical API response structure
-#[derive(Debug, Deserialize)]
-struct OpenWeatherResponse {
-    data: Vec<OpenWeatherHourlyData>,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenWeatherHourlyData {
-    dt: i64, // Unix timestamp
-    temp: f64,
-    humidity: Option<f64>,
-    wind_speed: Option<f64>,
-    weather: Vec<OpenWeatherCondition>,
-}
-
-#[derive(Debug, Deserialize)]
-struct OpenWeatherCondition {
-    main: String,
-    #[allow(dead_code)]
-    description: String,
-}
-
 impl WeatherService {
     /// Create a new weather service with configuration and API key
     pub fn new(config: WeatherApiConfig, api_key: Option<String>) -> Self {
+        let provider = create_weather_provider(&config, api_key).map_err(|e| e.to_string());
+        Self {
+            config,
+            provider,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Create a weather service with an already built provider
+    pub fn with_provider(config: WeatherApiConfig, provider: Box<dyn WeatherProvider>) -> Self {
         Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(config.request_timeout_seconds))
-                .build()
-                .unwrap_or_else(|_| Client::new()),
             config,
+            provider: Ok(provider),
             cache: HashMap::new(),
-            api_key,
         }
     }
 
+    /// Create a weather service from configuration, with the OpenWeather key
+    /// from the environment
+    pub fn with_config(config: WeatherApiConfig) -> Self {
+        Self::new(config, std::env::var("OPENWEATHER_API_KEY").ok())
+    }
+
     /// Create weather service with default configuration
     pub fn with_default_config() -> Self {
-        Self::new(
-            WeatherApiConfig::default(),
-            std::env::var("OPENWEATHER_API_KEY").ok(),
-        )
+        Self::with_config(WeatherApiConfig::default())
     }
 
     /// Get the current weather service configuration
@@ -86,18 +144,25 @@ impl WeatherService {
         longitude: f64,
         timestamp: DateTime<Utc>,
     ) -> Result<WeatherConditions, WeatherError> {
-        // Check if weather API is enabled
         if !self.config.enabled {
-            return Ok(self.generate_mock_weather());
+            return self.fallback(latitude, longitude, timestamp, WeatherError::Disabled);
         }
 
+        let provider = match &self.provider {
+            Ok(provider) => provider,
+            Err(e) => {
+                let error = WeatherError::ApiError(e.clone());
+                return self.fallback(latitude, longitude, timestamp, error);
+            }
+        };
+
         // Create cache key
         let cache_key = format!(
             "{}_{}_{}_{}",
             latitude,
             longitude,
             timestamp.timestamp() / 3600, // Hour-based caching
-            self.config.provider
+            provider.name()
         );
 
         // Check cache first
@@ -109,11 +174,7 @@ impl WeatherService {
             }
         }
 
-        // Try to fetch from API
-        match self
-            .fetch_weather_from_api(latitude, longitude, timestamp)
-            .await
-        {
+        match provider.weather_at(latitude, longitude, timestamp).await {
             Ok(weather) => {
                 // Cache the result
                 self.cache.insert(
@@ -125,97 +186,24 @@ impl WeatherService {
                 );
                 Ok(weather)
             }
-            Err(e) => {
-                // Fall back to mock data if configured
-                if self.config.fallback_to_mock {
-                    tracing::warn!("Weather API failed, falling back to mock data: {}", e);
-                    Ok(self.generate_mock_weather())
-                } else {
-                    Err(e)
-                }
-            }
-        }
-    }
-
-    /// Fetch weather data from the configured API
-    async fn fetch_weather_from_api(
-        &self,
-        latitude: f64,
-        longitude: f64,
-        timestamp: DateTime<Utc>,
-    ) -> Result<WeatherConditions, WeatherError> {
-        match self.config.provider.as_str() {
-            "openweathermap" => {
-                self.fetch_from_openweather(latitude, longitude, timestamp)
-                    .await
-            }
-            _ => Err(WeatherError::ApiError(format!(
-                "Unsupported weather provider: {}",
-                self.config.provider
-            ))),
+            Err(e) => self.fallback(latitude, longitude, timestamp, e),
         }
     }
 
-    /// Fetch weather from OpenWeatherMap Historical API
-    async fn fetch_from_openweather(
+    /// An estimate when configured to fall back to one, otherwise the error
+    fn fallback(
         &self,
         latitude: f64,
         longitude: f64,
         timestamp: DateTime<Utc>,
+        error: WeatherError,
     ) -> Result<WeatherConditions, WeatherError> {
-        let api_key = self.api_key.as_ref().ok_or_else(|| {
-            WeatherError::ApiError("OpenWeather API key not configured".to_string())
-        })?;
-
-        let base_url = crate::constants::env_config::openweather_api_base();
-        let url = format!(
-            "{}/data/3.0/onecall/timemachine?lat={}&lon={}&dt={}&appid={}&units=metric",
-            base_url,
-            latitude,
-            longitude,
-            timestamp.timestamp(),
-            api_key
-        );
-
-        tracing::debug!("Fetching weather from: {}", url);
-
-        let response = self.client.get(&url).send().await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(WeatherError::ApiError(format!(
-                "OpenWeather API returned status {}: {}",
-                status, error_text
-            )));
-        }
-
-        let weather_response: OpenWeatherResponse = response.json().await?;
-
-        // Find the closest data point to our timestamp
-        let target_timestamp = timestamp.timestamp();
-        let closest_data = weather_response
-            .data
-            .into_iter()
-            .min_by_key(|data| (data.dt - target_timestamp).abs())
-            .ok_or_else(|| WeatherError::DataUnavailable)?;
-
-        // Convert to our format
-        let conditions = if let Some(weather) = closest_data.weather.first() {
-            weather.main.clone()
+        if self.config.fallback_to_mock {
+            tracing::warn!("Weather unavailable, using a seasonal estimate: {}", error);
+            Ok(estimate_weather(latitude, longitude, timestamp))
         } else {
-            "clear".to_string()
-        };
-
-        Ok(WeatherConditions {
-            temperature_celsius: closest_data.temp as f32,
-            humidity_percentage: closest_data.humidity.map(|h| h as f32),
-            wind_speed_kmh: closest_data.wind_speed.map(|ws| (ws * 3.6) as f32), // Convert m/s to km/h
-            conditions,
-        })
+            Err(error)
+        }
     }
 
     /// Get weather conditions for an activity's start location and time
@@ -233,62 +221,6 @@ impl WeatherService {
         }
     }
 
-    /// Generate mock weather data for testing
-    pub fn generate_mock_weather(&self) -> WeatherConditions {
-        // Generate realistic weather based on current time
-        let now = Utc::now();
-        let hour = now.hour();
-
-        // Simple seasonal/time-based mock data with varied conditions
-        let (base_temp, conditions) = match now.month() {
-            12 | 1 | 2 => (2.0, if now.day() % 4 == 0 { "snow" } else { "cloudy" }), // Winter
-            3..=5 => (
-                12.0,
-                if now.day() % 3 == 0 {
-                    "rainy"
-                } else {
-                    "partly cloudy"
-                },
-            ), // Spring
-            6..=8 => (
-                22.0,
-                if now.day() % 5 == 0 {
-                    "thunderstorms"
-                } else {
-                    "sunny"
-                },
-            ), // Summer
-            _ => (
-                8.0,
-                if now.day() % 3 == 0 {
-                    "rainy"
-                } else {
-                    "overcast"
-                },
-            ), // Fall
-        };
-
-        // Add some variation based on time of day
-        let temp_adjustment = match hour {
-            6..=11 => -2.0, // Cooler morning
-            12..=17 => 3.0, // Warmer afternoon
-            18..=21 => 0.0, // Moderate evening
-            _ => -5.0,      // Cooler night
-        };
-
-        let temperature = base_temp + temp_adjustment;
-
-        // Use the conditions from seasonal data
-        let conditions_str = conditions;
-
-        WeatherConditions {
-            temperature_celsius: temperature,
-            humidity_percentage: Some(60.0 + (now.timestamp() % 30) as f32),
-            wind_speed_kmh: Some(5.0 + (now.timestamp() % 15) as f32),
-            conditions: conditions_str.to_string(),
-        }
-    }
-
     /// Analyze weather impact on performance
     #[allow(dead_code)]
     pub fn analyze_weather_impact(&self, weather: &WeatherConditions) -> WeatherImpact {
@@ -374,6 +306,350 @@ impl Default for WeatherService {
     }
 }
 
+/// Seasonal temperature estimate for when no provider has data
+///
+/// Only the temperature is estimated, from the month and local hour at the
+/// activity's start (seasons flipped south of the equator). Conditions are
+/// `unknown` and the result is marked [`WeatherProvenance::Estimated`].
+pub fn estimate_weather(
+    latitude: f64,
+    longitude: f64,
+    timestamp: DateTime<Utc>,
+) -> WeatherConditions {
+    let month = if latitude < 0.0 {
+        (timestamp.month() + 5) % 12 + 1
+    } else {
+        timestamp.month()
+    };
+    let base_temp = match month {
+        12 | 1 | 2 => 2.0,
+        3..=5 => 12.0,
+        6..=8 => 22.0,
+        _ => 10.0,
+    };
+
+    let local_hour =
+        (i64::from(timestamp.hour()) + (longitude / 15.0).round() as i64).rem_euclid(24);
+    let temp_adjustment = match local_hour {
+        6..=11 => -2.0, // Cooler morning
+        12..=17 => 3.0, // Warmer afternoon
+        18..=21 => 0.0, // Moderate evening
+        _ => -5.0,      // Cooler night
+    };
+
+    WeatherConditions {
+        temperature_celsius: base_temp + temp_adjustment,
+        humidity_percentage: None,
+        wind_speed_kmh: None,
+        conditions: "unknown".to_string(),
+        provenance: WeatherProvenance::Estimated,
+    }
+}
+
+/// OpenWeatherMap historical API response structure
+#[derive(Debug, Deserialize)]
+struct OpenWeatherResponse {
+    data: Vec<OpenWeatherHourlyData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenWeatherHourlyData {
+    dt: i64, // Unix timestamp
+    temp: f64,
+    humidity: Option<f64>,
+    wind_speed: Option<f64>,
+    weather: Vec<OpenWeatherCondition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenWeatherCondition {
+    main: String,
+    #[allow(dead_code)]
+    description: String,
+}
+
+/// OpenWeather One Call historical data
+pub struct OpenWeatherProvider {
+    client: Client,
+    api_key: Option<String>,
+}
+
+impl OpenWeatherProvider {
+    pub fn new(client: Client, api_key: Option<String>) -> Self {
+        Self { client, api_key }
+    }
+}
+
+#[async_trait::async_trait]
+impl WeatherProvider for OpenWeatherProvider {
+    fn name(&self) -> &'static str {
+        OPENWEATHER
+    }
+
+    async fn weather_at(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<WeatherConditions, WeatherError> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            WeatherError::ApiError("OpenWeather API key not configured".to_string())
+        })?;
+
+        let base_url = crate::constants::env_config::openweather_api_base();
+        let url = format!(
+            "{}/data/3.0/onecall/timemachine?lat={}&lon={}&dt={}&appid={}&units=metric",
+            base_url,
+            latitude,
+            longitude,
+            timestamp.timestamp(),
+            api_key
+        );
+
+        tracing::debug!("Fetching weather from OpenWeather for {}", timestamp);
+
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(WeatherError::ApiError(format!(
+                "OpenWeather API returned status {}: {}",
+                status, error_text
+            )));
+        }
+
+        let weather_response: OpenWeatherResponse = response.json().await?;
+
+        // Find the closest data point to our timestamp
+        let target_timestamp = timestamp.timestamp();
+        let closest_data = weather_response
+            .data
+            .into_iter()
+            .min_by_key(|data| (data.dt - target_timestamp).abs())
+            .ok_or(WeatherError::DataUnavailable)?;
+
+        let conditions = closest_data
+            .weather
+            .first()
+            .map_or_else(|| "clear".to_string(), |w| w.main.to_lowercase());
+
+        Ok(WeatherConditions {
+            temperature_celsius: closest_data.temp as f32,
+            humidity_percentage: closest_data.humidity.map(|h| h as f32),
+            wind_speed_kmh: closest_data.wind_speed.map(|ws| (ws * 3.6) as f32), // Convert m/s to km/h
+            conditions,
+            provenance: WeatherProvenance::Measured,
+        })
+    }
+}
+
+/// Open-Meteo hourly response
+#[derive(Debug, Deserialize)]
+struct OpenMeteoResponse {
+    hourly: OpenMeteoHourly,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoHourly {
+    time: Vec<String>,
+    temperature_2m: Vec<Option<f64>>,
+    relative_humidity_2m: Vec<Option<f64>>,
+    wind_speed_10m: Vec<Option<f64>>,
+    weather_code: Vec<Option<u8>>,
+}
+
+/// Open-Meteo historical archive, reanalysis from weather station and
+/// satellite data
+///
+/// The archive trails by about five days, so more recent activities are
+/// looked up in the forecast API's past days instead.
+pub struct OpenMeteoProvider {
+    client: Client,
+}
+
+impl OpenMeteoProvider {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+/// Conditions for a WMO weather interpretation code
+fn wmo_conditions(code: u8) -> &'static str {
+    match code {
+        0 => "clear",
+        1 | 2 => "partly cloudy",
+        3 => "cloudy",
+        45 | 48 => "fog",
+        51..=57 => "drizzle",
+        61..=67 => "rain",
+        71..=77 => "snow",
+        80..=82 => "rain showers",
+        85 | 86 => "snow showers",
+        95..=99 => "thunderstorms",
+        _ => "unknown",
+    }
+}
+
+/// The hour closest to `timestamp` that has a temperature
+fn closest_open_meteo_hour(
+    hourly: &OpenMeteoHourly,
+    timestamp: DateTime<Utc>,
+) -> Result<WeatherConditions, WeatherError> {
+    let index = hourly
+        .time
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| hourly.temperature_2m.get(*i).copied().flatten().is_some())
+        .filter_map(|(i, time)| {
+            let hour = NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M").ok()?;
+            Some((i, (hour.and_utc() - timestamp).num_seconds().abs()))
+        })
+        .min_by_key(|(_, gap)| *gap)
+        .map(|(i, _)| i)
+        .ok_or(WeatherError::DataUnavailable)?;
+
+    let value = |values: &[Option<f64>]| values.get(index).copied().flatten();
+    Ok(WeatherConditions {
+        temperature_celsius: value(&hourly.temperature_2m).unwrap_or_default() as f32,
+        humidity_percentage: value(&hourly.relative_humidity_2m).map(|h| h as f32),
+        wind_speed_kmh: value(&hourly.wind_speed_10m).map(|w| w as f32),
+        conditions: hourly
+            .weather_code
+            .get(index)
+            .copied()
+            .flatten()
+            .map_or("unknown", wmo_conditions)
+            .to_string(),
+        provenance: WeatherProvenance::Measured,
+    })
+}
+
+#[async_trait::async_trait]
+impl WeatherProvider for OpenMeteoProvider {
+    fn name(&self) -> &'static str {
+        OPEN_METEO
+    }
+
+    async fn weather_at(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<WeatherConditions, WeatherError> {
+        let (base_url, endpoint) =
+            if (Utc::now() - timestamp).num_days() < OPEN_METEO_ARCHIVE_DELAY_DAYS {
+                (
+                    crate::constants::env_config::open_meteo_forecast_base(),
+                    "forecast",
+                )
+            } else {
+                (
+                    crate::constants::env_config::open_meteo_archive_base(),
+                    "archive",
+                )
+            };
+        let date = timestamp.date_naive();
+        let url = format!(
+            "{}/v1/{}?latitude={}&longitude={}&start_date={}&end_date={}&hourly=temperature_2m,relative_humidity_2m,wind_speed_10m,weather_code&wind_speed_unit=kmh&timezone=GMT",
+            base_url, endpoint, latitude, longitude, date, date
+        );
+
+        tracing::debug!("Fetching weather from Open-Meteo {} for {}", endpoint, date);
+
+        let response = self.client.get(&url).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(WeatherError::ApiError(format!(
+                "Open-Meteo API returned status {}: {}",
+                status, error_text
+            )));
+        }
+
+        let weather_response: OpenMeteoResponse = response.json().await?;
+        closest_open_meteo_hour(&weather_response.hourly, timestamp)
+    }
+}
+
+/// A recorded observation in a weather fixture file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherObservation {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub time: DateTime<Utc>,
+    pub temperature_celsius: f32,
+    #[serde(default)]
+    pub humidity_percentage: Option<f32>,
+    #[serde(default)]
+    pub wind_speed_kmh: Option<f32>,
+    pub conditions: String,
+}
+
+/// Deterministic provider backed by a JSON array of observations
+///
+/// Returns the observation closest in time within 25 km and 3 hours of the
+/// request, so tests get the same weather on every run.
+pub struct FixtureWeatherProvider {
+    observations: Vec<WeatherObservation>,
+}
+
+impl FixtureWeatherProvider {
+    pub fn new(observations: Vec<WeatherObservation>) -> Self {
+        Self { observations }
+    }
+
+    /// Read observations from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WeatherError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            WeatherError::FixtureError(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let observations = serde_json::from_str(&contents).map_err(|e| {
+            WeatherError::FixtureError(format!("Failed to parse {}: {}", path.display(), e))
+        })?;
+        Ok(Self::new(observations))
+    }
+}
+
+#[async_trait::async_trait]
+impl WeatherProvider for FixtureWeatherProvider {
+    fn name(&self) -> &'static str {
+        FIXTURE
+    }
+
+    async fn weather_at(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<WeatherConditions, WeatherError> {
+        self.observations
+            .iter()
+            .filter(|o| {
+                haversine_meters((o.latitude, o.longitude), (latitude, longitude))
+                    <= FIXTURE_MAX_DISTANCE_METERS
+            })
+            .map(|o| (o, (o.time - timestamp).num_seconds().abs()))
+            .filter(|(_, gap)| *gap <= FIXTURE_MAX_GAP_HOURS * 3600)
+            .min_by_key(|(_, gap)| *gap)
+            .map(|(o, _)| WeatherConditions {
+                temperature_celsius: o.temperature_celsius,
+                humidity_percentage: o.humidity_percentage,
+                wind_speed_kmh: o.wind_speed_kmh,
+                conditions: o.conditions.clone(),
+                provenance: WeatherProvenance::Measured,
+            })
+            .ok_or(WeatherError::DataUnavailable)
+    }
+}
+
 /// Weather impact analysis result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherImpact {
@@ -407,6 +683,12 @@ pub enum WeatherError {
     #[allow(dead_code)]
     DataUnavailable,
 
+    #[error("Weather lookups are disabled")]
+    Disabled,
+
+    #[error("Weather fixture error: {0}")]
+    FixtureError(String),
+
     #[error("Network error: {0}")]
     NetworkError(#[from] reqwest::Error),
 }
@@ -424,15 +706,99 @@ mod tests {
     }
 
     #[test]
-    fn test_generate_mock_weather() {
-        let config = crate::config::fitness_config::WeatherApiConfig::default();
-        let service = WeatherService::new(config, None);
-        let weather = service.generate_mock_weather();
+    fn test_estimate_weather() {
+        // Midday UTC in July: a summer afternoon in London, a winter night
+        // in Sydney
+        let july = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 7, 15, 12, 0, 0).unwrap();
+        let london = estimate_weather(51.5, -0.1, july);
+        assert_eq!(london.temperature_celsius, 25.0);
+        assert_eq!(london.provenance, WeatherProvenance::Estimated);
+        assert_eq!(london.conditions, "unknown");
+        assert!(london.humidity_percentage.is_none());
+
+        let sydney = estimate_weather(-33.9, 151.2, july);
+        assert_eq!(sydney.temperature_celsius, -3.0);
+        // The same inputs always give the same estimate
+        assert_eq!(
+            estimate_weather(51.5, -0.1, july).temperature_celsius,
+            london.temperature_celsius
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fixture_provider() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(
+            &mut file,
+            br#"[
+                {"latitude": 45.50, "longitude": -73.57, "time": "2024-05-04T10:00:00Z",
+                 "temperature_celsius": 14.5, "humidity_percentage": 70.0, "conditions": "rain"},
+                {"latitude": 45.50, "longitude": -73.57, "time": "2024-05-04T13:00:00Z",
+                 "temperature_celsius": 18.0, "conditions": "cloudy"}
+            ]"#,
+        )
+        .unwrap();
+        let config = WeatherApiConfig {
+            provider: FIXTURE.to_string(),
+            fixture_path: Some(file.path().to_string_lossy().to_string()),
+            fallback_to_mock: false,
+            ..Default::default()
+        };
+        let mut service = WeatherService::new(config, None);
 
-        assert!(weather.temperature_celsius > -20.0 && weather.temperature_celsius < 40.0);
-        assert!(weather.humidity_percentage.is_some());
-        assert!(weather.wind_speed_kmh.is_some());
-        assert!(!weather.conditions.is_empty());
+        let at = |hour| chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 5, 4, hour, 20, 0).unwrap();
+        let weather = service
+            .get_weather_at_time(45.51, -73.56, at(10))
+            .await
+            .unwrap();
+        assert_eq!(weather.temperature_celsius, 14.5);
+        assert_eq!(weather.conditions, "rain");
+        assert_eq!(weather.provenance, WeatherProvenance::Measured);
+
+        // Too far away in space or time
+        assert!(matches!(
+            service.get_weather_at_time(48.85, 2.35, at(10)).await,
+            Err(WeatherError::DataUnavailable)
+        ));
+        assert!(matches!(
+            service.get_weather_at_time(45.51, -73.56, at(20)).await,
+            Err(WeatherError::DataUnavailable)
+        ));
+    }
+
+    #[test]
+    fn test_open_meteo_closest_hour() {
+        let hourly: OpenMeteoHourly = serde_json::from_value(serde_json::json!({
+            "time": ["2024-01-10T06:00", "2024-01-10T07:00", "2024-01-10T08:00"],
+            "temperature_2m": [-4.2, -3.1, null],
+            "relative_humidity_2m": [88, 85, 80],
+            "wind_speed_10m": [12.4, 14.0, 15.1],
+            "weather_code": [71, 73, 3]
+        }))
+        .unwrap();
+
+        let at = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 1, 10, 7, 50, 0).unwrap();
+        let weather = closest_open_meteo_hour(&hourly, at).unwrap();
+        // 08:00 has no temperature, so 07:00 is used
+        assert_eq!(weather.temperature_celsius, -3.1);
+        assert_eq!(weather.humidity_percentage, Some(85.0));
+        assert_eq!(weather.wind_speed_kmh, Some(14.0));
+        assert_eq!(weather.conditions, "snow");
+        assert_eq!(weather.provenance, WeatherProvenance::Measured);
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_without_fallback() {
+        let config = WeatherApiConfig {
+            provider: "unknown".to_string(),
+            fallback_to_mock: false,
+            ..Default::default()
+        };
+        let mut service = WeatherService::new(config, None);
+        assert!(matches!(
+            service.get_weather_at_time(45.5, -73.5, Utc::now()).await,
+            Err(WeatherError::ApiError(_))
+        ));
     }
 
     #[test]
@@ -444,6 +810,7 @@ mod tests {
             humidity_percentage: Some(50.0),
             wind_speed_kmh: Some(10.0),
             conditions: "snow".to_string(),
+            provenance: WeatherProvenance::Measured,
         };
 
         let impact = service.analyze_weather_impact(&cold_weather);
@@ -464,6 +831,7 @@ mod tests {
             humidity_percentage: Some(50.0),
             wind_speed_kmh: Some(5.0),
             conditions: "sunny".to_string(),
+            provenance: WeatherProvenance::Measured,
         };
 
         let impact = service.analyze_weather_impact(&ideal_weather);
@@ -479,6 +847,7 @@ mod tests {
             humidity_percentage: Some(85.0),
             wind_speed_kmh: Some(2.0),
             conditions: "sunny".to_string(),
+            provenance: WeatherProvenance::Measured,
         };
 
         let impact = service.analyze_weather_impact(&hot_humid_weather);
@@ -503,6 +872,8 @@ mod tests {
         assert!(result.is_ok());
         let weather = result.unwrap();
         assert!(weather.temperature_celsius > -50.0 && weather.temperature_celsius < 50.0);
+        // Without an API key the fallback is a marked estimate
+        assert_eq!(weather.provenance, WeatherProvenance::Estimated);
     }
 
     #[tokio::test]
@@ -0,0 +1,421 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Daily wellness analysis: sleep, resting heart rate and recovery signals
+//!
+//! Works on the `DailyWellness` days reported by providers with the
+//! wellness capability (Fitbit today). The last few days are compared with
+//! the user's own baseline from the days before them, so the signals adapt
+//! to each athlete rather than population norms.
+
+use super::TrendDirection;
+use crate::constants::json_fields::{DATE, DAYS, END_DATE};
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::models::{DailyWellness, SleepSummary};
+use crate::providers::{registry, FitnessProvider, ProviderCapability};
+use anyhow::{bail, Context, Result};
+use chrono::{Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Provider name used for self-reported wellness entries
+pub use crate::providers::manual::MANUAL_PROVIDER;
+
+/// Days treated as "recent" when comparing against the baseline
+pub const RECENT_DAYS: usize = 3;
+/// Resting HR this far above baseline suggests incomplete recovery
+pub const ELEVATED_RESTING_HR_BPM: f64 = 5.0;
+/// HRV this far below baseline (percent) suggests incomplete recovery
+pub const HRV_DROP_PERCENT: f64 = 15.0;
+/// Average nightly sleep below this is flagged
+pub const MIN_SLEEP_HOURS: f64 = 7.0;
+/// Resting HR changes within this band are reported as stable
+const STABLE_RESTING_HR_BPM: f64 = 2.0;
+/// Days returned by the wellness tools unless asked otherwise
+const DEFAULT_DAYS: i64 = 7;
+/// Longest range the wellness tools fetch in one call
+const MAX_DAYS: i64 = 90;
+
+/// Resolve the `days` and `end_date` tool arguments into an inclusive range
+pub fn date_range(args: &Value) -> Result<(NaiveDate, NaiveDate)> {
+    let end = match args[END_DATE].as_str() {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .with_context(|| format!("Invalid end_date '{}', expected YYYY-MM-DD", date))?,
+        None => Utc::now().date_naive(),
+    };
+    let days = args[DAYS]
+        .as_i64()
+        .unwrap_or(DEFAULT_DAYS)
+        .clamp(1, MAX_DAYS);
+
+    Ok((end - Duration::days(days - 1), end))
+}
+
+/// Resolve the optional `date` tool argument, defaulting to today
+pub fn date_arg(args: &Value) -> Result<NaiveDate> {
+    match args[DATE].as_str() {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .with_context(|| format!("Invalid date '{}', expected YYYY-MM-DD", date)),
+        None => Ok(Utc::now().date_naive()),
+    }
+}
+
+/// Build a self-reported wellness entry from `log_wellness` tool arguments
+pub fn parse_manual_entry(args: &Value) -> Result<DailyWellness> {
+    let mut entry = DailyWellness::new(date_arg(args)?, MANUAL_PROVIDER);
+
+    if let Some(hours) = args["sleep_hours"].as_f64() {
+        if !(0.0..=24.0).contains(&hours) {
+            bail!("sleep_hours must be between 0 and 24");
+        }
+        let minutes = (hours * 60.0).round() as u32;
+        entry.sleep = Some(SleepSummary {
+            start_time: None,
+            end_time: None,
+            minutes_asleep: minutes,
+            minutes_in_bed: minutes,
+            efficiency: None,
+            stages: None,
+            quality: None,
+        });
+    }
+    if let Some(quality) = args["sleep_quality"].as_u64() {
+        let Some(sleep) = entry.sleep.as_mut() else {
+            bail!("sleep_quality requires sleep_hours");
+        };
+        if !(1..=5).contains(&quality) {
+            bail!("sleep_quality must be between 1 and 5");
+        }
+        sleep.quality = Some(quality as u8);
+    }
+    if let Some(bpm) = args["resting_heart_rate"].as_u64() {
+        if !(25..=150).contains(&bpm) {
+            bail!("resting_heart_rate must be between 25 and 150 bpm");
+        }
+        entry.resting_heart_rate = Some(bpm as u32);
+    }
+    if let Some(rmssd) = args["hrv_rmssd_ms"].as_f64() {
+        if !(1.0..=300.0).contains(&rmssd) {
+            bail!("hrv_rmssd_ms must be between 1 and 300");
+        }
+        entry.hrv_rmssd_ms = Some(rmssd);
+    }
+
+    if entry.sleep.is_none() && entry.resting_heart_rate.is_none() && entry.hrv_rmssd_ms.is_none() {
+        bail!("Provide at least one of sleep_hours, resting_heart_rate or hrv_rmssd_ms");
+    }
+    Ok(entry)
+}
+
+/// Store a manual entry on top of anything already logged for that day
+pub async fn log_manual_entry(
+    database: &Database,
+    user_id: Uuid,
+    entry: &DailyWellness,
+) -> Result<DailyWellness> {
+    let mut merged = database
+        .get_daily_wellness(user_id, MANUAL_PROVIDER, entry.date, entry.date)
+        .await?
+        .pop()
+        .unwrap_or_else(|| DailyWellness::new(entry.date, MANUAL_PROVIDER));
+    merged.overlay(entry);
+    database.upsert_daily_wellness(user_id, &merged).await?;
+    Ok(merged)
+}
+
+/// Fetch wellness days from a provider and store them locally
+pub async fn sync_daily_wellness(
+    database: &Database,
+    provider: &dyn FitnessProvider,
+    user_id: Uuid,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<DailyWellness>> {
+    let days = provider.get_daily_wellness(start, end).await?;
+    for day in &days {
+        database.upsert_daily_wellness(user_id, day).await?;
+    }
+    Ok(days)
+}
+
+/// Resting heart rate for one day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestingHrPoint {
+    pub date: NaiveDate,
+    pub bpm: u32,
+}
+
+/// Resting heart rate over a period compared against the user's baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestingHrTrend {
+    pub points: Vec<RestingHrPoint>,
+    /// Average before the recent window
+    pub baseline_bpm: Option<f64>,
+    /// Average over the last `RECENT_DAYS` readings
+    pub recent_bpm: Option<f64>,
+    pub change_bpm: Option<f64>,
+    /// A falling resting HR is improving, a rising one declining
+    pub direction: TrendDirection,
+}
+
+/// Averages over the nights in a period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleepOverview {
+    pub nights: usize,
+    pub average_hours_asleep: Option<f64>,
+    pub average_efficiency: Option<f64>,
+    pub average_deep_minutes: Option<f64>,
+    pub average_rem_minutes: Option<f64>,
+}
+
+/// Recovery indicators derived from wellness data
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecoverySignals {
+    /// Recent resting HR minus baseline, when above `ELEVATED_RESTING_HR_BPM`
+    pub elevated_resting_hr_bpm: Option<f64>,
+    /// Recent HRV drop versus baseline in percent, when above `HRV_DROP_PERCENT`
+    pub hrv_drop_percent: Option<f64>,
+    /// Average recent sleep, when below `MIN_SLEEP_HOURS`
+    pub short_sleep_hours: Option<f64>,
+}
+
+impl RecoverySignals {
+    /// Whether any signal points to incomplete recovery
+    pub fn needs_recovery(&self) -> bool {
+        self.elevated_resting_hr_bpm.is_some()
+            || self.hrv_drop_percent.is_some()
+            || self.short_sleep_hours.is_some()
+    }
+}
+
+/// Analyze resting heart rate for days sorted oldest first
+pub fn resting_hr_trend(days: &[DailyWellness]) -> RestingHrTrend {
+    let points: Vec<RestingHrPoint> = days
+        .iter()
+        .filter_map(|day| {
+            day.resting_heart_rate.map(|bpm| RestingHrPoint {
+                date: day.date,
+                bpm,
+            })
+        })
+        .collect();
+
+    let values: Vec<f64> = points.iter().map(|p| f64::from(p.bpm)).collect();
+    let (baseline_bpm, recent_bpm) = baseline_and_recent(&values);
+    let change_bpm = baseline_bpm.zip(recent_bpm).map(|(b, r)| r - b);
+
+    let direction = match change_bpm {
+        Some(change) if change > STABLE_RESTING_HR_BPM => TrendDirection::Declining,
+        Some(change) if change < -STABLE_RESTING_HR_BPM => TrendDirection::Improving,
+        _ => TrendDirection::Stable,
+    };
+
+    RestingHrTrend {
+        points,
+        baseline_bpm,
+        recent_bpm,
+        change_bpm,
+        direction,
+    }
+}
+
+/// Summarize the nights in a period
+pub fn sleep_overview(days: &[DailyWellness]) -> SleepOverview {
+    let nights: Vec<_> = days.iter().filter_map(|day| day.sleep.as_ref()).collect();
+    let stages: Vec<_> = nights.iter().filter_map(|s| s.stages.as_ref()).collect();
+
+    SleepOverview {
+        nights: nights.len(),
+        average_hours_asleep: average(nights.iter().map(|s| f64::from(s.minutes_asleep) / 60.0)),
+        average_efficiency: average(nights.iter().filter_map(|s| s.efficiency.map(f64::from))),
+        average_deep_minutes: average(stages.iter().map(|s| f64::from(s.deep_minutes))),
+        average_rem_minutes: average(stages.iter().map(|s| f64::from(s.rem_minutes))),
+    }
+}
+
+/// Compare the most recent days against the user's baseline
+pub fn recovery_signals(days: &[DailyWellness]) -> RecoverySignals {
+    let trend = resting_hr_trend(days);
+    let elevated_resting_hr_bpm = trend
+        .change_bpm
+        .filter(|change| *change > ELEVATED_RESTING_HR_BPM);
+
+    let hrv: Vec<f64> = days.iter().filter_map(|day| day.hrv_rmssd_ms).collect();
+    let hrv_drop_percent = match baseline_and_recent(&hrv) {
+        (Some(baseline), Some(recent)) if baseline > 0.0 => {
+            Some((baseline - recent) / baseline * 100.0)
+        }
+        _ => None,
+    }
+    .filter(|drop| *drop > HRV_DROP_PERCENT);
+
+    let sleep: Vec<f64> = days
+        .iter()
+        .filter_map(|day| day.sleep.as_ref())
+        .map(|s| f64::from(s.minutes_asleep) / 60.0)
+        .collect();
+    let recent_sleep = &sleep[sleep.len().saturating_sub(RECENT_DAYS)..];
+    let short_sleep_hours =
+        average(recent_sleep.iter().copied()).filter(|hours| *hours < MIN_SLEEP_HOURS);
+
+    RecoverySignals {
+        elevated_resting_hr_bpm,
+        hrv_drop_percent,
+        short_sleep_hours,
+    }
+}
+
+/// Stored wellness days from every wellness provider plus manual entries,
+/// merged into one record per date (manual values win), oldest first
+pub async fn merged_wellness(
+    database: &Database,
+    user_id: Uuid,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> anyhow::Result<Vec<DailyWellness>> {
+    let mut providers: Vec<&str> = registry()
+        .provider_names()
+        .into_iter()
+        .filter(|name| {
+            registry()
+                .get(name)
+                .is_some_and(|d| d.supports(ProviderCapability::Wellness))
+        })
+        .collect();
+    providers.push(MANUAL_PROVIDER);
+
+    let mut days: BTreeMap<NaiveDate, DailyWellness> = BTreeMap::new();
+    for provider in providers {
+        for day in database
+            .get_daily_wellness(user_id, provider, start, end)
+            .await?
+        {
+            match days.get_mut(&day.date) {
+                Some(merged) => merged.overlay(&day),
+                None => {
+                    days.insert(day.date, day);
+                }
+            }
+        }
+    }
+    Ok(days.into_values().collect())
+}
+
+/// Result of the `get_sleep` tool
+pub fn sleep_report(days: &[DailyWellness]) -> Value {
+    let nights: Vec<Value> = days
+        .iter()
+        .filter_map(|day| {
+            day.sleep
+                .as_ref()
+                .map(|sleep| json!({ "date": day.date, "sleep": sleep }))
+        })
+        .collect();
+
+    json!({
+        "nights": nights,
+        "summary": sleep_overview(days),
+    })
+}
+
+/// Result of the `get_daily_wellness` tool
+pub fn daily_wellness_report(days: &[DailyWellness]) -> Value {
+    let signals = recovery_signals(days);
+    json!({
+        "days": days,
+        "total_days": days.len(),
+        "needs_recovery": signals.needs_recovery(),
+        "recovery_signals": signals,
+    })
+}
+
+/// Result of the `get_resting_hr_trend` tool
+pub fn resting_hr_report(days: &[DailyWellness]) -> Value {
+    json!({ "resting_hr_trend": resting_hr_trend(days) })
+}
+
+/// Split a series into the baseline average and the last `RECENT_DAYS` average
+fn baseline_and_recent(values: &[f64]) -> (Option<f64>, Option<f64>) {
+    if values.len() <= RECENT_DAYS {
+        return (None, average(values.iter().copied()));
+    }
+    let (baseline, recent) = values.split_at(values.len() - RECENT_DAYS);
+    (
+        average(baseline.iter().copied()),
+        average(recent.iter().copied()),
+    )
+}
+
+fn average(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0u32), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / f64::from(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SleepSummary;
+
+    fn day(offset: i64, rhr: u32, hrv: f64, minutes_asleep: u32) -> DailyWellness {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap() + chrono::Duration::days(offset);
+        let start = date.and_hms_opt(0, 0, 0).unwrap();
+        let mut wellness = DailyWellness::new(date, "fitbit");
+        wellness.resting_heart_rate = Some(rhr);
+        wellness.hrv_rmssd_ms = Some(hrv);
+        wellness.sleep = Some(SleepSummary {
+            start_time: Some(start),
+            end_time: Some(start + chrono::Duration::minutes(i64::from(minutes_asleep))),
+            minutes_asleep,
+            minutes_in_bed: minutes_asleep,
+            efficiency: Some(92),
+            stages: None,
+            quality: None,
+        });
+        wellness
+    }
+
+    #[test]
+    fn test_recovery_signals_flag_poor_recovery() {
+        let mut days: Vec<_> = (0..7).map(|i| day(i, 50, 70.0, 480)).collect();
+        days.extend((7..10).map(|i| day(i, 57, 50.0, 360)));
+
+        let signals = recovery_signals(&days);
+        assert_eq!(signals.elevated_resting_hr_bpm, Some(7.0));
+        assert!(signals.hrv_drop_percent.unwrap() > 28.0);
+        assert_eq!(signals.short_sleep_hours, Some(6.0));
+        assert!(signals.needs_recovery());
+
+        let trend = resting_hr_trend(&days);
+        assert_eq!(trend.points.len(), 10);
+        assert!(matches!(trend.direction, TrendDirection::Declining));
+    }
+
+    #[test]
+    fn test_date_range_defaults_and_limits() {
+        let (start, end) = date_range(&json!({"end_date": "2024-06-03", "days": 14})).unwrap();
+        assert_eq!(start, NaiveDate::from_ymd_opt(2024, 5, 21).unwrap());
+        assert_eq!(end, NaiveDate::from_ymd_opt(2024, 6, 3).unwrap());
+
+        let (start, end) = date_range(&json!({"days": 1000})).unwrap();
+        assert_eq!((end - start).num_days(), MAX_DAYS - 1);
+
+        assert!(date_range(&json!({"end_date": "June 3rd"})).is_err());
+    }
+
+    #[test]
+    fn test_steady_days_have_no_signals() {
+        let days: Vec<_> = (0..10).map(|i| day(i, 50, 70.0, 480)).collect();
+
+        assert!(!recovery_signals(&days).needs_recovery());
+        assert!(matches!(
+            resting_hr_trend(&days).direction,
+            TrendDirection::Stable
+        ));
+        assert_eq!(sleep_overview(&days).average_hours_asleep, Some(8.0));
+    }
+}
@@ -0,0 +1,499 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Acute:chronic workload ratio and injury risk
+//!
+//! Tracks, per sport family, how the last week of training compares with
+//! the month before it:
+//!
+//! | Signal          | Compares                                                    | Moderate | High |
+//! |-----------------|-------------------------------------------------------------|----------|------|
+//! | Coupled ACWR    | 7-day load against the 28-day weekly average                | 1.3      | 1.5  |
+//! | EWMA ACWR       | 7-day against 28-day exponentially weighted daily load      | 1.3      | 1.5  |
+//! | Mileage jump    | 7-day distance against the weekly average of the 3 before   | +30%     | +50% |
+//! | Long-run spike  | the week's longest run against that weekly average          | 50%      | 75%  |
+//!
+//! Load is training minutes weighted by heart-rate intensity, as in the
+//! readiness score. Ratios need activities spanning two weeks, and distance
+//! signals a base of [`MIN_BASE_WEEKLY_KM`] a week. Users with an injury
+//! history in their profile get every limit lowered by
+//! [`INJURY_HISTORY_MARGIN`], and their alerts say why.
+
+use super::insights::{Insight, InsightType};
+use super::patterns::sport_family;
+use super::readiness::training_load;
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::models::Activity;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Activities fetched for the workload of a user
+pub const ACTIVITY_LIMIT: usize = 500;
+/// Days of history used, enough to settle the 28-day EWMA
+const HISTORY_DAYS: i64 = 90;
+const ACUTE_DAYS: i64 = 7;
+const CHRONIC_DAYS: i64 = 28;
+/// Ratios need activities spanning at least this many days
+const MIN_LOAD_HISTORY_DAYS: i64 = 14;
+/// Weekly distance below which jumps and spikes aren't judged
+pub const MIN_BASE_WEEKLY_KM: f64 = 5.0;
+/// Workload ratio limits for moderate and high risk
+const RATIO_LIMITS: (f64, f64) = (1.3, 1.5);
+/// Weekly distance increase limits for moderate and high risk
+const MILEAGE_JUMP_LIMITS: (f64, f64) = (0.3, 0.5);
+/// Longest run as a share of weekly distance, for moderate and high risk
+const LONG_RUN_LIMITS: (f64, f64) = (0.5, 0.75);
+/// How much every limit is lowered for users with an injury history
+pub const INJURY_HISTORY_MARGIN: f64 = 0.1;
+
+/// Injury risk from recent changes in training
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum InjuryRisk {
+    Low,
+    Moderate,
+    High,
+}
+
+/// What an alert was raised for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkloadAlertType {
+    WorkloadRatio,
+    MileageJump,
+    LongRunSpike,
+}
+
+/// Workload of one sport family
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SportWorkload {
+    pub sport: String,
+    /// Load of the last 7 days
+    pub acute_load: f64,
+    /// Weekly average load of the last 28 days
+    pub chronic_load: f64,
+    pub coupled_ratio: Option<f64>,
+    /// Exponentially weighted daily loads, 7- and 28-day spans
+    pub ewma_acute_load: f64,
+    pub ewma_chronic_load: f64,
+    pub ewma_ratio: Option<f64>,
+    pub weekly_distance_km: f64,
+    /// Weekly average distance of the 3 weeks before the last 7 days
+    pub baseline_weekly_distance_km: f64,
+    /// Longest single session of the last 7 days
+    pub longest_session_km: f64,
+    pub risk: InjuryRisk,
+}
+
+/// A risky change in one sport's training
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadAlert {
+    pub sport: String,
+    pub alert_type: WorkloadAlertType,
+    pub risk: InjuryRisk,
+    pub value: f64,
+    /// Limit crossed, lowered for users with an injury history
+    pub limit: f64,
+    pub message: String,
+}
+
+/// Workload ratios and injury-risk alerts across the user's sports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadReport {
+    pub as_of: NaiveDate,
+    pub overall_risk: InjuryRisk,
+    pub injury_history: Vec<String>,
+    /// Sports trained in the last 28 days, heaviest acute load first
+    pub sports: Vec<SportWorkload>,
+    pub alerts: Vec<WorkloadAlert>,
+}
+
+impl WorkloadReport {
+    /// Injury risk insights, one per alert
+    pub fn insights(&self) -> Vec<Insight> {
+        self.alerts
+            .iter()
+            .map(|alert| Insight {
+                insight_type: InsightType::InjuryRisk,
+                message: alert.message.clone(),
+                confidence: if alert.risk == InjuryRisk::High {
+                    90.0
+                } else {
+                    80.0
+                },
+                data: serde_json::to_value(alert).ok(),
+            })
+            .collect()
+    }
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+fn sport_label(family: &str) -> String {
+    match family {
+        "run" => "Running".to_string(),
+        "ride" => "Cycling".to_string(),
+        "swim" => "Swimming".to_string(),
+        "walk" => "Walking".to_string(),
+        other => {
+            let mut chars = other.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Risk of a value against moderate and high limits, lowered by `margin`
+fn risk_for(value: f64, (moderate, high): (f64, f64), margin: f64) -> (InjuryRisk, f64) {
+    let (moderate, high) = (moderate - margin, high - margin);
+    if value >= high {
+        (InjuryRisk::High, high)
+    } else if value >= moderate {
+        (InjuryRisk::Moderate, moderate)
+    } else {
+        (InjuryRisk::Low, moderate)
+    }
+}
+
+/// Exponentially weighted daily loads from `start` to `end`
+fn ewma(daily: &BTreeMap<NaiveDate, f64>, start: NaiveDate, end: NaiveDate, span: i64) -> f64 {
+    let lambda = 2.0 / (span as f64 + 1.0);
+    let mut average = 0.0;
+    let mut day = start;
+    while day <= end {
+        let load = daily.get(&day).copied().unwrap_or(0.0);
+        average = lambda * load + (1.0 - lambda) * average;
+        day += Duration::days(1);
+    }
+    average
+}
+
+/// Workload of one sport family's activities, with any alerts it raises
+fn sport_workload(
+    family: &str,
+    activities: &[&Activity],
+    today: NaiveDate,
+    margin: f64,
+) -> Option<(SportWorkload, Vec<WorkloadAlert>)> {
+    let days_ago = |a: &Activity| (today - a.start_date.date_naive()).num_days();
+    let within = |a: &&&Activity, from: i64, to: i64| (from..to).contains(&days_ago(a));
+    let km = |a: &&Activity| a.distance_meters.unwrap_or(0.0) / 1000.0;
+
+    let acute: f64 = activities
+        .iter()
+        .filter(|a| within(a, 0, ACUTE_DAYS))
+        .map(|a| training_load(a))
+        .sum();
+    let chronic_total: f64 = activities
+        .iter()
+        .filter(|a| within(a, 0, CHRONIC_DAYS))
+        .map(|a| training_load(a))
+        .sum();
+    if chronic_total <= 0.0 {
+        return None;
+    }
+    let chronic = chronic_total / (CHRONIC_DAYS / ACUTE_DAYS) as f64;
+
+    let mut daily = BTreeMap::new();
+    for activity in activities {
+        *daily.entry(activity.start_date.date_naive()).or_insert(0.0) += training_load(activity);
+    }
+    let earliest = *daily.keys().next()?;
+    let enough_history = (today - earliest).num_days() >= MIN_LOAD_HISTORY_DAYS;
+    let ewma_acute = ewma(&daily, earliest, today, ACUTE_DAYS);
+    let ewma_chronic = ewma(&daily, earliest, today, CHRONIC_DAYS);
+    let coupled_ratio = enough_history.then(|| acute / chronic);
+    let ewma_ratio = (enough_history && ewma_chronic > 0.0).then(|| ewma_acute / ewma_chronic);
+
+    let weekly_km: f64 = activities
+        .iter()
+        .filter(|a| within(a, 0, ACUTE_DAYS))
+        .map(km)
+        .sum();
+    let baseline_km = activities
+        .iter()
+        .filter(|a| within(a, ACUTE_DAYS, CHRONIC_DAYS))
+        .map(km)
+        .sum::<f64>()
+        / ((CHRONIC_DAYS - ACUTE_DAYS) / ACUTE_DAYS) as f64;
+    let longest_km = activities
+        .iter()
+        .filter(|a| within(a, 0, ACUTE_DAYS))
+        .map(km)
+        .fold(0.0, f64::max);
+
+    let label = sport_label(family);
+    let mut alerts = Vec::new();
+
+    if let Some(ratio) = coupled_ratio.into_iter().chain(ewma_ratio).reduce(f64::max) {
+        let (risk, limit) = risk_for(ratio, RATIO_LIMITS, margin);
+        if risk > InjuryRisk::Low {
+            alerts.push(WorkloadAlert {
+                sport: family.to_string(),
+                alert_type: WorkloadAlertType::WorkloadRatio,
+                risk,
+                value: round(ratio, 2),
+                limit: round(limit, 2),
+                message: format!(
+                    "{} load over the last 7 days is {:.2}x your 4-week average (EWMA {:.2}); ratios above {:.1} are linked to a higher injury risk",
+                    label,
+                    coupled_ratio.unwrap_or(ratio),
+                    ewma_ratio.unwrap_or(ratio),
+                    limit
+                ),
+            });
+        }
+    }
+
+    if baseline_km >= MIN_BASE_WEEKLY_KM {
+        let jump = weekly_km / baseline_km - 1.0;
+        let (risk, limit) = risk_for(jump, MILEAGE_JUMP_LIMITS, margin);
+        if risk > InjuryRisk::Low {
+            alerts.push(WorkloadAlert {
+                sport: family.to_string(),
+                alert_type: WorkloadAlertType::MileageJump,
+                risk,
+                value: round(jump, 2),
+                limit: round(limit, 2),
+                message: format!(
+                    "{} distance this week is {:.1} km, up {:.0}% on your {:.1} km weekly average of the 3 weeks before; build by 10% or less a week",
+                    label,
+                    weekly_km,
+                    jump * 100.0,
+                    baseline_km
+                ),
+            });
+        }
+
+        if family == "run" {
+            let share = longest_km / baseline_km;
+            let (risk, limit) = risk_for(share, LONG_RUN_LIMITS, margin);
+            if risk > InjuryRisk::Low {
+                alerts.push(WorkloadAlert {
+                    sport: family.to_string(),
+                    alert_type: WorkloadAlertType::LongRunSpike,
+                    risk,
+                    value: round(share, 2),
+                    limit: round(limit, 2),
+                    message: format!(
+                        "This week's longest run of {:.1} km is {:.0}% of your usual {:.1} km week; keep long runs under {:.0}% of weekly distance",
+                        longest_km,
+                        share * 100.0,
+                        baseline_km,
+                        limit * 100.0
+                    ),
+                });
+            }
+        }
+    }
+
+    let risk = alerts
+        .iter()
+        .map(|alert| alert.risk)
+        .max()
+        .unwrap_or(InjuryRisk::Low);
+    Some((
+        SportWorkload {
+            sport: family.to_string(),
+            acute_load: round(acute, 1),
+            chronic_load: round(chronic, 1),
+            coupled_ratio: coupled_ratio.map(|r| round(r, 2)),
+            ewma_acute_load: round(ewma_acute, 1),
+            ewma_chronic_load: round(ewma_chronic, 1),
+            ewma_ratio: ewma_ratio.map(|r| round(r, 2)),
+            weekly_distance_km: round(weekly_km, 1),
+            baseline_weekly_distance_km: round(baseline_km, 1),
+            longest_session_km: round(longest_km, 1),
+            risk,
+        },
+        alerts,
+    ))
+}
+
+/// Workload ratios and alerts for every sport trained in the last 28 days
+///
+/// Only activities from the [`HISTORY_DAYS`] up to `now` are used.
+pub fn analyze_workload(
+    activities: &[Activity],
+    now: DateTime<Utc>,
+    injury_history: &[String],
+) -> WorkloadReport {
+    let today = now.date_naive();
+    let margin = if injury_history.is_empty() {
+        0.0
+    } else {
+        INJURY_HISTORY_MARGIN
+    };
+
+    let mut by_sport: BTreeMap<&str, Vec<&Activity>> = BTreeMap::new();
+    for activity in activities {
+        let days_ago = (today - activity.start_date.date_naive()).num_days();
+        if (0..HISTORY_DAYS).contains(&days_ago) {
+            by_sport
+                .entry(sport_family(&activity.sport_type))
+                .or_default()
+                .push(activity);
+        }
+    }
+
+    let mut sports = Vec::new();
+    let mut alerts = Vec::new();
+    for (family, sport_activities) in &by_sport {
+        if let Some((workload, sport_alerts)) =
+            sport_workload(family, sport_activities, today, margin)
+        {
+            sports.push(workload);
+            alerts.extend(sport_alerts);
+        }
+    }
+    sports.sort_by(|a, b| b.acute_load.total_cmp(&a.acute_load));
+    alerts.sort_by_key(|alert| std::cmp::Reverse(alert.risk));
+
+    if !injury_history.is_empty() {
+        for alert in &mut alerts {
+            alert.message.push_str(&format!(
+                ". Limits are lowered for your history of {}",
+                injury_history.join(", ")
+            ));
+        }
+    }
+
+    WorkloadReport {
+        as_of: today,
+        overall_risk: alerts
+            .iter()
+            .map(|alert| alert.risk)
+            .max()
+            .unwrap_or(InjuryRisk::Low),
+        injury_history: injury_history.to_vec(),
+        sports,
+        alerts,
+    }
+}
+
+/// The injuries listed in a user's profile
+pub async fn injury_history(database: &Database, user_id: Uuid) -> Vec<String> {
+    database
+        .get_user_profile(user_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|profile| {
+            serde_json::from_value::<Vec<String>>(profile["injury_history"].clone()).ok()
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|injury| !injury.trim().is_empty())
+        .collect()
+}
+
+/// A user's workload from already fetched activities
+pub async fn workload_for_user(
+    database: &Database,
+    user_id: Uuid,
+    activities: &[Activity],
+) -> WorkloadReport {
+    analyze_workload(
+        activities,
+        Utc::now(),
+        &injury_history(database, user_id).await,
+    )
+}
+
+/// The `injury_risk` section of the training load tools
+pub fn workload_json(report: &WorkloadReport) -> Value {
+    json!({
+        "overall_risk": report.overall_risk,
+        "injury_history": report.injury_history,
+        "sports": report.sports,
+        "alerts": report.alerts,
+        "insights": report.insights(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SportType;
+
+    fn run(days_ago: i64, km: f64) -> Activity {
+        Activity {
+            start_date: Utc::now() - Duration::days(days_ago),
+            duration_seconds: (km * 360.0) as u64,
+            distance_meters: Some(km * 1000.0),
+            average_heart_rate: Some(135),
+            sport_type: SportType::Run,
+            ..Default::default()
+        }
+    }
+
+    /// Four weeks of 8 km runs every other day
+    fn steady_month() -> Vec<Activity> {
+        (8..36).step_by(2).map(|day| run(day, 8.0)).collect()
+    }
+
+    #[test]
+    fn test_steady_training_is_low_risk() {
+        let mut activities = steady_month();
+        activities.extend((0..7).step_by(2).map(|day| run(day, 8.0)));
+
+        let report = analyze_workload(&activities, Utc::now(), &[]);
+        assert_eq!(report.overall_risk, InjuryRisk::Low);
+        let running = &report.sports[0];
+        assert_eq!(running.sport, "run");
+        assert!((0.8..1.3).contains(&running.coupled_ratio.unwrap()));
+        assert!((0.8..1.3).contains(&running.ewma_ratio.unwrap()));
+        assert!(report.insights().is_empty());
+    }
+
+    #[test]
+    fn test_spikes_raise_alerts() {
+        // Last week: 4 runs of 10 km and a 22 km long run
+        let mut activities = steady_month();
+        activities.extend([0, 1, 3, 5].map(|day| run(day, 10.0)));
+        activities.push(run(6, 22.0));
+
+        let report = analyze_workload(&activities, Utc::now(), &[]);
+        assert_eq!(report.overall_risk, InjuryRisk::High);
+        let types: Vec<WorkloadAlertType> = report.alerts.iter().map(|a| a.alert_type).collect();
+        assert!(types.contains(&WorkloadAlertType::WorkloadRatio));
+        assert!(types.contains(&WorkloadAlertType::MileageJump));
+        assert!(types.contains(&WorkloadAlertType::LongRunSpike));
+        assert_eq!(report.insights()[0].insight_type, InsightType::InjuryRisk);
+    }
+
+    #[test]
+    fn test_injury_history_lowers_limits() {
+        // About 45% more distance than usual: moderate, high after an injury
+        let mut activities = steady_month();
+        activities.extend((0..7).step_by(2).map(|day| run(day, 9.7)));
+
+        let report = analyze_workload(&activities, Utc::now(), &[]);
+        let jump = |report: &WorkloadReport| {
+            report
+                .alerts
+                .iter()
+                .find(|a| a.alert_type == WorkloadAlertType::MileageJump)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(jump(&report).risk, InjuryRisk::Moderate);
+
+        let injured = analyze_workload(&activities, Utc::now(), &["shin splints".to_string()]);
+        let alert = jump(&injured);
+        assert_eq!(alert.risk, InjuryRisk::High);
+        assert_eq!(alert.limit, 0.4);
+        assert!(alert.message.ends_with("your history of shin splints"));
+    }
+}
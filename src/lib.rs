@@ -87,6 +87,9 @@ pub mod mcp;
 /// Athlete Intelligence for activity analysis and insights
 pub mod intelligence;
 
+/// Scheduled delivery of weekly, monthly and yearly training digests
+pub mod digests;
+
 /// A2A (Agent-to-Agent) protocol implementation
 pub mod a2a;
 
@@ -120,6 +123,15 @@ pub mod api_key_routes;
 /// Dashboard routes for frontend consumption
 pub mod dashboard_routes;
 
+/// Webhook receivers for push-based provider sync
+pub mod webhook_routes;
+
+/// REST endpoints for manually logged activities
+pub mod activity_routes;
+
+/// REST endpoints for per-user analysis settings overrides
+pub mod analysis_settings_routes;
+
 /// WebSocket support for real-time updates
 pub mod websocket;
 
@@ -207,6 +207,10 @@ async fn create_tool_executor() -> Result<Arc<UniversalToolExecutor>> {
                         environment: crate::config::environment::Environment::Development,
                     },
                 },
+                a2a: crate::config::environment::A2AConfig {
+                    public_url: None,
+                    agent_card_signing_key_path: None,
+                },
                 external_services: crate::config::environment::ExternalServicesConfig {
                     weather: crate::config::environment::WeatherServiceConfig {
                         api_key: std::env::var("OPENWEATHER_API_KEY").ok(),
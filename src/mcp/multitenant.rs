@@ -10,20 +10,54 @@
 //! secure token storage, and user-scoped data access.
 
 use crate::a2a_routes::A2ARoutes;
+use crate::activity_routes::ActivityRoutes;
+use crate::analysis_settings_routes::AnalysisSettingsRoutes;
 use crate::api_key_routes::ApiKeyRoutes;
 use crate::auth::{AuthManager, AuthResult, McpAuthMiddleware};
 use crate::config::FitnessConfig;
 use crate::constants::{errors::*, json_fields::*, protocol, protocol::*, tools::*};
 use crate::dashboard_routes::DashboardRoutes;
 use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::digests::sinks::create_sinks;
+use crate::digests::{DigestScheduler, DigestSchedulerConfig};
+use crate::intelligence::activity_query::{self, ActivityQuery};
+use crate::intelligence::adjusted_pace::{self, AdjustedPace};
+use crate::intelligence::anomalies;
+use crate::intelligence::comparison::{self, ComparisonRequest};
+use crate::intelligence::digest::{self, DigestPeriod, DigestRequest};
+use crate::intelligence::gear::{self, GearUpdate, NewGear};
 use crate::intelligence::insights::ActivityContext;
+use crate::intelligence::patterns;
+use crate::intelligence::performance_analyzer::{
+    AdvancedPerformanceAnalyzer, PerformanceAnalyzerTrait,
+};
+use crate::intelligence::readiness;
+use crate::intelligence::routes;
+use crate::intelligence::settings::{self, AnalysisSettingsUpdate};
+use crate::intelligence::streaks::{self, StreakRequest};
+use crate::intelligence::thresholds;
 use crate::intelligence::weather::WeatherService;
-use crate::intelligence::ActivityAnalyzer;
+use crate::intelligence::wellness;
+use crate::intelligence::workload;
+use crate::intelligence::{
+    ActivityAnalyzer, AdvancedRecommendationEngine, RecommendationEngineTrait, TimeFrame,
+    TrainingRecommendation, UserFitnessProfile, WeatherProvenance,
+};
 use crate::mcp::schema::InitializeResponse;
-use crate::models::AuthRequest;
-use crate::providers::{create_provider, AuthData, FitnessProvider};
+use crate::models::{Activity, AuthRequest};
+use crate::oauth::manager::OAuthManager;
+use crate::oauth::refresher::{TokenRefresher, TokenRefresherConfig};
+use crate::oauth::OAuthError;
+use crate::providers::connections::ProviderConnections;
+use crate::providers::manual::{
+    ManualActivityProvider, ManualActivityRequest, ManualActivityUpdate, MANUAL_PROVIDER,
+};
+use crate::providers::merge::{all_provider_activities, MergedProvider, ALL_PROVIDERS};
+use crate::providers::rate_budget::{ProviderRateLimited, RequestContext};
+use crate::providers::FitnessProvider;
 use crate::routes::{AuthRoutes, LoginRequest, OAuthRoutes, RefreshTokenRequest, RegisterRequest};
 use crate::security::SecurityConfig;
+use crate::webhook_routes::{StravaWebhookEvent, SubscriptionVerification, WebhookRoutes};
 use crate::websocket::WebSocketManager;
 
 use anyhow::Result;
@@ -142,6 +176,10 @@ impl MultiTenantMcpServer {
         let api_key_routes = ApiKeyRoutes::new((*database).clone(), (*auth_manager).clone());
         let dashboard_routes = DashboardRoutes::new((*database).clone(), (*auth_manager).clone());
         let a2a_routes = A2ARoutes::new(database.clone(), auth_manager.clone(), config.clone());
+        let webhook_routes = WebhookRoutes::new(database.clone(), config.clone());
+        let activity_routes = ActivityRoutes::new(database.clone(), auth_manager.clone());
+        let analysis_settings_routes =
+            AnalysisSettingsRoutes::new(database.clone(), auth_manager.clone());
 
         // CORS configuration
         let cors = warp::cors()
@@ -579,9 +617,12 @@ impl MultiTenantMcpServer {
                 }
             });
 
-        // A2A Agent Card endpoint
+        // A2A Agent Card endpoint (also served at the standard well-known path)
         let a2a_agent_card = warp::path("a2a")
             .and(warp::path("agent-card"))
+            .or(warp::path(".well-known").and(warp::path("agent.json")))
+            .unify()
+            .and(warp::path::end())
             .and(warp::get())
             .and_then({
                 let a2a_routes = a2a_routes.clone();
@@ -627,6 +668,7 @@ impl MultiTenantMcpServer {
         // A2A Client Registration endpoint
         let a2a_register_client = warp::path("a2a")
             .and(warp::path("clients"))
+            .and(warp::path::end())
             .and(warp::post())
             .and(warp::header::optional::<String>("authorization"))
             .and(warp::body::json())
@@ -652,6 +694,7 @@ impl MultiTenantMcpServer {
         // A2A List Clients endpoint
         let a2a_list_clients = warp::path("a2a")
             .and(warp::path("clients"))
+            .and(warp::path::end())
             .and(warp::get())
             .and(warp::header::optional::<String>("authorization"))
             .and_then({
@@ -766,12 +809,339 @@ impl MultiTenantMcpServer {
                 }
             });
 
+        // A2A Remote Agent registration endpoint
+        let a2a_register_remote_agent = warp::path("a2a")
+            .and(warp::path("remote-agents"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::header::optional::<String>("authorization"))
+            .and(warp::body::json())
+            .and_then({
+                let a2a_routes = a2a_routes.clone();
+                move |auth_header: Option<String>,
+                      request: crate::a2a::remote::RemoteAgentRegistrationRequest| {
+                    let a2a_routes = a2a_routes.clone();
+                    async move {
+                        match a2a_routes
+                            .register_remote_agent(auth_header.as_deref(), request)
+                            .await
+                        {
+                            Ok(agent) => Ok(warp::reply::json(&agent)),
+                            Err(e) => {
+                                let error = serde_json::json!({"error": e.to_string()});
+                                Err(warp::reject::custom(ApiError(error)))
+                            }
+                        }
+                    }
+                }
+            });
+
+        // A2A List Remote Agents endpoint
+        let a2a_list_remote_agents = warp::path("a2a")
+            .and(warp::path("remote-agents"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::header::optional::<String>("authorization"))
+            .and_then({
+                let a2a_routes = a2a_routes.clone();
+                move |auth_header: Option<String>| {
+                    let a2a_routes = a2a_routes.clone();
+                    async move {
+                        match a2a_routes.list_remote_agents(auth_header.as_deref()).await {
+                            Ok(agents) => Ok(warp::reply::json(&agents)),
+                            Err(e) => {
+                                let error = serde_json::json!({"error": e.to_string()});
+                                Err(warp::reject::custom(ApiError(error)))
+                            }
+                        }
+                    }
+                }
+            });
+
+        // A2A Remove Remote Agent endpoint
+        let a2a_remove_remote_agent = warp::path("a2a")
+            .and(warp::path("remote-agents"))
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::delete())
+            .and(warp::header::optional::<String>("authorization"))
+            .and_then({
+                let a2a_routes = a2a_routes.clone();
+                move |agent_id: String, auth_header: Option<String>| {
+                    let a2a_routes = a2a_routes.clone();
+                    async move {
+                        match a2a_routes
+                            .remove_remote_agent(auth_header.as_deref(), &agent_id)
+                            .await
+                        {
+                            Ok(()) => Ok(warp::reply::json(
+                                &serde_json::json!({"removed": true, "agent_id": agent_id}),
+                            )),
+                            Err(e) => {
+                                let error = serde_json::json!({"error": e.to_string()});
+                                Err(warp::reject::custom(ApiError(error)))
+                            }
+                        }
+                    }
+                }
+            });
+
+        // Manual activity endpoints
+        let log_activity = warp::path("api")
+            .and(warp::path("activities"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::header::optional::<String>("authorization"))
+            .and(warp::body::json())
+            .and_then({
+                let activity_routes = activity_routes.clone();
+                move |auth_header: Option<String>, request: ManualActivityRequest| {
+                    let activity_routes = activity_routes.clone();
+                    async move {
+                        match activity_routes
+                            .log_activity(auth_header.as_deref(), request)
+                            .await
+                        {
+                            Ok(activity) => Ok(warp::reply::json(&activity)),
+                            Err(e) => {
+                                let error = serde_json::json!({"error": e.to_string()});
+                                Err(warp::reject::custom(ApiError(error)))
+                            }
+                        }
+                    }
+                }
+            });
+
+        let list_activities = warp::path("api")
+            .and(warp::path("activities"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::header::optional::<String>("authorization"))
+            .and(warp::query::<std::collections::HashMap<String, String>>())
+            .and_then({
+                let activity_routes = activity_routes.clone();
+                move |auth_header: Option<String>,
+                      params: std::collections::HashMap<String, String>| {
+                    let activity_routes = activity_routes.clone();
+                    async move {
+                        let limit = params.get("limit").and_then(|l| l.parse::<u32>().ok());
+                        match activity_routes
+                            .list_activities(auth_header.as_deref(), limit)
+                            .await
+                        {
+                            Ok(activities) => Ok(warp::reply::json(&activities)),
+                            Err(e) => {
+                                let error = serde_json::json!({"error": e.to_string()});
+                                Err(warp::reject::custom(ApiError(error)))
+                            }
+                        }
+                    }
+                }
+            });
+
+        let update_activity = warp::path("api")
+            .and(warp::path("activities"))
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::put())
+            .and(warp::header::optional::<String>("authorization"))
+            .and(warp::body::json())
+            .and_then({
+                let activity_routes = activity_routes.clone();
+                move |activity_id: String,
+                      auth_header: Option<String>,
+                      update: ManualActivityUpdate| {
+                    let activity_routes = activity_routes.clone();
+                    async move {
+                        match activity_routes
+                            .update_activity(auth_header.as_deref(), &activity_id, update)
+                            .await
+                        {
+                            Ok(activity) => Ok(warp::reply::json(&activity)),
+                            Err(e) => {
+                                let error = serde_json::json!({"error": e.to_string()});
+                                Err(warp::reject::custom(ApiError(error)))
+                            }
+                        }
+                    }
+                }
+            });
+
+        let delete_activity = warp::path("api")
+            .and(warp::path("activities"))
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::delete())
+            .and(warp::header::optional::<String>("authorization"))
+            .and_then({
+                let activity_routes = activity_routes.clone();
+                move |activity_id: String, auth_header: Option<String>| {
+                    let activity_routes = activity_routes.clone();
+                    async move {
+                        match activity_routes
+                            .delete_activity(auth_header.as_deref(), &activity_id)
+                            .await
+                        {
+                            Ok(()) => Ok(warp::reply::json(
+                                &serde_json::json!({"deleted": true, "activity_id": activity_id}),
+                            )),
+                            Err(e) => {
+                                let error = serde_json::json!({"error": e.to_string()});
+                                Err(warp::reject::custom(ApiError(error)))
+                            }
+                        }
+                    }
+                }
+            });
+
+        // Analysis settings endpoints
+        let get_analysis_settings = warp::path("api")
+            .and(warp::path("analysis-settings"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::header::optional::<String>("authorization"))
+            .and_then({
+                let analysis_settings_routes = analysis_settings_routes.clone();
+                move |auth_header: Option<String>| {
+                    let analysis_settings_routes = analysis_settings_routes.clone();
+                    async move {
+                        match analysis_settings_routes
+                            .get_settings(auth_header.as_deref())
+                            .await
+                        {
+                            Ok(settings) => Ok(warp::reply::json(&settings)),
+                            Err(e) => {
+                                let error = serde_json::json!({"error": e.to_string()});
+                                Err(warp::reject::custom(ApiError(error)))
+                            }
+                        }
+                    }
+                }
+            });
+
+        let update_analysis_settings = warp::path("api")
+            .and(warp::path("analysis-settings"))
+            .and(warp::path::end())
+            .and(warp::put())
+            .and(warp::header::optional::<String>("authorization"))
+            .and(warp::body::json())
+            .and_then({
+                let analysis_settings_routes = analysis_settings_routes.clone();
+                move |auth_header: Option<String>, update: AnalysisSettingsUpdate| {
+                    let analysis_settings_routes = analysis_settings_routes.clone();
+                    async move {
+                        match analysis_settings_routes
+                            .update_settings(auth_header.as_deref(), update)
+                            .await
+                        {
+                            Ok(settings) => Ok(warp::reply::json(&settings)),
+                            Err(e) => {
+                                let error = serde_json::json!({"error": e.to_string()});
+                                Err(warp::reject::custom(ApiError(error)))
+                            }
+                        }
+                    }
+                }
+            });
+
+        // Strava webhook subscription verification
+        let strava_webhook_verify = warp::path("webhooks")
+            .and(warp::path("strava"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::query::<SubscriptionVerification>())
+            .and_then({
+                let webhook_routes = webhook_routes.clone();
+                move |params: SubscriptionVerification| {
+                    let webhook_routes = webhook_routes.clone();
+                    async move {
+                        match webhook_routes.verify_subscription(&params) {
+                            Ok(challenge) => Ok(warp::reply::json(&challenge)),
+                            Err(e) => {
+                                warn!("Strava webhook verification rejected: {}", e);
+                                let error = serde_json::json!({"error": e.to_string()});
+                                Err(warp::reject::custom(ApiError(error)))
+                            }
+                        }
+                    }
+                }
+            });
+
+        // Strava webhook events - acknowledged immediately, processed in the background
+        let strava_webhook_event = warp::path("webhooks")
+            .and(warp::path("strava"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::body::json())
+            .and_then({
+                let webhook_routes = webhook_routes.clone();
+                move |event: StravaWebhookEvent| {
+                    let webhook_routes = webhook_routes.clone();
+                    async move {
+                        if let Err(e) = webhook_routes.authenticate_event(&event) {
+                            warn!("Strava webhook event rejected: {}", e);
+                            return Ok::<_, warp::Rejection>(warp::reply::with_status(
+                                warp::reply::json(&serde_json::json!({"error": e.to_string()})),
+                                warp::http::StatusCode::FORBIDDEN,
+                            ));
+                        }
+                        // Strava redelivers events that aren't acknowledged
+                        let Some(permit) = webhook_routes.try_admit_event() else {
+                            return Ok(warp::reply::with_status(
+                                warp::reply::json(&serde_json::json!({"status": "busy"})),
+                                warp::http::StatusCode::SERVICE_UNAVAILABLE,
+                            ));
+                        };
+                        tokio::spawn(async move {
+                            match webhook_routes.handle_strava_event(event).await {
+                                Ok(outcome) => info!("Strava webhook processed: {:?}", outcome),
+                                Err(e) => warn!("Strava webhook processing failed: {}", e),
+                            }
+                            drop(permit);
+                        });
+                        Ok(warp::reply::with_status(
+                            warp::reply::json(&serde_json::json!({"status": "received"})),
+                            warp::http::StatusCode::OK,
+                        ))
+                    }
+                }
+            });
+
         // WebSocket endpoint
         let websocket_route = websocket_manager.websocket_filter();
 
         // Start periodic WebSocket updates
         websocket_manager.start_periodic_updates();
 
+        // Renew provider tokens before they expire and flag revoked connections
+        TokenRefresher::new(
+            database.clone(),
+            Arc::new(OAuthManager::from_config(database.clone(), &config.oauth)),
+            TokenRefresherConfig::from_env(),
+        )
+        .with_websocket(websocket_manager.clone())
+        .start();
+
+        // Send weekly, monthly and yearly digests once each period is over
+        let digest_config = DigestSchedulerConfig::from_env();
+        if digest_config.enabled() {
+            match create_sinks(&digest_config) {
+                Ok(sinks) => {
+                    info!(
+                        "Digest delivery enabled: {}",
+                        digest_config.sinks.join(", ")
+                    );
+                    DigestScheduler::new(
+                        ProviderConnections::from_config(database.clone(), &config),
+                        digest_config,
+                        sinks,
+                    )
+                    .start();
+                }
+                Err(e) => warn!("Digest delivery disabled: {}", e),
+            }
+        }
+
         // Create security headers filter
         let security_headers_filter = warp::reply::with::headers({
             let headers = security_config.to_headers();
@@ -795,6 +1165,13 @@ impl MultiTenantMcpServer {
 
         let api_key_routes = list_api_keys.or(deactivate_api_key).or(get_api_key_usage);
 
+        let activity_routes = log_activity
+            .or(list_activities)
+            .or(update_activity)
+            .or(delete_activity)
+            .or(get_analysis_settings)
+            .or(update_analysis_settings);
+
         let dashboard_routes = dashboard_overview
             .or(dashboard_analytics)
             .or(dashboard_rate_limits)
@@ -809,13 +1186,19 @@ impl MultiTenantMcpServer {
             .or(a2a_client_usage)
             .or(a2a_client_rate_limit)
             .or(a2a_auth)
-            .or(a2a_execute);
+            .or(a2a_execute)
+            .or(a2a_register_remote_agent)
+            .or(a2a_list_remote_agents)
+            .or(a2a_remove_remote_agent);
 
         // HTTP routes with security headers (exclude WebSocket)
         let http_routes = auth_routes
             .or(api_key_routes)
+            .or(activity_routes)
             .or(dashboard_routes)
             .or(a2a_routes)
+            .or(strava_webhook_verify)
+            .or(strava_webhook_event)
             .or(health)
             .with(cors.clone())
             .with(security_headers_filter);
@@ -848,6 +1231,7 @@ impl MultiTenantMcpServer {
             let auth_manager = self.auth_manager.clone();
             let auth_middleware = self.auth_middleware.clone();
             let user_providers = self.user_providers.clone();
+            let config = self.config.clone();
 
             tokio::spawn(async move {
                 let (reader, mut writer) = socket.into_split();
@@ -862,6 +1246,7 @@ impl MultiTenantMcpServer {
                             &auth_manager,
                             &auth_middleware,
                             &user_providers,
+                            &config,
                         )
                         .await;
 
@@ -888,6 +1273,7 @@ impl MultiTenantMcpServer {
         auth_manager: &Arc<AuthManager>,
         auth_middleware: &Arc<McpAuthMiddleware>,
         user_providers: &UserProviderStorage,
+        config: &Arc<crate::config::environment::ServerConfig>,
     ) -> McpResponse {
         match request.method.as_str() {
             "initialize" => {
@@ -895,7 +1281,8 @@ impl MultiTenantMcpServer {
                     protocol::mcp_protocol_version(),
                     protocol::server_name_multitenant(),
                     SERVER_VERSION.to_string(),
-                );
+                )
+                .with_resources();
 
                 McpResponse {
                     jsonrpc: JSONRPC_VERSION.to_string(),
@@ -947,47 +1334,30 @@ impl MultiTenantMcpServer {
                             auth_result,
                             database,
                             user_providers,
+                            config,
                         )
                         .await
                     }
                     Err(e) => {
                         warn!("MCP tool call authentication failed: {}", e);
-
-                        // Determine specific error code based on error message
-                        let error_message = e.to_string();
-                        let (error_code, error_msg) = if error_message.contains("JWT token expired")
-                        {
-                            (
-                                crate::constants::errors::ERROR_TOKEN_EXPIRED,
-                                crate::constants::errors::MSG_TOKEN_EXPIRED,
-                            )
-                        } else if error_message.contains("JWT token signature is invalid") {
-                            (
-                                crate::constants::errors::ERROR_TOKEN_INVALID,
-                                crate::constants::errors::MSG_TOKEN_INVALID,
-                            )
-                        } else if error_message.contains("JWT token is malformed") {
-                            (
-                                crate::constants::errors::ERROR_TOKEN_MALFORMED,
-                                crate::constants::errors::MSG_TOKEN_MALFORMED,
-                            )
-                        } else {
-                            (ERROR_UNAUTHORIZED, "Authentication required")
-                        };
-
-                        McpResponse {
-                            jsonrpc: JSONRPC_VERSION.to_string(),
-                            result: None,
-                            error: Some(McpError {
-                                code: error_code,
-                                message: error_msg.to_string(),
-                                data: Some(serde_json::json!({
-                                    "detailed_error": error_message,
-                                    "authentication_failed": true
-                                })),
-                            }),
-                            id: request.id,
-                        }
+                        Self::authentication_failed_response(&e, request.id)
+                    }
+                }
+            }
+            "resources/list" | "resources/read" => {
+                match auth_middleware
+                    .authenticate_request(request.auth_token.as_deref())
+                    .await
+                {
+                    Ok(auth_result) => {
+                        let _ = database.update_last_active(auth_result.user_id).await;
+                        let connections =
+                            ProviderConnections::from_config(database.clone(), config);
+                        Self::handle_resources(request, auth_result.user_id, &connections).await
+                    }
+                    Err(e) => {
+                        warn!("MCP resource authentication failed: {}", e);
+                        Self::authentication_failed_response(&e, request.id)
                     }
                 }
             }
@@ -1034,6 +1404,126 @@ impl MultiTenantMcpServer {
         }
     }
 
+    /// Error response for a request whose credentials were rejected
+    fn authentication_failed_response(error: &anyhow::Error, id: Value) -> McpResponse {
+        // Determine specific error code based on error message
+        let error_message = error.to_string();
+        let (error_code, error_msg) = if error_message.contains("JWT token expired") {
+            (
+                crate::constants::errors::ERROR_TOKEN_EXPIRED,
+                crate::constants::errors::MSG_TOKEN_EXPIRED,
+            )
+        } else if error_message.contains("JWT token signature is invalid") {
+            (
+                crate::constants::errors::ERROR_TOKEN_INVALID,
+                crate::constants::errors::MSG_TOKEN_INVALID,
+            )
+        } else if error_message.contains("JWT token is malformed") {
+            (
+                crate::constants::errors::ERROR_TOKEN_MALFORMED,
+                crate::constants::errors::MSG_TOKEN_MALFORMED,
+            )
+        } else {
+            (ERROR_UNAUTHORIZED, "Authentication required")
+        };
+
+        McpResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            result: None,
+            error: Some(McpError {
+                code: error_code,
+                message: error_msg.to_string(),
+                data: Some(serde_json::json!({
+                    "detailed_error": error_message,
+                    "authentication_failed": true
+                })),
+            }),
+            id,
+        }
+    }
+
+    /// Handle `resources/list` and `resources/read` for the user's training digests
+    ///
+    /// Every stored digest is listed, along with a `latest` alias per period
+    /// that is built on first read.
+    async fn handle_resources(
+        request: McpRequest,
+        user_id: Uuid,
+        connections: &ProviderConnections,
+    ) -> McpResponse {
+        let database = connections.database();
+        let result = if request.method == "resources/list" {
+            let stored = match database
+                .list_digests(user_id, None, digest::RESOURCE_LIST_LIMIT)
+                .await
+            {
+                Ok(stored) => stored,
+                Err(e) => return Self::internal_error_response(e, request.id),
+            };
+            let latest = DigestPeriod::ALL.iter().map(|period| {
+                serde_json::json!({
+                    "uri": digest::resource_uri(*period, None),
+                    "name": format!("Latest {} digest", period.as_str()),
+                    "description": format!(
+                        "Training digest of the last finished {} period",
+                        period.as_str()
+                    ),
+                    "mimeType": "text/markdown",
+                })
+            });
+            let resources: Vec<Value> = latest
+                .chain(stored.iter().map(|stored| {
+                    let period =
+                        DigestPeriod::parse(&stored.period).unwrap_or(DigestPeriod::Weekly);
+                    serde_json::json!({
+                        "uri": digest::resource_uri(period, Some(stored.period_start)),
+                        "name": stored.title,
+                        "description": format!("{} training digest", stored.period),
+                        "mimeType": "text/markdown",
+                    })
+                }))
+                .collect();
+            serde_json::json!({ "resources": resources })
+        } else {
+            let params = request.params.unwrap_or_default();
+            let uri = params["uri"].as_str().unwrap_or_default();
+            let (period, date) = match digest::parse_resource_uri(uri) {
+                Ok(parsed) => parsed,
+                Err(e) => return Self::invalid_params_response(e, request.id),
+            };
+            let request_args = DigestRequest {
+                period,
+                date,
+                refresh: false,
+            };
+            let digest = match digest::requested_digest(connections, user_id, &request_args).await {
+                Ok(digest) => digest,
+                Err(e) => return Self::invalid_params_response(e, request.id),
+            };
+            serde_json::json!({
+                "contents": [
+                    {
+                        "uri": uri,
+                        "mimeType": "text/markdown",
+                        "text": digest["summary"],
+                    },
+                    {
+                        "uri": uri,
+                        "mimeType": "application/json",
+                        "text": digest.to_string(),
+                    }
+                ]
+            })
+        };
+
+        McpResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            result: Some(result),
+            error: None,
+            id: request.id,
+        }
+    }
+
     /// Handle authenticated tool call with user context and rate limiting
     #[allow(clippy::type_complexity)]
     async fn handle_authenticated_tool_call(
@@ -1041,11 +1531,13 @@ impl MultiTenantMcpServer {
         auth_result: AuthResult,
         database: &Arc<Database>,
         user_providers: &UserProviderStorage,
+        config: &Arc<crate::config::environment::ServerConfig>,
     ) -> McpResponse {
         let params = request.params.unwrap_or_default();
         let tool_name = params["name"].as_str().unwrap_or("");
         let args = &params["arguments"];
         let user_id = auth_result.user_id;
+        let connections = ProviderConnections::from_config(database.clone(), config);
 
         tracing::info!(
             "Executing tool call: {} for user: {} using {} authentication",
@@ -1084,10 +1576,35 @@ impl MultiTenantMcpServer {
             | GENERATE_RECOMMENDATIONS
             | ANALYZE_TRAINING_LOAD
             | DETECT_PATTERNS
-            | ANALYZE_PERFORMANCE_TRENDS => {
+            | ANALYZE_PERFORMANCE_TRENDS
+            | LIST_REMOTE_AGENTS
+            | DELEGATE_TO_AGENT
+            | GET_DELEGATED_TASK
+            | CANCEL_DELEGATED_TASK
+            | LOG_WELLNESS
+            | GET_READINESS
+            | LOG_ACTIVITY
+            | UPDATE_ACTIVITY
+            | DELETE_ACTIVITY
+            | GET_GEAR
+            | GEAR_USAGE_REPORT
+            | ADD_GEAR
+            | UPDATE_GEAR
+            | GET_ATHLETE_THRESHOLDS
+            | UPDATE_ANALYSIS_SETTINGS
+            | LIST_MY_ROUTES
+            | ROUTE_HISTORY
+            | DETECT_ANOMALIES
+            | QUERY_ACTIVITIES
+            | GET_STREAKS
+            | GET_DIGEST => {
                 let start_time = std::time::Instant::now();
                 let response = Self::execute_tool_call_without_provider(
-                    tool_name, args, request.id, user_id, database,
+                    tool_name,
+                    args,
+                    request.id,
+                    user_id,
+                    &connections,
                 )
                 .await;
 
@@ -1116,7 +1633,11 @@ impl MultiTenantMcpServer {
                     CALCULATE_METRICS,
                     COMPARE_ACTIVITIES,
                     PREDICT_PERFORMANCE,
+                    GET_SLEEP,
+                    GET_DAILY_WELLNESS,
+                    GET_RESTING_HR_TREND,
                 ];
+                let wellness_tools = [GET_SLEEP, GET_DAILY_WELLNESS, GET_RESTING_HR_TREND];
 
                 if !known_provider_tools.contains(&tool_name) {
                     // Unknown tool
@@ -1132,26 +1653,28 @@ impl MultiTenantMcpServer {
                     };
                 }
 
-                // For fitness data tools, we need a provider
-                let provider_name = args[PROVIDER].as_str().unwrap_or("");
+                // Without an explicit provider, activity tools see every
+                // connected provider merged into one view
+                let default_provider = if wellness_tools.contains(&tool_name) {
+                    "fitbit"
+                } else {
+                    ALL_PROVIDERS
+                };
+                let provider_name = args[PROVIDER].as_str().unwrap_or(default_provider);
 
                 // Get or create user-specific provider
                 let provider_result =
-                    Self::get_user_provider(user_id, provider_name, database, user_providers).await;
+                    Self::get_user_provider(user_id, provider_name, &connections, user_providers)
+                        .await;
 
                 let provider = match provider_result {
                     Ok(provider) => provider,
                     Err(e) => {
-                        return McpResponse {
-                            jsonrpc: JSONRPC_VERSION.to_string(),
-                            result: None,
-                            error: Some(McpError {
-                                code: ERROR_INTERNAL_ERROR,
-                                message: format!("Provider authentication failed: {}", e),
-                                data: None,
-                            }),
-                            id: request.id,
-                        };
+                        return Self::provider_error_response(
+                            request.id,
+                            "Provider authentication failed",
+                            &e,
+                        );
                     }
                 };
 
@@ -1188,9 +1711,27 @@ impl MultiTenantMcpServer {
     async fn get_user_provider(
         user_id: Uuid,
         provider_name: &str,
-        database: &Arc<Database>,
+        connections: &ProviderConnections,
         user_providers: &UserProviderStorage,
     ) -> Result<Box<dyn FitnessProvider>> {
+        let database = connections.database();
+        let api = connections.api();
+        // Manual activities need no connection
+        if provider_name == MANUAL_PROVIDER {
+            return Ok(Box::new(ManualActivityProvider::new(
+                database.clone(),
+                user_id,
+                None,
+            )));
+        }
+
+        // Every connected provider, with duplicate workouts merged
+        if provider_name == ALL_PROVIDERS {
+            let mut provider = MergedProvider::for_user(connections, user_id).await;
+            provider.set_request_context(RequestContext::interactive(user_id));
+            return Ok(Box::new(provider));
+        }
+
         let user_key = user_id.to_string();
 
         // Check if provider already exists for this user
@@ -1204,34 +1745,33 @@ impl MultiTenantMcpServer {
             }
         }
 
-        // Create new provider instance for user
-        let mut provider = create_provider(provider_name)?;
-
-        // Get user's decrypted token for this provider
-        let token = match provider_name {
-            "strava" => database.get_strava_token(user_id).await?,
-            "fitbit" => database.get_fitbit_token(user_id).await?,
-            _ => None,
-        };
-
-        if let Some(decrypted_token) = token {
-            // Authenticate provider with user's token
-            let auth_data = AuthData::OAuth2 {
-                client_id: crate::constants::env_config::strava_client_id().unwrap_or_default(),
-                client_secret: crate::constants::env_config::strava_client_secret()
-                    .unwrap_or_default(),
-                access_token: Some(decrypted_token.access_token),
-                refresh_token: Some(decrypted_token.refresh_token),
-            };
-
-            provider.authenticate(auth_data).await?;
-        } else {
-            return Err(anyhow::anyhow!(
-                "No valid token found for provider {}",
-                provider_name
-            ));
+        // A revoked connection can't be used until the user reconnects
+        if let Some(connection) = database
+            .get_provider_connection(user_id, provider_name)
+            .await?
+        {
+            if connection.needs_reauth {
+                return Err(OAuthError::ReauthorizationRequired(provider_name.to_string()).into());
+            }
         }
 
+        // Create new provider instance authenticated with the user's stored token
+        let token = database
+            .get_provider_token(user_id, provider_name)
+            .await?
+            .ok_or_else(|| {
+                anyhow::anyhow!("No valid token found for provider {}", provider_name)
+            })?;
+        let registry = crate::providers::registry();
+        let provider = registry
+            .create_authenticated(
+                provider_name,
+                api,
+                &token.access_token,
+                &token.refresh_token,
+            )
+            .await?;
+
         // Store provider for reuse
         {
             let mut providers_write = user_providers.write().await;
@@ -1242,19 +1782,50 @@ impl MultiTenantMcpServer {
         }
 
         // Return a new instance (simplified for now)
-        let mut new_provider = create_provider(provider_name)?;
-        if let Some(decrypted_token) = database.get_strava_token(user_id).await? {
-            let auth_data = AuthData::OAuth2 {
-                client_id: crate::constants::env_config::strava_client_id().unwrap_or_default(),
-                client_secret: crate::constants::env_config::strava_client_secret()
-                    .unwrap_or_default(),
-                access_token: Some(decrypted_token.access_token),
-                refresh_token: Some(decrypted_token.refresh_token),
-            };
-            new_provider.authenticate(auth_data).await?;
+        let mut provider = registry
+            .create_authenticated(
+                provider_name,
+                api,
+                &token.access_token,
+                &token.refresh_token,
+            )
+            .await?;
+        provider.set_request_context(RequestContext::interactive(user_id));
+
+        // Manually logged activities count alongside provider data
+        Ok(Box::new(ManualActivityProvider::new(
+            database.clone(),
+            user_id,
+            Some(provider),
+        )))
+    }
+
+    /// JSON-RPC invalid params error for a tool argument problem
+    fn invalid_params_response(message: impl std::fmt::Display, id: Value) -> McpResponse {
+        McpResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            result: None,
+            error: Some(McpError {
+                code: ERROR_INVALID_PARAMS,
+                message: message.to_string(),
+                data: None,
+            }),
+            id,
         }
+    }
 
-        Ok(new_provider)
+    /// JSON-RPC internal error for a failed tool operation
+    fn internal_error_response(message: impl std::fmt::Display, id: Value) -> McpResponse {
+        McpResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            result: None,
+            error: Some(McpError {
+                code: ERROR_INTERNAL_ERROR,
+                message: message.to_string(),
+                data: None,
+            }),
+            id,
+        }
     }
 
     /// Handle connect_strava tool call
@@ -1384,8 +1955,9 @@ impl MultiTenantMcpServer {
         args: &Value,
         id: Value,
         user_id: Uuid,
-        database: &Arc<Database>,
+        connections: &ProviderConnections,
     ) -> McpResponse {
+        let database = connections.database();
         let result = match tool_name {
             SET_GOAL => {
                 let goal_data = args.clone();
@@ -1524,84 +2096,615 @@ impl MultiTenantMcpServer {
                 Some(response)
             }
             GENERATE_RECOMMENDATIONS => {
-                let response = serde_json::json!({
-                    "training_recommendations": [
-                        {
-                            "type": "intensity",
-                            "title": "Add Interval Training",
-                            "description": "Include 1-2 high-intensity interval sessions per week",
-                            "priority": "medium",
-                            "rationale": "To improve speed and cardiovascular fitness"
-                        },
-                        {
-                            "type": "volume",
-                            "title": "Gradual Volume Increase",
-                            "description": "Increase weekly distance by 10% each week",
-                            "priority": "high",
-                            "rationale": "Based on your current training load"
-                        },
-                        {
-                            "type": "recovery",
-                            "title": "Include Rest Days",
-                            "description": "Schedule at least one complete rest day per week",
-                            "priority": "high",
-                            "rationale": "Essential for adaptation and injury prevention"
+                let engine = AdvancedRecommendationEngine::new();
+                let today = Utc::now().date_naive();
+                let wellness = database
+                    .get_daily_wellness(
+                        user_id,
+                        "fitbit",
+                        today - chrono::Duration::days(13),
+                        today,
+                    )
+                    .await
+                    .unwrap_or_default();
+                let recovery = engine
+                    .generate_wellness_recommendations(&wellness)
+                    .await
+                    .unwrap_or_default();
+                let readiness = readiness::readiness_for_user(connections, user_id, today)
+                    .await
+                    .ok()
+                    .flatten();
+                let activities =
+                    match all_provider_activities(connections, user_id, workload::ACTIVITY_LIMIT)
+                        .await
+                    {
+                        Ok(activities) => activities,
+                        Err(e) => {
+                            return Self::provider_error_response(
+                                id,
+                                "Failed to get activities",
+                                &e,
+                            )
                         }
-                    ]
-                });
-                Some(response)
+                    };
+                let workload = workload::workload_for_user(database, user_id, &activities).await;
+                let profile = UserFitnessProfile::with_defaults(
+                    user_id.to_string(),
+                    workload.injury_history.clone(),
+                );
+
+                let mut training =
+                    match engine.generate_recommendations(&profile, &activities).await {
+                        Ok(training) => training,
+                        Err(e) => {
+                            return McpResponse {
+                                jsonrpc: JSONRPC_VERSION.to_string(),
+                                result: None,
+                                error: Some(McpError {
+                                    code: ERROR_INTERNAL_ERROR,
+                                    message: format!("Failed to generate recommendations: {}", e),
+                                    data: None,
+                                }),
+                                id,
+                            };
+                        }
+                    };
+                // Poor readiness downgrades today's hard sessions
+                if let Some(readiness) = &readiness {
+                    training = engine.apply_readiness(training, readiness);
+                }
+                // Rapid load increases hold back volume advice
+                training = engine.apply_workload_risk(training, &workload);
+
+                Some(serde_json::json!({
+                    "training_recommendations": recommendations_json(&training),
+                    "recovery_recommendations": recovery,
+                    "activities_analyzed": activities.len(),
+                    "wellness_days_analyzed": wellness.len(),
+                    "readiness": readiness,
+                    "injury_risk": workload.overall_risk,
+                }))
+            }
+            LOG_ACTIVITY => {
+                let activity = match serde_json::from_value::<ManualActivityRequest>(args.clone())
+                    .map_err(anyhow::Error::from)
+                    .and_then(ManualActivityRequest::into_activity)
+                {
+                    Ok(activity) => activity,
+                    Err(e) => return Self::invalid_params_response(e, id),
+                };
+                match database.create_manual_activity(user_id, &activity).await {
+                    Ok(()) => Some(serde_json::json!({ "activity": activity })),
+                    Err(e) => {
+                        return McpResponse {
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                            result: None,
+                            error: Some(McpError {
+                                code: ERROR_INTERNAL_ERROR,
+                                message: format!("Failed to log activity: {}", e),
+                                data: None,
+                            }),
+                            id,
+                        };
+                    }
+                }
+            }
+            UPDATE_ACTIVITY => {
+                let activity_id = args[ACTIVITY_ID].as_str().unwrap_or("");
+                let update = match serde_json::from_value::<ManualActivityUpdate>(args.clone()) {
+                    Ok(update) => update,
+                    Err(e) => return Self::invalid_params_response(e, id),
+                };
+                let mut activity = match database.get_manual_activity(user_id, activity_id).await {
+                    Ok(Some(activity)) => activity,
+                    Ok(None) => {
+                        return Self::invalid_params_response(
+                            format!("Manual activity '{}' not found", activity_id),
+                            id,
+                        );
+                    }
+                    Err(e) => {
+                        return McpResponse {
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                            result: None,
+                            error: Some(McpError {
+                                code: ERROR_INTERNAL_ERROR,
+                                message: format!("Failed to load activity: {}", e),
+                                data: None,
+                            }),
+                            id,
+                        };
+                    }
+                };
+                if let Err(e) = update.apply(&mut activity) {
+                    return Self::invalid_params_response(e, id);
+                }
+                match database.update_manual_activity(user_id, &activity).await {
+                    Ok(_) => Some(serde_json::json!({ "activity": activity })),
+                    Err(e) => {
+                        return McpResponse {
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                            result: None,
+                            error: Some(McpError {
+                                code: ERROR_INTERNAL_ERROR,
+                                message: format!("Failed to update activity: {}", e),
+                                data: None,
+                            }),
+                            id,
+                        };
+                    }
+                }
+            }
+            DELETE_ACTIVITY => {
+                let activity_id = args[ACTIVITY_ID].as_str().unwrap_or("");
+                match database.delete_manual_activity(user_id, activity_id).await {
+                    Ok(true) => Some(serde_json::json!({
+                        "deleted": true,
+                        "activity_id": activity_id,
+                    })),
+                    Ok(false) => {
+                        return Self::invalid_params_response(
+                            format!("Manual activity '{}' not found", activity_id),
+                            id,
+                        );
+                    }
+                    Err(e) => {
+                        return McpResponse {
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                            result: None,
+                            error: Some(McpError {
+                                code: ERROR_INTERNAL_ERROR,
+                                message: format!("Failed to delete activity: {}", e),
+                                data: None,
+                            }),
+                            id,
+                        };
+                    }
+                }
+            }
+            GET_GEAR | GEAR_USAGE_REPORT => {
+                let gear_type = match args
+                    .get("gear_type")
+                    .filter(|v| !v.is_null())
+                    .map(|v| serde_json::from_value(v.clone()))
+                    .transpose()
+                {
+                    Ok(gear_type) => gear_type,
+                    Err(e) => return Self::invalid_params_response(e, id),
+                };
+                let include_retired = args[INCLUDE_RETIRED].as_bool().unwrap_or(false);
+                match gear::gear_for_user(connections, user_id).await {
+                    Ok(all_gear) if tool_name == GEAR_USAGE_REPORT => {
+                        Some(gear::gear_usage_report(&all_gear))
+                    }
+                    Ok(all_gear) => Some(gear::gear_list_report(
+                        &all_gear,
+                        gear_type,
+                        include_retired,
+                    )),
+                    Err(e) => {
+                        return Self::internal_error_response(
+                            format!("Failed to load gear: {}", e),
+                            id,
+                        );
+                    }
+                }
+            }
+            ADD_GEAR => {
+                let new_gear = match serde_json::from_value::<NewGear>(args.clone())
+                    .map_err(anyhow::Error::from)
+                    .and_then(NewGear::into_gear)
+                {
+                    Ok(new_gear) => new_gear,
+                    Err(e) => return Self::invalid_params_response(e, id),
+                };
+                match database.upsert_gear(user_id, &new_gear).await {
+                    Ok(()) => Some(serde_json::json!({ "gear": gear::gear_summary(&new_gear) })),
+                    Err(e) => {
+                        return Self::internal_error_response(
+                            format!("Failed to add gear: {}", e),
+                            id,
+                        );
+                    }
+                }
+            }
+            UPDATE_GEAR => {
+                let gear_id = args[GEAR_ID].as_str().unwrap_or("");
+                let update = match serde_json::from_value::<GearUpdate>(args.clone()) {
+                    Ok(update) => update,
+                    Err(e) => return Self::invalid_params_response(e, id),
+                };
+                let mut stored = match database.get_gear(user_id, gear_id).await {
+                    Ok(Some(stored)) => stored,
+                    Ok(None) => {
+                        return Self::invalid_params_response(
+                            format!("Gear '{}' not found", gear_id),
+                            id,
+                        );
+                    }
+                    Err(e) => {
+                        return Self::internal_error_response(
+                            format!("Failed to load gear: {}", e),
+                            id,
+                        );
+                    }
+                };
+                if let Err(e) = update.apply(&mut stored) {
+                    return Self::invalid_params_response(e, id);
+                }
+                match database.upsert_gear(user_id, &stored).await {
+                    Ok(()) => Some(serde_json::json!({ "gear": gear::gear_summary(&stored) })),
+                    Err(e) => {
+                        return Self::internal_error_response(
+                            format!("Failed to update gear: {}", e),
+                            id,
+                        );
+                    }
+                }
+            }
+            GET_ATHLETE_THRESHOLDS => {
+                let refresh = args[REFRESH].as_bool().unwrap_or(false);
+                let include_history = args[INCLUDE_HISTORY].as_bool().unwrap_or(false);
+                match thresholds::thresholds_for_user(connections, user_id, refresh).await {
+                    Ok(stored) => {
+                        let config = settings::effective_config(database, user_id).await;
+                        Some(thresholds::thresholds_report(
+                            &stored,
+                            &config.zone_thresholds,
+                            include_history,
+                        ))
+                    }
+                    Err(e) => {
+                        return Self::internal_error_response(
+                            format!("Failed to estimate thresholds: {}", e),
+                            id,
+                        );
+                    }
+                }
+            }
+            UPDATE_ANALYSIS_SETTINGS => {
+                let patch = args
+                    .get(SETTINGS)
+                    .cloned()
+                    .unwrap_or_else(|| serde_json::json!({}));
+                let reset = args[RESET].as_bool().unwrap_or(false);
+                match settings::update_user_settings(database, user_id, &patch, reset).await {
+                    Ok(updated) => serde_json::to_value(updated).ok(),
+                    Err(e) => return Self::invalid_params_response(e, id),
+                }
+            }
+            LIST_MY_ROUTES => {
+                let limit = args[LIMIT].as_u64().unwrap_or(10) as usize;
+                match routes::routes_for_user(
+                    connections,
+                    user_id,
+                    args["sport_type"].as_str(),
+                    limit,
+                )
+                .await
+                {
+                    Ok(found) => Some(found),
+                    Err(e) => {
+                        return Self::internal_error_response(
+                            format!("Failed to list routes: {}", e),
+                            id,
+                        );
+                    }
+                }
+            }
+            ROUTE_HISTORY => {
+                let route_id = args[ROUTE_ID].as_str();
+                let activity_id = args[ACTIVITY_ID].as_str();
+                if route_id.is_none() && activity_id.is_none() {
+                    return Self::invalid_params_response("Provide route_id or activity_id", id);
+                }
+                match routes::route_history_for_user(connections, user_id, route_id, activity_id)
+                    .await
+                {
+                    Ok(history) => Some(history),
+                    Err(e) => return Self::invalid_params_response(e, id),
+                }
+            }
+            DETECT_ANOMALIES => {
+                let days = args[DAYS].as_i64().unwrap_or(anomalies::DEFAULT_DAYS);
+                match anomalies::anomalies_for_user(
+                    connections,
+                    user_id,
+                    args[ACTIVITY_ID].as_str(),
+                    days,
+                )
+                .await
+                {
+                    Ok(report) => Some(report),
+                    Err(e) => return Self::invalid_params_response(e, id),
+                }
+            }
+            QUERY_ACTIVITIES => {
+                let query = match ActivityQuery::from_args(args) {
+                    Ok(query) => query,
+                    Err(e) => return Self::invalid_params_response(e, id),
+                };
+                match activity_query::query_for_user(
+                    connections,
+                    user_id,
+                    args[PROVIDER].as_str(),
+                    &query,
+                )
+                .await
+                {
+                    Ok(result) => serde_json::to_value(result).ok(),
+                    Err(e) => return Self::invalid_params_response(e, id),
+                }
+            }
+            GET_STREAKS => {
+                let request = match StreakRequest::from_args(args) {
+                    Ok(request) => request,
+                    Err(e) => return Self::invalid_params_response(e, id),
+                };
+                match streaks::streaks_for_user(
+                    connections,
+                    user_id,
+                    args[PROVIDER].as_str(),
+                    &request,
+                )
+                .await
+                {
+                    Ok(report) => Some(report),
+                    Err(e) => return Self::invalid_params_response(e, id),
+                }
+            }
+            GET_DIGEST => {
+                let request = match DigestRequest::from_args(args) {
+                    Ok(request) => request,
+                    Err(e) => return Self::invalid_params_response(e, id),
+                };
+                match digest::requested_digest(connections, user_id, &request).await {
+                    Ok(result) => Some(result),
+                    Err(e) => return Self::invalid_params_response(e, id),
+                }
+            }
+            LOG_WELLNESS => {
+                let entry = match wellness::parse_manual_entry(args) {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        return McpResponse {
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                            result: None,
+                            error: Some(McpError {
+                                code: ERROR_INVALID_PARAMS,
+                                message: e.to_string(),
+                                data: None,
+                            }),
+                            id,
+                        };
+                    }
+                };
+                match wellness::log_manual_entry(database, user_id, &entry).await {
+                    Ok(stored) => Some(serde_json::json!({ "logged": stored })),
+                    Err(e) => {
+                        return McpResponse {
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                            result: None,
+                            error: Some(McpError {
+                                code: ERROR_INTERNAL_ERROR,
+                                message: format!("Failed to log wellness: {}", e),
+                                data: None,
+                            }),
+                            id,
+                        };
+                    }
+                }
+            }
+            GET_READINESS => {
+                let date = match wellness::date_arg(args) {
+                    Ok(date) => date,
+                    Err(e) => {
+                        return McpResponse {
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                            result: None,
+                            error: Some(McpError {
+                                code: ERROR_INVALID_PARAMS,
+                                message: e.to_string(),
+                                data: None,
+                            }),
+                            id,
+                        };
+                    }
+                };
+                match readiness::readiness_for_user(connections, user_id, date).await {
+                    Ok(score) => Some(readiness::readiness_report(date, score.as_ref())),
+                    Err(e) => {
+                        return McpResponse {
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                            result: None,
+                            error: Some(McpError {
+                                code: ERROR_INTERNAL_ERROR,
+                                message: format!("Failed to calculate readiness: {}", e),
+                                data: None,
+                            }),
+                            id,
+                        };
+                    }
+                }
             }
             ANALYZE_TRAINING_LOAD => {
-                let response = serde_json::json!({
-                    "training_load_analysis": {
-                        "weekly_hours": 5.2,
-                        "weekly_distance_km": 35.0,
-                        "load_level": "moderate",
-                        "total_activities": 12,
-                        "insights": [
-                            "Current training load: moderate (5.2 hours/week)",
-                            "Training load is appropriate for current fitness level",
-                            "Consider periodization for optimal adaptation"
-                        ],
-                        "recommendations": [
-                            "Maintain current level",
-                            "Focus on consistency"
+                let activities =
+                    match all_provider_activities(connections, user_id, workload::ACTIVITY_LIMIT)
+                        .await
+                    {
+                        Ok(activities) => activities,
+                        Err(e) => {
+                            return Self::provider_error_response(
+                                id,
+                                "Failed to get activities",
+                                &e,
+                            )
+                        }
+                    };
+                let report = workload::workload_for_user(database, user_id, &activities).await;
+
+                let month_ago = Utc::now() - chrono::Duration::days(28);
+                let recent: Vec<_> = activities
+                    .iter()
+                    .filter(|a| a.start_date >= month_ago)
+                    .collect();
+                let weekly_hours = recent
+                    .iter()
+                    .map(|a| a.duration_seconds as f64 / 3600.0)
+                    .sum::<f64>()
+                    / 4.0;
+                let weekly_distance_km =
+                    recent.iter().filter_map(|a| a.distance_meters).sum::<f64>() / 4000.0;
+                let load_level = if weekly_hours < 3.0 {
+                    "low"
+                } else if weekly_hours < 6.0 {
+                    "moderate"
+                } else if weekly_hours < 10.0 {
+                    "high"
+                } else {
+                    "very_high"
+                };
+
+                let mut insights = vec![format!(
+                    "Current training load: {} ({:.1} hours/week)",
+                    load_level, weekly_hours
+                )];
+                insights.extend(report.alerts.iter().map(|alert| alert.message.clone()));
+                let recommendations = match report.overall_risk {
+                    workload::InjuryRisk::High => {
+                        vec![
+                            "Take a deload week",
+                            "Split long sessions into shorter ones",
                         ]
                     }
-                });
-                Some(response)
+                    workload::InjuryRisk::Moderate => {
+                        vec!["Hold volume at this week's level", "Monitor for niggles"]
+                    }
+                    workload::InjuryRisk::Low => match load_level {
+                        "low" => vec![
+                            "Consider increasing training frequency",
+                            "Add more variety to workouts",
+                        ],
+                        "moderate" => vec!["Maintain current level", "Focus on consistency"],
+                        "high" => {
+                            vec!["Ensure adequate recovery", "Monitor for overtraining signs"]
+                        }
+                        _ => vec!["Consider reducing volume", "Prioritize recovery"],
+                    },
+                };
+
+                Some(serde_json::json!({
+                    "training_load_analysis": {
+                        "weekly_hours": weekly_hours,
+                        "weekly_distance_km": weekly_distance_km,
+                        "load_level": load_level,
+                        "total_activities": recent.len(),
+                        "insights": insights,
+                        "recommendations": recommendations,
+                        "injury_risk": workload::workload_json(&report),
+                    }
+                }))
             }
             DETECT_PATTERNS => {
-                let response = serde_json::json!({
-                    "pattern_analysis": {
-                        "pattern_type": args["pattern_type"].as_str().unwrap_or("weekly"),
-                        "total_activities": 25,
-                        "patterns_detected": [
-                            "Regular training frequency detected",
-                            "Consistent effort levels across activities"
-                        ],
-                        "recommendations": [
-                            "Continue current training consistency",
-                            "Consider adding variety to workout types"
-                        ]
+                let pattern_type = args[PATTERN_TYPE].as_str().unwrap_or("all");
+                if let Err(e) = patterns::PatternType::select(pattern_type) {
+                    return Self::invalid_params_response(e, id);
+                }
+                match patterns::patterns_for_user(
+                    connections,
+                    user_id,
+                    args[PROVIDER].as_str(),
+                    pattern_type,
+                    args[TIMEFRAME].as_str(),
+                )
+                .await
+                {
+                    Ok(report) => Some(report),
+                    Err(e) => {
+                        return Self::internal_error_response(
+                            format!("Failed to detect patterns: {}", e),
+                            id,
+                        );
                     }
-                });
-                Some(response)
+                }
             }
             ANALYZE_PERFORMANCE_TRENDS => {
-                let response = serde_json::json!({
-                    "trend_analysis": {
-                        "timeframe": args["timeframe"].as_str().unwrap_or("month"),
-                        "metric": args["metric"].as_str().unwrap_or("pace"),
-                        "total_activities": 15,
-                        "trend_direction": "stable",
-                        "insights": [
-                            "Analyzed 15 activities over the past month",
-                            "Performance trends require more historical data for accurate analysis"
-                        ]
+                let activities = match all_provider_activities(connections, user_id, 200).await {
+                    Ok(activities) => activities,
+                    Err(e) => {
+                        return Self::provider_error_response(id, "Failed to get activities", &e)
                     }
-                });
-                Some(response)
+                };
+                Some(
+                    Self::performance_trends(
+                        &activities,
+                        args["timeframe"].as_str().unwrap_or("month"),
+                        args["metric"].as_str().unwrap_or("pace"),
+                    )
+                    .await,
+                )
+            }
+            LIST_REMOTE_AGENTS | DELEGATE_TO_AGENT | GET_DELEGATED_TASK | CANCEL_DELEGATED_TASK => {
+                let required: &[&str] = match tool_name {
+                    LIST_REMOTE_AGENTS => &[],
+                    DELEGATE_TO_AGENT => &[AGENT_ID, "message"],
+                    _ => &[AGENT_ID, TASK_ID],
+                };
+                if let Some(missing) = required.iter().find(|name| !args[**name].is_string()) {
+                    return Self::invalid_params_response(
+                        format!("Missing required parameter: {}", missing),
+                        id,
+                    );
+                }
+
+                let registry = crate::a2a::RemoteAgentRegistry::new(database.clone());
+                let agent_id = args[AGENT_ID].as_str().unwrap_or_default();
+                let task_id = args[TASK_ID].as_str().unwrap_or_default();
+
+                let outcome = match tool_name {
+                    LIST_REMOTE_AGENTS => registry
+                        .agents_for_user(user_id)
+                        .await
+                        .map(|agents| serde_json::json!({ "remote_agents": agents })),
+                    DELEGATE_TO_AGENT => {
+                        let message = args["message"].as_str().unwrap_or_default();
+                        let data = args.get("data").filter(|d| !d.is_null()).cloned();
+                        registry
+                            .delegate(user_id, agent_id, message, data)
+                            .await
+                            .map(|task| serde_json::json!({ "agent_id": agent_id, "task": task }))
+                    }
+                    GET_DELEGATED_TASK => registry
+                        .get_task(user_id, agent_id, task_id)
+                        .await
+                        .map(|task| serde_json::json!({ "agent_id": agent_id, "task": task })),
+                    _ => registry
+                        .cancel_task(user_id, agent_id, task_id)
+                        .await
+                        .map(|task| serde_json::json!({ "agent_id": agent_id, "task": task })),
+                };
+
+                match outcome {
+                    Ok(response) => Some(response),
+                    Err(e) => {
+                        let code = match e {
+                            crate::a2a::A2AError::RemoteAgentNotFound(_)
+                            | crate::a2a::A2AError::InvalidRequest(_) => ERROR_INVALID_PARAMS,
+                            _ => ERROR_INTERNAL_ERROR,
+                        };
+                        return McpResponse {
+                            jsonrpc: JSONRPC_VERSION.to_string(),
+                            result: None,
+                            error: Some(McpError {
+                                code,
+                                message: e.to_string(),
+                                data: None,
+                            }),
+                            id,
+                        };
+                    }
+                }
             }
             _ => {
                 return McpResponse {
@@ -1625,14 +2728,109 @@ impl MultiTenantMcpServer {
         }
     }
 
+    /// Trend of one metric over the activities in a timeframe
+    ///
+    /// `adjusted_pace` looks up the measured weather of each activity to take
+    /// the heat out of its pace.
+    async fn performance_trends(activities: &[Activity], timeframe: &str, metric: &str) -> Value {
+        let period = match timeframe {
+            "week" => TimeFrame::Week,
+            "quarter" => TimeFrame::Quarter,
+            "year" => TimeFrame::Year,
+            _ => TimeFrame::Month,
+        };
+
+        let mut weather = HashMap::new();
+        if metric == "adjusted_pace" {
+            let start = period.start_date();
+            let in_timeframe: Vec<&Activity> = activities
+                .iter()
+                .filter(|a| a.start_date >= start)
+                .collect();
+            let measured = adjusted_pace::measured_weather(&in_timeframe).await;
+            for (activity, conditions) in in_timeframe.iter().zip(measured) {
+                if let Some(conditions) = conditions {
+                    weather.insert(activity.id.clone(), conditions);
+                }
+            }
+        }
+
+        let analyzer = AdvancedPerformanceAnalyzer::new().with_weather(weather);
+        match analyzer.analyze_trends(activities, period, metric).await {
+            Ok(analysis) => serde_json::json!({
+                "trend_analysis": {
+                    "timeframe": timeframe,
+                    "metric": metric,
+                    "total_activities": activities.len(),
+                    "data_points_count": analysis.data_points.len(),
+                    "trend_direction": analysis.trend_direction,
+                    "trend_strength": analysis.trend_strength,
+                    "insights": analysis
+                        .insights
+                        .iter()
+                        .map(|insight| insight.message.clone())
+                        .collect::<Vec<_>>()
+                }
+            }),
+            Err(e) => serde_json::json!({
+                "trend_analysis": {
+                    "timeframe": timeframe,
+                    "metric": metric,
+                    "total_activities": activities.len(),
+                    "trend_direction": "stable",
+                    "insights": [e.to_string()]
+                }
+            }),
+        }
+    }
+
+    /// Build the error response for a failed provider API call
+    ///
+    /// Rate limiting gets its own code and tells the client when to retry.
+    fn provider_error_response(id: Value, context: &str, error: &anyhow::Error) -> McpResponse {
+        let mcp_error = if let Some(limited) = error.downcast_ref::<ProviderRateLimited>() {
+            McpError {
+                code: ERROR_PROVIDER_RATE_LIMITED,
+                message: limited.to_string(),
+                data: Some(serde_json::json!({
+                    "provider": limited.provider,
+                    "retry_after_secs": limited.retry_after_secs,
+                })),
+            }
+        } else if let Some(OAuthError::ReauthorizationRequired(provider)) =
+            error.downcast_ref::<OAuthError>()
+        {
+            McpError {
+                code: ERROR_PROVIDER_REAUTH_REQUIRED,
+                message: error.to_string(),
+                data: Some(serde_json::json!({
+                    "provider": provider,
+                    "needs_reauth": true,
+                })),
+            }
+        } else {
+            McpError {
+                code: ERROR_INTERNAL_ERROR,
+                message: format!("{}: {}", context, error),
+                data: None,
+            }
+        };
+        McpResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            result: None,
+            error: Some(mcp_error),
+            id,
+        }
+    }
+
     /// Execute tool call with provider
     async fn execute_tool_call(
         tool_name: &str,
         args: &Value,
         provider: &dyn FitnessProvider,
         id: Value,
-        _user_id: Uuid,
-        _database: &Arc<Database>,
+        user_id: Uuid,
+        database: &Arc<Database>,
     ) -> McpResponse {
         let result = match tool_name {
             GET_ACTIVITIES => {
@@ -1642,47 +2840,20 @@ impl MultiTenantMcpServer {
                 match provider.get_activities(limit, offset).await {
                     Ok(activities) => serde_json::to_value(activities).ok(),
                     Err(e) => {
-                        return McpResponse {
-                            jsonrpc: JSONRPC_VERSION.to_string(),
-                            result: None,
-                            error: Some(McpError {
-                                code: ERROR_INTERNAL_ERROR,
-                                message: format!("Failed to get activities: {}", e),
-                                data: None,
-                            }),
-                            id,
-                        };
+                        return Self::provider_error_response(id, "Failed to get activities", &e);
                     }
                 }
             }
             GET_ATHLETE => match provider.get_athlete().await {
                 Ok(athlete) => serde_json::to_value(athlete).ok(),
                 Err(e) => {
-                    return McpResponse {
-                        jsonrpc: JSONRPC_VERSION.to_string(),
-                        result: None,
-                        error: Some(McpError {
-                            code: ERROR_INTERNAL_ERROR,
-                            message: format!("Failed to get athlete: {}", e),
-                            data: None,
-                        }),
-                        id,
-                    };
+                    return Self::provider_error_response(id, "Failed to get athlete", &e);
                 }
             },
             GET_STATS => match provider.get_stats().await {
                 Ok(stats) => serde_json::to_value(stats).ok(),
                 Err(e) => {
-                    return McpResponse {
-                        jsonrpc: JSONRPC_VERSION.to_string(),
-                        result: None,
-                        error: Some(McpError {
-                            code: ERROR_INTERNAL_ERROR,
-                            message: format!("Failed to get stats: {}", e),
-                            data: None,
-                        }),
-                        id,
-                    };
+                    return Self::provider_error_response(id, "Failed to get stats", &e);
                 }
             },
             GET_ACTIVITY_INTELLIGENCE => {
@@ -1707,7 +2878,7 @@ impl MultiTenantMcpServer {
                                     let weather_config =
                                         fitness_config.weather_api.unwrap_or_default();
                                     let mut weather_service =
-                                        WeatherService::new(weather_config, None);
+                                        WeatherService::with_config(weather_config);
 
                                     weather_service
                                         .get_weather_for_activity(
@@ -1722,18 +2893,16 @@ impl MultiTenantMcpServer {
                                 };
 
                                 // Get location data if requested
-                                let location = if include_location
-                                    && activity.start_latitude.is_some()
-                                    && activity.start_longitude.is_some()
-                                {
+                                let location = if let (true, Some(latitude), Some(longitude)) = (
+                                    include_location,
+                                    activity.start_latitude,
+                                    activity.start_longitude,
+                                ) {
                                     let mut location_service =
                                         crate::intelligence::location::LocationService::new();
 
                                     match location_service
-                                        .get_location_from_coordinates(
-                                            activity.start_latitude.unwrap(),
-                                            activity.start_longitude.unwrap(),
-                                        )
+                                        .get_location_from_coordinates(latitude, longitude)
                                         .await
                                     {
                                         Ok(location_data) => {
@@ -1784,6 +2953,7 @@ impl MultiTenantMcpServer {
                                     },
                                     "contextual_factors": {
                                         "weather": intelligence.contextual_factors.weather,
+                                        "weather_provenance": WeatherProvenance::of(intelligence.contextual_factors.weather.as_ref()),
                                         "location": intelligence.contextual_factors.location,
                                         "time_of_day": intelligence.contextual_factors.time_of_day,
                                         "days_since_last_activity": intelligence.contextual_factors.days_since_last_activity,
@@ -1822,18 +2992,41 @@ impl MultiTenantMcpServer {
                             };
                         }
                     }
+                    Err(e) => {
+                        return Self::provider_error_response(id, "Failed to get activities", &e);
+                    }
+                }
+            }
+            GET_SLEEP | GET_DAILY_WELLNESS | GET_RESTING_HR_TREND => {
+                let (start, end) = match wellness::date_range(args) {
+                    Ok(range) => range,
                     Err(e) => {
                         return McpResponse {
                             jsonrpc: JSONRPC_VERSION.to_string(),
                             result: None,
                             error: Some(McpError {
-                                code: ERROR_INTERNAL_ERROR,
-                                message: format!("Failed to get activities: {}", e),
+                                code: ERROR_INVALID_PARAMS,
+                                message: e.to_string(),
                                 data: None,
                             }),
                             id,
                         };
                     }
+                };
+
+                match wellness::sync_daily_wellness(database, provider, user_id, start, end).await {
+                    Ok(days) => Some(match tool_name {
+                        GET_SLEEP => wellness::sleep_report(&days),
+                        GET_RESTING_HR_TREND => wellness::resting_hr_report(&days),
+                        _ => wellness::daily_wellness_report(&days),
+                    }),
+                    Err(e) => {
+                        return Self::provider_error_response(
+                            id,
+                            "Failed to get wellness data",
+                            &e,
+                        );
+                    }
                 }
             }
             // === ANALYTICS TOOLS ===
@@ -1891,16 +3084,7 @@ impl MultiTenantMcpServer {
                         }
                     }
                     Err(e) => {
-                        return McpResponse {
-                            jsonrpc: JSONRPC_VERSION.to_string(),
-                            result: None,
-                            error: Some(McpError {
-                                code: ERROR_INTERNAL_ERROR,
-                                message: format!("Failed to get activities: {}", e),
-                                data: None,
-                            }),
-                            id,
-                        };
+                        return Self::provider_error_response(id, "Failed to get activities", &e);
                     }
                 }
             }
@@ -1910,6 +3094,33 @@ impl MultiTenantMcpServer {
                 match provider.get_activities(Some(100), None).await {
                     Ok(activities) => {
                         if let Some(activity) = activities.iter().find(|a| a.id == activity_id) {
+                            let streams = provider.get_activity_streams(&activity.id).await.ok();
+                            let weather = adjusted_pace::measured_weather(&[activity])
+                                .await
+                                .pop()
+                                .flatten();
+                            let adjusted = AdjustedPace::for_activity(
+                                activity,
+                                streams.as_ref(),
+                                weather.as_ref(),
+                            );
+                            // Zones and stress scores use the user's estimated thresholds
+                            let metrics =
+                                match thresholds::metrics_calculator_for(database, user_id)
+                                    .await
+                                    .calculate_metrics_with_conditions(
+                                        activity,
+                                        streams.as_ref(),
+                                        weather.as_ref(),
+                                    ) {
+                                    Ok(metrics) => metrics,
+                                    Err(e) => {
+                                        return Self::internal_error_response(
+                                            format!("Failed to calculate metrics: {}", e),
+                                            id,
+                                        );
+                                    }
+                                };
                             let response = serde_json::json!({
                                 "metrics": {
                                     "activity_id": activity.id,
@@ -1925,7 +3136,17 @@ impl MultiTenantMcpServer {
                                         })
                                     },
                                     "elevation_gain_m": activity.elevation_gain,
-                                    "calories_burned": activity.calories
+                                    "calories_burned": activity.calories,
+                                    "trimp": metrics.trimp,
+                                    "power_to_weight_ratio": metrics.power_to_weight_ratio,
+                                    "aerobic_efficiency": metrics.aerobic_efficiency,
+                                    "training_stress_score": metrics.training_stress_score,
+                                    "intensity_factor": metrics.intensity_factor,
+                                    "variability_index": metrics.variability_index,
+                                    "efficiency_factor": metrics.efficiency_factor,
+                                    "decoupling_percentage": metrics.decoupling_percentage,
+                                    "custom_metrics": metrics.custom_metrics,
+                                    "adjusted_pace": adjusted
                                 }
                             });
                             Some(response)
@@ -1946,16 +3167,7 @@ impl MultiTenantMcpServer {
                         }
                     }
                     Err(e) => {
-                        return McpResponse {
-                            jsonrpc: JSONRPC_VERSION.to_string(),
-                            result: None,
-                            error: Some(McpError {
-                                code: ERROR_INTERNAL_ERROR,
-                                message: format!("Failed to get activities: {}", e),
-                                data: None,
-                            }),
-                            id,
-                        };
+                        return Self::provider_error_response(id, "Failed to get activities", &e);
                     }
                 }
             }
@@ -1965,124 +3177,26 @@ impl MultiTenantMcpServer {
 
                 match provider.get_activities(Some(100), None).await {
                     Ok(activities) => {
-                        let response = serde_json::json!({
-                            "trend_analysis": {
-                                "timeframe": timeframe,
-                                "metric": metric,
-                                "total_activities": activities.len(),
-                                "trend_direction": "stable",
-                                "insights": [
-                                    format!("Analyzed {} activities over the past {}", activities.len(), timeframe),
-                                    "Performance trends require more historical data for accurate analysis"
-                                ]
-                            }
-                        });
-                        Some(response)
-                    }
-                    Err(e) => {
-                        return McpResponse {
-                            jsonrpc: JSONRPC_VERSION.to_string(),
-                            result: None,
-                            error: Some(McpError {
-                                code: ERROR_INTERNAL_ERROR,
-                                message: format!("Failed to get activities: {}", e),
-                                data: None,
-                            }),
-                            id,
-                        };
-                    }
-                }
-            }
-            "compare_activities" => {
-                let activity_id1 = args["activity_id1"].as_str().unwrap_or("");
-                let activity_id2 = args["activity_id2"].as_str().unwrap_or("");
-
-                match provider.get_activities(Some(100), None).await {
-                    Ok(activities) => {
-                        let activity1 = activities.iter().find(|a| a.id == activity_id1);
-                        let activity2 = activities.iter().find(|a| a.id == activity_id2);
-
-                        if let (Some(a1), Some(a2)) = (activity1, activity2) {
-                            let response = serde_json::json!({
-                                "comparison": {
-                                    "activity1": {
-                                        "id": a1.id,
-                                        "name": a1.name,
-                                        "duration_minutes": a1.duration_seconds / 60,
-                                        "distance_km": a1.distance_meters.map(|d| d / 1000.0)
-                                    },
-                                    "activity2": {
-                                        "id": a2.id,
-                                        "name": a2.name,
-                                        "duration_minutes": a2.duration_seconds / 60,
-                                        "distance_km": a2.distance_meters.map(|d| d / 1000.0)
-                                    },
-                                    "insights": [
-                                        "Activity comparison shows differences in duration and distance",
-                                        "For detailed analysis, consider pace, heart rate, and effort levels"
-                                    ]
-                                }
-                            });
-                            Some(response)
-                        } else {
-                            return McpResponse {
-                                jsonrpc: JSONRPC_VERSION.to_string(),
-                                result: None,
-                                error: Some(McpError {
-                                    code: ERROR_INVALID_PARAMS,
-                                    message: "One or both activities not found".to_string(),
-                                    data: None,
-                                }),
-                                id,
-                            };
-                        }
+                        Some(Self::performance_trends(&activities, timeframe, metric).await)
                     }
                     Err(e) => {
-                        return McpResponse {
-                            jsonrpc: JSONRPC_VERSION.to_string(),
-                            result: None,
-                            error: Some(McpError {
-                                code: ERROR_INTERNAL_ERROR,
-                                message: format!("Failed to get activities: {}", e),
-                                data: None,
-                            }),
-                            id,
-                        };
+                        return Self::provider_error_response(id, "Failed to get activities", &e);
                     }
                 }
             }
-            "detect_patterns" => {
-                let pattern_type = args["pattern_type"].as_str().unwrap_or("weekly");
-
-                match provider.get_activities(Some(100), None).await {
-                    Ok(activities) => {
-                        let response = serde_json::json!({
-                            "pattern_analysis": {
-                                "pattern_type": pattern_type,
-                                "total_activities": activities.len(),
-                                "patterns_detected": [
-                                    "Regular training frequency detected",
-                                    "Consistent effort levels across activities"
-                                ],
-                                "recommendations": [
-                                    "Continue current training consistency",
-                                    "Consider adding variety to workout types"
-                                ]
-                            }
-                        });
-                        Some(response)
-                    }
+            COMPARE_ACTIVITIES => {
+                let request = match ComparisonRequest::from_args(args) {
+                    Ok(request) => request,
+                    Err(e) => return Self::invalid_params_response(e, id),
+                };
+                match comparison::compare_activities(provider, &request).await {
+                    Ok(comparison) => serde_json::to_value(comparison).ok(),
                     Err(e) => {
-                        return McpResponse {
-                            jsonrpc: JSONRPC_VERSION.to_string(),
-                            result: None,
-                            error: Some(McpError {
-                                code: ERROR_INTERNAL_ERROR,
-                                message: format!("Failed to get activities: {}", e),
-                                data: None,
-                            }),
+                        return Self::provider_error_response(
                             id,
-                        };
+                            "Failed to compare activities",
+                            &e,
+                        );
                     }
                 }
             }
@@ -2109,58 +3223,43 @@ impl MultiTenantMcpServer {
                     Some(response)
                 }
                 Err(e) => {
-                    return McpResponse {
-                        jsonrpc: JSONRPC_VERSION.to_string(),
-                        result: None,
-                        error: Some(McpError {
-                            code: ERROR_INTERNAL_ERROR,
-                            message: format!("Failed to get activities: {}", e),
-                            data: None,
-                        }),
-                        id,
-                    };
+                    return Self::provider_error_response(id, "Failed to get activities", &e);
                 }
             },
             "generate_recommendations" => match provider.get_activities(Some(20), None).await {
-                Ok(_activities) => {
-                    let response = serde_json::json!({
-                        "training_recommendations": [
-                            {
-                                "type": "intensity",
-                                "title": "Add Interval Training",
-                                "description": "Include 1-2 high-intensity interval sessions per week",
-                                "priority": "medium",
-                                "rationale": "To improve speed and cardiovascular fitness"
-                            },
-                            {
-                                "type": "volume",
-                                "title": "Gradual Volume Increase",
-                                "description": "Increase weekly distance by 10% each week",
-                                "priority": "high",
-                                "rationale": "Based on your current training load"
-                            },
+                Ok(activities) => {
+                    let engine = AdvancedRecommendationEngine::new();
+                    let workload =
+                        workload::workload_for_user(database, user_id, &activities).await;
+                    let profile = UserFitnessProfile::with_defaults(
+                        user_id.to_string(),
+                        workload.injury_history.clone(),
+                    );
+                    match engine.generate_recommendations(&profile, &activities).await {
+                        Ok(mut training) => {
+                            let today = Utc::now().date_naive();
+                            if let Some(readiness) =
+                                readiness::merged_readiness(database, user_id, today, &activities)
+                                    .await
                             {
-                                "type": "recovery",
-                                "title": "Include Rest Days",
-                                "description": "Schedule at least one complete rest day per week",
-                                "priority": "high",
-                                "rationale": "Essential for adaptation and injury prevention"
+                                training = engine.apply_readiness(training, &readiness);
                             }
-                        ]
-                    });
-                    Some(response)
+                            training = engine.apply_workload_risk(training, &workload);
+                            Some(serde_json::json!({
+                                "training_recommendations": recommendations_json(&training)
+                            }))
+                        }
+                        Err(e) => {
+                            return Self::provider_error_response(
+                                id,
+                                "Failed to generate recommendations",
+                                &e,
+                            );
+                        }
+                    }
                 }
                 Err(e) => {
-                    return McpResponse {
-                        jsonrpc: JSONRPC_VERSION.to_string(),
-                        result: None,
-                        error: Some(McpError {
-                            code: ERROR_INTERNAL_ERROR,
-                            message: format!("Failed to get activities: {}", e),
-                            data: None,
-                        }),
-                        id,
-                    };
+                    return Self::provider_error_response(id, "Failed to get activities", &e);
                 }
             },
             "calculate_fitness_score" => match provider.get_activities(Some(30), None).await {
@@ -2195,16 +3294,7 @@ impl MultiTenantMcpServer {
                     Some(response)
                 }
                 Err(e) => {
-                    return McpResponse {
-                        jsonrpc: JSONRPC_VERSION.to_string(),
-                        result: None,
-                        error: Some(McpError {
-                            code: ERROR_INTERNAL_ERROR,
-                            message: format!("Failed to get activities: {}", e),
-                            data: None,
-                        }),
-                        id,
-                    };
+                    return Self::provider_error_response(id, "Failed to get activities", &e);
                 }
             },
             "predict_performance" => {
@@ -2234,16 +3324,7 @@ impl MultiTenantMcpServer {
                         Some(response)
                     }
                     Err(e) => {
-                        return McpResponse {
-                            jsonrpc: JSONRPC_VERSION.to_string(),
-                            result: None,
-                            error: Some(McpError {
-                                code: ERROR_INTERNAL_ERROR,
-                                message: format!("Failed to get activities: {}", e),
-                                data: None,
-                            }),
-                            id,
-                        };
+                        return Self::provider_error_response(id, "Failed to get activities", &e);
                     }
                 }
             }
@@ -2291,16 +3372,7 @@ impl MultiTenantMcpServer {
                         Some(response)
                     }
                     Err(e) => {
-                        return McpResponse {
-                            jsonrpc: JSONRPC_VERSION.to_string(),
-                            result: None,
-                            error: Some(McpError {
-                                code: ERROR_INTERNAL_ERROR,
-                                message: format!("Failed to get activities: {}", e),
-                                data: None,
-                            }),
-                            id,
-                        };
+                        return Self::provider_error_response(id, "Failed to get activities", &e);
                     }
                 }
             }
@@ -2410,6 +3482,25 @@ struct ApiError(serde_json::Value);
 impl warp::reject::Reject for ApiError {}
 
 /// Add CORS and security headers to a reply
+/// Recommendations in the shape MCP clients already read: lowercase `type`
+/// and `priority` next to the title, description and rationale
+fn recommendations_json(recommendations: &[TrainingRecommendation]) -> serde_json::Value {
+    recommendations
+        .iter()
+        .map(|rec| {
+            serde_json::json!({
+                "type": format!("{:?}", rec.recommendation_type).to_lowercase(),
+                "title": rec.title,
+                "description": rec.description,
+                "priority": format!("{:?}", rec.priority).to_lowercase(),
+                "confidence": rec.confidence.as_score(),
+                "rationale": rec.rationale,
+                "actionable_steps": rec.actionable_steps,
+            })
+        })
+        .collect()
+}
+
 fn with_cors_headers(
     reply: impl warp::Reply,
     security_headers_env: Option<&str>,
@@ -29,16 +29,64 @@ pub struct ServerInfo {
 }
 
 /// MCP Tool Schema Definition
+///
+/// The capability, output schema and examples describe the tool to A2A
+/// partners in the agent card and are not part of the MCP tool listing.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolSchema {
     pub name: String,
     pub description: String,
     #[serde(rename = "inputSchema")]
     pub input_schema: JsonSchema,
+    #[serde(skip)]
+    pub capability: ToolCapability,
+    #[serde(skip)]
+    pub output_schema: JsonSchema,
+    #[serde(skip)]
+    pub examples: Vec<ToolExample>,
 }
 
-/// JSON Schema Definition
+/// Capability group a tool is advertised under
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToolCapability {
+    #[default]
+    FitnessDataAnalysis,
+    ActivityIntelligence,
+    GoalManagement,
+    PerformancePrediction,
+    TrainingAnalytics,
+    WellnessMonitoring,
+    ProviderIntegration,
+    AgentDelegation,
+}
+
+impl ToolCapability {
+    /// Name of the capability in the agent card
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::FitnessDataAnalysis => "fitness-data-analysis",
+            Self::ActivityIntelligence => "activity-intelligence",
+            Self::GoalManagement => "goal-management",
+            Self::PerformancePrediction => "performance-prediction",
+            Self::TrainingAnalytics => "training-analytics",
+            Self::WellnessMonitoring => "wellness-monitoring",
+            Self::ProviderIntegration => "provider-integration",
+            Self::AgentDelegation => "agent-delegation",
+        }
+    }
+}
+
+/// Example usage of a tool
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolExample {
+    pub description: String,
+    pub input: serde_json::Value,
+    pub output: serde_json::Value,
+}
+
+/// JSON Schema Definition
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct JsonSchema {
     #[serde(rename = "type")]
     pub schema_type: String,
@@ -220,6 +268,17 @@ impl InitializeResponse {
     }
 }
 
+impl InitializeResponse {
+    /// Advertise readable resources, for servers that serve `resources/list` and `resources/read`
+    pub fn with_resources(mut self) -> Self {
+        self.capabilities.resources = Some(ResourcesCapability {
+            subscribe: Some(false),
+            list_changed: Some(false),
+        });
+        self
+    }
+}
+
 /// Get all available tools (public interface for tests)
 pub fn get_tools() -> Vec<ToolSchema> {
     create_fitness_tools()
@@ -237,6 +296,10 @@ fn create_fitness_tools() -> Vec<ToolSchema> {
         create_connect_fitbit_tool(),
         create_get_connection_status_tool(),
         create_disconnect_provider_tool(),
+        // Manual Activity Tools
+        create_log_activity_tool(),
+        create_update_activity_tool(),
+        create_delete_activity_tool(),
         // Advanced Analytics Tools
         create_analyze_activity_tool(),
         create_calculate_metrics_tool(),
@@ -251,9 +314,59 @@ fn create_fitness_tools() -> Vec<ToolSchema> {
         create_calculate_fitness_score_tool(),
         create_predict_performance_tool(),
         create_analyze_training_load_tool(),
+        // Wellness Tools
+        create_get_sleep_tool(),
+        create_get_daily_wellness_tool(),
+        create_get_resting_hr_trend_tool(),
+        create_log_wellness_tool(),
+        create_get_readiness_tool(),
+        // Gear Tools
+        create_get_gear_tool(),
+        create_gear_usage_report_tool(),
+        create_add_gear_tool(),
+        create_update_gear_tool(),
+        // Athlete thresholds
+        create_get_athlete_thresholds_tool(),
+        // Analysis settings
+        create_update_analysis_settings_tool(),
+        // Repeated routes
+        create_list_my_routes_tool(),
+        create_route_history_tool(),
+        // Anomaly detection
+        create_detect_anomalies_tool(),
+        create_query_activities_tool(),
+        create_get_streaks_tool(),
+        create_get_digest_tool(),
+        // Remote Agent Delegation Tools
+        create_list_remote_agents_tool(),
+        create_delegate_to_agent_tool(),
+        create_get_delegated_task_tool(),
+        create_cancel_delegated_task_tool(),
     ]
 }
 
+/// Object schema for a tool result from its top-level fields and JSON types
+fn result_schema(fields: &[(&str, &str)]) -> JsonSchema {
+    JsonSchema {
+        schema_type: "object".to_string(),
+        properties: Some(
+            fields
+                .iter()
+                .map(|(name, field_type)| {
+                    (
+                        (*name).to_string(),
+                        PropertySchema {
+                            property_type: (*field_type).to_string(),
+                            description: None,
+                        },
+                    )
+                })
+                .collect(),
+        ),
+        required: None,
+    }
+}
+
 /// Create the get_activities tool schema
 fn create_get_activities_tool() -> ToolSchema {
     let mut properties = HashMap::new();
@@ -262,7 +375,10 @@ fn create_get_activities_tool() -> ToolSchema {
         PROVIDER.to_string(),
         PropertySchema {
             property_type: "string".to_string(),
-            description: Some("Fitness provider name (e.g., 'strava', 'fitbit')".to_string()),
+            description: Some(
+                "Fitness provider name (e.g., 'strava', 'fitbit'), or 'all' to merge every connected provider"
+                    .to_string(),
+            ),
         },
     );
 
@@ -290,6 +406,31 @@ fn create_get_activities_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec![PROVIDER.to_string()]),
         },
+        capability: ToolCapability::FitnessDataAnalysis,
+        output_schema: result_schema(&[
+            ("activities", "array"),
+            ("total_count", "number"),
+            ("provider", "string"),
+        ]),
+        examples: vec![ToolExample {
+            description: "Get recent activities".to_string(),
+            input: serde_json::json!({"limit": 5, "provider": "strava"}),
+            output: serde_json::json!({
+                "activities": [
+                    {
+                        "id": "123456",
+                        "name": "Morning Run",
+                        "sport_type": "Run",
+                        "start_date": "2024-01-15T07:00:00Z",
+                        "duration_seconds": 1800,
+                        "distance_meters": 5000,
+                        "elevation_gain": 50
+                    }
+                ],
+                "total_count": 1,
+                "provider": "strava"
+            }),
+        }],
     }
 }
 
@@ -313,6 +454,16 @@ fn create_get_athlete_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec![PROVIDER.to_string()]),
         },
+        capability: ToolCapability::FitnessDataAnalysis,
+        output_schema: result_schema(&[
+            ("id", "string"),
+            ("username", "string"),
+            ("firstname", "string"),
+            ("lastname", "string"),
+            ("profile_picture", "string"),
+            ("provider", "string"),
+        ]),
+        examples: vec![],
     }
 }
 
@@ -336,7 +487,131 @@ fn create_get_stats_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec![PROVIDER.to_string()]),
         },
+        capability: ToolCapability::FitnessDataAnalysis,
+        output_schema: result_schema(&[
+            ("total_activities", "number"),
+            ("total_distance", "number"),
+            ("total_duration", "number"),
+            ("total_elevation_gain", "number"),
+        ]),
+        examples: vec![],
+    }
+}
+
+/// Create the log_activity tool schema
+fn create_log_activity_tool() -> ToolSchema {
+    ToolSchema {
+        name: LOG_ACTIVITY.to_string(),
+        description: "Log an activity that no provider recorded, such as a strength session, class or untracked run".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(manual_activity_properties()),
+            required: Some(vec![
+                "sport_type".to_string(),
+                "duration_minutes".to_string(),
+            ]),
+        },
+        capability: ToolCapability::FitnessDataAnalysis,
+        output_schema: result_schema(&[("activity", "object")]),
+        examples: vec![],
+    }
+}
+
+/// Create the update_activity tool schema
+fn create_update_activity_tool() -> ToolSchema {
+    let mut properties = manual_activity_properties();
+
+    properties.insert(
+        ACTIVITY_ID.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("ID of a manually logged activity".to_string()),
+        },
+    );
+
+    ToolSchema {
+        name: UPDATE_ACTIVITY.to_string(),
+        description: "Edit a manually logged activity; omitted fields keep their values"
+            .to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: Some(vec![ACTIVITY_ID.to_string()]),
+        },
+        capability: ToolCapability::FitnessDataAnalysis,
+        output_schema: result_schema(&[("activity", "object")]),
+        examples: vec![],
+    }
+}
+
+/// Create the delete_activity tool schema
+fn create_delete_activity_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        ACTIVITY_ID.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("ID of a manually logged activity".to_string()),
+        },
+    );
+
+    ToolSchema {
+        name: DELETE_ACTIVITY.to_string(),
+        description: "Delete a manually logged activity".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: Some(vec![ACTIVITY_ID.to_string()]),
+        },
+        capability: ToolCapability::FitnessDataAnalysis,
+        output_schema: result_schema(&[("deleted", "boolean"), ("activity_id", "string")]),
+        examples: vec![],
+    }
+}
+
+/// Activity fields shared by log_activity and update_activity
+fn manual_activity_properties() -> HashMap<String, PropertySchema> {
+    let mut properties = HashMap::new();
+
+    for (name, property_type, description) in [
+        (
+            "sport_type",
+            "string",
+            "Sport type, e.g. 'run', 'ride', 'yoga', 'strength_training', 'workout'",
+        ),
+        ("duration_minutes", "number", "Duration in minutes"),
+        (
+            "name",
+            "string",
+            "Activity title (default: derived from the sport type)",
+        ),
+        (
+            "start_date",
+            "string",
+            "Start time, RFC 3339 (default: now)",
+        ),
+        ("distance_meters", "number", "Distance in meters"),
+        ("elevation_gain", "number", "Elevation gain in meters"),
+        ("average_heart_rate", "number", "Average heart rate in bpm"),
+        ("max_heart_rate", "number", "Maximum heart rate in bpm"),
+        ("calories", "number", "Calories burned"),
+        (
+            GEAR_ID,
+            "string",
+            "Shoes or bike used, as listed by get_gear",
+        ),
+    ] {
+        properties.insert(
+            name.to_string(),
+            PropertySchema {
+                property_type: property_type.to_string(),
+                description: Some(description.to_string()),
+            },
+        );
     }
+
+    properties
 }
 
 /// Create the get_activity_intelligence tool schema
@@ -386,6 +661,21 @@ fn create_get_activity_intelligence_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec![PROVIDER.to_string(), ACTIVITY_ID.to_string()]),
         },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[
+            ("summary", "string"),
+            ("activity_id", "string"),
+            ("activity_name", "string"),
+            ("sport_type", "string"),
+            ("duration_minutes", "number"),
+            ("distance_km", "number"),
+            ("performance_indicators", "object"),
+            ("contextual_factors", "object"),
+            ("key_insights", "array"),
+            ("generated_at", "string"),
+            ("status", "string"),
+        ]),
+        examples: vec![],
     }
 }
 
@@ -401,6 +691,9 @@ fn create_connect_strava_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec![]),
         },
+        capability: ToolCapability::ProviderIntegration,
+        output_schema: result_schema(&[("authorization_url", "string"), ("instructions", "object"), ("state", "string"), ("provider", "string"), ("expires_in_minutes", "number"), ("next_step", "string")]),
+        examples: vec![],
     }
 }
 
@@ -416,6 +709,9 @@ fn create_connect_fitbit_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec![]),
         },
+        capability: ToolCapability::ProviderIntegration,
+        output_schema: result_schema(&[("authorization_url", "string"), ("instructions", "object"), ("state", "string"), ("provider", "string"), ("expires_in_minutes", "number"), ("next_step", "string")]),
+        examples: vec![],
     }
 }
 
@@ -431,6 +727,9 @@ fn create_get_connection_status_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec![]),
         },
+        capability: ToolCapability::ProviderIntegration,
+        output_schema: result_schema(&[("providers", "object")]),
+        examples: vec![],
     }
 }
 
@@ -456,6 +755,9 @@ fn create_disconnect_provider_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec![PROVIDER.to_string()]),
         },
+        capability: ToolCapability::ProviderIntegration,
+        output_schema: result_schema(&[("provider", "string"), ("status", "string"), ("message", "string"), ("disconnected_at", "string")]),
+        examples: vec![],
     }
 }
 
@@ -489,6 +791,9 @@ fn create_analyze_activity_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec![PROVIDER.to_string(), ACTIVITY_ID.to_string()]),
         },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("activity_id", "string"), ("activity", "object"), ("analysis", "object"), ("insights", "array")]),
+        examples: vec![],
     }
 }
 
@@ -531,6 +836,9 @@ fn create_calculate_metrics_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec!["provider".to_string(), "activity_id".to_string()]),
         },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("activity_id", "string"), ("metrics", "object"), ("data_source", "string"), ("calculated_at", "string")]),
+        examples: vec![],
     }
 }
 
@@ -559,7 +867,7 @@ fn create_analyze_performance_trends_tool() -> ToolSchema {
 
     properties.insert("metric".to_string(), PropertySchema {
         property_type: "string".to_string(),
-        description: Some("Metric to analyze trends for ('pace', 'heart_rate', 'power', 'distance', 'duration')".to_string()),
+        description: Some("Metric to analyze trends for ('pace', 'grade_adjusted_pace', 'adjusted_pace', 'speed', 'heart_rate', 'power', 'distance', 'duration', 'elevation')".to_string()),
     });
 
     properties.insert(
@@ -583,6 +891,19 @@ fn create_analyze_performance_trends_tool() -> ToolSchema {
                 "metric".to_string(),
             ]),
         },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[
+            ("timeframe", "string"),
+            ("metric", "string"),
+            ("trend_direction", "string"),
+            ("trend_strength", "number"),
+            ("statistical_significance", "number"),
+            ("data_points_count", "number"),
+            ("insights", "array"),
+            ("analysis_date", "string"),
+            ("data_source", "string"),
+        ]),
+        examples: vec![],
     }
 }
 
@@ -594,7 +915,9 @@ fn create_compare_activities_tool() -> ToolSchema {
         "provider".to_string(),
         PropertySchema {
             property_type: "string".to_string(),
-            description: Some("Fitness provider name".to_string()),
+            description: Some(
+                "Fitness provider name (defaults to every connected provider)".to_string(),
+            ),
         },
     );
 
@@ -611,7 +934,26 @@ fn create_compare_activities_tool() -> ToolSchema {
         PropertySchema {
             property_type: "string".to_string(),
             description: Some(
-                "Type of comparison ('similar_activities', 'personal_best', 'average', 'recent')"
+                "Type of comparison ('similar_activities', 'personal_best', 'average', 'recent', 'activity'). Defaults to 'similar_activities', or 'activity' when compare_to is given"
+                    .to_string(),
+            ),
+        },
+    );
+
+    properties.insert(
+        "compare_to".to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("Second activity for a direct comparison".to_string()),
+        },
+    );
+
+    properties.insert(
+        "count".to_string(),
+        PropertySchema {
+            property_type: "number".to_string(),
+            description: Some(
+                "Efforts in the 'similar_activities' and 'recent' baselines (1-20, default 5)"
                     .to_string(),
             ),
         },
@@ -620,17 +962,16 @@ fn create_compare_activities_tool() -> ToolSchema {
     ToolSchema {
         name: "compare_activities".to_string(),
         description:
-            "Compare an activity against similar activities, personal bests, or historical averages"
+            "Compare an activity's pace, heart rate, efficiency, elevation and weather against similar efforts, personal bests, averages or another activity, with pace normalized for climbing and temperature"
                 .to_string(),
         input_schema: JsonSchema {
             schema_type: "object".to_string(),
             properties: Some(properties),
-            required: Some(vec![
-                "provider".to_string(),
-                "activity_id".to_string(),
-                "comparison_type".to_string(),
-            ]),
+            required: Some(vec!["provider".to_string(), "activity_id".to_string()]),
         },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("comparison_type", "string"), ("activity", "object"), ("compared_with", "array"), ("baseline", "object"), ("differences", "array"), ("is_personal_best", "boolean"), ("insights", "array"), ("activities_searched", "number")]),
+        examples: vec![],
     }
 }
 
@@ -642,31 +983,40 @@ fn create_detect_patterns_tool() -> ToolSchema {
         "provider".to_string(),
         PropertySchema {
             property_type: "string".to_string(),
-            description: Some("Fitness provider name".to_string()),
+            description: Some(
+                "Fitness provider name (e.g., 'strava', 'fitbit'), or 'all' to merge every connected provider. Defaults to 'all'"
+                    .to_string(),
+            ),
         },
     );
 
     properties.insert("pattern_type".to_string(), PropertySchema {
         property_type: "string".to_string(),
-        description: Some("Type of pattern to detect ('training_consistency', 'seasonal_trends', 'performance_plateaus', 'injury_risk')".to_string()),
+        description: Some("Pattern to detect ('weekly_rhythm', 'long_run_cadence', 'hard_easy_alternation', 'seasonal_volume', 'sport_mix_drift', or 'all'). The older 'training_consistency', 'seasonal_trends', 'performance_plateaus' and 'injury_risk' map onto these. Defaults to 'all'".to_string()),
     });
 
     properties.insert(
         "timeframe".to_string(),
         PropertySchema {
             property_type: "string".to_string(),
-            description: Some("Time period for pattern analysis".to_string()),
+            description: Some(
+                "Period to analyze ('week', 'month', 'quarter', 'sixmonths', 'year'). Defaults to 'year'"
+                    .to_string(),
+            ),
         },
     );
 
     ToolSchema {
         name: "detect_patterns".to_string(),
-        description: "Detect patterns in training data such as consistency trends, seasonal variations, or performance plateaus".to_string(),
+        description: "Detect training habits across all connected providers - weekly rhythm, long run cadence, hard/easy alternation, seasonal volume and sport mix drift - with supporting statistics and confidence".to_string(),
         input_schema: JsonSchema {
             schema_type: "object".to_string(),
             properties: Some(properties),
-            required: Some(vec!["provider".to_string(), "pattern_type".to_string()]),
+            required: Some(vec!["provider".to_string()]),
         },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("pattern_analysis", "object")]),
+        examples: vec![],
     }
 }
 
@@ -739,6 +1089,35 @@ fn create_set_goal_tool() -> ToolSchema {
                 "target_date".to_string(),
             ]),
         },
+        capability: ToolCapability::GoalManagement,
+        output_schema: result_schema(&[
+            ("goal_id", "string"),
+            ("title", "string"),
+            ("goal_type", "string"),
+            ("target_value", "number"),
+            ("timeframe", "string"),
+            ("status", "string"),
+            ("progress", "number"),
+            ("created_at", "string"),
+        ]),
+        examples: vec![ToolExample {
+            description: "Set a monthly running distance goal".to_string(),
+            input: serde_json::json!({
+                "title": "Run 100km",
+                "goal_type": "distance",
+                "target_value": 100000.0,
+                "target_date": "2024-02-01",
+                "sport_type": "Run"
+            }),
+            output: serde_json::json!({
+                "goal_id": "goal_123",
+                "title": "Run 100km",
+                "goal_type": "distance",
+                "target_value": 100000.0,
+                "status": "active",
+                "progress": 0.0
+            }),
+        }],
     }
 }
 
@@ -762,6 +1141,9 @@ fn create_track_progress_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec!["goal_id".to_string()]),
         },
+        capability: ToolCapability::GoalManagement,
+        output_schema: result_schema(&[("goal_id", "string"), ("progress_percentage", "number"), ("completion_date_estimate", "string"), ("milestones_achieved", "array"), ("insights", "array"), ("recommendations", "array"), ("on_track", "boolean"), ("activities_analyzed", "number"), ("tracking_date", "string"), ("data_source", "string")]),
+        examples: vec![],
     }
 }
 
@@ -796,6 +1178,9 @@ fn create_suggest_goals_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec!["provider".to_string()]),
         },
+        capability: ToolCapability::GoalManagement,
+        output_schema: result_schema(&[("goal_suggestions", "array"), ("total_suggestions", "number"), ("user_profile_used", "object"), ("activities_analyzed", "number"), ("generated_at", "string"), ("data_source", "string")]),
+        examples: vec![],
     }
 }
 
@@ -819,6 +1204,9 @@ fn create_analyze_goal_feasibility_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec!["goal_id".to_string()]),
         },
+        capability: ToolCapability::GoalManagement,
+        output_schema: result_schema(&[("goal_type", "string"), ("target_value", "number"), ("timeline_days", "number"), ("feasibility_score", "number"), ("feasibility_assessment", "string"), ("difficulty", "string"), ("analysis_factors", "object"), ("recommendations", "array"), ("analysis_date", "string"), ("data_source", "string")]),
+        examples: vec![],
     }
 }
 
@@ -865,6 +1253,19 @@ fn create_generate_recommendations_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec!["provider".to_string()]),
         },
+        capability: ToolCapability::TrainingAnalytics,
+        output_schema: result_schema(&[
+            ("recommendations", "array"),
+            ("total_recommendations", "number"),
+            ("user_profile_used", "object"),
+            ("activities_analyzed", "number"),
+            ("wellness_days_analyzed", "number"),
+            ("readiness", "object"),
+            ("injury_risk", "object"),
+            ("generated_at", "string"),
+            ("data_source", "string"),
+        ]),
+        examples: vec![],
     }
 }
 
@@ -898,6 +1299,9 @@ fn create_calculate_fitness_score_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec!["provider".to_string()]),
         },
+        capability: ToolCapability::TrainingAnalytics,
+        output_schema: result_schema(&[("overall_score", "number"), ("aerobic_fitness", "number"), ("strength_endurance", "number"), ("consistency", "number"), ("trend", "string"), ("last_updated", "string"), ("activities_analyzed", "number"), ("calculation_date", "string"), ("data_source", "string")]),
+        examples: vec![],
     }
 }
 
@@ -945,6 +1349,9 @@ fn create_predict_performance_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec!["provider".to_string(), "target_sport".to_string(), "target_distance".to_string()]),
         },
+        capability: ToolCapability::PerformancePrediction,
+        output_schema: result_schema(&[("target_goal", "object"), ("predicted_value", "number"), ("confidence", "string"), ("factors", "array"), ("recommendations", "array"), ("estimated_achievement_date", "string"), ("activities_analyzed", "number"), ("prediction_date", "string"), ("data_source", "string")]),
+        examples: vec![],
     }
 }
 
@@ -980,8 +1387,821 @@ fn create_analyze_training_load_tool() -> ToolSchema {
             properties: Some(properties),
             required: Some(vec!["provider".to_string()]),
         },
+        capability: ToolCapability::TrainingAnalytics,
+        output_schema: result_schema(&[
+            ("weekly_loads", "array"),
+            ("average_weekly_load", "number"),
+            ("load_balance_score", "number"),
+            ("recovery_needed", "boolean"),
+            ("recommendations", "array"),
+            ("insights", "array"),
+            ("injury_risk", "object"),
+            ("activities_analyzed", "number"),
+            ("analysis_date", "string"),
+            ("data_source", "string"),
+        ]),
+        examples: vec![],
+    }
+}
+
+/// Create the list_remote_agents tool schema
+fn create_list_remote_agents_tool() -> ToolSchema {
+    ToolSchema {
+        name: LIST_REMOTE_AGENTS.to_string(),
+        description: "List the remote A2A agents (e.g. nutrition or calendar agents) the user trusts for delegation".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(HashMap::new()),
+            required: None,
+        },
+        capability: ToolCapability::AgentDelegation,
+        output_schema: result_schema(&[("remote_agents", "array")]),
+        examples: vec![],
+    }
+}
+
+/// Create the delegate_to_agent tool schema
+fn create_delegate_to_agent_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        AGENT_ID.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("ID of a trusted remote agent (from list_remote_agents)".to_string()),
+        },
+    );
+
+    properties.insert(
+        "message".to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("Instruction for the remote agent".to_string()),
+        },
+    );
+
+    properties.insert(
+        "data".to_string(),
+        PropertySchema {
+            property_type: "object".to_string(),
+            description: Some(
+                "Structured payload to hand over, such as a training plan".to_string(),
+            ),
+        },
+    );
+
+    ToolSchema {
+        name: DELEGATE_TO_AGENT.to_string(),
+        description: "Delegate work to a trusted remote A2A agent, e.g. hand a training plan to a nutrition or calendar agent".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: Some(vec![AGENT_ID.to_string(), "message".to_string()]),
+        },
+        capability: ToolCapability::AgentDelegation,
+        output_schema: result_schema(&[("agent_id", "string"), ("task", "object")]),
+        examples: vec![],
+    }
+}
+
+/// Create the get_delegated_task tool schema
+fn create_get_delegated_task_tool() -> ToolSchema {
+    ToolSchema {
+        name: GET_DELEGATED_TASK.to_string(),
+        description: "Get the status and result of a task delegated to a remote agent".to_string(),
+        input_schema: delegated_task_schema(),
+        capability: ToolCapability::AgentDelegation,
+        output_schema: result_schema(&[("agent_id", "string"), ("task", "object")]),
+        examples: vec![],
+    }
+}
+
+/// Create the cancel_delegated_task tool schema
+fn create_cancel_delegated_task_tool() -> ToolSchema {
+    ToolSchema {
+        name: CANCEL_DELEGATED_TASK.to_string(),
+        description: "Cancel a task delegated to a remote agent".to_string(),
+        input_schema: delegated_task_schema(),
+        capability: ToolCapability::AgentDelegation,
+        output_schema: result_schema(&[("agent_id", "string"), ("task", "object")]),
+        examples: vec![],
+    }
+}
+
+/// Input schema shared by the delegated task tools
+fn delegated_task_schema() -> JsonSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        AGENT_ID.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("ID of the remote agent running the task".to_string()),
+        },
+    );
+
+    properties.insert(
+        TASK_ID.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("Task ID returned by the remote agent".to_string()),
+        },
+    );
+
+    JsonSchema {
+        schema_type: "object".to_string(),
+        properties: Some(properties),
+        required: Some(vec![AGENT_ID.to_string(), TASK_ID.to_string()]),
+    }
+}
+
+/// Create the get_sleep tool schema
+fn create_get_sleep_tool() -> ToolSchema {
+    ToolSchema {
+        name: GET_SLEEP.to_string(),
+        description: "Get nightly sleep duration, efficiency and stages (deep, light, REM, awake) from a wellness provider".to_string(),
+        input_schema: wellness_schema(),
+        capability: ToolCapability::WellnessMonitoring,
+        output_schema: result_schema(&[("nights", "array"), ("summary", "object"), ("provider", "string"), ("start_date", "string"), ("end_date", "string")]),
+        examples: vec![],
+    }
+}
+
+/// Create the get_daily_wellness tool schema
+fn create_get_daily_wellness_tool() -> ToolSchema {
+    ToolSchema {
+        name: GET_DAILY_WELLNESS.to_string(),
+        description: "Get daily wellness data (sleep, resting heart rate, HRV, SpO2, steps, active zone minutes) with recovery signals".to_string(),
+        input_schema: wellness_schema(),
+        capability: ToolCapability::WellnessMonitoring,
+        output_schema: result_schema(&[("days", "array"), ("total_days", "number"), ("needs_recovery", "array"), ("recovery_signals", "array"), ("provider", "string"), ("start_date", "string"), ("end_date", "string")]),
+        examples: vec![],
+    }
+}
+
+/// Create the get_resting_hr_trend tool schema
+fn create_get_resting_hr_trend_tool() -> ToolSchema {
+    ToolSchema {
+        name: GET_RESTING_HR_TREND.to_string(),
+        description: "Analyze resting heart rate over time against the user's baseline".to_string(),
+        input_schema: wellness_schema(),
+        capability: ToolCapability::WellnessMonitoring,
+        output_schema: result_schema(&[
+            ("resting_hr_trend", "object"),
+            ("provider", "string"),
+            ("start_date", "string"),
+            ("end_date", "string"),
+        ]),
+        examples: vec![],
+    }
+}
+
+/// Create the log_wellness tool schema
+fn create_log_wellness_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        DATE.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("Day the entry applies to, YYYY-MM-DD (default: today)".to_string()),
+        },
+    );
+
+    properties.insert(
+        "sleep_hours".to_string(),
+        PropertySchema {
+            property_type: "number".to_string(),
+            description: Some("Hours slept the night before".to_string()),
+        },
+    );
+
+    properties.insert(
+        "sleep_quality".to_string(),
+        PropertySchema {
+            property_type: "number".to_string(),
+            description: Some("Perceived sleep quality from 1 (poor) to 5 (great)".to_string()),
+        },
+    );
+
+    properties.insert(
+        "resting_heart_rate".to_string(),
+        PropertySchema {
+            property_type: "number".to_string(),
+            description: Some("Morning resting heart rate in bpm".to_string()),
+        },
+    );
+
+    properties.insert(
+        "hrv_rmssd_ms".to_string(),
+        PropertySchema {
+            property_type: "number".to_string(),
+            description: Some("Heart rate variability (RMSSD) in milliseconds".to_string()),
+        },
+    );
+
+    ToolSchema {
+        name: LOG_WELLNESS.to_string(),
+        description: "Log sleep, resting heart rate or HRV manually for days without a wearable"
+            .to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: None,
+        },
+        capability: ToolCapability::WellnessMonitoring,
+        output_schema: result_schema(&[("logged", "object")]),
+        examples: vec![],
+    }
+}
+
+/// Create the get_readiness tool schema
+fn create_get_readiness_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        DATE.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("Day to score, YYYY-MM-DD (default: today)".to_string()),
+        },
+    );
+
+    ToolSchema {
+        name: GET_READINESS.to_string(),
+        description: "Get a daily readiness score combining HRV, sleep, resting heart rate and training load, with contributing factors".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: None,
+        },
+        capability: ToolCapability::WellnessMonitoring,
+        output_schema: result_schema(&[("date", "string"), ("readiness", "object"), ("insights", "array"), ("message", "string")]),
+        examples: vec![],
+    }
+}
+
+/// Create the get_gear tool schema
+fn create_get_gear_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        "gear_type".to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("Only list 'shoes', 'bike' or 'other'".to_string()),
+        },
+    );
+
+    properties.insert(
+        INCLUDE_RETIRED.to_string(),
+        PropertySchema {
+            property_type: "boolean".to_string(),
+            description: Some("Include retired gear (default: false)".to_string()),
+        },
+    );
+
+    ToolSchema {
+        name: GET_GEAR.to_string(),
+        description: "List shoes, bikes and other gear with cumulative distance, moving time and activity count".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: None,
+        },
+        capability: ToolCapability::FitnessDataAnalysis,
+        output_schema: result_schema(&[("total_count", "number"), ("gear", "array")]),
+        examples: vec![],
     }
 }
+
+/// Create the gear_usage_report tool schema
+fn create_gear_usage_report_tool() -> ToolSchema {
+    ToolSchema {
+        name: GEAR_USAGE_REPORT.to_string(),
+        description: "Report how worn each piece of active gear is against its retirement distance, with replacement reminders".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(HashMap::new()),
+            required: None,
+        },
+        capability: ToolCapability::FitnessDataAnalysis,
+        output_schema: result_schema(&[("gear", "array"), ("nearing_retirement", "array"), ("replacement_due", "array"), ("insights", "array"), ("note", "string")]),
+        examples: vec![],
+    }
+}
+
+/// Create the add_gear tool schema
+fn create_add_gear_tool() -> ToolSchema {
+    let mut properties = gear_properties();
+
+    properties.insert(
+        "gear_type".to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("'shoes', 'bike' or 'other'".to_string()),
+        },
+    );
+
+    properties.insert(
+        "distance_km".to_string(),
+        PropertySchema {
+            property_type: "number".to_string(),
+            description: Some("Distance already on the gear (default: 0)".to_string()),
+        },
+    );
+
+    properties.insert(
+        "primary".to_string(),
+        PropertySchema {
+            property_type: "boolean".to_string(),
+            description: Some("Whether this is the default gear of its type".to_string()),
+        },
+    );
+
+    ToolSchema {
+        name: ADD_GEAR.to_string(),
+        description: "Add shoes, a bike or other gear that no provider tracks".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: Some(vec!["name".to_string(), "gear_type".to_string()]),
+        },
+        capability: ToolCapability::FitnessDataAnalysis,
+        output_schema: result_schema(&[("gear", "object")]),
+        examples: vec![],
+    }
+}
+
+/// Create the update_gear tool schema
+fn create_update_gear_tool() -> ToolSchema {
+    let mut properties = gear_properties();
+
+    properties.insert(
+        GEAR_ID.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("ID of the gear, as listed by get_gear".to_string()),
+        },
+    );
+
+    properties.insert(
+        "retired".to_string(),
+        PropertySchema {
+            property_type: "boolean".to_string(),
+            description: Some("Retire the gear, or bring it back into use".to_string()),
+        },
+    );
+
+    ToolSchema {
+        name: UPDATE_GEAR.to_string(),
+        description: "Set a retirement distance for gear, retire it, or edit its details; omitted fields keep their values".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: Some(vec![GEAR_ID.to_string()]),
+        },
+        capability: ToolCapability::FitnessDataAnalysis,
+        output_schema: result_schema(&[("gear", "object")]),
+        examples: vec![],
+    }
+}
+
+/// Gear fields shared by add_gear and update_gear
+fn gear_properties() -> HashMap<String, PropertySchema> {
+    let mut properties = HashMap::new();
+
+    for (name, property_type, description) in [
+        ("name", "string", "Gear name, e.g. 'Pegasus 40'"),
+        ("brand", "string", "Manufacturer"),
+        ("model", "string", "Model name"),
+        (
+            "retirement_distance_km",
+            "number",
+            "Distance after which to replace the gear (shoes default to 800 km)",
+        ),
+    ] {
+        properties.insert(
+            name.to_string(),
+            PropertySchema {
+                property_type: property_type.to_string(),
+                description: Some(description.to_string()),
+            },
+        );
+    }
+
+    properties
+}
+
+/// Create the get_athlete_thresholds tool schema
+fn create_get_athlete_thresholds_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        REFRESH.to_string(),
+        PropertySchema {
+            property_type: "boolean".to_string(),
+            description: Some(
+                "Re-estimate from recent activities even if a stored estimate is under a week old (default: false)"
+                    .to_string(),
+            ),
+        },
+    );
+
+    properties.insert(
+        INCLUDE_HISTORY.to_string(),
+        PropertySchema {
+            property_type: "boolean".to_string(),
+            description: Some("Include earlier estimates (default: false)".to_string()),
+        },
+    );
+
+    ToolSchema {
+        name: GET_ATHLETE_THRESHOLDS.to_string(),
+        description: "Estimate max heart rate, lactate threshold heart rate, FTP and threshold pace from activity history, with confidence and personal heart rate zones".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: None,
+        },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("thresholds", "object"), ("threshold_pace_formatted", "string"), ("heart_rate_zones", "object"), ("estimated_at", "string"), ("history", "array")]),
+        examples: vec![],
+    }
+}
+
+/// Create the list_my_routes tool schema
+fn create_list_my_routes_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        "sport_type".to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some(
+                "Only routes of this sport ('run', 'ride', 'walk', 'swim', ...)".to_string(),
+            ),
+        },
+    );
+
+    properties.insert(
+        LIMIT.to_string(),
+        PropertySchema {
+            property_type: "number".to_string(),
+            description: Some("Maximum number of routes to return (default: 10)".to_string()),
+        },
+    );
+
+    ToolSchema {
+        name: LIST_MY_ROUTES.to_string(),
+        description: "List the routes the user runs or rides repeatedly, grouped from activity GPS tracks, with a place name, distance, number of efforts, best time and pace trend".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: None,
+        },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("activities_analyzed", "number"), ("activities_with_gps", "number"), ("routes_found", "number"), ("routes", "array")]),
+        examples: vec![],
+    }
+}
+
+/// Create the route_history tool schema
+fn create_route_history_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        ROUTE_ID.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("Route ID from list_my_routes".to_string()),
+        },
+    );
+
+    properties.insert(
+        ACTIVITY_ID.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("An activity on the route, instead of route_id".to_string()),
+        },
+    );
+
+    ToolSchema {
+        name: ROUTE_HISTORY.to_string(),
+        description: "Every effort on one of the user's repeated routes, ranked by pace, with the pace trend - answers questions like 'am I getting faster on my usual loop?'".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: None,
+        },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("route", "object"), ("efforts", "array"), ("insights", "array")]),
+        examples: vec![],
+    }
+}
+
+/// Create the detect_anomalies tool schema
+fn create_detect_anomalies_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        ACTIVITY_ID.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some(
+                "Check only this activity; otherwise every activity in the last `days`".to_string(),
+            ),
+        },
+    );
+
+    properties.insert(
+        DAYS.to_string(),
+        PropertySchema {
+            property_type: "number".to_string(),
+            description: Some("Days of activities to check (default: 30, max: 365)".to_string()),
+        },
+    );
+
+    ToolSchema {
+        name: DETECT_ANOMALIES.to_string(),
+        description: "Find activities that stand out from the user's recent training (heart rate high for the pace, cardiac drift, sudden performance drops) and recordings with data problems (GPS jumps, heart rate strap dropouts, heart rate locked to cadence, power spikes), each with a severity and explanation".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: None,
+        },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("days", "number"), ("activities_analyzed", "number"), ("streams_analyzed", "number"), ("activities_with_anomalies", "number"), ("activities", "array"), ("insights", "array")]),
+        examples: vec![],
+    }
+}
+
+/// Create the query_activities tool schema
+fn create_query_activities_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    for (name, property_type, description) in [
+        (PROVIDER, "string", "Fitness provider name (defaults to every connected provider)"),
+        ("sport_type", "string", "Sport families ('run', 'ride', 'swim', 'walk', ...) or exact sport types ('trail_running'), comma-separated"),
+        ("start_date", "string", "Earliest start, YYYY-MM-DD or RFC 3339"),
+        (END_DATE, "string", "Latest start, YYYY-MM-DD (inclusive) or RFC 3339"),
+        ("min_distance_km", "number", "Minimum distance in km"),
+        ("max_distance_km", "number", "Maximum distance in km"),
+        ("min_duration_minutes", "number", "Minimum moving time in minutes"),
+        ("max_duration_minutes", "number", "Maximum moving time in minutes"),
+        ("min_elevation_gain", "number", "Minimum elevation gain in meters"),
+        ("max_elevation_gain", "number", "Maximum elevation gain in meters"),
+        ("location", "string", "Text to find in the city, region, country or trail name"),
+        ("near_latitude", "number", "Latitude the activity must start near"),
+        ("near_longitude", "number", "Longitude the activity must start near"),
+        ("radius_km", "number", "Radius around near_latitude/near_longitude (default: 10)"),
+        ("has_gps", "boolean", "Only activities with (true) or without (false) GPS data"),
+        ("min_temperature", "number", "Minimum measured temperature at the start, °C"),
+        ("max_temperature", "number", "Maximum measured temperature at the start, °C"),
+        ("weather_conditions", "string", "Text to find in the measured weather conditions ('rain', 'snow', ...)"),
+        ("group_by", "string", "Group into calendar periods ('week', 'month', 'year')"),
+        ("sort_by", "string", "Rank by 'date' (default), 'distance', 'duration', 'elevation_gain', 'pace', 'speed', 'heart_rate', or 'count' (with group_by)"),
+        ("order", "string", "'desc' or 'asc' (default: fastest first for pace, largest or latest first otherwise)"),
+        (LIMIT, "number", "Activities (and periods) to return, 1-100 (default: 10)"),
+    ] {
+        properties.insert(
+            name.to_string(),
+            PropertySchema {
+                property_type: property_type.to_string(),
+                description: Some(description.to_string()),
+            },
+        );
+    }
+
+    ToolSchema {
+        name: QUERY_ACTIVITIES.to_string(),
+        description: "Search the user's full activity history with filters on sport, dates, distance, duration, elevation, location and weather, optionally grouped by week, month or year, and ranked - answers questions like 'my longest run in 2024' or 'which month did I ride the most?' in one call".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: None,
+        },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("activities_searched", "number"), ("matched", "number"), ("totals", "object"), ("group_by", "string"), ("groups", "array"), ("sort_by", "string"), ("order", "string"), ("activities", "array"), ("notes", "array")]),
+        examples: vec![],
+    }
+}
+
+/// Create the get_streaks tool schema
+fn create_get_streaks_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    for (name, property_type, description) in [
+        (
+            PROVIDER,
+            "string",
+            "Fitness provider name (defaults to every connected provider)",
+        ),
+        (
+            "sport_type",
+            "string",
+            "Only count these sport families or exact sport types, comma-separated",
+        ),
+        (
+            "start_date",
+            "string",
+            "Ignore activities before this date, YYYY-MM-DD",
+        ),
+        (
+            END_DATE,
+            "string",
+            "Ignore activities after this date, YYYY-MM-DD",
+        ),
+        (
+            "min_distance_km",
+            "number",
+            "Only count activities of at least this distance, e.g. 10 for days with a 10 km run",
+        ),
+        (
+            "max_distance_km",
+            "number",
+            "Only count activities of at most this distance",
+        ),
+        (
+            "min_duration_minutes",
+            "number",
+            "Only count activities of at least this many minutes",
+        ),
+        (
+            "max_duration_minutes",
+            "number",
+            "Only count activities of at most this many minutes",
+        ),
+        (
+            "min_elevation_gain",
+            "number",
+            "Only count activities climbing at least this many meters",
+        ),
+        (
+            "max_elevation_gain",
+            "number",
+            "Only count activities climbing at most this many meters",
+        ),
+        (
+            "location",
+            "string",
+            "Only count activities whose city, region, country or trail name contains this text",
+        ),
+        (
+            "has_gps",
+            "boolean",
+            "Only count activities with (true) or without (false) GPS data",
+        ),
+        (
+            "week_target",
+            "number",
+            "Activities a week needs to extend a week streak, 1-14 (default: 1)",
+        ),
+    ] {
+        properties.insert(
+            name.to_string(),
+            PropertySchema {
+                property_type: property_type.to_string(),
+                description: Some(description.to_string()),
+            },
+        );
+    }
+
+    ToolSchema {
+        name: GET_STREAKS.to_string(),
+        description: "Current and longest streaks of consecutive active days and weeks, overall and per sport, with a 12-week consistency score and streak milestones - optionally counting only activities that meet a condition, e.g. 'how many days in a row have I run at least 10 km?'".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: None,
+        },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("as_of", "string"), ("week_target", "number"), ("activities_searched", "number"), ("overall", "object"), ("sports", "array"), ("milestones", "array"), ("insights", "array"), ("notes", "array")]),
+        examples: vec![],
+    }
+}
+
+/// Create the get_digest tool schema
+fn create_get_digest_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    for (name, property_type, description) in [
+        (
+            "period",
+            "string",
+            "Digest period: weekly, monthly or yearly (default: weekly)",
+        ),
+        (
+            "date",
+            "string",
+            "Any day in the period, YYYY-MM-DD (default: the last finished period)",
+        ),
+        (
+            "refresh",
+            "boolean",
+            "Rebuild a stored digest from the current data (default: false)",
+        ),
+    ] {
+        properties.insert(
+            name.to_string(),
+            PropertySchema {
+                property_type: property_type.to_string(),
+                description: Some(description.to_string()),
+            },
+        );
+    }
+
+    ToolSchema {
+        name: GET_DIGEST.to_string(),
+        description: "Weekly, monthly or year-in-review training digest: volume against the previous period, heart rate intensity distribution, personal records, goal progress and training load, with a Markdown summary. Digests of finished periods are stored and are also readable as digest:// resources".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: None,
+        },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("period", "string"), ("period_start", "string"), ("title", "string"), ("generated_at", "string"), ("delivered_at", "string"), ("resource_uri", "string"), ("summary", "string"), ("digest", "object")]),
+        examples: vec![],
+    }
+}
+
+/// Create the update_analysis_settings tool schema
+fn create_update_analysis_settings_tool() -> ToolSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        SETTINGS.to_string(),
+        PropertySchema {
+            property_type: "object".to_string(),
+            description: Some(
+                "Overrides shaped like the intelligence config, e.g. {\"zone_thresholds\": {\"tempo_max\": 85}}; null restores the inherited value"
+                    .to_string(),
+            ),
+        },
+    );
+
+    properties.insert(
+        RESET.to_string(),
+        PropertySchema {
+            property_type: "boolean".to_string(),
+            description: Some(
+                "Clear your existing overrides before applying settings (default: false)"
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSchema {
+        name: UPDATE_ANALYSIS_SETTINGS.to_string(),
+        description: "Override the effort thresholds, heart rate zone model, weather mapping or PR detection used to analyze your data, on top of server and club defaults; call without arguments to see the current settings".to_string(),
+        input_schema: JsonSchema {
+            schema_type: "object".to_string(),
+            properties: Some(properties),
+            required: None,
+        },
+        capability: ToolCapability::ActivityIntelligence,
+        output_schema: result_schema(&[("tenant_id", "string"), ("tenant_overrides", "object"), ("user_overrides", "object"), ("effective", "object")]),
+        examples: vec![],
+    }
+}
+
+/// Input schema shared by the wellness tools
+fn wellness_schema() -> JsonSchema {
+    let mut properties = HashMap::new();
+
+    properties.insert(
+        PROVIDER.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("Wellness provider name (default: 'fitbit')".to_string()),
+        },
+    );
+
+    properties.insert(
+        DAYS.to_string(),
+        PropertySchema {
+            property_type: "number".to_string(),
+            description: Some(
+                "Number of days to include, ending at end_date (default: 7, max: 90)".to_string(),
+            ),
+        },
+    );
+
+    properties.insert(
+        END_DATE.to_string(),
+        PropertySchema {
+            property_type: "string".to_string(),
+            description: Some("Last day to include, YYYY-MM-DD (default: today)".to_string()),
+        },
+    );
+
+    JsonSchema {
+        schema_type: "object".to_string(),
+        properties: Some(properties),
+        required: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1007,7 +2227,7 @@ mod tests {
 
         // Check that tools are available via tools/list
         let available_tools = get_tools();
-        assert_eq!(available_tools.len(), 21);
+        assert_eq!(available_tools.len(), 45);
 
         let tool_names: Vec<&str> = available_tools.iter().map(|t| t.name.as_str()).collect();
 
@@ -25,7 +25,7 @@
 //! - `PersonalRecord`: Individual performance records
 //! - `SportType`: Enumeration of supported activity types
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -91,6 +91,8 @@ impl UserTier {
 ///     region: Some("Quebec".to_string()),
 ///     country: Some("Canada".to_string()),
 ///     trail_name: Some("Mount Royal Trail".to_string()),
+///     gear_id: None,
+///     summary_polyline: None,
 ///     provider: "strava".to_string(),
 /// };
 /// ```
@@ -132,6 +134,12 @@ pub struct Activity {
     pub country: Option<String>,
     /// Trail or route name if available (e.g., "Saint-Hippolyte trail")
     pub trail_name: Option<String>,
+    /// Shoes or bike used for the activity (provider gear ID)
+    #[serde(default)]
+    pub gear_id: Option<String>,
+    /// Simplified GPS track in Google encoded polyline format
+    #[serde(default)]
+    pub summary_polyline: Option<String>,
     /// Source provider of this activity data
     pub provider: String,
 }
@@ -157,11 +165,34 @@ impl Default for Activity {
             region: None,
             country: None,
             trail_name: None,
+            gear_id: None,
+            summary_polyline: None,
             provider: "test".to_string(),
         }
     }
 }
 
+/// Time series recorded during an activity
+///
+/// Every present series has one sample per entry in `time`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActivityStreams {
+    /// Seconds since the start of the activity
+    pub time: Vec<u32>,
+    /// Heart rate in bpm
+    pub heart_rate: Option<Vec<u32>>,
+    /// Power in watts
+    pub power: Option<Vec<u32>>,
+    /// Distance covered so far, in meters
+    pub distance: Option<Vec<f64>>,
+    /// Altitude in meters
+    pub altitude: Option<Vec<f64>>,
+    /// Cadence as the provider reports it: revolutions per minute on the
+    /// bike, steps per minute of one leg when running
+    #[serde(default)]
+    pub cadence: Option<Vec<u32>>,
+}
+
 /// Enumeration of supported sport/activity types
 ///
 /// This enum covers the most common fitness activities across all providers.
@@ -415,6 +446,7 @@ impl SportType {
 ///     lastname: Some("Doe".to_string()),
 ///     profile_picture: Some("https://example.com/avatar.jpg".to_string()),
 ///     provider: "strava".to_string(),
+///     gear: vec![],
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -431,6 +463,78 @@ pub struct Athlete {
     pub profile_picture: Option<String>,
     /// Source provider of this athlete data
     pub provider: String,
+    /// Shoes, bikes and other equipment registered with the provider
+    #[serde(default)]
+    pub gear: Vec<Gear>,
+}
+
+/// Kind of equipment an activity was done with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GearType {
+    Shoes,
+    Bike,
+    Other,
+}
+
+/// Shoes, a bike or other equipment with its cumulative usage
+///
+/// Provider gear carries the provider's lifetime distance. Gear the user
+/// adds by hand starts from the distance they entered and accumulates the
+/// activities tagged with its `id`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gear {
+    /// Provider gear ID (e.g. Strava's "g12345"), matched by `Activity::gear_id`
+    pub id: String,
+    /// Name the athlete gave the gear (e.g. "Pegasus 40")
+    pub name: String,
+    /// Shoes, bike or other
+    pub gear_type: GearType,
+    /// Manufacturer
+    pub brand: Option<String>,
+    /// Model name
+    pub model: Option<String>,
+    /// Source provider, or "manual" for gear the user added
+    pub provider: String,
+    /// Cumulative distance in meters
+    pub distance_meters: f64,
+    /// Cumulative moving time in seconds
+    #[serde(default)]
+    pub moving_time_seconds: u64,
+    /// Number of activities done with the gear
+    #[serde(default)]
+    pub activity_count: u64,
+    /// Distance after which the athlete wants to replace the gear
+    #[serde(default)]
+    pub retirement_distance_meters: Option<f64>,
+    /// Whether this is the athlete's default gear of its type
+    #[serde(default)]
+    pub primary: bool,
+    /// Whether the gear has been retired
+    #[serde(default)]
+    pub retired: bool,
+}
+
+/// A training digest of one finished week, month or year
+///
+/// Kept per user so it can be read back without rebuilding it and sent to
+/// the delivery sinks once.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredDigest {
+    /// `weekly`, `monthly` or `yearly`
+    pub period: String,
+    /// First day of the period
+    pub period_start: NaiveDate,
+    /// Human-readable name of the period (e.g. "June 2024")
+    pub title: String,
+    /// When the digest was built
+    pub generated_at: DateTime<Utc>,
+    /// When the scheduler handled it, by delivering it or skipping a period without activity
+    pub delivered_at: Option<DateTime<Utc>>,
+    /// The digest rendered as Markdown
+    pub summary: String,
+    /// The digest's sections as JSON
+    pub content: serde_json::Value,
 }
 
 /// Aggregated fitness statistics for an athlete
@@ -501,8 +605,8 @@ pub enum PrMetric {
 
 /// Represents a user in the multi-tenant system
 ///
-/// Users are authenticated through OAuth providers and have encrypted tokens
-/// stored securely for accessing their fitness data.
+/// Users authenticate with the server directly; their fitness provider tokens
+/// are stored separately as [`ProviderConnection`]s.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     /// Unique user identifier
@@ -515,10 +619,6 @@ pub struct User {
     pub password_hash: String,
     /// User tier for rate limiting
     pub tier: UserTier,
-    /// Encrypted Strava tokens
-    pub strava_token: Option<EncryptedToken>,
-    /// Encrypted Fitbit tokens
-    pub fitbit_token: Option<EncryptedToken>,
     /// When the user account was created
     pub created_at: DateTime<Utc>,
     /// Last time user accessed the system
@@ -545,6 +645,147 @@ pub struct EncryptedToken {
     pub nonce: String,
 }
 
+/// A user's connection to a fitness provider
+///
+/// Connections are keyed by provider id, so any provider registered with the
+/// server can be stored without schema changes. Token material is never part
+/// of this struct; use `DatabaseProvider::get_provider_token` to decrypt it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderConnection {
+    /// User owning the connection
+    pub user_id: Uuid,
+    /// Provider identifier (e.g. "strava", "fitbit")
+    pub provider: String,
+    /// The user's id on the provider side (athlete id), when known
+    pub provider_user_id: Option<String>,
+    /// Granted OAuth scopes
+    pub scope: String,
+    /// When the access token expires
+    pub expires_at: DateTime<Utc>,
+    /// When the connection was first made
+    pub connected_at: DateTime<Utc>,
+    /// When the tokens were last updated
+    pub updated_at: DateTime<Utc>,
+    /// The provider rejected the refresh token; the user must reconnect
+    pub needs_reauth: bool,
+    /// Why re-authorization is needed, when known
+    pub reauth_reason: Option<String>,
+}
+
+impl ProviderConnection {
+    /// Whether the stored access token has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    /// Status reported to clients: `active` or `needs_reauth`
+    pub const fn status(&self) -> &'static str {
+        if self.needs_reauth {
+            "needs_reauth"
+        } else {
+            "active"
+        }
+    }
+}
+
+/// An activity synced into local storage from a provider
+///
+/// Deleted activities are kept as tombstones so consumers can tell a
+/// removal apart from an activity that was never synced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncedActivity {
+    /// User owning the activity
+    pub user_id: Uuid,
+    /// The activity as last fetched from the provider
+    pub activity: Activity,
+    /// When the activity was last fetched
+    pub synced_at: DateTime<Utc>,
+    /// When the provider reported the activity as deleted
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// Minutes spent in each sleep stage
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SleepStages {
+    pub deep_minutes: u32,
+    pub light_minutes: u32,
+    pub rem_minutes: u32,
+    pub awake_minutes: u32,
+}
+
+/// The main sleep period that ended on a given day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SleepSummary {
+    /// When the user fell asleep, in the user's local time (unknown for manual entries)
+    pub start_time: Option<NaiveDateTime>,
+    /// When the user woke up, in the user's local time (unknown for manual entries)
+    pub end_time: Option<NaiveDateTime>,
+    /// Total time asleep
+    pub minutes_asleep: u32,
+    /// Total time in bed, including time awake
+    pub minutes_in_bed: u32,
+    /// Provider-computed sleep efficiency (0-100)
+    pub efficiency: Option<u32>,
+    /// Stage breakdown, when the device recorded stages
+    pub stages: Option<SleepStages>,
+    /// Self-reported quality from 1 (poor) to 5 (great)
+    #[serde(default)]
+    pub quality: Option<u8>,
+}
+
+/// Wellness metrics a provider recorded for one calendar day
+///
+/// Every metric is optional: devices only report what they measure, and a
+/// day without the watch on still has steps from the phone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyWellness {
+    /// Calendar day in the user's local time
+    pub date: NaiveDate,
+    /// Provider the metrics came from
+    pub provider: String,
+    /// Sleep that ended on this day
+    pub sleep: Option<SleepSummary>,
+    /// Resting heart rate in bpm
+    pub resting_heart_rate: Option<u32>,
+    /// Heart rate variability (nightly RMSSD) in milliseconds
+    pub hrv_rmssd_ms: Option<f64>,
+    /// Average blood oxygen saturation during sleep, in percent
+    pub spo2_percent: Option<f64>,
+    /// Total steps
+    pub steps: Option<u32>,
+    /// Minutes spent in fat-burn, cardio or peak heart rate zones
+    pub active_zone_minutes: Option<u32>,
+}
+
+impl DailyWellness {
+    /// An empty record for a day, to be filled in metric by metric
+    pub fn new(date: NaiveDate, provider: &str) -> Self {
+        Self {
+            date,
+            provider: provider.to_string(),
+            sleep: None,
+            resting_heart_rate: None,
+            hrv_rmssd_ms: None,
+            spo2_percent: None,
+            steps: None,
+            active_zone_minutes: None,
+        }
+    }
+
+    /// Fill in metrics from another record for the same day; `other` wins
+    /// wherever both have a value
+    pub fn overlay(&mut self, other: &DailyWellness) {
+        if other.sleep.is_some() {
+            self.sleep.clone_from(&other.sleep);
+        }
+        self.resting_heart_rate = other.resting_heart_rate.or(self.resting_heart_rate);
+        self.hrv_rmssd_ms = other.hrv_rmssd_ms.or(self.hrv_rmssd_ms);
+        self.spo2_percent = other.spo2_percent.or(self.spo2_percent);
+        self.steps = other.steps.or(self.steps);
+        self.active_zone_minutes = other.active_zone_minutes.or(self.active_zone_minutes);
+    }
+}
+
 /// Decrypted OAuth token for API calls
 ///
 /// This is never stored - only exists in memory during API requests.
@@ -611,40 +852,12 @@ impl User {
             display_name,
             password_hash,
             tier: UserTier::Starter, // Default to starter tier
-            strava_token: None,
-            fitbit_token: None,
             created_at: now,
             last_active: now,
             is_active: true,
         }
     }
 
-    /// Check if user has valid Strava token
-    pub fn has_strava_access(&self) -> bool {
-        self.strava_token
-            .as_ref()
-            .is_some_and(|token| token.expires_at > Utc::now())
-    }
-
-    /// Check if user has valid Fitbit token
-    pub fn has_fitbit_access(&self) -> bool {
-        self.fitbit_token
-            .as_ref()
-            .is_some_and(|token| token.expires_at > Utc::now())
-    }
-
-    /// Get list of available providers for this user
-    pub fn available_providers(&self) -> Vec<String> {
-        let mut providers = Vec::new();
-        if self.has_strava_access() {
-            providers.push("strava".to_string());
-        }
-        if self.has_fitbit_access() {
-            providers.push("fitbit".to_string());
-        }
-        providers
-    }
-
     /// Update last active timestamp
     pub fn update_last_active(&mut self) {
         self.last_active = Utc::now();
@@ -773,6 +986,8 @@ mod tests {
             region: Some("Quebec".to_string()),
             country: Some("Canada".to_string()),
             trail_name: Some("Mount Royal Trail".to_string()),
+            gear_id: None,
+            summary_polyline: None,
             provider: "strava".to_string(),
         }
     }
@@ -786,6 +1001,7 @@ mod tests {
             lastname: Some("Doe".to_string()),
             profile_picture: Some("https://example.com/avatar.jpg".to_string()),
             provider: "strava".to_string(),
+            gear: vec![],
         }
     }
 
@@ -953,6 +1169,8 @@ mod tests {
             region: None,
             country: None,
             trail_name: None,
+            gear_id: None,
+            summary_polyline: None,
             provider: "manual".to_string(),
         };
 
@@ -10,13 +10,36 @@
 //! Handles the complete OAuth flow from authorization to token management.
 
 use super::{CallbackResponse, OAuthError, OAuthProvider, ProviderRegistry, TokenData};
+use crate::config::environment::OAuthConfig;
 use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::models::DecryptedToken;
 use anyhow::Result;
 use std::collections::HashMap;
-use std::sync::Arc;
-use tracing::{info, warn};
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+/// Per-connection refresh locks keyed by user and provider
+type RefreshLocks = HashMap<(Uuid, String), Arc<tokio::sync::Mutex<()>>>;
+
+/// Lock held while one connection's token is refreshed
+///
+/// Shared by every manager so concurrent requests for the same user and
+/// provider wait for one refresh instead of spending the refresh token twice.
+fn refresh_lock(user_id: Uuid, provider: &str) -> Arc<tokio::sync::Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<RefreshLocks>> = OnceLock::new();
+    let mut locks = LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    // Drop locks nobody is holding or waiting on
+    locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+    locks
+        .entry((user_id, provider.to_string()))
+        .or_default()
+        .clone()
+}
+
 /// Central OAuth manager
 pub struct OAuthManager {
     database: Arc<Database>,
@@ -44,12 +67,41 @@ impl OAuthManager {
         }
     }
 
+    /// Create an OAuth manager with every provider configured in `config`
+    ///
+    /// Providers missing credentials are skipped; asking for them later
+    /// yields `OAuthError::UnsupportedProvider`.
+    pub fn from_config(database: Arc<Database>, config: &OAuthConfig) -> Self {
+        let mut manager = Self::new(database);
+
+        let registry = crate::providers::registry();
+        for name in registry.provider_names() {
+            let Some(descriptor) = registry.get(name) else {
+                continue;
+            };
+            match descriptor.oauth_provider(config) {
+                Ok(provider) => manager.register_provider(provider),
+                Err(e) => debug!(
+                    "{} OAuth provider not configured: {}",
+                    descriptor.display_name, e
+                ),
+            }
+        }
+
+        manager
+    }
+
     /// Register OAuth provider
     pub fn register_provider(&mut self, provider: Box<dyn OAuthProvider>) {
         info!("Registering OAuth provider: {}", provider.name());
         self.registry.register_provider(provider);
     }
 
+    /// Whether an OAuth provider with this name is registered
+    pub fn supports(&self, provider: &str) -> bool {
+        self.registry.get_provider(provider).is_some()
+    }
+
     /// Generate authorization URL for a provider
     pub async fn generate_auth_url(
         &self,
@@ -152,6 +204,11 @@ impl OAuthManager {
             None => return Ok(None), // No token stored
         };
 
+        // A revoked grant won't come back by retrying; the user has to reconnect
+        if self.needs_reauth(user_id, provider).await? {
+            return Err(OAuthError::ReauthorizationRequired(provider.to_string()));
+        }
+
         // Get OAuth provider
         let oauth_provider = self
             .registry
@@ -163,20 +220,109 @@ impl OAuthManager {
             return Ok(Some(token_data));
         }
 
-        // Token needs refresh
+        self.refresh_stored_token(user_id, &token_data)
+            .await
+            .map(Some)
+    }
+
+    /// Refresh a stored token now, regardless of its expiry
+    ///
+    /// A rejected refresh token flags the connection as needing
+    /// re-authorization.
+    pub async fn refresh_connection(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+    ) -> Result<TokenData, OAuthError> {
+        let token_data = self
+            .get_token_data(user_id, provider)
+            .await?
+            .ok_or_else(|| {
+                OAuthError::TokenRefreshFailed(format!("No {} token stored", provider))
+            })?;
+
+        self.refresh_stored_token(user_id, &token_data).await
+    }
+
+    /// Refresh a stored token that expires before `cutoff`
+    ///
+    /// Used by the background refresher, which picks connections by expiry
+    /// and may find one a tool call has renewed since; that token is returned
+    /// as is.
+    pub async fn refresh_expiring(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        cutoff: chrono::DateTime<chrono::Utc>,
+    ) -> Result<TokenData, OAuthError> {
+        let token_data = self
+            .get_token_data(user_id, provider)
+            .await?
+            .ok_or_else(|| {
+                OAuthError::TokenRefreshFailed(format!("No {} token stored", provider))
+            })?;
+        if token_data.expires_at >= cutoff {
+            return Ok(token_data);
+        }
+
+        self.refresh_stored_token(user_id, &token_data).await
+    }
+
+    /// Exchange the refresh token and store the result
+    ///
+    /// One refresh per connection runs at a time; callers that waited get the
+    /// token the first one stored.
+    async fn refresh_stored_token(
+        &self,
+        user_id: Uuid,
+        token_data: &TokenData,
+    ) -> Result<TokenData, OAuthError> {
+        let provider = token_data.provider.as_str();
+        let oauth_provider = self
+            .registry
+            .get_provider(provider)
+            .ok_or_else(|| OAuthError::UnsupportedProvider(provider.to_string()))?;
+
+        let lock = refresh_lock(user_id, provider);
+        let _refreshing = lock.lock().await;
+        if let Some(current) = self.replaced_token(user_id, token_data).await? {
+            return Ok(current);
+        }
+
         info!(
             "Refreshing token for user {} provider {}",
             user_id, provider
         );
 
-        let new_token_data = oauth_provider
+        match oauth_provider
             .refresh_token(&token_data.refresh_token)
-            .await?;
-
-        // Store new tokens
-        self.store_tokens(user_id, &new_token_data).await?;
-
-        Ok(Some(new_token_data))
+            .await
+        {
+            Ok(new_token_data) => {
+                self.store_tokens(user_id, &new_token_data).await?;
+                Ok(new_token_data)
+            }
+            Err(OAuthError::ReauthorizationRequired(provider)) => {
+                // Another server may have used the refresh token first
+                if let Some(current) = self.replaced_token(user_id, token_data).await? {
+                    return Ok(current);
+                }
+                warn!(
+                    "{} rejected the refresh token for user {}, re-authorization required",
+                    provider, user_id
+                );
+                self.database
+                    .mark_provider_connection_needs_reauth(
+                        user_id,
+                        &provider,
+                        "Refresh token was revoked or expired",
+                    )
+                    .await
+                    .map_err(|e| OAuthError::DatabaseError(e.to_string()))?;
+                Err(OAuthError::ReauthorizationRequired(provider))
+            }
+            Err(e) => Err(e),
+        }
     }
 
     /// Get connection status for user
@@ -231,58 +377,58 @@ impl OAuthManager {
         Ok(state_data)
     }
 
+    /// The stored token, if it is no longer the one a refresh started from
+    async fn replaced_token(
+        &self,
+        user_id: Uuid,
+        token_data: &TokenData,
+    ) -> Result<Option<TokenData>, OAuthError> {
+        Ok(self
+            .get_token_data(user_id, &token_data.provider)
+            .await?
+            .filter(|current| {
+                current.access_token != token_data.access_token
+                    || current.expires_at != token_data.expires_at
+            }))
+    }
+
+    /// Whether the stored connection has been flagged for re-authorization
+    async fn needs_reauth(&self, user_id: Uuid, provider: &str) -> Result<bool, OAuthError> {
+        let connection = self
+            .database
+            .get_provider_connection(user_id, provider)
+            .await
+            .map_err(|e| OAuthError::DatabaseError(e.to_string()))?;
+
+        Ok(connection.is_some_and(|connection| connection.needs_reauth))
+    }
+
     /// Store tokens in database
     async fn store_tokens(&self, user_id: Uuid, token_data: &TokenData) -> Result<(), OAuthError> {
-        match token_data.provider.as_str() {
-            "strava" => {
-                self.database
-                    .update_strava_token(
-                        user_id,
-                        &token_data.access_token,
-                        &token_data.refresh_token,
-                        token_data.expires_at,
-                        token_data.scopes.clone(),
-                    )
-                    .await
-                    .map_err(|e| OAuthError::DatabaseError(e.to_string()))?;
-            }
-            "fitbit" => {
-                self.database
-                    .update_fitbit_token(
-                        user_id,
-                        &token_data.access_token,
-                        &token_data.refresh_token,
-                        token_data.expires_at,
-                        token_data.scopes.clone(),
-                    )
-                    .await
-                    .map_err(|e| OAuthError::DatabaseError(e.to_string()))?;
-            }
-            _ => return Err(OAuthError::UnsupportedProvider(token_data.provider.clone())),
-        }
-
-        Ok(())
+        let token = DecryptedToken {
+            access_token: token_data.access_token.clone(),
+            refresh_token: token_data.refresh_token.clone(),
+            expires_at: token_data.expires_at,
+            scope: token_data.scopes.clone(),
+        };
+        self.database
+            .upsert_provider_token(
+                user_id,
+                &token_data.provider,
+                &token,
+                token_data.provider_user_id.as_deref(),
+            )
+            .await
+            .map_err(|e| OAuthError::DatabaseError(e.to_string()))
     }
 
     /// Remove tokens from database
     async fn remove_tokens(&self, user_id: Uuid, provider: &str) -> Result<(), OAuthError> {
-        match provider {
-            "strava" => {
-                self.database
-                    .clear_strava_token(user_id)
-                    .await
-                    .map_err(|e| OAuthError::DatabaseError(e.to_string()))?;
-                Ok(())
-            }
-            "fitbit" => {
-                self.database
-                    .clear_fitbit_token(user_id)
-                    .await
-                    .map_err(|e| OAuthError::DatabaseError(e.to_string()))?;
-                Ok(())
-            }
-            _ => Err(OAuthError::UnsupportedProvider(provider.to_string())),
-        }
+        self.database
+            .delete_provider_connection(user_id, provider)
+            .await
+            .map_err(|e| OAuthError::DatabaseError(e.to_string()))?;
+        Ok(())
     }
 
     /// Get token data from database
@@ -291,30 +437,19 @@ impl OAuthManager {
         user_id: Uuid,
         provider: &str,
     ) -> Result<Option<TokenData>, OAuthError> {
-        match provider {
-            "strava" => match self.database.get_strava_token(user_id).await {
-                Ok(Some(token)) => Ok(Some(TokenData {
-                    access_token: token.access_token,
-                    refresh_token: token.refresh_token,
-                    expires_at: token.expires_at,
-                    scopes: token.scope,
-                    provider: "strava".to_string(),
-                })),
-                Ok(None) => Ok(None),
-                Err(e) => Err(OAuthError::DatabaseError(e.to_string())),
-            },
-            "fitbit" => match self.database.get_fitbit_token(user_id).await {
-                Ok(Some(token)) => Ok(Some(TokenData {
-                    access_token: token.access_token,
-                    refresh_token: token.refresh_token,
-                    expires_at: token.expires_at,
-                    scopes: token.scope,
-                    provider: "fitbit".to_string(),
-                })),
-                Ok(None) => Ok(None),
-                Err(e) => Err(OAuthError::DatabaseError(e.to_string())),
-            },
-            _ => Err(OAuthError::UnsupportedProvider(provider.to_string())),
-        }
+        let token = self
+            .database
+            .get_provider_token(user_id, provider)
+            .await
+            .map_err(|e| OAuthError::DatabaseError(e.to_string()))?;
+
+        Ok(token.map(|token| TokenData {
+            access_token: token.access_token,
+            refresh_token: token.refresh_token,
+            expires_at: token.expires_at,
+            scopes: token.scope,
+            provider: provider.to_string(),
+            provider_user_id: None,
+        }))
     }
 }
@@ -11,6 +11,7 @@
 
 pub mod manager;
 pub mod providers;
+pub mod refresher;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -25,6 +26,9 @@ pub struct TokenData {
     pub expires_at: chrono::DateTime<chrono::Utc>,
     pub scopes: String,
     pub provider: String,
+    /// The user's id at the provider, when the token response carries it
+    #[serde(default)]
+    pub provider_user_id: Option<String>,
 }
 
 /// OAuth authorization response
@@ -63,6 +67,9 @@ pub enum OAuthError {
     #[error("Token refresh failed: {0}")]
     TokenRefreshFailed(String),
 
+    #[error("Authorization revoked or expired, reconnect {0} to continue")]
+    ReauthorizationRequired(String),
+
     #[error("Invalid state parameter")]
     InvalidState,
 
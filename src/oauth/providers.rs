@@ -20,6 +20,7 @@ pub struct StravaOAuthProvider {
     client_id: String,
     client_secret: String,
     redirect_uri: String,
+    scopes: Vec<String>,
 }
 
 /// Strava token response format
@@ -29,6 +30,14 @@ struct StravaTokenResponse {
     refresh_token: String,
     expires_at: i64,
     scope: Option<String>,
+    /// Only present on the initial code exchange
+    #[serde(default)]
+    athlete: Option<StravaAthleteSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StravaAthleteSummary {
+    id: i64,
 }
 
 impl StravaOAuthProvider {
@@ -61,6 +70,10 @@ impl StravaOAuthProvider {
             client_id,
             client_secret,
             redirect_uri,
+            scopes: scopes_or_default(
+                &config.scopes,
+                crate::constants::oauth::STRAVA_DEFAULT_SCOPES,
+            ),
         })
     }
 
@@ -85,6 +98,7 @@ impl StravaOAuthProvider {
             client_id,
             client_secret,
             redirect_uri,
+            scopes: scopes_or_default(&[], crate::constants::oauth::STRAVA_DEFAULT_SCOPES),
         })
     }
 }
@@ -100,7 +114,7 @@ impl OAuthProvider for StravaOAuthProvider {
         _user_id: Uuid,
         state: String,
     ) -> Result<AuthorizationResponse, OAuthError> {
-        let scope = "read,activity:read_all";
+        let scope = self.scopes.join(",");
 
         let auth_base_url = crate::constants::env_config::strava_auth_url();
         let auth_url = format!(
@@ -108,7 +122,7 @@ impl OAuthProvider for StravaOAuthProvider {
             auth_base_url,
             urlencoding::encode(&self.client_id),
             urlencoding::encode(&self.redirect_uri),
-            urlencoding::encode(scope),
+            urlencoding::encode(&scope),
             urlencoding::encode(&state)
         );
 
@@ -139,10 +153,17 @@ impl OAuthProvider for StravaOAuthProvider {
             .await
             .map_err(|e| OAuthError::TokenExchangeFailed(e.to_string()))?;
 
+        let status = response.status();
         let response_text = response
             .text()
             .await
             .map_err(|e| OAuthError::TokenExchangeFailed(e.to_string()))?;
+        if !status.is_success() {
+            return Err(OAuthError::TokenExchangeFailed(format!(
+                "strava returned {}: {}",
+                status, response_text
+            )));
+        }
 
         let token_response: StravaTokenResponse = serde_json::from_str(&response_text)
             .map_err(|e| OAuthError::TokenExchangeFailed(format!("Parse error: {}", e)))?;
@@ -157,8 +178,9 @@ impl OAuthProvider for StravaOAuthProvider {
             expires_at,
             scopes: token_response
                 .scope
-                .unwrap_or_else(|| "read,activity:read_all".to_string()),
+                .unwrap_or_else(|| self.scopes.join(",")),
             provider: "strava".to_string(),
+            provider_user_id: token_response.athlete.map(|athlete| athlete.id.to_string()),
         })
     }
 
@@ -180,10 +202,14 @@ impl OAuthProvider for StravaOAuthProvider {
             .await
             .map_err(|e| OAuthError::TokenRefreshFailed(e.to_string()))?;
 
+        let status = response.status();
         let response_text = response
             .text()
             .await
             .map_err(|e| OAuthError::TokenRefreshFailed(e.to_string()))?;
+        if !status.is_success() {
+            return Err(refresh_error("strava", status, &response_text));
+        }
 
         let token_response: StravaTokenResponse = serde_json::from_str(&response_text)
             .map_err(|e| OAuthError::TokenRefreshFailed(format!("Parse error: {}", e)))?;
@@ -198,8 +224,9 @@ impl OAuthProvider for StravaOAuthProvider {
             expires_at,
             scopes: token_response
                 .scope
-                .unwrap_or_else(|| "read,activity:read_all".to_string()),
+                .unwrap_or_else(|| self.scopes.join(",")),
             provider: "strava".to_string(),
+            provider_user_id: token_response.athlete.map(|athlete| athlete.id.to_string()),
         })
     }
 
@@ -207,7 +234,7 @@ impl OAuthProvider for StravaOAuthProvider {
         let client = reqwest::Client::new();
 
         let response = client
-            .post("https://www.strava.com/oauth/deauthorize")
+            .post(crate::constants::env_config::strava_deauthorize_url())
             .form(&[("access_token", access_token)])
             .send()
             .await
@@ -236,6 +263,7 @@ pub struct FitbitOAuthProvider {
     client_id: String,
     client_secret: String,
     redirect_uri: String,
+    scopes: Vec<String>,
 }
 
 /// Fitbit token response format
@@ -245,6 +273,8 @@ struct FitbitTokenResponse {
     refresh_token: String,
     expires_in: i64,
     scope: String,
+    #[serde(default)]
+    user_id: Option<String>,
 }
 
 impl FitbitOAuthProvider {
@@ -277,6 +307,10 @@ impl FitbitOAuthProvider {
             client_id,
             client_secret,
             redirect_uri,
+            scopes: scopes_or_default(
+                &config.scopes,
+                crate::constants::oauth::FITBIT_DEFAULT_SCOPES,
+            ),
         })
     }
 
@@ -301,6 +335,7 @@ impl FitbitOAuthProvider {
             client_id,
             client_secret,
             redirect_uri,
+            scopes: scopes_or_default(&[], crate::constants::oauth::FITBIT_DEFAULT_SCOPES),
         })
     }
 }
@@ -316,13 +351,14 @@ impl OAuthProvider for FitbitOAuthProvider {
         _user_id: Uuid,
         state: String,
     ) -> Result<AuthorizationResponse, OAuthError> {
-        let scope = "activity heartrate location nutrition profile settings sleep social weight";
+        let scope = self.scopes.join(" ");
 
         let auth_url = format!(
-            "https://www.fitbit.com/oauth2/authorize?client_id={}&redirect_uri={}&response_type=code&scope={}&state={}",
+            "{}?client_id={}&redirect_uri={}&response_type=code&scope={}&state={}",
+            crate::constants::env_config::fitbit_auth_url(),
             urlencoding::encode(&self.client_id),
             urlencoding::encode(&self.redirect_uri),
-            urlencoding::encode(scope),
+            urlencoding::encode(&scope),
             urlencoding::encode(&state)
         );
 
@@ -349,17 +385,24 @@ impl OAuthProvider for FitbitOAuthProvider {
             general_purpose::STANDARD.encode(format!("{}:{}", self.client_id, self.client_secret));
 
         let response = client
-            .post("https://api.fitbit.com/oauth2/token")
+            .post(crate::constants::env_config::fitbit_token_url())
             .header("Authorization", format!("Basic {}", auth_header))
             .form(&params)
             .send()
             .await
             .map_err(|e| OAuthError::TokenExchangeFailed(e.to_string()))?;
 
+        let status = response.status();
         let response_text = response
             .text()
             .await
             .map_err(|e| OAuthError::TokenExchangeFailed(e.to_string()))?;
+        if !status.is_success() {
+            return Err(OAuthError::TokenExchangeFailed(format!(
+                "fitbit returned {}: {}",
+                status, response_text
+            )));
+        }
 
         let token_response: FitbitTokenResponse = serde_json::from_str(&response_text)
             .map_err(|e| OAuthError::TokenExchangeFailed(format!("Parse error: {}", e)))?;
@@ -372,6 +415,7 @@ impl OAuthProvider for FitbitOAuthProvider {
             expires_at,
             scopes: token_response.scope,
             provider: "fitbit".to_string(),
+            provider_user_id: token_response.user_id,
         })
     }
 
@@ -387,17 +431,21 @@ impl OAuthProvider for FitbitOAuthProvider {
             general_purpose::STANDARD.encode(format!("{}:{}", self.client_id, self.client_secret));
 
         let response = client
-            .post("https://api.fitbit.com/oauth2/token")
+            .post(crate::constants::env_config::fitbit_token_url())
             .header("Authorization", format!("Basic {}", auth_header))
             .form(&params)
             .send()
             .await
             .map_err(|e| OAuthError::TokenRefreshFailed(e.to_string()))?;
 
+        let status = response.status();
         let response_text = response
             .text()
             .await
             .map_err(|e| OAuthError::TokenRefreshFailed(e.to_string()))?;
+        if !status.is_success() {
+            return Err(refresh_error("fitbit", status, &response_text));
+        }
 
         let token_response: FitbitTokenResponse = serde_json::from_str(&response_text)
             .map_err(|e| OAuthError::TokenRefreshFailed(format!("Parse error: {}", e)))?;
@@ -410,6 +458,7 @@ impl OAuthProvider for FitbitOAuthProvider {
             expires_at,
             scopes: token_response.scope,
             provider: "fitbit".to_string(),
+            provider_user_id: token_response.user_id,
         })
     }
 
@@ -420,7 +469,7 @@ impl OAuthProvider for FitbitOAuthProvider {
             general_purpose::STANDARD.encode(format!("{}:{}", self.client_id, self.client_secret));
 
         let response = client
-            .post("https://api.fitbit.com/oauth2/revoke")
+            .post(crate::constants::env_config::fitbit_revoke_url())
             .header("Authorization", format!("Basic {}", auth_header))
             .form(&[("token", access_token)])
             .send()
@@ -444,3 +493,28 @@ impl OAuthProvider for FitbitOAuthProvider {
         Ok(token.expires_at > (now + buffer))
     }
 }
+
+/// Map a failed refresh response to an error
+///
+/// 400/401 from a token endpoint means the refresh token was rejected
+/// (`invalid_grant`): the user revoked access or the grant expired, and only
+/// a new authorization will fix it. Anything else is treated as transient.
+fn refresh_error(provider: &str, status: reqwest::StatusCode, body: &str) -> OAuthError {
+    if status == reqwest::StatusCode::BAD_REQUEST
+        || status == reqwest::StatusCode::UNAUTHORIZED
+        || body.contains("invalid_grant")
+    {
+        OAuthError::ReauthorizationRequired(provider.to_string())
+    } else {
+        OAuthError::TokenRefreshFailed(format!("{} returned {}: {}", provider, status, body))
+    }
+}
+
+/// Configured scopes, or the provider's comma-separated defaults when none are set
+fn scopes_or_default(scopes: &[String], default: &str) -> Vec<String> {
+    if scopes.is_empty() {
+        default.split(',').map(str::to_string).collect()
+    } else {
+        scopes.to_vec()
+    }
+}
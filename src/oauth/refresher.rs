@@ -0,0 +1,209 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! # Background Token Refresher
+//!
+//! Renews provider access tokens shortly before they expire, so tool calls
+//! don't pay for a refresh (or discover a revoked grant) mid-conversation.
+//! Connections whose refresh token the provider rejects are flagged as
+//! needing re-authorization, and subscribers of the `connections` websocket
+//! topic are told right away.
+
+use super::manager::OAuthManager;
+use super::OAuthError;
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::websocket::WebSocketManager;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Status reported for a connection the user must reconnect
+const STATUS_NEEDS_REAUTH: &str = "needs_reauth";
+
+/// Scheduling for the background refresher
+#[derive(Debug, Clone)]
+pub struct TokenRefresherConfig {
+    /// Time between scans for expiring tokens
+    pub interval: Duration,
+    /// Tokens expiring within this window are renewed
+    pub refresh_ahead: chrono::Duration,
+}
+
+impl TokenRefresherConfig {
+    /// Read the schedule from `TOKEN_REFRESH_INTERVAL_SECS` and `TOKEN_REFRESH_AHEAD_SECS`
+    pub fn from_env() -> Self {
+        Self {
+            interval: Duration::from_secs(
+                crate::constants::env_config::token_refresh_interval_secs(),
+            ),
+            refresh_ahead: chrono::Duration::seconds(
+                crate::constants::env_config::token_refresh_ahead_secs(),
+            ),
+        }
+    }
+}
+
+impl Default for TokenRefresherConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(300),
+            refresh_ahead: chrono::Duration::minutes(30),
+        }
+    }
+}
+
+/// What happened to one connection during a refresh pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefreshOutcome {
+    /// New tokens were stored
+    Refreshed {
+        user_id: Uuid,
+        provider: String,
+        expires_at: DateTime<Utc>,
+    },
+    /// The provider rejected the refresh token; the connection is flagged
+    NeedsReauth { user_id: Uuid, provider: String },
+    /// A transient failure; the connection is retried on the next pass
+    Failed {
+        user_id: Uuid,
+        provider: String,
+        error: String,
+    },
+}
+
+/// Periodically renews tokens that are about to expire
+pub struct TokenRefresher {
+    database: Arc<Database>,
+    oauth_manager: Arc<OAuthManager>,
+    websocket_manager: Option<Arc<WebSocketManager>>,
+    config: TokenRefresherConfig,
+}
+
+impl TokenRefresher {
+    pub fn new(
+        database: Arc<Database>,
+        oauth_manager: Arc<OAuthManager>,
+        config: TokenRefresherConfig,
+    ) -> Self {
+        Self {
+            database,
+            oauth_manager,
+            websocket_manager: None,
+            config,
+        }
+    }
+
+    /// Push connection changes to websocket subscribers
+    pub fn with_websocket(mut self, websocket_manager: Arc<WebSocketManager>) -> Self {
+        self.websocket_manager = Some(websocket_manager);
+        self
+    }
+
+    /// Refresh every connection expiring within the configured window
+    pub async fn run_once(&self) -> Result<Vec<RefreshOutcome>> {
+        let cutoff = Utc::now() + self.config.refresh_ahead;
+        let connections = self
+            .database
+            .list_provider_connections_expiring_before(cutoff)
+            .await?;
+
+        let mut outcomes = Vec::new();
+        for connection in connections {
+            if !self.oauth_manager.supports(&connection.provider) {
+                debug!(
+                    "Skipping {} token refresh for user {}: provider not configured",
+                    connection.provider, connection.user_id
+                );
+                continue;
+            }
+
+            let outcome = self
+                .refresh(connection.user_id, &connection.provider, cutoff)
+                .await;
+            self.notify(&outcome).await;
+            outcomes.push(outcome);
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Run `run_once` on the configured interval in a background task
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.interval);
+
+            loop {
+                interval.tick().await;
+
+                match self.run_once().await {
+                    Ok(outcomes) if !outcomes.is_empty() => {
+                        info!("Token refresher processed {} connections", outcomes.len());
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Token refresh pass failed: {}", e),
+                }
+            }
+        })
+    }
+
+    async fn refresh(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        cutoff: chrono::DateTime<Utc>,
+    ) -> RefreshOutcome {
+        match self
+            .oauth_manager
+            .refresh_expiring(user_id, provider, cutoff)
+            .await
+        {
+            Ok(token) => RefreshOutcome::Refreshed {
+                user_id,
+                provider: provider.to_string(),
+                expires_at: token.expires_at,
+            },
+            Err(OAuthError::ReauthorizationRequired(_)) => RefreshOutcome::NeedsReauth {
+                user_id,
+                provider: provider.to_string(),
+            },
+            Err(e) => {
+                warn!(
+                    "Background refresh of {} token for user {} failed: {}",
+                    provider, user_id, e
+                );
+                RefreshOutcome::Failed {
+                    user_id,
+                    provider: provider.to_string(),
+                    error: e.to_string(),
+                }
+            }
+        }
+    }
+
+    /// Tell the user's websocket clients when a connection needs attention
+    async fn notify(&self, outcome: &RefreshOutcome) {
+        let Some(websocket_manager) = &self.websocket_manager else {
+            return;
+        };
+
+        if let RefreshOutcome::NeedsReauth { user_id, provider } = outcome {
+            websocket_manager
+                .broadcast_connection_update(
+                    user_id,
+                    provider,
+                    STATUS_NEEDS_REAUTH,
+                    Some(format!(
+                        "{} access was revoked or expired. Reconnect to keep syncing.",
+                        provider
+                    )),
+                )
+                .await;
+        }
+    }
+}
@@ -284,7 +284,7 @@ pub mod strava {
         ];
 
         let response: StravaTokenResponse = client
-            .post("https://www.strava.com/oauth/token")
+            .post(crate::constants::env_config::strava_token_url())
             .form(&params)
             .send()
             .await?
@@ -322,7 +322,7 @@ pub mod strava {
         ];
 
         let response: StravaTokenResponse = client
-            .post("https://www.strava.com/oauth/token")
+            .post(crate::constants::env_config::strava_token_url())
             .form(&params)
             .send()
             .await?
@@ -356,7 +356,7 @@ pub mod strava {
         ];
 
         let response: StravaTokenResponse = client
-            .post("https://www.strava.com/oauth/token")
+            .post(crate::constants::env_config::strava_token_url())
             .form(&params)
             .send()
             .await?
@@ -414,7 +414,7 @@ pub mod fitbit {
         ];
 
         let response: FitbitTokenResponse = client
-            .post("https://api.fitbit.com/oauth2/token")
+            .post(crate::constants::env_config::fitbit_token_url())
             .form(&params)
             .send()
             .await?
@@ -456,7 +456,7 @@ pub mod fitbit {
         ];
 
         let response: FitbitTokenResponse = client
-            .post("https://api.fitbit.com/oauth2/token")
+            .post(crate::constants::env_config::fitbit_token_url())
             .form(&params)
             .send()
             .await?
@@ -494,7 +494,7 @@ pub mod fitbit {
         ];
 
         let response: FitbitTokenResponse = client
-            .post("https://api.fitbit.com/oauth2/token")
+            .post(crate::constants::env_config::fitbit_token_url())
             .form(&params)
             .send()
             .await?
@@ -12,13 +12,12 @@
 #![allow(clippy::single_match)]
 
 use crate::database_plugins::{factory::Database, DatabaseProvider};
-use crate::intelligence::activity_analyzer::ActivityAnalyzerTrait;
 use crate::intelligence::goal_engine::GoalEngineTrait;
 use crate::intelligence::performance_analyzer::PerformanceAnalyzerTrait;
 use crate::intelligence::recommendation_engine::RecommendationEngineTrait;
 use crate::intelligence::ActivityIntelligence;
-use crate::providers::{create_provider, AuthData};
-use chrono::Datelike;
+use crate::providers::connections::ProviderConnections;
+use crate::providers::rate_budget::{ProviderRateLimited, RequestContext};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -54,11 +53,51 @@ pub struct UniversalTool {
     ) -> Result<UniversalResponse, crate::protocols::ProtocolError>,
 }
 
+/// Tools dispatched to async handlers by `execute_tool`
+const ASYNC_TOOLS: [&str; 34] = [
+    "get_activities",
+    "get_athlete",
+    "get_stats",
+    "analyze_activity",
+    "get_activity_intelligence",
+    "get_connection_status",
+    "connect_strava",
+    "connect_fitbit",
+    "list_remote_agents",
+    "delegate_to_agent",
+    "get_delegated_task",
+    "cancel_delegated_task",
+    "get_sleep",
+    "get_daily_wellness",
+    "get_resting_hr_trend",
+    "log_wellness",
+    "get_readiness",
+    "log_activity",
+    "update_activity",
+    "delete_activity",
+    "get_gear",
+    "gear_usage_report",
+    "add_gear",
+    "update_gear",
+    "get_athlete_thresholds",
+    "update_analysis_settings",
+    "detect_patterns",
+    "compare_activities",
+    "list_my_routes",
+    "route_history",
+    "detect_anomalies",
+    "query_activities",
+    "get_streaks",
+    "get_digest",
+];
+
 /// Universal tool executor
 pub struct UniversalToolExecutor {
     pub database: Arc<Database>,
     pub intelligence: Arc<ActivityIntelligence>,
     pub config: Arc<crate::config::environment::ServerConfig>,
+    oauth_manager: Arc<crate::oauth::manager::OAuthManager>,
+    connections: ProviderConnections,
     tools: HashMap<String, UniversalTool>,
 }
 
@@ -68,10 +107,21 @@ impl UniversalToolExecutor {
         intelligence: Arc<ActivityIntelligence>,
         config: Arc<crate::config::environment::ServerConfig>,
     ) -> Self {
+        let oauth_manager = Arc::new(crate::oauth::manager::OAuthManager::from_config(
+            database.clone(),
+            &config.oauth,
+        ));
+        let connections = ProviderConnections::new(
+            database.clone(),
+            oauth_manager.clone(),
+            config.external_services.clone(),
+        );
         let mut executor = Self {
             database,
             intelligence,
             config,
+            oauth_manager,
+            connections,
             tools: HashMap::new(),
         };
 
@@ -85,44 +135,98 @@ impl UniversalToolExecutor {
         user_id: uuid::Uuid,
         provider: &str,
     ) -> Result<Option<crate::oauth::TokenData>, crate::oauth::OAuthError> {
-        let mut oauth_manager = crate::oauth::manager::OAuthManager::new(self.database.clone());
+        if !self.oauth_manager.supports(provider) {
+            return Err(if crate::providers::registry().is_supported(provider) {
+                crate::oauth::OAuthError::ConfigurationError(format!(
+                    "Failed to initialize {} provider",
+                    provider
+                ))
+            } else {
+                crate::oauth::OAuthError::UnsupportedProvider(provider.to_string())
+            });
+        }
 
-        // Register the appropriate provider using centralized config
-        match provider {
-            "strava" => {
-                if let Ok(strava_provider) =
-                    crate::oauth::providers::StravaOAuthProvider::from_config(
-                        &self.config.oauth.strava,
-                    )
-                {
-                    oauth_manager.register_provider(Box::new(strava_provider));
-                } else {
-                    return Err(crate::oauth::OAuthError::ConfigurationError(
-                        "Failed to initialize Strava provider".to_string(),
-                    ));
-                }
-            }
-            "fitbit" => {
-                if let Ok(fitbit_provider) =
-                    crate::oauth::providers::FitbitOAuthProvider::from_config(
-                        &self.config.oauth.fitbit,
-                    )
-                {
-                    oauth_manager.register_provider(Box::new(fitbit_provider));
-                } else {
-                    return Err(crate::oauth::OAuthError::ConfigurationError(
-                        "Failed to initialize Fitbit provider".to_string(),
-                    ));
+        self.oauth_manager
+            .ensure_valid_token(user_id, provider)
+            .await
+    }
+
+    /// Build a provider signed in with the user's stored tokens
+    ///
+    /// The registry supplies the provider's client credentials, and its calls
+    /// count against the user's share of the provider's rate budget.
+    async fn authenticated_provider(
+        &self,
+        user_id: uuid::Uuid,
+        provider: &str,
+        token: &crate::oauth::TokenData,
+    ) -> anyhow::Result<Box<dyn crate::providers::FitnessProvider>> {
+        let mut provider = crate::providers::registry()
+            .create_authenticated(
+                provider,
+                &self.config.external_services,
+                &token.access_token,
+                &token.refresh_token,
+            )
+            .await?;
+        provider.set_request_context(RequestContext::interactive(user_id));
+        Ok(provider)
+    }
+
+    /// Recent activities from the requested provider, the user's other
+    /// connected providers and manual entries, merged per workout
+    ///
+    /// A rate-limited provider fails the call, so the tool can say when to
+    /// retry instead of reporting that there are no activities.
+    async fn merged_recent_activities(
+        &self,
+        user_id: uuid::Uuid,
+        request: &UniversalRequest,
+        limit: usize,
+    ) -> anyhow::Result<Vec<crate::models::Activity>> {
+        let provider_type = request
+            .parameters
+            .get("provider")
+            .and_then(|v| v.as_str())
+            .unwrap_or("strava");
+        let mut activities = Vec::new();
+        if let Ok(Some(token_data)) = self.get_valid_token(user_id, provider_type).await {
+            if let Ok(provider) = self
+                .authenticated_provider(user_id, provider_type, &token_data)
+                .await
+            {
+                match provider.get_activities(Some(limit), None).await {
+                    Ok(recent) => activities = recent,
+                    Err(e) if e.is::<ProviderRateLimited>() => return Err(e),
+                    Err(_) => {}
                 }
             }
-            _ => {
-                return Err(crate::oauth::OAuthError::UnsupportedProvider(
-                    provider.to_string(),
-                ))
-            }
         }
+        crate::providers::merge::with_all_providers(&self.connections, user_id, activities, limit)
+            .await
+    }
 
-        oauth_manager.ensure_valid_token(user_id, provider).await
+    /// Response for a failed provider call
+    ///
+    /// A rate limit is reported with when to retry; anything else fails the
+    /// tool.
+    fn provider_error_response(
+        error: anyhow::Error,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        match error.downcast_ref::<ProviderRateLimited>() {
+            Some(limited) => Ok(UniversalResponse {
+                success: false,
+                result: None,
+                error: Some(error.to_string()),
+                metadata: Some(HashMap::from([(
+                    "retry_after_secs".to_string(),
+                    serde_json::json!(limited.retry_after_secs),
+                )])),
+            }),
+            None => Err(crate::protocols::ProtocolError::ExecutionFailed(
+                error.to_string(),
+            )),
+        }
     }
 
     /// Register all default tools
@@ -172,19 +276,6 @@ impl UniversalToolExecutor {
             handler: Self::handle_analyze_performance_trends,
         });
 
-        self.register_tool(UniversalTool {
-            name: "compare_activities".to_string(),
-            description: "Compare an activity against similar activities or personal bests"
-                .to_string(),
-            handler: Self::handle_compare_activities,
-        });
-
-        self.register_tool(UniversalTool {
-            name: "detect_patterns".to_string(),
-            description: "Detect patterns in training data".to_string(),
-            handler: Self::handle_detect_patterns,
-        });
-
         self.register_tool(UniversalTool {
             name: "track_progress".to_string(),
             description: "Track progress toward a specific goal".to_string(),
@@ -250,6 +341,29 @@ impl UniversalToolExecutor {
             "get_connection_status" => self.handle_connection_status_async(request).await,
             "connect_strava" => self.handle_connect_strava_async(request).await,
             "connect_fitbit" => self.handle_connect_fitbit_async(request).await,
+            "list_remote_agents"
+            | "delegate_to_agent"
+            | "get_delegated_task"
+            | "cancel_delegated_task" => self.handle_remote_agent_async(request).await,
+            "get_sleep" | "get_daily_wellness" | "get_resting_hr_trend" => {
+                self.handle_wellness_async(request).await
+            }
+            "log_wellness" | "get_readiness" => self.handle_readiness_async(request).await,
+            "log_activity" | "update_activity" | "delete_activity" => {
+                self.handle_manual_activity_async(request).await
+            }
+            "get_gear" | "gear_usage_report" | "add_gear" | "update_gear" => {
+                self.handle_gear_async(request).await
+            }
+            "get_athlete_thresholds" => self.handle_athlete_thresholds_async(request).await,
+            "update_analysis_settings" => self.handle_analysis_settings_async(request).await,
+            "detect_patterns" => self.handle_detect_patterns_async(request).await,
+            "compare_activities" => self.handle_compare_activities_async(request).await,
+            "list_my_routes" | "route_history" => self.handle_routes_async(request).await,
+            "detect_anomalies" => self.handle_detect_anomalies_async(request).await,
+            "query_activities" => self.handle_query_activities_async(request).await,
+            "get_streaks" => self.handle_get_streaks_async(request).await,
+            "get_digest" => self.handle_get_digest_async(request).await,
             _ => {
                 // Handle synchronous tools
                 let tool = self.tools.get(&request.tool_name).ok_or_else(|| {
@@ -265,6 +379,11 @@ impl UniversalToolExecutor {
         self.tools.values().collect()
     }
 
+    /// Whether `execute_tool` can run the named tool
+    pub fn supports_tool(&self, name: &str) -> bool {
+        ASYNC_TOOLS.contains(&name) || self.tools.contains_key(name)
+    }
+
     /// Get tool by name
     pub fn get_tool(&self, name: &str) -> Option<&UniversalTool> {
         self.tools.get(name)
@@ -288,96 +407,119 @@ impl UniversalToolExecutor {
             .and_then(|v| v.as_str())
             .unwrap_or("strava");
 
-        // Get REAL Strava data
-        let activities = if provider_type == "strava" {
+        let activities = if provider_type == crate::providers::manual::MANUAL_PROVIDER {
             match uuid::Uuid::parse_str(&request.user_id) {
-                Ok(user_uuid) => {
-                    // Get valid Strava token (with automatic refresh if needed)
-                    match self.get_valid_token(user_uuid, "strava").await {
-                        Ok(Some(token_data)) => {
-                            // Create Strava provider with real token
-                            match create_provider("strava") {
-                                Ok(mut provider) => {
-                                    let auth_data = AuthData::OAuth2 {
-                                        client_id: std::env::var("STRAVA_CLIENT_ID")
-                                            .unwrap_or_default(),
-                                        client_secret: std::env::var("STRAVA_CLIENT_SECRET")
-                                            .unwrap_or_default(),
-                                        access_token: Some(token_data.access_token.clone()),
-                                        refresh_token: Some(token_data.refresh_token.clone()),
-                                    };
-
-                                    // Authenticate and get REAL activities
-                                    match provider.authenticate(auth_data).await {
-                                        Ok(()) => {
-                                            match provider.get_activities(Some(limit), None).await {
-                                                Ok(real_activities) => {
-                                                    // Convert REAL activities to JSON
-                                                    real_activities.into_iter().map(|activity| {
-                                                        serde_json::json!({
-                                                            "id": activity.id,
-                                                            "name": activity.name,
-                                                            "sport_type": format!("{:?}", activity.sport_type),
-                                                            "start_date": activity.start_date.to_rfc3339(),
-                                                            "duration_seconds": activity.duration_seconds,
-                                                            "distance_meters": activity.distance_meters.unwrap_or(0.0),
-                                                            "elevation_gain": activity.elevation_gain.unwrap_or(0.0),
-                                                            "average_heart_rate": activity.average_heart_rate,
-                                                            "max_heart_rate": activity.max_heart_rate,
-                                                            "calories": activity.calories,
-                                                            "start_latitude": activity.start_latitude,
-                                                            "start_longitude": activity.start_longitude,
-                                                            "city": activity.city,
-                                                            "country": activity.country,
-                                                            "provider": "strava",
-                                                            "is_real_data": true
-                                                        })
-                                                    }).collect()
-                                                }
-                                                Err(e) => {
-                                                    eprintln!("Strava API call failed: {}", e);
-                                                    vec![serde_json::json!({
-                                                        "error": format!("Strava API call failed: {}", e),
-                                                        "is_real_data": false
-                                                    })]
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            eprintln!("Strava authentication failed: {}", e);
-                                            vec![serde_json::json!({
-                                                "error": format!("Strava authentication failed: {}", e),
-                                                "is_real_data": false
-                                            })]
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!("Failed to create Strava provider: {}", e);
-                                    vec![serde_json::json!({
-                                        "error": format!("Failed to create Strava provider: {}", e),
-                                        "is_real_data": false
-                                    })]
-                                }
+                Ok(user_uuid) => self
+                    .database
+                    .list_manual_activities(user_uuid, Some(limit as u32))
+                    .await
+                    .map_err(|e| crate::protocols::ProtocolError::ExecutionFailed(e.to_string()))?
+                    .into_iter()
+                    .map(|activity| serde_json::json!(activity))
+                    .collect(),
+                Err(e) => {
+                    vec![serde_json::json!({
+                        "error": format!("Invalid user ID format: {}", e),
+                        "is_real_data": false
+                    })]
+                }
+            }
+        } else if provider_type == crate::providers::merge::ALL_PROVIDERS {
+            match uuid::Uuid::parse_str(&request.user_id) {
+                Ok(user_uuid) => match crate::providers::merge::merged_activities(
+                    &self.connections,
+                    user_uuid,
+                    limit,
+                )
+                .await
+                {
+                    Ok(merged) => merged,
+                    Err(e) => return Self::provider_error_response(e),
+                }
+                .into_iter()
+                .map(|merged| {
+                    let mut activity = serde_json::json!(merged.activity);
+                    activity["sources"] = serde_json::json!(merged.sources);
+                    activity
+                })
+                .collect(),
+                Err(e) => {
+                    vec![serde_json::json!({
+                        "error": format!("Invalid user ID format: {}", e),
+                        "is_real_data": false
+                    })]
+                }
+            }
+        } else {
+            // Get REAL activities from the requested provider
+            match uuid::Uuid::parse_str(&request.user_id) {
+                Ok(user_uuid) => match self.get_valid_token(user_uuid, provider_type).await {
+                    Ok(Some(token_data)) => match self
+                        .authenticated_provider(user_uuid, provider_type, &token_data)
+                        .await
+                    {
+                        Ok(provider) => match provider.get_activities(Some(limit), None).await {
+                            Ok(real_activities) => real_activities
+                                .into_iter()
+                                .map(|activity| {
+                                    serde_json::json!({
+                                        "id": activity.id,
+                                        "name": activity.name,
+                                        "sport_type": format!("{:?}", activity.sport_type),
+                                        "start_date": activity.start_date.to_rfc3339(),
+                                        "duration_seconds": activity.duration_seconds,
+                                        "distance_meters": activity.distance_meters.unwrap_or(0.0),
+                                        "elevation_gain": activity.elevation_gain.unwrap_or(0.0),
+                                        "average_heart_rate": activity.average_heart_rate,
+                                        "max_heart_rate": activity.max_heart_rate,
+                                        "calories": activity.calories,
+                                        "start_latitude": activity.start_latitude,
+                                        "start_longitude": activity.start_longitude,
+                                        "city": activity.city,
+                                        "country": activity.country,
+                                        "provider": activity.provider,
+                                        "is_real_data": true
+                                    })
+                                })
+                                .collect(),
+                            Err(e) => {
+                                eprintln!("{} API call failed: {}", provider_type, e);
+                                vec![serde_json::json!({
+                                    "error": format!("{} API call failed: {}", provider_type, e),
+                                    "is_real_data": false
+                                })]
                             }
-                        }
-                        Ok(None) => {
-                            vec![serde_json::json!({
-                                "error": "No Strava token found for user - please connect your Strava account first",
-                                "is_real_data": false,
-                                "note": "Connect your Strava account via the OAuth flow to get real data"
-                            })]
-                        }
+                        },
                         Err(e) => {
-                            eprintln!("OAuth error: {}", e);
+                            eprintln!("{} authentication failed: {}", provider_type, e);
                             vec![serde_json::json!({
-                                "error": format!("OAuth error: {}", e),
-                                "is_real_data": false,
-                                "note": "Token may have expired or been revoked. Please reconnect your Strava account."
+                                "error": format!("{} authentication failed: {}", provider_type, e),
+                                "is_real_data": false
                             })]
                         }
+                    },
+                    Ok(None) => {
+                        vec![serde_json::json!({
+                            "error": format!(
+                                "No {} token found for user - please connect your {} account first",
+                                provider_type, provider_type
+                            ),
+                            "is_real_data": false,
+                            "note": "Connect your account via the OAuth flow to get real data"
+                        })]
                     }
-                }
+                    Err(e) => {
+                        eprintln!("OAuth error: {}", e);
+                        vec![serde_json::json!({
+                            "error": format!("OAuth error: {}", e),
+                            "is_real_data": false,
+                            "note": format!(
+                                "Token may have expired or been revoked. Please reconnect your {} account.",
+                                provider_type
+                            )
+                        })]
+                    }
+                },
                 Err(e) => {
                     vec![serde_json::json!({
                         "error": format!("Invalid user ID format: {}", e),
@@ -385,8 +527,6 @@ impl UniversalToolExecutor {
                     })]
                 }
             }
-        } else {
-            vec![]
         };
 
         let result = serde_json::json!({
@@ -412,50 +552,46 @@ impl UniversalToolExecutor {
         &self,
         request: UniversalRequest,
     ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        let provider_type = request
+            .parameters
+            .get("provider")
+            .and_then(|v| v.as_str())
+            .unwrap_or("strava");
+
         // Get REAL athlete data
         let athlete_data = match uuid::Uuid::parse_str(&request.user_id) {
-            Ok(user_uuid) => match self.get_valid_token(user_uuid, "strava").await {
-                Ok(Some(token_data)) => match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET")
-                                .unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        match provider.authenticate(auth_data).await {
-                            Ok(()) => match provider.get_athlete().await {
-                                Ok(athlete) => serde_json::json!({
-                                    "id": athlete.id,
-                                    "username": athlete.username,
-                                    "firstname": athlete.firstname,
-                                    "lastname": athlete.lastname,
-                                    "profile_picture": athlete.profile_picture,
-                                    "provider": athlete.provider,
-                                    "is_real_data": true
-                                }),
-                                Err(e) => serde_json::json!({
-                                    "error": format!("Failed to get athlete data: {}", e),
-                                    "is_real_data": false
-                                }),
-                            },
-                            Err(e) => serde_json::json!({
-                                "error": format!("Authentication failed: {}", e),
-                                "is_real_data": false
-                            }),
-                        }
-                    }
+            Ok(user_uuid) => match self.get_valid_token(user_uuid, provider_type).await {
+                Ok(Some(token_data)) => match self
+                    .authenticated_provider(user_uuid, provider_type, &token_data)
+                    .await
+                {
+                    Ok(provider) => match provider.get_athlete().await {
+                        Ok(athlete) => serde_json::json!({
+                            "id": athlete.id,
+                            "username": athlete.username,
+                            "firstname": athlete.firstname,
+                            "lastname": athlete.lastname,
+                            "profile_picture": athlete.profile_picture,
+                            "provider": athlete.provider,
+                            "is_real_data": true
+                        }),
+                        Err(e) => serde_json::json!({
+                            "error": format!("Failed to get athlete data: {}", e),
+                            "is_real_data": false
+                        }),
+                    },
                     Err(e) => serde_json::json!({
-                        "error": format!("Failed to create provider: {}", e),
+                        "error": format!("Authentication failed: {}", e),
                         "is_real_data": false
                     }),
                 },
                 Ok(None) => serde_json::json!({
-                    "error": "No Strava token found - please connect your Strava account first",
+                    "error": format!(
+                        "No {} token found - please connect your {} account first",
+                        provider_type, provider_type
+                    ),
                     "is_real_data": false,
-                    "note": "Connect your Strava account via the OAuth flow to get real data"
+                    "note": "Connect your account via the OAuth flow to get real data"
                 }),
                 Err(e) => serde_json::json!({
                     "error": format!("Database error: {}", e),
@@ -497,39 +633,36 @@ impl UniversalToolExecutor {
             crate::protocols::ProtocolError::InvalidParameters("Invalid user ID format".to_string())
         })?;
 
+        // Manually logged activities are stored locally
+        let manual_activity = self
+            .database
+            .get_manual_activity(user_uuid, activity_id)
+            .await
+            .ok()
+            .flatten();
+
         // Get real activity data async
-        let activity_result = {
-            // Get valid Strava token (with automatic refresh if needed)
-            match self.get_valid_token(user_uuid, "strava").await {
-                Ok(Some(token_data)) => {
-                    // Create Strava provider with real token
-                    match create_provider("strava") {
-                        Ok(mut provider) => {
-                            let auth_data = AuthData::OAuth2 {
-                                client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                                client_secret: std::env::var("STRAVA_CLIENT_SECRET")
-                                    .unwrap_or_default(),
-                                access_token: Some(token_data.access_token.clone()),
-                                refresh_token: Some(token_data.refresh_token.clone()),
-                            };
-
-                            // Authenticate and get real activity
-                            match provider.authenticate(auth_data).await {
-                                Ok(()) => {
-                                    // Get all activities and find the specific one
-                                    match provider.get_activities(Some(100), None).await {
-                                        Ok(activities) => {
-                                            activities.into_iter().find(|a| a.id == activity_id)
-                                        }
-                                        Err(_) => None,
-                                    }
-                                }
-                                Err(_) => None,
-                            }
-                        }
+        let activity_result = if manual_activity.is_some() {
+            manual_activity
+        } else {
+            let provider_type = request
+                .parameters
+                .get("provider")
+                .and_then(|v| v.as_str())
+                .unwrap_or("strava");
+
+            // Get a valid token (with automatic refresh if needed) and find the activity
+            match self.get_valid_token(user_uuid, provider_type).await {
+                Ok(Some(token_data)) => match self
+                    .authenticated_provider(user_uuid, provider_type, &token_data)
+                    .await
+                {
+                    Ok(provider) => match provider.get_activities(Some(100), None).await {
+                        Ok(activities) => activities.into_iter().find(|a| a.id == activity_id),
                         Err(_) => None,
-                    }
-                }
+                    },
+                    Err(_) => None,
+                },
                 _ => None,
             }
         };
@@ -612,47 +745,12 @@ impl UniversalToolExecutor {
             crate::protocols::ProtocolError::InvalidParameters("Invalid user ID format".to_string())
         })?;
 
-        // Use OAuth manager to check connection status for all providers
         let database = executor.database.clone();
         let rt = tokio::runtime::Handle::current();
-        let connection_status = rt.block_on(async {
-            let mut oauth_manager = crate::oauth::manager::OAuthManager::new(database);
-
-            // Register all providers using centralized config
-            if let Ok(strava_provider) = crate::oauth::providers::StravaOAuthProvider::from_config(
-                &executor.config.oauth.strava,
-            ) {
-                oauth_manager.register_provider(Box::new(strava_provider));
-            }
-            if let Ok(fitbit_provider) = crate::oauth::providers::FitbitOAuthProvider::from_config(
-                &executor.config.oauth.fitbit,
-            ) {
-                oauth_manager.register_provider(Box::new(fitbit_provider));
-            }
-
-            match oauth_manager.get_connection_status(user_uuid).await {
-                Ok(statuses) => statuses,
-                Err(_) => {
-                    let mut default_status = std::collections::HashMap::new();
-                    default_status.insert("strava".to_string(), false);
-                    default_status.insert("fitbit".to_string(), false);
-                    default_status
-                }
-            }
-        });
-
-        let status = serde_json::json!({
-            "providers": {
-                "strava": {
-                    "connected": connection_status.get("strava").unwrap_or(&false),
-                    "status": if *connection_status.get("strava").unwrap_or(&false) { "active" } else { "not_connected" }
-                },
-                "fitbit": {
-                    "connected": connection_status.get("fitbit").unwrap_or(&false),
-                    "status": if *connection_status.get("fitbit").unwrap_or(&false) { "active" } else { "not_connected" }
-                }
-            }
-        });
+        let connections = rt
+            .block_on(async move { database.list_provider_connections(user_uuid).await })
+            .unwrap_or_default();
+        let status = Self::connection_status_json(&connections);
 
         Ok(UniversalResponse {
             success: true,
@@ -782,33 +880,35 @@ impl UniversalToolExecutor {
         // Get REAL stats from the provider
         let stats = match uuid::Uuid::parse_str(&request.user_id) {
             Ok(user_uuid) => match self.get_valid_token(user_uuid, provider_type).await {
-                Ok(Some(token_data)) => match create_provider(provider_type) {
+                Ok(Some(token_data)) => match crate::providers::registry()
+                    .create_authenticated(
+                        provider_type,
+                        &self.config.external_services,
+                        &token_data.access_token,
+                        &token_data.refresh_token,
+                    )
+                    .await
+                {
                     Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET")
-                                .unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        match provider.authenticate(auth_data).await {
-                            Ok(()) => match provider.get_stats().await {
-                                Ok(stats) => serde_json::to_value(&stats).unwrap_or_else(|_| {
-                                    serde_json::json!({
-                                        "error": "Failed to serialize stats",
-                                        "is_real_data": false
-                                    })
+                        provider.set_request_context(RequestContext::interactive(user_uuid));
+                        match provider.get_stats().await {
+                            Ok(stats) => serde_json::to_value(&stats).unwrap_or_else(|_| {
+                                serde_json::json!({
+                                    "error": "Failed to serialize stats",
+                                    "is_real_data": false
+                                })
+                            }),
+                            Err(e) => match e.downcast_ref::<ProviderRateLimited>() {
+                                Some(limited) => serde_json::json!({
+                                    "error": e.to_string(),
+                                    "retry_after_secs": limited.retry_after_secs,
+                                    "is_real_data": false
                                 }),
-                                Err(e) => serde_json::json!({
+                                None => serde_json::json!({
                                     "error": format!("Failed to get stats: {}", e),
                                     "is_real_data": false
                                 }),
                             },
-                            Err(e) => serde_json::json!({
-                                "error": format!("Authentication failed: {}", e),
-                                "is_real_data": false
-                            }),
                         }
                     }
                     Err(e) => serde_json::json!({
@@ -880,44 +980,12 @@ impl UniversalToolExecutor {
             crate::protocols::ProtocolError::InvalidParameters("Invalid user ID format".to_string())
         })?;
 
-        // Use OAuth manager to check connection status for all providers
-        let database = self.database.clone();
-        let mut oauth_manager = crate::oauth::manager::OAuthManager::new(database);
-
-        // Register all providers using centralized config
-        if let Ok(strava_provider) =
-            crate::oauth::providers::StravaOAuthProvider::from_config(&self.config.oauth.strava)
-        {
-            oauth_manager.register_provider(Box::new(strava_provider));
-        }
-        if let Ok(fitbit_provider) =
-            crate::oauth::providers::FitbitOAuthProvider::from_config(&self.config.oauth.fitbit)
-        {
-            oauth_manager.register_provider(Box::new(fitbit_provider));
-        }
-
-        let connection_status = match oauth_manager.get_connection_status(user_uuid).await {
-            Ok(statuses) => statuses,
-            Err(_) => {
-                let mut default_status = std::collections::HashMap::new();
-                default_status.insert("strava".to_string(), false);
-                default_status.insert("fitbit".to_string(), false);
-                default_status
-            }
-        };
-
-        let status = serde_json::json!({
-            "providers": {
-                "strava": {
-                    "connected": connection_status.get("strava").unwrap_or(&false),
-                    "status": if *connection_status.get("strava").unwrap_or(&false) { "active" } else { "not_connected" }
-                },
-                "fitbit": {
-                    "connected": connection_status.get("fitbit").unwrap_or(&false),
-                    "status": if *connection_status.get("fitbit").unwrap_or(&false) { "active" } else { "not_connected" }
-                }
-            }
-        });
+        let connections = self
+            .database
+            .list_provider_connections(user_uuid)
+            .await
+            .unwrap_or_default();
+        let status = Self::connection_status_json(&connections);
 
         Ok(UniversalResponse {
             success: true,
@@ -927,70 +995,752 @@ impl UniversalToolExecutor {
         })
     }
 
-    /// Handle Strava connection asynchronously
-    async fn handle_connect_strava_async(
+    /// Per-provider connection status, including connections needing re-authorization
+    fn connection_status_json(connections: &[crate::models::ProviderConnection]) -> Value {
+        let provider_status = |provider: &str| match connections
+            .iter()
+            .find(|connection| connection.provider == provider)
+        {
+            Some(connection) => serde_json::json!({
+                "connected": true,
+                "status": connection.status(),
+                "needs_reauth": connection.needs_reauth,
+                "reauth_reason": connection.reauth_reason,
+            }),
+            None => serde_json::json!({
+                "connected": false,
+                "status": "not_connected",
+                "needs_reauth": false,
+            }),
+        };
+
+        serde_json::json!({
+            "providers": {
+                "strava": provider_status("strava"),
+                "fitbit": provider_status("fitbit"),
+            }
+        })
+    }
+
+    /// Handle remote agent delegation tools asynchronously
+    async fn handle_remote_agent_async(
         &self,
         request: UniversalRequest,
     ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
-        // Parse user ID
         let user_uuid = uuid::Uuid::parse_str(&request.user_id).map_err(|_| {
             crate::protocols::ProtocolError::InvalidParameters("Invalid user ID format".to_string())
         })?;
 
-        // Create OAuth manager with database
-        let mut oauth_manager = crate::oauth::manager::OAuthManager::new(self.database.clone());
-
-        // Register Strava provider using centralized config
-        match crate::oauth::providers::StravaOAuthProvider::from_config(&self.config.oauth.strava) {
-            Ok(strava_provider) => {
-                oauth_manager.register_provider(Box::new(strava_provider));
+        let registry = crate::a2a::RemoteAgentRegistry::new(self.database.clone());
+        let params = &request.parameters;
+        let required = |name: &str| {
+            params.get(name).and_then(|v| v.as_str()).ok_or_else(|| {
+                crate::protocols::ProtocolError::InvalidParameters(format!(
+                    "Missing required parameter: {}",
+                    name
+                ))
+            })
+        };
 
-                // Generate authorization URL
-                match oauth_manager.generate_auth_url(user_uuid, "strava").await {
-                    Ok(auth_response) => Ok(UniversalResponse {
-                        success: true,
-                        result: Some(serde_json::json!({
-                            "authorization_url": auth_response.authorization_url,
-                            "state": auth_response.state,
-                            "provider": auth_response.provider
-                        })),
-                        error: None,
-                        metadata: None,
-                    }),
-                    Err(e) => Ok(UniversalResponse {
-                        success: false,
-                        result: None,
-                        error: Some(format!(
-                            "Failed to generate Strava authorization URL: {}",
-                            e
-                        )),
-                        metadata: None,
-                    }),
-                }
+        let outcome = match request.tool_name.as_str() {
+            "list_remote_agents" => registry
+                .agents_for_user(user_uuid)
+                .await
+                .map(|agents| serde_json::json!({ "remote_agents": agents })),
+            "delegate_to_agent" => {
+                let agent_id = required("agent_id")?;
+                let message = required("message")?;
+                let data = params.get("data").filter(|d| !d.is_null()).cloned();
+                registry
+                    .delegate(user_uuid, agent_id, message, data)
+                    .await
+                    .map(|task| serde_json::json!({ "agent_id": agent_id, "task": task }))
             }
-            Err(e) => Ok(UniversalResponse {
-                success: false,
-                result: None,
-                error: Some(format!("Failed to initialize Strava provider: {}", e)),
-                metadata: None,
-            }),
-        }
+            "get_delegated_task" => {
+                let agent_id = required("agent_id")?;
+                registry
+                    .get_task(user_uuid, agent_id, required("task_id")?)
+                    .await
+                    .map(|task| serde_json::json!({ "agent_id": agent_id, "task": task }))
+            }
+            _ => {
+                let agent_id = required("agent_id")?;
+                registry
+                    .cancel_task(user_uuid, agent_id, required("task_id")?)
+                    .await
+                    .map(|task| serde_json::json!({ "agent_id": agent_id, "task": task }))
+            }
+        };
+
+        let result =
+            outcome.map_err(|e| crate::protocols::ProtocolError::ExecutionFailed(e.to_string()))?;
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(result),
+            error: None,
+            metadata: None,
+        })
     }
 
-    /// Handle Fitbit connection asynchronously
-    async fn handle_connect_fitbit_async(
+    /// Handle logging, editing and deleting manual activities
+    async fn handle_manual_activity_async(
         &self,
         request: UniversalRequest,
     ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
-        // Parse user ID
-        let user_uuid = uuid::Uuid::parse_str(&request.user_id).map_err(|_| {
-            crate::protocols::ProtocolError::InvalidParameters("Invalid user ID format".to_string())
-        })?;
+        use crate::protocols::ProtocolError;
+        use crate::providers::manual::{ManualActivityRequest, ManualActivityUpdate};
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id)
+            .map_err(|_| ProtocolError::InvalidParameters("Invalid user ID format".to_string()))?;
+        let invalid = |e: &dyn std::fmt::Display| ProtocolError::InvalidParameters(e.to_string());
+        let failed = |e: anyhow::Error| ProtocolError::ExecutionFailed(e.to_string());
+
+        if request.tool_name == "log_activity" {
+            let activity =
+                serde_json::from_value::<ManualActivityRequest>(request.parameters.clone())
+                    .map_err(|e| invalid(&e))?
+                    .into_activity()
+                    .map_err(|e| invalid(&e))?;
+            self.database
+                .create_manual_activity(user_uuid, &activity)
+                .await
+                .map_err(failed)?;
+            return Ok(UniversalResponse {
+                success: true,
+                result: Some(serde_json::json!({ "activity": activity })),
+                error: None,
+                metadata: None,
+            });
+        }
 
-        // Create OAuth manager with database
-        let mut oauth_manager = crate::oauth::manager::OAuthManager::new(self.database.clone());
+        let activity_id = request
+            .parameters
+            .get("activity_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ProtocolError::InvalidParameters("activity_id is required".to_string())
+            })?;
+        let not_found = || {
+            ProtocolError::InvalidParameters(format!("Manual activity '{}' not found", activity_id))
+        };
 
-        // Register Fitbit provider using centralized config
+        let result = if request.tool_name == "delete_activity" {
+            if !self
+                .database
+                .delete_manual_activity(user_uuid, activity_id)
+                .await
+                .map_err(failed)?
+            {
+                return Err(not_found());
+            }
+            serde_json::json!({ "deleted": true, "activity_id": activity_id })
+        } else {
+            let update = serde_json::from_value::<ManualActivityUpdate>(request.parameters.clone())
+                .map_err(|e| invalid(&e))?;
+            let mut activity = self
+                .database
+                .get_manual_activity(user_uuid, activity_id)
+                .await
+                .map_err(failed)?
+                .ok_or_else(not_found)?;
+            update.apply(&mut activity).map_err(|e| invalid(&e))?;
+            self.database
+                .update_manual_activity(user_uuid, &activity)
+                .await
+                .map_err(failed)?;
+            serde_json::json!({ "activity": activity })
+        };
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(result),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle manual wellness logging and readiness scoring
+    async fn handle_readiness_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::{readiness, wellness};
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id).map_err(|_| {
+            crate::protocols::ProtocolError::InvalidParameters("Invalid user ID format".to_string())
+        })?;
+
+        let result = if request.tool_name == "log_wellness" {
+            let entry = wellness::parse_manual_entry(&request.parameters)
+                .map_err(|e| crate::protocols::ProtocolError::InvalidParameters(e.to_string()))?;
+            let stored = wellness::log_manual_entry(&self.database, user_uuid, &entry)
+                .await
+                .map_err(|e| crate::protocols::ProtocolError::ExecutionFailed(e.to_string()))?;
+            serde_json::json!({ "logged": stored })
+        } else {
+            let date = wellness::date_arg(&request.parameters)
+                .map_err(|e| crate::protocols::ProtocolError::InvalidParameters(e.to_string()))?;
+            let score = readiness::readiness_for_user(&self.connections, user_uuid, date)
+                .await
+                .map_err(|e| crate::protocols::ProtocolError::ExecutionFailed(e.to_string()))?;
+            readiness::readiness_report(date, score.as_ref())
+        };
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(result),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle gear listing, wear reports and manual gear edits
+    async fn handle_gear_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::gear::{self, GearUpdate, NewGear};
+        use crate::protocols::ProtocolError;
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id)
+            .map_err(|_| ProtocolError::InvalidParameters("Invalid user ID format".to_string()))?;
+        let invalid = |e: &dyn std::fmt::Display| ProtocolError::InvalidParameters(e.to_string());
+        let failed = |e: anyhow::Error| ProtocolError::ExecutionFailed(e.to_string());
+
+        let result = match request.tool_name.as_str() {
+            "add_gear" => {
+                let new_gear = serde_json::from_value::<NewGear>(request.parameters.clone())
+                    .map_err(|e| invalid(&e))?
+                    .into_gear()
+                    .map_err(|e| invalid(&e))?;
+                self.database
+                    .upsert_gear(user_uuid, &new_gear)
+                    .await
+                    .map_err(failed)?;
+                serde_json::json!({ "gear": gear::gear_summary(&new_gear) })
+            }
+            "update_gear" => {
+                let gear_id = request
+                    .parameters
+                    .get("gear_id")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        ProtocolError::InvalidParameters("gear_id is required".to_string())
+                    })?;
+                let update = serde_json::from_value::<GearUpdate>(request.parameters.clone())
+                    .map_err(|e| invalid(&e))?;
+                let mut stored = self
+                    .database
+                    .get_gear(user_uuid, gear_id)
+                    .await
+                    .map_err(failed)?
+                    .ok_or_else(|| {
+                        ProtocolError::InvalidParameters(format!("Gear '{}' not found", gear_id))
+                    })?;
+                update.apply(&mut stored).map_err(|e| invalid(&e))?;
+                self.database
+                    .upsert_gear(user_uuid, &stored)
+                    .await
+                    .map_err(failed)?;
+                serde_json::json!({ "gear": gear::gear_summary(&stored) })
+            }
+            tool => {
+                let gear_type = request
+                    .parameters
+                    .get("gear_type")
+                    .filter(|v| !v.is_null())
+                    .map(|v| serde_json::from_value(v.clone()))
+                    .transpose()
+                    .map_err(|e| invalid(&e))?;
+                let include_retired = request
+                    .parameters
+                    .get("include_retired")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let all_gear = gear::gear_for_user(&self.connections, user_uuid)
+                    .await
+                    .map_err(failed)?;
+                if tool == "gear_usage_report" {
+                    gear::gear_usage_report(&all_gear)
+                } else {
+                    gear::gear_list_report(&all_gear, gear_type, include_retired)
+                }
+            }
+        };
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(result),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle get_athlete_thresholds, re-estimating when stale or asked to
+    async fn handle_athlete_thresholds_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::{settings, thresholds};
+        use crate::protocols::ProtocolError;
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id)
+            .map_err(|_| ProtocolError::InvalidParameters("Invalid user ID format".to_string()))?;
+        let flag = |name: &str| {
+            request
+                .parameters
+                .get(name)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        };
+
+        let stored = thresholds::thresholds_for_user(&self.connections, user_uuid, flag("refresh"))
+            .await
+            .map_err(|e| ProtocolError::ExecutionFailed(e.to_string()))?;
+        let config = settings::effective_config(&self.database, user_uuid).await;
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(thresholds::thresholds_report(
+                &stored,
+                &config.zone_thresholds,
+                flag("include_history"),
+            )),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle update_analysis_settings, merging the user's overrides
+    async fn handle_analysis_settings_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::settings;
+        use crate::protocols::ProtocolError;
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id)
+            .map_err(|_| ProtocolError::InvalidParameters("Invalid user ID format".to_string()))?;
+        let patch = request
+            .parameters
+            .get("settings")
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!({}));
+        let reset = request
+            .parameters
+            .get("reset")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let updated = settings::update_user_settings(&self.database, user_uuid, &patch, reset)
+            .await
+            .map_err(|e| ProtocolError::InvalidParameters(e.to_string()))?;
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(
+                serde_json::to_value(updated)
+                    .map_err(|e| ProtocolError::ExecutionFailed(e.to_string()))?,
+            ),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle detect_patterns over the user's merged activity history
+    async fn handle_detect_patterns_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::patterns;
+        use crate::protocols::ProtocolError;
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id)
+            .map_err(|_| ProtocolError::InvalidParameters("Invalid user ID format".to_string()))?;
+        let pattern_type = request
+            .parameters
+            .get("pattern_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("all");
+        let timeframe = request.parameters.get("timeframe").and_then(|v| v.as_str());
+        let provider = request.parameters.get("provider").and_then(|v| v.as_str());
+
+        patterns::PatternType::select(pattern_type)
+            .map_err(|e| ProtocolError::InvalidParameters(e.to_string()))?;
+        let report = patterns::patterns_for_user(
+            &self.connections,
+            user_uuid,
+            provider,
+            pattern_type,
+            timeframe,
+        )
+        .await
+        .map_err(|e| ProtocolError::ExecutionFailed(e.to_string()))?;
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(report),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle compare_activities over every connected provider
+    async fn handle_compare_activities_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::comparison::{self, ComparisonRequest};
+        use crate::protocols::ProtocolError;
+        use crate::providers::manual::{ManualActivityProvider, MANUAL_PROVIDER};
+        use crate::providers::merge::{MergedProvider, ALL_PROVIDERS};
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id)
+            .map_err(|_| ProtocolError::InvalidParameters("Invalid user ID format".to_string()))?;
+        let comparison_request = ComparisonRequest::from_args(&request.parameters)
+            .map_err(|e| ProtocolError::InvalidParameters(e.to_string()))?;
+
+        let provider_name = request
+            .parameters
+            .get("provider")
+            .and_then(|v| v.as_str())
+            .unwrap_or(ALL_PROVIDERS);
+        let provider: Box<dyn crate::providers::FitnessProvider> = match provider_name {
+            ALL_PROVIDERS => Box::new(MergedProvider::for_user(&self.connections, user_uuid).await),
+            MANUAL_PROVIDER => Box::new(ManualActivityProvider::new(
+                self.database.clone(),
+                user_uuid,
+                None,
+            )),
+            name => self
+                .connections
+                .connected_provider(user_uuid, name)
+                .await
+                .map_err(|e| ProtocolError::ExecutionFailed(e.to_string()))?
+                .ok_or_else(|| {
+                    ProtocolError::ExecutionFailed(format!("Not connected to {}", name))
+                })?,
+        };
+        let comparison = comparison::compare_activities(provider.as_ref(), &comparison_request)
+            .await
+            .map_err(|e| ProtocolError::ExecutionFailed(e.to_string()))?;
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(serde_json::to_value(comparison).map_err(|e| {
+                ProtocolError::ExecutionFailed(format!("Failed to serialize comparison: {}", e))
+            })?),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle query_activities over the user's full history
+    async fn handle_query_activities_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::activity_query::{self, ActivityQuery};
+        use crate::protocols::ProtocolError;
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id)
+            .map_err(|_| ProtocolError::InvalidParameters("Invalid user ID format".to_string()))?;
+        let query = ActivityQuery::from_args(&request.parameters)
+            .map_err(|e| ProtocolError::InvalidParameters(e.to_string()))?;
+        let provider = request.parameters.get("provider").and_then(|v| v.as_str());
+
+        let result =
+            match activity_query::query_for_user(&self.connections, user_uuid, provider, &query)
+                .await
+            {
+                Ok(result) => result,
+                Err(e) => return Self::provider_error_response(e),
+            };
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(serde_json::to_value(result).map_err(|e| {
+                ProtocolError::ExecutionFailed(format!("Failed to serialize query: {}", e))
+            })?),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle get_streaks over the user's full history
+    async fn handle_get_streaks_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::streaks::{self, StreakRequest};
+        use crate::protocols::ProtocolError;
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id)
+            .map_err(|_| ProtocolError::InvalidParameters("Invalid user ID format".to_string()))?;
+        let streak_request = StreakRequest::from_args(&request.parameters)
+            .map_err(|e| ProtocolError::InvalidParameters(e.to_string()))?;
+        let provider = request.parameters.get("provider").and_then(|v| v.as_str());
+
+        let result =
+            streaks::streaks_for_user(&self.connections, user_uuid, provider, &streak_request)
+                .await
+                .map_err(|e| ProtocolError::ExecutionFailed(e.to_string()))?;
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(result),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle get_digest, building and storing the digest when needed
+    async fn handle_get_digest_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::digest::{self, DigestRequest};
+        use crate::protocols::ProtocolError;
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id)
+            .map_err(|_| ProtocolError::InvalidParameters("Invalid user ID format".to_string()))?;
+        let digest_request = DigestRequest::from_args(&request.parameters)
+            .map_err(|e| ProtocolError::InvalidParameters(e.to_string()))?;
+
+        let result = digest::requested_digest(&self.connections, user_uuid, &digest_request)
+            .await
+            .map_err(|e| ProtocolError::ExecutionFailed(e.to_string()))?;
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(result),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle the repeated route tools
+    async fn handle_routes_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::routes;
+        use crate::protocols::ProtocolError;
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id)
+            .map_err(|_| ProtocolError::InvalidParameters("Invalid user ID format".to_string()))?;
+        let param = |name: &str| request.parameters.get(name).and_then(|v| v.as_str());
+
+        let result = if request.tool_name == "list_my_routes" {
+            let limit = request
+                .parameters
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(10) as usize;
+            routes::routes_for_user(&self.connections, user_uuid, param("sport_type"), limit).await
+        } else {
+            if param("route_id").is_none() && param("activity_id").is_none() {
+                return Err(ProtocolError::InvalidParameters(
+                    "Provide route_id or activity_id".to_string(),
+                ));
+            }
+            routes::route_history_for_user(
+                &self.connections,
+                user_uuid,
+                param("route_id"),
+                param("activity_id"),
+            )
+            .await
+        }
+        .map_err(|e| ProtocolError::ExecutionFailed(e.to_string()))?;
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(result),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle anomaly detection over recent activities or a single one
+    async fn handle_detect_anomalies_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::anomalies;
+        use crate::protocols::ProtocolError;
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id)
+            .map_err(|_| ProtocolError::InvalidParameters("Invalid user ID format".to_string()))?;
+        let days = request
+            .parameters
+            .get("days")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(anomalies::DEFAULT_DAYS);
+        let activity_id = request
+            .parameters
+            .get("activity_id")
+            .and_then(|v| v.as_str());
+
+        let result = anomalies::anomalies_for_user(&self.connections, user_uuid, activity_id, days)
+            .await
+            .map_err(|e| ProtocolError::ExecutionFailed(e.to_string()))?;
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(result),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle the wellness tools: fetch from the provider, store, then analyze
+    async fn handle_wellness_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        use crate::intelligence::wellness;
+
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id).map_err(|_| {
+            crate::protocols::ProtocolError::InvalidParameters("Invalid user ID format".to_string())
+        })?;
+        let provider_name = request
+            .parameters
+            .get("provider")
+            .and_then(|v| v.as_str())
+            .unwrap_or("fitbit");
+        let supports_wellness = crate::providers::registry()
+            .get(provider_name)
+            .is_some_and(|d| d.supports(crate::providers::ProviderCapability::Wellness));
+        if !supports_wellness {
+            return Err(crate::protocols::ProtocolError::InvalidParameters(format!(
+                "{} does not provide wellness data",
+                provider_name
+            )));
+        }
+        let (start, end) = wellness::date_range(&request.parameters)
+            .map_err(|e| crate::protocols::ProtocolError::InvalidParameters(e.to_string()))?;
+
+        let token = match self.get_valid_token(user_uuid, provider_name).await {
+            Ok(Some(token)) => token,
+            Ok(None) => {
+                return Ok(UniversalResponse {
+                    success: false,
+                    result: None,
+                    error: Some(format!(
+                        "No {} token found for user - please connect your {} account first",
+                        provider_name, provider_name
+                    )),
+                    metadata: None,
+                });
+            }
+            Err(e) => {
+                return Ok(UniversalResponse {
+                    success: false,
+                    result: None,
+                    error: Some(format!("OAuth error: {}", e)),
+                    metadata: None,
+                });
+            }
+        };
+
+        let days = async {
+            let provider = crate::providers::registry()
+                .create_authenticated(
+                    provider_name,
+                    &self.config.external_services,
+                    &token.access_token,
+                    &token.refresh_token,
+                )
+                .await?;
+            wellness::sync_daily_wellness(&self.database, provider.as_ref(), user_uuid, start, end)
+                .await
+        }
+        .await
+        .map_err(|e| crate::protocols::ProtocolError::ExecutionFailed(e.to_string()))?;
+
+        let mut result = match request.tool_name.as_str() {
+            "get_sleep" => wellness::sleep_report(&days),
+            "get_resting_hr_trend" => wellness::resting_hr_report(&days),
+            _ => wellness::daily_wellness_report(&days),
+        };
+        result["provider"] = serde_json::Value::String(provider_name.to_string());
+        result["start_date"] = serde_json::json!(start);
+        result["end_date"] = serde_json::json!(end);
+
+        Ok(UniversalResponse {
+            success: true,
+            result: Some(result),
+            error: None,
+            metadata: None,
+        })
+    }
+
+    /// Handle Strava connection asynchronously
+    async fn handle_connect_strava_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        // Parse user ID
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id).map_err(|_| {
+            crate::protocols::ProtocolError::InvalidParameters("Invalid user ID format".to_string())
+        })?;
+
+        // Create OAuth manager with database
+        let mut oauth_manager = crate::oauth::manager::OAuthManager::new(self.database.clone());
+
+        // Register Strava provider using centralized config
+        match crate::oauth::providers::StravaOAuthProvider::from_config(&self.config.oauth.strava) {
+            Ok(strava_provider) => {
+                oauth_manager.register_provider(Box::new(strava_provider));
+
+                // Generate authorization URL
+                match oauth_manager.generate_auth_url(user_uuid, "strava").await {
+                    Ok(auth_response) => Ok(UniversalResponse {
+                        success: true,
+                        result: Some(serde_json::json!({
+                            "authorization_url": auth_response.authorization_url,
+                            "state": auth_response.state,
+                            "provider": auth_response.provider
+                        })),
+                        error: None,
+                        metadata: None,
+                    }),
+                    Err(e) => Ok(UniversalResponse {
+                        success: false,
+                        result: None,
+                        error: Some(format!(
+                            "Failed to generate Strava authorization URL: {}",
+                            e
+                        )),
+                        metadata: None,
+                    }),
+                }
+            }
+            Err(e) => Ok(UniversalResponse {
+                success: false,
+                result: None,
+                error: Some(format!("Failed to initialize Strava provider: {}", e)),
+                metadata: None,
+            }),
+        }
+    }
+
+    /// Handle Fitbit connection asynchronously
+    async fn handle_connect_fitbit_async(
+        &self,
+        request: UniversalRequest,
+    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
+        // Parse user ID
+        let user_uuid = uuid::Uuid::parse_str(&request.user_id).map_err(|_| {
+            crate::protocols::ProtocolError::InvalidParameters("Invalid user ID format".to_string())
+        })?;
+
+        // Create OAuth manager with database
+        let mut oauth_manager = crate::oauth::manager::OAuthManager::new(self.database.clone());
+
+        // Register Fitbit provider using centralized config
         match crate::oauth::providers::FitbitOAuthProvider::from_config(&self.config.oauth.fitbit) {
             Ok(fitbit_provider) => {
                 oauth_manager.register_provider(Box::new(fitbit_provider));
@@ -1117,12 +1867,16 @@ impl UniversalToolExecutor {
             )
         });
 
-        let scope = "activity heartrate location nutrition profile settings sleep social weight";
+        let scope = "activity heartrate location nutrition oxygen_saturation profile settings sleep social weight";
         let state = uuid::Uuid::new_v4().to_string();
 
         let auth_url = format!(
-            "https://www.fitbit.com/oauth2/authorize?client_id={}&redirect_uri={}&response_type=code&scope={}&state={}",
-            client_id, redirect_uri, scope, state
+            "{}?client_id={}&redirect_uri={}&response_type=code&scope={}&state={}",
+            crate::constants::env_config::fitbit_auth_url(),
+            client_id,
+            redirect_uri,
+            scope,
+            state
         );
 
         Ok(UniversalResponse {
@@ -1180,34 +1934,22 @@ impl UniversalToolExecutor {
             // Use OAuth manager to revoke tokens
             let mut oauth_manager = crate::oauth::manager::OAuthManager::new(executor.database.clone());
 
-            // Register the provider based on type
-            let provider_result = match provider {
-                "strava" => {
-                    match crate::oauth::providers::StravaOAuthProvider::from_config(&executor.config.oauth.strava) {
-                        Ok(strava_provider) => {
-                            oauth_manager.register_provider(Box::new(strava_provider));
-                            oauth_manager.disconnect_provider(user_uuid, "strava").await
-                        }
-                        Err(e) => Err(crate::oauth::OAuthError::ConfigurationError(e.to_string())),
-                    }
-                }
-                "fitbit" => {
-                    match crate::oauth::providers::FitbitOAuthProvider::from_config(&executor.config.oauth.fitbit) {
-                        Ok(fitbit_provider) => {
-                            oauth_manager.register_provider(Box::new(fitbit_provider));
-                            oauth_manager.disconnect_provider(user_uuid, "fitbit").await
-                        }
-                        Err(e) => Err(crate::oauth::OAuthError::ConfigurationError(e.to_string())),
-                    }
-                }
-                _ => {
-                    return Ok(UniversalResponse {
-                        success: false,
-                        result: None,
-                        error: Some(format!("Unsupported provider: {}", provider)),
-                        metadata: None,
-                    });
+            let Some(descriptor) = crate::providers::registry().get(provider) else {
+                return Ok(UniversalResponse {
+                    success: false,
+                    result: None,
+                    error: Some(format!("Unsupported provider: {}", provider)),
+                    metadata: None,
+                });
+            };
+
+            // Register the provider's OAuth client so its tokens can be revoked
+            let provider_result = match descriptor.oauth_provider(&executor.config.oauth) {
+                Ok(oauth_provider) => {
+                    oauth_manager.register_provider(oauth_provider);
+                    oauth_manager.disconnect_provider(user_uuid, descriptor.name).await
                 }
+                Err(e) => Err(e),
             };
 
             match provider_result {
@@ -1274,424 +2016,122 @@ impl UniversalToolExecutor {
                 ))?;
 
             // Try to get activity from various sources
-            // First try Strava if user has connection
-            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, "strava").await {
-                match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET").unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        if let Ok(()) = provider.authenticate(auth_data).await {
-                            // Get recent activities and find the one with matching ID
-                            if let Ok(activities) = provider.get_activities(Some(50), None).await {
-                                if let Some(activity) = activities.iter().find(|a| a.id == activity_id) {
-                                    // Calculate metrics using the intelligence system
-                                    let metrics_calculator = crate::intelligence::metrics::MetricsCalculator::new();
-                                    match metrics_calculator.calculate_metrics(activity) {
-                                        Ok(metrics) => {
-                                            return Ok(UniversalResponse {
-                                                success: true,
-                                                result: Some(serde_json::json!({
-                                                    "activity_id": activity_id,
-                                                    "metrics": {
-                                                        "trimp": metrics.trimp,
-                                                        "power_to_weight_ratio": metrics.power_to_weight_ratio,
-                                                        "aerobic_efficiency": metrics.aerobic_efficiency,
-                                                        "training_stress_score": metrics.training_stress_score,
-                                                        "intensity_factor": metrics.intensity_factor,
-                                                        "variability_index": metrics.variability_index,
-                                                        "efficiency_factor": metrics.efficiency_factor,
-                                                        "decoupling_percentage": metrics.decoupling_percentage,
-                                                        "custom_metrics": metrics.custom_metrics
-                                                    },
-                                                    "data_source": "strava",
-                                                    "calculated_at": chrono::Utc::now().to_rfc3339()
-                                                })),
-                                                error: None,
-                                                metadata: Some({
-                                                    let mut map = std::collections::HashMap::new();
-                                                    map.insert("calculation_engine".to_string(), serde_json::Value::String("intelligence_metrics".to_string()));
-                                                    map.insert("activity_sport_type".to_string(), serde_json::Value::String(format!("{:?}", activity.sport_type)));
-                                                    map
-                                                }),
-                                            });
-                                        }
-                                        Err(e) => {
-                                            return Ok(UniversalResponse {
-                                                success: false,
-                                                result: None,
-                                                error: Some(format!("Failed to calculate metrics: {}", e)),
-                                                metadata: None,
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
-
-            // If we couldn't find the activity, return an error
-            Ok(UniversalResponse {
-                success: false,
-                result: None,
-                error: Some(format!("Activity with ID '{}' not found or user not connected to any provider", activity_id)),
-                metadata: Some({
-                    let mut map = std::collections::HashMap::new();
-                    map.insert("suggestion".to_string(), serde_json::Value::String("Connect to Strava or other providers to access your activities".to_string()));
-                    map
-                }),
-            })
-        });
-
-        metrics_result
-    }
-
-    /// Handle analyze_performance_trends tool
-    fn handle_analyze_performance_trends(
-        executor: &UniversalToolExecutor,
-        request: UniversalRequest,
-    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
-        // Extract parameters
-        let timeframe_str = request
-            .parameters
-            .get("timeframe")
-            .and_then(|v| v.as_str())
-            .unwrap_or("month");
-
-        let metric = request
-            .parameters
-            .get("metric")
-            .and_then(|v| v.as_str())
-            .unwrap_or("pace");
-
-        // Convert timeframe string to TimeFrame enum
-        let timeframe = match timeframe_str {
-            "week" => crate::intelligence::TimeFrame::Week,
-            "month" => crate::intelligence::TimeFrame::Month,
-            "quarter" => crate::intelligence::TimeFrame::Quarter,
-            "year" => crate::intelligence::TimeFrame::Year,
-            _ => crate::intelligence::TimeFrame::Month,
-        };
-
-        let rt = tokio::runtime::Handle::current();
-        let analysis_result = rt.block_on(async {
-            // Parse user ID
-            let user_uuid = uuid::Uuid::parse_str(&request.user_id).map_err(|_| {
-                crate::protocols::ProtocolError::InvalidParameters(
-                    "Invalid user ID format".to_string(),
-                )
-            })?;
-
-            // Get activities from provider
-            let mut activities = Vec::new();
-            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, "strava").await {
-                match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET")
-                                .unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        if let Ok(()) = provider.authenticate(auth_data).await {
-                            if let Ok(provider_activities) =
-                                provider.get_activities(Some(200), None).await
-                            {
-                                activities = provider_activities;
-                            }
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
-
-            if activities.is_empty() {
-                return Ok(UniversalResponse {
-                    success: false,
-                    result: None,
-                    error: Some(
-                        "No activities found or user not connected to any provider".to_string(),
-                    ),
-                    metadata: None,
-                });
-            }
-
-            // Use the performance analyzer from intelligence module
-            let analyzer =
-                crate::intelligence::performance_analyzer::AdvancedPerformanceAnalyzer::new();
-
-            match analyzer
-                .analyze_trends(&activities, timeframe, metric)
-                .await
-            {
-                Ok(trend_analysis) => Ok(UniversalResponse {
-                    success: true,
-                    result: Some(serde_json::json!({
-                        "timeframe": timeframe_str,
-                        "metric": metric,
-                        "trend_direction": format!("{:?}", trend_analysis.trend_direction),
-                        "trend_strength": trend_analysis.trend_strength,
-                        "statistical_significance": trend_analysis.statistical_significance,
-                        "data_points_count": trend_analysis.data_points.len(),
-                        "insights": trend_analysis.insights,
-                        "analysis_date": chrono::Utc::now().to_rfc3339(),
-                        "data_source": "strava"
-                    })),
-                    error: None,
-                    metadata: Some({
-                        let mut map = std::collections::HashMap::new();
-                        map.insert(
-                            "analysis_engine".to_string(),
-                            serde_json::Value::String("advanced_performance_analyzer".to_string()),
-                        );
-                        map.insert(
-                            "activities_analyzed".to_string(),
-                            serde_json::Value::Number(serde_json::Number::from(activities.len())),
-                        );
-                        map
-                    }),
-                }),
-                Err(e) => Ok(UniversalResponse {
-                    success: false,
-                    result: None,
-                    error: Some(format!("Failed to analyze performance trends: {}", e)),
-                    metadata: None,
-                }),
-            }
-        });
-
-        analysis_result
-    }
-
-    /// Handle compare_activities tool
-    fn handle_compare_activities(
-        executor: &UniversalToolExecutor,
-        request: UniversalRequest,
-    ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
-        // Extract activity ID to compare
-        let activity_id = request
-            .parameters
-            .get("activity_id")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                crate::protocols::ProtocolError::InvalidParameters(
-                    "Missing required parameter: activity_id".to_string(),
-                )
-            })?;
-
-        let rt = tokio::runtime::Handle::current();
-        let comparison_result = rt.block_on(async {
-            // Parse user ID
-            let user_uuid = uuid::Uuid::parse_str(&request.user_id).map_err(|_| {
-                crate::protocols::ProtocolError::InvalidParameters(
-                    "Invalid user ID format".to_string(),
-                )
-            })?;
-
-            // Get activities from provider
-            let mut activities = Vec::new();
-            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, "strava").await {
-                match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET")
-                                .unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        if let Ok(()) = provider.authenticate(auth_data).await {
-                            if let Ok(provider_activities) =
-                                provider.get_activities(Some(200), None).await
-                            {
-                                activities = provider_activities;
-                            }
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
-
-            if activities.is_empty() {
-                return Ok(UniversalResponse {
-                    success: false,
-                    result: None,
-                    error: Some(
-                        "No activities found or user not connected to any provider".to_string(),
-                    ),
-                    metadata: None,
-                });
-            }
-
-            // Find the target activity
-            let target_activity = activities.iter().find(|a| a.id == activity_id);
-            if target_activity.is_none() {
-                return Ok(UniversalResponse {
-                    success: false,
-                    result: None,
-                    error: Some(format!("Activity with ID '{}' not found", activity_id)),
-                    metadata: None,
-                });
-            }
-
-            let target_activity = target_activity.unwrap();
-
-            // Use the activity analyzer to compare to history
-            let analyzer = crate::intelligence::activity_analyzer::AdvancedActivityAnalyzer::new();
-
-            match analyzer
-                .compare_to_history(target_activity, &activities)
-                .await
-            {
-                Ok(comparison_insights) => {
-                    // Calculate additional comparison metrics
-                    let same_sport_activities: Vec<_> = activities
-                        .iter()
-                        .filter(|a| {
-                            a.sport_type == target_activity.sport_type && a.id != activity_id
-                        })
-                        .collect();
-
-                    let personal_bests = {
-                        let mut pbs = std::collections::HashMap::new();
-
-                        if let Some(distance) = target_activity.distance_meters {
-                            let best_distance = same_sport_activities
-                                .iter()
-                                .filter_map(|a| a.distance_meters)
-                                .fold(0.0, |max, d| if d > max { d } else { max });
-
-                            pbs.insert(
-                                "distance",
-                                serde_json::json!({
-                                    "current": distance,
-                                    "personal_best": best_distance,
-                                    "is_pb": distance >= best_distance
-                                }),
-                            );
-                        }
-
-                        if let Some(speed) = target_activity.average_speed {
-                            let best_speed = same_sport_activities
-                                .iter()
-                                .filter_map(|a| a.average_speed)
-                                .fold(0.0, |max, s| if s > max { s } else { max });
-
-                            pbs.insert(
-                                "speed",
-                                serde_json::json!({
-                                    "current": speed,
-                                    "personal_best": best_speed,
-                                    "is_pb": speed >= best_speed
-                                }),
-                            );
-                        }
-
-                        pbs
-                    };
-
-                    let percentile_rankings = {
-                        let mut rankings = std::collections::HashMap::new();
-
-                        if let Some(distance) = target_activity.distance_meters {
-                            let distances: Vec<f64> = same_sport_activities
-                                .iter()
-                                .filter_map(|a| a.distance_meters)
-                                .collect();
-
-                            if !distances.is_empty() {
-                                let better_count =
-                                    distances.iter().filter(|&&d| d > distance).count();
-                                let percentile = ((distances.len() - better_count) as f64
-                                    / distances.len() as f64)
-                                    * 100.0;
-                                rankings.insert("distance_percentile", percentile);
-                            }
-                        }
-
-                        if let Some(speed) = target_activity.average_speed {
-                            let speeds: Vec<f64> = same_sport_activities
-                                .iter()
-                                .filter_map(|a| a.average_speed)
-                                .collect();
-
-                            if !speeds.is_empty() {
-                                let better_count = speeds.iter().filter(|&&s| s > speed).count();
-                                let percentile = ((speeds.len() - better_count) as f64
-                                    / speeds.len() as f64)
-                                    * 100.0;
-                                rankings.insert("speed_percentile", percentile);
+            // First try Strava if user has connection
+            let provider_type = request
+                .parameters
+                .get("provider")
+                .and_then(|v| v.as_str())
+                .unwrap_or("strava");
+            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, provider_type).await {
+                match executor.authenticated_provider(user_uuid, provider_type, &token_data).await {
+                    Ok(provider) => {
+                        // Get recent activities and find the one with matching ID
+                        if let Ok(activities) = provider.get_activities(Some(50), None).await {
+                            if let Some(activity) = activities.iter().find(|a| a.id == activity_id) {
+                                // Calculate metrics against the user's estimated thresholds
+                                let metrics_calculator = crate::intelligence::thresholds::metrics_calculator_for(&executor.database, user_uuid).await;
+                                // Altitude streams and measured weather refine the adjusted pace
+                                let streams = provider.get_activity_streams(&activity.id).await.ok();
+                                let weather = crate::intelligence::adjusted_pace::measured_weather(&[activity])
+                                    .await
+                                    .pop()
+                                    .flatten();
+                                match metrics_calculator.calculate_metrics_with_conditions(
+                                    activity,
+                                    streams.as_ref(),
+                                    weather.as_ref(),
+                                ) {
+                                    Ok(metrics) => {
+                                        return Ok(UniversalResponse {
+                                            success: true,
+                                            result: Some(serde_json::json!({
+                                                "activity_id": activity_id,
+                                                "metrics": {
+                                                    "trimp": metrics.trimp,
+                                                    "power_to_weight_ratio": metrics.power_to_weight_ratio,
+                                                    "aerobic_efficiency": metrics.aerobic_efficiency,
+                                                    "training_stress_score": metrics.training_stress_score,
+                                                    "intensity_factor": metrics.intensity_factor,
+                                                    "variability_index": metrics.variability_index,
+                                                    "efficiency_factor": metrics.efficiency_factor,
+                                                    "decoupling_percentage": metrics.decoupling_percentage,
+                                                    "grade_adjusted_pace_seconds_per_km": metrics.grade_adjusted_pace_seconds_per_km,
+                                                    "heat_adjusted_pace_seconds_per_km": metrics.heat_adjusted_pace_seconds_per_km,
+                                                    "custom_metrics": metrics.custom_metrics
+                                                },
+                                                "data_source": provider_type,
+                                                "calculated_at": chrono::Utc::now().to_rfc3339()
+                                            })),
+                                            error: None,
+                                            metadata: Some({
+                                                let mut map = std::collections::HashMap::new();
+                                                map.insert("calculation_engine".to_string(), serde_json::Value::String("intelligence_metrics".to_string()));
+                                                map.insert("activity_sport_type".to_string(), serde_json::Value::String(format!("{:?}", activity.sport_type)));
+                                                map
+                                            }),
+                                        });
+                                    }
+                                    Err(e) => {
+                                        return Ok(UniversalResponse {
+                                            success: false,
+                                            result: None,
+                                            error: Some(format!("Failed to calculate metrics: {}", e)),
+                                            metadata: None,
+                                        });
+                                    }
+                                }
                             }
                         }
-
-                        rankings
-                    };
-
-                    Ok(UniversalResponse {
-                        success: true,
-                        result: Some(serde_json::json!({
-                            "activity_id": activity_id,
-                            "sport_type": format!("{:?}", target_activity.sport_type),
-                            "comparison_insights": comparison_insights,
-                            "personal_bests": personal_bests,
-                            "percentile_rankings": percentile_rankings,
-                            "similar_activities_count": same_sport_activities.len(),
-                            "comparison_date": chrono::Utc::now().to_rfc3339(),
-                            "data_source": "strava"
-                        })),
-                        error: None,
-                        metadata: Some({
-                            let mut map = std::collections::HashMap::new();
-                            map.insert(
-                                "comparison_engine".to_string(),
-                                serde_json::Value::String("advanced_activity_analyzer".to_string()),
-                            );
-                            map.insert(
-                                "total_activities_analyzed".to_string(),
-                                serde_json::Value::Number(serde_json::Number::from(
-                                    activities.len(),
-                                )),
-                            );
-                            map
-                        }),
-                    })
+                    }
+                    Err(_) => {}
                 }
-                Err(e) => Ok(UniversalResponse {
-                    success: false,
-                    result: None,
-                    error: Some(format!("Failed to compare activities: {}", e)),
-                    metadata: None,
-                }),
             }
+
+            // If we couldn't find the activity, return an error
+            Ok(UniversalResponse {
+                success: false,
+                result: None,
+                error: Some(format!("Activity with ID '{}' not found or user not connected to any provider", activity_id)),
+                metadata: Some({
+                    let mut map = std::collections::HashMap::new();
+                    map.insert("suggestion".to_string(), serde_json::Value::String("Connect to Strava or other providers to access your activities".to_string()));
+                    map
+                }),
+            })
         });
 
-        comparison_result
+        metrics_result
     }
 
-    /// Handle detect_patterns tool
-    fn handle_detect_patterns(
+    /// Handle analyze_performance_trends tool
+    fn handle_analyze_performance_trends(
         executor: &UniversalToolExecutor,
         request: UniversalRequest,
     ) -> Result<UniversalResponse, crate::protocols::ProtocolError> {
-        // Extract pattern type parameter
-        let pattern_type = request
+        // Extract parameters
+        let timeframe_str = request
             .parameters
-            .get("pattern_type")
+            .get("timeframe")
+            .and_then(|v| v.as_str())
+            .unwrap_or("month");
+
+        let metric = request
+            .parameters
+            .get("metric")
             .and_then(|v| v.as_str())
-            .unwrap_or("all"); // all, consistency, seasonal, performance
+            .unwrap_or("pace");
+
+        // Convert timeframe string to TimeFrame enum
+        let timeframe = match timeframe_str {
+            "week" => crate::intelligence::TimeFrame::Week,
+            "month" => crate::intelligence::TimeFrame::Month,
+            "quarter" => crate::intelligence::TimeFrame::Quarter,
+            "year" => crate::intelligence::TimeFrame::Year,
+            _ => crate::intelligence::TimeFrame::Month,
+        };
 
         let rt = tokio::runtime::Handle::current();
-        let patterns_result = rt.block_on(async {
+        let analysis_result = rt.block_on(async {
             // Parse user ID
             let user_uuid = uuid::Uuid::parse_str(&request.user_id).map_err(|_| {
                 crate::protocols::ProtocolError::InvalidParameters(
@@ -1699,30 +2139,13 @@ impl UniversalToolExecutor {
                 )
             })?;
 
-            // Get activities from provider
-            let mut activities = Vec::new();
-            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, "strava").await {
-                match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET")
-                                .unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        if let Ok(()) = provider.authenticate(auth_data).await {
-                            if let Ok(provider_activities) =
-                                provider.get_activities(Some(365), None).await
-                            {
-                                activities = provider_activities;
-                            }
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
+            let activities = match executor
+                .merged_recent_activities(user_uuid, &request, 200)
+                .await
+            {
+                Ok(activities) => activities,
+                Err(e) => return UniversalToolExecutor::provider_error_response(e),
+            };
 
             if activities.is_empty() {
                 return Ok(UniversalResponse {
@@ -1735,176 +2158,69 @@ impl UniversalToolExecutor {
                 });
             }
 
-            // Detect different types of patterns
-            let mut detected_patterns = Vec::new();
-
-            // 1. Training consistency patterns
-            if pattern_type == "all" || pattern_type == "consistency" {
-                let mut weekly_counts = std::collections::HashMap::new();
-                for activity in &activities {
-                    let week_start = activity
-                        .start_date
-                        .date_naive()
-                        .week(chrono::Weekday::Mon)
-                        .first_day();
-                    *weekly_counts.entry(week_start).or_insert(0) += 1;
-                }
-
-                let avg_weekly_activities =
-                    weekly_counts.values().sum::<i32>() as f64 / weekly_counts.len() as f64;
-                let consistent_weeks = weekly_counts.values().filter(|&&count| count >= 3).count();
-                let consistency_ratio = consistent_weeks as f64 / weekly_counts.len() as f64;
-
-                detected_patterns.push(serde_json::json!({
-                    "type": "training_consistency",
-                    "description": if consistency_ratio > 0.7 {
-                        "Highly consistent training pattern"
-                    } else if consistency_ratio > 0.4 {
-                        "Moderately consistent training"
-                    } else {
-                        "Inconsistent training pattern"
-                    },
-                    "metrics": {
-                        "average_weekly_activities": avg_weekly_activities,
-                        "consistency_ratio": consistency_ratio,
-                        "consistent_weeks": consistent_weeks,
-                        "total_weeks": weekly_counts.len()
-                    }
-                }));
-            }
-
-            // 2. Seasonal patterns
-            if pattern_type == "all" || pattern_type == "seasonal" {
-                let mut monthly_distance = std::collections::HashMap::new();
-                for activity in &activities {
-                    let month = activity.start_date.format("%Y-%m").to_string();
-                    let distance = activity.distance_meters.unwrap_or(0.0);
-                    *monthly_distance.entry(month).or_insert(0.0) += distance;
-                }
-
-                if monthly_distance.len() >= 3 {
-                    let distances: Vec<f64> = monthly_distance.values().cloned().collect();
-                    let avg_distance = distances.iter().sum::<f64>() / distances.len() as f64;
-                    let max_distance = distances
-                        .iter()
-                        .fold(0.0, |max, &d| if d > max { d } else { max });
-                    let min_distance =
-                        distances
-                            .iter()
-                            .fold(f64::INFINITY, |min, &d| if d < min { d } else { min });
-
-                    detected_patterns.push(serde_json::json!({
-                        "type": "seasonal_volume",
-                        "description": "Monthly training volume variations",
-                        "metrics": {
-                            "average_monthly_distance": avg_distance,
-                            "peak_month_distance": max_distance,
-                            "lowest_month_distance": min_distance,
-                            "volume_variability": (max_distance - min_distance) / avg_distance
-                        }
-                    }));
-                }
-            }
-
-            // 3. Performance patterns
-            if pattern_type == "all" || pattern_type == "performance" {
-                let running_activities: Vec<_> = activities
+            // Heat adjustment needs the measured weather of each activity in the timeframe
+            let mut weather = std::collections::HashMap::new();
+            if metric == "adjusted_pace" {
+                let start = timeframe.start_date();
+                let in_timeframe: Vec<&crate::models::Activity> = activities
                     .iter()
-                    .filter(|a| format!("{:?}", a.sport_type) == "Run")
-                    .filter(|a| a.average_speed.is_some())
+                    .filter(|a| a.start_date >= start)
                     .collect();
-
-                if running_activities.len() >= 5 {
-                    let speeds: Vec<f64> = running_activities
-                        .iter()
-                        .filter_map(|a| a.average_speed)
-                        .collect();
-
-                    // Calculate trend over last 10 activities
-                    let recent_speeds: Vec<f64> = speeds.iter().rev().take(10).cloned().collect();
-                    if recent_speeds.len() >= 5 {
-                        let first_half_avg =
-                            recent_speeds[..recent_speeds.len() / 2].iter().sum::<f64>()
-                                / (recent_speeds.len() / 2) as f64;
-                        let second_half_avg =
-                            recent_speeds[recent_speeds.len() / 2..].iter().sum::<f64>()
-                                / (recent_speeds.len() - recent_speeds.len() / 2) as f64;
-
-                        let improvement =
-                            ((second_half_avg - first_half_avg) / first_half_avg) * 100.0;
-
-                        detected_patterns.push(serde_json::json!({
-                            "type": "performance_trend",
-                            "description": if improvement > 2.0 {
-                                "Improving pace trend detected"
-                            } else if improvement < -2.0 {
-                                "Declining pace trend detected"
-                            } else {
-                                "Stable pace performance"
-                            },
-                            "metrics": {
-                                "pace_improvement_percentage": improvement,
-                                "recent_activities_analyzed": recent_speeds.len(),
-                                "current_average_speed": second_half_avg
-                            }
-                        }));
+                let measured =
+                    crate::intelligence::adjusted_pace::measured_weather(&in_timeframe).await;
+                for (activity, conditions) in in_timeframe.iter().zip(measured) {
+                    if let Some(conditions) = conditions {
+                        weather.insert(activity.id.clone(), conditions);
                     }
                 }
             }
 
-            // 4. Weekly patterns (day of week preferences)
-            if pattern_type == "all" || pattern_type == "weekly" {
-                let mut day_counts = std::collections::HashMap::new();
-                for activity in &activities {
-                    let day_of_week = activity.start_date.weekday().to_string();
-                    *day_counts.entry(day_of_week).or_insert(0) += 1;
-                }
-
-                let most_active_day = day_counts
-                    .iter()
-                    .max_by_key(|(_, &count)| count)
-                    .map(|(day, count)| (day.clone(), *count));
-
-                if let Some((day, count)) = most_active_day {
-                    detected_patterns.push(serde_json::json!({
-                        "type": "weekly_preference",
-                        "description": format!("Most active on {}", day),
-                        "metrics": {
-                            "preferred_day": day,
-                            "activities_on_preferred_day": count,
-                            "day_distribution": day_counts
-                        }
-                    }));
-                }
-            }
+            // Use the performance analyzer from intelligence module
+            let analyzer =
+                crate::intelligence::performance_analyzer::AdvancedPerformanceAnalyzer::new()
+                    .with_weather(weather);
 
-            Ok(UniversalResponse {
-                success: true,
-                result: Some(serde_json::json!({
-                    "pattern_type_requested": pattern_type,
-                    "patterns_detected": detected_patterns,
-                    "total_patterns": detected_patterns.len(),
-                    "activities_analyzed": activities.len(),
-                    "analysis_date": chrono::Utc::now().to_rfc3339(),
-                    "data_source": "strava"
-                })),
-                error: None,
-                metadata: Some({
-                    let mut map = std::collections::HashMap::new();
-                    map.insert(
-                        "pattern_detection_engine".to_string(),
-                        serde_json::Value::String("statistical_analysis".to_string()),
-                    );
-                    map.insert(
-                        "analysis_period_days".to_string(),
-                        serde_json::Value::Number(serde_json::Number::from(365)),
-                    );
-                    map
+            match analyzer
+                .analyze_trends(&activities, timeframe, metric)
+                .await
+            {
+                Ok(trend_analysis) => Ok(UniversalResponse {
+                    success: true,
+                    result: Some(serde_json::json!({
+                        "timeframe": timeframe_str,
+                        "metric": metric,
+                        "trend_direction": format!("{:?}", trend_analysis.trend_direction),
+                        "trend_strength": trend_analysis.trend_strength,
+                        "statistical_significance": trend_analysis.statistical_significance,
+                        "data_points_count": trend_analysis.data_points.len(),
+                        "insights": trend_analysis.insights,
+                        "analysis_date": chrono::Utc::now().to_rfc3339(),
+                        "data_source": crate::providers::merge::ALL_PROVIDERS
+                    })),
+                    error: None,
+                    metadata: Some({
+                        let mut map = std::collections::HashMap::new();
+                        map.insert(
+                            "analysis_engine".to_string(),
+                            serde_json::Value::String("advanced_performance_analyzer".to_string()),
+                        );
+                        map.insert(
+                            "activities_analyzed".to_string(),
+                            serde_json::Value::Number(serde_json::Number::from(activities.len())),
+                        );
+                        map
+                    }),
                 }),
-            })
+                Err(e) => Ok(UniversalResponse {
+                    success: false,
+                    result: None,
+                    error: Some(format!("Failed to analyze performance trends: {}", e)),
+                    metadata: None,
+                }),
+            }
         });
 
-        patterns_result
+        analysis_result
     }
 
     /// Handle track_progress tool
@@ -1939,27 +2255,13 @@ impl UniversalToolExecutor {
                     "Invalid user ID format".to_string()
                 ))?;
 
-            // Get activities from provider
-            let mut activities = Vec::new();
-            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, "strava").await {
-                match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET").unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        if let Ok(()) = provider.authenticate(auth_data).await {
-                            if let Ok(provider_activities) = provider.get_activities(Some(100), None).await {
-                                activities = provider_activities;
-                            }
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
+            let activities = match executor
+                .merged_recent_activities(user_uuid, &request, 100)
+                .await
+            {
+                Ok(activities) => activities,
+                Err(e) => return UniversalToolExecutor::provider_error_response(e),
+            };
 
             if activities.is_empty() {
                 return Ok(UniversalResponse {
@@ -2016,7 +2318,7 @@ impl UniversalToolExecutor {
                             "on_track": progress_report.on_track,
                             "activities_analyzed": activities.len(),
                             "tracking_date": chrono::Utc::now().to_rfc3339(),
-                            "data_source": "strava"
+                            "data_source": crate::providers::merge::ALL_PROVIDERS
                         })),
                         error: None,
                         metadata: Some({
@@ -2055,30 +2357,13 @@ impl UniversalToolExecutor {
                 )
             })?;
 
-            // Get activities from provider
-            let mut activities = Vec::new();
-            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, "strava").await {
-                match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET")
-                                .unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        if let Ok(()) = provider.authenticate(auth_data).await {
-                            if let Ok(provider_activities) =
-                                provider.get_activities(Some(100), None).await
-                            {
-                                activities = provider_activities;
-                            }
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
+            let activities = match executor
+                .merged_recent_activities(user_uuid, &request, 100)
+                .await
+            {
+                Ok(activities) => activities,
+                Err(e) => return UniversalToolExecutor::provider_error_response(e),
+            };
 
             if activities.is_empty() {
                 return Ok(UniversalResponse {
@@ -2132,7 +2417,7 @@ impl UniversalToolExecutor {
                         },
                         "activities_analyzed": activities.len(),
                         "generated_at": chrono::Utc::now().to_rfc3339(),
-                        "data_source": "strava"
+                        "data_source": crate::providers::merge::ALL_PROVIDERS
                     })),
                     error: None,
                     metadata: Some({
@@ -2192,27 +2477,13 @@ impl UniversalToolExecutor {
                     "Invalid user ID format".to_string()
                 ))?;
 
-            // Get activities from provider
-            let mut activities = Vec::new();
-            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, "strava").await {
-                match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET").unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        if let Ok(()) = provider.authenticate(auth_data).await {
-                            if let Ok(provider_activities) = provider.get_activities(Some(100), None).await {
-                                activities = provider_activities;
-                            }
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
+            let activities = match executor
+                .merged_recent_activities(user_uuid, &request, 100)
+                .await
+            {
+                Ok(activities) => activities,
+                Err(e) => return UniversalToolExecutor::provider_error_response(e),
+            };
 
             if activities.is_empty() {
                 return Ok(UniversalResponse {
@@ -2342,7 +2613,7 @@ impl UniversalToolExecutor {
                         _ => vec!["This goal may be too ambitious", "Focus on building base fitness first", "Consider a more achievable target"]
                     },
                     "analysis_date": chrono::Utc::now().to_rfc3339(),
-                    "data_source": "strava"
+                    "data_source": crate::providers::merge::ALL_PROVIDERS
                 })),
                 error: None,
                 metadata: Some({
@@ -2371,30 +2642,13 @@ impl UniversalToolExecutor {
                 )
             })?;
 
-            // Get activities from provider
-            let mut activities = Vec::new();
-            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, "strava").await {
-                match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET")
-                                .unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        if let Ok(()) = provider.authenticate(auth_data).await {
-                            if let Ok(provider_activities) =
-                                provider.get_activities(Some(50), None).await
-                            {
-                                activities = provider_activities;
-                            }
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
+            let activities = match executor
+                .merged_recent_activities(user_uuid, &request, 50)
+                .await
+            {
+                Ok(activities) => activities,
+                Err(e) => return UniversalToolExecutor::provider_error_response(e),
+            };
 
             if activities.is_empty() {
                 return Ok(UniversalResponse {
@@ -2407,40 +2661,67 @@ impl UniversalToolExecutor {
                 });
             }
 
+            // Injuries listed in the profile tighten the workload limits
+            let injury_history =
+                crate::intelligence::workload::injury_history(&executor.database, user_uuid).await;
+
             // Create a basic user profile for recommendations
-            let user_profile = crate::intelligence::UserFitnessProfile {
-                user_id: request.user_id.clone(),
-                age: Some(30), // Default values - could be enhanced with real user data
-                gender: Some("U".to_string()),
-                weight: Some(70.0),
-                height: Some(175.0),
-                fitness_level: crate::intelligence::FitnessLevel::Intermediate,
-                primary_sports: vec!["Run".to_string()],
-                training_history_months: 12,
-                preferences: crate::intelligence::UserPreferences {
-                    preferred_units: "metric".to_string(),
-                    training_focus: vec!["endurance".to_string()],
-                    injury_history: vec![],
-                    time_availability: crate::intelligence::TimeAvailability {
-                        hours_per_week: 5.0,
-                        preferred_days: vec![
-                            "Monday".to_string(),
-                            "Wednesday".to_string(),
-                            "Friday".to_string(),
-                        ],
-                        preferred_duration_minutes: Some(60),
-                    },
-                },
-            };
+            let user_profile = crate::intelligence::UserFitnessProfile::with_defaults(
+                request.user_id.clone(),
+                injury_history,
+            );
 
             // Use the recommendation engine
             let engine =
                 crate::intelligence::recommendation_engine::AdvancedRecommendationEngine::new();
 
-            match engine
+            // Stored wellness days let the engine factor in recovery
+            let today = chrono::Utc::now().date_naive();
+            let wellness = executor
+                .database
+                .get_daily_wellness(
+                    user_uuid,
+                    "fitbit",
+                    today - chrono::Duration::days(13),
+                    today,
+                )
+                .await
+                .unwrap_or_default();
+            let readiness = crate::intelligence::readiness::merged_readiness(
+                &executor.database,
+                user_uuid,
+                today,
+                &activities,
+            )
+            .await;
+            let workload = crate::intelligence::workload::analyze_workload(
+                &activities,
+                chrono::Utc::now(),
+                &user_profile.preferences.injury_history,
+            );
+
+            let recommendations = match engine
                 .generate_recommendations(&user_profile, &activities)
                 .await
             {
+                Ok(mut recommendations) => {
+                    if let Ok(recovery) = engine.generate_wellness_recommendations(&wellness).await
+                    {
+                        // Recovery advice goes first: it overrides training advice
+                        recommendations.splice(0..0, recovery);
+                    }
+                    // Poor readiness downgrades today's hard sessions
+                    if let Some(readiness) = &readiness {
+                        recommendations = engine.apply_readiness(recommendations, readiness);
+                    }
+                    // Rapid load increases hold back volume advice
+                    recommendations = engine.apply_workload_risk(recommendations, &workload);
+                    Ok(recommendations)
+                }
+                Err(e) => Err(e),
+            };
+
+            match recommendations {
                 Ok(recommendations) => Ok(UniversalResponse {
                     success: true,
                     result: Some(serde_json::json!({
@@ -2451,8 +2732,11 @@ impl UniversalToolExecutor {
                             "primary_sports": user_profile.primary_sports,
                         },
                         "activities_analyzed": activities.len(),
+                        "wellness_days_analyzed": wellness.len(),
+                        "readiness": readiness,
+                        "injury_risk": workload.overall_risk,
                         "generated_at": chrono::Utc::now().to_rfc3339(),
-                        "data_source": "strava"
+                        "data_source": crate::providers::merge::ALL_PROVIDERS
                     })),
                     error: None,
                     metadata: Some({
@@ -2494,30 +2778,13 @@ impl UniversalToolExecutor {
                 )
             })?;
 
-            // Get activities from provider
-            let mut activities = Vec::new();
-            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, "strava").await {
-                match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET")
-                                .unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        if let Ok(()) = provider.authenticate(auth_data).await {
-                            if let Ok(provider_activities) =
-                                provider.get_activities(Some(100), None).await
-                            {
-                                activities = provider_activities;
-                            }
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
+            let activities = match executor
+                .merged_recent_activities(user_uuid, &request, 100)
+                .await
+            {
+                Ok(activities) => activities,
+                Err(e) => return UniversalToolExecutor::provider_error_response(e),
+            };
 
             if activities.is_empty() {
                 return Ok(UniversalResponse {
@@ -2546,7 +2813,7 @@ impl UniversalToolExecutor {
                         "last_updated": fitness_score.last_updated.to_rfc3339(),
                         "activities_analyzed": activities.len(),
                         "calculation_date": chrono::Utc::now().to_rfc3339(),
-                        "data_source": "strava"
+                        "data_source": crate::providers::merge::ALL_PROVIDERS
                     })),
                     error: None,
                     metadata: Some({
@@ -2610,27 +2877,13 @@ impl UniversalToolExecutor {
                     "Invalid user ID format".to_string()
                 ))?;
 
-            // Get activities from provider
-            let mut activities = Vec::new();
-            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, "strava").await {
-                match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET").unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        if let Ok(()) = provider.authenticate(auth_data).await {
-                            if let Ok(provider_activities) = provider.get_activities(Some(100), None).await {
-                                activities = provider_activities;
-                            }
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
+            let activities = match executor
+                .merged_recent_activities(user_uuid, &request, 100)
+                .await
+            {
+                Ok(activities) => activities,
+                Err(e) => return UniversalToolExecutor::provider_error_response(e),
+            };
 
             if activities.is_empty() {
                 return Ok(UniversalResponse {
@@ -2665,7 +2918,7 @@ impl UniversalToolExecutor {
                             "estimated_achievement_date": prediction.estimated_achievement_date.to_rfc3339(),
                             "activities_analyzed": activities.len(),
                             "prediction_date": chrono::Utc::now().to_rfc3339(),
-                            "data_source": "strava"
+                            "data_source": crate::providers::merge::ALL_PROVIDERS
                         })),
                         error: None,
                         metadata: Some({
@@ -2704,30 +2957,13 @@ impl UniversalToolExecutor {
                 )
             })?;
 
-            // Get activities from provider
-            let mut activities = Vec::new();
-            if let Ok(Some(token_data)) = executor.get_valid_token(user_uuid, "strava").await {
-                match create_provider("strava") {
-                    Ok(mut provider) => {
-                        let auth_data = AuthData::OAuth2 {
-                            client_id: std::env::var("STRAVA_CLIENT_ID").unwrap_or_default(),
-                            client_secret: std::env::var("STRAVA_CLIENT_SECRET")
-                                .unwrap_or_default(),
-                            access_token: Some(token_data.access_token.clone()),
-                            refresh_token: Some(token_data.refresh_token.clone()),
-                        };
-
-                        if let Ok(()) = provider.authenticate(auth_data).await {
-                            if let Ok(provider_activities) =
-                                provider.get_activities(Some(100), None).await
-                            {
-                                activities = provider_activities;
-                            }
-                        }
-                    }
-                    Err(_) => {}
-                }
-            }
+            let activities = match executor
+                .merged_recent_activities(user_uuid, &request, 100)
+                .await
+            {
+                Ok(activities) => activities,
+                Err(e) => return UniversalToolExecutor::provider_error_response(e),
+            };
 
             if activities.is_empty() {
                 return Ok(UniversalResponse {
@@ -2744,6 +2980,14 @@ impl UniversalToolExecutor {
             let analyzer =
                 crate::intelligence::performance_analyzer::AdvancedPerformanceAnalyzer::new();
 
+            // Acute:chronic ratios per sport, with injury-risk alerts
+            let workload = crate::intelligence::workload::workload_for_user(
+                &executor.database,
+                user_uuid,
+                &activities,
+            )
+            .await;
+
             match analyzer.analyze_training_load(&activities).await {
                 Ok(training_load_analysis) => Ok(UniversalResponse {
                     success: true,
@@ -2751,12 +2995,15 @@ impl UniversalToolExecutor {
                         "weekly_loads": training_load_analysis.weekly_loads,
                         "average_weekly_load": training_load_analysis.average_weekly_load,
                         "load_balance_score": training_load_analysis.load_balance_score,
-                        "recovery_needed": training_load_analysis.recovery_needed,
+                        "recovery_needed": training_load_analysis.recovery_needed
+                            || workload.overall_risk
+                                == crate::intelligence::workload::InjuryRisk::High,
                         "recommendations": training_load_analysis.recommendations,
                         "insights": training_load_analysis.insights,
+                        "injury_risk": crate::intelligence::workload::workload_json(&workload),
                         "activities_analyzed": activities.len(),
                         "analysis_date": chrono::Utc::now().to_rfc3339(),
-                        "data_source": "strava"
+                        "data_source": crate::providers::merge::ALL_PROVIDERS
                     })),
                     error: None,
                     metadata: Some({
@@ -2874,6 +3121,10 @@ mod tests {
                     environment: crate::config::environment::Environment::Development,
                 },
             },
+            a2a: crate::config::environment::A2AConfig {
+                public_url: None,
+                agent_card_signing_key_path: None,
+            },
             external_services: crate::config::environment::ExternalServicesConfig {
                 weather: crate::config::environment::WeatherServiceConfig {
                     api_key: None,
@@ -2916,7 +3167,17 @@ mod tests {
         // analyze_activity is handled async in execute_tool method
 
         let tools = executor.list_tools();
-        assert_eq!(tools.len(), 16); // All sync tools registered, plus async tools handled in execute_tool
+        assert_eq!(tools.len(), 14); // All sync tools registered, plus async tools handled in execute_tool
+    }
+
+    #[tokio::test]
+    async fn test_supports_every_mcp_tool() {
+        let executor = create_test_executor().await;
+
+        for tool in crate::mcp::schema::get_tools() {
+            assert!(executor.supports_tool(&tool.name), "{}", tool.name);
+        }
+        assert!(!executor.supports_tool("unknown_tool"));
     }
 
     #[tokio::test]
@@ -0,0 +1,83 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reaching the providers a user has connected
+//!
+//! [`ProviderConnections`] bundles what building an authenticated provider
+//! takes: the database holding the tokens, an [`OAuthManager`] to refresh
+//! them and the provider API endpoints the server was configured with.
+
+use super::{registry, FitnessProvider};
+use crate::config::environment::{ExternalServicesConfig, ServerConfig};
+use crate::database_plugins::factory::Database;
+use crate::oauth::manager::OAuthManager;
+use anyhow::Result;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Builds providers signed in with users' stored, refreshed tokens
+#[derive(Clone)]
+pub struct ProviderConnections {
+    database: Arc<Database>,
+    oauth_manager: Arc<OAuthManager>,
+    api: ExternalServicesConfig,
+}
+
+impl ProviderConnections {
+    pub fn new(
+        database: Arc<Database>,
+        oauth_manager: Arc<OAuthManager>,
+        api: ExternalServicesConfig,
+    ) -> Self {
+        Self {
+            database,
+            oauth_manager,
+            api,
+        }
+    }
+
+    /// Connections using the server's OAuth clients and provider endpoints
+    pub fn from_config(database: Arc<Database>, config: &ServerConfig) -> Self {
+        let oauth_manager = Arc::new(OAuthManager::from_config(database.clone(), &config.oauth));
+        Self::new(database, oauth_manager, config.external_services.clone())
+    }
+
+    pub fn database(&self) -> &Arc<Database> {
+        &self.database
+    }
+
+    pub fn api(&self) -> &ExternalServicesConfig {
+        &self.api
+    }
+
+    /// A provider authenticated with the user's stored token, or `None` when
+    /// the user hasn't connected it
+    ///
+    /// Expired tokens are refreshed first. A connection waiting for
+    /// re-authorization is an error.
+    pub async fn connected_provider(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+    ) -> Result<Option<Box<dyn FitnessProvider>>> {
+        let Some(token) = self
+            .oauth_manager
+            .ensure_valid_token(user_id, provider)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let provider = registry()
+            .create_authenticated(
+                provider,
+                &self.api,
+                &token.access_token,
+                &token.refresh_token,
+            )
+            .await?;
+        Ok(Some(provider))
+    }
+}
@@ -0,0 +1,921 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! # Fake Provider Server
+//!
+//! An in-process stand-in for the Strava and Fitbit APIs so the whole
+//! OAuth → provider → tool pipeline can run offline and in CI. It serves the
+//! authorize, token, athlete, activities, streams, stats and Fitbit wellness
+//! endpoints from fixture data, returns realistic rate-limit headers, and can be told to
+//! fail with 401, 429, 5xx or slow responses.
+//!
+//! ```rust,no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use pierre_mcp_server::providers::fake_server::{FakeFailure, FakeProviderServer};
+//!
+//! let server = FakeProviderServer::start(Default::default()).await?;
+//! server.configure_env();
+//! server.fail_next("/strava/api/v3/athlete", FakeFailure::Unauthorized, 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::config::environment::{
+    ExternalServicesConfig, FitbitApiConfig, StravaApiConfig, WeatherServiceConfig,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use warp::http::{Response, StatusCode};
+use warp::hyper::Body;
+use warp::Filter;
+
+/// Strava's 15-minute and daily limits advertised by the fake server
+const STRAVA_RATE_LIMIT: (u32, u32) = (600, 30000);
+/// Fitbit's hourly per-user limit advertised by the fake server
+const FITBIT_RATE_LIMIT: u32 = 150;
+/// Lifetime of issued access tokens in seconds
+const TOKEN_LIFETIME_SECS: i64 = 6 * 60 * 60;
+/// Longest range, in days, Fitbit's HRV and SpO2 interval endpoints accept
+const FITBIT_INTERVAL_MAX_DAYS: i64 = 30;
+/// Most activities Fitbit's activity log list returns per request
+const FITBIT_ACTIVITY_LIST_MAX: usize = 100;
+
+/// Fixture data served by the fake provider APIs
+///
+/// Values use the providers' own JSON shapes so the real response parsing in
+/// `StravaProvider` and `FitbitProvider` is exercised.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FakeProviderData {
+    pub strava_athlete: Value,
+    pub strava_activities: Vec<Value>,
+    pub strava_stats: Value,
+    /// Stream sets keyed by activity id
+    #[serde(default)]
+    pub strava_streams: HashMap<String, Value>,
+    pub fitbit_profile: Value,
+    pub fitbit_activities: Vec<Value>,
+    pub fitbit_lifetime: Value,
+    /// Fitbit wellness time series keyed by resource (`sleep`, `hrv`,
+    /// `spo2`, `activities/heart`, `activities/steps`,
+    /// `activities/active-zone-minutes`); entries carry `dateTime`, or
+    /// `dateOfSleep` for sleep logs
+    #[serde(default)]
+    pub fitbit_wellness: HashMap<String, Vec<Value>>,
+}
+
+impl FakeProviderData {
+    /// Load fixtures from a JSON file
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+impl Default for FakeProviderData {
+    fn default() -> Self {
+        // The ride is sampled each minute with a 20-minute effort at 265 W
+        // from minute 30
+        let ride_minutes: Vec<u32> = (0..=90).collect();
+        let stream = |data: Vec<Value>| json!({"data": data, "series_type": "time", "original_size": 91, "resolution": "medium"});
+        let strava_streams = HashMap::from([
+            (
+                "1001".to_string(),
+                json!({
+                    "time": {"data": [0, 600, 1200, 1800], "series_type": "time", "original_size": 4, "resolution": "high"},
+                    "heartrate": {"data": [120, 148, 156, 162], "series_type": "time", "original_size": 4, "resolution": "high"},
+                    "altitude": {"data": [12.0, 20.5, 31.0, 18.2], "series_type": "time", "original_size": 4, "resolution": "high"},
+                    "distance": {"data": [0.0, 1700.0, 3400.0, 5000.0], "series_type": "time", "original_size": 4, "resolution": "high"}
+                }),
+            ),
+            (
+                "1002".to_string(),
+                json!({
+                    "time": stream(ride_minutes.iter().map(|m| json!(m * 60)).collect()),
+                    "watts": stream(
+                        ride_minutes
+                            .iter()
+                            .map(|m| match m {
+                                30..=49 => json!(265),
+                                89.. => Value::Null,
+                                _ => json!(190),
+                            })
+                            .collect()
+                    ),
+                    "heartrate": stream(
+                        ride_minutes
+                            .iter()
+                            .map(|m| json!(if (30..50).contains(m) { 158 } else { 132 }))
+                            .collect()
+                    ),
+                }),
+            ),
+        ]);
+
+        Self {
+            strava_athlete: json!({
+                "id": 12345,
+                "username": "fake_athlete",
+                "firstname": "Fake",
+                "lastname": "Athlete",
+                "profile": "https://example.com/avatar.png",
+                "shoes": [
+                    {"id": "g201", "name": "Pegasus 40", "primary": true, "distance": 780000.0, "retired": false},
+                    {"id": "g202", "name": "Vaporfly 3", "primary": false, "distance": 120000.0, "retired": false}
+                ],
+                "bikes": [
+                    {"id": "b301", "name": "Tarmac SL7", "primary": true, "distance": 4850000.0, "retired": false}
+                ]
+            }),
+            strava_activities: vec![
+                json!({
+                    "id": 1001, "name": "Morning Run", "type": "Run",
+                    "start_date": "2024-06-03T06:30:00Z", "elapsed_time": 1800,
+                    "distance": 5000.0, "total_elevation_gain": 45.0,
+                    "average_heartrate": 148.0, "max_heartrate": 171.0,
+                    "average_speed": 2.78, "max_speed": 3.9,
+                    "start_latlng": [45.5017, -73.5673],
+                    "gear_id": "g201"
+                }),
+                json!({
+                    "id": 1002, "name": "Lunch Ride", "type": "Ride",
+                    "start_date": "2024-06-02T12:00:00Z", "elapsed_time": 5400,
+                    "distance": 40200.0, "total_elevation_gain": 380.0,
+                    "average_heartrate": 135.0, "max_heartrate": 165.0,
+                    "average_speed": 7.4, "max_speed": 14.2,
+                    "start_latlng": [45.5088, -73.5540],
+                    "gear_id": "b301"
+                }),
+                json!({
+                    "id": 1003, "name": "Long Run", "type": "Run",
+                    "start_date": "2024-06-01T07:00:00Z", "elapsed_time": 6300,
+                    "distance": 21100.0, "total_elevation_gain": 160.0,
+                    "average_heartrate": 152.0, "max_heartrate": 178.0,
+                    "average_speed": 3.35, "max_speed": 4.4,
+                    "start_latlng": [45.5017, -73.5673],
+                    "gear_id": "g202"
+                }),
+            ],
+            strava_stats: json!({
+                "all_ride_totals": {"count": 120, "distance": 4850000.0, "moving_time": 612000, "elevation_gain": 41000.0},
+                "all_run_totals": {"count": 310, "distance": 3020000.0, "moving_time": 1090000, "elevation_gain": 25500.0}
+            }),
+            strava_streams,
+            fitbit_profile: json!({
+                "user": {
+                    "encodedId": "FAKE123",
+                    "displayName": "Fake Fitbit User",
+                    "firstName": "Fake",
+                    "lastName": "User",
+                    "avatar": "https://example.com/fitbit.png"
+                }
+            }),
+            fitbit_activities: vec![
+                json!({
+                    "activityId": 2001, "activityName": "Walk", "activityTypeId": 90001,
+                    "startTime": "2024-06-03T18:00:00.000-04:00", "duration": 2400000,
+                    "distance": 3.2, "steps": 4100, "calories": 190,
+                    "elevationGain": 12.0, "averageHeartRate": 102
+                }),
+                json!({
+                    "activityId": 2002, "activityName": "Run", "activityTypeId": 90009,
+                    "startTime": "2024-06-02T07:15:00.000-04:00", "duration": 2700000,
+                    "distance": 8.0, "steps": 8900, "calories": 610,
+                    "elevationGain": 55.0, "averageHeartRate": 151
+                }),
+            ],
+            fitbit_lifetime: json!({
+                "lifetime": {"total": {"distance": 2450.5, "floors": 1800.0, "steps": 3200000}}
+            }),
+            fitbit_wellness: default_fitbit_wellness(),
+        }
+    }
+}
+
+/// Two weeks ending 2024-06-03 where the last three days show poor recovery:
+/// resting HR up, HRV down and short nights
+fn default_fitbit_wellness() -> HashMap<String, Vec<Value>> {
+    let mut wellness: HashMap<String, Vec<Value>> = HashMap::new();
+    let first = chrono::NaiveDate::from_ymd_opt(2024, 5, 21).unwrap_or_default();
+
+    for (i, date) in first.iter_days().take(14).enumerate() {
+        let tired = i >= 11;
+        let day = date.format("%Y-%m-%d").to_string();
+        let bed = (date - chrono::Duration::days(1)).format("%Y-%m-%d");
+        let (asleep, deep, rem, wake) = if tired {
+            (350, 50, 60, 40)
+        } else {
+            (450, 85, 100, 45)
+        };
+
+        let mut push = |resource: &str, entry: Value| {
+            wellness
+                .entry(resource.to_string())
+                .or_default()
+                .push(entry);
+        };
+        push(
+            "sleep",
+            json!({
+                "dateOfSleep": day, "isMainSleep": true,
+                "startTime": format!("{}T23:00:00.000", bed),
+                "endTime": format!("{}T{:02}:{:02}:00.000", day, (asleep + wake) / 60 - 1, (asleep + wake) % 60),
+                "minutesAsleep": asleep, "timeInBed": asleep + wake, "efficiency": 90,
+                "levels": {"summary": {
+                    "deep": {"minutes": deep}, "light": {"minutes": asleep - deep - rem},
+                    "rem": {"minutes": rem}, "wake": {"minutes": wake}
+                }}
+            }),
+        );
+        push(
+            "activities/heart",
+            json!({"dateTime": day, "value": {"restingHeartRate": if tired { 59 } else { 52 }}}),
+        );
+        push(
+            "hrv",
+            json!({"dateTime": day, "value": {"dailyRmssd": if tired { 48.0 } else { 65.0 }}}),
+        );
+        push(
+            "spo2",
+            json!({"dateTime": day, "value": {"avg": 96.5, "min": 94.0, "max": 98.0}}),
+        );
+        push(
+            "activities/steps",
+            json!({"dateTime": day, "value": (8000 + i * 150).to_string()}),
+        );
+        push(
+            "activities/active-zone-minutes",
+            json!({"dateTime": day, "value": {"activeZoneMinutes": 20 + i}}),
+        );
+    }
+
+    wellness
+}
+
+/// Failure the fake server can be told to return instead of fixture data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FakeFailure {
+    /// 401 as if the access token expired or was revoked
+    Unauthorized,
+    /// 429 with `Retry-After` and exhausted rate-limit headers
+    RateLimited { retry_after_secs: u64 },
+    /// An arbitrary 5xx (or other) status
+    Status(u16),
+    /// Delay the response, then serve it normally
+    Slow(Duration),
+}
+
+#[derive(Debug, Clone)]
+struct FailureRule {
+    path_prefix: String,
+    failure: FakeFailure,
+    /// `None` keeps failing until cleared
+    remaining: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    data: Mutex<FakeProviderData>,
+    failures: Mutex<Vec<FailureRule>>,
+    requests: Mutex<Vec<String>>,
+    revoked_refresh_tokens: Mutex<HashSet<String>>,
+    rotate_refresh_tokens: AtomicBool,
+    reuse_refresh_tokens: AtomicBool,
+    tokens_issued: AtomicU64,
+    strava_usage: AtomicU32,
+    fitbit_usage: AtomicU32,
+}
+
+impl FakeState {
+    fn data(&self) -> MutexGuard<'_, FakeProviderData> {
+        self.data
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Consume the first failure rule matching a path
+    fn take_failure(&self, path: &str) -> Option<FakeFailure> {
+        let mut failures = self
+            .failures
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let index = failures
+            .iter()
+            .position(|rule| path.starts_with(&rule.path_prefix))?;
+        let failure = failures[index].failure.clone();
+        match &mut failures[index].remaining {
+            Some(1) => {
+                failures.remove(index);
+            }
+            Some(remaining) => *remaining -= 1,
+            None => {}
+        }
+        Some(failure)
+    }
+}
+
+/// A running fake Strava/Fitbit API bound to an ephemeral local port
+pub struct FakeProviderServer {
+    address: SocketAddr,
+    state: Arc<FakeState>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl FakeProviderServer {
+    /// Start serving the given fixtures on `127.0.0.1` with a random port
+    pub async fn start(data: FakeProviderData) -> Result<Self> {
+        Self::start_on(([127, 0, 0, 1], 0).into(), data).await
+    }
+
+    /// Start serving the given fixtures on a specific address
+    pub async fn start_on(address: SocketAddr, data: FakeProviderData) -> Result<Self> {
+        let state = Arc::new(FakeState {
+            data: Mutex::new(data),
+            ..Default::default()
+        });
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+
+        let (address, server) = warp::serve(routes(state.clone()))
+            .try_bind_with_graceful_shutdown(address, async {
+                shutdown_rx.await.ok();
+            })?;
+        tokio::spawn(server);
+
+        Ok(Self {
+            address,
+            state,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    /// Root URL of the server, e.g. `http://127.0.0.1:54321`
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Provider endpoints served by this instance, for passing to
+    /// `ProviderRegistry::create_authenticated` or a `ServerConfig`
+    ///
+    /// The weather service is disabled.
+    pub fn external_services(&self) -> ExternalServicesConfig {
+        ExternalServicesConfig {
+            weather: WeatherServiceConfig {
+                api_key: None,
+                base_url: format!("{}/weather", self.url()),
+                enabled: false,
+            },
+            strava_api: StravaApiConfig {
+                base_url: format!("{}/strava/api/v3", self.url()),
+                auth_url: format!("{}/strava/oauth/authorize", self.url()),
+                token_url: format!("{}/strava/oauth/token", self.url()),
+            },
+            fitbit_api: FitbitApiConfig {
+                base_url: format!("{}/fitbit", self.url()),
+                auth_url: format!("{}/fitbit/oauth2/authorize", self.url()),
+                token_url: format!("{}/fitbit/oauth2/token", self.url()),
+            },
+        }
+    }
+
+    /// Environment variables that point the provider and OAuth clients here
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let ExternalServicesConfig {
+            strava_api: strava,
+            fitbit_api: fitbit,
+            ..
+        } = self.external_services();
+        vec![
+            ("STRAVA_API_BASE", strava.base_url),
+            ("STRAVA_AUTH_URL", strava.auth_url),
+            ("STRAVA_TOKEN_URL", strava.token_url),
+            (
+                "STRAVA_DEAUTHORIZE_URL",
+                format!("{}/strava/oauth/deauthorize", self.url()),
+            ),
+            ("FITBIT_API_BASE", fitbit.base_url),
+            ("FITBIT_AUTH_URL", fitbit.auth_url),
+            ("FITBIT_TOKEN_URL", fitbit.token_url),
+            (
+                "FITBIT_REVOKE_URL",
+                format!("{}/fitbit/oauth2/revoke", self.url()),
+            ),
+        ]
+    }
+
+    /// Point the provider and OAuth clients at this server
+    ///
+    /// Sets the same environment variables `ServerConfig::from_env` and
+    /// `env_config` read, so everything created afterwards talks to the fake.
+    pub fn configure_env(&self) {
+        for (name, value) in self.env_vars() {
+            std::env::set_var(name, value);
+        }
+    }
+
+    /// Fail the next `times` requests whose path starts with `path_prefix`
+    pub fn fail_next(&self, path_prefix: &str, failure: FakeFailure, times: usize) {
+        if times == 0 {
+            return;
+        }
+        self.push_failure(path_prefix, failure, Some(times));
+    }
+
+    /// Fail every request whose path starts with `path_prefix` until cleared
+    pub fn fail_always(&self, path_prefix: &str, failure: FakeFailure) {
+        self.push_failure(path_prefix, failure, None);
+    }
+
+    /// Remove all injected failures
+    pub fn clear_failures(&self) {
+        self.state
+            .failures
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Reject future refreshes with this refresh token as `invalid_grant`
+    ///
+    /// Simulates the user revoking access on the provider side.
+    pub fn revoke_refresh_token(&self, refresh_token: &str) {
+        self.state
+            .revoked_refresh_tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(refresh_token.to_string());
+    }
+
+    /// Accept each refresh token only once, as providers that rotate them do
+    ///
+    /// A second refresh with the same token then fails with `invalid_grant`.
+    pub fn rotate_refresh_tokens(&self) {
+        self.state
+            .rotate_refresh_tokens
+            .store(true, Ordering::SeqCst);
+    }
+
+    /// Hand the same refresh token back on every Strava refresh
+    ///
+    /// Strava usually does this, renewing only the access token.
+    pub fn reuse_refresh_tokens(&self) {
+        self.state
+            .reuse_refresh_tokens
+            .store(true, Ordering::SeqCst);
+    }
+
+    /// Replace the fixture data being served
+    pub fn set_data(&self, data: FakeProviderData) {
+        *self.state.data() = data;
+    }
+
+    /// Requests received so far as `"METHOD /path"`
+    pub fn requests(&self) -> Vec<String> {
+        self.state
+            .requests
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    fn push_failure(&self, path_prefix: &str, failure: FakeFailure, remaining: Option<usize>) {
+        self.state
+            .failures
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(FailureRule {
+                path_prefix: path_prefix.to_string(),
+                failure,
+                remaining,
+            });
+    }
+}
+
+impl Drop for FakeProviderServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+/// Parsed request handed to the route dispatcher
+struct FakeRequest {
+    method: warp::http::Method,
+    path: String,
+    query: HashMap<String, String>,
+    form: HashMap<String, String>,
+    authorization: Option<String>,
+}
+
+fn routes(
+    state: Arc<FakeState>,
+) -> impl Filter<Extract = (Response<Body>,), Error = warp::Rejection> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::body::bytes())
+        .and_then(
+            move |method,
+                  path: warp::path::FullPath,
+                  query: String,
+                  authorization,
+                  body: warp::hyper::body::Bytes| {
+                let state = state.clone();
+                async move {
+                    let request = FakeRequest {
+                        method,
+                        path: path.as_str().to_string(),
+                        query: url::form_urlencoded::parse(query.as_bytes())
+                            .into_owned()
+                            .collect(),
+                        form: url::form_urlencoded::parse(&body).into_owned().collect(),
+                        authorization,
+                    };
+                    Ok::<_, warp::Rejection>(handle(&state, request).await)
+                }
+            },
+        )
+}
+
+async fn handle(state: &FakeState, request: FakeRequest) -> Response<Body> {
+    state
+        .requests
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .push(format!("{} {}", request.method, request.path));
+
+    let is_strava = request.path.starts_with("/strava/");
+    let usage = if is_strava {
+        state.strava_usage.fetch_add(1, Ordering::SeqCst) + 1
+    } else {
+        state.fitbit_usage.fetch_add(1, Ordering::SeqCst) + 1
+    };
+
+    if let Some(failure) = state.take_failure(&request.path) {
+        match failure {
+            FakeFailure::Slow(delay) => tokio::time::sleep(delay).await,
+            FakeFailure::Unauthorized => {
+                return error_response(StatusCode::UNAUTHORIZED, "Authorization Error")
+            }
+            FakeFailure::RateLimited { retry_after_secs } => {
+                let mut response =
+                    error_response(StatusCode::TOO_MANY_REQUESTS, "Rate Limit Exceeded");
+                let headers = response.headers_mut();
+                headers.insert("Retry-After", retry_after_secs.into());
+                if is_strava {
+                    // Strava's windows are shared; only Retry-After marks the block
+                    insert_strava_rate_headers(headers, usage);
+                } else {
+                    insert_fitbit_rate_headers(headers, FITBIT_RATE_LIMIT, retry_after_secs as i64);
+                }
+                return response;
+            }
+            FakeFailure::Status(status) => {
+                let status =
+                    StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+                return error_response(status, "Injected failure");
+            }
+        }
+    }
+
+    let mut response = if is_strava {
+        strava_route(state, &request)
+    } else if request.path.starts_with("/fitbit/") {
+        fitbit_route(state, &request)
+    } else {
+        error_response(StatusCode::NOT_FOUND, "Not Found")
+    };
+
+    let headers = response.headers_mut();
+    if is_strava {
+        insert_strava_rate_headers(headers, usage);
+    } else {
+        let reset = 3600 - chrono::Utc::now().timestamp() % 3600;
+        insert_fitbit_rate_headers(headers, usage, reset);
+    }
+    response
+}
+
+fn strava_route(state: &FakeState, request: &FakeRequest) -> Response<Body> {
+    let path = request.path.trim_start_matches("/strava");
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["oauth", "authorize"]) => authorize_redirect(request, "fake-strava-code"),
+        ("POST", ["oauth", "token"]) => {
+            if let Some(error) = invalid_grant(state, request) {
+                return error;
+            }
+            let n = state.tokens_issued.fetch_add(1, Ordering::SeqCst) + 1;
+            let expires_at = chrono::Utc::now().timestamp() + TOKEN_LIFETIME_SECS;
+            let athlete = state.data().strava_athlete.clone();
+            let refresh_token = match request.form.get("refresh_token") {
+                Some(token) if state.reuse_refresh_tokens.load(Ordering::SeqCst) => token.clone(),
+                _ => format!("fake-strava-refresh-{}", n),
+            };
+            json_response(json!({
+                "token_type": "Bearer",
+                "access_token": format!("fake-strava-access-{}", n),
+                "refresh_token": refresh_token,
+                "expires_at": expires_at,
+                "expires_in": TOKEN_LIFETIME_SECS,
+                "scope": "read,activity:read_all",
+                "athlete": athlete,
+            }))
+        }
+        ("POST", ["oauth", "deauthorize"]) => json_response(json!({})),
+        (_, ["api", "v3", ..]) if request.authorization.is_none() => {
+            error_response(StatusCode::UNAUTHORIZED, "Authorization Error")
+        }
+        ("GET", ["api", "v3", "athlete"]) => json_response(state.data().strava_athlete.clone()),
+        ("GET", ["api", "v3", "athlete", "activities"]) => {
+            let per_page = query_usize(request, "per_page").unwrap_or(30).max(1);
+            let page = query_usize(request, "page").unwrap_or(1).max(1);
+            let after = query_usize(request, "after").map(|secs| secs as i64);
+            let before = query_usize(request, "before").map(|secs| secs as i64);
+            let activities: Vec<Value> = state
+                .data()
+                .strava_activities
+                .iter()
+                .filter(|activity| {
+                    let started = activity["start_date"]
+                        .as_str()
+                        .and_then(|date| chrono::DateTime::parse_from_rfc3339(date).ok())
+                        .map(|date| date.timestamp());
+                    started.is_none_or(|started| {
+                        after.is_none_or(|after| started > after)
+                            && before.is_none_or(|before| started < before)
+                    })
+                })
+                .skip((page - 1) * per_page)
+                .take(per_page)
+                .cloned()
+                .collect();
+            json_response(Value::Array(activities))
+        }
+        ("GET", ["api", "v3", "activities", id]) => {
+            match find_by_id(&state.data().strava_activities, "id", id) {
+                Some(activity) => json_response(activity),
+                None => error_response(StatusCode::NOT_FOUND, "Record Not Found"),
+            }
+        }
+        ("GET", ["api", "v3", "activities", id, "streams"]) => {
+            match state.data().strava_streams.get(*id) {
+                Some(streams) => json_response(streams.clone()),
+                None => error_response(StatusCode::NOT_FOUND, "Record Not Found"),
+            }
+        }
+        ("GET", ["api", "v3", "athletes", _, "stats"]) => {
+            json_response(state.data().strava_stats.clone())
+        }
+        _ => error_response(StatusCode::NOT_FOUND, "Not Found"),
+    }
+}
+
+fn fitbit_route(state: &FakeState, request: &FakeRequest) -> Response<Body> {
+    let path = request.path.trim_start_matches("/fitbit");
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["oauth2", "authorize"]) => authorize_redirect(request, "fake-fitbit-code"),
+        ("POST", ["oauth2", "token"]) => {
+            if let Some(error) = invalid_grant(state, request) {
+                return error;
+            }
+            let n = state.tokens_issued.fetch_add(1, Ordering::SeqCst) + 1;
+            let user_id = state.data().fitbit_profile["user"]["encodedId"].clone();
+            json_response(json!({
+                "token_type": "Bearer",
+                "access_token": format!("fake-fitbit-access-{}", n),
+                "refresh_token": format!("fake-fitbit-refresh-{}", n),
+                "expires_in": TOKEN_LIFETIME_SECS,
+                "scope": "activity profile",
+                "user_id": user_id,
+            }))
+        }
+        ("POST", ["oauth2", "revoke"]) => json_response(json!({})),
+        (_, [version, ..]) if version.starts_with('1') && request.authorization.is_none() => {
+            error_response(StatusCode::UNAUTHORIZED, "Authorization Error")
+        }
+        ("GET", ["1", "user", "-", "profile.json"]) => {
+            json_response(state.data().fitbit_profile.clone())
+        }
+        ("GET", ["1", "user", "-", "activities.json"]) => {
+            json_response(state.data().fitbit_lifetime.clone())
+        }
+        ("GET", ["1", "user", "-", "activities", "list.json"]) => {
+            let limit = query_usize(request, "limit").unwrap_or(FITBIT_ACTIVITY_LIST_MAX);
+            let offset = query_usize(request, "offset").unwrap_or(0);
+            if limit > FITBIT_ACTIVITY_LIST_MAX {
+                return error_response(StatusCode::BAD_REQUEST, "limit must be 100 or less");
+            }
+            let data = state.data();
+            let activities: Vec<Value> = data
+                .fitbit_activities
+                .iter()
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect();
+            let next = if offset + activities.len() < data.fitbit_activities.len() {
+                format!(
+                    "/1/user/-/activities/list.json?limit={}&offset={}",
+                    limit,
+                    offset + activities.len()
+                )
+            } else {
+                String::new()
+            };
+            json_response(json!({
+                "activities": activities,
+                "pagination": {"limit": limit, "offset": offset, "next": next}
+            }))
+        }
+        ("GET", [_, "user", "-", resource @ .., "date", start, end]) => {
+            fitbit_wellness_range(state, &resource.join("/"), start, end)
+        }
+        ("GET", ["1", "user", "-", "activities", file]) => {
+            let id = file.trim_end_matches(".json");
+            match find_by_id(&state.data().fitbit_activities, "activityId", id) {
+                Some(activity) => json_response(json!({ "activity": activity })),
+                None => error_response(StatusCode::NOT_FOUND, "Record Not Found"),
+            }
+        }
+        _ => error_response(StatusCode::NOT_FOUND, "Not Found"),
+    }
+}
+
+/// Serve a wellness time series for `start..=end` in Fitbit's response shape
+fn fitbit_wellness_range(
+    state: &FakeState,
+    resource: &str,
+    start: &str,
+    end: &str,
+) -> Response<Body> {
+    let end = end.trim_end_matches(".json");
+    if matches!(resource, "hrv" | "spo2") {
+        let parse = |day: &str| chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d");
+        match (parse(start), parse(end)) {
+            (Ok(first), Ok(last)) if (last - first).num_days() < FITBIT_INTERVAL_MAX_DAYS => {}
+            _ => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    "Invalid date range, the maximum range is 30 days",
+                )
+            }
+        }
+    }
+    let entries: Vec<Value> = state
+        .data()
+        .fitbit_wellness
+        .get(resource)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|entry| {
+                    let date = entry["dateTime"]
+                        .as_str()
+                        .or_else(|| entry["dateOfSleep"].as_str())
+                        .unwrap_or_default();
+                    // ISO dates compare correctly as strings
+                    (start..=end).contains(&date)
+                })
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    match resource {
+        "spo2" => json_response(Value::Array(entries)),
+        "sleep" | "hrv" => json_response(json!({ resource: entries })),
+        _ => json_response(
+            json!({ format!("activities-{}", resource.trim_start_matches("activities/")): entries }),
+        ),
+    }
+}
+
+/// Simulate the user approving access by redirecting back with a code
+fn authorize_redirect(request: &FakeRequest, code: &str) -> Response<Body> {
+    let Some(redirect_uri) = request.query.get("redirect_uri") else {
+        return error_response(StatusCode::BAD_REQUEST, "Missing redirect_uri");
+    };
+    let mut location = match url::Url::parse(redirect_uri) {
+        Ok(location) => location,
+        Err(_) => return error_response(StatusCode::BAD_REQUEST, "Invalid redirect_uri"),
+    };
+    location.query_pairs_mut().append_pair("code", code);
+    if let Some(state) = request.query.get("state") {
+        location.query_pairs_mut().append_pair("state", state);
+    }
+
+    Response::builder()
+        .status(StatusCode::FOUND)
+        .header("Location", location.as_str())
+        .body(Body::empty())
+        .unwrap_or_default()
+}
+
+/// Reject token requests missing the code or refresh token for their grant,
+/// or refreshing with a revoked refresh token
+///
+/// With rotation on, a refresh token that passes is revoked for next time.
+fn invalid_grant(state: &FakeState, request: &FakeRequest) -> Option<Response<Body>> {
+    let required = match request.form.get("grant_type").map(String::as_str) {
+        Some("authorization_code") => "code",
+        Some("refresh_token") => "refresh_token",
+        _ => {
+            return Some(error_response(
+                StatusCode::BAD_REQUEST,
+                "unsupported_grant_type",
+            ))
+        }
+    };
+    let mut revoked = state
+        .revoked_refresh_tokens
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    match request.form.get(required) {
+        Some(value) if !value.is_empty() && !revoked.contains(value) => {
+            if required == "refresh_token" && state.rotate_refresh_tokens.load(Ordering::SeqCst) {
+                revoked.insert(value.clone());
+            }
+            None
+        }
+        _ => Some(error_response(StatusCode::BAD_REQUEST, "invalid_grant")),
+    }
+}
+
+fn find_by_id(items: &[Value], field: &str, id: &str) -> Option<Value> {
+    items
+        .iter()
+        .find(|item| match &item[field] {
+            Value::Number(number) => number.to_string() == id,
+            Value::String(value) => value == id,
+            _ => false,
+        })
+        .cloned()
+}
+
+fn query_usize(request: &FakeRequest, name: &str) -> Option<usize> {
+    request.query.get(name).and_then(|value| value.parse().ok())
+}
+
+fn insert_strava_rate_headers(headers: &mut warp::http::HeaderMap, usage: u32) {
+    let (short, daily) = STRAVA_RATE_LIMIT;
+    insert_header(headers, "X-RateLimit-Limit", format!("{},{}", short, daily));
+    insert_header(
+        headers,
+        "X-RateLimit-Usage",
+        format!("{},{}", usage.min(short), usage),
+    );
+}
+
+fn insert_fitbit_rate_headers(headers: &mut warp::http::HeaderMap, usage: u32, reset_secs: i64) {
+    insert_header(
+        headers,
+        "Fitbit-Rate-Limit-Limit",
+        FITBIT_RATE_LIMIT.to_string(),
+    );
+    insert_header(
+        headers,
+        "Fitbit-Rate-Limit-Remaining",
+        FITBIT_RATE_LIMIT.saturating_sub(usage).to_string(),
+    );
+    insert_header(headers, "Fitbit-Rate-Limit-Reset", reset_secs.to_string());
+}
+
+fn insert_header(headers: &mut warp::http::HeaderMap, name: &'static str, value: String) {
+    if let Ok(value) = value.parse() {
+        headers.insert(name, value);
+    }
+}
+
+fn json_response(body: Value) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap_or_default()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    let body = json!({
+        "message": message,
+        "errors": [{"resource": "Fake", "code": status.as_u16().to_string()}]
+    });
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap_or_default()
+}
@@ -11,23 +11,33 @@
 //! - Activity data retrieval with comprehensive metrics
 //! - User profile information
 //! - Aggregated fitness statistics
+//! - Daily wellness data: sleep stages, resting heart rate, HRV, SpO2, steps
+//!   and active zone minutes
 //!
 //! # API Documentation
 //! - [Fitbit Web API](https://dev.fitbit.com/build/reference/web-api/)
 //! - [OAuth2 Authorization](https://dev.fitbit.com/build/reference/web-api/developer-guide/authorization/)
 
-use super::{AuthData, FitnessProvider};
-use crate::models::{Activity, Athlete, PersonalRecord, SportType, Stats};
+use super::rate_budget::{self, RequestContext};
+use super::{AuthData, FitnessProvider, ProviderCapability, ProviderDescriptor, QuotaScope};
+use crate::config::environment::FitbitApiConfig;
+use crate::models::{
+    Activity, Athlete, DailyWellness, PersonalRecord, SleepStages, SleepSummary, SportType, Stats,
+};
 use crate::oauth2_client::PkceParams;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use tracing::info;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
 
-const FITBIT_API_BASE: &str = "https://api.fitbit.com/1";
-const FITBIT_AUTH_URL: &str = "https://www.fitbit.com/oauth2/authorize";
+/// Longest range, in days, Fitbit's HRV and SpO2 interval endpoints accept
+const INTERVAL_MAX_DAYS: i64 = 30;
+/// Most activities Fitbit's activity log list returns per request
+const ACTIVITY_LIST_MAX: usize = 100;
 
 /// Fitbit provider implementation supporting OAuth2 with PKCE
 pub struct FitbitProvider {
@@ -36,6 +46,31 @@ pub struct FitbitProvider {
     client_id: Option<String>,
     client_secret: Option<String>,
     refresh_token: Option<String>,
+    request_context: RequestContext,
+    api: FitbitApiConfig,
+}
+
+/// Registry descriptor for the Fitbit provider
+pub fn descriptor() -> ProviderDescriptor {
+    ProviderDescriptor {
+        name: "fitbit",
+        display_name: "Fitbit",
+        factory: |api| Box::new(FitbitProvider::with_api_config(api.fitbit_api.clone())),
+        client_credentials: || {
+            Some((
+                crate::constants::env_config::fitbit_client_id()?,
+                crate::constants::env_config::fitbit_client_secret()?,
+            ))
+        },
+        oauth_config: |config| &config.fitbit,
+        oauth_client: |config| {
+            Ok(Box::new(
+                crate::oauth::providers::FitbitOAuthProvider::from_config(config)?,
+            ))
+        },
+        quota_scope: QuotaScope::PerUser,
+        capabilities: &[ProviderCapability::Activities, ProviderCapability::Wellness],
+    }
 }
 
 impl Default for FitbitProvider {
@@ -47,12 +82,19 @@ impl Default for FitbitProvider {
 impl FitbitProvider {
     /// Create a new Fitbit provider instance
     pub fn new() -> Self {
+        Self::with_api_config(FitbitApiConfig::from_env())
+    }
+
+    /// Create a Fitbit provider instance that talks to the given endpoints
+    pub fn with_api_config(api: FitbitApiConfig) -> Self {
         Self {
             client: Client::new(),
             access_token: None,
             client_id: None,
             client_secret: None,
             refresh_token: None,
+            request_context: RequestContext::default(),
+            api,
         }
     }
 
@@ -64,9 +106,11 @@ impl FitbitProvider {
     ///
     /// # Scopes
     /// Requests the following Fitbit scopes:
-    /// - `activity` - Access to activities and exercise logs
+    /// - `activity` - Access to activities, steps and active zone minutes
+    /// - `heartrate` - Access to resting heart rate and HRV
+    /// - `oxygen_saturation` - Access to SpO2
     /// - `profile` - Access to profile information
-    /// - `sleep` - Access to sleep data (for future enhancement)
+    /// - `sleep` - Access to sleep logs and stages
     #[allow(dead_code)]
     pub fn get_auth_url(&self, redirect_uri: &str, state: &str) -> Result<String> {
         let client_id = self
@@ -74,12 +118,15 @@ impl FitbitProvider {
             .as_ref()
             .context("Client ID not configured")?;
 
-        let mut url = url::Url::parse(FITBIT_AUTH_URL)?;
+        let mut url = url::Url::parse(&self.api.auth_url)?;
         url.query_pairs_mut()
             .append_pair("client_id", client_id)
             .append_pair("redirect_uri", redirect_uri)
             .append_pair("response_type", "code")
-            .append_pair("scope", "activity profile sleep")
+            .append_pair(
+                "scope",
+                "activity heartrate oxygen_saturation profile sleep",
+            )
             .append_pair("state", state);
 
         Ok(url.to_string())
@@ -103,12 +150,15 @@ impl FitbitProvider {
             .as_ref()
             .context("Client ID not configured")?;
 
-        let mut url = url::Url::parse(FITBIT_AUTH_URL)?;
+        let mut url = url::Url::parse(&self.api.auth_url)?;
         url.query_pairs_mut()
             .append_pair("client_id", client_id)
             .append_pair("redirect_uri", redirect_uri)
             .append_pair("response_type", "code")
-            .append_pair("scope", "activity profile sleep")
+            .append_pair(
+                "scope",
+                "activity heartrate oxygen_saturation profile sleep",
+            )
             .append_pair("state", state)
             .append_pair("code_challenge", &pkce.code_challenge)
             .append_pair("code_challenge_method", &pkce.code_challenge_method);
@@ -223,32 +273,104 @@ impl FitbitProvider {
         ))
     }
 
-    /// Get activities for a specific date range
-    /// Fitbit API requires date-based queries rather than pagination
+    /// Send a request within the user's Fitbit rate budget
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        rate_budget::budget()
+            .send("fitbit", &self.request_context, request)
+            .await
+    }
+
+    /// Get one page of activities for a specific date range
     async fn get_activities_for_period(
         &self,
         start_date: &str,
         end_date: &str,
-    ) -> Result<Vec<FitbitActivity>> {
+        limit: usize,
+        offset: usize,
+    ) -> Result<FitbitActivitiesResponse> {
         let token = self.access_token.as_ref().context("Not authenticated")?;
 
         let response: FitbitActivitiesResponse = self
-            .client
-            .get(format!("{}/user/-/activities/list.json", FITBIT_API_BASE))
-            .bearer_auth(token)
-            .query(&[
-                ("beforeDate", end_date),
-                ("afterDate", start_date),
-                ("sort", "desc"),
-                ("limit", "100"),
-                ("offset", "0"),
-            ])
-            .send()
+            .send(
+                self.client
+                    .get(format!(
+                        "{}/1/user/-/activities/list.json",
+                        self.api.base_url
+                    ))
+                    .bearer_auth(token)
+                    .query(&[
+                        ("beforeDate", end_date),
+                        ("afterDate", start_date),
+                        ("sort", "desc"),
+                        ("limit", &limit.to_string()),
+                        ("offset", &offset.to_string()),
+                    ]),
+            )
             .await?
             .json()
             .await?;
 
-        Ok(response.activities)
+        Ok(response)
+    }
+
+    /// GET a Fitbit date-range endpoint such as `1/user/-/hrv/date/{start}/{end}.json`
+    async fn get_date_range<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<T> {
+        let token = self.access_token.as_ref().context("Not authenticated")?;
+
+        let response = self
+            .send(
+                self.client
+                    .get(format!(
+                        "{}/{}/date/{}/{}.json",
+                        self.api.base_url,
+                        path,
+                        start.format("%Y-%m-%d"),
+                        end.format("%Y-%m-%d")
+                    ))
+                    .bearer_auth(token),
+            )
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    /// GET an HRV or SpO2 interval endpoint in ranges of at most 30 days
+    ///
+    /// These series need a compatible device, so failures other than rate
+    /// limiting leave them empty instead of failing the whole wellness call.
+    async fn get_interval_series<T: DeserializeOwned, E>(
+        &self,
+        path: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+        entries: fn(T) -> Vec<E>,
+    ) -> Result<Vec<E>> {
+        let mut series = Vec::new();
+        let mut chunk_start = start;
+        while chunk_start <= end {
+            let chunk_end = end.min(chunk_start + chrono::Duration::days(INTERVAL_MAX_DAYS - 1));
+            match self.get_date_range(path, chunk_start, chunk_end).await {
+                Ok(response) => series.extend(entries(response)),
+                Err(e)
+                    if e.downcast_ref::<rate_budget::ProviderRateLimited>()
+                        .is_some() =>
+                {
+                    return Err(e)
+                }
+                Err(e) => {
+                    warn!("Fitbit {} unavailable, leaving it empty: {}", path, e);
+                    return Ok(Vec::new());
+                }
+            }
+            chunk_start = chunk_end + chrono::Duration::days(1);
+        }
+        Ok(series)
     }
 }
 
@@ -276,10 +398,11 @@ impl FitnessProvider for FitbitProvider {
         let token = self.access_token.as_ref().context("Not authenticated")?;
 
         let response: FitbitUser = self
-            .client
-            .get(format!("{}/user/-/profile.json", FITBIT_API_BASE))
-            .bearer_auth(token)
-            .send()
+            .send(
+                self.client
+                    .get(format!("{}/1/user/-/profile.json", self.api.base_url))
+                    .bearer_auth(token),
+            )
             .await?
             .json()
             .await?;
@@ -291,31 +414,53 @@ impl FitnessProvider for FitbitProvider {
             lastname: response.user.last_name,
             profile_picture: response.user.avatar,
             provider: "fitbit".to_string(),
+            gear: vec![],
         })
     }
 
     async fn get_activities(
         &self,
         limit: Option<usize>,
-        _offset: Option<usize>,
+        offset: Option<usize>,
     ) -> Result<Vec<Activity>> {
-        // Fitbit API works with date ranges rather than offset pagination
-        // Get activities from the last 30 days by default
-        let end_date = chrono::Utc::now().date_naive();
-        let start_date = end_date - chrono::Duration::days(30);
-
-        let activities = self
-            .get_activities_for_period(
-                &start_date.format("%Y-%m-%d").to_string(),
-                &end_date.format("%Y-%m-%d").to_string(),
-            )
-            .await?;
-
-        let mut result: Vec<Activity> = activities.into_iter().map(|a| a.into()).collect();
+        self.get_activities_between(limit, offset, None, None).await
+    }
 
-        // Apply limit if specified
-        if let Some(limit) = limit {
-            result.truncate(limit);
+    async fn get_activities_between(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Activity>> {
+        // Get activities from the 30 days before the range end by default
+        let end_date = before.unwrap_or_else(Utc::now).date_naive();
+        let start_date = after.map_or(end_date - chrono::Duration::days(30), |after| {
+            after.date_naive()
+        });
+
+        let start_date = start_date.format("%Y-%m-%d").to_string();
+        let end_date = end_date.format("%Y-%m-%d").to_string();
+
+        // The list endpoint returns at most 100 activities, so follow its pages
+        let wanted = limit.unwrap_or(ACTIVITY_LIST_MAX);
+        let mut offset = offset.unwrap_or(0);
+        let mut result: Vec<Activity> = Vec::new();
+        while result.len() < wanted {
+            let page = self
+                .get_activities_for_period(
+                    &start_date,
+                    &end_date,
+                    (wanted - result.len()).min(ACTIVITY_LIST_MAX),
+                    offset,
+                )
+                .await?;
+            let count = page.activities.len();
+            offset += count;
+            result.extend(page.activities.into_iter().map(Activity::from));
+            if count == 0 || page.pagination.next.is_empty() {
+                break;
+            }
         }
 
         Ok(result)
@@ -326,10 +471,14 @@ impl FitnessProvider for FitbitProvider {
         let token = self.access_token.as_ref().context("Not authenticated")?;
 
         let response: FitbitActivityDetail = self
-            .client
-            .get(format!("{}/user/-/activities/{}.json", FITBIT_API_BASE, id))
-            .bearer_auth(token)
-            .send()
+            .send(
+                self.client
+                    .get(format!(
+                        "{}/1/user/-/activities/{}.json",
+                        self.api.base_url, id
+                    ))
+                    .bearer_auth(token),
+            )
             .await?
             .json()
             .await?;
@@ -342,10 +491,11 @@ impl FitnessProvider for FitbitProvider {
 
         // Get lifetime stats from Fitbit
         let response: FitbitLifetimeStats = self
-            .client
-            .get(format!("{}/user/-/activities.json", FITBIT_API_BASE))
-            .bearer_auth(token)
-            .send()
+            .send(
+                self.client
+                    .get(format!("{}/1/user/-/activities.json", self.api.base_url))
+                    .bearer_auth(token),
+            )
             .await?
             .json()
             .await?;
@@ -366,6 +516,62 @@ impl FitnessProvider for FitbitProvider {
         Ok(vec![])
     }
 
+    async fn get_daily_wellness(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyWellness>> {
+        let sleep: FitbitSleepResponse =
+            self.get_date_range("1.2/user/-/sleep", start, end).await?;
+        let heart: FitbitHeartResponse = self
+            .get_date_range("1/user/-/activities/heart", start, end)
+            .await?;
+        let hrv = self
+            .get_interval_series("1/user/-/hrv", start, end, |response: FitbitHrvResponse| {
+                response.hrv
+            })
+            .await?;
+        let spo2 = self
+            .get_interval_series("1/user/-/spo2", start, end, |days: Vec<FitbitSpo2Day>| days)
+            .await?;
+        let steps: FitbitStepsResponse = self
+            .get_date_range("1/user/-/activities/steps", start, end)
+            .await?;
+        let azm: FitbitAzmResponse = self
+            .get_date_range("1/user/-/activities/active-zone-minutes", start, end)
+            .await?;
+
+        let mut days: BTreeMap<NaiveDate, DailyWellness> = BTreeMap::new();
+
+        for log in main_sleep_logs(sleep.sleep) {
+            let date = log.date_of_sleep;
+            wellness_day(&mut days, date).sleep = Some(log.into_summary()?);
+        }
+        for entry in heart.days {
+            wellness_day(&mut days, entry.date_time).resting_heart_rate =
+                entry.value.resting_heart_rate;
+        }
+        for entry in hrv {
+            wellness_day(&mut days, entry.date_time).hrv_rmssd_ms = Some(entry.value.daily_rmssd);
+        }
+        for entry in spo2 {
+            wellness_day(&mut days, entry.date_time).spo2_percent = Some(entry.value.avg);
+        }
+        for entry in steps.days {
+            wellness_day(&mut days, entry.date_time).steps = entry.value.parse().ok();
+        }
+        for entry in azm.days {
+            wellness_day(&mut days, entry.date_time).active_zone_minutes =
+                Some(entry.value.active_zone_minutes);
+        }
+
+        Ok(days.into_values().collect())
+    }
+
+    fn set_request_context(&mut self, context: RequestContext) {
+        self.request_context = context;
+    }
+
     #[allow(dead_code)]
     fn provider_name(&self) -> &'static str {
         "Fitbit"
@@ -394,6 +600,15 @@ struct FitbitUserProfile {
 #[derive(Debug, Deserialize)]
 struct FitbitActivitiesResponse {
     activities: Vec<FitbitActivity>,
+    #[serde(default)]
+    pagination: FitbitPagination,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FitbitPagination {
+    /// URL of the next page, empty on the last one
+    #[serde(default)]
+    next: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -463,6 +678,159 @@ struct FitbitLifetimeTotal {
     steps: u64,
 }
 
+#[derive(Debug, Deserialize)]
+struct FitbitSleepResponse {
+    sleep: Vec<FitbitSleepLog>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FitbitSleepLog {
+    date_of_sleep: NaiveDate,
+    start_time: String, // local time without offset
+    end_time: String,
+    minutes_asleep: u32,
+    time_in_bed: u32,
+    efficiency: Option<u32>,
+    #[serde(default)]
+    is_main_sleep: bool,
+    levels: Option<FitbitSleepLevels>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FitbitSleepLevels {
+    summary: FitbitSleepLevelSummary,
+}
+
+/// Stage totals; "classic" logs from older devices have no deep/light/rem
+#[derive(Debug, Deserialize)]
+struct FitbitSleepLevelSummary {
+    deep: Option<FitbitSleepLevel>,
+    light: Option<FitbitSleepLevel>,
+    rem: Option<FitbitSleepLevel>,
+    wake: Option<FitbitSleepLevel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FitbitSleepLevel {
+    minutes: u32,
+}
+
+impl FitbitSleepLog {
+    fn into_summary(self) -> Result<SleepSummary> {
+        let parse = |time: &str| {
+            NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M:%S%.f")
+                .with_context(|| format!("Invalid Fitbit sleep time: {}", time))
+        };
+
+        let stages = self.levels.and_then(|levels| {
+            let summary = levels.summary;
+            let minutes = |level: &Option<FitbitSleepLevel>| level.as_ref().map(|l| l.minutes);
+            Some(SleepStages {
+                deep_minutes: minutes(&summary.deep)?,
+                light_minutes: minutes(&summary.light)?,
+                rem_minutes: minutes(&summary.rem)?,
+                awake_minutes: minutes(&summary.wake).unwrap_or(0),
+            })
+        });
+
+        Ok(SleepSummary {
+            start_time: Some(parse(&self.start_time)?),
+            end_time: Some(parse(&self.end_time)?),
+            minutes_asleep: self.minutes_asleep,
+            minutes_in_bed: self.time_in_bed,
+            efficiency: self.efficiency,
+            stages,
+            quality: None,
+        })
+    }
+}
+
+/// The main sleep for each night; naps are dropped
+///
+/// Fitbit flags the main sleep, but falls back to the longest log when no
+/// log for a night carries the flag.
+fn main_sleep_logs(logs: Vec<FitbitSleepLog>) -> Vec<FitbitSleepLog> {
+    let mut nights: BTreeMap<NaiveDate, FitbitSleepLog> = BTreeMap::new();
+    for log in logs {
+        let replace = match nights.get(&log.date_of_sleep) {
+            None => true,
+            Some(current) => {
+                (log.is_main_sleep, log.minutes_asleep)
+                    > (current.is_main_sleep, current.minutes_asleep)
+            }
+        };
+        if replace {
+            nights.insert(log.date_of_sleep, log);
+        }
+    }
+    nights.into_values().collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct FitbitHeartResponse {
+    #[serde(rename = "activities-heart")]
+    days: Vec<FitbitDay<FitbitHeartValue>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FitbitHeartValue {
+    resting_heart_rate: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FitbitHrvResponse {
+    hrv: Vec<FitbitDay<FitbitHrvValue>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FitbitHrvValue {
+    daily_rmssd: f64,
+}
+
+type FitbitSpo2Day = FitbitDay<FitbitSpo2Value>;
+
+#[derive(Debug, Deserialize)]
+struct FitbitSpo2Value {
+    avg: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct FitbitStepsResponse {
+    #[serde(rename = "activities-steps")]
+    days: Vec<FitbitDay<String>>, // Fitbit sends step counts as strings
+}
+
+#[derive(Debug, Deserialize)]
+struct FitbitAzmResponse {
+    #[serde(rename = "activities-active-zone-minutes")]
+    days: Vec<FitbitDay<FitbitAzmValue>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FitbitAzmValue {
+    active_zone_minutes: u32,
+}
+
+fn wellness_day(
+    days: &mut BTreeMap<NaiveDate, DailyWellness>,
+    date: NaiveDate,
+) -> &mut DailyWellness {
+    days.entry(date)
+        .or_insert_with(|| DailyWellness::new(date, "fitbit"))
+}
+
+/// One entry of a Fitbit time series
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FitbitDay<T> {
+    date_time: NaiveDate,
+    value: T,
+}
+
 impl From<FitbitActivity> for Activity {
     fn from(fitbit: FitbitActivity) -> Self {
         // Parse start time
@@ -508,6 +876,8 @@ impl From<FitbitActivity> for Activity {
             region: None,
             country: None,
             trail_name: None,
+            gear_id: None,
+            summary_polyline: None,
             provider: "fitbit".to_string(),
         }
     }
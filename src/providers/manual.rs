@@ -0,0 +1,443 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Manually logged activities
+//!
+//! Strength sessions, classes and untracked workouts entered by the user are
+//! stored locally with `provider = "manual"`. [`ManualActivityProvider`]
+//! serves them through the `FitnessProvider` interface, optionally merged
+//! with a connected provider's activities, so every analyzer sees them.
+
+use super::{AuthData, FitnessProvider, RequestContext};
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::models::{
+    Activity, ActivityStreams, Athlete, DailyWellness, PersonalRecord, SportType, Stats,
+};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Provider name for user-entered data
+pub const MANUAL_PROVIDER: &str = "manual";
+
+/// Longest accepted manual activity
+const MAX_DURATION_MINUTES: f64 = 24.0 * 60.0;
+/// Clock skew tolerated for start times slightly in the future
+const MAX_FUTURE_START_MINUTES: i64 = 15;
+
+/// A new manual activity as sent to `log_activity` or `POST /api/activities`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManualActivityRequest {
+    /// Sport type, e.g. `yoga`, `strength_training`, `run`
+    pub sport_type: String,
+    pub duration_minutes: f64,
+    /// Defaults to a name derived from the sport type
+    pub name: Option<String>,
+    /// Defaults to now
+    pub start_date: Option<DateTime<Utc>>,
+    pub distance_meters: Option<f64>,
+    pub elevation_gain: Option<f64>,
+    pub average_heart_rate: Option<u32>,
+    pub max_heart_rate: Option<u32>,
+    pub calories: Option<u32>,
+    /// Shoes or bike used, from `get_gear`
+    pub gear_id: Option<String>,
+}
+
+/// Fields to change on a manual activity; absent fields are kept
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ManualActivityUpdate {
+    pub sport_type: Option<String>,
+    pub duration_minutes: Option<f64>,
+    pub name: Option<String>,
+    pub start_date: Option<DateTime<Utc>>,
+    pub distance_meters: Option<f64>,
+    pub elevation_gain: Option<f64>,
+    pub average_heart_rate: Option<u32>,
+    pub max_heart_rate: Option<u32>,
+    pub calories: Option<u32>,
+    /// Shoes or bike used, from `get_gear`
+    pub gear_id: Option<String>,
+}
+
+impl ManualActivityRequest {
+    /// Validate the request and build the activity to store
+    pub fn into_activity(self) -> Result<Activity> {
+        let sport_type = parse_sport_type(&self.sport_type)?;
+        let mut activity = Activity {
+            id: format!("{}-{}", MANUAL_PROVIDER, Uuid::new_v4()),
+            name: self.name.unwrap_or_else(|| {
+                let label = sport_type.display_name();
+                let mut chars = label.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_uppercase().collect::<String>() + chars.as_str()
+                })
+            }),
+            sport_type,
+            start_date: self.start_date.unwrap_or_else(Utc::now),
+            duration_seconds: duration_seconds(self.duration_minutes)?,
+            distance_meters: self.distance_meters,
+            elevation_gain: self.elevation_gain,
+            average_heart_rate: self.average_heart_rate,
+            max_heart_rate: self.max_heart_rate,
+            average_speed: None,
+            max_speed: None,
+            calories: self.calories,
+            start_latitude: None,
+            start_longitude: None,
+            city: None,
+            region: None,
+            country: None,
+            trail_name: None,
+            gear_id: self.gear_id,
+            summary_polyline: None,
+            provider: MANUAL_PROVIDER.to_string(),
+        };
+        finish(&mut activity)?;
+        Ok(activity)
+    }
+}
+
+impl ManualActivityUpdate {
+    /// Validate the update and apply it to a stored activity
+    pub fn apply(self, activity: &mut Activity) -> Result<()> {
+        if let Some(sport_type) = self.sport_type {
+            activity.sport_type = parse_sport_type(&sport_type)?;
+        }
+        if let Some(minutes) = self.duration_minutes {
+            activity.duration_seconds = duration_seconds(minutes)?;
+        }
+        if let Some(name) = self.name {
+            activity.name = name;
+        }
+        if let Some(start_date) = self.start_date {
+            activity.start_date = start_date;
+        }
+        if self.distance_meters.is_some() {
+            activity.distance_meters = self.distance_meters;
+        }
+        if self.elevation_gain.is_some() {
+            activity.elevation_gain = self.elevation_gain;
+        }
+        if self.average_heart_rate.is_some() {
+            activity.average_heart_rate = self.average_heart_rate;
+        }
+        if self.max_heart_rate.is_some() {
+            activity.max_heart_rate = self.max_heart_rate;
+        }
+        if self.calories.is_some() {
+            activity.calories = self.calories;
+        }
+        if self.gear_id.is_some() {
+            activity.gear_id = self.gear_id;
+        }
+        finish(activity)
+    }
+}
+
+/// Parse a sport type name, accepting only the known `SportType` variants
+///
+/// Both internal names (`bike_ride`) and serialized names (`ride`) work;
+/// spaces and case are ignored.
+pub fn parse_sport_type(name: &str) -> Result<SportType> {
+    let normalized = name.trim().to_lowercase().replace([' ', '-'], "_");
+    match SportType::from_internal_string(&normalized) {
+        SportType::Other(_) => serde_json::from_value(serde_json::Value::String(normalized))
+            .ok()
+            .filter(|sport| !matches!(sport, SportType::Other(_)))
+            .with_context(|| {
+                format!(
+                    "Unknown sport_type '{}', expected e.g. run, ride, swim, walk, yoga, strength_training, workout",
+                    name
+                )
+            }),
+        sport_type => Ok(sport_type),
+    }
+}
+
+fn duration_seconds(minutes: f64) -> Result<u64> {
+    if !(minutes > 0.0 && minutes <= MAX_DURATION_MINUTES) {
+        bail!("duration_minutes must be greater than 0 and at most 1440");
+    }
+    Ok((minutes * 60.0).round() as u64)
+}
+
+/// Check cross-field rules and derive the average speed
+fn finish(activity: &mut Activity) -> Result<()> {
+    if activity.name.trim().is_empty() {
+        bail!("name must not be empty");
+    }
+    if activity.start_date > Utc::now() + Duration::minutes(MAX_FUTURE_START_MINUTES) {
+        bail!("start_date must not be in the future");
+    }
+    if activity.distance_meters.is_some_and(|d| d < 0.0) {
+        bail!("distance_meters must not be negative");
+    }
+    if activity.elevation_gain.is_some_and(|e| e < 0.0) {
+        bail!("elevation_gain must not be negative");
+    }
+    for heart_rate in [activity.average_heart_rate, activity.max_heart_rate]
+        .into_iter()
+        .flatten()
+    {
+        if !(30..=250).contains(&heart_rate) {
+            bail!("heart rates must be between 30 and 250 bpm");
+        }
+    }
+    if let (Some(average), Some(max)) = (activity.average_heart_rate, activity.max_heart_rate) {
+        if max < average {
+            bail!("max_heart_rate must not be below average_heart_rate");
+        }
+    }
+
+    activity.average_speed = activity
+        .distance_meters
+        .filter(|d| *d > 0.0)
+        .map(|d| d / activity.duration_seconds as f64);
+    Ok(())
+}
+
+/// Serves a user's manual activities, merged with a connected provider's
+///
+/// Without an inner provider only manual activities are available; with one,
+/// activity lists interleave both by start date and everything else is
+/// delegated.
+pub struct ManualActivityProvider {
+    database: Arc<Database>,
+    user_id: Uuid,
+    inner: Option<Box<dyn FitnessProvider>>,
+}
+
+impl ManualActivityProvider {
+    pub fn new(
+        database: Arc<Database>,
+        user_id: Uuid,
+        inner: Option<Box<dyn FitnessProvider>>,
+    ) -> Self {
+        Self {
+            database,
+            user_id,
+            inner,
+        }
+    }
+}
+
+#[async_trait]
+impl FitnessProvider for ManualActivityProvider {
+    async fn authenticate(&mut self, auth_data: AuthData) -> Result<()> {
+        match self.inner.as_mut() {
+            Some(inner) => inner.authenticate(auth_data).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn get_athlete(&self) -> Result<Athlete> {
+        match &self.inner {
+            Some(inner) => inner.get_athlete().await,
+            None => bail!("Manual activities have no athlete profile; connect a provider"),
+        }
+    }
+
+    async fn get_activities(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Activity>> {
+        let offset = offset.unwrap_or(0);
+        let wanted = limit.map(|limit| limit + offset);
+
+        let mut activities = self
+            .database
+            .list_manual_activities(self.user_id, wanted.map(|n| n as u32))
+            .await?;
+        if let Some(inner) = &self.inner {
+            activities.extend(inner.get_activities(wanted, None).await?);
+        }
+
+        activities.sort_by_key(|activity| std::cmp::Reverse(activity.start_date));
+        Ok(activities
+            .into_iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    async fn get_activity(&self, id: &str) -> Result<Activity> {
+        if let Some(activity) = self.database.get_manual_activity(self.user_id, id).await? {
+            return Ok(activity);
+        }
+        match &self.inner {
+            Some(inner) => inner.get_activity(id).await,
+            None => bail!("Manual activity {} not found", id),
+        }
+    }
+
+    async fn get_stats(&self) -> Result<Stats> {
+        let manual = self
+            .database
+            .list_manual_activities(self.user_id, None)
+            .await?;
+        let mut stats = match &self.inner {
+            Some(inner) => inner.get_stats().await?,
+            None => Stats {
+                total_activities: 0,
+                total_distance: 0.0,
+                total_duration: 0,
+                total_elevation_gain: 0.0,
+            },
+        };
+
+        stats.total_activities += manual.len() as u64;
+        for activity in &manual {
+            stats.total_distance += activity.distance_meters.unwrap_or(0.0);
+            stats.total_duration += activity.duration_seconds;
+            stats.total_elevation_gain += activity.elevation_gain.unwrap_or(0.0);
+        }
+        Ok(stats)
+    }
+
+    async fn get_personal_records(&self) -> Result<Vec<PersonalRecord>> {
+        match &self.inner {
+            Some(inner) => inner.get_personal_records().await,
+            None => Ok(vec![]),
+        }
+    }
+
+    fn provider_name(&self) -> &'static str {
+        self.inner
+            .as_ref()
+            .map_or(MANUAL_PROVIDER, |inner| inner.provider_name())
+    }
+
+    async fn get_daily_wellness(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyWellness>> {
+        match &self.inner {
+            Some(inner) => inner.get_daily_wellness(start, end).await,
+            None => bail!("{} does not provide wellness data", MANUAL_PROVIDER),
+        }
+    }
+
+    async fn get_activity_streams(&self, id: &str) -> Result<ActivityStreams> {
+        match &self.inner {
+            Some(inner) => inner.get_activity_streams(id).await,
+            None => bail!("{} does not provide activity streams", MANUAL_PROVIDER),
+        }
+    }
+
+    fn set_request_context(&mut self, context: RequestContext) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.set_request_context(context);
+        }
+    }
+}
+
+/// Add a user's manual activities to a provider's list, newest first
+///
+/// Storage errors leave the list as it was.
+pub async fn with_manual_activities(
+    database: &Database,
+    user_id: Uuid,
+    mut activities: Vec<Activity>,
+    limit: usize,
+) -> Vec<Activity> {
+    if let Ok(manual) = database
+        .list_manual_activities(user_id, Some(limit as u32))
+        .await
+    {
+        activities.extend(manual);
+        activities.sort_by_key(|activity| std::cmp::Reverse(activity.start_date));
+        activities.truncate(limit);
+    }
+    activities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(sport_type: &str, minutes: f64) -> ManualActivityRequest {
+        ManualActivityRequest {
+            sport_type: sport_type.to_string(),
+            duration_minutes: minutes,
+            name: None,
+            start_date: None,
+            distance_meters: None,
+            elevation_gain: None,
+            average_heart_rate: None,
+            max_heart_rate: None,
+            calories: None,
+            gear_id: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_sport_type_accepts_known_names() {
+        assert_eq!(parse_sport_type("yoga").unwrap(), SportType::Yoga);
+        assert_eq!(
+            parse_sport_type("Strength Training").unwrap(),
+            SportType::StrengthTraining
+        );
+        assert_eq!(parse_sport_type("bike_ride").unwrap(), SportType::Ride);
+        assert_eq!(parse_sport_type("ride").unwrap(), SportType::Ride);
+        assert!(parse_sport_type("underwater_basket_weaving").is_err());
+    }
+
+    #[test]
+    fn test_request_builds_manual_activity() {
+        let mut req = request("run", 30.0);
+        req.distance_meters = Some(6000.0);
+        let activity = req.into_activity().unwrap();
+
+        assert!(activity.id.starts_with("manual-"));
+        assert_eq!(activity.provider, MANUAL_PROVIDER);
+        assert_eq!(activity.name, "Run");
+        assert_eq!(activity.duration_seconds, 1800);
+        assert!((activity.average_speed.unwrap() - 3.333).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_request_validation() {
+        assert!(request("yoga", 0.0).into_activity().is_err());
+        assert!(request("yoga", 2000.0).into_activity().is_err());
+
+        let mut future = request("yoga", 45.0);
+        future.start_date = Some(Utc::now() + Duration::days(1));
+        assert!(future.into_activity().is_err());
+
+        let mut heart_rate = request("run", 45.0);
+        heart_rate.average_heart_rate = Some(160);
+        heart_rate.max_heart_rate = Some(150);
+        assert!(heart_rate.into_activity().is_err());
+    }
+
+    #[test]
+    fn test_update_keeps_absent_fields() {
+        let mut activity = request("yoga", 45.0).into_activity().unwrap();
+        ManualActivityUpdate {
+            duration_minutes: Some(60.0),
+            calories: Some(200),
+            ..Default::default()
+        }
+        .apply(&mut activity)
+        .unwrap();
+
+        assert_eq!(activity.sport_type, SportType::Yoga);
+        assert_eq!(activity.duration_seconds, 3600);
+        assert_eq!(activity.calories, Some(200));
+
+        let invalid = ManualActivityUpdate {
+            sport_type: Some("quidditch".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.apply(&mut activity).is_err());
+    }
+}
@@ -0,0 +1,969 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Merging the same workout recorded by several providers
+//!
+//! A watch synced to both Strava and Fitbit reports each run twice. Records
+//! from different providers are treated as one workout when their sports are
+//! compatible, their times overlap and their distances agree (see
+//! [`ActivityMergeConfig`]). The merged record takes each group of fields
+//! from the highest-precedence provider that has them, so heart rate can come
+//! from Fitbit while GPS comes from Strava.
+//!
+//! [`MergedProvider`] serves that view through `FitnessProvider` as the
+//! `all` provider; [`all_provider_activities`] gives it to code that only
+//! holds the user's [`ProviderConnections`].
+
+use super::connections::ProviderConnections;
+use super::manual::ManualActivityProvider;
+use super::rate_budget::{ProviderRateLimited, RequestContext};
+use super::{registry, AuthData, FitnessProvider, ProviderCapability};
+use crate::config::fitness_config::{ActivityMergeConfig, FieldPrecedence};
+use crate::config::FitnessConfig;
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::models::{
+    Activity, ActivityStreams, Athlete, DailyWellness, PersonalRecord, SportType, Stats,
+};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Provider name selecting the merged view of every connected provider
+pub const ALL_PROVIDERS: &str = "all";
+
+/// Recent activities fetched from other providers when looking for the
+/// counterparts of a single activity
+const COUNTERPART_WINDOW: usize = 30;
+/// Activities requested per page when walking a provider's history
+const HISTORY_PAGE: usize = 200;
+
+/// Activities read from one or more providers' histories
+#[derive(Debug, Clone, Default)]
+pub struct ActivityHistory {
+    pub activities: Vec<Activity>,
+    /// Why parts of the history are missing, e.g. a provider's rate limit
+    pub gaps: Vec<String>,
+}
+
+fn started_between(
+    activity: &Activity,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+) -> bool {
+    after.is_none_or(|after| activity.start_date >= after)
+        && before.is_none_or(|before| activity.start_date <= before)
+}
+
+/// One provider record that went into a merged activity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActivitySource {
+    pub provider: String,
+    pub activity_id: String,
+}
+
+/// A workout merged from one or more provider records
+#[derive(Debug, Clone, Serialize)]
+pub struct MergedActivity {
+    pub activity: Activity,
+    pub sources: Vec<ActivitySource>,
+}
+
+/// The merge settings from the fitness configuration
+pub fn merge_config() -> ActivityMergeConfig {
+    FitnessConfig::load(None).unwrap_or_default().activity_merge
+}
+
+/// Position of a provider in a precedence list; unlisted providers follow
+/// in `fallback` order
+fn rank(order: &[String], fallback: &[String], provider: &str) -> usize {
+    let provider = provider.to_lowercase();
+    order
+        .iter()
+        .position(|name| *name == provider)
+        .or_else(|| {
+            fallback
+                .iter()
+                .position(|name| *name == provider)
+                .map(|i| order.len() + i)
+        })
+        .unwrap_or(usize::MAX)
+}
+
+/// Generic sport types that any specific sport may be recorded as
+fn is_generic(sport: &SportType) -> bool {
+    matches!(sport, SportType::Workout | SportType::Other(_))
+}
+
+/// Whether two records from different providers describe the same workout
+pub fn is_same_workout(a: &Activity, b: &Activity, config: &ActivityMergeConfig) -> bool {
+    if a.provider == b.provider {
+        return false;
+    }
+    if a.sport_type != b.sport_type && !is_generic(&a.sport_type) && !is_generic(&b.sport_type) {
+        return false;
+    }
+
+    let start_gap = (a.start_date - b.start_date).num_seconds().abs();
+    let overlaps = start_gap <= config.start_tolerance_seconds || {
+        let end = |activity: &Activity| {
+            activity.start_date + Duration::seconds(activity.duration_seconds as i64)
+        };
+        let overlap = (end(a).min(end(b)) - a.start_date.max(b.start_date)).num_seconds();
+        let shorter = a.duration_seconds.min(b.duration_seconds).max(1);
+        overlap as f64 / shorter as f64 >= config.min_time_overlap
+    };
+    if !overlaps {
+        return false;
+    }
+
+    match (a.distance_meters, b.distance_meters) {
+        (Some(x), Some(y)) if x > 0.0 && y > 0.0 => {
+            (x - y).abs() / x.max(y) <= config.max_distance_difference
+        }
+        _ => true,
+    }
+}
+
+/// Group records of the same workout and merge each group, newest first
+pub fn merge_activities(
+    activities: Vec<Activity>,
+    config: &ActivityMergeConfig,
+) -> Vec<MergedActivity> {
+    let mut sorted = activities;
+    sorted.sort_by_key(|activity| activity.start_date);
+
+    let mut groups: Vec<Vec<Activity>> = Vec::new();
+    for activity in sorted {
+        let group = groups.iter_mut().find(|group| {
+            group
+                .iter()
+                .all(|member| member.provider != activity.provider)
+                && group
+                    .iter()
+                    .any(|member| is_same_workout(member, &activity, config))
+        });
+        match group {
+            Some(group) => group.push(activity),
+            None => groups.push(vec![activity]),
+        }
+    }
+
+    let mut merged: Vec<MergedActivity> = groups
+        .into_iter()
+        .map(|group| merge_group(group, &config.field_precedence))
+        .collect();
+    merged.sort_by_key(|merged| std::cmp::Reverse(merged.activity.start_date));
+    merged
+}
+
+/// Merged activities without their sources
+pub fn deduplicate(activities: Vec<Activity>, config: &ActivityMergeConfig) -> Vec<Activity> {
+    merge_activities(activities, config)
+        .into_iter()
+        .map(|merged| merged.activity)
+        .collect()
+}
+
+/// Merge the records of one workout field group by field group
+fn merge_group(mut records: Vec<Activity>, precedence: &FieldPrecedence) -> MergedActivity {
+    let sources = records
+        .iter()
+        .map(|record| ActivitySource {
+            provider: record.provider.clone(),
+            activity_id: record.id.clone(),
+        })
+        .collect();
+
+    let by = |order: &[String], records: &[Activity]| {
+        let mut ranked: Vec<&Activity> = records.iter().collect();
+        ranked.sort_by_key(|record| rank(order, &precedence.default, &record.provider));
+        ranked.into_iter().cloned().collect::<Vec<_>>()
+    };
+    fn first<T>(records: &[Activity], field: impl Fn(&Activity) -> Option<T>) -> Option<T> {
+        records.iter().find_map(field)
+    }
+
+    records = by(&precedence.default, &records);
+    let mut merged = records[0].clone();
+    if is_generic(&merged.sport_type) {
+        if let Some(specific) = records.iter().find(|r| !is_generic(&r.sport_type)) {
+            merged.sport_type = specific.sport_type.clone();
+        }
+    }
+    merged.gear_id = first(&records, |r| r.gear_id.clone());
+
+    let heart_rate = by(&precedence.heart_rate, &records);
+    merged.average_heart_rate = first(&heart_rate, |r| r.average_heart_rate);
+    merged.max_heart_rate = first(&heart_rate, |r| r.max_heart_rate);
+
+    // Coordinates and place names travel together from one record
+    let gps = by(&precedence.gps, &records);
+    if let Some(located) = gps
+        .iter()
+        .find(|r| r.start_latitude.is_some() || r.city.is_some())
+    {
+        merged.start_latitude = located.start_latitude;
+        merged.start_longitude = located.start_longitude;
+        merged.city.clone_from(&located.city);
+        merged.region.clone_from(&located.region);
+        merged.country.clone_from(&located.country);
+        merged.trail_name.clone_from(&located.trail_name);
+    }
+    merged.summary_polyline = first(&gps, |r| r.summary_polyline.clone());
+
+    let distance = by(&precedence.distance, &records);
+    merged.distance_meters = first(&distance, |r| r.distance_meters);
+    merged.elevation_gain = first(&distance, |r| r.elevation_gain);
+    merged.average_speed = first(&distance, |r| r.average_speed);
+    merged.max_speed = first(&distance, |r| r.max_speed);
+
+    merged.calories = first(&by(&precedence.calories, &records), |r| r.calories);
+
+    MergedActivity {
+        activity: merged,
+        sources,
+    }
+}
+
+/// Authenticated providers the user has connected that supply activities
+///
+/// Providers that can't be reached are logged and skipped.
+pub async fn connected_activity_providers(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+) -> Vec<Box<dyn FitnessProvider>> {
+    let mut providers = Vec::new();
+    for name in registry().provider_names() {
+        let supports_activities = registry()
+            .get(name)
+            .is_some_and(|d| d.supports(ProviderCapability::Activities));
+        if !supports_activities {
+            continue;
+        }
+        match connections.connected_provider(user_id, name).await {
+            Ok(Some(provider)) => providers.push(provider),
+            Ok(None) => {}
+            Err(e) => warn!("Skipping {} activities: {}", name, e),
+        }
+    }
+    providers
+}
+
+/// Recent activities from every connected provider plus manual entries,
+/// merged and newest first
+///
+/// Providers that fail are logged and skipped, except a rate-limited one:
+/// its [`ProviderRateLimited`] is returned so the caller can say when to
+/// retry instead of answering from a partial history.
+pub async fn all_provider_activities(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    limit: usize,
+) -> Result<Vec<Activity>> {
+    with_all_providers(connections, user_id, Vec::new(), limit).await
+}
+
+/// A provider's activities page by page, up to `limit`, optionally limited
+/// to those started between `after` and `before`
+///
+/// Stops at the first short or failing page, or at a page with nothing new
+/// for providers that ignore the offset. A failing first page is returned
+/// as the error, so a [`ProviderRateLimited`](super::rate_budget::ProviderRateLimited)
+/// reaches the caller; a later one is recorded as a gap.
+pub async fn paged_activities(
+    provider: &dyn FitnessProvider,
+    limit: usize,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+) -> Result<ActivityHistory> {
+    let mut history = ActivityHistory::default();
+    let mut seen = HashSet::new();
+    // Offsets count everything the provider returned, not just what the dates kept
+    let mut offset = 0;
+    while history.activities.len() < limit {
+        let page = match provider
+            .get_activities_between(Some(HISTORY_PAGE), Some(offset), after, before)
+            .await
+        {
+            Ok(page) => page,
+            Err(e) if offset == 0 => return Err(e),
+            Err(e) => {
+                let kept = history.activities.len();
+                warn!(
+                    "Stopping {} history at {}: {}",
+                    provider.provider_name(),
+                    kept,
+                    e
+                );
+                history.gaps.push(format!(
+                    "{} history is partial, stopped after {} activities: {}",
+                    provider.provider_name(),
+                    kept,
+                    e
+                ));
+                break;
+            }
+        };
+        offset += page.len();
+        let full_page = page.len() >= HISTORY_PAGE;
+        let mut new_activities = 0;
+        for activity in page {
+            if seen.insert(activity.id.clone()) {
+                new_activities += 1;
+                if started_between(&activity, after, before) {
+                    history.activities.push(activity);
+                }
+            }
+        }
+        if !full_page || new_activities == 0 {
+            break;
+        }
+    }
+    history.activities.truncate(limit);
+    Ok(history)
+}
+
+/// Complete a provider history that stopped early with the copies its
+/// webhooks stored
+///
+/// The walk's error is only returned when no stored copy can stand in.
+async fn with_synced_activities(
+    database: &Database,
+    user_id: Uuid,
+    provider: &str,
+    walked: Result<ActivityHistory>,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+) -> Result<ActivityHistory> {
+    let (mut history, error) = match walked {
+        Ok(history) if history.gaps.is_empty() => return Ok(history),
+        Ok(history) => (history, None),
+        Err(e) => (ActivityHistory::default(), Some(e)),
+    };
+
+    let known: HashSet<String> = history.activities.iter().map(|a| a.id.clone()).collect();
+    let synced: Vec<Activity> = database
+        .list_synced_activities(user_id, provider, None)
+        .await?
+        .into_iter()
+        .filter(|a| started_between(a, after, before) && !known.contains(&a.id))
+        .collect();
+
+    match error {
+        Some(e) if synced.is_empty() => return Err(e),
+        Some(e) => history.gaps.push(format!(
+            "{} history unavailable ({}), showing {} activities synced earlier",
+            provider,
+            e,
+            synced.len()
+        )),
+        None if !synced.is_empty() => history.gaps.push(format!(
+            "Added {} {} activities synced earlier",
+            synced.len(),
+            provider
+        )),
+        None => {}
+    }
+    history.activities.extend(synced);
+    Ok(history)
+}
+
+/// One provider's history up to `limit`, falling back on webhook-synced
+/// copies for what the provider couldn't serve
+pub async fn provider_history(
+    database: &Database,
+    user_id: Uuid,
+    provider: &dyn FitnessProvider,
+    limit: usize,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+) -> Result<ActivityHistory> {
+    let walked = paged_activities(provider, limit, after, before).await;
+    let name = provider.provider_name().to_lowercase();
+    with_synced_activities(database, user_id, &name, walked, after, before).await
+}
+
+/// Like [`all_provider_activities`], but walking each provider's history
+/// page by page rather than taking one page of recent activities
+///
+/// A provider that can't be read is noted in the gaps instead of failing
+/// the whole history.
+pub async fn activity_history(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    limit: usize,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+) -> ActivityHistory {
+    let mut activities = Vec::new();
+    let mut gaps = Vec::new();
+    let database = connections.database();
+    for provider in connected_activity_providers(connections, user_id).await {
+        match provider_history(database, user_id, provider.as_ref(), limit, after, before).await {
+            Ok(history) => {
+                activities.extend(history.activities);
+                gaps.extend(history.gaps);
+            }
+            Err(e) => {
+                warn!("Skipping {} history: {}", provider.provider_name(), e);
+                gaps.push(format!(
+                    "{} history unavailable: {}",
+                    provider.provider_name(),
+                    e
+                ));
+            }
+        }
+    }
+    if let Ok(manual) = database
+        .list_manual_activities(user_id, Some(limit as u32))
+        .await
+    {
+        activities.extend(
+            manual
+                .into_iter()
+                .filter(|a| started_between(a, after, before)),
+        );
+    }
+    let mut merged = deduplicate(activities, &merge_config());
+    merged.truncate(limit);
+    ActivityHistory {
+        activities: merged,
+        gaps,
+    }
+}
+
+/// Streams of the given activities from the providers that recorded them
+///
+/// Activities from providers without the streams capability are skipped, as
+/// are streams that fail to load.
+pub async fn activity_streams(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    activities: &[&Activity],
+) -> Vec<(String, ActivityStreams)> {
+    let providers: Vec<_> = connected_activity_providers(connections, user_id)
+        .await
+        .into_iter()
+        .filter(|provider| {
+            registry()
+                .get(&provider.provider_name().to_lowercase())
+                .is_some_and(|d| d.supports(ProviderCapability::Streams))
+        })
+        .collect();
+
+    let mut streams = Vec::new();
+    for activity in activities {
+        let Some(provider) = providers
+            .iter()
+            .find(|p| p.provider_name().eq_ignore_ascii_case(&activity.provider))
+        else {
+            continue;
+        };
+        match provider.get_activity_streams(&activity.id).await {
+            Ok(recorded) => streams.push((activity.id.clone(), recorded)),
+            Err(e) => warn!("Skipping streams for activity {}: {}", activity.id, e),
+        }
+    }
+    streams
+}
+
+/// Like [`all_provider_activities`], keeping the records behind each workout
+pub async fn merged_activities(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    limit: usize,
+) -> Result<Vec<MergedActivity>> {
+    let activities = collect_activities(connections, user_id, Vec::new(), limit).await?;
+    let mut merged = merge_activities(activities, &merge_config());
+    merged.truncate(limit);
+    Ok(merged)
+}
+
+/// Merge already fetched activities with those of the user's other
+/// connected providers and manual entries
+///
+/// Providers whose activities are already in `activities` are not fetched
+/// again. Failures are handled as in [`all_provider_activities`].
+pub async fn with_all_providers(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    activities: Vec<Activity>,
+    limit: usize,
+) -> Result<Vec<Activity>> {
+    let activities = collect_activities(connections, user_id, activities, limit).await?;
+    let mut merged = deduplicate(activities, &merge_config());
+    merged.truncate(limit);
+    Ok(merged)
+}
+
+async fn collect_activities(
+    connections: &ProviderConnections,
+    user_id: Uuid,
+    mut activities: Vec<Activity>,
+    limit: usize,
+) -> Result<Vec<Activity>> {
+    let fetched: HashSet<String> = activities
+        .iter()
+        .map(|activity| activity.provider.clone())
+        .collect();
+
+    for provider in connected_activity_providers(connections, user_id).await {
+        let name = provider.provider_name().to_lowercase();
+        if fetched.contains(&name) {
+            continue;
+        }
+        match provider.get_activities(Some(limit), None).await {
+            Ok(recent) => activities.extend(recent),
+            Err(e) if e.is::<ProviderRateLimited>() => return Err(e),
+            Err(e) => warn!("Skipping {} activities in merged view: {}", name, e),
+        }
+    }
+    if let Ok(manual) = connections
+        .database()
+        .list_manual_activities(user_id, Some(limit as u32))
+        .await
+    {
+        activities.extend(manual);
+    }
+    Ok(activities)
+}
+
+/// Every connected provider behind one `FitnessProvider`
+///
+/// Activity lists are merged across providers. Lifetime stats, the athlete
+/// profile and personal records can't be merged record by record, so they
+/// come from the highest-precedence provider that answers.
+pub struct MergedProvider {
+    providers: Vec<Box<dyn FitnessProvider>>,
+    config: ActivityMergeConfig,
+}
+
+impl MergedProvider {
+    /// Merge the given providers, ordered by the default field precedence
+    pub fn new(mut providers: Vec<Box<dyn FitnessProvider>>, config: ActivityMergeConfig) -> Self {
+        let order = &config.field_precedence.default;
+        providers.sort_by_key(|provider| rank(order, order, provider.provider_name()));
+        Self { providers, config }
+    }
+
+    /// A user's connected activity providers plus their manual activities
+    pub async fn for_user(connections: &ProviderConnections, user_id: Uuid) -> Self {
+        let mut providers = connected_activity_providers(connections, user_id).await;
+        providers.push(Box::new(ManualActivityProvider::new(
+            connections.database().clone(),
+            user_id,
+            None,
+        )));
+        Self::new(providers, merge_config())
+    }
+
+    /// Fetch from every provider, failing only when all of them fail
+    async fn fetch_all(&self, limit: Option<usize>) -> Result<Vec<Activity>> {
+        let mut activities = Vec::new();
+        let mut failures = 0;
+        let mut last_error = None;
+        for provider in &self.providers {
+            match provider.get_activities(limit, None).await {
+                Ok(recent) => activities.extend(recent),
+                Err(e) => {
+                    warn!(
+                        "Skipping {} activities in merged view: {}",
+                        provider.provider_name(),
+                        e
+                    );
+                    failures += 1;
+                    last_error = Some(e);
+                }
+            }
+        }
+        match last_error {
+            Some(e) if failures == self.providers.len() => Err(e),
+            _ => Ok(activities),
+        }
+    }
+}
+
+#[async_trait]
+impl FitnessProvider for MergedProvider {
+    async fn authenticate(&mut self, _auth_data: AuthData) -> Result<()> {
+        bail!("The merged view uses each provider's own connection")
+    }
+
+    async fn get_athlete(&self) -> Result<Athlete> {
+        let mut last_error = None;
+        for provider in &self.providers {
+            match provider.get_athlete().await {
+                Ok(athlete) => return Ok(athlete),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No connected providers")))
+    }
+
+    async fn get_activities(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Activity>> {
+        let offset = offset.unwrap_or(0);
+        let activities = self.fetch_all(limit.map(|limit| limit + offset)).await?;
+        Ok(deduplicate(activities, &self.config)
+            .into_iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    async fn get_activity(&self, id: &str) -> Result<Activity> {
+        let mut found = None;
+        for provider in &self.providers {
+            if let Ok(activity) = provider.get_activity(id).await {
+                found = Some(activity);
+                break;
+            }
+        }
+        let Some(activity) = found else {
+            bail!("Activity {} not found in any connected provider", id);
+        };
+
+        // Look for the same workout among the other providers' recent activities
+        let mut candidates = vec![activity.clone()];
+        for provider in &self.providers {
+            if let Ok(recent) = provider
+                .get_activities(Some(COUNTERPART_WINDOW), None)
+                .await
+            {
+                candidates.extend(
+                    recent
+                        .into_iter()
+                        .filter(|other| other.provider != activity.provider),
+                );
+            }
+        }
+        Ok(merge_activities(candidates, &self.config)
+            .into_iter()
+            .find(|merged| {
+                merged
+                    .sources
+                    .iter()
+                    .any(|s| s.provider == activity.provider && s.activity_id == activity.id)
+            })
+            .map_or(activity, |merged| merged.activity))
+    }
+
+    async fn get_stats(&self) -> Result<Stats> {
+        let mut last_error = None;
+        for provider in &self.providers {
+            match provider.get_stats().await {
+                Ok(stats) => return Ok(stats),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No connected providers")))
+    }
+
+    async fn get_personal_records(&self) -> Result<Vec<PersonalRecord>> {
+        let mut last_error = None;
+        for provider in &self.providers {
+            match provider.get_personal_records().await {
+                Ok(records) => return Ok(records),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No connected providers")))
+    }
+
+    fn provider_name(&self) -> &'static str {
+        ALL_PROVIDERS
+    }
+
+    async fn get_activity_streams(&self, id: &str) -> Result<ActivityStreams> {
+        for provider in &self.providers {
+            if let Ok(streams) = provider.get_activity_streams(id).await {
+                return Ok(streams);
+            }
+        }
+        bail!("No connected provider has streams for activity {}", id)
+    }
+
+    async fn get_daily_wellness(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<DailyWellness>> {
+        // Lower-precedence providers first so higher ones overlay them
+        let mut days: BTreeMap<NaiveDate, DailyWellness> = BTreeMap::new();
+        let mut any = false;
+        for provider in self.providers.iter().rev() {
+            if let Ok(provider_days) = provider.get_daily_wellness(start, end).await {
+                any = true;
+                for day in provider_days {
+                    days.entry(day.date)
+                        .and_modify(|merged| merged.overlay(&day))
+                        .or_insert(day);
+                }
+            }
+        }
+        if !any {
+            bail!("No connected provider supplies wellness data");
+        }
+        Ok(days.into_values().collect())
+    }
+
+    fn set_request_context(&mut self, context: RequestContext) {
+        for provider in &mut self.providers {
+            provider.set_request_context(context);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::rate_budget::ProviderRateLimited;
+    use chrono::{TimeZone, Utc};
+
+    fn record(provider: &str, minute: u32, distance: f64) -> Activity {
+        Activity {
+            id: format!("{}-{}", provider, minute),
+            provider: provider.to_string(),
+            start_date: Utc.with_ymd_and_hms(2024, 6, 3, 6, minute, 0).unwrap(),
+            duration_seconds: 1800,
+            distance_meters: Some(distance),
+            average_heart_rate: None,
+            calories: None,
+            start_latitude: None,
+            start_longitude: None,
+            ..Activity::default()
+        }
+    }
+
+    #[test]
+    fn test_matching_rules() {
+        let config = ActivityMergeConfig::default();
+        let strava = record("strava", 30, 5000.0);
+
+        assert!(is_same_workout(
+            &strava,
+            &record("fitbit", 31, 4950.0),
+            &config
+        ));
+        // Same provider never merges
+        assert!(!is_same_workout(
+            &strava,
+            &record("strava", 31, 5000.0),
+            &config
+        ));
+        // Distances too far apart
+        assert!(!is_same_workout(
+            &strava,
+            &record("fitbit", 31, 8000.0),
+            &config
+        ));
+        // Starts 20 minutes apart overlap only a third of the run
+        assert!(!is_same_workout(
+            &strava,
+            &record("fitbit", 50, 5000.0),
+            &config
+        ));
+
+        let mut ride = record("fitbit", 30, 5000.0);
+        ride.sport_type = SportType::Ride;
+        assert!(!is_same_workout(&strava, &ride, &config));
+        ride.sport_type = SportType::Workout;
+        assert!(is_same_workout(&strava, &ride, &config));
+    }
+
+    #[test]
+    fn test_field_precedence() {
+        let mut strava = record("strava", 30, 5000.0);
+        strava.average_heart_rate = Some(148);
+        strava.start_latitude = Some(45.5);
+        strava.start_longitude = Some(-73.5);
+        let mut fitbit = record("fitbit", 31, 4950.0);
+        fitbit.average_heart_rate = Some(151);
+        fitbit.calories = Some(320);
+        let walk = record("fitbit", 59, 3000.0);
+
+        let merged = merge_activities(vec![walk, fitbit, strava], &ActivityMergeConfig::default());
+        assert_eq!(merged.len(), 2);
+        let run = &merged[1];
+        assert_eq!(run.sources.len(), 2);
+        assert_eq!(run.activity.id, "strava-30");
+        assert_eq!(run.activity.average_heart_rate, Some(151));
+        assert_eq!(run.activity.start_latitude, Some(45.5));
+        assert_eq!(run.activity.distance_meters, Some(5000.0));
+        assert_eq!(run.activity.calories, Some(320));
+
+        // Precedence is configurable per field group
+        let mut config = ActivityMergeConfig::default();
+        config.field_precedence.heart_rate = vec!["strava".to_string()];
+        let merged = merge_activities(
+            vec![record("fitbit", 31, 4950.0), {
+                let mut strava = record("strava", 30, 5000.0);
+                strava.average_heart_rate = Some(148);
+                strava
+            }],
+            &config,
+        );
+        assert_eq!(merged[0].activity.average_heart_rate, Some(148));
+    }
+
+    /// Serves `total` daily runs newest first, refusing pages from `rate_limited_from` on
+    #[derive(Default)]
+    struct PagedStub {
+        total: usize,
+        rate_limited_from: Option<usize>,
+        offsets: std::sync::Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl FitnessProvider for PagedStub {
+        async fn authenticate(&mut self, _auth_data: AuthData) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get_athlete(&self) -> Result<Athlete> {
+            bail!("not needed")
+        }
+
+        async fn get_activities(
+            &self,
+            limit: Option<usize>,
+            offset: Option<usize>,
+        ) -> Result<Vec<Activity>> {
+            let offset = offset.unwrap_or(0);
+            self.offsets.lock().unwrap().push(offset);
+            if self.rate_limited_from.is_some_and(|from| offset >= from) {
+                return Err(ProviderRateLimited {
+                    provider: "strava".to_string(),
+                    retry_after_secs: 900,
+                }
+                .into());
+            }
+            let first = Utc.with_ymd_and_hms(2024, 6, 30, 7, 0, 0).unwrap();
+            Ok((offset..self.total.min(offset + limit.unwrap_or(30)))
+                .map(|i| Activity {
+                    id: i.to_string(),
+                    start_date: first - Duration::days(i as i64),
+                    ..record("strava", 0, 5000.0)
+                })
+                .collect())
+        }
+
+        async fn get_activity(&self, _id: &str) -> Result<Activity> {
+            bail!("not needed")
+        }
+
+        async fn get_stats(&self) -> Result<Stats> {
+            bail!("not needed")
+        }
+
+        async fn get_personal_records(&self) -> Result<Vec<PersonalRecord>> {
+            bail!("not needed")
+        }
+
+        fn provider_name(&self) -> &'static str {
+            "Strava"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paged_history_rate_limited() {
+        // Refused on the first page: the caller gets the rate limit itself
+        let refused = PagedStub {
+            total: 500,
+            rate_limited_from: Some(0),
+            ..Default::default()
+        };
+        let error = paged_activities(&refused, 5000, None, None)
+            .await
+            .unwrap_err();
+        let limited = error
+            .downcast_ref::<ProviderRateLimited>()
+            .expect("rate limit kept");
+        assert_eq!(limited.retry_after_secs, 900);
+
+        // Refused later: what was read is kept and the gap noted
+        let partial = PagedStub {
+            total: 500,
+            rate_limited_from: Some(HISTORY_PAGE),
+            ..Default::default()
+        };
+        let history = paged_activities(&partial, 5000, None, None).await.unwrap();
+        assert_eq!(history.activities.len(), HISTORY_PAGE);
+        assert_eq!(history.gaps.len(), 1);
+        assert!(history.gaps[0].contains("retry after 900 seconds"));
+
+        // Only activities within the dates are kept
+        let after = Utc.with_ymd_and_hms(2024, 6, 21, 0, 0, 0).unwrap();
+        let history = paged_activities(&partial, 5000, Some(after), None)
+            .await
+            .unwrap();
+        assert_eq!(history.activities.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_paged_history_offsets_count_filtered_activities() {
+        // The first page is entirely newer than `before` and the walk still goes on
+        let stub = PagedStub {
+            total: 500,
+            ..Default::default()
+        };
+        let before = Utc.with_ymd_and_hms(2024, 6, 30, 7, 0, 0).unwrap() - Duration::days(250);
+        let history = paged_activities(&stub, 5000, None, Some(before))
+            .await
+            .unwrap();
+        assert_eq!(history.activities.len(), 250);
+        assert!(history.gaps.is_empty());
+        assert_eq!(*stub.offsets.lock().unwrap(), vec![0, 200, 400]);
+    }
+
+    #[tokio::test]
+    async fn test_synced_activities_fill_gaps() {
+        let database = Database::new("sqlite::memory:", vec![0u8; 32])
+            .await
+            .unwrap();
+        let user =
+            crate::models::User::new("runner@example.com".to_string(), "hash".to_string(), None);
+        let user_id = database.create_user(&user).await.unwrap();
+        let stored = Activity {
+            id: "webhook".to_string(),
+            ..record("strava", 0, 5000.0)
+        };
+        database
+            .upsert_synced_activity(user_id, &stored)
+            .await
+            .unwrap();
+
+        let refused = PagedStub {
+            total: 500,
+            rate_limited_from: Some(0),
+            ..Default::default()
+        };
+        let history = provider_history(&database, user_id, &refused, 5000, None, None)
+            .await
+            .unwrap();
+        assert_eq!(history.activities.len(), 1);
+        assert_eq!(history.activities[0].id, "webhook");
+        assert!(history.gaps[0].contains("synced earlier"));
+
+        // A complete walk doesn't need the stored copies
+        let complete = PagedStub {
+            total: 3,
+            ..Default::default()
+        };
+        let history = provider_history(&database, user_id, &complete, 5000, None, None)
+            .await
+            .unwrap();
+        assert_eq!(history.activities.len(), 3);
+        assert!(history.gaps.is_empty());
+    }
+}
@@ -4,13 +4,25 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::models::{Activity, Athlete, PersonalRecord, Stats};
+use crate::config::environment::{ExternalServicesConfig, OAuthConfig, OAuthProviderConfig};
+use crate::models::{Activity, ActivityStreams, Athlete, DailyWellness, PersonalRecord, Stats};
+use crate::oauth::{OAuthError, OAuthProvider};
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+use std::sync::OnceLock;
 
+pub mod connections;
+pub mod fake_server;
 pub mod fitbit;
+pub mod manual;
+pub mod merge;
+pub mod rate_budget;
 pub mod strava;
 
+use rate_budget::RequestContext;
+
 #[async_trait]
 pub trait FitnessProvider: Send + Sync {
     async fn authenticate(&mut self, auth_data: AuthData) -> Result<()>;
@@ -23,6 +35,20 @@ pub trait FitnessProvider: Send + Sync {
         offset: Option<usize>,
     ) -> Result<Vec<Activity>>;
 
+    /// Activities that started after `after` and before `before`, a page at a time
+    ///
+    /// Providers that can't filter by date return activities of any date, so
+    /// callers still check the dates of what comes back.
+    async fn get_activities_between(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+        _after: Option<DateTime<Utc>>,
+        _before: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Activity>> {
+        self.get_activities(limit, offset).await
+    }
+
     #[allow(dead_code)]
     async fn get_activity(&self, id: &str) -> Result<Activity>;
 
@@ -33,6 +59,33 @@ pub trait FitnessProvider: Send + Sync {
 
     #[allow(dead_code)]
     fn provider_name(&self) -> &'static str;
+
+    /// Daily wellness metrics (sleep, resting HR, HRV, SpO2, steps) for an inclusive date range
+    ///
+    /// Only providers registered with `ProviderCapability::Wellness` implement this.
+    async fn get_daily_wellness(
+        &self,
+        _start: NaiveDate,
+        _end: NaiveDate,
+    ) -> Result<Vec<DailyWellness>> {
+        Err(anyhow::anyhow!(
+            "{} does not provide wellness data",
+            self.provider_name()
+        ))
+    }
+
+    /// Heart rate, power, distance and altitude recordings for one activity
+    ///
+    /// Only providers registered with `ProviderCapability::Streams` implement this.
+    async fn get_activity_streams(&self, _id: &str) -> Result<ActivityStreams> {
+        Err(anyhow::anyhow!(
+            "{} does not provide activity streams",
+            self.provider_name()
+        ))
+    }
+
+    /// Attribute subsequent API calls to a user and priority for rate budgeting
+    fn set_request_context(&mut self, _context: RequestContext) {}
 }
 
 #[derive(Debug, Clone)]
@@ -47,13 +100,161 @@ pub enum AuthData {
     ApiKey(String),
 }
 
-pub fn create_provider(provider_type: &str) -> Result<Box<dyn FitnessProvider>> {
-    match provider_type.to_lowercase().as_str() {
-        "strava" => Ok(Box::new(strava::StravaProvider::new())),
-        "fitbit" => Ok(Box::new(fitbit::FitbitProvider::new())),
-        _ => Err(anyhow::anyhow!(
-            "Unknown provider: {}. Currently supported: strava, fitbit",
-            provider_type
-        )),
+/// Who a provider's rate limit applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaScope {
+    /// One quota for the whole OAuth application, shared by every tenant
+    Application,
+    /// A separate quota for each connected user
+    PerUser,
+}
+
+/// Kinds of data a provider can supply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderCapability {
+    /// Workouts via `get_activities`
+    Activities,
+    /// Daily sleep, heart rate and step data via `get_daily_wellness`
+    Wellness,
+    /// Shoes and bikes listed on the athlete returned by `get_athlete`
+    Gear,
+    /// Per-activity time series via `get_activity_streams`
+    Streams,
+}
+
+/// Static description of a fitness provider plugged into the registry
+///
+/// Everything the server needs to know about a provider lives here, so adding
+/// a new one means writing its `FitnessProvider` implementation and
+/// registering a descriptor rather than touching every call site.
+#[derive(Debug, Clone, Copy)]
+pub struct ProviderDescriptor {
+    /// Provider identifier used in tool arguments and token storage
+    pub name: &'static str,
+    /// Human readable name
+    pub display_name: &'static str,
+    /// Builds an unauthenticated provider instance talking to the configured endpoints
+    pub factory: fn(&ExternalServicesConfig) -> Box<dyn FitnessProvider>,
+    /// Resolves the OAuth client credentials as `(client_id, client_secret)`
+    pub client_credentials: fn() -> Option<(String, String)>,
+    /// Selects the provider's OAuth application settings from the server configuration
+    pub oauth_config: fn(&OAuthConfig) -> &OAuthProviderConfig,
+    /// Builds the OAuth client that owns the provider's endpoints, scopes and token exchange
+    pub oauth_client: fn(&OAuthProviderConfig) -> Result<Box<dyn OAuthProvider>, OAuthError>,
+    /// Whether the provider's rate limit is shared across users
+    pub quota_scope: QuotaScope,
+    /// Data the provider can supply
+    pub capabilities: &'static [ProviderCapability],
+}
+
+impl ProviderDescriptor {
+    /// Whether the provider can supply a kind of data
+    pub fn supports(&self, capability: ProviderCapability) -> bool {
+        self.capabilities.contains(&capability)
     }
+
+    /// Build the provider's OAuth client from the server's OAuth settings
+    pub fn oauth_provider(
+        &self,
+        config: &OAuthConfig,
+    ) -> std::result::Result<Box<dyn OAuthProvider>, OAuthError> {
+        (self.oauth_client)((self.oauth_config)(config))
+    }
+}
+
+/// Registry of the fitness providers available to the server
+#[derive(Debug, Clone, Default)]
+pub struct ProviderRegistry {
+    providers: HashMap<&'static str, ProviderDescriptor>,
+}
+
+impl ProviderRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with the built-in Strava and Fitbit providers
+    pub fn with_builtin_providers() -> Self {
+        let mut registry = Self::new();
+        registry.register(strava::descriptor());
+        registry.register(fitbit::descriptor());
+        registry
+    }
+
+    /// Register a provider, replacing any previous descriptor with the same name
+    pub fn register(&mut self, descriptor: ProviderDescriptor) {
+        self.providers.insert(descriptor.name, descriptor);
+    }
+
+    /// Look up a provider descriptor by name (case-insensitive)
+    pub fn get(&self, name: &str) -> Option<&ProviderDescriptor> {
+        self.providers.get(name.to_lowercase().as_str())
+    }
+
+    /// Check whether a provider is registered
+    pub fn is_supported(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Names of all registered providers, sorted
+    pub fn provider_names(&self) -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = self.providers.keys().copied().collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Create an unauthenticated provider instance using the API endpoints in `api`
+    pub fn create(
+        &self,
+        name: &str,
+        api: &ExternalServicesConfig,
+    ) -> Result<Box<dyn FitnessProvider>> {
+        let descriptor = self.get(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown provider: {}. Currently supported: {}",
+                name,
+                self.provider_names().join(", ")
+            )
+        })?;
+        Ok((descriptor.factory)(api))
+    }
+
+    /// Create a provider authenticated with a user's stored OAuth token
+    ///
+    /// The OAuth client credentials come from the provider's own descriptor.
+    pub async fn create_authenticated(
+        &self,
+        name: &str,
+        api: &ExternalServicesConfig,
+        access_token: &str,
+        refresh_token: &str,
+    ) -> Result<Box<dyn FitnessProvider>> {
+        let mut provider = self.create(name, api)?;
+        let (client_id, client_secret) = self
+            .get(name)
+            .and_then(|descriptor| (descriptor.client_credentials)())
+            .unwrap_or_default();
+
+        provider
+            .authenticate(AuthData::OAuth2 {
+                client_id,
+                client_secret,
+                access_token: Some(access_token.to_string()),
+                refresh_token: Some(refresh_token.to_string()),
+            })
+            .await?;
+        Ok(provider)
+    }
+}
+
+/// Process-wide registry holding the built-in providers
+pub fn registry() -> &'static ProviderRegistry {
+    static REGISTRY: OnceLock<ProviderRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(ProviderRegistry::with_builtin_providers)
+}
+
+/// Create a provider using the API endpoints configured in the environment
+pub fn create_provider(provider_type: &str) -> Result<Box<dyn FitnessProvider>> {
+    registry().create(provider_type, &ExternalServicesConfig::from_env()?)
 }
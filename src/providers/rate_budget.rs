@@ -0,0 +1,540 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! # Provider Rate Budget
+//!
+//! Every tenant calls Strava and Fitbit with the same OAuth application, so
+//! the provider's quota is shared by everyone on the server. This module
+//! tracks that quota from the rate-limit headers each provider returns and
+//! decides, before a request goes out, whether it should proceed, wait in
+//! the queue, or be refused with a "retry after" hint.
+//!
+//! - Strava reports application-wide 15-minute and daily windows in
+//!   `X-RateLimit-Limit` / `X-RateLimit-Usage`.
+//! - Fitbit reports an hourly per-user window in `Fitbit-Rate-Limit-Limit`,
+//!   `Fitbit-Rate-Limit-Remaining` and `Fitbit-Rate-Limit-Reset`.
+//!
+//! Interactive tool calls may use the whole remaining quota while background
+//! sync stops short of a reserved slice, and once a shared window is half
+//! spent no single user may take more than their fair share of it.
+
+use super::QuotaScope;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use thiserror::Error;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Length of Strava's short rate-limit window
+const STRAVA_SHORT_WINDOW_SECS: i64 = 15 * 60;
+/// Length of Strava's daily rate-limit window
+const STRAVA_DAILY_WINDOW_SECS: i64 = 24 * 60 * 60;
+/// Length of Fitbit's hourly rate-limit window
+const FITBIT_WINDOW_SECS: i64 = 60 * 60;
+/// How long a background request yields to queued interactive requests
+const INTERACTIVE_YIELD_SECS: i64 = 1;
+
+/// Who a provider request is made for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestPriority {
+    /// A tool call a client is waiting on
+    #[default]
+    Interactive,
+    /// Webhook sync and other work nobody is waiting on
+    Background,
+}
+
+/// Attribution for a provider request, used for budgeting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RequestContext {
+    pub user_id: Option<Uuid>,
+    pub priority: RequestPriority,
+}
+
+impl RequestContext {
+    /// A tool call made on behalf of a user
+    pub fn interactive(user_id: Uuid) -> Self {
+        Self {
+            user_id: Some(user_id),
+            priority: RequestPriority::Interactive,
+        }
+    }
+
+    /// Background sync made on behalf of a user
+    pub fn background(user_id: Uuid) -> Self {
+        Self {
+            user_id: Some(user_id),
+            priority: RequestPriority::Background,
+        }
+    }
+}
+
+/// The provider's quota is exhausted for this request
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("{provider} API rate limit reached, retry after {retry_after_secs} seconds")]
+pub struct ProviderRateLimited {
+    pub provider: String,
+    pub retry_after_secs: u64,
+}
+
+/// Tuning for how the shared quota is split
+#[derive(Debug, Clone)]
+pub struct BudgetConfig {
+    /// Fraction of each window background requests must leave for interactive ones
+    pub interactive_reserve: f64,
+    /// Largest fraction of a shared window one user may consume under contention
+    pub per_user_share: f64,
+    /// Longest an interactive request waits in the queue before being refused
+    pub max_interactive_wait: Duration,
+    /// Longest a background request waits in the queue before being refused
+    pub max_background_wait: Duration,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            interactive_reserve: 0.2,
+            per_user_share: 0.25,
+            max_interactive_wait: Duration::seconds(10),
+            max_background_wait: Duration::minutes(15),
+        }
+    }
+}
+
+/// Remaining quota in one provider window
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowStatus {
+    pub period_secs: i64,
+    pub limit: u32,
+    pub used: u32,
+    pub resets_at: DateTime<Utc>,
+}
+
+/// Budgeting decision for a single request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    Proceed,
+    Wait(Duration),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct BudgetKey {
+    provider: String,
+    user_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone)]
+struct QuotaWindow {
+    period_secs: i64,
+    limit: u32,
+    used: u32,
+    resets_at: DateTime<Utc>,
+}
+
+impl QuotaWindow {
+    fn new(period_secs: i64, limit: u32, used: u32, now: DateTime<Utc>) -> Self {
+        Self {
+            period_secs,
+            limit,
+            used,
+            resets_at: next_boundary(now, period_secs),
+        }
+    }
+
+    /// Start a fresh window once the previous one has reset
+    fn roll(&mut self, now: DateTime<Utc>) {
+        if now >= self.resets_at {
+            self.used = 0;
+            self.resets_at = next_boundary(now, self.period_secs);
+        }
+    }
+
+    fn remaining(&self) -> u32 {
+        self.limit.saturating_sub(self.used)
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProviderBudget {
+    windows: Vec<QuotaWindow>,
+    blocked_until: Option<DateTime<Utc>>,
+    user_usage: HashMap<Uuid, u32>,
+    user_usage_resets_at: Option<DateTime<Utc>>,
+    waiting_interactive: usize,
+}
+
+impl ProviderBudget {
+    fn roll(&mut self, now: DateTime<Utc>) {
+        for window in &mut self.windows {
+            window.roll(now);
+        }
+        if self.blocked_until.is_some_and(|until| now >= until) {
+            self.blocked_until = None;
+        }
+        if self.user_usage_resets_at.is_some_and(|at| now >= at) {
+            self.user_usage.clear();
+            self.user_usage_resets_at = None;
+        }
+    }
+
+    /// Replace the tracked windows with what the provider just reported
+    fn observe(&mut self, windows: Vec<QuotaWindow>) {
+        if let Some(short) = windows.first() {
+            if self
+                .user_usage_resets_at
+                .is_none_or(|at| at != short.resets_at)
+            {
+                self.user_usage.clear();
+                self.user_usage_resets_at = Some(short.resets_at);
+            }
+        }
+        self.windows = windows;
+    }
+
+    fn admit(
+        &mut self,
+        context: &RequestContext,
+        scope: QuotaScope,
+        config: &BudgetConfig,
+        now: DateTime<Utc>,
+    ) -> Admission {
+        self.roll(now);
+
+        if let Some(until) = self.blocked_until {
+            return Admission::Wait(until - now);
+        }
+
+        if context.priority == RequestPriority::Background && self.waiting_interactive > 0 {
+            return Admission::Wait(Duration::seconds(INTERACTIVE_YIELD_SECS));
+        }
+
+        for window in &self.windows {
+            let reserve = match context.priority {
+                RequestPriority::Interactive => 0,
+                RequestPriority::Background => {
+                    (f64::from(window.limit) * config.interactive_reserve).ceil() as u32
+                }
+            };
+            if window.remaining() <= reserve {
+                return Admission::Wait(window.resets_at - now);
+            }
+        }
+
+        // Fair share only matters when the quota is shared between users
+        if let (QuotaScope::Application, Some(user_id), Some(short)) =
+            (scope, context.user_id, self.windows.first())
+        {
+            let contended = short.used >= short.limit / 2;
+            let share = (f64::from(short.limit) * config.per_user_share).ceil() as u32;
+            let user_used = self.user_usage.get(&user_id).copied().unwrap_or(0);
+            if contended && user_used >= share.max(1) {
+                return Admission::Wait(short.resets_at - now);
+            }
+        }
+
+        for window in &mut self.windows {
+            window.used += 1;
+        }
+        if let Some(user_id) = context.user_id {
+            *self.user_usage.entry(user_id).or_insert(0) += 1;
+        }
+        Admission::Proceed
+    }
+}
+
+/// Process-wide tracker of every provider's shared quota
+#[derive(Debug, Default)]
+pub struct RateBudget {
+    config: BudgetConfig,
+    budgets: Mutex<HashMap<BudgetKey, ProviderBudget>>,
+}
+
+impl RateBudget {
+    pub fn new(config: BudgetConfig) -> Self {
+        Self {
+            config,
+            budgets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Decide whether a request may go out now
+    ///
+    /// Admitted requests are counted against the budget immediately so that
+    /// concurrent callers see each other before the provider responds.
+    pub fn admit_at(
+        &self,
+        provider: &str,
+        context: &RequestContext,
+        now: DateTime<Utc>,
+    ) -> Admission {
+        let scope = quota_scope(provider);
+        let mut budgets = self.lock();
+        budgets
+            .entry(budget_key(provider, scope, context))
+            .or_default()
+            .admit(context, scope, &self.config, now)
+    }
+
+    /// Wait for budget to become available, or fail with a retry hint
+    pub async fn acquire(
+        &self,
+        provider: &str,
+        context: &RequestContext,
+    ) -> Result<(), ProviderRateLimited> {
+        let max_wait = match context.priority {
+            RequestPriority::Interactive => self.config.max_interactive_wait,
+            RequestPriority::Background => self.config.max_background_wait,
+        };
+        let started = Utc::now();
+        let mut waiting = None;
+
+        loop {
+            let now = Utc::now();
+            let wait = match self.admit_at(provider, context, now) {
+                Admission::Proceed => return Ok(()),
+                Admission::Wait(wait) => wait.max(Duration::milliseconds(100)),
+            };
+
+            if (now - started) + wait > max_wait {
+                return Err(ProviderRateLimited {
+                    provider: provider.to_string(),
+                    retry_after_secs: ceil_secs(wait),
+                });
+            }
+
+            if waiting.is_none() && context.priority == RequestPriority::Interactive {
+                waiting = Some(InteractiveWaiting::new(self, provider, context));
+            }
+            debug!(
+                "Queueing {} request for {}ms to stay within rate budget",
+                provider,
+                wait.num_milliseconds()
+            );
+            tokio::time::sleep(wait.to_std().unwrap_or_default()).await;
+        }
+    }
+
+    /// Send a provider request within the budget and record what it reports
+    ///
+    /// A 429 from the provider blocks further requests until the window
+    /// resets and is returned as [`ProviderRateLimited`].
+    pub async fn send(
+        &self,
+        provider: &str,
+        context: &RequestContext,
+        request: RequestBuilder,
+    ) -> anyhow::Result<Response> {
+        self.acquire(provider, context).await?;
+        let response = request.send().await?;
+        let now = Utc::now();
+        self.record_headers_at(provider, context, response.headers(), now);
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after =
+                self.record_rate_limited_at(provider, context, response.headers(), now);
+            warn!(
+                "{} returned 429, blocking requests for {}s",
+                provider,
+                retry_after.num_seconds()
+            );
+            return Err(ProviderRateLimited {
+                provider: provider.to_string(),
+                retry_after_secs: ceil_secs(retry_after),
+            }
+            .into());
+        }
+
+        Ok(response)
+    }
+
+    /// Update the budget from a provider response's rate-limit headers
+    pub fn record_headers_at(
+        &self,
+        provider: &str,
+        context: &RequestContext,
+        headers: &HeaderMap,
+        now: DateTime<Utc>,
+    ) {
+        let windows = parse_strava_windows(headers, now)
+            .or_else(|| parse_fitbit_window(headers, now).map(|window| vec![window]));
+        if let Some(windows) = windows {
+            let scope = quota_scope(provider);
+            self.lock()
+                .entry(budget_key(provider, scope, context))
+                .or_default()
+                .observe(windows);
+        }
+    }
+
+    /// Block a provider after a 429 and return how long until it may be retried
+    pub fn record_rate_limited_at(
+        &self,
+        provider: &str,
+        context: &RequestContext,
+        headers: &HeaderMap,
+        now: DateTime<Utc>,
+    ) -> Duration {
+        let scope = quota_scope(provider);
+        let mut budgets = self.lock();
+        let budget = budgets
+            .entry(budget_key(provider, scope, context))
+            .or_default();
+
+        let retry_after = header_u64(headers, "retry-after")
+            .map(|secs| Duration::seconds(secs as i64))
+            .or_else(|| {
+                budget
+                    .windows
+                    .iter()
+                    .filter(|window| window.remaining() == 0)
+                    .map(|window| window.resets_at - now)
+                    .max()
+            })
+            .unwrap_or_else(|| next_boundary(now, STRAVA_SHORT_WINDOW_SECS) - now);
+
+        budget.blocked_until = Some(now + retry_after);
+        retry_after
+    }
+
+    /// Current windows tracked for a provider (and user, for per-user quotas)
+    pub fn status(&self, provider: &str, context: &RequestContext) -> Vec<WindowStatus> {
+        let scope = quota_scope(provider);
+        self.lock()
+            .get(&budget_key(provider, scope, context))
+            .map(|budget| {
+                budget
+                    .windows
+                    .iter()
+                    .map(|window| WindowStatus {
+                        period_secs: window.period_secs,
+                        limit: window.limit,
+                        used: window.used,
+                        resets_at: window.resets_at,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn set_interactive_waiting(&self, provider: &str, context: &RequestContext, waiting: bool) {
+        let scope = quota_scope(provider);
+        let mut budgets = self.lock();
+        let budget = budgets
+            .entry(budget_key(provider, scope, context))
+            .or_default();
+        if waiting {
+            budget.waiting_interactive += 1;
+        } else {
+            budget.waiting_interactive = budget.waiting_interactive.saturating_sub(1);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<BudgetKey, ProviderBudget>> {
+        self.budgets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Marks an interactive request as queued until dropped, so a caller that
+/// gives up mid-wait doesn't hold background requests back forever
+struct InteractiveWaiting<'a> {
+    budget: &'a RateBudget,
+    provider: &'a str,
+    context: &'a RequestContext,
+}
+
+impl<'a> InteractiveWaiting<'a> {
+    fn new(budget: &'a RateBudget, provider: &'a str, context: &'a RequestContext) -> Self {
+        budget.set_interactive_waiting(provider, context, true);
+        Self {
+            budget,
+            provider,
+            context,
+        }
+    }
+}
+
+impl Drop for InteractiveWaiting<'_> {
+    fn drop(&mut self) {
+        self.budget
+            .set_interactive_waiting(self.provider, self.context, false);
+    }
+}
+
+/// Process-wide budget shared by every tenant
+pub fn budget() -> &'static RateBudget {
+    static BUDGET: OnceLock<RateBudget> = OnceLock::new();
+    BUDGET.get_or_init(RateBudget::default)
+}
+
+fn quota_scope(provider: &str) -> QuotaScope {
+    super::registry()
+        .get(provider)
+        .map(|descriptor| descriptor.quota_scope)
+        .unwrap_or(QuotaScope::Application)
+}
+
+fn budget_key(provider: &str, scope: QuotaScope, context: &RequestContext) -> BudgetKey {
+    BudgetKey {
+        provider: provider.to_lowercase(),
+        user_id: match scope {
+            QuotaScope::Application => None,
+            QuotaScope::PerUser => context.user_id,
+        },
+    }
+}
+
+/// Start of the next window of `period_secs` aligned to the Unix epoch
+fn next_boundary(now: DateTime<Utc>, period_secs: i64) -> DateTime<Utc> {
+    let next = (now.timestamp() / period_secs + 1) * period_secs;
+    DateTime::from_timestamp(next, 0).unwrap_or(now)
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    let millis = duration.num_milliseconds().max(0) as u64;
+    millis.div_ceil(1000)
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    header_str(headers, name).and_then(|value| value.trim().parse().ok())
+}
+
+/// Parse a comma separated header such as `"100,1000"`
+fn header_pair(headers: &HeaderMap, name: &str) -> Option<(u32, u32)> {
+    let (first, second) = header_str(headers, name)?.split_once(',')?;
+    Some((first.trim().parse().ok()?, second.trim().parse().ok()?))
+}
+
+/// Strava's 15-minute and daily windows, in that order
+fn parse_strava_windows(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Vec<QuotaWindow>> {
+    let (short_limit, daily_limit) = header_pair(headers, "x-ratelimit-limit")?;
+    let (short_used, daily_used) = header_pair(headers, "x-ratelimit-usage")?;
+    Some(vec![
+        QuotaWindow::new(STRAVA_SHORT_WINDOW_SECS, short_limit, short_used, now),
+        QuotaWindow::new(STRAVA_DAILY_WINDOW_SECS, daily_limit, daily_used, now),
+    ])
+}
+
+/// Fitbit's hourly window
+fn parse_fitbit_window(headers: &HeaderMap, now: DateTime<Utc>) -> Option<QuotaWindow> {
+    let limit = header_u64(headers, "fitbit-rate-limit-limit")? as u32;
+    let remaining = header_u64(headers, "fitbit-rate-limit-remaining")? as u32;
+    let mut window = QuotaWindow::new(FITBIT_WINDOW_SECS, limit, limit - remaining.min(limit), now);
+    if let Some(reset) = header_u64(headers, "fitbit-rate-limit-reset") {
+        window.resets_at = now + Duration::seconds(reset as i64);
+    }
+    Some(window)
+}
@@ -4,10 +4,13 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::{AuthData, FitnessProvider};
+use super::rate_budget::{self, RequestContext};
+use super::{AuthData, FitnessProvider, ProviderCapability, ProviderDescriptor, QuotaScope};
+use crate::config::environment::StravaApiConfig;
 use crate::config::FitnessConfig;
-use crate::constants::env_config;
-use crate::models::{Activity, Athlete, PersonalRecord, SportType, Stats};
+use crate::models::{
+    Activity, ActivityStreams, Athlete, Gear, GearType, PersonalRecord, SportType, Stats,
+};
 use crate::oauth2_client::PkceParams;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
@@ -22,6 +25,35 @@ pub struct StravaProvider {
     client_id: Option<String>,
     client_secret: Option<String>,
     refresh_token: Option<String>,
+    request_context: RequestContext,
+    api: StravaApiConfig,
+}
+
+/// Registry descriptor for the Strava provider
+pub fn descriptor() -> ProviderDescriptor {
+    ProviderDescriptor {
+        name: "strava",
+        display_name: "Strava",
+        factory: |api| Box::new(StravaProvider::with_api_config(api.strava_api.clone())),
+        client_credentials: || {
+            Some((
+                crate::constants::env_config::strava_client_id()?,
+                crate::constants::env_config::strava_client_secret()?,
+            ))
+        },
+        oauth_config: |config| &config.strava,
+        oauth_client: |config| {
+            Ok(Box::new(
+                crate::oauth::providers::StravaOAuthProvider::from_config(config)?,
+            ))
+        },
+        quota_scope: QuotaScope::Application,
+        capabilities: &[
+            ProviderCapability::Activities,
+            ProviderCapability::Gear,
+            ProviderCapability::Streams,
+        ],
+    }
 }
 
 impl Default for StravaProvider {
@@ -32,12 +64,19 @@ impl Default for StravaProvider {
 
 impl StravaProvider {
     pub fn new() -> Self {
+        Self::with_api_config(StravaApiConfig::from_env())
+    }
+
+    /// Create a provider that talks to the given Strava endpoints
+    pub fn with_api_config(api: StravaApiConfig) -> Self {
         Self {
             client: Client::new(),
             access_token: None,
             client_id: None,
             client_secret: None,
             refresh_token: None,
+            request_context: RequestContext::default(),
+            api,
         }
     }
 
@@ -48,7 +87,7 @@ impl StravaProvider {
             .as_ref()
             .context("Client ID not configured")?;
 
-        let mut url = url::Url::parse(&env_config::strava_auth_url())?;
+        let mut url = url::Url::parse(&self.api.auth_url)?;
         url.query_pairs_mut()
             .append_pair("client_id", client_id)
             .append_pair("redirect_uri", redirect_uri)
@@ -72,7 +111,7 @@ impl StravaProvider {
             .as_ref()
             .context("Client ID not configured")?;
 
-        let mut url = url::Url::parse(&env_config::strava_auth_url())?;
+        let mut url = url::Url::parse(&self.api.auth_url)?;
         url.query_pairs_mut()
             .append_pair("client_id", client_id)
             .append_pair("redirect_uri", redirect_uri)
@@ -211,10 +250,11 @@ impl FitnessProvider for StravaProvider {
         let token = self.access_token.as_ref().context("Not authenticated")?;
 
         let response: StravaAthlete = self
-            .client
-            .get(format!("{}/athlete", env_config::strava_api_base()))
-            .bearer_auth(token)
-            .send()
+            .send(
+                self.client
+                    .get(format!("{}/athlete", self.api.base_url))
+                    .bearer_auth(token),
+            )
             .await?
             .json()
             .await?;
@@ -226,6 +266,17 @@ impl FitnessProvider for StravaProvider {
             lastname: response.lastname,
             profile_picture: response.profile,
             provider: "strava".to_string(),
+            gear: response
+                .shoes
+                .into_iter()
+                .map(|shoe| shoe.into_gear(GearType::Shoes))
+                .chain(
+                    response
+                        .bikes
+                        .into_iter()
+                        .map(|bike| bike.into_gear(GearType::Bike)),
+                )
+                .collect(),
         })
     }
 
@@ -233,6 +284,16 @@ impl FitnessProvider for StravaProvider {
         &self,
         limit: Option<usize>,
         offset: Option<usize>,
+    ) -> Result<Vec<Activity>> {
+        self.get_activities_between(limit, offset, None, None).await
+    }
+
+    async fn get_activities_between(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
     ) -> Result<Vec<Activity>> {
         let token = self.access_token.as_ref().context("Not authenticated")?;
 
@@ -243,16 +304,18 @@ impl FitnessProvider for StravaProvider {
         if let Some(offset) = offset {
             query.push(("page", (offset / limit.unwrap_or(30) + 1).to_string()));
         }
+        if let Some(after) = after {
+            query.push(("after", after.timestamp().to_string()));
+        }
+        if let Some(before) = before {
+            query.push(("before", before.timestamp().to_string()));
+        }
 
-        let url = format!("{}/athlete/activities", env_config::strava_api_base());
+        let url = format!("{}/athlete/activities", self.api.base_url);
         info!("Fetching activities from: {} with query: {:?}", url, query);
 
         let response = self
-            .client
-            .get(&url)
-            .bearer_auth(token)
-            .query(&query)
-            .send()
+            .send(self.client.get(&url).bearer_auth(token).query(&query))
             .await
             .context("Failed to send request to Strava API")?;
 
@@ -326,15 +389,13 @@ impl FitnessProvider for StravaProvider {
         let token = self.access_token.as_ref().context("Not authenticated")?;
 
         let response: StravaActivity = self
-            .client
-            .get(format!(
-                "{}/activities/{}",
-                env_config::strava_api_base(),
-                id
-            ))
-            .bearer_auth(token)
-            .send()
+            .send(
+                self.client
+                    .get(format!("{}/activities/{}", self.api.base_url, id))
+                    .bearer_auth(token),
+            )
             .await?
+            .error_for_status()?
             .json()
             .await?;
 
@@ -368,6 +429,36 @@ impl FitnessProvider for StravaProvider {
         Ok(vec![])
     }
 
+    async fn get_activity_streams(&self, id: &str) -> Result<ActivityStreams> {
+        let token = self.access_token.as_ref().context("Not authenticated")?;
+
+        let response: StravaStreamSet = self
+            .send(
+                self.client
+                    .get(format!("{}/activities/{}/streams", self.api.base_url, id))
+                    .query(&[
+                        ("keys", "time,heartrate,watts,distance,altitude,cadence"),
+                        ("key_by_type", "true"),
+                    ])
+                    .bearer_auth(token),
+            )
+            .await?
+            .json()
+            .await?;
+
+        // Missing activities answer with an error body instead of streams
+        anyhow::ensure!(
+            response.time.is_some(),
+            "No streams recorded for activity {}",
+            id
+        );
+        Ok(response.into())
+    }
+
+    fn set_request_context(&mut self, context: RequestContext) {
+        self.request_context = context;
+    }
+
     #[allow(dead_code)]
     fn provider_name(&self) -> &'static str {
         "Strava"
@@ -375,30 +466,38 @@ impl FitnessProvider for StravaProvider {
 }
 
 impl StravaProvider {
+    /// Send a request within the shared Strava rate budget
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        rate_budget::budget()
+            .send("strava", &self.request_context, request)
+            .await
+    }
+
     // Try to get stats from Strava's athlete stats endpoint
     async fn get_strava_athlete_stats(&self) -> Result<Stats> {
         let token = self.access_token.as_ref().context("Not authenticated")?;
 
         // Get athlete ID first
         let athlete: StravaAthlete = self
-            .client
-            .get(format!("{}/athlete", env_config::strava_api_base()))
-            .bearer_auth(token)
-            .send()
+            .send(
+                self.client
+                    .get(format!("{}/athlete", self.api.base_url))
+                    .bearer_auth(token),
+            )
             .await?
             .json()
             .await?;
 
         // Get athlete stats
         let response: StravaAthleteStats = self
-            .client
-            .get(format!(
-                "{}/athletes/{}/stats",
-                env_config::strava_api_base(),
-                athlete.id
-            ))
-            .bearer_auth(token)
-            .send()
+            .send(
+                self.client
+                    .get(format!(
+                        "{}/athletes/{}/stats",
+                        self.api.base_url, athlete.id
+                    ))
+                    .bearer_auth(token),
+            )
             .await?
             .json()
             .await?;
@@ -422,6 +521,76 @@ struct StravaAthlete {
     firstname: Option<String>,
     lastname: Option<String>,
     profile: Option<String>,
+    #[serde(default)]
+    shoes: Vec<StravaSummaryGear>,
+    #[serde(default)]
+    bikes: Vec<StravaSummaryGear>,
+}
+
+/// Shoes or bike as listed on the authenticated athlete
+#[derive(Debug, Deserialize)]
+struct StravaSummaryGear {
+    id: String,
+    name: String,
+    #[serde(default)]
+    primary: bool,
+    /// Lifetime distance in meters
+    #[serde(default)]
+    distance: f64,
+    #[serde(default)]
+    retired: bool,
+}
+
+impl StravaSummaryGear {
+    fn into_gear(self, gear_type: GearType) -> Gear {
+        Gear {
+            id: self.id,
+            name: self.name,
+            gear_type,
+            brand: None,
+            model: None,
+            provider: "strava".to_string(),
+            distance_meters: self.distance,
+            moving_time_seconds: 0,
+            activity_count: 0,
+            retirement_distance_meters: None,
+            primary: self.primary,
+            retired: self.retired,
+        }
+    }
+}
+
+/// One series from the streams endpoint
+#[derive(Debug, Deserialize)]
+struct StravaStream<T> {
+    data: Vec<T>,
+}
+
+/// Streams keyed by type (`key_by_type=true`)
+#[derive(Debug, Deserialize)]
+struct StravaStreamSet {
+    time: Option<StravaStream<u32>>,
+    heartrate: Option<StravaStream<u32>>,
+    /// Null while not pedalling
+    watts: Option<StravaStream<Option<u32>>>,
+    distance: Option<StravaStream<f64>>,
+    altitude: Option<StravaStream<f64>>,
+    cadence: Option<StravaStream<u32>>,
+}
+
+impl From<StravaStreamSet> for ActivityStreams {
+    fn from(streams: StravaStreamSet) -> Self {
+        ActivityStreams {
+            time: streams.time.map(|s| s.data).unwrap_or_default(),
+            heart_rate: streams.heartrate.map(|s| s.data),
+            power: streams
+                .watts
+                .map(|s| s.data.into_iter().map(Option::unwrap_or_default).collect()),
+            distance: streams.distance.map(|s| s.data),
+            altitude: streams.altitude.map(|s| s.data),
+            cadence: streams.cadence.map(|s| s.data),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -439,6 +608,13 @@ struct StravaActivity {
     average_speed: Option<f64>,
     max_speed: Option<f64>,
     start_latlng: Option<Vec<f64>>, // [latitude, longitude]
+    gear_id: Option<String>,
+    map: Option<StravaMap>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StravaMap {
+    summary_polyline: Option<String>,
 }
 
 impl From<StravaActivity> for Activity {
@@ -477,6 +653,11 @@ impl From<StravaActivity> for Activity {
             region: None,
             country: None,
             trail_name: None,
+            gear_id: strava.gear_id,
+            summary_polyline: strava
+                .map
+                .and_then(|map| map.summary_polyline)
+                .filter(|polyline| !polyline.is_empty()),
             provider: "strava".to_string(),
         }
     }
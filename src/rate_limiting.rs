@@ -301,8 +301,6 @@ mod tests {
             display_name: Some("Test User".to_string()),
             password_hash: "hash".to_string(),
             tier: UserTier::Professional,
-            strava_token: None,
-            fitbit_token: None,
             created_at: Utc::now(),
             last_active: Utc::now(),
             is_active: true,
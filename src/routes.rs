@@ -8,11 +8,12 @@
 
 use crate::{
     auth::AuthManager,
+    config::environment::OAuthConfig,
     database_plugins::{factory::Database, DatabaseProvider},
-    models::User,
+    models::{DecryptedToken, User},
+    oauth::OAuthProvider,
 };
 use anyhow::Result;
-use base64::{engine::general_purpose, Engine};
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 use uuid::Uuid;
@@ -74,6 +75,10 @@ pub struct OAuthAuthorizationResponse {
 pub struct ConnectionStatus {
     pub provider: String,
     pub connected: bool,
+    /// `active`, `needs_reauth` or `not_connected`
+    pub status: String,
+    pub needs_reauth: bool,
+    pub reauth_reason: Option<String>,
     pub expires_at: Option<String>,
     pub scopes: Option<String>,
 }
@@ -86,34 +91,6 @@ pub struct OAuthCallbackResponse {
     pub scopes: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct StravaTokenResponse {
-    access_token: String,
-    refresh_token: String,
-    expires_at: i64,
-    #[allow(dead_code)]
-    expires_in: i64,
-    #[allow(dead_code)]
-    token_type: String,
-    #[serde(default)]
-    scope: Option<String>,
-    #[allow(dead_code)]
-    #[serde(default)]
-    athlete: serde_json::Value,
-}
-
-#[derive(Debug, Deserialize)]
-struct FitbitTokenResponse {
-    access_token: String,
-    refresh_token: String,
-    expires_in: i64,
-    #[allow(dead_code)]
-    token_type: String,
-    scope: String,
-    #[allow(dead_code)]
-    user_id: String,
-}
-
 #[derive(Clone)]
 pub struct AuthRoutes {
     database: Database,
@@ -189,8 +166,18 @@ impl AuthRoutes {
         // Update last active timestamp
         self.database.update_last_active(user.id).await?;
 
-        // Generate JWT token
-        let jwt_token = self.auth_manager.generate_token(&user)?;
+        // Generate JWT token listing the user's connected providers
+        let providers = self
+            .database
+            .list_provider_connections(user.id)
+            .await?
+            .into_iter()
+            .filter(|connection| !connection.is_expired())
+            .map(|connection| connection.provider)
+            .collect();
+        let jwt_token = self
+            .auth_manager
+            .generate_token_with_providers(&user, providers)?;
         let expires_at = chrono::Utc::now() + chrono::Duration::hours(24); // Default 24h expiry
 
         info!(
@@ -270,13 +257,37 @@ impl AuthRoutes {
 /// OAuth flow routes for connecting fitness providers
 #[derive(Clone)]
 pub struct OAuthRoutes {
-    #[allow(dead_code)]
     database: Database,
+    oauth_config: Option<OAuthConfig>,
 }
 
 impl OAuthRoutes {
+    /// OAuth routes reading provider settings from the environment on each request
     pub fn new(database: Database) -> Self {
-        Self { database }
+        Self {
+            database,
+            oauth_config: None,
+        }
+    }
+
+    /// OAuth routes using fixed provider settings
+    pub fn with_oauth_config(database: Database, oauth_config: OAuthConfig) -> Self {
+        Self {
+            database,
+            oauth_config: Some(oauth_config),
+        }
+    }
+
+    /// OAuth client for a registered provider
+    fn oauth_provider(&self, provider: &str) -> Result<Box<dyn OAuthProvider>> {
+        let descriptor = crate::providers::registry()
+            .get(provider)
+            .ok_or_else(|| anyhow::anyhow!("Unsupported provider: {}", provider))?;
+        let oauth_provider = match &self.oauth_config {
+            Some(config) => descriptor.oauth_provider(config),
+            None => descriptor.oauth_provider(&OAuthConfig::from_env()?),
+        }?;
+        Ok(oauth_provider)
     }
 
     /// Get OAuth authorization URL for a provider with real configuration
@@ -289,71 +300,17 @@ impl OAuthRoutes {
         let state = format!("{}:{}", user_id, uuid::Uuid::new_v4());
         self.store_oauth_state(user_id, provider, &state).await?;
 
-        match provider {
-            "strava" => {
-                let client_id = std::env::var("STRAVA_CLIENT_ID")
-                    .or_else(|_| std::env::var("strava_client_id"))
-                    .unwrap_or_else(|_| "YOUR_STRAVA_CLIENT_ID".to_string());
-
-                let redirect_uri = std::env::var("STRAVA_REDIRECT_URI")
-                    .or_else(|_| std::env::var("strava_redirect_uri"))
-                    .unwrap_or_else(|_| {
-                        format!(
-                            "http://localhost:{}/oauth/callback/strava",
-                            crate::constants::ports::DEFAULT_HTTP_PORT
-                        )
-                    });
-
-                let scope = "read,activity:read_all";
-
-                let auth_url = format!(
-                    "https://www.strava.com/oauth/authorize?client_id={}&redirect_uri={}&response_type=code&scope={}&state={}",
-                    urlencoding::encode(&client_id),
-                    urlencoding::encode(&redirect_uri),
-                    urlencoding::encode(scope),
-                    urlencoding::encode(&state)
-                );
-
-                Ok(OAuthAuthorizationResponse {
-                    authorization_url: auth_url,
-                    state: state.clone(),
-                    instructions: "Visit the URL above to authorize access to your Strava account. You'll be redirected back after authorization.".to_string(),
-                    expires_in_minutes: 10,
-                })
-            }
-            "fitbit" => {
-                let client_id = std::env::var("FITBIT_CLIENT_ID")
-                    .or_else(|_| std::env::var("fitbit_client_id"))
-                    .unwrap_or_else(|_| "YOUR_FITBIT_CLIENT_ID".to_string());
-
-                let redirect_uri = std::env::var("FITBIT_REDIRECT_URI")
-                    .or_else(|_| std::env::var("fitbit_redirect_uri"))
-                    .unwrap_or_else(|_| {
-                        format!(
-                            "http://localhost:{}/oauth/callback/fitbit",
-                            crate::constants::ports::DEFAULT_HTTP_PORT
-                        )
-                    });
-
-                let scope = "activity%20profile";
-
-                let auth_url = format!(
-                    "https://www.fitbit.com/oauth2/authorize?client_id={}&redirect_uri={}&response_type=code&scope={}&state={}",
-                    urlencoding::encode(&client_id),
-                    urlencoding::encode(&redirect_uri),
-                    scope,
-                    urlencoding::encode(&state)
-                );
-
-                Ok(OAuthAuthorizationResponse {
-                    authorization_url: auth_url,
-                    state: state.clone(),
-                    instructions: "Visit the URL above to authorize access to your Fitbit account. You'll be redirected back after authorization.".to_string(),
-                    expires_in_minutes: 10,
-                })
-            }
-            _ => Err(anyhow::anyhow!("Unsupported provider: {}", provider)),
-        }
+        let authorization = self
+            .oauth_provider(provider)?
+            .generate_auth_url(user_id, state)
+            .await?;
+
+        Ok(OAuthAuthorizationResponse {
+            authorization_url: authorization.authorization_url,
+            state: authorization.state,
+            instructions: authorization.instructions,
+            expires_in_minutes: i64::from(authorization.expires_in_minutes),
+        })
     }
 
     /// Store OAuth state for CSRF protection
@@ -398,232 +355,80 @@ impl OAuthRoutes {
             user_id, provider
         );
 
-        // Exchange code for tokens (implementation depends on provider)
-        match provider {
-            "strava" => {
-                let token_response = self.exchange_strava_code(code).await?;
-
-                // Store encrypted tokens in database
-                let expires_at =
-                    chrono::DateTime::<chrono::Utc>::from_timestamp(token_response.expires_at, 0)
-                        .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::hours(6));
-
-                self.database
-                    .update_strava_token(
-                        user_id,
-                        &token_response.access_token,
-                        &token_response.refresh_token,
-                        expires_at,
-                        token_response
-                            .scope
-                            .clone()
-                            .unwrap_or_else(|| "read,activity:read_all".to_string()),
-                    )
-                    .await?;
-
-                info!("Strava tokens stored successfully for user: {}", user_id);
-
-                Ok(OAuthCallbackResponse {
-                    user_id: user_id.to_string(),
-                    provider: "strava".to_string(),
-                    expires_at: expires_at.to_rfc3339(),
-                    scopes: token_response
-                        .scope
-                        .unwrap_or_else(|| "read,activity:read_all".to_string()),
-                })
-            }
-            "fitbit" => {
-                let token_response = self.exchange_fitbit_code(code).await?;
-
-                // Store encrypted tokens in database
-                let expires_at =
-                    chrono::Utc::now() + chrono::Duration::seconds(token_response.expires_in);
-
-                self.database
-                    .update_fitbit_token(
-                        user_id,
-                        &token_response.access_token,
-                        &token_response.refresh_token,
-                        expires_at,
-                        token_response.scope.clone(),
-                    )
-                    .await?;
-
-                info!("Fitbit tokens stored successfully for user: {}", user_id);
-
-                Ok(OAuthCallbackResponse {
-                    user_id: user_id.to_string(),
-                    provider: "fitbit".to_string(),
-                    expires_at: expires_at.to_rfc3339(),
-                    scopes: token_response.scope,
-                })
-            }
-            _ => Err(anyhow::anyhow!("Unsupported provider: {}", provider)),
-        }
-    }
-
-    /// Exchange Strava authorization code for tokens
-    async fn exchange_strava_code(&self, code: &str) -> Result<StravaTokenResponse> {
-        let client_id = std::env::var("STRAVA_CLIENT_ID")
-            .or_else(|_| std::env::var("strava_client_id"))
-            .unwrap_or_else(|_| "163846".to_string()); // Default for testing
-
-        let client_secret = std::env::var("STRAVA_CLIENT_SECRET")
-            .or_else(|_| std::env::var("strava_client_secret"))
-            .unwrap_or_else(|_| "1dfc45ad0a1f6983b835e4495aa9473d111d03bc".to_string()); // Default for testing
-
-        let params = [
-            ("client_id", client_id.as_str()),
-            ("client_secret", client_secret.as_str()),
-            ("code", code),
-            ("grant_type", "authorization_code"),
-        ];
-
-        let client = reqwest::Client::new();
-        let response = client
-            .post("https://www.strava.com/oauth/token")
-            .form(&params)
-            .send()
+        let oauth_provider = self.oauth_provider(provider)?;
+        let token = oauth_provider.exchange_code(code, state).await?;
+
+        self.database
+            .upsert_provider_token(
+                user_id,
+                oauth_provider.name(),
+                &DecryptedToken {
+                    access_token: token.access_token,
+                    refresh_token: token.refresh_token,
+                    expires_at: token.expires_at,
+                    scope: token.scopes.clone(),
+                },
+                token.provider_user_id.as_deref(),
+            )
             .await?;
 
-        let status = response.status();
-        let response_text = response.text().await?;
-
         info!(
-            "Strava token exchange response - Status: {}, Body: {}",
-            status, response_text
+            "{} tokens stored successfully for user: {}",
+            oauth_provider.name(),
+            user_id
         );
 
-        if !status.is_success() {
-            return Err(anyhow::anyhow!(
-                "Strava token exchange failed: {}",
-                response_text
-            ));
-        }
-
-        let token_response: StravaTokenResponse =
-            serde_json::from_str(&response_text).map_err(|e| {
-                anyhow::anyhow!(
-                    "Failed to parse Strava response: {}. Response was: {}",
-                    e,
-                    response_text
-                )
-            })?;
-        info!("Strava token exchange successful");
-
-        Ok(token_response)
-    }
-
-    /// Exchange Fitbit authorization code for tokens
-    async fn exchange_fitbit_code(&self, code: &str) -> Result<FitbitTokenResponse> {
-        let client_id = std::env::var("FITBIT_CLIENT_ID")
-            .or_else(|_| std::env::var("fitbit_client_id"))
-            .unwrap_or_else(|_| "YOUR_FITBIT_CLIENT_ID".to_string());
-
-        let client_secret = std::env::var("FITBIT_CLIENT_SECRET")
-            .or_else(|_| std::env::var("fitbit_client_secret"))
-            .unwrap_or_else(|_| "YOUR_FITBIT_CLIENT_SECRET".to_string());
-
-        let redirect_uri = std::env::var("FITBIT_REDIRECT_URI")
-            .or_else(|_| std::env::var("fitbit_redirect_uri"))
-            .unwrap_or_else(|_| {
-                format!(
-                    "http://localhost:{}/oauth/callback/fitbit",
-                    crate::constants::ports::DEFAULT_HTTP_PORT
-                )
-            });
-
-        let params = [
-            ("client_id", client_id.as_str()),
-            ("grant_type", "authorization_code"),
-            ("redirect_uri", redirect_uri.as_str()),
-            ("code", code),
-        ];
-
-        let auth_header =
-            general_purpose::STANDARD.encode(format!("{}:{}", client_id, client_secret));
-
-        let client = reqwest::Client::new();
-        let response = client
-            .post("https://api.fitbit.com/oauth2/token")
-            .header("Authorization", format!("Basic {}", auth_header))
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .form(&params)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            return Err(anyhow::anyhow!(
-                "Fitbit token exchange failed: {}",
-                error_text
-            ));
-        }
-
-        let token_response: FitbitTokenResponse = response.json().await?;
-        info!("Fitbit token exchange successful");
-
-        Ok(token_response)
+        Ok(OAuthCallbackResponse {
+            user_id: user_id.to_string(),
+            provider: oauth_provider.name().to_string(),
+            expires_at: token.expires_at.to_rfc3339(),
+            scopes: token.scopes,
+        })
     }
 
     /// Get connection status for all providers for a user
     pub async fn get_connection_status(&self, user_id: Uuid) -> Result<Vec<ConnectionStatus>> {
-        let mut statuses = Vec::new();
-
-        // Check Strava connection
-        if let Ok(Some(strava_token)) = self.database.get_strava_token(user_id).await {
-            statuses.push(ConnectionStatus {
-                provider: "strava".to_string(),
-                connected: true,
-                expires_at: Some(strava_token.expires_at.to_rfc3339()),
-                scopes: Some(strava_token.scope),
-            });
-        } else {
-            statuses.push(ConnectionStatus {
-                provider: "strava".to_string(),
-                connected: false,
-                expires_at: None,
-                scopes: None,
-            });
-        }
-
-        // Check Fitbit connection
-        if let Ok(Some(fitbit_token)) = self.database.get_fitbit_token(user_id).await {
-            statuses.push(ConnectionStatus {
-                provider: "fitbit".to_string(),
-                connected: true,
-                expires_at: Some(fitbit_token.expires_at.to_rfc3339()),
-                scopes: Some(fitbit_token.scope),
-            });
-        } else {
-            statuses.push(ConnectionStatus {
-                provider: "fitbit".to_string(),
-                connected: false,
-                expires_at: None,
-                scopes: None,
-            });
-        }
-
-        Ok(statuses)
+        let connections = self.database.list_provider_connections(user_id).await?;
+
+        Ok(crate::providers::registry()
+            .provider_names()
+            .into_iter()
+            .map(
+                |provider| match connections.iter().find(|c| c.provider == provider) {
+                    Some(connection) => ConnectionStatus {
+                        provider: provider.to_string(),
+                        connected: true,
+                        status: connection.status().to_string(),
+                        needs_reauth: connection.needs_reauth,
+                        reauth_reason: connection.reauth_reason.clone(),
+                        expires_at: Some(connection.expires_at.to_rfc3339()),
+                        scopes: Some(connection.scope.clone()),
+                    },
+                    None => ConnectionStatus {
+                        provider: provider.to_string(),
+                        connected: false,
+                        status: "not_connected".to_string(),
+                        needs_reauth: false,
+                        reauth_reason: None,
+                        expires_at: None,
+                        scopes: None,
+                    },
+                },
+            )
+            .collect())
     }
 
     /// Disconnect a provider by removing stored tokens
     pub async fn disconnect_provider(&self, user_id: Uuid, provider: &str) -> Result<()> {
-        match provider {
-            "strava" => {
-                // Token revocation would clear stored tokens from database
-                // For now, we'd need to add a method to clear specific provider tokens
-                info!("Disconnecting Strava for user {}", user_id);
-                // self.database.clear_strava_token(user_id).await?;
-                Ok(())
-            }
-            "fitbit" => {
-                info!("Disconnecting Fitbit for user {}", user_id);
-                // self.database.clear_fitbit_token(user_id).await?;
-                Ok(())
-            }
-            _ => Err(anyhow::anyhow!("Unsupported provider: {}", provider)),
+        if !crate::providers::registry().is_supported(provider) {
+            return Err(anyhow::anyhow!("Unsupported provider: {}", provider));
         }
+
+        info!("Disconnecting {} for user {}", provider, user_id);
+        self.database
+            .delete_provider_connection(user_id, provider)
+            .await?;
+        Ok(())
     }
 }
 
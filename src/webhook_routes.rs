@@ -0,0 +1,337 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! # Provider Webhook Routes
+//!
+//! Receives Strava's push subscription events so activities are synced as
+//! soon as they change rather than only when a tool is called. Handles the
+//! subscription verification handshake, activity create/update/delete events
+//! and athlete deauthorization.
+//!
+//! The event callback carries no signature, so events are only accepted when
+//! they name the configured subscription id, and a deauthorization is only
+//! acted on once Strava itself refuses the stored grant.
+
+use crate::config::environment::ServerConfig;
+use crate::constants::env_config;
+use crate::database_plugins::{factory::Database, DatabaseProvider};
+use crate::oauth::manager::OAuthManager;
+use crate::oauth::providers::StravaOAuthProvider;
+use crate::oauth::OAuthError;
+use crate::providers::rate_budget::RequestContext;
+use crate::providers::FitnessProvider;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::info;
+use uuid::Uuid;
+
+const STRAVA: &str = "strava";
+
+/// Events processed at once; further events are refused so Strava redelivers them
+const MAX_CONCURRENT_EVENTS: usize = 4;
+
+/// Query parameters Strava sends when verifying a webhook subscription
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubscriptionVerification {
+    #[serde(rename = "hub.mode")]
+    pub mode: String,
+    #[serde(rename = "hub.challenge")]
+    pub challenge: String,
+    #[serde(rename = "hub.verify_token")]
+    pub verify_token: String,
+}
+
+/// Response echoing the challenge back to Strava
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriptionChallenge {
+    #[serde(rename = "hub.challenge")]
+    pub challenge: String,
+}
+
+/// Event pushed by Strava to the subscription callback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StravaWebhookEvent {
+    /// "activity" or "athlete"
+    pub object_type: String,
+    /// Activity id, or athlete id for athlete events
+    pub object_id: i64,
+    /// "create", "update" or "delete"
+    pub aspect_type: String,
+    /// Athlete owning the object
+    pub owner_id: i64,
+    pub subscription_id: i64,
+    /// Unix timestamp of the event
+    pub event_time: i64,
+    /// Changed fields, e.g. `{"title": "..."}` or `{"authorized": "false"}`
+    #[serde(default)]
+    pub updates: HashMap<String, Value>,
+}
+
+impl StravaWebhookEvent {
+    /// Whether this event reports the athlete revoking access
+    pub fn is_deauthorization(&self) -> bool {
+        self.object_type == "athlete"
+            && self
+                .updates
+                .get("authorized")
+                .is_some_and(|value| value == "false" || value == false)
+    }
+}
+
+/// What processing a webhook event did
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum WebhookOutcome {
+    ActivitySynced { user_id: Uuid, activity_id: String },
+    ActivityDeleted { user_id: Uuid, activity_id: String },
+    Deauthorized { user_id: Uuid },
+    Ignored { reason: String },
+}
+
+/// Routes for provider webhook subscriptions
+#[derive(Clone)]
+pub struct WebhookRoutes {
+    database: Arc<Database>,
+    config: Arc<ServerConfig>,
+    subscription_id: Option<i64>,
+    event_permits: Arc<Semaphore>,
+    /// Activities being fetched, flagged when another event arrived meanwhile
+    activities_in_flight: Arc<Mutex<HashMap<(Uuid, String), bool>>>,
+}
+
+impl WebhookRoutes {
+    pub fn new(database: Arc<Database>, config: Arc<ServerConfig>) -> Self {
+        Self {
+            database,
+            config,
+            subscription_id: env_config::strava_webhook_subscription_id(),
+            event_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_EVENTS)),
+            activities_in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Override the subscription id events must carry
+    pub fn with_subscription_id(mut self, subscription_id: Option<i64>) -> Self {
+        self.subscription_id = subscription_id;
+        self
+    }
+
+    /// Check that an event was sent for our push subscription
+    pub fn authenticate_event(&self, event: &StravaWebhookEvent) -> Result<()> {
+        let expected = self
+            .subscription_id
+            .ok_or_else(|| anyhow::anyhow!("Strava webhook subscription id is not configured"))?;
+        if event.subscription_id != expected {
+            return Err(anyhow::anyhow!(
+                "Event is for unknown subscription {}",
+                event.subscription_id
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reserve a slot to process an event, or `None` when the server is saturated
+    pub fn try_admit_event(&self) -> Option<OwnedSemaphorePermit> {
+        self.event_permits.clone().try_acquire_owned().ok()
+    }
+
+    /// Answer Strava's subscription verification handshake
+    pub fn verify_subscription(
+        &self,
+        params: &SubscriptionVerification,
+    ) -> Result<SubscriptionChallenge> {
+        let expected = env_config::strava_webhook_verify_token()
+            .ok_or_else(|| anyhow::anyhow!("Strava webhook verify token is not configured"))?;
+
+        if params.mode != "subscribe" {
+            return Err(anyhow::anyhow!("Unsupported hub.mode: {}", params.mode));
+        }
+        if params.verify_token != expected {
+            return Err(anyhow::anyhow!("Invalid webhook verify token"));
+        }
+
+        Ok(SubscriptionChallenge {
+            challenge: params.challenge.clone(),
+        })
+    }
+
+    /// Apply a Strava webhook event to local storage
+    pub async fn handle_strava_event(&self, event: StravaWebhookEvent) -> Result<WebhookOutcome> {
+        self.authenticate_event(&event)?;
+
+        let Some(connection) = self
+            .database
+            .get_provider_connection_by_provider_user_id(STRAVA, &event.owner_id.to_string())
+            .await?
+        else {
+            return Ok(WebhookOutcome::Ignored {
+                reason: format!("No user connected for Strava athlete {}", event.owner_id),
+            });
+        };
+        let user_id = connection.user_id;
+
+        match (event.object_type.as_str(), event.aspect_type.as_str()) {
+            ("activity", "create" | "update") => {
+                self.sync_activity(user_id, &event.object_id.to_string())
+                    .await
+            }
+            ("activity", "delete") => {
+                let activity_id = event.object_id.to_string();
+                // Only trust the event once Strava no longer serves the activity
+                if self.activity_exists(user_id, &activity_id).await? {
+                    return Ok(WebhookOutcome::Ignored {
+                        reason: format!(
+                            "Strava still serves activity {} for user {}, deletion not confirmed",
+                            activity_id, user_id
+                        ),
+                    });
+                }
+                self.database
+                    .mark_synced_activity_deleted(user_id, STRAVA, &activity_id)
+                    .await?;
+                info!(
+                    "Tombstoned Strava activity {} for user {}",
+                    activity_id, user_id
+                );
+                Ok(WebhookOutcome::ActivityDeleted {
+                    user_id,
+                    activity_id,
+                })
+            }
+            ("athlete", _) if event.is_deauthorization() => {
+                let oauth_manager = self.oauth_manager()?;
+                // Only trust the event once Strava refuses the stored grant too
+                match oauth_manager.refresh_connection(user_id, STRAVA).await {
+                    Ok(_) => {
+                        return Ok(WebhookOutcome::Ignored {
+                            reason: format!(
+                                "Strava still honours the grant for user {}, deauthorization not confirmed",
+                                user_id
+                            ),
+                        })
+                    }
+                    Err(OAuthError::ReauthorizationRequired(_)) => {}
+                    Err(e) => return Err(e.into()),
+                }
+                oauth_manager.disconnect_provider(user_id, STRAVA).await?;
+                info!("Strava deauthorized by athlete for user {}", user_id);
+                Ok(WebhookOutcome::Deauthorized { user_id })
+            }
+            (object_type, aspect_type) => Ok(WebhookOutcome::Ignored {
+                reason: format!("Unhandled {} {} event", object_type, aspect_type),
+            }),
+        }
+    }
+
+    /// Fetch a single activity and store it locally
+    async fn sync_activity(&self, user_id: Uuid, activity_id: &str) -> Result<WebhookOutcome> {
+        let key = (user_id, activity_id.to_string());
+        {
+            let mut in_flight = self.lock_in_flight();
+            if let Some(dirty) = in_flight.get_mut(&key) {
+                // The running fetch may have read the activity before this edit,
+                // so it fetches once more when it finishes
+                *dirty = true;
+                return Ok(WebhookOutcome::Ignored {
+                    reason: format!(
+                        "Strava activity {} is already being synced, re-fetch queued",
+                        activity_id
+                    ),
+                });
+            }
+            in_flight.insert(key.clone(), false);
+        }
+
+        loop {
+            let result = self.fetch_activity(user_id, activity_id).await;
+            let mut in_flight = self.lock_in_flight();
+            if in_flight.get(&key) == Some(&true) {
+                in_flight.insert(key.clone(), false);
+                continue;
+            }
+            in_flight.remove(&key);
+            return result;
+        }
+    }
+
+    fn lock_in_flight(&self) -> std::sync::MutexGuard<'_, HashMap<(Uuid, String), bool>> {
+        self.activities_in_flight
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    async fn fetch_activity(&self, user_id: Uuid, activity_id: &str) -> Result<WebhookOutcome> {
+        let activity = self
+            .owner_provider(user_id)
+            .await?
+            .get_activity(activity_id)
+            .await?;
+        self.database
+            .upsert_synced_activity(user_id, &activity)
+            .await?;
+
+        info!(
+            "Synced Strava activity {} for user {}",
+            activity_id, user_id
+        );
+        Ok(WebhookOutcome::ActivitySynced {
+            user_id,
+            activity_id: activity.id,
+        })
+    }
+
+    /// Whether Strava still returns the activity to its owner
+    async fn activity_exists(&self, user_id: Uuid, activity_id: &str) -> Result<bool> {
+        match self
+            .owner_provider(user_id)
+            .await?
+            .get_activity(activity_id)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e)
+                if e.downcast_ref::<reqwest::Error>()
+                    .and_then(reqwest::Error::status)
+                    == Some(reqwest::StatusCode::NOT_FOUND) =>
+            {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Strava client authenticated with the user's own token
+    async fn owner_provider(&self, user_id: Uuid) -> Result<Box<dyn FitnessProvider>> {
+        let token = self
+            .oauth_manager()?
+            .ensure_valid_token(user_id, STRAVA)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No Strava token stored for user {}", user_id))?;
+
+        let mut provider = crate::providers::registry()
+            .create_authenticated(
+                STRAVA,
+                &self.config.external_services,
+                &token.access_token,
+                &token.refresh_token,
+            )
+            .await?;
+        provider.set_request_context(RequestContext::background(user_id));
+        Ok(provider)
+    }
+
+    fn oauth_manager(&self) -> Result<OAuthManager> {
+        let mut oauth_manager = OAuthManager::new(self.database.clone());
+        oauth_manager.register_provider(Box::new(StravaOAuthProvider::from_config(
+            &self.config.oauth.strava,
+        )?));
+        Ok(oauth_manager)
+    }
+}
@@ -43,6 +43,12 @@ pub enum WebSocketMessage {
         total_requests_this_month: u64,
         active_connections: usize,
     },
+    #[serde(rename = "provider_connection")]
+    ProviderConnection {
+        provider: String,
+        status: String,
+        message: Option<String>,
+    },
     #[serde(rename = "error")]
     Error { message: String },
     #[serde(rename = "success")]
@@ -222,6 +228,24 @@ impl WebSocketManager {
             .await;
     }
 
+    /// Notify a user's `connections` subscribers that a provider connection changed
+    pub async fn broadcast_connection_update(
+        &self,
+        user_id: &Uuid,
+        provider: &str,
+        status: &str,
+        message: Option<String>,
+    ) {
+        let message = WebSocketMessage::ProviderConnection {
+            provider: provider.to_string(),
+            status: status.to_string(),
+            message,
+        };
+
+        self.send_to_user_subscribers(user_id, &message, "connections")
+            .await;
+    }
+
     /// Broadcast system statistics
     pub async fn broadcast_system_stats(&self) -> Result<()> {
         let stats = self.get_system_stats().await?;
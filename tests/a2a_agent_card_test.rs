@@ -0,0 +1,82 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Agent Card Tests
+//!
+//! Checks the card served by the A2A routes is generated from the live
+//! tool set and carries a verifiable signature when a key is configured.
+
+use anyhow::Result;
+use pierre_mcp_server::a2a::agent_card::AgentCardSigner;
+use pierre_mcp_server::a2a::remote::RemoteAgentCard;
+use pierre_mcp_server::a2a_routes::A2ARoutes;
+use pierre_mcp_server::auth::AuthManager;
+use pierre_mcp_server::config::environment::ServerConfig;
+use pierre_mcp_server::database_plugins::factory::Database;
+use std::sync::Arc;
+use url::Url;
+
+async fn create_routes(config: ServerConfig) -> Result<A2ARoutes> {
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let auth_manager = Arc::new(AuthManager::new(vec![0u8; 64], 24));
+    Ok(A2ARoutes::new(database, auth_manager, Arc::new(config)))
+}
+
+#[tokio::test]
+async fn test_served_card_matches_tool_set() -> Result<()> {
+    let mut config = ServerConfig::from_env()?;
+    config.a2a.public_url = Some("https://pierre.example.com".to_string());
+    config.a2a.agent_card_signing_key_path = None;
+    let routes = create_routes(config).await?;
+
+    let card = routes.get_agent_card().await?;
+    let tool_names: Vec<String> = pierre_mcp_server::mcp::schema::get_tools()
+        .into_iter()
+        .map(|tool| tool.name)
+        .collect();
+    let card_tool_names: Vec<String> = card.tools.iter().map(|t| t.name.clone()).collect();
+    assert_eq!(card_tool_names, tool_names);
+
+    assert_eq!(
+        card.url.as_deref(),
+        Some("https://pierre.example.com/a2a/execute")
+    );
+    assert!(card.signatures.is_none());
+
+    // Other agents can discover us with the same client Pierre uses outbound
+    let remote: RemoteAgentCard = serde_json::from_value(serde_json::to_value(&card)?)?;
+    let endpoint = remote.validate(&Url::parse("https://pierre.example.com/")?)?;
+    assert_eq!(endpoint.as_str(), "https://pierre.example.com/a2a/execute");
+    assert!(remote
+        .capability_names()
+        .contains(&"activity-intelligence".to_string()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_served_card_is_signed() -> Result<()> {
+    let key_dir = tempfile::tempdir()?;
+    let key_path = key_dir.path().join("agent_card.key");
+
+    let mut config = ServerConfig::from_env()?;
+    config.a2a.agent_card_signing_key_path = Some(key_path.clone());
+    let routes = create_routes(config).await?;
+
+    // The key is generated on first start and reused afterwards
+    assert!(key_path.exists());
+    let signer = AgentCardSigner::load_or_generate(&key_path)?;
+
+    let card = routes.get_agent_card().await?;
+    assert_eq!(card.signatures.as_ref().map(Vec::len), Some(1));
+    assert!(card.verify_signature(signer.public_key()));
+
+    let mut tampered = card.clone();
+    tampered.url = Some("https://attacker.example.com/a2a/execute".to_string());
+    assert!(!tampered.verify_signature(signer.public_key()));
+
+    Ok(())
+}
@@ -0,0 +1,294 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A2A Client Lifecycle Tests
+//!
+//! Covers deactivation, credential rotation with an overlap window,
+//! session revocation and custom quotas for registered partner agents,
+//! and that only admin services can manage them.
+
+use anyhow::Result;
+use chrono::Utc;
+use pierre_mcp_server::a2a::auth::A2AAuthenticator;
+use pierre_mcp_server::a2a::client::{
+    A2AClientManager, A2AClientQuota, A2AClientTier, ClientCredentials, ClientRegistrationRequest,
+};
+use pierre_mcp_server::a2a::A2AError;
+use pierre_mcp_server::a2a_routes::A2ARoutes;
+use pierre_mcp_server::admin_routes::{admin_routes, AdminApiContext};
+use pierre_mcp_server::auth::AuthManager;
+use pierre_mcp_server::config::environment::ServerConfig;
+use pierre_mcp_server::database_plugins::{factory::Database, DatabaseProvider};
+use pierre_mcp_server::models::{User, UserTier};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+use warp::http::StatusCode;
+
+async fn setup() -> Result<(Arc<Database>, A2AClientManager, ClientCredentials)> {
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let manager = A2AClientManager::new(database.clone());
+    let credentials = manager
+        .register_client(ClientRegistrationRequest {
+            name: "Coaching Partner".to_string(),
+            description: "Partner agent building training plans".to_string(),
+            capabilities: vec!["fitness-data-analysis".to_string()],
+            redirect_uris: vec![],
+            contact_email: "partner@example.com".to_string(),
+        })
+        .await?;
+    Ok((database, manager, credentials))
+}
+
+#[tokio::test]
+async fn test_registration_returns_working_credentials() -> Result<()> {
+    let (database, manager, credentials) = setup().await?;
+    let authenticator = A2AAuthenticator::new(database);
+
+    assert!(credentials.api_key.starts_with("pk_live_"));
+    let auth = authenticator
+        .authenticate_api_key(&credentials.api_key)
+        .await?;
+    assert!(matches!(
+        auth.auth_method,
+        pierre_mcp_server::auth::AuthMethod::ApiKey { ref tier, .. } if tier == "A2A-Trial"
+    ));
+
+    let client = manager
+        .verify_client_secret(&credentials.client_id, &credentials.client_secret)
+        .await?;
+    assert_eq!(client.name, "Coaching Partner");
+    assert!(matches!(
+        manager
+            .verify_client_secret(&credentials.client_id, "a2a_secret_wrong")
+            .await,
+        Err(A2AError::AuthenticationFailed(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rotation_keeps_previous_credentials_during_overlap() -> Result<()> {
+    let (database, manager, original) = setup().await?;
+    let authenticator = A2AAuthenticator::new(database);
+
+    let rotation = manager
+        .rotate_credentials(&original.client_id, Some(2))
+        .await?;
+    let rotated = rotation.credentials;
+    assert_ne!(rotated.api_key, original.api_key);
+    assert_ne!(rotated.client_secret, original.client_secret);
+    assert!(rotation.previous_credentials_valid_until > Utc::now());
+
+    // Both generations work while the overlap window is open
+    for credentials in [&original, &rotated] {
+        authenticator
+            .authenticate_api_key(&credentials.api_key)
+            .await?;
+        manager
+            .verify_client_secret(&credentials.client_id, &credentials.client_secret)
+            .await?;
+    }
+
+    // Rotating again without overlap retires everything issued before
+    let latest = manager
+        .rotate_credentials(&original.client_id, Some(0))
+        .await?
+        .credentials;
+    for retired in [&original, &rotated] {
+        assert!(authenticator
+            .authenticate_api_key(&retired.api_key)
+            .await
+            .is_err());
+        assert!(manager
+            .verify_client_secret(&retired.client_id, &retired.client_secret)
+            .await
+            .is_err());
+    }
+    authenticator.authenticate_api_key(&latest.api_key).await?;
+
+    assert!(matches!(
+        manager
+            .rotate_credentials(&original.client_id, Some(10_000))
+            .await,
+        Err(A2AError::InvalidRequest(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deactivation_blocks_keys_and_revokes_sessions() -> Result<()> {
+    let (database, manager, credentials) = setup().await?;
+    let authenticator = A2AAuthenticator::new(database);
+
+    manager.create_session(&credentials.client_id, None).await?;
+    assert_eq!(
+        manager
+            .get_active_sessions(&credentials.client_id)
+            .await?
+            .len(),
+        1
+    );
+
+    manager.deactivate_client(&credentials.client_id).await?;
+    let client = manager
+        .get_client(&credentials.client_id)
+        .await?
+        .expect("client exists");
+    assert!(!client.is_active);
+    assert!(manager
+        .get_active_sessions(&credentials.client_id)
+        .await?
+        .is_empty());
+    assert!(authenticator
+        .authenticate_api_key(&credentials.api_key)
+        .await
+        .is_err());
+    assert!(matches!(
+        manager
+            .verify_client_secret(&credentials.client_id, &credentials.client_secret)
+            .await,
+        Err(A2AError::AuthenticationFailed(_))
+    ));
+
+    manager.reactivate_client(&credentials.client_id).await?;
+    authenticator
+        .authenticate_api_key(&credentials.api_key)
+        .await?;
+
+    assert!(matches!(
+        manager.deactivate_client("a2a_client_missing").await,
+        Err(A2AError::ClientNotRegistered(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_custom_quota_enforced_on_authentication() -> Result<()> {
+    let (database, manager, credentials) = setup().await?;
+    let authenticator = A2AAuthenticator::new(database);
+
+    let quota = manager
+        .update_client_quota(
+            &credentials.client_id,
+            A2AClientQuota {
+                tier: A2AClientTier::Enterprise,
+                monthly_limit: Some(2),
+            },
+        )
+        .await?;
+    assert_eq!(quota.effective_monthly_limit(), Some(2));
+
+    for _ in 0..2 {
+        authenticator
+            .authenticate_api_key(&credentials.api_key)
+            .await?;
+        manager
+            .record_usage(&credentials.client_id, "get_activities", true)
+            .await?;
+    }
+
+    let error = authenticator
+        .authenticate_api_key(&credentials.api_key)
+        .await
+        .expect_err("quota exhausted");
+    assert!(error.to_string().contains("rate limit exceeded"));
+
+    // Clearing the override falls back to the unlimited enterprise tier
+    manager
+        .update_client_quota(
+            &credentials.client_id,
+            A2AClientQuota {
+                tier: A2AClientTier::Enterprise,
+                monthly_limit: None,
+            },
+        )
+        .await?;
+    authenticator
+        .authenticate_api_key(&credentials.api_key)
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sessions_issued_and_user_jwt_cannot_manage_clients() -> Result<()> {
+    let (database, manager, credentials) = setup().await?;
+    let auth_manager = Arc::new(AuthManager::new(vec![0u8; 64], 24));
+    let routes = A2ARoutes::new(
+        database.clone(),
+        auth_manager.clone(),
+        Arc::new(ServerConfig::from_env()?),
+    );
+
+    // Client credentials are exchanged for a session
+    let session = routes
+        .authenticate(json!({
+            "client_id": credentials.client_id,
+            "client_secret": credentials.client_secret
+        }))
+        .await?;
+    assert_eq!(session["status"], "authenticated");
+
+    // A signed-in user who doesn't administer the server can't touch the partner
+    let user = User {
+        id: Uuid::new_v4(),
+        email: "athlete@example.com".to_string(),
+        display_name: None,
+        password_hash: "test_hash".to_string(),
+        tier: UserTier::Starter,
+        is_active: true,
+        created_at: Utc::now(),
+        last_active: Utc::now(),
+    };
+    database.create_user(&user).await?;
+    let user_jwt = auth_manager.generate_token(&user)?;
+
+    let admin = admin_routes(AdminApiContext::new(
+        (*database).clone(),
+        "admin-jwt-secret",
+        (*auth_manager).clone(),
+    ));
+    let client_path = format!("/admin/a2a-clients/{}", credentials.client_id);
+    for (method, path) in [
+        ("POST", format!("{}/deactivate", client_path)),
+        ("POST", format!("{}/rotate-credentials", client_path)),
+        ("GET", format!("{}/sessions", client_path)),
+        ("PUT", format!("{}/quota", client_path)),
+    ] {
+        let response = warp::test::request()
+            .method(method)
+            .path(&path)
+            .header("authorization", format!("Bearer {}", user_jwt))
+            .json(&json!({"overlap_hours": 1, "tier": "Enterprise"}))
+            .reply(&admin)
+            .await;
+        assert_eq!(
+            response.status(),
+            StatusCode::UNAUTHORIZED,
+            "{} {}",
+            method,
+            path
+        );
+    }
+
+    assert!(manager
+        .get_client(&credentials.client_id)
+        .await?
+        .is_some_and(|c| c.is_active));
+    assert_eq!(
+        manager
+            .get_active_sessions(&credentials.client_id)
+            .await?
+            .len(),
+        1
+    );
+
+    Ok(())
+}
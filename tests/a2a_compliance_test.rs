@@ -10,7 +10,15 @@
 //! Google A2A specification at https://github.com/google-a2a/A2A
 
 use pierre_mcp_server::a2a::protocol::{A2ARequest, A2AServer};
+use pierre_mcp_server::a2a::AgentCard;
+use pierre_mcp_server::config::environment::ServerConfig;
+use pierre_mcp_server::database_plugins::factory::Database;
+use pierre_mcp_server::protocols::universal::UniversalToolExecutor;
 use serde_json::json;
+use std::sync::Arc;
+
+mod common;
+use common::test_intelligence;
 
 #[tokio::test]
 async fn test_jsonrpc_2_0_compliance() {
@@ -155,7 +163,12 @@ async fn test_task_management_compliance() {
 
 #[tokio::test]
 async fn test_tools_schema_compliance() {
-    let server = A2AServer::new();
+    let database = Arc::new(
+        Database::new("sqlite::memory:", vec![0u8; 32])
+            .await
+            .unwrap(),
+    );
+    let server = A2AServer::new_with_dependencies(database.clone(), test_intelligence());
 
     // Test tools list returns proper schema
     let request = A2ARequest {
@@ -172,11 +185,23 @@ async fn test_tools_schema_compliance() {
     assert!(tools.is_array());
 
     // Verify each tool has required schema
-    for tool in tools.as_array().unwrap() {
+    let tools = tools.as_array().unwrap();
+    assert!(!tools.is_empty());
+    for tool in tools {
         assert!(tool["name"].is_string());
         assert!(tool["description"].is_string());
         assert!(tool["parameters"].is_object());
+        assert!(tool["output_schema"]["properties"].is_object());
     }
+
+    // Same tools as the card served for an executor over the same dependencies
+    let config = ServerConfig::from_env().unwrap();
+    let executor =
+        UniversalToolExecutor::new(database, test_intelligence(), Arc::new(config.clone()));
+    let card = AgentCard::for_server(&executor, &config);
+    let listed: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+    let advertised: Vec<&str> = card.tools.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(listed, advertised);
 }
 
 #[tokio::test]
@@ -208,16 +233,18 @@ async fn test_authentication_scheme_support() {
     // Verify supported authentication schemes match A2A spec
     let auth_schemes = &agent_card.authentication.schemes;
 
-    // Should support at least api-key and oauth2
+    // Should support api-key plus JWT bearer tokens issued by /auth/login
     assert!(auth_schemes.contains(&"api-key".to_string()));
-    assert!(auth_schemes.contains(&"oauth2".to_string()));
-
-    // Verify OAuth2 configuration is present
-    assert!(agent_card.authentication.oauth2.is_some());
-    let oauth2 = agent_card.authentication.oauth2.unwrap();
-    assert!(!oauth2.authorization_url.is_empty());
-    assert!(!oauth2.token_url.is_empty());
-    assert!(!oauth2.scopes.is_empty());
+    assert!(auth_schemes.contains(&"jwt".to_string()));
+
+    // Only schemes the A2A endpoints accept are advertised
+    assert!(!auth_schemes.contains(&"oauth2".to_string()));
+    assert!(agent_card.authentication.oauth2.is_none());
+
+    // Verify JWT configuration is present
+    let jwt = agent_card.authentication.jwt.clone().unwrap();
+    assert!(!jwt.header_name.is_empty());
+    assert!(jwt.token_url.ends_with("/auth/login"));
 
     // Verify API key configuration
     assert!(agent_card.authentication.api_key.is_some());
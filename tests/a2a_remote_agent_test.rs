@@ -0,0 +1,316 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Remote Agent Delegation Tests
+//!
+//! Runs a local mock A2A agent and checks discovery, trust registration
+//! and delegation of tasks through the outbound client. The mock listens
+//! on loopback, so these registries allow private networks; the default
+//! policy is checked against the same agent separately.
+
+use anyhow::Result;
+use pierre_mcp_server::a2a::remote::{
+    AgentOwner, RemoteAgentPolicy, RemoteAgentRegistrationRequest, RemoteAgentRegistry,
+};
+use pierre_mcp_server::a2a::A2AError;
+use pierre_mcp_server::database_plugins::{factory::Database, DatabaseProvider};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use warp::Filter;
+
+mod common;
+use common::{create_user, database_with_user};
+
+const AGENT_TOKEN: &str = "nutrition-agent-secret";
+
+/// Start a mock nutrition agent and return its base URL
+fn spawn_mock_agent(card_url: Option<&'static str>) -> String {
+    let card = warp::path!(".well-known" / "agent.json")
+        .and(warp::get())
+        .map(move || {
+            let mut card = json!({
+                "name": "Nutrition Agent",
+                "description": "Builds meal plans from training plans",
+                "version": "1.0.0",
+                "capabilities": ["meal-planning", "macro-tracking"]
+            });
+            if let Some(url) = card_url {
+                card["url"] = json!(url);
+            }
+            warp::reply::json(&card)
+        });
+
+    let rpc = warp::path("a2a")
+        .and(warp::post())
+        .and(warp::header::optional::<String>("authorization"))
+        .and(warp::body::json())
+        .map(|auth: Option<String>, request: Value| {
+            let id = request["id"].clone();
+            if auth.as_deref() != Some(&format!("Bearer {}", AGENT_TOKEN)) {
+                return warp::reply::json(&json!({
+                    "jsonrpc": "2.0",
+                    "error": {"code": -32001, "message": "Unauthorized"},
+                    "id": id
+                }));
+            }
+
+            let result = match request["method"].as_str().unwrap_or("") {
+                "message/send" => json!({
+                    "id": "task-42",
+                    "status": "pending",
+                    "received_parts": request["params"]["message"]["parts"]
+                }),
+                "tasks/get" => json!({
+                    "id": request["params"]["task_id"],
+                    "status": "completed",
+                    "result": {"meals": 21}
+                }),
+                "tasks/cancel" => json!({
+                    "id": request["params"]["task_id"],
+                    "status": "cancelled"
+                }),
+                _ => Value::Null,
+            };
+
+            warp::reply::json(&json!({"jsonrpc": "2.0", "result": result, "id": id}))
+        });
+
+    let (addr, server): (SocketAddr, _) =
+        warp::serve(card.or(rpc)).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+
+    format!("http://{}", addr)
+}
+
+/// A registry that may reach the mock agent on loopback
+fn local_registry(database: Arc<Database>) -> RemoteAgentRegistry {
+    RemoteAgentRegistry::with_policy(
+        database,
+        RemoteAgentPolicy {
+            allow_private_networks: true,
+        },
+    )
+}
+
+fn registration(base_url: &str) -> RemoteAgentRegistrationRequest {
+    RemoteAgentRegistrationRequest {
+        base_url: base_url.to_string(),
+        auth_token: Some(AGENT_TOKEN.to_string()),
+    }
+}
+
+#[tokio::test]
+async fn test_register_list_and_remove_remote_agent() -> Result<()> {
+    let (database, user) = database_with_user("athlete@example.com").await?;
+    let user_id = user.id;
+    let registry = local_registry(database);
+    let base_url = spawn_mock_agent(None);
+
+    let agent = registry
+        .register_user_agent(user_id, registration(&base_url))
+        .await?;
+    assert_eq!(agent.name, "Nutrition Agent");
+    assert_eq!(agent.endpoint_url, format!("{}/a2a", base_url));
+    assert_eq!(agent.capabilities, vec!["meal-planning", "macro-tracking"]);
+
+    // Registering the same agent again refreshes it instead of duplicating
+    let again = registry
+        .register_user_agent(user_id, registration(&base_url))
+        .await?;
+    assert_eq!(again.id, agent.id);
+
+    let agents = registry.agents_for_user(user_id).await?;
+    assert_eq!(agents.len(), 1);
+    assert_eq!(agents[0].auth_token.as_deref(), Some(AGENT_TOKEN));
+
+    // The credential never leaves the server in serialized form
+    let serialized = serde_json::to_value(&agents[0])?;
+    assert!(serialized.get("auth_token").is_none());
+
+    registry.remove_user_agent(user_id, &agent.id).await?;
+    assert!(registry.agents_for_user(user_id).await?.is_empty());
+    assert!(matches!(
+        registry.remove_user_agent(user_id, &agent.id).await,
+        Err(A2AError::RemoteAgentNotFound(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_agent_card_with_foreign_endpoint_rejected() -> Result<()> {
+    let (database, user) = database_with_user("athlete@example.com").await?;
+    let user_id = user.id;
+    let registry = local_registry(database);
+    let base_url = spawn_mock_agent(Some("https://collector.example.com/a2a"));
+
+    let result = registry
+        .register_user_agent(user_id, registration(&base_url))
+        .await;
+    assert!(matches!(result, Err(A2AError::RemoteAgentError(_))));
+    assert!(registry.agents_for_user(user_id).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delegate_and_follow_task() -> Result<()> {
+    let (database, user) = database_with_user("athlete@example.com").await?;
+    let user_id = user.id;
+    let registry = local_registry(database);
+    let base_url = spawn_mock_agent(None);
+    let agent = registry
+        .register_user_agent(user_id, registration(&base_url))
+        .await?;
+
+    let plan = json!({"week": 1, "sessions": ["easy 8k", "tempo 10k", "long 20k"]});
+    let task = registry
+        .delegate(
+            user_id,
+            &agent.id,
+            "Build a meal plan for this week",
+            Some(plan.clone()),
+        )
+        .await?;
+    assert_eq!(task["id"], "task-42");
+    assert_eq!(task["received_parts"][0]["type"], "text");
+    assert_eq!(task["received_parts"][1]["content"], plan);
+
+    let status = registry.get_task(user_id, &agent.id, "task-42").await?;
+    assert_eq!(status["status"], "completed");
+
+    let cancelled = registry.cancel_task(user_id, &agent.id, "task-42").await?;
+    assert_eq!(cancelled["status"], "cancelled");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remote_agent_errors_surface() -> Result<()> {
+    let (database, user) = database_with_user("athlete@example.com").await?;
+    let user_id = user.id;
+    let registry = local_registry(database);
+    let base_url = spawn_mock_agent(None);
+
+    // Without the shared secret the remote agent rejects the call
+    let agent = registry
+        .register_user_agent(
+            user_id,
+            RemoteAgentRegistrationRequest {
+                base_url,
+                auth_token: None,
+            },
+        )
+        .await?;
+    let result = registry.delegate(user_id, &agent.id, "hello", None).await;
+    assert!(matches!(result, Err(A2AError::RemoteAgentError(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remote_agents_isolated_per_user() -> Result<()> {
+    let (database, user) = database_with_user("athlete@example.com").await?;
+    let user_id = user.id;
+    let other_user = create_user(&database, "other@example.com").await?.id;
+    let registry = local_registry(database);
+    let base_url = spawn_mock_agent(None);
+
+    let agent = registry
+        .register_user_agent(user_id, registration(&base_url))
+        .await?;
+
+    assert!(registry.agents_for_user(other_user).await?.is_empty());
+    assert!(matches!(
+        registry
+            .delegate(other_user, &agent.id, "hello", None)
+            .await,
+        Err(A2AError::RemoteAgentNotFound(_))
+    ));
+    assert!(matches!(
+        registry.remove_user_agent(other_user, &agent.id).await,
+        Err(A2AError::RemoteAgentNotFound(_))
+    ));
+    assert_eq!(registry.agents_for_user(user_id).await?.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_default_policy_refuses_private_agents() -> Result<()> {
+    let (database, user) = database_with_user("athlete@example.com").await?;
+    let user_id = user.id;
+    let registry = RemoteAgentRegistry::with_policy(database, RemoteAgentPolicy::default());
+    let base_url = spawn_mock_agent(None);
+    let https_loopback = base_url.replacen("http://", "https://", 1);
+
+    for url in [
+        base_url.as_str(),
+        https_loopback.as_str(),
+        "https://169.254.169.254/latest/meta-data/",
+        "https://10.0.0.8/",
+    ] {
+        let result = registry
+            .register_user_agent(user_id, registration(url))
+            .await;
+        assert!(
+            matches!(result, Err(A2AError::InvalidRequest(_))),
+            "{} was registered",
+            url
+        );
+    }
+    assert!(registry.agents_for_user(user_id).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remote_agents_shared_within_tenant() -> Result<()> {
+    let (database, user) = database_with_user("athlete@example.com").await?;
+    let member = user.id;
+    let teammate = create_user(&database, "teammate@example.com").await?.id;
+    let outsider = create_user(&database, "outsider@example.com").await?.id;
+    database.set_user_tenant(member, Some("club")).await?;
+    database.set_user_tenant(teammate, Some("club")).await?;
+    let registry = local_registry(database);
+    let base_url = spawn_mock_agent(None);
+
+    // Tenant admins curate the registry; members can't change it
+    let tenant = AgentOwner::Tenant("club".to_string());
+    let agent = registry
+        .register_agent(&tenant, registration(&base_url))
+        .await?;
+    assert!(matches!(
+        registry
+            .register_user_agent(member, registration(&base_url))
+            .await,
+        Err(A2AError::Forbidden(_))
+    ));
+    assert!(matches!(
+        registry.remove_user_agent(member, &agent.id).await,
+        Err(A2AError::Forbidden(_))
+    ));
+
+    // Every member delegates through it
+    assert_eq!(registry.agents_for_user(teammate).await?.len(), 1);
+    let task = registry
+        .delegate(teammate, &agent.id, "Plan my meals", None)
+        .await?;
+    assert_eq!(task["id"], "task-42");
+
+    // Users outside the tenant don't see it
+    assert!(registry.agents_for_user(outsider).await?.is_empty());
+    assert!(matches!(
+        registry.delegate(outsider, &agent.id, "hello", None).await,
+        Err(A2AError::RemoteAgentNotFound(_))
+    ));
+
+    registry.remove_agent(&tenant, &agent.id).await?;
+    assert!(registry.agents_for_user(member).await?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Activity Comparison Tests
+//!
+//! Compares an activity older than the latest hundred against personal
+//! bests, similar efforts and a second activity through the
+//! `compare_activities` tool.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use pierre_mcp_server::database_plugins::DatabaseProvider;
+use pierre_mcp_server::models::Activity;
+use pierre_mcp_server::protocols::universal::UniversalToolExecutor;
+use pierre_mcp_server::protocols::ProtocolError;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+mod common;
+use common::{call_tool, database_with_user, manual_run, tool_executor};
+
+fn run(id: &str, days_ago: i64, meters: f64, minutes: u64, heart_rate: u32) -> Activity {
+    Activity {
+        elevation_gain: Some(50.0),
+        average_heart_rate: Some(heart_rate),
+        ..manual_run(
+            id,
+            Utc::now() - Duration::days(days_ago),
+            minutes * 60,
+            meters,
+        )
+    }
+}
+
+async fn compare(
+    executor: &UniversalToolExecutor,
+    user_id: Uuid,
+    parameters: Value,
+) -> Result<Value, ProtocolError> {
+    call_tool(executor, user_id, "compare_activities", parameters).await
+}
+
+#[tokio::test]
+async fn test_compare_activities_tool() -> Result<()> {
+    let (database, user) = database_with_user("compare@example.com").await?;
+
+    // Three 10k runs, the one to compare buried under 110 newer 5k runs
+    for activity in [
+        run("target", 300, 10_000.0, 50, 150),
+        run("best", 250, 10_000.0, 46, 160),
+        run("slow", 20, 10_000.0, 55, 145),
+    ] {
+        database.create_manual_activity(user.id, &activity).await?;
+    }
+    for day in 0..110 {
+        let easy = run(&format!("easy-{}", day), day, 5_000.0, 27, 140);
+        database.create_manual_activity(user.id, &easy).await?;
+    }
+
+    let executor = tool_executor(database.clone())?;
+
+    let best = compare(
+        &executor,
+        user.id,
+        json!({"provider": "all", "activity_id": "target", "comparison_type": "personal_best"}),
+    )
+    .await?;
+    assert_eq!(best["activity"]["id"], "target");
+    assert_eq!(best["activity"]["pace_seconds_per_km"], 300.0);
+    assert_eq!(best["compared_with"][0]["id"], "best");
+    assert_eq!(best["is_personal_best"], false);
+    assert_eq!(best["activities_searched"], 113);
+    let pace = best["differences"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|d| d["metric"] == "pace_seconds_per_km")
+        .unwrap();
+    assert_eq!(pace["difference"], 24.0);
+
+    let similar = compare(
+        &executor,
+        user.id,
+        json!({"provider": "manual", "activity_id": "target", "count": 2}),
+    )
+    .await?;
+    assert_eq!(similar["comparison_type"], "similar_activities");
+    assert_eq!(similar["compared_with"][0]["id"], "best");
+    assert_eq!(similar["compared_with"][1]["id"], "slow");
+    assert_eq!(similar["baseline"]["activities"], 2);
+    assert!(similar["compared_with"][0]["similarity"].as_f64().unwrap() > 0.8);
+
+    let direct = compare(
+        &executor,
+        user.id,
+        json!({"activity_id": "target", "compare_to": "slow"}),
+    )
+    .await?;
+    assert_eq!(direct["comparison_type"], "activity");
+    assert_eq!(direct["baseline"]["pace_seconds_per_km"], 330.0);
+    assert!(direct["insights"][0]
+        .as_str()
+        .unwrap()
+        .starts_with("30 s/km faster"));
+
+    assert!(matches!(
+        compare(
+            &executor,
+            user.id,
+            json!({"activity_id": "target", "comparison_type": "fastest"}),
+        )
+        .await,
+        Err(ProtocolError::InvalidParameters(_))
+    ));
+    assert!(matches!(
+        compare(&executor, user.id, json!({"activity_id": "missing"})).await,
+        Err(ProtocolError::ExecutionFailed(_))
+    ));
+
+    Ok(())
+}
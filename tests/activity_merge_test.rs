@@ -0,0 +1,183 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Activity Merge Tests
+//!
+//! Connects a user to both fake Strava and fake Fitbit, where the watch
+//! synced the same morning run to each, and checks the merged `all`
+//! provider view counts it once with fields taken by precedence.
+
+use anyhow::Result;
+use pierre_mcp_server::database_plugins::factory::Database;
+use pierre_mcp_server::models::SportType;
+use pierre_mcp_server::protocols::universal::UniversalRequest;
+use pierre_mcp_server::providers::fake_server::{
+    FakeFailure, FakeProviderData, FakeProviderServer,
+};
+use pierre_mcp_server::providers::merge::{all_provider_activities, MergedProvider, ALL_PROVIDERS};
+use pierre_mcp_server::providers::rate_budget::ProviderRateLimited;
+use pierre_mcp_server::providers::FitnessProvider;
+use serde_json::json;
+use serial_test::serial;
+use std::sync::Arc;
+use uuid::Uuid;
+
+mod common;
+use common::{
+    connect_provider, create_user, provider_connections, start_fake_server, tool_executor,
+};
+
+/// Fake data where Fitbit also recorded Strava's 1001 morning run
+async fn start_overlapping_server() -> Result<FakeProviderServer> {
+    let mut data = FakeProviderData::default();
+    data.fitbit_activities.insert(
+        0,
+        json!({
+            "activityId": 2003, "activityName": "Run", "activityTypeId": 90009,
+            "startTime": "2024-06-03T02:30:40.000-04:00", "duration": 1790000,
+            "distance": 4.95, "steps": 5600, "calories": 320,
+            "elevationGain": 40.0, "averageHeartRate": 150
+        }),
+    );
+    start_fake_server(data).await
+}
+
+async fn connect_both(database: &Database) -> Result<Uuid> {
+    let user_id = create_user(database, "merge@example.com").await?.id;
+    for (provider, external_id) in [("strava", "12345"), ("fitbit", "FAKE123")] {
+        connect_provider(database, user_id, provider, external_id, "read").await?;
+    }
+    Ok(user_id)
+}
+
+#[tokio::test]
+#[serial]
+async fn test_merged_provider_deduplicates_across_providers() -> Result<()> {
+    let _server = start_overlapping_server().await?;
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let user_id = connect_both(&database).await?;
+
+    let connections = provider_connections(database.clone())?;
+    let provider = MergedProvider::for_user(&connections, user_id).await;
+    assert_eq!(provider.provider_name(), ALL_PROVIDERS);
+
+    // Six provider records, five workouts
+    let activities = provider.get_activities(Some(10), None).await?;
+    let ids: Vec<&str> = activities.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec!["2001", "1001", "1002", "2002", "1003"]);
+
+    // Identity and GPS from Strava, heart rate and calories from Fitbit
+    let run = &activities[1];
+    assert_eq!(run.provider, "strava");
+    assert_eq!(run.sport_type, SportType::Run);
+    assert_eq!(run.distance_meters, Some(5000.0));
+    assert_eq!(run.start_latitude, Some(45.5017));
+    assert_eq!(run.average_heart_rate, Some(150));
+    assert_eq!(run.calories, Some(320));
+    assert_eq!(run.gear_id.as_deref(), Some("g201"));
+
+    let page = provider.get_activities(Some(2), Some(1)).await?;
+    assert_eq!(page[0].id, "1001");
+    assert_eq!(page.len(), 2);
+
+    // Either provider's id finds the merged workout
+    assert_eq!(provider.get_activity("2003").await?.id, "1001");
+    assert_eq!(provider.get_activity("1001").await?.calories, Some(320));
+
+    assert_eq!(
+        all_provider_activities(&connections, user_id, 3)
+            .await?
+            .len(),
+        3
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_all_providers_view_in_tools() -> Result<()> {
+    let _server = start_overlapping_server().await?;
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let user_id = connect_both(&database).await?;
+    let executor = tool_executor(database.clone())?;
+
+    let response = executor
+        .execute_tool(UniversalRequest {
+            tool_name: "get_activities".to_string(),
+            parameters: json!({"provider": "all", "limit": 10}),
+            user_id: user_id.to_string(),
+            protocol: "test".to_string(),
+        })
+        .await?;
+    let result = response.result.unwrap();
+    assert_eq!(result["total_count"], 5);
+    let sources = result["activities"][1]["sources"].as_array().unwrap();
+    assert_eq!(sources.len(), 2);
+    assert!(sources.contains(&json!({"provider": "fitbit", "activity_id": "2003"})));
+
+    // Gear usage counts the doubly recorded run once
+    let response = executor
+        .execute_tool(UniversalRequest {
+            tool_name: "get_gear".to_string(),
+            parameters: json!({}),
+            user_id: user_id.to_string(),
+            protocol: "test".to_string(),
+        })
+        .await?;
+    let gear = response.result.unwrap();
+    let pegasus = gear["gear"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["id"] == "g201")
+        .unwrap();
+    assert_eq!(pegasus["activity_count"], 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_rate_limited_provider_fails_merged_view() -> Result<()> {
+    let server = start_overlapping_server().await?;
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let user_id = connect_both(&database).await?;
+    let connections = provider_connections(database.clone())?;
+    let executor = tool_executor(database.clone())?;
+    server.fail_next(
+        "/fitbit/1/user/-/activities/list.json",
+        FakeFailure::RateLimited {
+            retry_after_secs: 1,
+        },
+        2,
+    );
+
+    // A partial history would look like missing workouts, so the limit is reported
+    let error = all_provider_activities(&connections, user_id, 10)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error
+            .downcast_ref::<ProviderRateLimited>()
+            .unwrap()
+            .provider,
+        "fitbit"
+    );
+
+    let response = executor
+        .execute_tool(UniversalRequest {
+            tool_name: "get_activities".to_string(),
+            parameters: json!({"provider": "all", "limit": 10}),
+            user_id: user_id.to_string(),
+            protocol: "test".to_string(),
+        })
+        .await?;
+    assert!(!response.success);
+    assert_eq!(response.metadata.unwrap()["retry_after_secs"], 1);
+
+    Ok(())
+}
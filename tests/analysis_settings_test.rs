@@ -0,0 +1,187 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Analysis Settings Tests
+//!
+//! Layers a club's zone model over the server defaults and a member's own
+//! overrides over that, through the settings module, the
+//! `update_analysis_settings` tool and the REST routes.
+
+use anyhow::Result;
+use chrono::Utc;
+use pierre_mcp_server::analysis_settings_routes::AnalysisSettingsRoutes;
+use pierre_mcp_server::auth::AuthManager;
+use pierre_mcp_server::database_plugins::{factory::Database, DatabaseProvider};
+use pierre_mcp_server::intelligence::settings::{self, AnalysisSettingsUpdate};
+use pierre_mcp_server::intelligence::thresholds::{
+    self, AthleteThresholds, StoredThresholds, ThresholdConfidence, ThresholdEstimate,
+};
+use pierre_mcp_server::protocols::universal::UniversalToolExecutor;
+use pierre_mcp_server::protocols::ProtocolError;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+
+mod common;
+use common::{call_tool, create_user, tool_executor};
+
+async fn update(
+    executor: &UniversalToolExecutor,
+    user_id: Uuid,
+    parameters: Value,
+) -> Result<Value, ProtocolError> {
+    call_tool(executor, user_id, "update_analysis_settings", parameters).await
+}
+
+#[tokio::test]
+async fn test_tenant_and_user_layers() -> Result<()> {
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let member = create_user(&database, "member@example.com").await?;
+    let outsider = create_user(&database, "outsider@example.com").await?;
+    let executor = tool_executor(database.clone())?;
+
+    // The club shifts every zone boundary up
+    let club = settings::update_tenant_settings(
+        &database,
+        "harriers",
+        &json!({"zone_thresholds": {
+            "recovery_max": 65, "endurance_max": 75, "tempo_max": 85, "threshold_max": 92
+        }}),
+        false,
+    )
+    .await?;
+    assert_eq!(club["effective"]["zone_thresholds"]["tempo_max"], 85.0);
+    database
+        .set_user_tenant(member.id, Some("harriers"))
+        .await?;
+    assert_eq!(
+        database.get_user_tenant(member.id).await?.as_deref(),
+        Some("harriers")
+    );
+
+    let config = settings::effective_config(&database, member.id).await;
+    assert_eq!(config.zone_thresholds.recovery_max, 65.0);
+    let config = settings::effective_config(&database, outsider.id).await;
+    assert_eq!(config.zone_thresholds.recovery_max, 60.0);
+
+    // The member raises their own threshold boundary on top of the club's
+    let result = update(
+        &executor,
+        member.id,
+        json!({"settings": {"zone_thresholds": {"threshold_max": 94}}}),
+    )
+    .await?;
+    assert_eq!(result["tenant_id"], "harriers");
+    assert_eq!(
+        result["user_overrides"],
+        json!({"zone_thresholds": {"threshold_max": 94}})
+    );
+    assert_eq!(result["effective"]["zone_thresholds"]["tempo_max"], 85.0);
+    assert_eq!(
+        result["effective"]["zone_thresholds"]["threshold_max"],
+        94.0
+    );
+
+    // Zones in the threshold report follow the member's model
+    let stored = StoredThresholds {
+        current: AthleteThresholds {
+            max_heart_rate: Some(ThresholdEstimate {
+                value: 200.0,
+                confidence: ThresholdConfidence::High,
+                source_activity_id: None,
+                samples: 3,
+            }),
+            threshold_heart_rate: None,
+            functional_threshold_power: None,
+            threshold_pace: None,
+            estimated_at: Utc::now(),
+        },
+        history: vec![],
+    };
+    let config = settings::effective_config(&database, member.id).await;
+    let report = thresholds::thresholds_report(&stored, &config.zone_thresholds, false);
+    assert_eq!(report["heart_rate_zones"][0]["max_bpm"], 130);
+    assert_eq!(report["heart_rate_zones"][3]["max_bpm"], 188);
+
+    // Overrides are checked against the config schema
+    for bad in [
+        json!({"settings": {"zone_thresholds": {"zone6_max": 97}}}),
+        json!({"settings": {"zone_thresholds": {"tempo_max": 96}}}),
+        json!({"settings": {"effort_thresholds": {"hard_max": "very"}}}),
+        json!({"settings": ["zone_thresholds"]}),
+    ] {
+        assert!(matches!(
+            update(&executor, member.id, bad).await,
+            Err(ProtocolError::InvalidParameters(_))
+        ));
+    }
+
+    // Null restores the inherited value, reset clears everything
+    let result = update(
+        &executor,
+        member.id,
+        json!({"settings": {"zone_thresholds": {"threshold_max": null}}}),
+    )
+    .await?;
+    assert_eq!(result["user_overrides"], json!({}));
+    assert_eq!(
+        result["effective"]["zone_thresholds"]["threshold_max"],
+        92.0
+    );
+
+    update(
+        &executor,
+        member.id,
+        json!({"settings": {"weather_mapping": {"wind_threshold": 25}}}),
+    )
+    .await?;
+    let result = update(&executor, member.id, json!({"reset": true})).await?;
+    assert_eq!(result["user_overrides"], json!({}));
+    assert_eq!(
+        result["effective"]["weather_mapping"]["wind_threshold"],
+        15.0
+    );
+
+    // Leaving the club drops its layer
+    database.set_user_tenant(member.id, None).await?;
+    let result = update(&executor, member.id, json!({})).await?;
+    assert!(result["tenant_id"].is_null());
+    assert_eq!(result["effective"]["zone_thresholds"]["tempo_max"], 80.0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_analysis_settings_routes() -> Result<()> {
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let user = create_user(&database, "routes@example.com").await?;
+    let auth_manager = Arc::new(AuthManager::new(vec![7u8; 32], 24));
+    let routes = AnalysisSettingsRoutes::new(database.clone(), auth_manager.clone());
+    let auth_header = format!("Bearer {}", auth_manager.generate_token(&user)?);
+    let auth = Some(auth_header.as_str());
+
+    let current = routes.get_settings(auth).await?;
+    assert_eq!(current.user_overrides, json!({}));
+    assert_eq!(current.effective.effort_thresholds.hard_max, 7.0);
+
+    let update: AnalysisSettingsUpdate = serde_json::from_value(json!({
+        "settings": {"effort_thresholds": {"hard_max": 8}}
+    }))?;
+    let updated = routes.update_settings(auth, update).await?;
+    assert_eq!(updated.effective.effort_thresholds.hard_max, 8.0);
+    assert_eq!(
+        routes.get_settings(auth).await?.user_overrides,
+        json!({"effort_thresholds": {"hard_max": 8}})
+    );
+
+    let invalid: AnalysisSettingsUpdate = serde_json::from_value(json!({
+        "settings": {"effort_thresholds": {"light_max": 9}}
+    }))?;
+    assert!(routes.update_settings(auth, invalid).await.is_err());
+    assert!(routes.get_settings(None).await.is_err());
+
+    Ok(())
+}
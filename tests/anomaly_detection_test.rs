@@ -0,0 +1,109 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Anomaly Detection Tests
+//!
+//! Checks logged activities against the user's rolling baseline through the
+//! `detect_anomalies` tool.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use pierre_mcp_server::database_plugins::DatabaseProvider;
+use pierre_mcp_server::models::Activity;
+use pierre_mcp_server::protocols::universal::UniversalToolExecutor;
+use pierre_mcp_server::protocols::ProtocolError;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+mod common;
+use common::{call_tool, database_with_user, manual_run, tool_executor};
+
+fn run(id: &str, days_ago: i64, minutes: u64, heart_rate: u32) -> Activity {
+    Activity {
+        elevation_gain: Some(40.0),
+        average_heart_rate: Some(heart_rate),
+        max_heart_rate: Some(175),
+        max_speed: Some(5.0),
+        ..manual_run(
+            id,
+            Utc::now() - Duration::days(days_ago),
+            minutes * 60,
+            10_000.0,
+        )
+    }
+}
+
+async fn detect(
+    executor: &UniversalToolExecutor,
+    user_id: Uuid,
+    parameters: Value,
+) -> Result<Value, ProtocolError> {
+    call_tool(executor, user_id, "detect_anomalies", parameters).await
+}
+
+#[tokio::test]
+async fn test_detect_anomalies_tool() -> Result<()> {
+    let (database, user) = database_with_user("anomalies@example.com").await?;
+
+    // Five weeks of steady 10k runs, then one with a racing heart and one
+    // far slower than usual
+    for i in 0..9 {
+        let minutes = 48 + (i % 5) as u64;
+        let activity = run(
+            &format!("base-{}", i),
+            40 - i * 4,
+            minutes,
+            140 + i as u32 % 3,
+        );
+        database.create_manual_activity(user.id, &activity).await?;
+    }
+    database
+        .create_manual_activity(user.id, &run("hot", 3, 50, 163))
+        .await?;
+    database
+        .create_manual_activity(user.id, &run("slow", 1, 61, 141))
+        .await?;
+
+    let executor = tool_executor(database.clone())?;
+
+    let recent = detect(&executor, user.id, json!({"days": 14})).await?;
+    assert_eq!(recent["days"], 14);
+    assert_eq!(recent["activities_analyzed"], 4);
+    assert_eq!(recent["streams_analyzed"], 0);
+    assert_eq!(recent["activities_with_anomalies"], 2);
+    let flagged = recent["activities"].as_array().unwrap();
+    assert_eq!(flagged[0]["activity_id"], "slow");
+    assert_eq!(
+        flagged[0]["anomalies"][0]["anomaly_type"],
+        "performance_drop"
+    );
+    assert_eq!(flagged[1]["activity_id"], "hot");
+    let anomaly = &flagged[1]["anomalies"][0];
+    assert_eq!(anomaly["anomaly_type"], "heart_rate_at_pace");
+    assert_eq!(anomaly["severity"], "Critical");
+    assert!(anomaly["description"]
+        .as_str()
+        .unwrap()
+        .starts_with("Heart rate averaged 163 bpm"));
+
+    let insights = recent["insights"].as_array().unwrap();
+    assert_eq!(insights.len(), 2);
+    assert_eq!(insights[1]["insight_type"], "anomaly");
+    assert_eq!(insights[1]["data"]["activity_id"], "hot");
+
+    // A single activity is reported even when nothing stands out
+    let single = detect(&executor, user.id, json!({"activity_id": "base-8"})).await?;
+    assert!(single["days"].is_null());
+    assert_eq!(single["activities"][0]["activity_id"], "base-8");
+    assert_eq!(single["activities_with_anomalies"], 0);
+
+    assert!(matches!(
+        detect(&executor, user.id, json!({"activity_id": "missing"})).await,
+        Err(ProtocolError::ExecutionFailed(_))
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,251 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Athlete Threshold Tests
+//!
+//! Estimates max heart rate, threshold heart rate, FTP and threshold pace
+//! from the fake Strava activities and streams, and checks they are stored
+//! in the user profile with history and feed the metrics calculator.
+
+use anyhow::Result;
+use chrono::Utc;
+use pierre_mcp_server::auth::AuthManager;
+use pierre_mcp_server::config::environment::ServerConfig;
+use pierre_mcp_server::database_plugins::{factory::Database, DatabaseProvider};
+use pierre_mcp_server::intelligence::thresholds::{
+    self, ThresholdConfidence, ThresholdEstimate, PROFILE_KEY,
+};
+use pierre_mcp_server::mcp::multitenant::MultiTenantMcpServer;
+use pierre_mcp_server::models::DailyWellness;
+use pierre_mcp_server::protocols::universal::UniversalToolExecutor;
+use pierre_mcp_server::providers::registry;
+use serde_json::{json, Value};
+use serial_test::serial;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use uuid::Uuid;
+
+mod common;
+use common::{
+    call_tool, connect_provider, create_user, provider_connections, start_fake_server,
+    tool_executor,
+};
+
+async fn connect_strava(database: &Database) -> Result<Uuid> {
+    let user_id = create_user(database, "thresholds@example.com").await?.id;
+    connect_provider(
+        database,
+        user_id,
+        "strava",
+        "12345",
+        "read,activity:read_all",
+    )
+    .await?;
+    Ok(user_id)
+}
+
+async fn get_thresholds(
+    executor: &UniversalToolExecutor,
+    user_id: Uuid,
+    parameters: Value,
+) -> Value {
+    call_tool(executor, user_id, "get_athlete_thresholds", parameters)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_thresholds_from_strava_history() -> Result<()> {
+    let server = start_fake_server(Default::default()).await?;
+
+    // Power arrives with nulls where the rider stopped pedalling
+    let strava = registry()
+        .create_authenticated(
+            "strava",
+            &server.external_services(),
+            "fake-access",
+            "fake-refresh",
+        )
+        .await?;
+    let streams = strava.get_activity_streams("1002").await?;
+    let power = streams.power.unwrap();
+    assert_eq!(power.len(), streams.time.len());
+    assert_eq!(power[90], 0);
+    assert!(strava.get_activity_streams("1003").await.is_err());
+
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let user_id = connect_strava(&database).await?;
+    let executor = tool_executor(database.clone())?;
+
+    let report = get_thresholds(&executor, user_id, json!({})).await;
+    let estimated = &report["thresholds"];
+    assert_eq!(estimated["max_heart_rate"]["value"], 178.0);
+    assert_eq!(estimated["max_heart_rate"]["source_activity_id"], "1003");
+    // Best 20 minutes of heart rate was the ride's effort at 158 bpm
+    assert_eq!(estimated["threshold_heart_rate"]["value"], 150.0);
+    assert_eq!(estimated["threshold_heart_rate"]["confidence"], "medium");
+    // 265 W for 20 minutes
+    assert_eq!(estimated["functional_threshold_power"]["value"], 252.0);
+    assert_eq!(estimated["functional_threshold_power"]["samples"], 1);
+    // Only runs far from an hour, so the pace is extrapolated
+    assert_eq!(estimated["threshold_pace"]["source_activity_id"], "1003");
+    assert_eq!(estimated["threshold_pace"]["confidence"], "low");
+    assert_eq!(report["threshold_pace_formatted"], "4:49/km");
+
+    let zones = report["heart_rate_zones"].as_array().unwrap();
+    assert_eq!(zones.len(), 5);
+    assert_eq!(zones[0]["max_bpm"], 107);
+    assert_eq!(zones[4]["name"], "vo2max");
+
+    // Stored in the profile and reused until stale
+    let profile = database.get_user_profile(user_id).await?.unwrap();
+    assert_eq!(
+        profile[PROFILE_KEY]["current"]["functional_threshold_power"]["value"],
+        252.0
+    );
+    let again = get_thresholds(&executor, user_id, json!({})).await;
+    assert_eq!(again["estimated_at"], report["estimated_at"]);
+
+    let calculator = thresholds::metrics_calculator_for(&database, user_id).await;
+    assert_eq!(calculator.ftp, Some(252.0));
+    assert_eq!(calculator.lthr, Some(150.0));
+    assert_eq!(calculator.max_hr, Some(178.0));
+
+    // A changed estimate keeps the previous one in the history
+    let mut raised = thresholds::load_thresholds(&database, user_id)
+        .await?
+        .unwrap()
+        .current;
+    raised.functional_threshold_power = Some(ThresholdEstimate {
+        value: 270.0,
+        confidence: ThresholdConfidence::Medium,
+        source_activity_id: None,
+        samples: 2,
+    });
+    thresholds::save_thresholds(&database, user_id, raised).await?;
+    let with_history = get_thresholds(&executor, user_id, json!({"include_history": true})).await;
+    assert_eq!(
+        with_history["thresholds"]["functional_threshold_power"]["value"],
+        270.0
+    );
+    let history = with_history["history"].as_array().unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["functional_threshold_power"]["value"], 252.0);
+
+    // Refreshing re-estimates from the provider data
+    let refreshed = get_thresholds(
+        &executor,
+        user_id,
+        json!({"refresh": true, "include_history": true}),
+    )
+    .await;
+    assert_eq!(
+        refreshed["thresholds"]["functional_threshold_power"]["value"],
+        252.0
+    );
+    assert_eq!(refreshed["history"].as_array().unwrap().len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_thresholds_without_history() -> Result<()> {
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let user_id = create_user(&database, "new@example.com").await?.id;
+    let executor = tool_executor(database.clone())?;
+
+    let report = get_thresholds(&executor, user_id, json!({})).await;
+    assert!(report["thresholds"]["max_heart_rate"].is_null());
+    assert!(report["heart_rate_zones"].is_null());
+    assert!(report["threshold_pace_formatted"].is_null());
+
+    Ok(())
+}
+
+/// Send one authenticated `tools/call` to the MCP server on `port`
+async fn mcp_tool_call(port: u16, token: &str, tool: &str, arguments: Value) -> Result<Value> {
+    let mut stream = None;
+    for _ in 0..20 {
+        match TcpStream::connect(("127.0.0.1", port)).await {
+            Ok(connected) => {
+                stream = Some(connected);
+                break;
+            }
+            Err(_) => tokio::time::sleep(std::time::Duration::from_millis(100)).await,
+        }
+    }
+    let mut stream = stream.ok_or_else(|| anyhow::anyhow!("MCP server did not start"))?;
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/call",
+        "params": {"name": tool, "arguments": arguments},
+        "auth": format!("Bearer {}", token),
+    });
+    stream
+        .write_all(format!("{}\n", request).as_bytes())
+        .await?;
+    let mut line = String::new();
+    BufReader::new(&mut stream).read_line(&mut line).await?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+#[tokio::test]
+#[serial]
+async fn test_mcp_metrics_use_stored_thresholds() -> Result<()> {
+    let server = start_fake_server(Default::default()).await?;
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let user_id = connect_strava(&database).await?;
+    let user = database.get_user(user_id).await?.unwrap();
+    thresholds::estimate_for_user(&provider_connections(database.clone())?, user_id).await?;
+    let mut today = DailyWellness::new(Utc::now().date_naive(), "fitbit");
+    today.resting_heart_rate = Some(50);
+    database.upsert_daily_wellness(user_id, &today).await?;
+
+    let auth_manager = AuthManager::new(vec![0u8; 64], 24);
+    let token = auth_manager.generate_token(&user)?;
+    let port = 19000 + rand::random::<u16>() % 10000;
+    let mcp = MultiTenantMcpServer::new(
+        (*database).clone(),
+        auth_manager,
+        Arc::new(ServerConfig::from_env()?),
+    );
+    let handle = tokio::spawn(async move { mcp.run(port).await });
+
+    let response = mcp_tool_call(
+        port,
+        &token,
+        "calculate_metrics",
+        json!({"provider": "strava", "activity_id": "1003"}),
+    )
+    .await?;
+    handle.abort();
+    assert!(response["error"].is_null(), "{}", response);
+
+    // TRIMP needs the estimated max heart rate and the stored resting rate
+    let run = registry()
+        .create_authenticated(
+            "strava",
+            &server.external_services(),
+            "fake-access",
+            "fake-refresh",
+        )
+        .await?
+        .get_activity("1003")
+        .await?;
+    let expected = thresholds::metrics_calculator_for(&database, user_id)
+        .await
+        .calculate_metrics_with_conditions(&run, None, None)?
+        .trimp
+        .unwrap();
+    let metrics = &response["result"]["metrics"];
+    assert_eq!(metrics["activity_id"], "1003");
+    assert_eq!(metrics["trimp"].as_f64(), Some(expected));
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Helpers shared by the integration tests
+//!
+//! Each test binary compiles this module on its own and uses only part of
+//! it, hence the `dead_code` allowance.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use pierre_mcp_server::config::environment::ServerConfig;
+use pierre_mcp_server::database::generate_encryption_key;
+use pierre_mcp_server::database_plugins::{factory::Database, DatabaseProvider};
+use pierre_mcp_server::intelligence::{
+    ActivityIntelligence, ContextualFactors, PerformanceMetrics, TimeOfDay, TrendDirection,
+    TrendIndicators,
+};
+use pierre_mcp_server::models::{Activity, DecryptedToken, SportType, User};
+use pierre_mcp_server::protocols::universal::{UniversalRequest, UniversalToolExecutor};
+use pierre_mcp_server::protocols::ProtocolError;
+use pierre_mcp_server::providers::connections::ProviderConnections;
+use pierre_mcp_server::providers::fake_server::{FakeProviderData, FakeProviderServer};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use uuid::Uuid;
+
+/// Placeholder intelligence for building a `UniversalToolExecutor`
+pub fn test_intelligence() -> Arc<ActivityIntelligence> {
+    Arc::new(ActivityIntelligence::new(
+        "Test Intelligence".to_string(),
+        vec![],
+        PerformanceMetrics {
+            relative_effort: None,
+            zone_distribution: None,
+            personal_records: vec![],
+            efficiency_score: None,
+            trend_indicators: TrendIndicators {
+                pace_trend: TrendDirection::Stable,
+                effort_trend: TrendDirection::Stable,
+                distance_trend: TrendDirection::Stable,
+                consistency_score: 0.0,
+            },
+        },
+        ContextualFactors {
+            weather: None,
+            location: None,
+            time_of_day: TimeOfDay::Morning,
+            days_since_last_activity: None,
+            weekly_load: None,
+        },
+    ))
+}
+
+/// Store a new user with the given email
+pub async fn create_user(database: &Database, email: &str) -> Result<User> {
+    let user = User::new(email.to_string(), "hash".to_string(), None);
+    database.create_user(&user).await?;
+    Ok(user)
+}
+
+/// An in-memory database holding one user
+pub async fn database_with_user(email: &str) -> Result<(Arc<Database>, User)> {
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let user = create_user(&database, email).await?;
+    Ok((database, user))
+}
+
+/// An in-memory database holding one user and a tool executor over it
+pub async fn setup(email: &str) -> Result<(Arc<Database>, UniversalToolExecutor, Uuid)> {
+    let (database, user) = database_with_user(email).await?;
+    let executor = tool_executor(database.clone())?;
+    Ok((database, executor, user.id))
+}
+
+/// Tool executor over `database`, configured from the environment
+pub fn tool_executor(database: Arc<Database>) -> Result<UniversalToolExecutor> {
+    Ok(UniversalToolExecutor::new(
+        database,
+        test_intelligence(),
+        Arc::new(ServerConfig::from_env()?),
+    ))
+}
+
+/// Provider connections over `database`, configured from the environment
+pub fn provider_connections(database: Arc<Database>) -> Result<ProviderConnections> {
+    Ok(ProviderConnections::from_config(
+        database,
+        &ServerConfig::from_env()?,
+    ))
+}
+
+/// A manual run with just the basics; tests fill in the fields they
+/// exercise with struct update syntax
+pub fn manual_run(
+    id: &str,
+    start_date: DateTime<Utc>,
+    duration_seconds: u64,
+    meters: f64,
+) -> Activity {
+    Activity {
+        id: id.to_string(),
+        name: format!("Run {}", id),
+        sport_type: SportType::Run,
+        start_date,
+        duration_seconds,
+        distance_meters: Some(meters),
+        provider: "manual".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Store a token connecting the user to `provider` as `external_id`
+pub async fn connect_provider(
+    database: &Database,
+    user_id: Uuid,
+    provider: &str,
+    external_id: &str,
+    scope: &str,
+) -> Result<()> {
+    database
+        .upsert_provider_token(
+            user_id,
+            provider,
+            &DecryptedToken {
+                access_token: format!("{}-access", provider),
+                refresh_token: format!("{}-refresh", provider),
+                expires_at: Utc::now() + Duration::hours(6),
+                scope: scope.to_string(),
+            },
+            Some(external_id),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Run `tool` for the user and return its result
+///
+/// Protocol errors are returned; a response that reports failure panics
+/// with its error.
+pub async fn call_tool(
+    executor: &UniversalToolExecutor,
+    user_id: Uuid,
+    tool: &str,
+    parameters: Value,
+) -> Result<Value, ProtocolError> {
+    let response = executor
+        .execute_tool(UniversalRequest {
+            tool_name: tool.to_string(),
+            parameters,
+            user_id: user_id.to_string(),
+            protocol: "test".to_string(),
+        })
+        .await?;
+    assert!(response.success, "{:?}", response.error);
+    Ok(response.result.unwrap())
+}
+
+/// Start the fake provider APIs and point the environment at them
+///
+/// Both providers' OAuth clients get fake credentials, so configuration
+/// read from the environment afterwards talks to the fake server.
+pub async fn start_fake_server(data: FakeProviderData) -> Result<FakeProviderServer> {
+    let server = FakeProviderServer::start(data).await?;
+    server.configure_env();
+    for provider in ["STRAVA", "FITBIT"] {
+        std::env::set_var(format!("{}_CLIENT_ID", provider), "fake_client");
+        std::env::set_var(format!("{}_CLIENT_SECRET", provider), "fake_secret");
+    }
+    Ok(server)
+}
+
+/// Keep the single-tenant MCP server's storage out of the working tree
+///
+/// Without `ENCRYPTION_KEY_PATH` and `DATABASE_URL` its tool calls create
+/// `data/encryption.key` and `data/pierre.db` under the current directory.
+pub fn isolate_server_storage() {
+    static KEY_PATH: OnceLock<PathBuf> = OnceLock::new();
+    let key_path = KEY_PATH.get_or_init(|| {
+        let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("encryption.key");
+        if !path.exists() {
+            std::fs::write(&path, generate_encryption_key()).expect("write test encryption key");
+        }
+        path
+    });
+    std::env::set_var("ENCRYPTION_KEY_PATH", key_path);
+    std::env::set_var("DATABASE_URL", "sqlite::memory:");
+}
@@ -1,6 +1,6 @@
 use chrono::Utc;
 use pierre_mcp_server::database_plugins::{factory::Database, DatabaseProvider};
-use pierre_mcp_server::models::User;
+use pierre_mcp_server::models::{DecryptedToken, User};
 use serde_json::json;
 use uuid::Uuid;
 
@@ -22,8 +22,6 @@ async fn create_test_user(db: &Database) -> Uuid {
         created_at: Utc::now(),
         last_active: Utc::now(),
         is_active: true,
-        strava_token: None,
-        fitbit_token: None,
     };
 
     db.create_user(&user).await.expect("Failed to create user");
@@ -79,19 +77,23 @@ async fn test_oauth_token_management() {
 
     // Test storing Strava token
     let expires_at = Utc::now() + chrono::Duration::hours(1);
-    db.update_strava_token(
+    db.upsert_provider_token(
         user_id,
-        "test_access_token",
-        "test_refresh_token",
-        expires_at,
-        "read,activity:read_all".to_string(),
+        "strava",
+        &DecryptedToken {
+            access_token: "test_access_token".to_string(),
+            refresh_token: "test_refresh_token".to_string(),
+            expires_at,
+            scope: "read,activity:read_all".to_string(),
+        },
+        None,
     )
     .await
     .expect("Failed to update Strava token");
 
     // Test retrieving Strava token
     let token = db
-        .get_strava_token(user_id)
+        .get_provider_token(user_id, "strava")
         .await
         .expect("Failed to get Strava token");
     assert!(token.is_some(), "Strava token should exist");
@@ -102,12 +104,12 @@ async fn test_oauth_token_management() {
     assert_eq!(token.scope, "read,activity:read_all");
 
     // Test clearing Strava token
-    db.clear_strava_token(user_id)
+    db.delete_provider_connection(user_id, "strava")
         .await
         .expect("Failed to clear Strava token");
 
     let cleared_token = db
-        .get_strava_token(user_id)
+        .get_provider_token(user_id, "strava")
         .await
         .expect("Failed to get Strava token after clear");
     assert!(cleared_token.is_none(), "Strava token should be cleared");
@@ -253,8 +255,6 @@ async fn test_database_trait_abstraction() {
                 created_at: Utc::now(),
                 last_active: Utc::now(),
                 is_active: true,
-                strava_token: None,
-                fitbit_token: None,
             };
 
             db_clone.create_user(&user).await
@@ -294,8 +294,6 @@ async fn test_system_stats() {
             created_at: Utc::now(),
             last_active: Utc::now(),
             is_active: true,
-            strava_token: None,
-            fitbit_token: None,
         };
 
         db.create_user(&user).await.expect("Failed to create user");
@@ -19,6 +19,9 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
 use tokio::time::{timeout, Duration};
 
+mod common;
+use common::isolate_server_storage;
+
 /// Helper to create a temporary config file with test data
 async fn create_test_config_file() -> Result<(TempDir, String)> {
     let temp_dir = TempDir::new()?;
@@ -55,6 +58,7 @@ async fn test_complete_server_workflow() -> Result<()> {
     assert!(config.providers.contains_key("strava"));
 
     // 3. Start MCP server
+    isolate_server_storage();
     let server = McpServer::new(config);
     let server_task = tokio::spawn(async move { server.run(8090).await });
 
@@ -227,6 +231,7 @@ async fn test_config_env_workflow() -> Result<()> {
     }; // Guard is dropped here
 
     // Test MCP server with env-loaded config
+    isolate_server_storage();
     let server = McpServer::new(config);
     let server_task = tokio::spawn(async move { server.run(8091).await });
 
@@ -296,6 +301,7 @@ async fn test_config_persistence_workflow() -> Result<()> {
     assert_eq!(loaded_provider.api_key, original_provider.api_key);
 
     // 6. Test MCP server with reloaded config
+    isolate_server_storage();
     let server = McpServer::new(loaded_config);
     let server_task = tokio::spawn(async move { server.run(8092).await });
 
@@ -319,6 +325,7 @@ async fn test_error_recovery_workflow() -> Result<()> {
         providers: HashMap::new(),
     };
 
+    isolate_server_storage();
     let server = McpServer::new(empty_config);
     let server_task = tokio::spawn(async move { server.run(8093).await });
 
@@ -386,6 +393,7 @@ async fn test_concurrent_operations_workflow() -> Result<()> {
         providers: HashMap::new(),
     };
 
+    isolate_server_storage();
     let server = McpServer::new(config);
     let server_task = tokio::spawn(async move { server.run(8094).await });
 
@@ -0,0 +1,236 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Fake Provider Server Tests
+//!
+//! Runs the real Strava and Fitbit clients, the OAuth callback and a tool
+//! call against the in-process fake provider APIs.
+
+use anyhow::Result;
+use pierre_mcp_server::database_plugins::DatabaseProvider;
+use pierre_mcp_server::protocols::universal::UniversalRequest;
+use pierre_mcp_server::providers::fake_server::{
+    FakeFailure, FakeProviderData, FakeProviderServer,
+};
+use pierre_mcp_server::providers::merge::paged_activities;
+use pierre_mcp_server::providers::rate_budget::ProviderRateLimited;
+use pierre_mcp_server::providers::{registry, FitnessProvider};
+use pierre_mcp_server::routes::OAuthRoutes;
+use serde_json::json;
+use serial_test::serial;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::{database_with_user, start_fake_server, tool_executor};
+
+async fn strava_provider(server: &FakeProviderServer) -> Result<Box<dyn FitnessProvider>> {
+    registry()
+        .create_authenticated(
+            "strava",
+            &server.external_services(),
+            "fake-access",
+            "fake-refresh",
+        )
+        .await
+}
+
+#[tokio::test]
+#[serial]
+async fn test_strava_provider_against_fake_server() -> Result<()> {
+    let server = FakeProviderServer::start(Default::default()).await?;
+    let provider = strava_provider(&server).await?;
+
+    let athlete = provider.get_athlete().await?;
+    assert_eq!(athlete.id, "12345");
+    assert_eq!(athlete.provider, "strava");
+
+    let activities = provider.get_activities(Some(2), None).await?;
+    assert_eq!(activities.len(), 2);
+    assert_eq!(activities[0].name, "Morning Run");
+
+    let activity = provider.get_activity("1003").await?;
+    assert_eq!(activity.name, "Long Run");
+
+    let stats = provider.get_stats().await?;
+    assert_eq!(stats.total_activities, 430);
+
+    assert!(server
+        .requests()
+        .contains(&"GET /strava/api/v3/athletes/12345/stats".to_string()));
+
+    let streams: serde_json::Value = reqwest::Client::new()
+        .get(format!(
+            "{}/activities/1001/streams",
+            server.external_services().strava_api.base_url
+        ))
+        .bearer_auth("fake-access")
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(streams["heartrate"]["data"][1], 148);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_fitbit_provider_against_fake_server() -> Result<()> {
+    let server = FakeProviderServer::start(Default::default()).await?;
+    let provider = registry()
+        .create_authenticated(
+            "fitbit",
+            &server.external_services(),
+            "fake-access",
+            "fake-refresh",
+        )
+        .await?;
+
+    let athlete = provider.get_athlete().await?;
+    assert_eq!(athlete.id, "FAKE123");
+
+    let activities = provider.get_activities(None, None).await?;
+    assert_eq!(activities.len(), 2);
+    assert_eq!(activities[1].distance_meters, Some(8000.0));
+
+    let activity = provider.get_activity("2001").await?;
+    assert_eq!(activity.name, "Walk");
+
+    let stats = provider.get_stats().await?;
+    assert_eq!(stats.total_distance, 2_450_500.0);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_fitbit_history_follows_pagination() -> Result<()> {
+    let server = FakeProviderServer::start(Default::default()).await?;
+    let first = chrono::NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+    server.set_data(FakeProviderData {
+        fitbit_activities: (0..250)
+            .map(|i| {
+                json!({
+                    "activityId": 3000 + i, "activityName": "Walk", "activityTypeId": 90001,
+                    "startTime": format!("{}T18:00:00.000-04:00", first - chrono::Duration::days(i)),
+                    "duration": 2400000, "distance": 3.2
+                })
+            })
+            .collect(),
+        ..Default::default()
+    });
+    let provider = registry()
+        .create_authenticated(
+            "fitbit",
+            &server.external_services(),
+            "fake-access",
+            "fake-refresh",
+        )
+        .await?;
+
+    // 100 per request, however many a history page asks for
+    let history = paged_activities(provider.as_ref(), 5000, None, None).await?;
+    assert_eq!(history.activities.len(), 250);
+    assert!(history.gaps.is_empty());
+    let list_requests = server
+        .requests()
+        .iter()
+        .filter(|request| request.starts_with("GET /fitbit/1/user/-/activities/list.json"))
+        .count();
+    assert_eq!(list_requests, 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_failure_injection() -> Result<()> {
+    let server = FakeProviderServer::start(Default::default()).await?;
+    let provider = strava_provider(&server).await?;
+    let activities_path = "/strava/api/v3/athlete/activities";
+
+    server.fail_next(activities_path, FakeFailure::Unauthorized, 1);
+    let error = provider.get_activities(None, None).await.unwrap_err();
+    assert!(error.to_string().contains("401"));
+
+    server.fail_next(activities_path, FakeFailure::Status(503), 1);
+    let error = provider.get_activities(None, None).await.unwrap_err();
+    assert!(error.to_string().contains("503"));
+
+    server.fail_next(
+        activities_path,
+        FakeFailure::Slow(Duration::from_millis(300)),
+        1,
+    );
+    let started = Instant::now();
+    provider.get_activities(None, None).await?;
+    assert!(started.elapsed() >= Duration::from_millis(300));
+
+    server.fail_next(
+        activities_path,
+        FakeFailure::RateLimited {
+            retry_after_secs: 1,
+        },
+        1,
+    );
+    let error = provider.get_activities(None, None).await.unwrap_err();
+    let limited = error.downcast_ref::<ProviderRateLimited>().unwrap();
+    assert_eq!(limited.retry_after_secs, 1);
+
+    // Once the injected failures are used up the fixtures are served again
+    assert_eq!(provider.get_activities(None, None).await?.len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_oauth_to_tool_pipeline() -> Result<()> {
+    let server = start_fake_server(Default::default()).await?;
+    let (database, user) = database_with_user("pipeline@example.com").await?;
+    let user_id = user.id;
+
+    // Authorize: the fake provider redirects straight back with a code
+    let oauth_routes = OAuthRoutes::new(database.as_ref().clone());
+    let authorization = oauth_routes.get_auth_url(user_id, "strava").await?;
+    assert!(authorization.authorization_url.starts_with(&server.url()));
+
+    let redirect = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?
+        .get(&authorization.authorization_url)
+        .send()
+        .await?;
+    let location = url::Url::parse(redirect.headers()["location"].to_str()?)?;
+    let params: std::collections::HashMap<_, _> = location.query_pairs().into_owned().collect();
+
+    // Callback exchanges the code against the fake token endpoint
+    oauth_routes
+        .handle_callback(&params["code"], &params["state"], "strava")
+        .await?;
+    let connection = database
+        .get_provider_connection(user_id, "strava")
+        .await?
+        .expect("connection stored");
+    assert_eq!(connection.provider_user_id.as_deref(), Some("12345"));
+
+    // Tool call reads activities through the stored token
+    let executor = tool_executor(database.clone())?;
+    let response = executor
+        .execute_tool(UniversalRequest {
+            tool_name: "get_activities".to_string(),
+            parameters: json!({"provider": "strava", "limit": 2}),
+            user_id: user_id.to_string(),
+            protocol: "test".to_string(),
+        })
+        .await?;
+    assert!(response.success);
+    let activities = &response.result.unwrap()["activities"];
+    assert_eq!(activities[0]["name"], "Morning Run");
+    assert_eq!(activities[1]["is_real_data"], true);
+
+    Ok(())
+}
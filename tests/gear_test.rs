@@ -0,0 +1,226 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Gear Tracking Tests
+//!
+//! Syncs shoes and bikes from the fake Strava API, totals the activities
+//! tagged with them, and checks retirement thresholds and the replacement
+//! insights emitted through `InsightGenerator`.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use pierre_mcp_server::database_plugins::DatabaseProvider;
+use pierre_mcp_server::intelligence::insights::{InsightGenerator, InsightType};
+use pierre_mcp_server::models::GearType;
+use pierre_mcp_server::protocols::ProtocolError;
+use pierre_mcp_server::providers::registry;
+use serde_json::{json, Value};
+use serial_test::serial;
+
+mod common;
+use common::{call_tool, connect_provider, setup, start_fake_server};
+
+fn find<'a>(gear: &'a Value, id: &str) -> &'a Value {
+    gear["gear"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["id"] == id)
+        .unwrap_or_else(|| panic!("{} missing from {}", id, gear))
+}
+
+#[tokio::test]
+#[serial]
+async fn test_strava_gear_usage_and_retirement() -> Result<()> {
+    let server = start_fake_server(Default::default()).await?;
+
+    // The provider reports gear on the athlete and on each activity
+    let strava = registry()
+        .create_authenticated(
+            "strava",
+            &server.external_services(),
+            "fake-access",
+            "fake-refresh",
+        )
+        .await?;
+    let athlete = strava.get_athlete().await?;
+    assert_eq!(athlete.gear.len(), 3);
+    assert_eq!(athlete.gear[2].gear_type, GearType::Bike);
+    let activities = strava.get_activities(Some(3), None).await?;
+    assert_eq!(activities[0].gear_id.as_deref(), Some("g201"));
+
+    let (database, executor, user_id) = setup("gear@example.com").await?;
+    connect_provider(
+        &database,
+        user_id,
+        "strava",
+        "12345",
+        "read,activity:read_all",
+    )
+    .await?;
+
+    let listed = call_tool(&executor, user_id, "get_gear", json!({})).await?;
+    assert_eq!(listed["total_count"], 3);
+    let pegasus = find(&listed, "g201");
+    assert_eq!(pegasus["distance_km"], 780.0);
+    assert_eq!(pegasus["activity_count"], 1);
+    assert_eq!(pegasus["moving_time_hours"], 0.5);
+    assert_eq!(pegasus["retirement_distance_km"], 800.0);
+    assert_eq!(pegasus["status"], "nearing_retirement");
+    assert!(find(&listed, "b301")["retirement_distance_km"].is_null());
+
+    let shoes_only = call_tool(
+        &executor,
+        user_id,
+        "get_gear",
+        json!({"gear_type": "shoes"}),
+    )
+    .await?;
+    assert_eq!(shoes_only["total_count"], 2);
+
+    // "Your Pegasus are at 780 km, consider replacing"
+    let report = call_tool(&executor, user_id, "gear_usage_report", json!({})).await?;
+    assert_eq!(report["gear"][0]["id"], "g201");
+    assert_eq!(report["nearing_retirement"], 1);
+    let insights = report["insights"].as_array().unwrap();
+    assert_eq!(insights.len(), 1);
+    assert_eq!(insights[0]["insight_type"], "gear_wear");
+    assert!(insights[0]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Your Pegasus 40 are at 780 km, consider replacing them"));
+
+    // A per-gear threshold survives the next provider sync
+    call_tool(
+        &executor,
+        user_id,
+        "update_gear",
+        json!({"gear_id": "b301", "retirement_distance_km": 4000}),
+    )
+    .await?;
+    let report = call_tool(&executor, user_id, "gear_usage_report", json!({})).await?;
+    assert_eq!(report["replacement_due"], 1);
+    assert_eq!(find(&report, "b301")["status"], "replacement_due");
+    assert_eq!(report["insights"].as_array().unwrap().len(), 2);
+
+    // Retired gear drops out of the report and the default listing
+    call_tool(
+        &executor,
+        user_id,
+        "update_gear",
+        json!({"gear_id": "g201", "retired": true}),
+    )
+    .await?;
+    let listed = call_tool(&executor, user_id, "get_gear", json!({})).await?;
+    assert_eq!(listed["total_count"], 2);
+    let listed = call_tool(
+        &executor,
+        user_id,
+        "get_gear",
+        json!({"include_retired": true}),
+    )
+    .await?;
+    assert_eq!(find(&listed, "g201")["status"], "retired");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_manual_gear_accumulates_tagged_activities() -> Result<()> {
+    let (database, executor, user_id) = setup("gear@example.com").await?;
+
+    let added = call_tool(
+        &executor,
+        user_id,
+        "add_gear",
+        json!({
+            "name": "Ghost 15",
+            "gear_type": "shoes",
+            "brand": "Brooks",
+            "distance_km": 480,
+            "retirement_distance_km": 500,
+        }),
+    )
+    .await?;
+    let gear_id = added["gear"]["id"].as_str().unwrap().to_string();
+    assert_eq!(added["gear"]["provider"], "manual");
+    assert_eq!(added["gear"]["status"], "nearing_retirement");
+
+    for _ in 0..2 {
+        call_tool(
+            &executor,
+            user_id,
+            "log_activity",
+            json!({
+                "sport_type": "run",
+                "duration_minutes": 60,
+                "distance_meters": 12000.0,
+                "start_date": (Utc::now() - Duration::days(1)).to_rfc3339(),
+                "gear_id": gear_id,
+            }),
+        )
+        .await?;
+    }
+
+    let listed = call_tool(&executor, user_id, "get_gear", json!({})).await?;
+    let ghost = find(&listed, &gear_id);
+    assert_eq!(ghost["distance_km"], 504.0);
+    assert_eq!(ghost["activity_count"], 2);
+    assert_eq!(ghost["moving_time_hours"], 2.0);
+    assert_eq!(ghost["status"], "replacement_due");
+
+    // Stored gear keeps the distance it was added with; usage is applied on read
+    let stored = database.list_gear(user_id).await?;
+    let insights = InsightGenerator::new().generate_gear_insights(&stored);
+    assert_eq!(insights.len(), 1);
+    assert!(insights[0].message.contains("consider replacing them soon"));
+
+    for parameters in [
+        json!({"gear_type": "shoes"}),
+        json!({"name": "Ghost", "gear_type": "skis"}),
+        json!({"name": "Ghost", "gear_type": "shoes", "distance_km": -1}),
+    ] {
+        assert!(
+            matches!(
+                call_tool(&executor, user_id, "add_gear", parameters.clone()).await,
+                Err(ProtocolError::InvalidParameters(_))
+            ),
+            "{}",
+            parameters
+        );
+    }
+    assert!(matches!(
+        call_tool(
+            &executor,
+            user_id,
+            "update_gear",
+            json!({"gear_id": "missing", "retired": true}),
+        )
+        .await,
+        Err(ProtocolError::InvalidParameters(_))
+    ));
+    assert!(matches!(
+        call_tool(
+            &executor,
+            user_id,
+            "update_gear",
+            json!({"gear_id": gear_id, "retirement_distance_km": -5}),
+        )
+        .await,
+        Err(ProtocolError::InvalidParameters(_))
+    ));
+
+    // Replacement reminders come straight from the generator too
+    let report = call_tool(&executor, user_id, "gear_usage_report", json!({})).await?;
+    let message = report["insights"][0]["message"].as_str().unwrap();
+    assert!(message.contains("past the 500 km"), "{}", message);
+    assert_eq!(
+        serde_json::to_value(InsightType::GearWear)?,
+        json!("gear_wear")
+    );
+
+    Ok(())
+}
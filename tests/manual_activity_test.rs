@@ -0,0 +1,226 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Manual Activity Tests
+//!
+//! Logs, edits and deletes user-entered activities through the universal
+//! tools and the REST routes, and checks they are merged with provider
+//! activities for the analyzers.
+
+use anyhow::Result;
+use chrono::Utc;
+use pierre_mcp_server::activity_routes::ActivityRoutes;
+use pierre_mcp_server::auth::AuthManager;
+use pierre_mcp_server::database_plugins::DatabaseProvider;
+use pierre_mcp_server::models::SportType;
+use pierre_mcp_server::protocols::ProtocolError;
+use pierre_mcp_server::providers::manual::{
+    ManualActivityProvider, ManualActivityRequest, ManualActivityUpdate,
+};
+use pierre_mcp_server::providers::{registry, FitnessProvider};
+use serde_json::json;
+use serial_test::serial;
+use std::sync::Arc;
+
+mod common;
+use common::{call_tool, create_user, database_with_user, start_fake_server, tool_executor};
+
+#[tokio::test]
+async fn test_manual_activity_tools() -> Result<()> {
+    let (database, user) = database_with_user("manual@example.com").await?;
+    let executor = tool_executor(database.clone())?;
+
+    // "45 min yoga this morning"
+    let logged = call_tool(
+        &executor,
+        user.id,
+        "log_activity",
+        json!({"sport_type": "yoga", "duration_minutes": 45}),
+    )
+    .await?;
+    let activity = &logged["activity"];
+    let activity_id = activity["id"].as_str().unwrap().to_string();
+    assert_eq!(activity["provider"], "manual");
+    assert_eq!(activity["sport_type"], "yoga");
+    assert_eq!(activity["name"], "Yoga session");
+    assert_eq!(activity["duration_seconds"], 2700);
+
+    let updated = call_tool(
+        &executor,
+        user.id,
+        "update_activity",
+        json!({"activity_id": activity_id, "duration_minutes": 60, "calories": 180}),
+    )
+    .await?;
+    assert_eq!(updated["activity"]["duration_seconds"], 3600);
+    assert_eq!(updated["activity"]["calories"], 180);
+    assert_eq!(updated["activity"]["sport_type"], "yoga");
+
+    let listed = call_tool(
+        &executor,
+        user.id,
+        "get_activities",
+        json!({"provider": "manual", "limit": 10}),
+    )
+    .await?;
+    assert_eq!(listed["total_count"], 1);
+    assert_eq!(listed["activities"][0]["id"], activity_id.as_str());
+
+    // Manual activities can be analyzed without any provider connection
+    let analysis = call_tool(
+        &executor,
+        user.id,
+        "analyze_activity",
+        json!({"activity_id": activity_id}),
+    )
+    .await?;
+    assert_eq!(analysis["activity"]["duration_seconds"], 3600);
+
+    call_tool(
+        &executor,
+        user.id,
+        "delete_activity",
+        json!({"activity_id": activity_id}),
+    )
+    .await?;
+    assert!(database
+        .get_manual_activity(user.id, &activity_id)
+        .await?
+        .is_none());
+    assert!(matches!(
+        call_tool(
+            &executor,
+            user.id,
+            "delete_activity",
+            json!({"activity_id": activity_id}),
+        )
+        .await,
+        Err(ProtocolError::InvalidParameters(_))
+    ));
+
+    for parameters in [
+        json!({"sport_type": "quidditch", "duration_minutes": 45}),
+        json!({"sport_type": "yoga"}),
+        json!({"sport_type": "run", "duration_minutes": -5}),
+        json!({"sport_type": "run", "duration_minutes": 30, "start_date": "2999-01-01T00:00:00Z"}),
+    ] {
+        assert!(
+            matches!(
+                call_tool(&executor, user.id, "log_activity", parameters.clone()).await,
+                Err(ProtocolError::InvalidParameters(_))
+            ),
+            "{}",
+            parameters
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_manual_activities_merge_with_provider_activities() -> Result<()> {
+    let server = start_fake_server(Default::default()).await?;
+
+    let (database, user) = database_with_user("manual@example.com").await?;
+    let strength = serde_json::from_value::<ManualActivityRequest>(json!({
+        "sport_type": "strength_training",
+        "duration_minutes": 50,
+        "start_date": "2024-06-02T18:00:00Z",
+    }))?
+    .into_activity()?;
+    database.create_manual_activity(user.id, &strength).await?;
+
+    let strava = registry()
+        .create_authenticated(
+            "strava",
+            &server.external_services(),
+            "fake-access",
+            "fake-refresh",
+        )
+        .await?;
+    let provider = ManualActivityProvider::new(database.clone(), user.id, Some(strava));
+
+    // Interleaved with the fake Strava runs and rides by start date
+    let activities = provider.get_activities(Some(3), None).await?;
+    let ids: Vec<&str> = activities.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec!["1001", strength.id.as_str(), "1002"]);
+    assert_eq!(activities[1].sport_type, SportType::StrengthTraining);
+
+    let page = provider.get_activities(Some(2), Some(1)).await?;
+    assert_eq!(page[0].id, strength.id);
+    assert_eq!(page.len(), 2);
+
+    assert_eq!(
+        provider.get_activity(&strength.id).await?.duration_seconds,
+        3000
+    );
+    assert_eq!(provider.get_activity("1003").await?.name, "Long Run");
+
+    // Without a connected provider only manual activities count
+    let manual_only = ManualActivityProvider::new(database.clone(), user.id, None);
+    let stats = manual_only.get_stats().await?;
+    assert_eq!(stats.total_activities, 1);
+    assert_eq!(stats.total_duration, 3000);
+    assert_eq!(provider.provider_name(), "Strava");
+    assert_eq!(manual_only.provider_name(), "manual");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_manual_activity_routes() -> Result<()> {
+    let (database, user) = database_with_user("manual@example.com").await?;
+    let auth_manager = Arc::new(AuthManager::new(vec![7u8; 32], 24));
+    let routes = ActivityRoutes::new(database.clone(), auth_manager.clone());
+    let auth_header = format!("Bearer {}", auth_manager.generate_token(&user)?);
+    let auth = Some(auth_header.as_str());
+
+    let request: ManualActivityRequest = serde_json::from_value(json!({
+        "sport_type": "Strength Training",
+        "duration_minutes": 40,
+        "name": "Leg day",
+        "start_date": (Utc::now() - chrono::Duration::hours(2)).to_rfc3339(),
+    }))?;
+    let activity = routes.log_activity(auth, request).await?;
+    assert_eq!(activity.sport_type, SportType::StrengthTraining);
+
+    let updated = routes
+        .update_activity(
+            auth,
+            &activity.id,
+            ManualActivityUpdate {
+                name: Some("Heavy leg day".to_string()),
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(updated.name, "Heavy leg day");
+    assert_eq!(updated.duration_seconds, 2400);
+
+    let listed = routes.list_activities(auth, None).await?;
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].name, "Heavy leg day");
+
+    // Other users can't see or touch the activity
+    let other = create_user(&database, "other@example.com").await?;
+    let other_header = format!("Bearer {}", auth_manager.generate_token(&other)?);
+    assert!(routes
+        .list_activities(Some(other_header.as_str()), None)
+        .await?
+        .is_empty());
+    assert!(routes
+        .delete_activity(Some(other_header.as_str()), &activity.id)
+        .await
+        .is_err());
+
+    assert!(routes.list_activities(None, None).await.is_err());
+
+    routes.delete_activity(auth, &activity.id).await?;
+    assert!(routes.list_activities(auth, None).await?.is_empty());
+
+    Ok(())
+}
@@ -78,6 +78,10 @@ fn create_test_server_config(
                 environment: pierre_mcp_server::config::environment::Environment::Development,
             },
         },
+        a2a: pierre_mcp_server::config::environment::A2AConfig {
+            public_url: None,
+            agent_card_signing_key_path: None,
+        },
         external_services: pierre_mcp_server::config::environment::ExternalServicesConfig {
             weather: pierre_mcp_server::config::environment::WeatherServiceConfig {
                 api_key: None,
@@ -277,7 +281,7 @@ async fn test_mcp_server_initialization() -> Result<()> {
     assert!(tools_response["result"]["tools"].is_array());
 
     let tools = tools_response["result"]["tools"].as_array().unwrap();
-    assert_eq!(tools.len(), 21);
+    assert_eq!(tools.len(), 45);
 
     // Verify key analytics tools are present
     let tool_names: Vec<&str> = tools
@@ -481,10 +485,7 @@ async fn test_analytics_tools_comprehensive() -> Result<()> {
             json!({"provider": "strava", "target_sport": "run", "target_distance": 10000}),
         ),
         ("analyze_training_load", json!({"timeframe": "month"})),
-        (
-            "detect_patterns",
-            json!({"pattern_type": "training_consistency"}),
-        ),
+        ("detect_patterns", json!({"pattern_type": "weekly_rhythm"})),
         (
             "analyze_performance_trends",
             json!({"timeframe": "month", "metric": "pace"}),
@@ -568,6 +569,54 @@ async fn test_error_handling_invalid_tool() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_remote_agent_tools_require_their_parameters() -> Result<()> {
+    let (database, auth_manager, test_port) = setup_test_environment().await?;
+    let (_user_id, jwt_token) = create_test_user(&database, &auth_manager).await?;
+
+    let server = MultiTenantMcpServer::new(database, auth_manager, create_test_server_config());
+    let server_handle = tokio::spawn(async move { server.run(test_port).await });
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    let mut client = McpTestClient::connect(test_port).await?;
+    client.initialize().await?;
+    client.set_token(jwt_token);
+
+    for (tool, arguments, missing) in [
+        (
+            "delegate_to_agent",
+            json!({"message": "plan meals"}),
+            "agent_id",
+        ),
+        (
+            "delegate_to_agent",
+            json!({"agent_id": "nutrition"}),
+            "message",
+        ),
+        (
+            "get_delegated_task",
+            json!({"agent_id": "nutrition"}),
+            "task_id",
+        ),
+        (
+            "cancel_delegated_task",
+            json!({"task_id": "task-42"}),
+            "agent_id",
+        ),
+    ] {
+        let response = client.call_tool(tool, arguments).await?;
+        assert_eq!(response["error"]["code"], -32602, "{}", tool); // ERROR_INVALID_PARAMS
+        assert!(response["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains(missing));
+    }
+
+    server_handle.abort();
+    Ok(())
+}
+
 /// Integration test that mimics the exact workflow we demonstrated
 #[tokio::test]
 async fn test_fitness_report_generation_workflow() -> Result<()> {
@@ -637,7 +686,7 @@ async fn test_fitness_report_generation_workflow() -> Result<()> {
         .call_tool(
             "detect_patterns",
             json!({
-                "pattern_type": "training_consistency"
+                "pattern_type": "weekly_rhythm"
             }),
         )
         .await?;
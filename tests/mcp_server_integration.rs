@@ -18,8 +18,12 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpStream;
 use tokio::time::{timeout, Duration};
 
+mod common;
+use common::isolate_server_storage;
+
 /// Helper to create a test configuration
 fn create_test_config() -> Config {
+    isolate_server_storage();
     let mut providers = HashMap::new();
 
     // Add a mock provider configuration
@@ -13,8 +13,8 @@ fn test_mcp_tool_schemas() {
     // Test that all analytics tools are properly defined
     let tools = get_tools();
 
-    // Should have all 21 tools
-    assert_eq!(tools.len(), 21);
+    // Should have all 45 tools
+    assert_eq!(tools.len(), 45);
 
     // Check key analytics tools are present
     let tool_names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
@@ -49,6 +49,37 @@ fn test_mcp_tool_schemas() {
     assert!(tool_names.contains(&"calculate_fitness_score"));
     assert!(tool_names.contains(&"predict_performance"));
     assert!(tool_names.contains(&"analyze_training_load"));
+
+    // Manual activities
+    assert!(tool_names.contains(&"log_activity"));
+    assert!(tool_names.contains(&"update_activity"));
+    assert!(tool_names.contains(&"delete_activity"));
+
+    // Wellness
+    assert!(tool_names.contains(&"get_sleep"));
+    assert!(tool_names.contains(&"get_daily_wellness"));
+    assert!(tool_names.contains(&"get_resting_hr_trend"));
+    assert!(tool_names.contains(&"log_wellness"));
+    assert!(tool_names.contains(&"get_readiness"));
+
+    // Gear
+    assert!(tool_names.contains(&"get_gear"));
+    assert!(tool_names.contains(&"gear_usage_report"));
+    assert!(tool_names.contains(&"add_gear"));
+    assert!(tool_names.contains(&"update_gear"));
+
+    // Athlete thresholds
+    assert!(tool_names.contains(&"get_athlete_thresholds"));
+
+    // Analysis settings
+    assert!(tool_names.contains(&"update_analysis_settings"));
+
+    // Repeated routes
+    assert!(tool_names.contains(&"list_my_routes"));
+    assert!(tool_names.contains(&"route_history"));
+
+    // Anomaly detection
+    assert!(tool_names.contains(&"detect_anomalies"));
 }
 
 #[test]
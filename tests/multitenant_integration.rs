@@ -3,6 +3,7 @@ use pierre_mcp_server::{
     auth::{generate_jwt_secret, AuthManager},
     database::generate_encryption_key,
     database_plugins::{factory::Database, DatabaseProvider},
+    models::DecryptedToken,
     routes::{AuthRoutes, LoginRequest, RegisterRequest},
 };
 use tempfile::TempDir;
@@ -109,17 +110,24 @@ async fn test_database_encryption() -> Result<()> {
     // Store encrypted Strava token
     let expires_at = chrono::Utc::now() + chrono::Duration::hours(6);
     database
-        .update_strava_token(
+        .upsert_provider_token(
             user_id,
-            "secret_access_token_123",
-            "secret_refresh_token_456",
-            expires_at,
-            "read,activity:read_all".to_string(),
+            "strava",
+            &DecryptedToken {
+                access_token: "secret_access_token_123".to_string(),
+                refresh_token: "secret_refresh_token_456".to_string(),
+                expires_at,
+                scope: "read,activity:read_all".to_string(),
+            },
+            None,
         )
         .await?;
 
     // Retrieve and decrypt token
-    let decrypted_token = database.get_strava_token(user_id).await?.unwrap();
+    let decrypted_token = database
+        .get_provider_token(user_id, "strava")
+        .await?
+        .unwrap();
     assert_eq!(decrypted_token.access_token, "secret_access_token_123");
     assert_eq!(decrypted_token.refresh_token, "secret_refresh_token_456");
     assert_eq!(decrypted_token.scope, "read,activity:read_all");
@@ -192,30 +200,44 @@ async fn test_user_isolation() -> Result<()> {
     let expires_at = chrono::Utc::now() + chrono::Duration::hours(6);
 
     database
-        .update_strava_token(
+        .upsert_provider_token(
             user1_id,
-            "user1_access_token",
-            "user1_refresh_token",
-            expires_at,
-            "read,activity:read_all".to_string(),
+            "strava",
+            &DecryptedToken {
+                access_token: "user1_access_token".to_string(),
+                refresh_token: "user1_refresh_token".to_string(),
+                expires_at,
+                scope: "read,activity:read_all".to_string(),
+            },
+            None,
         )
         .await?;
 
     database
-        .update_strava_token(
+        .upsert_provider_token(
             user2_id,
-            "user2_access_token",
-            "user2_refresh_token",
-            expires_at,
-            "read,activity:read_all".to_string(),
+            "strava",
+            &DecryptedToken {
+                access_token: "user2_access_token".to_string(),
+                refresh_token: "user2_refresh_token".to_string(),
+                expires_at,
+                scope: "read,activity:read_all".to_string(),
+            },
+            None,
         )
         .await?;
 
     // Verify user isolation - each user can only access their own tokens
-    let user1_token = database.get_strava_token(user1_id).await?.unwrap();
+    let user1_token = database
+        .get_provider_token(user1_id, "strava")
+        .await?
+        .unwrap();
     assert_eq!(user1_token.access_token, "user1_access_token");
 
-    let user2_token = database.get_strava_token(user2_id).await?.unwrap();
+    let user2_token = database
+        .get_provider_token(user2_id, "strava")
+        .await?
+        .unwrap();
     assert_eq!(user2_token.access_token, "user2_access_token");
 
     // Verify users cannot access each other's data
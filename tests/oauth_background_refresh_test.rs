@@ -0,0 +1,266 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Background Token Refresh Tests
+//!
+//! Runs the token refresher against the fake Strava API: expiring tokens are
+//! renewed ahead of time and revoked grants flag the connection for
+//! re-authorization.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use pierre_mcp_server::config::environment::ServerConfig;
+use pierre_mcp_server::database_plugins::{factory::Database, DatabaseProvider};
+use pierre_mcp_server::models::DecryptedToken;
+use pierre_mcp_server::oauth::manager::OAuthManager;
+use pierre_mcp_server::oauth::refresher::{RefreshOutcome, TokenRefresher, TokenRefresherConfig};
+use pierre_mcp_server::oauth::OAuthError;
+use pierre_mcp_server::providers::fake_server::FakeProviderServer;
+use pierre_mcp_server::routes::OAuthRoutes;
+use serial_test::serial;
+use std::sync::Arc;
+use uuid::Uuid;
+
+mod common;
+use common::{create_user, start_fake_server};
+
+struct Fixture {
+    server: FakeProviderServer,
+    database: Arc<Database>,
+    oauth_manager: Arc<OAuthManager>,
+    refresher: TokenRefresher,
+}
+
+async fn setup() -> Result<Fixture> {
+    let server = start_fake_server(Default::default()).await?;
+
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let oauth_manager = Arc::new(OAuthManager::from_config(
+        database.clone(),
+        &ServerConfig::from_env()?.oauth,
+    ));
+    let refresher = TokenRefresher::new(
+        database.clone(),
+        oauth_manager.clone(),
+        TokenRefresherConfig::default(),
+    );
+
+    Ok(Fixture {
+        server,
+        database,
+        oauth_manager,
+        refresher,
+    })
+}
+
+async fn connect_strava(
+    database: &Database,
+    email: &str,
+    refresh_token: &str,
+    expires_in: Duration,
+) -> Result<Uuid> {
+    let user_id = create_user(database, email).await?.id;
+    let token = DecryptedToken {
+        access_token: format!("{}-access", refresh_token),
+        refresh_token: refresh_token.to_string(),
+        expires_at: Utc::now() + expires_in,
+        scope: "read,activity:read_all".to_string(),
+    };
+    database
+        .upsert_provider_token(user_id, "strava", &token, Some("12345"))
+        .await?;
+    Ok(user_id)
+}
+
+#[tokio::test]
+#[serial]
+async fn test_refresher_renews_tokens_before_expiry() -> Result<()> {
+    let fixture = setup().await?;
+    let expiring = connect_strava(
+        &fixture.database,
+        "expiring@example.com",
+        "expiring-refresh",
+        Duration::minutes(10),
+    )
+    .await?;
+    let fresh = connect_strava(
+        &fixture.database,
+        "fresh@example.com",
+        "fresh-refresh",
+        Duration::hours(5),
+    )
+    .await?;
+
+    let outcomes = fixture.refresher.run_once().await?;
+    assert_eq!(outcomes.len(), 1);
+    assert!(matches!(
+        &outcomes[0],
+        RefreshOutcome::Refreshed { user_id, provider, .. }
+            if *user_id == expiring && provider == "strava"
+    ));
+
+    let token = fixture
+        .database
+        .get_provider_token(expiring, "strava")
+        .await?
+        .unwrap();
+    assert!(token.access_token.starts_with("fake-strava-access-"));
+    assert!(token.expires_at > Utc::now() + Duration::hours(5));
+
+    let untouched = fixture
+        .database
+        .get_provider_token(fresh, "strava")
+        .await?
+        .unwrap();
+    assert_eq!(untouched.access_token, "fresh-refresh-access");
+
+    // Nothing left inside the refresh window
+    assert!(fixture.refresher.run_once().await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_revoked_grant_marks_connection_for_reauth() -> Result<()> {
+    let fixture = setup().await?;
+    fixture.server.revoke_refresh_token("revoked-refresh");
+    let user_id = connect_strava(
+        &fixture.database,
+        "revoked@example.com",
+        "revoked-refresh",
+        Duration::minutes(5),
+    )
+    .await?;
+
+    let outcomes = fixture.refresher.run_once().await?;
+    assert_eq!(
+        outcomes,
+        vec![RefreshOutcome::NeedsReauth {
+            user_id,
+            provider: "strava".to_string(),
+        }]
+    );
+
+    // Flagged connections are skipped by later passes
+    assert!(fixture.refresher.run_once().await?.is_empty());
+
+    let statuses = OAuthRoutes::new(fixture.database.as_ref().clone())
+        .get_connection_status(user_id)
+        .await?;
+    let strava = statuses.iter().find(|s| s.provider == "strava").unwrap();
+    assert!(strava.connected);
+    assert!(strava.needs_reauth);
+    assert_eq!(strava.status, "needs_reauth");
+
+    // Tool calls fail fast instead of hitting the provider again
+    let error = fixture
+        .oauth_manager
+        .ensure_valid_token(user_id, "strava")
+        .await
+        .unwrap_err();
+    assert!(matches!(error, OAuthError::ReauthorizationRequired(ref p) if p == "strava"));
+
+    // Reconnecting stores new tokens and clears the flag
+    fixture
+        .database
+        .upsert_provider_token(
+            user_id,
+            "strava",
+            &DecryptedToken {
+                access_token: "new-access".to_string(),
+                refresh_token: "new-refresh".to_string(),
+                expires_at: Utc::now() + Duration::hours(6),
+                scope: "read".to_string(),
+            },
+            None,
+        )
+        .await?;
+    let connection = fixture
+        .database
+        .get_provider_connection(user_id, "strava")
+        .await?
+        .unwrap();
+    assert!(!connection.needs_reauth);
+    assert_eq!(connection.reauth_reason, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_concurrent_refreshes_spend_rotating_token_once() -> Result<()> {
+    let fixture = setup().await?;
+    fixture.server.rotate_refresh_tokens();
+    let user_id = connect_strava(
+        &fixture.database,
+        "racing@example.com",
+        "racing-refresh",
+        Duration::minutes(-5),
+    )
+    .await?;
+
+    // A tool call and the background refresher racing on the same expired token
+    let (from_tool, from_refresher) = tokio::join!(
+        fixture.oauth_manager.ensure_valid_token(user_id, "strava"),
+        fixture.oauth_manager.refresh_expiring(
+            user_id,
+            "strava",
+            Utc::now() + TokenRefresherConfig::default().refresh_ahead
+        ),
+    );
+    let from_tool = from_tool?.unwrap();
+    let from_refresher = from_refresher?;
+    assert_eq!(from_tool.access_token, from_refresher.access_token);
+    let exchanges = fixture
+        .server
+        .requests()
+        .iter()
+        .filter(|r| r.as_str() == "POST /strava/oauth/token")
+        .count();
+    assert_eq!(exchanges, 1);
+
+    let connection = fixture
+        .database
+        .get_provider_connection(user_id, "strava")
+        .await?
+        .unwrap();
+    assert!(!connection.needs_reauth);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_concurrent_refreshes_with_reused_refresh_token() -> Result<()> {
+    let fixture = setup().await?;
+    fixture.server.reuse_refresh_tokens();
+    let user_id = connect_strava(
+        &fixture.database,
+        "steady@example.com",
+        "steady-refresh",
+        Duration::minutes(-5),
+    )
+    .await?;
+
+    // Strava renews only the access token, so waiters must notice that change
+    let (first, second) = tokio::join!(
+        fixture.oauth_manager.ensure_valid_token(user_id, "strava"),
+        fixture.oauth_manager.ensure_valid_token(user_id, "strava"),
+    );
+    let first = first?.unwrap();
+    assert_eq!(first.refresh_token, "steady-refresh");
+    assert_eq!(first.access_token, second?.unwrap().access_token);
+    let exchanges = fixture
+        .server
+        .requests()
+        .iter()
+        .filter(|r| r.as_str() == "POST /strava/oauth/token")
+        .count();
+    assert_eq!(exchanges, 1);
+
+    Ok(())
+}
@@ -7,7 +7,10 @@
 //! End-to-end tests for OAuth flow with MCP integration
 
 use pierre_mcp_server::{
-    auth::AuthManager, database::generate_encryption_key, database_plugins::factory::Database,
+    auth::AuthManager,
+    config::environment::{OAuthConfig, OAuthProviderConfig},
+    database::generate_encryption_key,
+    database_plugins::factory::Database,
     mcp::multitenant::MultiTenantMcpServer,
 };
 use serde_json::json;
@@ -76,6 +79,10 @@ async fn test_oauth_flow_through_mcp() {
                 environment: pierre_mcp_server::config::environment::Environment::Development,
             },
         },
+        a2a: pierre_mcp_server::config::environment::A2AConfig {
+            public_url: None,
+            agent_card_signing_key_path: None,
+        },
         external_services: pierre_mcp_server::config::environment::ExternalServicesConfig {
             weather: pierre_mcp_server::config::environment::WeatherServiceConfig {
                 api_key: None,
@@ -213,7 +220,25 @@ async fn test_oauth_state_csrf_protection() {
     let database = Database::new("sqlite::memory:", encryption_key)
         .await
         .unwrap();
-    let oauth_routes = pierre_mcp_server::routes::OAuthRoutes::new(database);
+    let oauth_routes = pierre_mcp_server::routes::OAuthRoutes::with_oauth_config(
+        database,
+        OAuthConfig {
+            strava: OAuthProviderConfig {
+                client_id: Some("test_client".to_string()),
+                client_secret: Some("test_secret".to_string()),
+                redirect_uri: None,
+                scopes: vec![],
+                enabled: true,
+            },
+            fitbit: OAuthProviderConfig {
+                client_id: None,
+                client_secret: None,
+                redirect_uri: None,
+                scopes: vec![],
+                enabled: false,
+            },
+        },
+    );
 
     let user_id = uuid::Uuid::new_v4();
 
@@ -8,12 +8,31 @@
 
 use pierre_mcp_server::{
     auth::AuthManager,
+    config::environment::{OAuthConfig, OAuthProviderConfig},
     database::generate_encryption_key,
     database_plugins::factory::Database,
     routes::{AuthRoutes, OAuthRoutes, RegisterRequest},
 };
 use uuid::Uuid;
 
+/// OAuth routes with client credentials for both providers
+fn configured_oauth_routes(database: Database) -> OAuthRoutes {
+    let provider = |client_id: &str| OAuthProviderConfig {
+        client_id: Some(client_id.to_string()),
+        client_secret: Some("test_secret".to_string()),
+        redirect_uri: None,
+        scopes: vec![],
+        enabled: true,
+    };
+    OAuthRoutes::with_oauth_config(
+        database,
+        OAuthConfig {
+            strava: provider("test_strava_client"),
+            fitbit: provider("test_fitbit_client"),
+        },
+    )
+}
+
 #[tokio::test]
 async fn test_oauth_authorization_url_generation() {
     // Setup
@@ -24,7 +43,7 @@ async fn test_oauth_authorization_url_generation() {
     let auth_manager = AuthManager::new(vec![0u8; 64], 24);
 
     let auth_routes = AuthRoutes::new(database.clone(), auth_manager.clone());
-    let oauth_routes = OAuthRoutes::new(database.clone());
+    let oauth_routes = configured_oauth_routes(database.clone());
 
     // Register and login user
     let register_request = RegisterRequest {
@@ -60,7 +79,7 @@ async fn test_oauth_authorization_url_generation() {
     assert!(fitbit_auth.authorization_url.contains("redirect_uri="));
     assert!(fitbit_auth
         .authorization_url
-        .contains("scope=activity%20profile"));
+        .contains("scope=activity%20heartrate%20oxygen_saturation%20profile%20sleep"));
     assert!(fitbit_auth.state.contains(&user_id.to_string()));
 }
 
@@ -157,7 +176,7 @@ async fn test_oauth_urls_contain_required_parameters() {
     let database = Database::new("sqlite::memory:", encryption_key)
         .await
         .unwrap();
-    let oauth_routes = OAuthRoutes::new(database.clone());
+    let oauth_routes = configured_oauth_routes(database.clone());
 
     let user_id = Uuid::new_v4();
 
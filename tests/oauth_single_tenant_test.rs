@@ -10,7 +10,7 @@
 
 use pierre_mcp_server::database::generate_encryption_key;
 use pierre_mcp_server::database_plugins::{factory::Database, DatabaseProvider};
-use pierre_mcp_server::models::User;
+use pierre_mcp_server::models::{DecryptedToken, User};
 use pierre_mcp_server::oauth::{manager::OAuthManager, providers::StravaOAuthProvider};
 use std::sync::Arc;
 use uuid::Uuid;
@@ -114,8 +114,6 @@ async fn test_connection_status_single_tenant() {
         created_at: chrono::Utc::now(),
         last_active: chrono::Utc::now(),
         is_active: true,
-        strava_token: None,
-        fitbit_token: None,
     };
     database.create_user(&user).await.unwrap();
 
@@ -179,20 +177,22 @@ async fn test_token_refresh() {
         created_at: chrono::Utc::now(),
         last_active: chrono::Utc::now(),
         is_active: true,
-        strava_token: None,
-        fitbit_token: None,
     };
     database.create_user(&user).await.unwrap();
 
     // Store expired token
     let expires_at = chrono::Utc::now() - chrono::Duration::hours(1); // Expired 1 hour ago
     database
-        .update_strava_token(
+        .upsert_provider_token(
             user_id,
-            "old_access_token",
-            "refresh_token_123",
-            expires_at,
-            "read,activity:read_all".to_string(),
+            "strava",
+            &DecryptedToken {
+                access_token: "old_access_token".to_string(),
+                refresh_token: "refresh_token_123".to_string(),
+                expires_at,
+                scope: "read,activity:read_all".to_string(),
+            },
+            None,
         )
         .await
         .unwrap();
@@ -234,25 +234,31 @@ async fn test_disconnect_provider() {
         created_at: chrono::Utc::now(),
         last_active: chrono::Utc::now(),
         is_active: true,
-        strava_token: None,
-        fitbit_token: None,
     };
     database.create_user(&user).await.unwrap();
 
     // Store token
     database
-        .update_strava_token(
+        .upsert_provider_token(
             user_id,
-            "access_token",
-            "refresh_token",
-            chrono::Utc::now() + chrono::Duration::hours(1),
-            "read,activity:read_all".to_string(),
+            "strava",
+            &DecryptedToken {
+                access_token: "access_token".to_string(),
+                refresh_token: "refresh_token".to_string(),
+                expires_at: chrono::Utc::now() + chrono::Duration::hours(1),
+                scope: "read,activity:read_all".to_string(),
+            },
+            None,
         )
         .await
         .unwrap();
 
     // Verify token exists
-    assert!(database.get_strava_token(user_id).await.unwrap().is_some());
+    assert!(database
+        .get_provider_token(user_id, "strava")
+        .await
+        .unwrap()
+        .is_some());
 
     // Create OAuth manager and disconnect
     let mut oauth_manager = OAuthManager::new(database.clone());
@@ -275,7 +281,11 @@ async fn test_disconnect_provider() {
     assert!(result.is_ok());
 
     // Verify token is removed
-    assert!(database.get_strava_token(user_id).await.unwrap().is_none());
+    assert!(database
+        .get_provider_token(user_id, "strava")
+        .await
+        .unwrap()
+        .is_none());
 }
 
 /// Test multi-provider OAuth management
@@ -14,7 +14,7 @@ use pierre_mcp_server::intelligence::{
     ActivityIntelligence, ContextualFactors, PerformanceMetrics, TimeOfDay, TrendDirection,
     TrendIndicators,
 };
-use pierre_mcp_server::models::User;
+use pierre_mcp_server::models::{DecryptedToken, User};
 use pierre_mcp_server::protocols::universal::{UniversalRequest, UniversalToolExecutor};
 use serde_json::json;
 use std::sync::Arc;
@@ -75,6 +75,10 @@ fn create_test_server_config(
                 environment: pierre_mcp_server::config::environment::Environment::Development,
             },
         },
+        a2a: pierre_mcp_server::config::environment::A2AConfig {
+            public_url: None,
+            agent_card_signing_key_path: None,
+        },
         external_services: pierre_mcp_server::config::environment::ExternalServicesConfig {
             weather: pierre_mcp_server::config::environment::WeatherServiceConfig {
                 api_key: None,
@@ -162,20 +166,22 @@ async fn test_get_activities_with_expired_token() {
         created_at: chrono::Utc::now(),
         last_active: chrono::Utc::now(),
         is_active: true,
-        strava_token: None,
-        fitbit_token: None,
     };
     database.create_user(&user).await.unwrap();
 
     // Store expired token
     let expires_at = chrono::Utc::now() - chrono::Duration::hours(1); // Expired
     database
-        .update_strava_token(
+        .upsert_provider_token(
             user_id,
-            "expired_access_token",
-            "refresh_token_123",
-            expires_at,
-            "read,activity:read_all".to_string(),
+            "strava",
+            &DecryptedToken {
+                access_token: "expired_access_token".to_string(),
+                refresh_token: "refresh_token_123".to_string(),
+                expires_at,
+                scope: "read,activity:read_all".to_string(),
+            },
+            None,
         )
         .await
         .unwrap();
@@ -235,8 +241,6 @@ async fn test_connection_status_with_oauth_manager() {
         created_at: chrono::Utc::now(),
         last_active: chrono::Utc::now(),
         is_active: true,
-        strava_token: None,
-        fitbit_token: None,
     };
     database.create_user(&user).await.unwrap();
 
@@ -295,20 +299,22 @@ async fn test_analyze_activity_token_refresh() {
         created_at: chrono::Utc::now(),
         last_active: chrono::Utc::now(),
         is_active: true,
-        strava_token: None,
-        fitbit_token: None,
     };
     database.create_user(&user).await.unwrap();
 
     // Store token that will expire soon
     let expires_at = chrono::Utc::now() + chrono::Duration::minutes(3); // Expires in 3 minutes (within buffer)
     database
-        .update_strava_token(
+        .upsert_provider_token(
             user_id,
-            "soon_to_expire_token",
-            "refresh_token_456",
-            expires_at,
-            "read,activity:read_all".to_string(),
+            "strava",
+            &DecryptedToken {
+                access_token: "soon_to_expire_token".to_string(),
+                refresh_token: "refresh_token_456".to_string(),
+                expires_at,
+                scope: "read,activity:read_all".to_string(),
+            },
+            None,
         )
         .await
         .unwrap();
@@ -365,20 +371,22 @@ async fn test_concurrent_token_operations() {
         created_at: chrono::Utc::now(),
         last_active: chrono::Utc::now(),
         is_active: true,
-        strava_token: None,
-        fitbit_token: None,
     };
     database.create_user(&user).await.unwrap();
 
     // Store valid token
     let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
     database
-        .update_strava_token(
+        .upsert_provider_token(
             user_id,
-            "valid_token",
-            "refresh_token",
-            expires_at,
-            "read,activity:read_all".to_string(),
+            "strava",
+            &DecryptedToken {
+                access_token: "valid_token".to_string(),
+                refresh_token: "refresh_token".to_string(),
+                expires_at,
+                scope: "read,activity:read_all".to_string(),
+            },
+            None,
         )
         .await
         .unwrap();
@@ -443,8 +451,6 @@ async fn test_oauth_provider_init_failure() {
         created_at: chrono::Utc::now(),
         last_active: chrono::Utc::now(),
         is_active: true,
-        strava_token: None,
-        fitbit_token: None,
     };
     database.create_user(&user).await.unwrap();
 
@@ -0,0 +1,93 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Pattern Detection Tests
+//!
+//! Runs the `detect_patterns` tool over logged activities and checks that
+//! `pattern_type` selects the analysis.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use pierre_mcp_server::database_plugins::DatabaseProvider;
+use pierre_mcp_server::protocols::universal::UniversalToolExecutor;
+use pierre_mcp_server::protocols::ProtocolError;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+mod common;
+use common::{call_tool, database_with_user, manual_run, tool_executor};
+
+async fn detect(
+    executor: &UniversalToolExecutor,
+    user_id: Uuid,
+    parameters: Value,
+) -> Result<Value, ProtocolError> {
+    Ok(
+        call_tool(executor, user_id, "detect_patterns", parameters).await?["pattern_analysis"]
+            .clone(),
+    )
+}
+
+#[tokio::test]
+async fn test_detect_patterns_tool() -> Result<()> {
+    let (database, user) = database_with_user("patterns@example.com").await?;
+
+    // Twelve weeks of two short runs and a weekly long run
+    let start = Utc::now() - Duration::days(84);
+    for week in 0..12 {
+        for (day, distance) in [(0, 6000.0), (2, 7000.0), (5, 18000.0)] {
+            let activity = manual_run(
+                &format!("run-{}-{}", week, day),
+                start + Duration::weeks(week) + Duration::days(day),
+                3600,
+                distance,
+            );
+            database.create_manual_activity(user.id, &activity).await?;
+        }
+    }
+
+    let executor = tool_executor(database.clone())?;
+
+    let analysis = detect(
+        &executor,
+        user.id,
+        json!({"pattern_type": "long_run_cadence", "timeframe": "quarter"}),
+    )
+    .await?;
+    assert_eq!(analysis["activities_analyzed"], 36);
+    let patterns = analysis["patterns"].as_array().unwrap();
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(patterns[0]["pattern_type"], "long_run_cadence");
+    assert_eq!(patterns[0]["detected"], true);
+    assert_eq!(patterns[0]["statistics"]["long_runs"], 12);
+    assert_eq!(patterns[0]["statistics"]["average_gap_days"], 7.0);
+    assert_eq!(analysis["patterns_detected"].as_array().unwrap().len(), 1);
+
+    // Every analysis runs by default
+    let analysis = detect(&executor, user.id, json!({})).await?;
+    assert_eq!(analysis["pattern_type"], "all");
+    assert_eq!(analysis["patterns"].as_array().unwrap().len(), 5);
+
+    // Pattern types from before these analyses map onto them
+    let analysis = detect(
+        &executor,
+        user.id,
+        json!({"pattern_type": "training_consistency", "provider": "manual"}),
+    )
+    .await?;
+    let patterns = analysis["patterns"].as_array().unwrap();
+    assert_eq!(patterns.len(), 2);
+    assert_eq!(patterns[0]["pattern_type"], "weekly_rhythm");
+    assert_eq!(patterns[1]["pattern_type"], "long_run_cadence");
+    assert_eq!(analysis["activities_analyzed"], 36);
+
+    assert!(matches!(
+        detect(&executor, user.id, json!({"pattern_type": "plateaus"})).await,
+        Err(ProtocolError::InvalidParameters(_))
+    ));
+
+    Ok(())
+}
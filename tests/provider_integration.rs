@@ -11,7 +11,9 @@
 
 use anyhow::Result;
 use mockito::Server;
+use pierre_mcp_server::config::environment::ExternalServicesConfig;
 use pierre_mcp_server::models::{Activity, Athlete, SportType, Stats};
+use pierre_mcp_server::oauth::OAuthError;
 use pierre_mcp_server::providers::fitbit::FitbitProvider;
 use pierre_mcp_server::providers::strava::StravaProvider;
 use pierre_mcp_server::providers::{AuthData, FitnessProvider};
@@ -172,6 +174,40 @@ async fn test_provider_factory() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_provider_registry() -> Result<()> {
+    use pierre_mcp_server::providers::{
+        ProviderCapability, ProviderDescriptor, ProviderRegistry, QuotaScope,
+    };
+
+    let mut registry = ProviderRegistry::with_builtin_providers();
+    assert_eq!(registry.provider_names(), vec!["fitbit", "strava"]);
+    assert!(registry.is_supported("Strava"));
+    assert!(!registry.is_supported("garmin"));
+
+    let api = ExternalServicesConfig::from_env()?;
+    let error = registry.create("garmin", &api).err().unwrap();
+    assert!(error.to_string().contains("fitbit, strava"));
+
+    // A third provider plugs in through a descriptor alone
+    registry.register(ProviderDescriptor {
+        name: "garmin",
+        display_name: "Garmin Connect",
+        factory: |api| Box::new(StravaProvider::with_api_config(api.strava_api.clone())),
+        client_credentials: || Some(("id".to_string(), "secret".to_string())),
+        oauth_config: |config| &config.strava,
+        oauth_client: |_| Err(OAuthError::UnsupportedProvider("garmin".to_string())),
+        quota_scope: QuotaScope::Application,
+        capabilities: &[ProviderCapability::Activities],
+    });
+    assert!(registry.is_supported("garmin"));
+    registry
+        .create_authenticated("garmin", &api, "access", "refresh")
+        .await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_auth_data_variants() -> Result<()> {
     // Test OAuth2 variant
@@ -224,6 +260,8 @@ async fn test_activity_model_conversion() -> Result<()> {
         region: None,
         country: None,
         trail_name: None,
+        gear_id: None,
+        summary_polyline: None,
         provider: "strava".to_string(),
     };
 
@@ -252,6 +290,7 @@ async fn test_athlete_model() -> Result<()> {
         lastname: Some("User".to_string()),
         profile_picture: Some("https://example.com/avatar.jpg".to_string()),
         provider: "strava".to_string(),
+        gear: vec![],
     };
 
     // Test serialization
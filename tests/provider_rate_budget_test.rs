@@ -0,0 +1,178 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Provider Rate Budget Tests
+//!
+//! Exercises the shared provider quota tracker against rate-limit headers
+//! served by a local mock of the provider API.
+
+use anyhow::Result;
+use chrono::Utc;
+use mockito::Server;
+use pierre_mcp_server::providers::rate_budget::{
+    Admission, BudgetConfig, ProviderRateLimited, RateBudget, RequestContext,
+};
+use reqwest::header::{HeaderMap, HeaderValue};
+use uuid::Uuid;
+
+fn strava_headers(limit: &str, usage: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("X-RateLimit-Limit", HeaderValue::from_str(limit).unwrap());
+    headers.insert("X-RateLimit-Usage", HeaderValue::from_str(usage).unwrap());
+    headers
+}
+
+#[tokio::test]
+async fn test_reads_strava_rate_limit_headers() -> Result<()> {
+    let mut server = Server::new_async().await;
+    server
+        .mock("GET", "/athlete")
+        .with_header("X-RateLimit-Limit", "100,1000")
+        .with_header("X-RateLimit-Usage", "42,310")
+        .with_body("{}")
+        .create_async()
+        .await;
+
+    let budget = RateBudget::new(BudgetConfig::default());
+    let context = RequestContext::interactive(Uuid::new_v4());
+    let request = reqwest::Client::new().get(format!("{}/athlete", server.url()));
+    budget.send("strava", &context, request).await?;
+
+    let windows = budget.status("strava", &context);
+    assert_eq!(windows.len(), 2);
+    assert_eq!((windows[0].limit, windows[0].used), (100, 42));
+    assert_eq!((windows[1].limit, windows[1].used), (1000, 310));
+    assert_eq!(windows[0].period_secs, 15 * 60);
+    assert!(windows[0].resets_at > Utc::now());
+
+    Ok(())
+}
+
+#[test]
+fn test_background_sync_leaves_headroom_for_interactive_calls() {
+    let budget = RateBudget::new(BudgetConfig::default());
+    let user_id = Uuid::new_v4();
+    let now = Utc::now();
+    budget.record_headers_at(
+        "strava",
+        &RequestContext::interactive(user_id),
+        &strava_headers("100,1000", "85,300"),
+        now,
+    );
+
+    assert!(matches!(
+        budget.admit_at("strava", &RequestContext::background(user_id), now),
+        Admission::Wait(_)
+    ));
+    assert_eq!(
+        budget.admit_at("strava", &RequestContext::interactive(user_id), now),
+        Admission::Proceed
+    );
+}
+
+#[test]
+fn test_heavy_user_cannot_exhaust_shared_window() {
+    let budget = RateBudget::new(BudgetConfig::default());
+    let heavy = RequestContext::interactive(Uuid::new_v4());
+    let light = RequestContext::interactive(Uuid::new_v4());
+    let now = Utc::now();
+    budget.record_headers_at("strava", &heavy, &strava_headers("100,1000", "50,300"), now);
+
+    // A quarter of the window is the most one user gets once it is contended
+    for _ in 0..25 {
+        assert_eq!(budget.admit_at("strava", &heavy, now), Admission::Proceed);
+    }
+    assert!(matches!(
+        budget.admit_at("strava", &heavy, now),
+        Admission::Wait(_)
+    ));
+    assert_eq!(budget.admit_at("strava", &light, now), Admission::Proceed);
+}
+
+#[test]
+fn test_fitbit_quota_is_tracked_per_user() {
+    let budget = RateBudget::new(BudgetConfig::default());
+    let exhausted = RequestContext::interactive(Uuid::new_v4());
+    let other = RequestContext::interactive(Uuid::new_v4());
+    let now = Utc::now();
+
+    let mut headers = HeaderMap::new();
+    headers.insert("Fitbit-Rate-Limit-Limit", HeaderValue::from_static("150"));
+    headers.insert("Fitbit-Rate-Limit-Remaining", HeaderValue::from_static("0"));
+    headers.insert("Fitbit-Rate-Limit-Reset", HeaderValue::from_static("600"));
+    budget.record_headers_at("fitbit", &exhausted, &headers, now);
+
+    match budget.admit_at("fitbit", &exhausted, now) {
+        Admission::Wait(wait) => assert_eq!(wait.num_seconds(), 600),
+        Admission::Proceed => panic!("exhausted user should wait for the reset"),
+    }
+    assert_eq!(budget.admit_at("fitbit", &other, now), Admission::Proceed);
+}
+
+#[tokio::test]
+async fn test_abandoned_interactive_wait_releases_background_requests() {
+    let budget = RateBudget::new(BudgetConfig {
+        max_interactive_wait: chrono::Duration::minutes(30),
+        ..BudgetConfig::default()
+    });
+    let heavy = RequestContext::interactive(Uuid::new_v4());
+    let light = RequestContext::background(Uuid::new_v4());
+    let now = Utc::now();
+    budget.record_headers_at("strava", &heavy, &strava_headers("100,1000", "50,300"), now);
+    for _ in 0..25 {
+        assert_eq!(budget.admit_at("strava", &heavy, now), Admission::Proceed);
+    }
+
+    // The caller gives up while its request is queued for the next window
+    let queued = tokio::time::timeout(
+        std::time::Duration::from_millis(50),
+        budget.acquire("strava", &heavy),
+    )
+    .await;
+    assert!(queued.is_err());
+
+    assert_eq!(
+        budget.admit_at("strava", &light, Utc::now()),
+        Admission::Proceed
+    );
+}
+
+#[tokio::test]
+async fn test_provider_429_returns_retry_after() -> Result<()> {
+    let mut server = Server::new_async().await;
+    let limited = server
+        .mock("GET", "/athlete/activities")
+        .with_status(429)
+        .with_header("Retry-After", "120")
+        .with_header("X-RateLimit-Limit", "100,1000")
+        .with_header("X-RateLimit-Usage", "100,400")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let budget = RateBudget::new(BudgetConfig::default());
+    let context = RequestContext::interactive(Uuid::new_v4());
+    let url = format!("{}/athlete/activities", server.url());
+
+    let error = budget
+        .send("strava", &context, reqwest::Client::new().get(&url))
+        .await
+        .unwrap_err();
+    let limited_error = error.downcast_ref::<ProviderRateLimited>().unwrap();
+    assert_eq!(limited_error.provider, "strava");
+    assert_eq!(limited_error.retry_after_secs, 120);
+
+    // Further calls are refused locally instead of hitting the provider again
+    let error = budget
+        .send("strava", &context, reqwest::Client::new().get(&url))
+        .await
+        .unwrap_err();
+    let retry = error.downcast_ref::<ProviderRateLimited>().unwrap();
+    assert!(retry.retry_after_secs > 0 && retry.retry_after_secs <= 120);
+    limited.assert_async().await;
+
+    Ok(())
+}
@@ -0,0 +1,227 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Readiness Score Tests
+//!
+//! Scores readiness from Fitbit wellness data served by the fake provider
+//! API and from manual `log_wellness` entries, and checks that poor
+//! readiness downgrades hard-session recommendations.
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+use pierre_mcp_server::database_plugins::DatabaseProvider;
+use pierre_mcp_server::intelligence::readiness::{ReadinessEngine, ReadinessLevel};
+use pierre_mcp_server::intelligence::wellness::merged_wellness;
+use pierre_mcp_server::intelligence::{
+    AdvancedRecommendationEngine, Confidence, RecommendationPriority, RecommendationType,
+    TrainingRecommendation,
+};
+use pierre_mcp_server::protocols::ProtocolError;
+use serde_json::json;
+use serial_test::serial;
+
+mod common;
+use common::{call_tool, connect_provider, setup, start_fake_server};
+
+fn date(day: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap()
+}
+
+fn recommendation(kind: RecommendationType, title: &str) -> TrainingRecommendation {
+    TrainingRecommendation {
+        recommendation_type: kind,
+        title: title.to_string(),
+        description: String::new(),
+        priority: RecommendationPriority::Medium,
+        confidence: Confidence::Medium,
+        rationale: String::new(),
+        actionable_steps: vec![],
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_readiness_from_fitbit_wellness() -> Result<()> {
+    let _server = start_fake_server(Default::default()).await?;
+
+    let (_database, executor, user_id) = setup("readiness@example.com").await?;
+    connect_provider(
+        &executor.database,
+        user_id,
+        "fitbit",
+        "FAKE123",
+        "activity heartrate sleep",
+    )
+    .await?;
+
+    // The fixture's last days show elevated resting HR, suppressed HRV and short sleep
+    let result = call_tool(
+        &executor,
+        user_id,
+        "get_readiness",
+        json!({"date": "2024-06-03"}),
+    )
+    .await?;
+    let readiness = &result["readiness"];
+    assert_eq!(readiness["level"], "low", "{}", readiness);
+    assert!(readiness["score"].as_u64().unwrap() < 50);
+    let factors: Vec<&str> = readiness["factors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["factor"].as_str().unwrap())
+        .collect();
+    assert!(factors.contains(&"hrv"));
+    assert!(factors.contains(&"resting_heart_rate"));
+    assert!(factors.contains(&"sleep"));
+    assert!(!result["insights"].as_array().unwrap().is_empty());
+
+    // An earlier, well-recovered day scores higher
+    let result = call_tool(
+        &executor,
+        user_id,
+        "get_readiness",
+        json!({"date": "2024-05-31"}),
+    )
+    .await?;
+    assert_ne!(result["readiness"]["level"], "low");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_manual_wellness_entries_drive_readiness() -> Result<()> {
+    let (database, executor, user_id) = setup("readiness@example.com").await?;
+
+    for offset in 0..7 {
+        let day = date("2024-06-01") + Duration::days(offset);
+        call_tool(
+            &executor,
+            user_id,
+            "log_wellness",
+            json!({
+                "date": day.to_string(),
+                "sleep_hours": 8.0,
+                "sleep_quality": 4,
+                "resting_heart_rate": 50,
+                "hrv_rmssd_ms": 70.0,
+            }),
+        )
+        .await?;
+    }
+
+    // Later entries for the same day only replace the fields they mention
+    call_tool(
+        &executor,
+        user_id,
+        "log_wellness",
+        json!({"date": "2024-06-07", "resting_heart_rate": 51}),
+    )
+    .await?;
+    let stored = database
+        .get_daily_wellness(user_id, "manual", date("2024-06-07"), date("2024-06-07"))
+        .await?;
+    assert_eq!(stored[0].resting_heart_rate, Some(51));
+    assert_eq!(stored[0].sleep.as_ref().unwrap().minutes_asleep, 480);
+    assert_eq!(stored[0].sleep.as_ref().unwrap().quality, Some(4));
+
+    let result = call_tool(
+        &executor,
+        user_id,
+        "get_readiness",
+        json!({"date": "2024-06-07"}),
+    )
+    .await?;
+    assert_eq!(result["readiness"]["level"], "high");
+    assert_eq!(
+        result["readiness"]["missing_factors"],
+        json!(["training_load"])
+    );
+
+    // A bad night and a spiking resting HR pull the next day down
+    call_tool(
+        &executor,
+        user_id,
+        "log_wellness",
+        json!({
+            "date": "2024-06-08",
+            "sleep_hours": 4.5,
+            "sleep_quality": 1,
+            "resting_heart_rate": 60,
+            "hrv_rmssd_ms": 45.0,
+        }),
+    )
+    .await?;
+    let result = call_tool(
+        &executor,
+        user_id,
+        "get_readiness",
+        json!({"date": "2024-06-08"}),
+    )
+    .await?;
+    assert_eq!(result["readiness"]["level"], "low");
+
+    // The merged view feeds the engine directly too
+    let days = merged_wellness(&database, user_id, date("2024-05-12"), date("2024-06-08")).await?;
+    assert_eq!(days.len(), 8);
+    let score = ReadinessEngine::new()
+        .calculate(date("2024-06-08"), &days, &[])
+        .unwrap();
+    assert_eq!(score.level, ReadinessLevel::Low);
+
+    // Poor readiness drops interval work and leads with an easy day
+    let adjusted = AdvancedRecommendationEngine::new().apply_readiness(
+        vec![
+            recommendation(RecommendationType::Intensity, "Add Intervals"),
+            recommendation(RecommendationType::Volume, "Build Volume"),
+        ],
+        &score,
+    );
+    assert_eq!(adjusted.len(), 2);
+    assert_eq!(
+        adjusted[0].recommendation_type,
+        RecommendationType::Recovery
+    );
+    assert!(matches!(adjusted[0].priority, RecommendationPriority::High));
+    assert!(adjusted
+        .iter()
+        .all(|r| r.recommendation_type != RecommendationType::Intensity));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_readiness_tools_validate_input() -> Result<()> {
+    let (_database, executor, user_id) = setup("readiness@example.com").await?;
+
+    for parameters in [
+        json!({}),
+        json!({"sleep_hours": 30}),
+        json!({"sleep_hours": 7, "sleep_quality": 9}),
+        json!({"sleep_quality": 3}),
+        json!({"resting_heart_rate": 5}),
+        json!({"date": "yesterday", "sleep_hours": 7}),
+    ] {
+        let result = call_tool(&executor, user_id, "log_wellness", parameters.clone()).await;
+        assert!(
+            matches!(result, Err(ProtocolError::InvalidParameters(_))),
+            "{}",
+            parameters
+        );
+    }
+
+    // No data at all yields no score rather than an error
+    let result = call_tool(
+        &executor,
+        user_id,
+        "get_readiness",
+        json!({"date": "2024-06-08"}),
+    )
+    .await?;
+    assert!(result["readiness"].is_null());
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Route Detection Tests
+//!
+//! Groups logged activities with GPS tracks into repeated routes and follows
+//! the pace on one through the `list_my_routes` and `route_history` tools.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use pierre_mcp_server::database_plugins::DatabaseProvider;
+use pierre_mcp_server::intelligence::routes::encode_polyline;
+use pierre_mcp_server::models::Activity;
+use pierre_mcp_server::protocols::ProtocolError;
+use serde_json::json;
+
+mod common;
+use common::{call_tool, database_with_user, manual_run, tool_executor};
+
+/// A loop of about 4 km around a block, shifted north by `offset` degrees
+fn block_loop(offset: f64) -> String {
+    let (lat, lng) = (45.50 + offset, -73.59);
+    encode_polyline(&[
+        (lat, lng),
+        (lat + 0.009, lng),
+        (lat + 0.009, lng + 0.0128),
+        (lat, lng + 0.0128),
+        (lat, lng),
+    ])
+}
+
+/// A point-to-point run of about 3 km along one street
+fn one_way() -> String {
+    encode_polyline(&[(45.55, -73.60), (45.5635, -73.60), (45.577, -73.60)])
+}
+
+fn run(id: &str, days_ago: i64, minutes: u64, polyline: String) -> Activity {
+    Activity {
+        city: Some("Montreal".to_string()),
+        summary_polyline: Some(polyline),
+        ..manual_run(
+            id,
+            Utc::now() - Duration::days(days_ago),
+            minutes * 60,
+            4000.0,
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_route_tools() -> Result<()> {
+    // Geocoding fails fast, so names fall back to the activities' city
+    std::env::set_var("NOMINATIM_API_BASE_URL", "http://127.0.0.1:9");
+
+    let (database, user) = database_with_user("routes@example.com").await?;
+
+    // The same loop four times, a little faster each time, plus two
+    // one-way runs and a one-off treadmill run
+    for activity in [
+        run("loop-1", 60, 24, block_loop(0.0)),
+        run("loop-2", 40, 23, block_loop(0.0003)),
+        run("loop-3", 20, 23, block_loop(-0.0002)),
+        run("loop-4", 5, 22, block_loop(0.0001)),
+        Activity {
+            distance_meters: Some(3000.0),
+            ..run("street-1", 30, 17, one_way())
+        },
+        Activity {
+            distance_meters: Some(3000.0),
+            ..run("street-2", 10, 16, one_way())
+        },
+        Activity {
+            summary_polyline: None,
+            ..run("treadmill", 15, 30, String::new())
+        },
+    ] {
+        database.create_manual_activity(user.id, &activity).await?;
+    }
+
+    let executor = tool_executor(database.clone())?;
+
+    let listed = call_tool(&executor, user.id, "list_my_routes", json!({})).await?;
+    assert_eq!(listed["activities_with_gps"], 6);
+    assert_eq!(listed["routes_found"], 2);
+    let route = &listed["routes"][0];
+    assert_eq!(route["route_id"], "route-loop-1");
+    assert_eq!(route["name"], "Montreal loop");
+    assert_eq!(route["is_loop"], true);
+    assert_eq!(route["activity_count"], 4);
+    assert_eq!(route["best_activity_id"], "loop-4");
+    assert_eq!(route["trend"]["direction"], "improving");
+    assert_eq!(listed["routes"][1]["is_loop"], false);
+
+    // Names are kept in the profile
+    let profile = database.get_user_profile(user.id).await?.unwrap();
+    assert_eq!(profile["route_names"]["route-loop-1"], "Montreal loop");
+
+    let history = call_tool(
+        &executor,
+        user.id,
+        "route_history",
+        json!({"route_id": "route-loop-1"}),
+    )
+    .await?;
+    let efforts = history["efforts"].as_array().unwrap();
+    assert_eq!(efforts.len(), 4);
+    assert_eq!(efforts[0]["activity_id"], "loop-1");
+    assert_eq!(efforts[3]["rank"], 1);
+    assert!(history["insights"][1]
+        .as_str()
+        .unwrap()
+        .starts_with("Getting faster"));
+
+    let by_activity = call_tool(
+        &executor,
+        user.id,
+        "route_history",
+        json!({"activity_id": "street-2"}),
+    )
+    .await?;
+    assert_eq!(by_activity["route"]["route_id"], "route-street-1");
+    assert!(by_activity["route"]["trend"].is_null());
+
+    assert!(matches!(
+        call_tool(&executor, user.id, "route_history", json!({})).await,
+        Err(ProtocolError::InvalidParameters(_))
+    ));
+    assert!(matches!(
+        call_tool(
+            &executor,
+            user.id,
+            "route_history",
+            json!({"activity_id": "treadmill"})
+        )
+        .await,
+        Err(ProtocolError::ExecutionFailed(_))
+    ));
+
+    Ok(())
+}
@@ -27,8 +27,6 @@ async fn create_test_user(database: &Database, email: &str) -> Result<Uuid> {
         display_name: Some(format!("Test User ({})", email)),
         password_hash: "test_hash".to_string(),
         tier: pierre_mcp_server::models::UserTier::Professional,
-        strava_token: None,
-        fitbit_token: None,
         is_active: true,
         created_at: Utc::now(),
         last_active: Utc::now(),
@@ -0,0 +1,317 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strava Webhook Tests
+//!
+//! Replays a recorded sequence of Strava push events against the webhook
+//! handler with the Strava API mocked locally.
+
+use anyhow::Result;
+use mockito::Server;
+use pierre_mcp_server::config::environment::ServerConfig;
+use pierre_mcp_server::database_plugins::{factory::Database, DatabaseProvider};
+use pierre_mcp_server::providers::fake_server::{
+    FakeFailure, FakeProviderData, FakeProviderServer,
+};
+use pierre_mcp_server::webhook_routes::{
+    StravaWebhookEvent, SubscriptionVerification, WebhookOutcome, WebhookRoutes,
+};
+use serde_json::json;
+use serial_test::serial;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+use common::{connect_provider, create_user};
+
+fn strava_activity(id: u64, name: &str) -> serde_json::Value {
+    json!({
+        "id": id,
+        "name": name,
+        "type": "Run",
+        "start_date": "2024-06-01T07:00:00Z",
+        "elapsed_time": 1800,
+        "distance": 5000.0,
+        "total_elevation_gain": 40.0
+    })
+}
+
+#[tokio::test]
+#[serial]
+async fn test_replay_strava_events() -> Result<()> {
+    let mut server = Server::new_async().await;
+    std::env::set_var(
+        "STRAVA_DEAUTHORIZE_URL",
+        format!("{}/oauth/deauthorize", server.url()),
+    );
+    std::env::set_var("STRAVA_TOKEN_URL", format!("{}/oauth/token", server.url()));
+
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let mut config = ServerConfig::from_env()?;
+    config.external_services.strava_api.base_url = server.url();
+    config.oauth.strava.client_id = Some("test_client".to_string());
+    config.oauth.strava.client_secret = Some("test_secret".to_string());
+    let routes =
+        WebhookRoutes::new(database.clone(), Arc::new(config)).with_subscription_id(Some(1));
+
+    let user_id = create_user(&database, "athlete@example.com").await?.id;
+    connect_provider(
+        &database,
+        user_id,
+        "strava",
+        "12345",
+        "read,activity:read_all",
+    )
+    .await?;
+
+    let created = server
+        .mock("GET", "/activities/1001")
+        .with_body(strava_activity(1001, "Morning Run").to_string())
+        .expect(1)
+        .create_async()
+        .await;
+
+    // Recorded event sequence: create, rename, delete twice, stranger, deauthorize twice
+    let events: Vec<StravaWebhookEvent> = serde_json::from_value(json!([
+        {"object_type": "activity", "object_id": 1001, "aspect_type": "create",
+         "owner_id": 12345, "subscription_id": 1, "event_time": 1717225200, "updates": {}},
+        {"object_type": "activity", "object_id": 1001, "aspect_type": "update",
+         "owner_id": 12345, "subscription_id": 1, "event_time": 1717225260,
+         "updates": {"title": "Renamed Run"}},
+        {"object_type": "activity", "object_id": 1001, "aspect_type": "delete",
+         "owner_id": 12345, "subscription_id": 1, "event_time": 1717225320, "updates": {}},
+        {"object_type": "activity", "object_id": 1001, "aspect_type": "delete",
+         "owner_id": 12345, "subscription_id": 1, "event_time": 1717225350, "updates": {}},
+        {"object_type": "activity", "object_id": 2002, "aspect_type": "create",
+         "owner_id": 99999, "subscription_id": 1, "event_time": 1717225380, "updates": {}},
+        {"object_type": "athlete", "object_id": 12345, "aspect_type": "update",
+         "owner_id": 12345, "subscription_id": 1, "event_time": 1717225440,
+         "updates": {"authorized": "false"}},
+        {"object_type": "athlete", "object_id": 12345, "aspect_type": "update",
+         "owner_id": 12345, "subscription_id": 1, "event_time": 1717225500,
+         "updates": {"authorized": "false"}}
+    ]))?;
+    let mut events = events.into_iter();
+
+    // Create fetches the activity into local storage
+    let outcome = routes.handle_strava_event(events.next().unwrap()).await?;
+    assert!(matches!(outcome, WebhookOutcome::ActivitySynced { .. }));
+    created.assert_async().await;
+    created.remove_async().await;
+    let stored = database
+        .list_synced_activities(user_id, "strava", None)
+        .await?;
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].name, "Morning Run");
+
+    // Update re-fetches incrementally
+    let renamed = server
+        .mock("GET", "/activities/1001")
+        .with_body(strava_activity(1001, "Renamed Run").to_string())
+        .create_async()
+        .await;
+    routes.handle_strava_event(events.next().unwrap()).await?;
+    let stored = database
+        .list_synced_activities(user_id, "strava", None)
+        .await?;
+    assert_eq!(stored[0].name, "Renamed Run");
+
+    // A delete Strava doesn't back up keeps the activity
+    let outcome = routes.handle_strava_event(events.next().unwrap()).await?;
+    assert!(matches!(outcome, WebhookOutcome::Ignored { .. }));
+    assert_eq!(
+        database
+            .list_synced_activities(user_id, "strava", None)
+            .await?
+            .len(),
+        1
+    );
+    renamed.remove_async().await;
+
+    // Once Strava no longer has the activity, delete leaves a tombstone
+    server
+        .mock("GET", "/activities/1001")
+        .with_status(404)
+        .with_body(json!({"message": "Record Not Found", "errors": []}).to_string())
+        .create_async()
+        .await;
+    let outcome = routes.handle_strava_event(events.next().unwrap()).await?;
+    assert!(matches!(outcome, WebhookOutcome::ActivityDeleted { .. }));
+    assert!(database
+        .list_synced_activities(user_id, "strava", None)
+        .await?
+        .is_empty());
+    let tombstone = database
+        .get_synced_activity(user_id, "strava", "1001")
+        .await?
+        .expect("tombstone kept");
+    assert!(tombstone.deleted_at.is_some());
+
+    // Events for athletes we don't know are ignored
+    let outcome = routes.handle_strava_event(events.next().unwrap()).await?;
+    assert!(matches!(outcome, WebhookOutcome::Ignored { .. }));
+
+    // A deauthorization Strava doesn't back up leaves the connection alone
+    let still_valid = server
+        .mock("POST", "/oauth/token")
+        .with_body(
+            json!({
+                "access_token": "access_2",
+                "refresh_token": "refresh_2",
+                "expires_at": (chrono::Utc::now() + chrono::Duration::hours(6)).timestamp()
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+    let outcome = routes.handle_strava_event(events.next().unwrap()).await?;
+    assert!(matches!(outcome, WebhookOutcome::Ignored { .. }));
+    still_valid.assert_async().await;
+    still_valid.remove_async().await;
+    assert!(database
+        .get_provider_token(user_id, "strava")
+        .await?
+        .is_some());
+
+    // Once Strava rejects the grant, deauthorization clears the stored tokens
+    server
+        .mock("POST", "/oauth/token")
+        .with_status(400)
+        .with_body(json!({"message": "Bad Request", "errors": [{"code": "invalid"}]}).to_string())
+        .create_async()
+        .await;
+    server
+        .mock("POST", "/oauth/deauthorize")
+        .with_status(401)
+        .create_async()
+        .await;
+    let outcome = routes.handle_strava_event(events.next().unwrap()).await?;
+    assert_eq!(outcome, WebhookOutcome::Deauthorized { user_id });
+    assert!(database
+        .get_provider_token(user_id, "strava")
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_update_during_fetch_is_refetched() -> Result<()> {
+    let mut data = FakeProviderData {
+        strava_activities: vec![strava_activity(1001, "Morning Run")],
+        ..Default::default()
+    };
+    let server = FakeProviderServer::start(data.clone()).await?;
+    server.configure_env();
+
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let mut config = ServerConfig::from_env()?;
+    config.oauth.strava.client_id = Some("test_client".to_string());
+    config.oauth.strava.client_secret = Some("test_secret".to_string());
+    let routes =
+        WebhookRoutes::new(database.clone(), Arc::new(config)).with_subscription_id(Some(1));
+
+    let user_id = create_user(&database, "athlete@example.com").await?.id;
+    connect_provider(
+        &database,
+        user_id,
+        "strava",
+        "12345",
+        "read,activity:read_all",
+    )
+    .await?;
+
+    let event = |aspect_type: &str| -> Result<StravaWebhookEvent> {
+        Ok(serde_json::from_value(json!({
+            "object_type": "activity", "object_id": 1001, "aspect_type": aspect_type,
+            "owner_id": 12345, "subscription_id": 1, "event_time": 1717225200,
+            "updates": {}
+        }))?)
+    };
+
+    // The create fetch is still waiting on Strava when the rename arrives
+    server.fail_next(
+        "/strava/api/v3/activities/1001",
+        FakeFailure::Slow(Duration::from_millis(300)),
+        1,
+    );
+    let create = tokio::spawn({
+        let routes = routes.clone();
+        let event = event("create")?;
+        async move { routes.handle_strava_event(event).await }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    data.strava_activities = vec![strava_activity(1001, "Renamed Run")];
+    server.set_data(data);
+    let outcome = routes.handle_strava_event(event("update")?).await?;
+    assert!(matches!(outcome, WebhookOutcome::Ignored { .. }));
+
+    let outcome = create.await??;
+    assert!(matches!(outcome, WebhookOutcome::ActivitySynced { .. }));
+    let fetches = server
+        .requests()
+        .iter()
+        .filter(|request| request.as_str() == "GET /strava/api/v3/activities/1001")
+        .count();
+    assert_eq!(fetches, 2);
+    let stored = database
+        .list_synced_activities(user_id, "strava", None)
+        .await?;
+    assert_eq!(stored[0].name, "Renamed Run");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscription_verification() -> Result<()> {
+    std::env::set_var("STRAVA_WEBHOOK_VERIFY_TOKEN", "expected_token");
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let routes = WebhookRoutes::new(database, Arc::new(ServerConfig::from_env()?));
+
+    let params = |mode: &str, token: &str| SubscriptionVerification {
+        mode: mode.to_string(),
+        challenge: "challenge_abc".to_string(),
+        verify_token: token.to_string(),
+    };
+
+    let challenge = routes.verify_subscription(&params("subscribe", "expected_token"))?;
+    assert_eq!(
+        serde_json::to_value(&challenge)?,
+        json!({"hub.challenge": "challenge_abc"})
+    );
+    assert!(routes
+        .verify_subscription(&params("subscribe", "wrong_token"))
+        .is_err());
+    assert!(routes
+        .verify_subscription(&params("unsubscribe", "expected_token"))
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_events_for_other_subscriptions_rejected() -> Result<()> {
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let config = Arc::new(ServerConfig::from_env()?);
+    let event: StravaWebhookEvent = serde_json::from_value(json!({
+        "object_type": "athlete", "object_id": 12345, "aspect_type": "update",
+        "owner_id": 12345, "subscription_id": 666, "event_time": 1717225440,
+        "updates": {"authorized": "false"}
+    }))?;
+
+    let routes = WebhookRoutes::new(database.clone(), config.clone()).with_subscription_id(Some(1));
+    assert!(routes.authenticate_event(&event).is_err());
+    assert!(routes.handle_strava_event(event.clone()).await.is_err());
+
+    // Without a configured subscription every event is refused
+    let unconfigured = WebhookRoutes::new(database, config).with_subscription_id(None);
+    assert!(unconfigured.authenticate_event(&event).is_err());
+    assert!(unconfigured.handle_strava_event(event).await.is_err());
+
+    Ok(())
+}
@@ -70,6 +70,10 @@ fn create_test_server_config(
                 environment: pierre_mcp_server::config::environment::Environment::Development,
             },
         },
+        a2a: pierre_mcp_server::config::environment::A2AConfig {
+            public_url: None,
+            agent_card_signing_key_path: None,
+        },
         external_services: pierre_mcp_server::config::environment::ExternalServicesConfig {
             weather: pierre_mcp_server::config::environment::WeatherServiceConfig {
                 api_key: None,
@@ -119,8 +123,6 @@ async fn create_test_user(
         display_name: Some(format!("Test User ({})", email)),
         password_hash: "test_hash".to_string(),
         tier,
-        strava_token: None,
-        fitbit_token: None,
         is_active: true,
         created_at: Utc::now(),
         last_active: Utc::now(),
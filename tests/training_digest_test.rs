@@ -0,0 +1,143 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Training Digest Tests
+//!
+//! Builds, stores and delivers weekly digests: the scheduler sends a
+//! finished week once through any sink that accepts it, skips users who
+//! didn't train, and the stored digest is what `get_digest` returns.
+
+use anyhow::{anyhow, Result};
+use chrono::{Duration, NaiveDate, Utc};
+use pierre_mcp_server::database_plugins::DatabaseProvider;
+use pierre_mcp_server::digests::sinks::{DigestSink, FileOutboxSink};
+use pierre_mcp_server::digests::{DigestOutcome, DigestScheduler, DigestSchedulerConfig};
+use pierre_mcp_server::intelligence::digest::{self, DigestPeriod, DigestRequest};
+use pierre_mcp_server::models::{Activity, StoredDigest, User};
+
+mod common;
+use common::{create_user, database_with_user, manual_run, provider_connections};
+
+/// A sink whose service is down
+struct FailingSink;
+
+#[async_trait::async_trait]
+impl DigestSink for FailingSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn deliver(&self, _user: &User, _digest: &StoredDigest) -> Result<()> {
+        Err(anyhow!("connection refused"))
+    }
+}
+
+fn run(id: &str, date: NaiveDate, km: f64) -> Activity {
+    Activity {
+        name: id.to_string(),
+        average_heart_rate: Some(140),
+        ..manual_run(
+            id,
+            date.and_hms_opt(7, 0, 0).unwrap().and_utc(),
+            (km * 330.0) as u64,
+            km * 1000.0,
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_weekly_digest_delivery() -> Result<()> {
+    let (database, runner) = database_with_user("runner@example.com").await?;
+    let resting = create_user(&database, "resting@example.com").await?;
+
+    let now = Utc::now();
+    let week = DigestPeriod::Weekly.last_complete(now.date_naive());
+    for (id, day, km) in [
+        ("before", week - Duration::days(6), 8.0),
+        ("easy", week, 6.0),
+        ("tempo", week + Duration::days(2), 10.0),
+        ("long", week + Duration::days(5), 18.0),
+    ] {
+        database
+            .create_manual_activity(runner.id, &run(id, day, km))
+            .await?;
+    }
+
+    let outbox = tempfile::tempdir()?;
+    let config = DigestSchedulerConfig {
+        send_hour: 0,
+        periods: vec![DigestPeriod::Weekly],
+        sinks: vec!["file".to_string(), "webhook".to_string()],
+        ..Default::default()
+    };
+    let sinks: Vec<Box<dyn DigestSink>> = vec![
+        Box::new(FileOutboxSink::new(outbox.path())),
+        Box::new(FailingSink),
+    ];
+    let connections = provider_connections(database.clone())?;
+    let scheduler = DigestScheduler::new(connections.clone(), config, sinks);
+
+    // One sink failing doesn't hold the digest back; the idle user gets none
+    let outcomes = scheduler.run_at(now).await?;
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes.contains(&DigestOutcome::Delivered {
+        user_id: runner.id,
+        period: DigestPeriod::Weekly,
+        period_start: week,
+        sinks: vec!["file".to_string()],
+    }));
+    assert!(outcomes.contains(&DigestOutcome::Skipped {
+        user_id: resting.id,
+        period: DigestPeriod::Weekly,
+        period_start: week,
+    }));
+
+    let markdown = std::fs::read_to_string(
+        outbox
+            .path()
+            .join(runner.id.to_string())
+            .join(format!("weekly-{}.md", week)),
+    )?;
+    assert!(markdown.starts_with(&format!("# {}", DigestPeriod::Weekly.title(week))));
+    assert!(markdown.contains("3 activities, 3.1 h, 34.0 km, 300 m climbing over 3 active days."));
+    assert!(markdown.contains("- Longest: long, 18.0 km"));
+
+    // Each week is sent once
+    assert!(scheduler.run_at(now).await?.is_empty());
+
+    // get_digest returns the stored digest of the last finished week
+    let request = DigestRequest::from_args(&serde_json::json!({"period": "week"}))?;
+    let result = digest::requested_digest(&connections, runner.id, &request).await?;
+    assert_eq!(result["period_start"], week.to_string());
+    assert!(!result["delivered_at"].is_null());
+    assert_eq!(result["digest"]["volume"]["activities"], 3);
+    assert_eq!(result["digest"]["volume"]["previous"]["activities"], 1);
+    assert_eq!(result["resource_uri"], format!("digest://weekly/{}", week));
+
+    // The week in progress is built on request but not stored
+    let current = DigestRequest {
+        period: DigestPeriod::Weekly,
+        date: Some(now.date_naive()),
+        refresh: false,
+    };
+    let result = digest::requested_digest(&connections, runner.id, &current).await?;
+    assert_eq!(result["digest"]["complete"], false);
+    let stored = database.list_digests(runner.id, Some("weekly"), 10).await?;
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].period_start, week);
+
+    // Digests of future periods can't be built
+    let future = DigestRequest {
+        period: DigestPeriod::Monthly,
+        date: Some(now.date_naive() + Duration::days(40)),
+        refresh: false,
+    };
+    assert!(digest::requested_digest(&connections, runner.id, &future)
+        .await
+        .is_err());
+
+    Ok(())
+}
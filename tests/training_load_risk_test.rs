@@ -0,0 +1,118 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Training Load Injury Risk Tests
+//!
+//! Checks workload ratios, injury-risk alerts and the recommendation
+//! adjustments they drive for a user with an injury history.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use pierre_mcp_server::database_plugins::DatabaseProvider;
+use pierre_mcp_server::intelligence::recommendation_engine::{
+    AdvancedRecommendationEngine, RecommendationEngineTrait,
+};
+use pierre_mcp_server::intelligence::workload::{self, InjuryRisk, WorkloadAlertType};
+use pierre_mcp_server::intelligence::{
+    FitnessLevel, RecommendationType, TimeAvailability, UserFitnessProfile, UserPreferences,
+};
+use pierre_mcp_server::models::{Activity, User};
+use serde_json::json;
+
+mod common;
+use common::{database_with_user, manual_run};
+
+fn run(days_ago: i64, km: f64) -> Activity {
+    Activity {
+        average_heart_rate: Some(138),
+        ..manual_run(
+            &format!("run-{}", days_ago),
+            Utc::now() - Duration::days(days_ago),
+            (km * 330.0) as u64,
+            km * 1000.0,
+        )
+    }
+}
+
+fn profile(user: &User, injury_history: Vec<String>) -> UserFitnessProfile {
+    UserFitnessProfile {
+        user_id: user.id.to_string(),
+        age: Some(35),
+        gender: None,
+        weight: None,
+        height: None,
+        fitness_level: FitnessLevel::Intermediate,
+        primary_sports: vec!["Run".to_string()],
+        training_history_months: 24,
+        preferences: UserPreferences {
+            preferred_units: "metric".to_string(),
+            training_focus: vec!["endurance".to_string()],
+            injury_history,
+            time_availability: TimeAvailability {
+                hours_per_week: 5.0,
+                preferred_days: vec![],
+                preferred_duration_minutes: None,
+            },
+        },
+    }
+}
+
+#[tokio::test]
+async fn test_training_load_injury_risk() -> Result<()> {
+    let (database, user) = database_with_user("workload@example.com").await?;
+    database
+        .upsert_user_profile(
+            user.id,
+            json!({"injury_history": ["achilles tendinopathy"]}),
+        )
+        .await?;
+
+    // A month of 8 km runs every other day, then a week with a 24 km long run
+    let mut activities: Vec<Activity> = (8..36).step_by(2).map(|day| run(day, 8.0)).collect();
+    activities.extend([(1, 10.0), (3, 10.0), (5, 24.0)].map(|(day, km)| run(day, km)));
+
+    let injury_history = workload::injury_history(&database, user.id).await;
+    assert_eq!(injury_history, vec!["achilles tendinopathy".to_string()]);
+
+    let report = workload::workload_for_user(&database, user.id, &activities).await;
+    assert_eq!(report.overall_risk, InjuryRisk::High);
+    let running = &report.sports[0];
+    assert_eq!(running.sport, "run");
+    assert_eq!(running.weekly_distance_km, 44.0);
+    assert!(running.coupled_ratio.unwrap() > 1.4);
+    let alert_types: Vec<WorkloadAlertType> =
+        report.alerts.iter().map(|alert| alert.alert_type).collect();
+    assert!(alert_types.contains(&WorkloadAlertType::MileageJump));
+    assert!(alert_types.contains(&WorkloadAlertType::LongRunSpike));
+
+    let section = workload::workload_json(&report);
+    assert_eq!(section["overall_risk"], "high");
+    assert_eq!(section["insights"][0]["insight_type"], "injury_risk");
+    assert!(section["insights"][0]["message"]
+        .as_str()
+        .unwrap()
+        .ends_with("your history of achilles tendinopathy"));
+
+    // High risk leads with a deload week and drops advice to add volume
+    let engine = AdvancedRecommendationEngine::new();
+    let recommendations = engine
+        .generate_recommendations(&profile(&user, injury_history), &activities)
+        .await?;
+    let adjusted = engine.apply_workload_risk(recommendations, &report);
+    assert_eq!(adjusted[0].title, "High Injury Risk: Take a Deload Week");
+    assert!(adjusted
+        .iter()
+        .all(|r| r.recommendation_type != RecommendationType::Volume));
+
+    // Without an injury history the same week is judged against looser limits
+    let report = workload::analyze_workload(&activities, Utc::now(), &[]);
+    assert!(report
+        .alerts
+        .iter()
+        .all(|alert| !alert.message.contains("history")));
+
+    Ok(())
+}
@@ -0,0 +1,260 @@
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Wellness Data Tests
+//!
+//! Fetches Fitbit sleep, heart rate, HRV, SpO2 and step data from the fake
+//! provider API, persists it and runs the wellness tools and recovery
+//! recommendations over it.
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+use pierre_mcp_server::database_plugins::{factory::Database, DatabaseProvider};
+use pierre_mcp_server::intelligence::{
+    AdvancedRecommendationEngine, RecommendationEngineTrait, RecommendationType,
+};
+use pierre_mcp_server::models::DecryptedToken;
+use pierre_mcp_server::protocols::universal::UniversalRequest;
+use pierre_mcp_server::protocols::ProtocolError;
+use pierre_mcp_server::providers::fake_server::{FakeFailure, FakeProviderServer};
+use pierre_mcp_server::providers::registry;
+use serde_json::json;
+use serial_test::serial;
+use std::sync::Arc;
+use uuid::Uuid;
+
+mod common;
+use common::{create_user, start_fake_server, tool_executor};
+
+fn date(day: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap()
+}
+
+async fn connect_fitbit(database: &Database) -> Result<Uuid> {
+    let user_id = create_user(database, "wellness@example.com").await?.id;
+    let token = DecryptedToken {
+        access_token: "fitbit-access".to_string(),
+        refresh_token: "fitbit-refresh".to_string(),
+        expires_at: Utc::now() + Duration::hours(6),
+        scope: "activity heartrate oxygen_saturation profile sleep".to_string(),
+    };
+    database
+        .upsert_provider_token(user_id, "fitbit", &token, Some("FAKE123"))
+        .await?;
+    Ok(user_id)
+}
+
+fn wellness_request(user_id: Uuid, tool: &str) -> UniversalRequest {
+    UniversalRequest {
+        tool_name: tool.to_string(),
+        parameters: json!({"provider": "fitbit", "days": 14, "end_date": "2024-06-03"}),
+        user_id: user_id.to_string(),
+        protocol: "test".to_string(),
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_fitbit_daily_wellness_from_fake_server() -> Result<()> {
+    let server = FakeProviderServer::start(Default::default()).await?;
+    let provider = registry()
+        .create_authenticated(
+            "fitbit",
+            &server.external_services(),
+            "fake-access",
+            "fake-refresh",
+        )
+        .await?;
+
+    let days = provider
+        .get_daily_wellness(date("2024-06-01"), date("2024-06-03"))
+        .await?;
+    assert_eq!(days.len(), 3);
+
+    let last = &days[2];
+    assert_eq!(last.date, date("2024-06-03"));
+    assert_eq!(last.provider, "fitbit");
+    assert_eq!(last.resting_heart_rate, Some(59));
+    assert_eq!(last.hrv_rmssd_ms, Some(48.0));
+    assert_eq!(last.spo2_percent, Some(96.5));
+    assert_eq!(last.steps, Some(9950));
+    assert_eq!(last.active_zone_minutes, Some(33));
+
+    let sleep = last.sleep.as_ref().unwrap();
+    assert_eq!(sleep.minutes_asleep, 350);
+    assert_eq!(sleep.start_time.unwrap().to_string(), "2024-06-02 23:00:00");
+    assert_eq!(sleep.stages.as_ref().unwrap().deep_minutes, 50);
+
+    // Providers without the wellness capability refuse the call
+    let strava = registry()
+        .create_authenticated(
+            "strava",
+            &server.external_services(),
+            "fake-access",
+            "fake-refresh",
+        )
+        .await?;
+    assert!(strava
+        .get_daily_wellness(date("2024-06-01"), date("2024-06-03"))
+        .await
+        .is_err());
+    assert!(!registry()
+        .get("strava")
+        .unwrap()
+        .supports(pierre_mcp_server::providers::ProviderCapability::Wellness));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_fitbit_interval_series_fetched_in_30_day_chunks() -> Result<()> {
+    let server = FakeProviderServer::start(Default::default()).await?;
+    let provider = registry()
+        .create_authenticated(
+            "fitbit",
+            &server.external_services(),
+            "fake-access",
+            "fake-refresh",
+        )
+        .await?;
+
+    // 90 days of HRV and SpO2 take three requests each
+    let days = provider
+        .get_daily_wellness(date("2024-03-06"), date("2024-06-03"))
+        .await?;
+    assert_eq!(days.len(), 14);
+    assert!(days.iter().all(|day| day.hrv_rmssd_ms.is_some()));
+    assert!(days.iter().all(|day| day.spo2_percent.is_some()));
+    let requests = server.requests();
+    let count = |resource: &str| {
+        requests
+            .iter()
+            .filter(|request| request.starts_with(&format!("GET /fitbit/1/user/-/{}/", resource)))
+            .count()
+    };
+    assert_eq!(count("hrv"), 3);
+    assert_eq!(count("spo2"), 3);
+
+    // A failing series leaves its fields empty instead of failing the call
+    server.fail_always("/fitbit/1/user/-/spo2", FakeFailure::Status(403));
+    let days = provider
+        .get_daily_wellness(date("2024-06-01"), date("2024-06-03"))
+        .await?;
+    assert_eq!(days.len(), 3);
+    assert!(days.iter().all(|day| day.spo2_percent.is_none()));
+    assert!(days.iter().all(|day| day.hrv_rmssd_ms.is_some()));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_wellness_tools_store_and_analyze() -> Result<()> {
+    let _server = start_fake_server(Default::default()).await?;
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let user_id = connect_fitbit(&database).await?;
+    let executor = tool_executor(database.clone())?;
+
+    let response = executor
+        .execute_tool(wellness_request(user_id, "get_daily_wellness"))
+        .await?;
+    assert!(response.success, "{:?}", response.error);
+    let result = response.result.unwrap();
+    assert_eq!(result["total_days"], 14);
+    assert_eq!(result["needs_recovery"], true);
+    assert_eq!(result["recovery_signals"]["elevated_resting_hr_bpm"], 7.0);
+
+    let response = executor
+        .execute_tool(wellness_request(user_id, "get_resting_hr_trend"))
+        .await?;
+    let trend = &response.result.unwrap()["resting_hr_trend"];
+    assert_eq!(trend["points"].as_array().unwrap().len(), 14);
+    assert_eq!(trend["baseline_bpm"], 52.0);
+    assert_eq!(trend["direction"], "declining");
+
+    let response = executor
+        .execute_tool(wellness_request(user_id, "get_sleep"))
+        .await?;
+    let sleep = response.result.unwrap();
+    assert_eq!(sleep["nights"].as_array().unwrap().len(), 14);
+    assert_eq!(sleep["summary"]["nights"], 14);
+
+    // Days are persisted and feed recovery recommendations
+    let stored = database
+        .get_daily_wellness(user_id, "fitbit", date("2024-05-21"), date("2024-06-03"))
+        .await?;
+    assert_eq!(stored.len(), 14);
+
+    let recommendations = AdvancedRecommendationEngine::new()
+        .generate_wellness_recommendations(&stored)
+        .await?;
+    assert_eq!(recommendations.len(), 2);
+    assert!(recommendations
+        .iter()
+        .all(|r| r.recommendation_type == RecommendationType::Recovery));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_wellness_tools_reject_activity_only_providers() -> Result<()> {
+    let _server = start_fake_server(Default::default()).await?;
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let user_id = connect_fitbit(&database).await?;
+    let executor = tool_executor(database)?;
+
+    let mut request = wellness_request(user_id, "get_sleep");
+    request.parameters["provider"] = json!("strava");
+    let error = executor.execute_tool(request).await.unwrap_err();
+    assert!(matches!(error, ProtocolError::InvalidParameters(ref m) if m.contains("strava")));
+
+    let mut request = wellness_request(user_id, "get_sleep");
+    request.parameters["end_date"] = json!("last tuesday");
+    assert!(matches!(
+        executor.execute_tool(request).await,
+        Err(ProtocolError::InvalidParameters(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_wellness_tools_refresh_expired_tokens() -> Result<()> {
+    let server = start_fake_server(Default::default()).await?;
+    let database = Arc::new(Database::new("sqlite::memory:", vec![0u8; 32]).await?);
+    let user_id = connect_fitbit(&database).await?;
+    let mut token = database
+        .get_provider_token(user_id, "fitbit")
+        .await?
+        .unwrap();
+    token.expires_at = Utc::now() - Duration::hours(1);
+    database
+        .upsert_provider_token(user_id, "fitbit", &token, Some("FAKE123"))
+        .await?;
+    let executor = tool_executor(database.clone())?;
+
+    let response = executor
+        .execute_tool(wellness_request(user_id, "get_sleep"))
+        .await?;
+    assert!(response.success, "{:?}", response.error);
+
+    // The provider was built with the refreshed token, not the expired one
+    assert!(server
+        .requests()
+        .iter()
+        .any(|request| request == "POST /fitbit/oauth2/token"));
+    let token = database
+        .get_provider_token(user_id, "fitbit")
+        .await?
+        .unwrap();
+    assert_eq!(token.access_token, "fake-fitbit-access-1");
+    assert!(token.expires_at > Utc::now());
+
+    Ok(())
+}